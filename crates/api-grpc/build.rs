@@ -4,6 +4,7 @@ use std::path::PathBuf;
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     let proto_dir = PathBuf::from(env::var("CARGO_MANIFEST_DIR")?)
         .join("proto");
+    let out_dir = PathBuf::from(env::var("OUT_DIR")?);
 
     let proto_files = vec![
         proto_dir.join("common.proto"),
@@ -23,6 +24,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .type_attribute(".", "#[serde(rename_all = \"camelCase\")]")
         .build_server(true)
         .build_client(true)
+        // Descriptors for the reflection service
+        .file_descriptor_set_path(out_dir.join("llm_optimizer_descriptor.bin"))
         .out_dir("src/generated");
 
     // Compile all proto files
@@ -77,6 +77,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let list_request = ListOptimizationsRequest {
         status_filter: vec![],
         strategy_filter: vec![],
+        service_filter: vec![],
         time_range: None,
         pagination: Some(llm_optimizer_api_grpc::proto::common::PageRequest {
             page: 1,
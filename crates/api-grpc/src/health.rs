@@ -0,0 +1,190 @@
+//! Standard gRPC health checking protocol
+//!
+//! Serves `grpc.health.v1.Health`, the protocol spoken by Kubernetes gRPC
+//! probes, Envoy and `grpc_health_probe`. Component statuses come from a
+//! [`HealthSource`], normally the process's service manager, which is polled
+//! so that `Check` and `Watch` follow component health. The empty service
+//! name reports the server as a whole and is `NOT_SERVING` while any
+//! component is.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+use tonic_health::pb::health_server::{Health, HealthServer};
+use tonic_health::server::HealthReporter;
+use tracing::debug;
+
+pub use tonic_health::ServingStatus;
+
+/// Default interval between polls of the [`HealthSource`]
+pub const DEFAULT_HEALTH_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Supplies the serving status of each component
+#[tonic::async_trait]
+pub trait HealthSource: Send + Sync + fmt::Debug {
+    /// Current status of every component, keyed by the name clients check
+    async fn statuses(&self) -> Vec<(String, ServingStatus)>;
+}
+
+/// Build the `grpc.health.v1.Health` service
+///
+/// Without a `source` only the overall status is reported, as `SERVING`.
+/// With one, statuses are read before this returns and then every
+/// `poll_interval` until `shutdown` fires.
+pub async fn health_service(
+    source: Option<Arc<dyn HealthSource>>,
+    poll_interval: Duration,
+    mut shutdown: broadcast::Receiver<()>,
+) -> HealthServer<impl Health> {
+    let (reporter, service) = tonic_health::server::health_reporter();
+    let Some(source) = source else {
+        return service;
+    };
+
+    let mut sync = StatusSync {
+        reporter,
+        source,
+        reported: HashMap::new(),
+    };
+    sync.update().await;
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(poll_interval);
+        ticker.tick().await;
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => sync.update().await,
+                _ = shutdown.recv() => break,
+            }
+        }
+    });
+
+    service
+}
+
+/// Pushes changed statuses from a source to the health service
+struct StatusSync {
+    reporter: HealthReporter,
+    source: Arc<dyn HealthSource>,
+    /// Last status reported per service, so watchers only see changes
+    reported: HashMap<String, ServingStatus>,
+}
+
+impl StatusSync {
+    async fn update(&mut self) {
+        let statuses = self.source.statuses().await;
+        let overall = if statuses
+            .iter()
+            .all(|(_, status)| *status == ServingStatus::Serving)
+        {
+            ServingStatus::Serving
+        } else {
+            ServingStatus::NotServing
+        };
+
+        for (name, status) in statuses.into_iter().chain([(String::new(), overall)]) {
+            if self.reported.get(&name) != Some(&status) {
+                debug!(service = %name, %status, "Health status changed");
+                self.reporter.set_service_status(&name, status).await;
+                self.reported.insert(name, status);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tonic::transport::{server::TcpIncoming, Endpoint, Server};
+    use tonic::Code;
+    use tonic_health::pb::{
+        health_check_response::ServingStatus as WireStatus, health_client::HealthClient,
+        HealthCheckRequest,
+    };
+
+    #[derive(Debug)]
+    struct FixedHealth(Vec<(&'static str, ServingStatus)>);
+
+    #[tonic::async_trait]
+    impl HealthSource for FixedHealth {
+        async fn statuses(&self) -> Vec<(String, ServingStatus)> {
+            self.0
+                .iter()
+                .map(|(name, status)| ((*name).to_string(), *status))
+                .collect()
+        }
+    }
+
+    async fn client_for(source: FixedHealth) -> HealthClient<tonic::transport::Channel> {
+        let (_shutdown_tx, shutdown_rx) = broadcast::channel(1);
+        let service = health_service(
+            Some(Arc::new(source)),
+            DEFAULT_HEALTH_POLL_INTERVAL,
+            shutdown_rx,
+        )
+        .await;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(service)
+                .serve_with_incoming(incoming),
+        );
+
+        let channel = Endpoint::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        HealthClient::new(channel)
+    }
+
+    async fn check(client: &mut HealthClient<tonic::transport::Channel>, service: &str) -> i32 {
+        client
+            .check(HealthCheckRequest {
+                service: service.to_string(),
+            })
+            .await
+            .unwrap()
+            .into_inner()
+            .status
+    }
+
+    #[tokio::test]
+    async fn test_check_reports_component_status() {
+        let mut client = client_for(FixedHealth(vec![
+            ("processor", ServingStatus::Serving),
+            ("storage", ServingStatus::NotServing),
+        ]))
+        .await;
+
+        assert_eq!(
+            check(&mut client, "processor").await,
+            WireStatus::Serving as i32
+        );
+        assert_eq!(
+            check(&mut client, "storage").await,
+            WireStatus::NotServing as i32
+        );
+        assert_eq!(check(&mut client, "").await, WireStatus::NotServing as i32);
+
+        let unknown = client
+            .check(HealthCheckRequest {
+                service: "unknown".to_string(),
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(unknown.code(), Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_overall_status_serving_when_all_components_are() {
+        let mut client = client_for(FixedHealth(vec![("processor", ServingStatus::Serving)])).await;
+        assert_eq!(check(&mut client, "").await, WireStatus::Serving as i32);
+    }
+}
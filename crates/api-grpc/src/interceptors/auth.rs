@@ -1,5 +1,6 @@
 //! Authentication interceptor for gRPC requests
 
+use super::MethodPath;
 use crate::auth::{extract_token_from_metadata, TokenManager};
 use crate::error::ApiError;
 use std::sync::Arc;
@@ -41,7 +42,7 @@ impl AuthInterceptor {
 
     /// Intercept and authenticate request
     pub fn intercept<T>(&self, mut request: Request<T>) -> Result<Request<T>, Status> {
-        let path = request.uri().path();
+        let path = MethodPath::of(&request).unwrap_or_default();
 
         // Skip authentication for public paths
         if self.is_public_path(path) {
@@ -0,0 +1,148 @@
+//! Draining of gRPC requests for graceful shutdown
+//!
+//! Tonic interceptors only see a request on its way in, so draining is a
+//! tower layer: once draining starts, new calls fail with `UNAVAILABLE` while
+//! calls already in flight run until their response headers are sent.
+//! Health checks are always let through so probes can see the drain.
+
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use tonic::body::BoxBody;
+use tonic::codegen::http;
+use tonic::Status;
+use tower::{Layer, Service};
+
+pub use llm_optimizer_config::{DrainState, InFlight};
+
+/// Path prefixes of health services, which keep answering while draining
+const EXEMPT_PREFIXES: &[&str] = &["/grpc.health.v1.Health/", "/llm.optimizer.health."];
+
+/// Layer that rejects new calls with `UNAVAILABLE` while draining
+#[derive(Debug, Clone)]
+pub struct DrainLayer {
+    state: Arc<DrainState>,
+}
+
+impl DrainLayer {
+    /// Gate calls on `state`
+    pub fn new(state: Arc<DrainState>) -> Self {
+        Self { state }
+    }
+}
+
+impl<S> Layer<S> for DrainLayer {
+    type Service = DrainService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DrainService {
+            inner,
+            state: Arc::clone(&self.state),
+        }
+    }
+}
+
+/// Service produced by [`DrainLayer`]
+#[derive(Debug, Clone)]
+pub struct DrainService<S> {
+    inner: S,
+    state: Arc<DrainState>,
+}
+
+impl<S, B> Service<http::Request<B>> for DrainService<S>
+where
+    S: Service<http::Request<B>, Response = http::Response<BoxBody>>,
+    S::Future: Send + 'static,
+{
+    type Response = http::Response<BoxBody>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let path = request.uri().path();
+        if EXEMPT_PREFIXES.iter().any(|p| path.starts_with(p)) {
+            return Box::pin(self.inner.call(request));
+        }
+
+        let Some(in_flight) = self.state.track() else {
+            let status = Status::unavailable("Server is shutting down");
+            return Box::pin(async move { Ok(status.into_http()) });
+        };
+
+        let response = self.inner.call(request);
+        Box::pin(async move {
+            let response = response.await;
+            drop(in_flight);
+            response
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::convert::Infallible;
+    use std::time::Duration;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    fn grpc_status(response: &http::Response<BoxBody>) -> Option<tonic::Code> {
+        Status::from_header_map(response.headers()).map(|status| status.code())
+    }
+
+    fn request(path: &str) -> http::Request<()> {
+        http::Request::post(path).body(()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_call_completes_while_new_ones_are_rejected() {
+        let state = Arc::new(DrainState::new());
+        let release = Arc::new(Notify::new());
+        let service = DrainLayer::new(state.clone()).layer(tower::service_fn({
+            let release = release.clone();
+            move |_: http::Request<()>| {
+                let release = release.clone();
+                async move {
+                    release.notified().await;
+                    Ok::<_, Infallible>(http::Response::new(tonic::body::empty_body()))
+                }
+            }
+        }));
+        let method = "/llm.optimizer.optimization.OptimizationService/DeployOptimization";
+
+        let in_flight = tokio::spawn(service.clone().oneshot(request(method)));
+        while state.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        state.start();
+        let rejected = service.clone().oneshot(request(method)).await.unwrap();
+        assert_eq!(grpc_status(&rejected), Some(tonic::Code::Unavailable));
+
+        let idle = tokio::spawn({
+            let state = state.clone();
+            async move { state.wait_idle().await }
+        });
+        release.notify_one();
+
+        let completed = in_flight.await.unwrap().unwrap();
+        assert_eq!(grpc_status(&completed), None);
+        tokio::time::timeout(Duration::from_secs(1), idle)
+            .await
+            .expect("drain did not become idle")
+            .unwrap();
+
+        // Health checks still get through
+        release.notify_one();
+        let probe = service
+            .oneshot(request("/grpc.health.v1.Health/Check"))
+            .await
+            .unwrap();
+        assert_eq!(grpc_status(&probe), None);
+    }
+}
@@ -1,14 +1,21 @@
 //! Logging and tracing interceptor for gRPC requests
 
+use super::method::MethodPath;
+pub use llm_optimizer_config::LoggingConfig;
 use tonic::{Request, Status};
-use tracing::{info, warn, Span};
-use std::time::Instant;
+use tracing::{debug, info, warn, Span};
 
 /// Logging interceptor
+///
+/// Interceptors only see a call on its way in, so requests are logged when
+/// received, sampled at the config's `sample_rate`. Calls rejected by the
+/// other interceptors are always logged through [`LoggingInterceptor::rejected`].
 #[derive(Clone)]
 pub struct LoggingInterceptor {
     /// Service name for logging
     service_name: String,
+    /// Request log sampling
+    config: LoggingConfig,
 }
 
 impl LoggingInterceptor {
@@ -16,12 +23,19 @@ impl LoggingInterceptor {
     pub fn new(service_name: impl Into<String>) -> Self {
         Self {
             service_name: service_name.into(),
+            config: LoggingConfig::default(),
         }
     }
 
+    /// Sample request logs with the given config
+    pub fn with_config(mut self, config: LoggingConfig) -> Self {
+        self.config = config;
+        self
+    }
+
     /// Intercept and log request
     pub fn intercept<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
-        let path = request.uri().path().to_string();
+        let path = MethodPath::of(&request).unwrap_or("unknown").to_string();
         let metadata = request.metadata().clone();
 
         // Extract useful metadata
@@ -36,16 +50,37 @@ impl LoggingInterceptor {
             .map(|s| s.to_string())
             .unwrap_or_else(|| uuid::Uuid::new_v4().to_string());
 
-        info!(
-            service = %self.service_name,
-            method = %path,
-            user_agent = %user_agent,
-            request_id = %request_id,
-            "gRPC request received"
-        );
+        if self.config.sampled() {
+            info!(
+                service = %self.service_name,
+                method = %path,
+                user_agent = %user_agent,
+                request_id = %request_id,
+                "gRPC request received"
+            );
+        } else {
+            debug!(
+                service = %self.service_name,
+                method = %path,
+                user_agent = %user_agent,
+                request_id = %request_id,
+                "gRPC request received"
+            );
+        }
 
         Ok(request)
     }
+
+    /// Log a call that another interceptor rejected; never sampled out
+    pub fn rejected(&self, method: &str, status: &Status) {
+        warn!(
+            service = %self.service_name,
+            method = %method,
+            status_code = %status.code(),
+            error = %status.message(),
+            "gRPC request rejected"
+        );
+    }
 }
 
 /// Create a logging span for request tracking
@@ -87,30 +122,6 @@ pub fn log_request_complete(
     }
 }
 
-/// Request timing wrapper
-pub struct RequestTimer {
-    start: Instant,
-    method: String,
-    request_id: String,
-}
-
-impl RequestTimer {
-    /// Create a new request timer
-    pub fn new(method: impl Into<String>, request_id: impl Into<String>) -> Self {
-        Self {
-            start: Instant::now(),
-            method: method.into(),
-            request_id: request_id.into(),
-        }
-    }
-
-    /// Complete the request and log timing
-    pub fn complete(self, status: Result<(), Status>) {
-        let duration = self.start.elapsed();
-        log_request_complete(&self.method, &self.request_id, duration, status);
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -125,9 +136,18 @@ mod tests {
     }
 
     #[test]
-    fn test_request_timer() {
-        let timer = RequestTimer::new("TestMethod", "req-123");
-        std::thread::sleep(std::time::Duration::from_millis(10));
-        timer.complete(Ok(()));
+    fn test_logs_method_path() {
+        let interceptor = LoggingInterceptor::new("test-service")
+            .with_config(LoggingConfig::new().with_sample_rate(0.0));
+        let mut request = Request::new(());
+        request
+            .extensions_mut()
+            .insert(MethodPath("/llm.optimizer.health.HealthService/Check".to_string()));
+
+        let request = interceptor.intercept(request).unwrap();
+        assert_eq!(
+            MethodPath::of(&request),
+            Some("/llm.optimizer.health.HealthService/Check")
+        );
     }
 }
@@ -0,0 +1,63 @@
+//! gRPC method path for interceptors
+//!
+//! Tonic interceptors receive a `tonic::Request`, which does not carry the
+//! URI. [`MethodPathLayer`] copies the path into the request extensions on the
+//! way in, so interceptors such as rate limiting can key on the method.
+
+use std::task::{Context, Poll};
+use tonic::codegen::http;
+use tonic::Request;
+use tower::{Layer, Service};
+
+/// Full gRPC method path of a call, e.g.
+/// `/llm.optimizer.optimization.OptimizationService/GetOptimization`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MethodPath(pub String);
+
+impl MethodPath {
+    /// Path of `request`, or `None` if it did not pass through a
+    /// [`MethodPathLayer`]
+    pub fn of<T>(request: &Request<T>) -> Option<&str> {
+        request
+            .extensions()
+            .get::<Self>()
+            .map(|path| path.0.as_str())
+    }
+}
+
+/// Layer that records each call's [`MethodPath`]; must wrap the interceptors
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MethodPathLayer;
+
+impl<S> Layer<S> for MethodPathLayer {
+    type Service = MethodPathService<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        MethodPathService { inner }
+    }
+}
+
+/// Service produced by [`MethodPathLayer`]
+#[derive(Debug, Clone)]
+pub struct MethodPathService<S> {
+    inner: S,
+}
+
+impl<S, B> Service<http::Request<B>> for MethodPathService<S>
+where
+    S: Service<http::Request<B>>,
+{
+    type Response = S::Response;
+    type Error = S::Error;
+    type Future = S::Future;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut request: http::Request<B>) -> Self::Future {
+        let path = MethodPath(request.uri().path().to_string());
+        request.extensions_mut().insert(path);
+        self.inner.call(request)
+    }
+}
@@ -4,24 +4,47 @@
 //! - Authentication and authorization
 //! - Request logging and tracing
 //! - Rate limiting
+//! - Draining on shutdown
 //! - Error handling
 
 pub mod auth;
+pub mod drain;
 pub mod logging;
+pub mod method;
 pub mod ratelimit;
 
 pub use auth::{create_auth_interceptor, AuthInterceptor};
-pub use logging::{create_request_span, log_request_complete, LoggingInterceptor, RequestTimer};
-pub use ratelimit::{create_ratelimit_interceptor, RateLimitConfig, RateLimitInterceptor};
+pub use drain::{DrainLayer, DrainService, DrainState, InFlight};
+pub use logging::{create_request_span, log_request_complete, LoggingConfig, LoggingInterceptor};
+pub use method::{MethodPath, MethodPathLayer, MethodPathService};
+pub use ratelimit::{
+    create_ratelimit_interceptor, MethodRateLimit, RateLimitAlgorithm, RateLimitConfig,
+    RateLimitInterceptor,
+};
 
 use tonic::{Request, Status};
 
+/// Order in which [`CombinedInterceptor`] applies authentication and rate
+/// limiting; logging always runs first
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InterceptorOrder {
+    /// Authenticate, then rate limit. Requests with bad credentials are
+    /// rejected without spending any rate-limit budget, and per-user limits
+    /// are keyed on the authenticated principal.
+    #[default]
+    AuthFirst,
+    /// Rate limit, then authenticate. For anonymous endpoints, where there is
+    /// no principal and per-user limits key on the client address.
+    RateLimitFirst,
+}
+
 /// Combined interceptor that applies multiple interceptors in sequence
 #[derive(Clone)]
 pub struct CombinedInterceptor {
     auth: Option<AuthInterceptor>,
     logging: Option<LoggingInterceptor>,
     ratelimit: Option<RateLimitInterceptor>,
+    order: InterceptorOrder,
 }
 
 impl CombinedInterceptor {
@@ -31,6 +54,7 @@ impl CombinedInterceptor {
             auth: None,
             logging: None,
             ratelimit: None,
+            order: InterceptorOrder::default(),
         }
     }
 
@@ -52,26 +76,54 @@ impl CombinedInterceptor {
         self
     }
 
+    /// Set the order of authentication and rate limiting
+    pub fn with_order(mut self, order: InterceptorOrder) -> Self {
+        self.order = order;
+        self
+    }
+
     /// Intercept request through all configured interceptors
     pub fn intercept<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
         let mut request = request;
 
         // Apply logging first
-        if let Some(ref logging) = self.logging {
-            request = logging.intercept(request)?;
-        }
+        let Some(ref logging) = self.logging else {
+            return self.authorize(request);
+        };
+        request = logging.intercept(request)?;
+
+        let method = MethodPath::of(&request).unwrap_or("unknown").to_string();
+        self.authorize(request).map_err(|status| {
+            logging.rejected(&method, &status);
+            status
+        })
+    }
 
-        // Apply rate limiting
-        if let Some(ref ratelimit) = self.ratelimit {
-            request = ratelimit.intercept(request)?;
+    fn authorize<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
+        match self.order {
+            InterceptorOrder::AuthFirst => {
+                let request = self.authenticate(request)?;
+                self.rate_limit(request)
+            }
+            InterceptorOrder::RateLimitFirst => {
+                let request = self.rate_limit(request)?;
+                self.authenticate(request)
+            }
         }
+    }
 
-        // Apply authentication last (after rate limiting)
-        if let Some(ref auth) = self.auth {
-            request = auth.intercept(request)?;
+    fn authenticate<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
+        match self.auth {
+            Some(ref auth) => auth.intercept(request),
+            None => Ok(request),
         }
+    }
 
-        Ok(request)
+    fn rate_limit<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
+        match self.ratelimit {
+            Some(ref ratelimit) => ratelimit.intercept(request),
+            None => Ok(request),
+        }
     }
 }
 
@@ -84,6 +136,8 @@ impl Default for CombinedInterceptor {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::auth::TokenManager;
+    use std::sync::Arc;
 
     #[test]
     fn test_combined_interceptor() {
@@ -99,4 +153,57 @@ mod tests {
         let result = interceptor.intercept(request);
         assert!(result.is_ok());
     }
+
+    /// Auth and a per-user limit of one request
+    fn limited(order: InterceptorOrder) -> (CombinedInterceptor, Arc<TokenManager>) {
+        let token_manager = Arc::new(TokenManager::new("secret", "issuer".to_string()));
+        let ratelimit = RateLimitInterceptor::new(RateLimitConfig {
+            requests_per_second: 1,
+            burst_size: 1,
+            per_user: true,
+            ..Default::default()
+        });
+
+        let interceptor = CombinedInterceptor::new()
+            .with_auth(AuthInterceptor::new(Arc::clone(&token_manager)))
+            .with_ratelimit(ratelimit)
+            .with_order(order);
+        (interceptor, token_manager)
+    }
+
+    fn request_with_token(token: &str) -> Request<()> {
+        let mut request = Request::new(());
+        request
+            .metadata_mut()
+            .insert("authorization", format!("Bearer {}", token).parse().unwrap());
+        request
+    }
+
+    #[test]
+    fn test_auth_first_rejects_bad_token_without_spending_budget() {
+        let (interceptor, token_manager) = limited(InterceptorOrder::AuthFirst);
+
+        for _ in 0..3 {
+            let status = interceptor.intercept(request_with_token("forged")).unwrap_err();
+            assert_eq!(status.code(), tonic::Code::Unauthenticated);
+        }
+
+        let token = token_manager.generate_token("alice", vec![]).unwrap();
+        assert!(interceptor.intercept(request_with_token(&token)).is_ok());
+
+        let status = interceptor.intercept(request_with_token(&token)).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    }
+
+    #[test]
+    fn test_rate_limit_first_spends_budget_on_bad_token() {
+        let (interceptor, token_manager) = limited(InterceptorOrder::RateLimitFirst);
+
+        let status = interceptor.intercept(request_with_token("forged")).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::Unauthenticated);
+
+        let token = token_manager.generate_token("alice", vec![]).unwrap();
+        let status = interceptor.intercept(request_with_token(&token)).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+    }
 }
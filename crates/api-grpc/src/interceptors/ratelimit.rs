@@ -1,17 +1,33 @@
 //! Rate limiting interceptor for gRPC requests
 
+use super::MethodPath;
 use crate::error::ApiError;
 use dashmap::DashMap;
-use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed},
-    Quota, RateLimiter as GovernorRateLimiter,
-};
-use std::num::NonZeroU32;
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use tonic::{Request, Status};
 use tracing::warn;
 
+pub use llm_optimizer_config::RateLimitAlgorithm;
+use llm_optimizer_config::RateLimiter;
+
+/// Window over which `requests_per_second` is counted
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// Method path prefixes never rate limited, so health probes and reflection
+/// keep working while clients are being throttled
+const EXEMPT_PREFIXES: &[&str] = &["/grpc.health.v1.Health/", "/grpc.reflection."];
+
+/// Limit applied to a single gRPC method in place of the default
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MethodRateLimit {
+    /// Requests per second allowed
+    pub requests_per_second: u32,
+    /// Burst size
+    pub burst_size: u32,
+}
+
 /// Rate limiter configuration
 #[derive(Debug, Clone)]
 pub struct RateLimitConfig {
@@ -21,6 +37,49 @@ pub struct RateLimitConfig {
     pub burst_size: u32,
     /// Enable per-user rate limiting
     pub per_user: bool,
+    /// Limiting algorithm
+    pub algorithm: RateLimitAlgorithm,
+    /// Per-method overrides keyed by full gRPC method path, e.g.
+    /// `/llm.optimizer.optimization.OptimizationService/GetOptimization`.
+    /// Each overridden method has its own budget; all other methods share
+    /// the default one.
+    pub method_limits: HashMap<String, MethodRateLimit>,
+}
+
+impl RateLimitConfig {
+    /// Override the limit for a single method
+    pub fn with_method_limit(
+        mut self,
+        method: impl Into<String>,
+        requests_per_second: u32,
+        burst_size: u32,
+    ) -> Self {
+        self.method_limits.insert(
+            method.into(),
+            MethodRateLimit {
+                requests_per_second,
+                burst_size,
+            },
+        );
+        self
+    }
+
+    /// Limit for a method, falling back to the default
+    fn limit_for(&self, method: &str) -> MethodRateLimit {
+        self.method_limits
+            .get(method)
+            .copied()
+            .unwrap_or(MethodRateLimit {
+                requests_per_second: self.requests_per_second,
+                burst_size: self.burst_size,
+            })
+    }
+
+    /// New limiter for a method's budget
+    fn limiter_for(&self, method: &str) -> RateLimiter {
+        let limit = self.limit_for(method);
+        RateLimiter::new(self.algorithm, limit.requests_per_second, WINDOW, limit.burst_size)
+    }
 }
 
 impl Default for RateLimitConfig {
@@ -29,16 +88,19 @@ impl Default for RateLimitConfig {
             requests_per_second: 100,
             burst_size: 10,
             per_user: true,
+            algorithm: RateLimitAlgorithm::TokenBucket,
+            method_limits: HashMap::new(),
         }
     }
 }
 
 /// Rate limiting interceptor
 pub struct RateLimitInterceptor {
-    /// Global rate limiter
-    global_limiter: Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
-    /// Per-user rate limiters
-    user_limiters: Arc<DashMap<String, Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>,
+    /// Global rate limiters, keyed by overridden method path or `""` for
+    /// the shared default budget
+    global_limiters: Arc<DashMap<String, Arc<RateLimiter>>>,
+    /// Per-user rate limiters, keyed by user and then as `global_limiters`
+    user_limiters: Arc<DashMap<(String, String), Arc<RateLimiter>>>,
     /// Configuration
     config: RateLimitConfig,
 }
@@ -46,36 +108,36 @@ pub struct RateLimitInterceptor {
 impl RateLimitInterceptor {
     /// Create a new rate limit interceptor
     pub fn new(config: RateLimitConfig) -> Self {
-        let quota = Quota::per_second(
-            NonZeroU32::new(config.requests_per_second)
-                .expect("requests_per_second must be > 0"),
-        )
-        .allow_burst(
-            NonZeroU32::new(config.burst_size).expect("burst_size must be > 0"),
-        );
-
         Self {
-            global_limiter: Arc::new(GovernorRateLimiter::direct(quota)),
+            global_limiters: Arc::new(DashMap::new()),
             user_limiters: Arc::new(DashMap::new()),
             config,
         }
     }
 
+    /// Budget a method is counted against: its own if overridden, otherwise
+    /// the shared default
+    fn budget<'a>(&self, method: &'a str) -> &'a str {
+        if self.config.method_limits.contains_key(method) {
+            method
+        } else {
+            ""
+        }
+    }
+
+    /// Get or create the global rate limiter for a budget
+    fn get_global_limiter(&self, budget: &str) -> Arc<RateLimiter> {
+        self.global_limiters
+            .entry(budget.to_string())
+            .or_insert_with(|| Arc::new(self.config.limiter_for(budget)))
+            .clone()
+    }
+
     /// Get or create a rate limiter for a user
-    fn get_user_limiter(&self, user_id: &str) -> Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
+    fn get_user_limiter(&self, user_id: &str, budget: &str) -> Arc<RateLimiter> {
         self.user_limiters
-            .entry(user_id.to_string())
-            .or_insert_with(|| {
-                let quota = Quota::per_second(
-                    NonZeroU32::new(self.config.requests_per_second)
-                        .expect("requests_per_second must be > 0"),
-                )
-                .allow_burst(
-                    NonZeroU32::new(self.config.burst_size)
-                        .expect("burst_size must be > 0"),
-                );
-                Arc::new(GovernorRateLimiter::direct(quota))
-            })
+            .entry((user_id.to_string(), budget.to_string()))
+            .or_insert_with(|| Arc::new(self.config.limiter_for(budget)))
             .clone()
     }
 
@@ -97,32 +159,49 @@ impl RateLimitInterceptor {
     }
 
     /// Intercept and apply rate limiting
+    ///
+    /// The method is read from the [`MethodPath`] recorded by
+    /// [`MethodPathLayer`](super::MethodPathLayer); without it the call
+    /// counts against the default budget. Health checks and reflection are
+    /// never limited.
     pub fn intercept<T>(&self, request: Request<T>) -> Result<Request<T>, Status> {
+        let method = MethodPath::of(&request).unwrap_or_default();
+        if EXEMPT_PREFIXES.iter().any(|prefix| method.starts_with(prefix)) {
+            return Ok(request);
+        }
+        let user_id = Self::extract_user_id(&request);
+        self.check(method, user_id.as_deref())?;
+        Ok(request)
+    }
+
+    /// Record a call to `method`, failing if it exceeds the method's limit
+    fn check(&self, method: &str, user_id: Option<&str>) -> Result<(), Status> {
+        let budget = self.budget(method);
+
         // Check global rate limit first
-        if self.global_limiter.check().is_err() {
-            warn!("Global rate limit exceeded");
+        if self.get_global_limiter(budget).check().is_err() {
+            warn!("Global rate limit exceeded for method: {}", method);
             return Err(Status::from(ApiError::RateLimitExceeded));
         }
 
         // Check per-user rate limit if enabled
         if self.config.per_user {
-            if let Some(user_id) = Self::extract_user_id(&request) {
-                let limiter = self.get_user_limiter(&user_id);
-                if limiter.check().is_err() {
-                    warn!("Rate limit exceeded for user: {}", user_id);
+            if let Some(user_id) = user_id {
+                if self.get_user_limiter(user_id, budget).check().is_err() {
+                    warn!("Rate limit exceeded for user: {} on method: {}", user_id, method);
                     return Err(Status::from(ApiError::RateLimitExceeded));
                 }
             }
         }
 
-        Ok(request)
+        Ok(())
     }
 }
 
 impl Clone for RateLimitInterceptor {
     fn clone(&self) -> Self {
         Self {
-            global_limiter: Arc::clone(&self.global_limiter),
+            global_limiters: Arc::clone(&self.global_limiters),
             user_limiters: Arc::clone(&self.user_limiters),
             config: self.config.clone(),
         }
@@ -154,6 +233,7 @@ mod tests {
             requests_per_second: 2,
             burst_size: 2,
             per_user: false,
+            ..Default::default()
         };
         let interceptor = RateLimitInterceptor::new(config);
 
@@ -164,4 +244,110 @@ mod tests {
         // Third request should fail (rate limit exceeded)
         assert!(interceptor.intercept(Request::new(())).is_err());
     }
+
+    #[test]
+    fn test_health_and_reflection_are_not_limited() {
+        let config = RateLimitConfig {
+            requests_per_second: 1,
+            burst_size: 1,
+            per_user: false,
+            ..Default::default()
+        };
+        let interceptor = RateLimitInterceptor::new(config);
+        let request = |path: &str| {
+            let mut request = Request::new(());
+            request.extensions_mut().insert(MethodPath(path.to_string()));
+            request
+        };
+
+        assert!(interceptor.intercept(request(GET)).is_ok());
+        assert!(interceptor.intercept(request(GET)).is_err());
+        for _ in 0..5 {
+            assert!(interceptor.intercept(request("/grpc.health.v1.Health/Check")).is_ok());
+            assert!(interceptor
+                .intercept(request(
+                    "/grpc.reflection.v1alpha.ServerReflection/ServerReflectionInfo"
+                ))
+                .is_ok());
+        }
+    }
+
+    const BATCH_CREATE: &str =
+        "/llm.optimizer.optimization.OptimizationService/BatchCreateOptimizations";
+    const GET: &str = "/llm.optimizer.optimization.OptimizationService/GetOptimization";
+
+    #[test]
+    fn test_method_override_limits_expensive_method_only() {
+        let config = RateLimitConfig {
+            requests_per_second: 100,
+            burst_size: 100,
+            ..Default::default()
+        }
+        .with_method_limit(BATCH_CREATE, 1, 2);
+        let interceptor = RateLimitInterceptor::new(config);
+
+        assert!(interceptor.check(BATCH_CREATE, Some("alice")).is_ok());
+        assert!(interceptor.check(BATCH_CREATE, Some("alice")).is_ok());
+        let status = interceptor.check(BATCH_CREATE, Some("alice")).unwrap_err();
+        assert_eq!(status.code(), tonic::Code::ResourceExhausted);
+
+        for _ in 0..10 {
+            assert!(interceptor.check(GET, Some("alice")).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_unlisted_methods_share_default_budget() {
+        let config = RateLimitConfig {
+            requests_per_second: 2,
+            burst_size: 2,
+            per_user: false,
+            ..Default::default()
+        }
+        .with_method_limit(BATCH_CREATE, 10, 10);
+        let interceptor = RateLimitInterceptor::new(config);
+
+        assert!(interceptor.check(GET, None).is_ok());
+        assert!(interceptor.check("/llm.optimizer.config.ConfigService/GetConfig", None).is_ok());
+        assert!(interceptor.check(GET, None).is_err());
+        assert!(interceptor.check(BATCH_CREATE, None).is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_intercepted_service_limits_by_request_path() {
+        use crate::interceptors::MethodPathLayer;
+        use std::convert::Infallible;
+        use tonic::codegen::http;
+        use tonic::service::interceptor::InterceptedService;
+        use tower::{Layer, ServiceExt};
+
+        let config = RateLimitConfig {
+            requests_per_second: 100,
+            burst_size: 100,
+            per_user: false,
+            ..Default::default()
+        }
+        .with_method_limit(BATCH_CREATE, 1, 1);
+        let service = MethodPathLayer.layer(InterceptedService::new(
+            tower::service_fn(|_: http::Request<()>| async {
+                Ok::<_, Infallible>(http::Response::new(tonic::body::empty_body()))
+            }),
+            create_ratelimit_interceptor(config),
+        ));
+
+        let call = |path: &'static str| {
+            let service = service.clone();
+            async move {
+                let request = http::Request::post(path).body(()).unwrap();
+                let response = service.oneshot(request).await.unwrap();
+                Status::from_header_map(response.headers()).map(|status| status.code())
+            }
+        };
+
+        assert_eq!(call(BATCH_CREATE).await, None);
+        assert_eq!(call(BATCH_CREATE).await, Some(tonic::Code::ResourceExhausted));
+        for _ in 0..5 {
+            assert_eq!(call(GET).await, None);
+        }
+    }
 }
@@ -43,6 +43,7 @@
 
 pub mod auth;
 pub mod error;
+pub mod health;
 pub mod interceptors;
 pub mod server;
 pub mod services;
@@ -51,7 +52,9 @@ pub mod streaming;
 // Re-export commonly used types
 pub use auth::{Claims, Permission, TokenManager};
 pub use error::{ApiError, Result};
+pub use health::{HealthSource, ServingStatus};
 pub use server::{GrpcServer, GrpcServerConfig, TlsConfig};
+pub use services::ServiceStatusSource;
 
 /// Generated protobuf code
 /// This module will be populated by build.rs
@@ -93,6 +96,10 @@ pub mod proto {
     pub mod admin {
         tonic::include_proto!("llm.optimizer.admin");
     }
+
+    /// Encoded `FileDescriptorSet` of every proto file, served by reflection
+    pub const FILE_DESCRIPTOR_SET: &[u8] =
+        tonic::include_file_descriptor_set!("llm_optimizer_descriptor");
 }
 
 /// Library version
@@ -2,7 +2,11 @@
 
 use crate::auth::TokenManager;
 use crate::error::{ApiError, Result};
-use crate::interceptors::{LoggingInterceptor, RateLimitConfig, RateLimitInterceptor};
+use crate::health::{HealthSource, DEFAULT_HEALTH_POLL_INTERVAL};
+use crate::interceptors::{
+    CombinedInterceptor, DrainLayer, DrainState, LoggingConfig, LoggingInterceptor,
+    MethodPathLayer, RateLimitConfig, RateLimitInterceptor,
+};
 use crate::services::*;
 use std::net::SocketAddr;
 use std::path::PathBuf;
@@ -63,10 +67,26 @@ pub struct GrpcServerConfig {
     pub jwt_issuer: String,
     /// Rate limit configuration
     pub rate_limit: RateLimitConfig,
-    /// Enable gRPC reflection
+    /// Request log sampling
+    pub logging: LoggingConfig,
+    /// Serve the gRPC reflection service, so clients such as `grpcurl` can
+    /// list services and fetch descriptors. On by default in debug builds
+    /// only.
     pub enable_reflection: bool,
-    /// Enable health checking
+    /// Enable health checking, including the standard `grpc.health.v1.Health`
+    /// service used by Kubernetes probes and `grpc_health_probe`
     pub enable_health: bool,
+    /// Component statuses reported by `grpc.health.v1.Health`; without one
+    /// only the overall status is reported, always as serving
+    pub health_source: Option<Arc<dyn HealthSource>>,
+    /// How often `health_source` is polled
+    pub health_poll_interval: Duration,
+    /// Managed services listed by `AdminService.ListServices`; without one
+    /// the list is empty
+    pub service_status: Option<Arc<dyn ServiceStatusSource>>,
+    /// Draining flag set on shutdown; new calls fail with `UNAVAILABLE`
+    /// while those in flight finish
+    pub drain: Arc<DrainState>,
     /// Request timeout
     pub request_timeout: Duration,
     /// Maximum concurrent connections
@@ -85,8 +105,13 @@ impl Default for GrpcServerConfig {
             jwt_secret: "change-me-in-production".to_string(),
             jwt_issuer: "llm-optimizer".to_string(),
             rate_limit: RateLimitConfig::default(),
-            enable_reflection: true,
+            logging: LoggingConfig::default(),
+            enable_reflection: cfg!(debug_assertions),
             enable_health: true,
+            health_source: None,
+            health_poll_interval: DEFAULT_HEALTH_POLL_INTERVAL,
+            service_status: None,
+            drain: Arc::new(DrainState::new()),
             request_timeout: Duration::from_secs(30),
             max_concurrent_connections: 1000,
             tcp_keepalive: Some(Duration::from_secs(60)),
@@ -133,7 +158,7 @@ impl GrpcServer {
     }
 
     /// Build and configure the server
-    async fn build_server(&self) -> Result<tonic::transport::server::Router> {
+    async fn build_server(&self) -> Result<Server> {
         let mut server = Server::builder()
             .timeout(self.config.request_timeout)
             .concurrency_limit_per_connection(256)
@@ -166,11 +191,23 @@ impl GrpcServer {
     pub async fn serve(self) -> Result<()> {
         info!("Starting gRPC server on {}", self.config.addr);
 
-        let server = self.build_server().await?;
-
         // Create interceptors
-        let logging = LoggingInterceptor::new("grpc-api");
+        let logging = LoggingInterceptor::new("grpc-api").with_config(self.config.logging);
         let ratelimit = RateLimitInterceptor::new(self.config.rate_limit.clone());
+        let interceptor = CombinedInterceptor::new()
+            .with_logging(logging)
+            .with_ratelimit(ratelimit);
+
+        // The method path is recorded before the interceptors run, since
+        // they cannot see the URI
+        let mut server = self
+            .build_server()
+            .await?
+            .layer(DrainLayer::new(Arc::clone(&self.config.drain)))
+            .layer(MethodPathLayer)
+            .layer(tonic::service::interceptor(move |request| {
+                interceptor.intercept(request)
+            }));
 
         // Create services
         let optimization_service = optimization::OptimizationServiceImpl::new();
@@ -178,13 +215,13 @@ impl GrpcServer {
         let metrics_service = metrics::MetricsServiceImpl::new();
         let integrations_service = integrations::IntegrationServiceImpl::new();
         let health_service = health::HealthServiceImpl::new();
-        let admin_service = admin::AdminServiceImpl::new();
-
-        // Build router with services
-        let mut router = server;
+        let mut admin_service = admin::AdminServiceImpl::new();
+        if let Some(source) = self.config.service_status.clone() {
+            admin_service = admin_service.with_service_status(source);
+        }
 
-        // Add optimization service
-        router = router.add_service(
+        // Build router with services, starting with the optimization service
+        let mut router = server.add_service(
             crate::proto::optimization::optimization_service_server::OptimizationServiceServer::new(
                 optimization_service,
             ),
@@ -209,13 +246,21 @@ impl GrpcServer {
             ),
         );
 
-        // Add health service
+        // Add health services
         if self.config.enable_health {
             router = router.add_service(
                 crate::proto::health::health_service_server::HealthServiceServer::new(
                     health_service,
                 ),
             );
+            router = router.add_service(
+                crate::health::health_service(
+                    self.config.health_source.clone(),
+                    self.config.health_poll_interval,
+                    self.shutdown_signal(),
+                )
+                .await,
+            );
         }
 
         // Add admin service
@@ -226,7 +271,11 @@ impl GrpcServer {
         // Add reflection if enabled
         if self.config.enable_reflection {
             info!("gRPC reflection enabled");
-            // Reflection will be added when we have the file descriptor sets
+            let v1 = reflection_builder().build_v1().map_err(reflection_error)?;
+            let v1alpha = reflection_builder()
+                .build_v1alpha()
+                .map_err(reflection_error)?;
+            router = router.add_service(v1).add_service(v1alpha);
         }
 
         info!("gRPC server listening on {}", self.config.addr);
@@ -247,18 +296,86 @@ impl GrpcServer {
     }
 }
 
+/// Reflection over every compiled proto file
+///
+/// Served as both `v1` and `v1alpha`: newer `grpcurl` releases use the former,
+/// while Postman and older clients only speak the latter.
+fn reflection_builder() -> tonic_reflection::server::Builder<'static> {
+    tonic_reflection::server::Builder::configure()
+        .register_encoded_file_descriptor_set(crate::proto::FILE_DESCRIPTOR_SET)
+}
+
+fn reflection_error(e: tonic_reflection::server::Error) -> ApiError {
+    ApiError::Configuration(format!("Failed to build reflection service: {}", e))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use tokio_stream::StreamExt;
+    use tonic::transport::{server::TcpIncoming, Endpoint};
+    use tonic_reflection::pb::v1::{
+        server_reflection_client::ServerReflectionClient,
+        server_reflection_request::MessageRequest, server_reflection_response::MessageResponse,
+        ServerReflectionRequest,
+    };
 
     #[test]
     fn test_default_config() {
         let config = GrpcServerConfig::default();
         assert_eq!(config.addr.port(), 50051);
-        assert!(config.enable_reflection);
+        assert_eq!(config.enable_reflection, cfg!(debug_assertions));
         assert!(config.enable_health);
     }
 
+    #[tokio::test]
+    async fn test_reflection_lists_services() {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let incoming = TcpIncoming::from_listener(listener, true, None).unwrap();
+        tokio::spawn(
+            Server::builder()
+                .add_service(reflection_builder().build_v1().unwrap())
+                .serve_with_incoming(incoming),
+        );
+
+        let channel = Endpoint::from_shared(format!("http://{}", addr))
+            .unwrap()
+            .connect()
+            .await
+            .unwrap();
+        let mut client = ServerReflectionClient::new(channel);
+        let request = ServerReflectionRequest {
+            host: String::new(),
+            message_request: Some(MessageRequest::ListServices(String::new())),
+        };
+        let mut responses = client
+            .server_reflection_info(tokio_stream::once(request))
+            .await
+            .unwrap()
+            .into_inner();
+
+        let response = responses.next().await.unwrap().unwrap();
+        let Some(MessageResponse::ListServicesResponse(list)) = response.message_response else {
+            panic!("expected a list of services");
+        };
+        let mut services: Vec<String> = list.service.into_iter().map(|s| s.name).collect();
+        services.sort();
+
+        assert_eq!(
+            services,
+            [
+                "grpc.reflection.v1.ServerReflection",
+                "llm.optimizer.admin.AdminService",
+                "llm.optimizer.config.ConfigService",
+                "llm.optimizer.health.HealthService",
+                "llm.optimizer.integrations.IntegrationService",
+                "llm.optimizer.metrics.MetricsService",
+                "llm.optimizer.optimization.OptimizationService",
+            ]
+        );
+    }
+
     #[tokio::test]
     async fn test_server_creation() {
         let config = GrpcServerConfig::default();
@@ -1,16 +1,34 @@
 //! Admin service implementation
 
 use crate::proto::admin::*;
+use std::fmt;
+use std::sync::Arc;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
 use tracing::debug;
 
+/// Supplies the status of the services managed by the process, normally
+/// its service manager
+#[tonic::async_trait]
+pub trait ServiceStatusSource: Send + Sync + fmt::Debug {
+    /// Current status of every managed service
+    async fn service_statuses(&self) -> Vec<ManagedService>;
+}
+
 #[derive(Debug, Clone, Default)]
-pub struct AdminServiceImpl {}
+pub struct AdminServiceImpl {
+    service_status: Option<Arc<dyn ServiceStatusSource>>,
+}
 
 impl AdminServiceImpl {
     pub fn new() -> Self {
-        Self {}
+        Self::default()
+    }
+
+    /// Report managed services from `source` in `ListServices`
+    pub fn with_service_status(mut self, source: Arc<dyn ServiceStatusSource>) -> Self {
+        self.service_status = Some(source);
+        self
     }
 }
 
@@ -49,6 +67,22 @@ impl admin_service_server::AdminService for AdminServiceImpl {
         }))
     }
     
+    async fn list_services(&self, _req: Request<ListServicesRequest>) -> Result<Response<ListServicesResponse>, Status> {
+        debug!("ListServices called");
+        let services = match &self.service_status {
+            Some(source) => source.service_statuses().await,
+            None => vec![],
+        };
+        Ok(Response::new(ListServicesResponse {
+            services,
+            status: Some(crate::proto::common::ApiResponse {
+                status: crate::proto::common::ResponseStatus::Success as i32,
+                message: "Services listed".to_string(),
+                errors: vec![],
+            }),
+        }))
+    }
+
     async fn get_database_stats(&self, _req: Request<GetDatabaseStatsRequest>) -> Result<Response<GetDatabaseStatsResponse>, Status> {
         debug!("GetDatabaseStats called");
         Ok(Response::new(GetDatabaseStatsResponse {
@@ -233,3 +267,44 @@ impl admin_service_server::AdminService for AdminServiceImpl {
         Ok(Response::new(ReceiverStream::new(rx)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use admin_service_server::AdminService;
+
+    #[derive(Debug)]
+    struct FixedStatuses;
+
+    #[tonic::async_trait]
+    impl ServiceStatusSource for FixedStatuses {
+        async fn service_statuses(&self) -> Vec<ManagedService> {
+            vec![ManagedService {
+                name: "storage".to_string(),
+                state: "running".to_string(),
+                uptime_seconds: 42,
+                restart_count: 2,
+                last_health_message: String::new(),
+            }]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_services_reports_source_statuses() {
+        let response = AdminServiceImpl::new()
+            .list_services(Request::new(ListServicesRequest {}))
+            .await
+            .unwrap();
+        assert!(response.into_inner().services.is_empty());
+
+        let response = AdminServiceImpl::new()
+            .with_service_status(Arc::new(FixedStatuses))
+            .list_services(Request::new(ListServicesRequest {}))
+            .await
+            .unwrap();
+        let services = response.into_inner().services;
+        assert_eq!(services.len(), 1);
+        assert_eq!(services[0].name, "storage");
+        assert_eq!(services[0].restart_count, 2);
+    }
+}
@@ -0,0 +1,162 @@
+//! Storage for optimization decisions
+
+use crate::error::{ApiError, Result};
+use crate::proto::optimization::{DecisionStatus, OptimizationDecision, OptimizationStrategy};
+use dashmap::mapref::entry::Entry;
+use dashmap::DashMap;
+use std::fmt::Debug;
+use std::time::SystemTime;
+
+/// Criteria for listing decisions; empty lists match everything
+#[derive(Debug, Clone, Default)]
+pub struct DecisionFilter {
+    /// Match any of these statuses
+    pub statuses: Vec<DecisionStatus>,
+    /// Match any of these strategies
+    pub strategies: Vec<OptimizationStrategy>,
+    /// Match decisions targeting any of these services
+    pub services: Vec<String>,
+    /// Only decisions created at or after this time
+    pub created_after: Option<SystemTime>,
+    /// Only decisions created at or before this time
+    pub created_before: Option<SystemTime>,
+}
+
+impl DecisionFilter {
+    /// Whether a decision satisfies every criterion
+    pub fn matches(&self, decision: &OptimizationDecision) -> bool {
+        if !self.statuses.is_empty() && !self.statuses.contains(&decision.status()) {
+            return false;
+        }
+
+        if !self.strategies.is_empty() && !self.strategies.contains(&decision.strategy()) {
+            return false;
+        }
+
+        if !self.services.is_empty()
+            && !decision
+                .target_services
+                .iter()
+                .any(|service| self.services.contains(service))
+        {
+            return false;
+        }
+
+        if self.created_after.is_some() || self.created_before.is_some() {
+            let Some(created_at) = created_at(decision) else {
+                return false;
+            };
+            if self.created_after.is_some_and(|after| created_at < after)
+                || self
+                    .created_before
+                    .is_some_and(|before| created_at > before)
+            {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+/// Creation time of a decision, if it has a valid one
+fn created_at(decision: &OptimizationDecision) -> Option<SystemTime> {
+    decision
+        .created_at
+        .clone()
+        .and_then(|timestamp| SystemTime::try_from(timestamp).ok())
+}
+
+/// Persists optimization decisions for the gRPC optimization service
+pub trait DecisionStore: Send + Sync + Debug {
+    /// Store a new decision, failing if one with the same ID exists
+    fn create(&self, decision: OptimizationDecision) -> Result<OptimizationDecision>;
+
+    /// Fetch a decision by ID
+    fn get(&self, id: &str) -> Result<OptimizationDecision>;
+
+    /// All decisions matching `filter`, oldest first
+    fn list(&self, filter: &DecisionFilter) -> Vec<OptimizationDecision>;
+
+    /// Apply `update` to a stored decision and return the result. The update
+    /// runs atomically with respect to other writers; if it fails the stored
+    /// decision is left unchanged.
+    fn update(
+        &self,
+        id: &str,
+        update: &mut dyn FnMut(&mut OptimizationDecision) -> Result<()>,
+    ) -> Result<OptimizationDecision>;
+}
+
+/// In-memory [`DecisionStore`]; contents are lost on restart
+#[derive(Debug, Default)]
+pub struct InMemoryDecisionStore {
+    decisions: DashMap<String, OptimizationDecision>,
+}
+
+impl InMemoryDecisionStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of stored decisions
+    pub fn len(&self) -> usize {
+        self.decisions.len()
+    }
+
+    /// Whether the store holds no decisions
+    pub fn is_empty(&self) -> bool {
+        self.decisions.is_empty()
+    }
+}
+
+impl DecisionStore for InMemoryDecisionStore {
+    fn create(&self, decision: OptimizationDecision) -> Result<OptimizationDecision> {
+        match self.decisions.entry(decision.id.clone()) {
+            Entry::Occupied(_) => Err(ApiError::AlreadyExists(format!(
+                "Optimization {} already exists",
+                decision.id
+            ))),
+            Entry::Vacant(entry) => Ok(entry.insert(decision).clone()),
+        }
+    }
+
+    fn get(&self, id: &str) -> Result<OptimizationDecision> {
+        self.decisions
+            .get(id)
+            .map(|decision| decision.clone())
+            .ok_or_else(|| ApiError::NotFound(format!("Optimization {} not found", id)))
+    }
+
+    fn list(&self, filter: &DecisionFilter) -> Vec<OptimizationDecision> {
+        let mut decisions: Vec<_> = self
+            .decisions
+            .iter()
+            .filter(|entry| filter.matches(entry.value()))
+            .map(|entry| entry.value().clone())
+            .collect();
+        decisions.sort_by(|a, b| {
+            created_at(a)
+                .cmp(&created_at(b))
+                .then_with(|| a.id.cmp(&b.id))
+        });
+        decisions
+    }
+
+    fn update(
+        &self,
+        id: &str,
+        update: &mut dyn FnMut(&mut OptimizationDecision) -> Result<()>,
+    ) -> Result<OptimizationDecision> {
+        let mut entry = self
+            .decisions
+            .get_mut(id)
+            .ok_or_else(|| ApiError::NotFound(format!("Optimization {} not found", id)))?;
+
+        let mut updated = entry.clone();
+        update(&mut updated)?;
+        *entry = updated.clone();
+        Ok(updated)
+    }
+}
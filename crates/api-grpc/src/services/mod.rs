@@ -1,15 +1,19 @@
 //! Service implementations for gRPC API
 
+pub mod decision_store;
 pub mod optimization;
 pub mod config;
 pub mod metrics;
 pub mod integrations;
 pub mod health;
 pub mod admin;
+pub mod validation;
 
+pub use decision_store::{DecisionFilter, DecisionStore, InMemoryDecisionStore};
 pub use optimization::OptimizationServiceImpl;
 pub use config::ConfigServiceImpl;
 pub use metrics::MetricsServiceImpl;
 pub use integrations::IntegrationServiceImpl;
 pub use health::HealthServiceImpl;
-pub use admin::AdminServiceImpl;
+pub use admin::{AdminServiceImpl, ServiceStatusSource};
+pub use validation::validate_decision;
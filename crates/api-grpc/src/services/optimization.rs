@@ -1,27 +1,119 @@
 //! Optimization service implementation
 
 use crate::error::{ApiError, Result};
+use crate::proto::common::{ApiResponse, PageResponse, ResponseStatus};
 use crate::proto::optimization::*;
+use crate::services::decision_store::{DecisionFilter, DecisionStore, InMemoryDecisionStore};
+use crate::services::validation::validate_decision;
 use crate::streaming::optimization::OptimizationStreamManager;
 use std::sync::Arc;
+use std::time::SystemTime;
 use tokio::sync::RwLock;
 use tokio_stream::wrappers::ReceiverStream;
 use tonic::{Request, Response, Status};
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
+
+/// Page size used when a list request does not specify one
+const DEFAULT_PAGE_SIZE: i32 = 10;
+
+/// Largest page size a list request may ask for
+const MAX_PAGE_SIZE: i32 = 100;
+
+/// Statuses from which a decision may be deployed
+const DEPLOYABLE: &[DecisionStatus] = &[
+    DecisionStatus::Pending,
+    DecisionStatus::AwaitingApproval,
+    DecisionStatus::DeploymentFailed,
+];
+
+/// Statuses from which a decision may be marked as failing validation
+const VALIDATABLE: &[DecisionStatus] = &[
+    DecisionStatus::Pending,
+    DecisionStatus::Validating,
+    DecisionStatus::AwaitingApproval,
+];
+
+/// Statuses from which a deployed decision may be rolled back
+const ROLLBACKABLE: &[DecisionStatus] = &[
+    DecisionStatus::Deploying,
+    DecisionStatus::Deployed,
+    DecisionStatus::Monitoring,
+    DecisionStatus::Completed,
+];
+
+/// Statuses from which a decision may be cancelled
+const CANCELLABLE: &[DecisionStatus] = &[
+    DecisionStatus::Pending,
+    DecisionStatus::Validating,
+    DecisionStatus::AwaitingApproval,
+];
 
 /// Optimization service implementation
 #[derive(Debug, Clone)]
 pub struct OptimizationServiceImpl {
     stream_manager: Arc<RwLock<OptimizationStreamManager>>,
+    store: Arc<dyn DecisionStore>,
 }
 
 impl OptimizationServiceImpl {
-    /// Create a new optimization service
+    /// Create a new optimization service backed by an in-memory store
     pub fn new() -> Self {
+        Self::with_store(Arc::new(InMemoryDecisionStore::new()))
+    }
+
+    /// Create a new optimization service backed by `store`
+    pub fn with_store(store: Arc<dyn DecisionStore>) -> Self {
         Self {
             stream_manager: Arc::new(RwLock::new(OptimizationStreamManager::new())),
+            store,
         }
     }
+
+    /// Build and store a pending decision from a create request
+    fn create_decision(&self, req: CreateOptimizationRequest) -> Result<OptimizationDecision> {
+        let decision = OptimizationDecision {
+            id: uuid::Uuid::new_v4().to_string(),
+            created_at: Some(prost_types::Timestamp::from(SystemTime::now())),
+            strategy: req.strategy,
+            target_services: req.target_services,
+            changes: req.changes,
+            rationale: req.rationale,
+            expected_impact: req.expected_impact,
+            constraints: req.constraints,
+            status: DecisionStatus::Pending as i32,
+            deployed_at: None,
+            rolled_back_at: None,
+            actual_impact: None,
+            metadata: std::collections::HashMap::new(),
+        };
+
+        self.store.create(decision)
+    }
+
+    /// Move a stored decision to `to`, provided it is currently in one of `from`
+    fn transition(
+        &self,
+        id: &str,
+        from: &[DecisionStatus],
+        to: DecisionStatus,
+        apply: impl Fn(&mut OptimizationDecision),
+    ) -> Result<OptimizationDecision> {
+        self.store.update(id, &mut |decision| {
+            let current = decision.status();
+            if !from.contains(&current) {
+                return Err(ApiError::FailedPrecondition(format!(
+                    "Optimization {} is {}, cannot move to {}",
+                    id,
+                    current.as_str_name(),
+                    to.as_str_name()
+                )));
+            }
+
+            decision.status = to as i32;
+            apply(decision);
+            Ok(())
+        })
+    }
 }
 
 impl Default for OptimizationServiceImpl {
@@ -30,6 +122,31 @@ impl Default for OptimizationServiceImpl {
     }
 }
 
+/// Successful API response status with `message`
+fn success(message: impl Into<String>) -> Option<ApiResponse> {
+    Some(ApiResponse {
+        status: ResponseStatus::Success as i32,
+        message: message.into(),
+        errors: vec![],
+    })
+}
+
+/// Translate a list request's filters into a [`DecisionFilter`]
+fn list_filter(req: &ListOptimizationsRequest) -> DecisionFilter {
+    let time_range = req.time_range.clone().unwrap_or_default();
+    let to_time = |timestamp: Option<prost_types::Timestamp>| {
+        timestamp.and_then(|timestamp| SystemTime::try_from(timestamp).ok())
+    };
+
+    DecisionFilter {
+        statuses: req.status_filter().collect(),
+        strategies: req.strategy_filter().collect(),
+        services: req.service_filter.clone(),
+        created_after: to_time(time_range.start),
+        created_before: to_time(time_range.end),
+    }
+}
+
 #[tonic::async_trait]
 impl optimization_service_server::OptimizationService for OptimizationServiceImpl {
     async fn create_optimization(
@@ -38,34 +155,13 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
     ) -> std::result::Result<Response<CreateOptimizationResponse>, Status> {
         debug!("CreateOptimization called");
 
-        let req = request.into_inner();
-
-        // Create optimization decision
-        let decision = OptimizationDecision {
-            id: uuid::Uuid::new_v4().to_string(),
-            created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            strategy: req.strategy,
-            target_services: req.target_services,
-            changes: req.changes,
-            rationale: req.rationale,
-            expected_impact: req.expected_impact,
-            constraints: req.constraints,
-            status: DecisionStatus::Pending as i32,
-            deployed_at: None,
-            rolled_back_at: None,
-            actual_impact: None,
-            metadata: std::collections::HashMap::new(),
-        };
+        let decision = self.create_decision(request.into_inner())?;
 
         info!("Created optimization decision: {}", decision.id);
 
         let response = CreateOptimizationResponse {
             decision: Some(decision),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Optimization created successfully".to_string(),
-                errors: vec![],
-            }),
+            status: success("Optimization created successfully"),
         };
 
         Ok(Response::new(response))
@@ -78,32 +174,11 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
         debug!("GetOptimization called");
 
         let req = request.into_inner();
-
-        // In production, fetch from database
-        // For now, return a mock response
-        let decision = OptimizationDecision {
-            id: req.decision_id.clone(),
-            created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            strategy: OptimizationStrategy::CostPerformanceScoring as i32,
-            target_services: vec!["service-1".to_string()],
-            changes: vec![],
-            rationale: "Mock optimization".to_string(),
-            expected_impact: None,
-            constraints: vec![],
-            status: DecisionStatus::Pending as i32,
-            deployed_at: None,
-            rolled_back_at: None,
-            actual_impact: None,
-            metadata: std::collections::HashMap::new(),
-        };
+        let decision = self.store.get(&req.decision_id)?;
 
         let response = GetOptimizationResponse {
             decision: Some(decision),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Optimization retrieved successfully".to_string(),
-                errors: vec![],
-            }),
+            status: success("Optimization retrieved successfully"),
         };
 
         Ok(Response::new(response))
@@ -115,22 +190,41 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
     ) -> std::result::Result<Response<ListOptimizationsResponse>, Status> {
         debug!("ListOptimizations called");
 
-        let _req = request.into_inner();
+        let req = request.into_inner();
+        let mut decisions = self.store.list(&list_filter(&req));
+
+        let page_request = req.pagination.unwrap_or_default();
+        if !page_request.ascending {
+            decisions.reverse();
+        }
+
+        let page_size = if page_request.page_size > 0 {
+            page_request.page_size.min(MAX_PAGE_SIZE)
+        } else {
+            DEFAULT_PAGE_SIZE
+        };
+        let page = page_request.page.max(1);
+        let total_items = decisions.len();
+        let total_pages = total_items.div_ceil(page_size as usize);
+        let skip = (page as usize - 1).saturating_mul(page_size as usize);
+
+        let decisions = decisions
+            .into_iter()
+            .skip(skip)
+            .take(page_size as usize)
+            .collect();
+        let total_items = i32::try_from(total_items).unwrap_or(i32::MAX);
+        let total_pages = i32::try_from(total_pages).unwrap_or(i32::MAX);
 
-        // In production, fetch from database with filters
         let response = ListOptimizationsResponse {
-            decisions: vec![],
-            pagination: Some(crate::proto::common::PageResponse {
-                total_items: 0,
-                total_pages: 0,
-                current_page: 1,
-                page_size: 10,
-            }),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Optimizations retrieved successfully".to_string(),
-                errors: vec![],
+            decisions,
+            pagination: Some(PageResponse {
+                total_items,
+                total_pages,
+                current_page: page,
+                page_size,
             }),
+            status: success("Optimizations retrieved successfully"),
         };
 
         Ok(Response::new(response))
@@ -143,32 +237,34 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
         debug!("DeployOptimization called");
 
         let req = request.into_inner();
-
-        // In production, trigger deployment workflow
-        let decision = OptimizationDecision {
-            id: req.decision_id.clone(),
-            created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            strategy: OptimizationStrategy::CostPerformanceScoring as i32,
-            target_services: vec![],
-            changes: vec![],
-            rationale: "Deploying optimization".to_string(),
-            expected_impact: None,
-            constraints: vec![],
-            status: DecisionStatus::Deploying as i32,
-            deployed_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            rolled_back_at: None,
-            actual_impact: None,
-            metadata: std::collections::HashMap::new(),
-        };
+        if req.canary && !(0.0..=100.0).contains(&req.canary_percentage) {
+            return Err(ApiError::InvalidArgument(format!(
+                "canary_percentage must be between 0 and 100, got {}",
+                req.canary_percentage
+            ))
+            .into());
+        }
+        let deployment_id = uuid::Uuid::new_v4().to_string();
+
+        let decision = self.transition(
+            &req.decision_id,
+            DEPLOYABLE,
+            DecisionStatus::Deployed,
+            |decision| {
+                decision.deployed_at = Some(prost_types::Timestamp::from(SystemTime::now()));
+                decision.rolled_back_at = None;
+                decision
+                    .metadata
+                    .insert("deployment_id".to_string(), deployment_id.clone());
+            },
+        )?;
+
+        info!("Deployed optimization decision: {}", decision.id);
 
         let response = DeployOptimizationResponse {
             decision: Some(decision),
-            deployment_id: uuid::Uuid::new_v4().to_string(),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Deployment initiated".to_string(),
-                errors: vec![],
-            }),
+            deployment_id,
+            status: success("Optimization deployed"),
         };
 
         Ok(Response::new(response))
@@ -182,29 +278,23 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
 
         let req = request.into_inner();
 
-        let decision = OptimizationDecision {
-            id: req.decision_id,
-            created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            strategy: OptimizationStrategy::CostPerformanceScoring as i32,
-            target_services: vec![],
-            changes: vec![],
-            rationale: req.reason,
-            expected_impact: None,
-            constraints: vec![],
-            status: DecisionStatus::RolledBack as i32,
-            deployed_at: None,
-            rolled_back_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            actual_impact: None,
-            metadata: std::collections::HashMap::new(),
-        };
+        let decision = self.transition(
+            &req.decision_id,
+            ROLLBACKABLE,
+            DecisionStatus::RolledBack,
+            |decision| {
+                decision.rolled_back_at = Some(prost_types::Timestamp::from(SystemTime::now()));
+                decision
+                    .metadata
+                    .insert("rollback_reason".to_string(), req.reason.clone());
+            },
+        )?;
+
+        info!("Rolled back optimization decision: {}", decision.id);
 
         let response = RollbackOptimizationResponse {
             decision: Some(decision),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Rollback completed".to_string(),
-                errors: vec![],
-            }),
+            status: success("Rollback completed"),
         };
 
         Ok(Response::new(response))
@@ -218,29 +308,22 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
 
         let req = request.into_inner();
 
-        let decision = OptimizationDecision {
-            id: req.decision_id,
-            created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-            strategy: OptimizationStrategy::CostPerformanceScoring as i32,
-            target_services: vec![],
-            changes: vec![],
-            rationale: req.reason,
-            expected_impact: None,
-            constraints: vec![],
-            status: DecisionStatus::Cancelled as i32,
-            deployed_at: None,
-            rolled_back_at: None,
-            actual_impact: None,
-            metadata: std::collections::HashMap::new(),
-        };
+        let decision = self.transition(
+            &req.decision_id,
+            CANCELLABLE,
+            DecisionStatus::Cancelled,
+            |decision| {
+                decision
+                    .metadata
+                    .insert("cancel_reason".to_string(), req.reason.clone());
+            },
+        )?;
+
+        info!("Cancelled optimization decision: {}", decision.id);
 
         let response = CancelOptimizationResponse {
             decision: Some(decision),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Optimization cancelled".to_string(),
-                errors: vec![],
-            }),
+            status: success("Optimization cancelled"),
         };
 
         Ok(Response::new(response))
@@ -252,28 +335,34 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
     ) -> std::result::Result<Response<ValidateOptimizationResponse>, Status> {
         debug!("ValidateOptimization called");
 
-        let _req = request.into_inner();
-
-        let validation = ValidationResult {
-            valid: true,
-            errors: vec![],
-            warnings: vec![],
-            revised_impact: None,
-        };
+        let req = request.into_inner();
+        let decision = self.store.get(&req.decision_id)?;
+        let validation = validate_decision(&decision);
+
+        // Outside a dry run, a failed validation blocks deployment
+        if !validation.valid && !req.dry_run {
+            self.transition(
+                &req.decision_id,
+                VALIDATABLE,
+                DecisionStatus::ValidationFailed,
+                |_| {},
+            )?;
+            info!(
+                "Optimization decision {} failed validation",
+                req.decision_id
+            );
+        }
 
         let response = ValidateOptimizationResponse {
             validation: Some(validation),
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: "Validation completed".to_string(),
-                errors: vec![],
-            }),
+            status: success("Validation completed"),
         };
 
         Ok(Response::new(response))
     }
 
-    type SubscribeOptimizationEventsStream = ReceiverStream<std::result::Result<OptimizationEvent, Status>>;
+    type SubscribeOptimizationEventsStream =
+        ReceiverStream<std::result::Result<OptimizationEvent, Status>>;
 
     async fn subscribe_optimization_events(
         &self,
@@ -301,42 +390,30 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
         let mut failed = 0;
 
         while let Some(req) = stream.message().await? {
-            // Process each request
-            let decision = OptimizationDecision {
-                id: uuid::Uuid::new_v4().to_string(),
-                created_at: Some(prost_types::Timestamp::from(std::time::SystemTime::now())),
-                strategy: req.strategy,
-                target_services: req.target_services,
-                changes: req.changes,
-                rationale: req.rationale,
-                expected_impact: req.expected_impact,
-                constraints: req.constraints,
-                status: DecisionStatus::Pending as i32,
-                deployed_at: None,
-                rolled_back_at: None,
-                actual_impact: None,
-                metadata: std::collections::HashMap::new(),
-            };
-
-            decisions.push(decision);
-            successful += 1;
+            match self.create_decision(req) {
+                Ok(decision) => {
+                    decisions.push(decision);
+                    successful += 1;
+                }
+                Err(e) => {
+                    warn!("Failed to create optimization in batch: {}", e);
+                    failed += 1;
+                }
+            }
         }
 
         let response = BatchCreateOptimizationsResponse {
             decisions,
             successful,
             failed,
-            status: Some(crate::proto::common::ApiResponse {
-                status: crate::proto::common::ResponseStatus::Success as i32,
-                message: format!("Batch created {} optimizations", successful),
-                errors: vec![],
-            }),
+            status: success(format!("Batch created {} optimizations", successful)),
         };
 
         Ok(Response::new(response))
     }
 
-    type OptimizationSessionStream = ReceiverStream<std::result::Result<OptimizationSessionMessage, Status>>;
+    type OptimizationSessionStream =
+        ReceiverStream<std::result::Result<OptimizationSessionMessage, Status>>;
 
     async fn optimization_session(
         &self,
@@ -350,3 +427,281 @@ impl optimization_service_server::OptimizationService for OptimizationServiceImp
         Ok(Response::new(stream))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use optimization_service_server::OptimizationService;
+
+    fn create_request(strategy: OptimizationStrategy, service: &str) -> CreateOptimizationRequest {
+        CreateOptimizationRequest {
+            strategy: strategy as i32,
+            target_services: vec![service.to_string()],
+            rationale: "Reduce cost".to_string(),
+            ..Default::default()
+        }
+    }
+
+    async fn create(
+        service: &OptimizationServiceImpl,
+        strategy: OptimizationStrategy,
+        target: &str,
+    ) -> OptimizationDecision {
+        service
+            .create_optimization(Request::new(create_request(strategy, target)))
+            .await
+            .unwrap()
+            .into_inner()
+            .decision
+            .unwrap()
+    }
+
+    async fn get(service: &OptimizationServiceImpl, id: &str) -> OptimizationDecision {
+        service
+            .get_optimization(Request::new(GetOptimizationRequest {
+                decision_id: id.to_string(),
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .decision
+            .unwrap()
+    }
+
+    /// IDs returned by a list request, sorted
+    async fn list_ids(
+        service: &OptimizationServiceImpl,
+        req: ListOptimizationsRequest,
+    ) -> Vec<String> {
+        let ids = service
+            .list_optimizations(Request::new(req))
+            .await
+            .unwrap()
+            .into_inner()
+            .decisions
+            .into_iter()
+            .map(|decision| decision.id)
+            .collect();
+        sorted(ids)
+    }
+
+    fn sorted(mut ids: Vec<String>) -> Vec<String> {
+        ids.sort();
+        ids
+    }
+
+    #[tokio::test]
+    async fn test_lifecycle_persists_status() {
+        let service = OptimizationServiceImpl::new();
+        let created = create(
+            &service,
+            OptimizationStrategy::CostPerformanceScoring,
+            "svc-a",
+        )
+        .await;
+
+        let fetched = get(&service, &created.id).await;
+        assert_eq!(fetched, created);
+        assert_eq!(fetched.status(), DecisionStatus::Pending);
+
+        service
+            .deploy_optimization(Request::new(DeployOptimizationRequest {
+                decision_id: created.id.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap();
+        let deployed = get(&service, &created.id).await;
+        assert_eq!(deployed.status(), DecisionStatus::Deployed);
+        assert!(deployed.deployed_at.is_some());
+
+        service
+            .rollback_optimization(Request::new(RollbackOptimizationRequest {
+                decision_id: created.id.clone(),
+                reason: "quality regression".to_string(),
+            }))
+            .await
+            .unwrap();
+        let rolled_back = get(&service, &created.id).await;
+        assert_eq!(rolled_back.status(), DecisionStatus::RolledBack);
+        assert!(rolled_back.rolled_back_at.is_some());
+        assert_eq!(
+            rolled_back.metadata["rollback_reason"],
+            "quality regression"
+        );
+
+        let status = service
+            .deploy_optimization(Request::new(DeployOptimizationRequest {
+                decision_id: created.id.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+        assert_eq!(
+            get(&service, &created.id).await.status(),
+            DecisionStatus::RolledBack
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_unknown_optimization_is_not_found() {
+        let service = OptimizationServiceImpl::new();
+        let status = service
+            .get_optimization(Request::new(GetOptimizationRequest {
+                decision_id: "missing".to_string(),
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::NotFound);
+    }
+
+    #[tokio::test]
+    async fn test_list_applies_filters() {
+        let service = OptimizationServiceImpl::new();
+        let cost_a = create(
+            &service,
+            OptimizationStrategy::CostPerformanceScoring,
+            "svc-a",
+        )
+        .await;
+        let cost_b = create(
+            &service,
+            OptimizationStrategy::CostPerformanceScoring,
+            "svc-b",
+        )
+        .await;
+        let other_a = create(&service, OptimizationStrategy::AbTesting, "svc-a").await;
+
+        service
+            .cancel_optimization(Request::new(CancelOptimizationRequest {
+                decision_id: cost_b.id.clone(),
+                reason: String::new(),
+            }))
+            .await
+            .unwrap();
+
+        let by_status = list_ids(
+            &service,
+            ListOptimizationsRequest {
+                status_filter: vec![DecisionStatus::Pending as i32],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            by_status,
+            sorted(vec![cost_a.id.clone(), other_a.id.clone()])
+        );
+
+        let by_strategy = list_ids(
+            &service,
+            ListOptimizationsRequest {
+                strategy_filter: vec![OptimizationStrategy::CostPerformanceScoring as i32],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(
+            by_strategy,
+            sorted(vec![cost_a.id.clone(), cost_b.id.clone()])
+        );
+
+        let by_service = list_ids(
+            &service,
+            ListOptimizationsRequest {
+                service_filter: vec!["svc-a".to_string()],
+                strategy_filter: vec![OptimizationStrategy::CostPerformanceScoring as i32],
+                ..Default::default()
+            },
+        )
+        .await;
+        assert_eq!(by_service, vec![cost_a.id.clone()]);
+
+        let all = list_ids(&service, ListOptimizationsRequest::default()).await;
+        assert_eq!(all.len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_list_handles_extreme_pagination() {
+        let service = OptimizationServiceImpl::new();
+        create(&service, OptimizationStrategy::AbTesting, "svc-a").await;
+
+        let list = |page, page_size| {
+            service.list_optimizations(Request::new(ListOptimizationsRequest {
+                pagination: Some(crate::proto::common::PageRequest {
+                    page,
+                    page_size,
+                    ..Default::default()
+                }),
+                ..Default::default()
+            }))
+        };
+
+        let response = list(1, i32::MAX).await.unwrap().into_inner();
+        let pagination = response.pagination.unwrap();
+        assert_eq!(response.decisions.len(), 1);
+        assert_eq!(pagination.page_size, MAX_PAGE_SIZE);
+        assert_eq!(pagination.total_pages, 1);
+
+        let response = list(i32::MAX, i32::MAX).await.unwrap().into_inner();
+        assert!(response.decisions.is_empty());
+        assert_eq!(response.pagination.unwrap().current_page, i32::MAX);
+    }
+
+    #[tokio::test]
+    async fn test_failed_validation_blocks_deploy_unless_dry_run() {
+        let service = OptimizationServiceImpl::new();
+        let created = service
+            .create_optimization(Request::new(CreateOptimizationRequest {
+                constraints: vec![Constraint {
+                    constraint_type: "min_confidence".to_string(),
+                    value: "high".to_string(),
+                    hard: true,
+                }],
+                ..create_request(OptimizationStrategy::CostPerformanceScoring, "svc-a")
+            }))
+            .await
+            .unwrap()
+            .into_inner()
+            .decision
+            .unwrap();
+
+        let validate = |dry_run| ValidateOptimizationRequest {
+            decision_id: created.id.clone(),
+            dry_run,
+        };
+
+        let validation = service
+            .validate_optimization(Request::new(validate(true)))
+            .await
+            .unwrap()
+            .into_inner()
+            .validation
+            .unwrap();
+        assert!(!validation.valid);
+        assert_eq!(validation.errors[0].field, "constraints[0].value");
+        assert_eq!(
+            get(&service, &created.id).await.status(),
+            DecisionStatus::Pending
+        );
+
+        service
+            .validate_optimization(Request::new(validate(false)))
+            .await
+            .unwrap();
+        assert_eq!(
+            get(&service, &created.id).await.status(),
+            DecisionStatus::ValidationFailed
+        );
+
+        let status = service
+            .deploy_optimization(Request::new(DeployOptimizationRequest {
+                decision_id: created.id.clone(),
+                ..Default::default()
+            }))
+            .await
+            .unwrap_err();
+        assert_eq!(status.code(), tonic::Code::FailedPrecondition);
+    }
+}
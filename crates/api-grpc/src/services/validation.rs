@@ -0,0 +1,317 @@
+//! Validation of optimization decisions
+//!
+//! Each known constraint type declares how its value is parsed and, where it
+//! can be, how it is checked against the decision's expected impact:
+//!
+//! | Constraint type           | Value          | Satisfied when                      |
+//! |---------------------------|----------------|-------------------------------------|
+//! | `min_cost_reduction_pct`  | number         | `cost_reduction_pct >= value`       |
+//! | `min_quality_delta_pct`   | number         | `quality_delta_pct >= value`        |
+//! | `max_latency_delta_pct`   | number         | `latency_delta_pct <= value`        |
+//! | `min_confidence`          | number, 0–1    | `confidence >= value`               |
+//! | `min_quality`             | number, 0–1    | not checked against impact          |
+//! | `max_cost`                | number, ≥ 0    | not checked against impact          |
+//! | `rollout_percentage`      | number, 0–100  | not checked against impact          |
+//! | `max_rollout_percentage`  | number, 0–100  | not checked against impact          |
+//! | `require_approval`        | `true`/`false` | not checked against impact          |
+//!
+//! Unknown constraint types are reported as warnings.
+
+use crate::proto::common::ErrorDetail;
+use crate::proto::optimization::{
+    Constraint, ExpectedImpact, OptimizationDecision, ValidationResult,
+};
+
+/// How a constraint's value must parse
+#[derive(Debug, Clone, Copy)]
+enum ValueKind {
+    /// Any finite number
+    Number,
+    /// A finite number within an inclusive range
+    Range(f64, f64),
+    /// `true` or `false`
+    Bool,
+}
+
+/// Expected impact a constraint is checked against
+#[derive(Debug, Clone, Copy)]
+enum ImpactBound {
+    /// The impact must be at least the constraint's value
+    AtLeast(fn(&ExpectedImpact) -> f64),
+    /// The impact must be at most the constraint's value
+    AtMost(fn(&ExpectedImpact) -> f64),
+}
+
+/// Value kind and impact bound of a known constraint type
+fn constraint_spec(constraint_type: &str) -> Option<(ValueKind, Option<ImpactBound>)> {
+    let spec = match constraint_type {
+        "min_cost_reduction_pct" => (
+            ValueKind::Number,
+            Some(ImpactBound::AtLeast(|impact| impact.cost_reduction_pct)),
+        ),
+        "min_quality_delta_pct" => (
+            ValueKind::Number,
+            Some(ImpactBound::AtLeast(|impact| impact.quality_delta_pct)),
+        ),
+        "max_latency_delta_pct" => (
+            ValueKind::Number,
+            Some(ImpactBound::AtMost(|impact| impact.latency_delta_pct)),
+        ),
+        "min_confidence" => (
+            ValueKind::Range(0.0, 1.0),
+            Some(ImpactBound::AtLeast(|impact| impact.confidence)),
+        ),
+        "min_quality" => (ValueKind::Range(0.0, 1.0), None),
+        "max_cost" => (ValueKind::Range(0.0, f64::INFINITY), None),
+        "rollout_percentage" | "max_rollout_percentage" => (ValueKind::Range(0.0, 100.0), None),
+        "require_approval" => (ValueKind::Bool, None),
+        _ => return None,
+    };
+    Some(spec)
+}
+
+/// Error detail with a machine-readable `code` for `field`
+fn error(code: &str, field: impl Into<String>, message: impl Into<String>) -> ErrorDetail {
+    ErrorDetail {
+        code: code.to_string(),
+        message: message.into(),
+        field: field.into(),
+        metadata: Default::default(),
+    }
+}
+
+/// Parse a numeric constraint value, rejecting NaN and infinities
+fn parse_number(value: &str) -> Option<f64> {
+    value.trim().parse::<f64>().ok().filter(|n| n.is_finite())
+}
+
+/// Check a single constraint, recording any problems in `result`
+fn validate_constraint(
+    index: usize,
+    constraint: &Constraint,
+    impact: Option<&ExpectedImpact>,
+    result: &mut ValidationResult,
+) {
+    let field = format!("constraints[{}].value", index);
+    let name = &constraint.constraint_type;
+
+    let Some((kind, bound)) = constraint_spec(name) else {
+        result.warnings.push(format!(
+            "Unknown constraint type '{}' was not checked",
+            name
+        ));
+        return;
+    };
+
+    let number = match kind {
+        ValueKind::Bool => {
+            if constraint.value.trim().parse::<bool>().is_err() {
+                result.errors.push(error(
+                    "INVALID_CONSTRAINT_VALUE",
+                    field,
+                    format!("{} must be true or false, got '{}'", name, constraint.value),
+                ));
+            }
+            return;
+        }
+        ValueKind::Number | ValueKind::Range(..) => match parse_number(&constraint.value) {
+            Some(number) => number,
+            None => {
+                result.errors.push(error(
+                    "INVALID_CONSTRAINT_VALUE",
+                    field,
+                    format!("{} must be a number, got '{}'", name, constraint.value),
+                ));
+                return;
+            }
+        },
+    };
+
+    if let ValueKind::Range(min, max) = kind {
+        if !(min..=max).contains(&number) {
+            let code = if name.contains("rollout") {
+                "ROLLOUT_OUT_OF_RANGE"
+            } else {
+                "CONSTRAINT_OUT_OF_RANGE"
+            };
+            result.errors.push(error(
+                code,
+                field,
+                format!(
+                    "{} must be between {} and {}, got {}",
+                    name, min, max, number
+                ),
+            ));
+            return;
+        }
+    }
+
+    let (Some(bound), Some(impact)) = (bound, impact) else {
+        if bound.is_some() && constraint.hard {
+            result.warnings.push(format!(
+                "Hard constraint {} cannot be checked without an expected impact",
+                name
+            ));
+        }
+        return;
+    };
+
+    let (satisfied, expected) = match bound {
+        ImpactBound::AtLeast(get) => (get(impact) >= number, get(impact)),
+        ImpactBound::AtMost(get) => (get(impact) <= number, get(impact)),
+    };
+    if satisfied {
+        return;
+    }
+
+    let message = format!(
+        "{} of {} is not met by the expected impact ({})",
+        name, number, expected
+    );
+    if constraint.hard {
+        result
+            .errors
+            .push(error("UNSATISFIABLE_CONSTRAINT", field, message));
+    } else {
+        result.warnings.push(format!("Soft constraint {}", message));
+    }
+}
+
+/// Validate a decision's target services and constraints
+///
+/// The result is invalid if any error was recorded; soft constraints the
+/// expected impact does not meet and unknown constraint types only produce
+/// warnings.
+pub fn validate_decision(decision: &OptimizationDecision) -> ValidationResult {
+    let mut result = ValidationResult::default();
+
+    if decision.target_services.is_empty() {
+        result.errors.push(error(
+            "EMPTY_TARGET_SERVICES",
+            "target_services",
+            "At least one target service is required",
+        ));
+    } else if let Some(index) = decision
+        .target_services
+        .iter()
+        .position(|service| service.trim().is_empty())
+    {
+        result.errors.push(error(
+            "EMPTY_TARGET_SERVICE",
+            format!("target_services[{}]", index),
+            "Target service names must not be empty",
+        ));
+    }
+
+    if decision.changes.is_empty() {
+        result
+            .warnings
+            .push("Optimization has no configuration changes".to_string());
+    }
+
+    for (index, constraint) in decision.constraints.iter().enumerate() {
+        validate_constraint(
+            index,
+            constraint,
+            decision.expected_impact.as_ref(),
+            &mut result,
+        );
+    }
+
+    result.valid = result.errors.is_empty();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decision(target_services: Vec<&str>, constraints: Vec<Constraint>) -> OptimizationDecision {
+        OptimizationDecision {
+            target_services: target_services.into_iter().map(String::from).collect(),
+            expected_impact: Some(ExpectedImpact {
+                cost_reduction_pct: 40.0,
+                quality_delta_pct: -3.0,
+                latency_delta_pct: 5.0,
+                confidence: 0.9,
+            }),
+            constraints,
+            ..Default::default()
+        }
+    }
+
+    fn constraint(constraint_type: &str, value: &str, hard: bool) -> Constraint {
+        Constraint {
+            constraint_type: constraint_type.to_string(),
+            value: value.to_string(),
+            hard,
+        }
+    }
+
+    fn codes(result: &ValidationResult) -> Vec<&str> {
+        result.errors.iter().map(|e| e.code.as_str()).collect()
+    }
+
+    #[test]
+    fn test_satisfied_constraints_are_valid() {
+        let result = validate_decision(&decision(
+            vec!["svc-a"],
+            vec![
+                constraint("min_cost_reduction_pct", "30", true),
+                constraint("max_latency_delta_pct", "10", true),
+                constraint("rollout_percentage", "25", false),
+            ],
+        ));
+        assert!(result.valid, "{:?}", result.errors);
+    }
+
+    #[test]
+    fn test_empty_target_services_is_invalid() {
+        let result = validate_decision(&decision(vec![], vec![]));
+        assert!(!result.valid);
+        assert_eq!(codes(&result), vec!["EMPTY_TARGET_SERVICES"]);
+        assert_eq!(result.errors[0].field, "target_services");
+    }
+
+    #[test]
+    fn test_malformed_constraint_value_is_invalid() {
+        let result = validate_decision(&decision(
+            vec!["svc-a"],
+            vec![
+                constraint("min_quality", "0.8", true),
+                constraint("max_latency_delta_pct", "fast", false),
+            ],
+        ));
+        assert!(!result.valid);
+        assert_eq!(codes(&result), vec!["INVALID_CONSTRAINT_VALUE"]);
+        assert_eq!(result.errors[0].field, "constraints[1].value");
+    }
+
+    #[test]
+    fn test_unsatisfiable_hard_constraint_is_invalid_but_soft_only_warns() {
+        let hard = validate_decision(&decision(
+            vec!["svc-a"],
+            vec![constraint("min_quality_delta_pct", "0", true)],
+        ));
+        assert_eq!(codes(&hard), vec!["UNSATISFIABLE_CONSTRAINT"]);
+
+        let soft = validate_decision(&decision(
+            vec!["svc-a"],
+            vec![constraint("min_quality_delta_pct", "0", false)],
+        ));
+        assert!(soft.valid);
+        assert!(soft
+            .warnings
+            .iter()
+            .any(|w| w.contains("min_quality_delta_pct")));
+    }
+
+    #[test]
+    fn test_rollout_percentage_out_of_range_is_invalid() {
+        let result = validate_decision(&decision(
+            vec!["svc-a"],
+            vec![constraint("max_rollout_percentage", "150", false)],
+        ));
+        assert_eq!(codes(&result), vec!["ROLLOUT_OUT_OF_RANGE"]);
+    }
+}
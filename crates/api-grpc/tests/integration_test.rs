@@ -13,6 +13,6 @@ async fn test_server_creation() {
 fn test_config_defaults() {
     let config = GrpcServerConfig::default();
     assert_eq!(config.addr.port(), 50051);
-    assert!(config.enable_reflection);
+    assert_eq!(config.enable_reflection, cfg!(debug_assertions));
     assert!(config.enable_health);
 }
@@ -1,13 +1,14 @@
 //! Error types and handling for the REST API
 
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
 use std::fmt;
 use thiserror::Error;
+use utoipa::ToSchema;
 
 /// API error type
 #[derive(Error, Debug)]
@@ -27,6 +28,9 @@ pub enum ApiError {
     #[error("Conflict: {0}")]
     Conflict(String),
 
+    #[error("Failed precondition: {0}")]
+    FailedPrecondition(String),
+
     #[error("Rate limit exceeded: {0}")]
     RateLimit(String),
 
@@ -45,6 +49,12 @@ pub enum ApiError {
     #[error("Unsupported media type: {0}")]
     UnsupportedMediaType(String),
 
+    #[error("Payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    #[error("Unprocessable entity: {0}")]
+    UnprocessableEntity(String),
+
     #[error(transparent)]
     OptimizerError(#[from] llm_optimizer_types::OptimizerError),
 
@@ -61,12 +71,15 @@ impl ApiError {
             ApiError::Validation(_) => StatusCode::BAD_REQUEST,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::FailedPrecondition(_) => StatusCode::CONFLICT,
             ApiError::RateLimit(_) => StatusCode::TOO_MANY_REQUESTS,
             ApiError::BadRequest(_) => StatusCode::BAD_REQUEST,
             ApiError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
             ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
             ApiError::Timeout(_) => StatusCode::GATEWAY_TIMEOUT,
             ApiError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            ApiError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            ApiError::UnprocessableEntity(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ApiError::OptimizerError(e) => match e {
                 llm_optimizer_types::OptimizerError::NotFound(_) => StatusCode::NOT_FOUND,
                 llm_optimizer_types::OptimizerError::AlreadyExists(_) => StatusCode::CONFLICT,
@@ -85,12 +98,15 @@ impl ApiError {
             ApiError::Validation(_) => "validation_error",
             ApiError::NotFound(_) => "not_found",
             ApiError::Conflict(_) => "conflict",
+            ApiError::FailedPrecondition(_) => "failed_precondition",
             ApiError::RateLimit(_) => "rate_limit_exceeded",
             ApiError::BadRequest(_) => "bad_request",
             ApiError::Internal(_) => "internal_error",
             ApiError::ServiceUnavailable(_) => "service_unavailable",
             ApiError::Timeout(_) => "timeout",
             ApiError::UnsupportedMediaType(_) => "unsupported_media_type",
+            ApiError::PayloadTooLarge(_) => "payload_too_large",
+            ApiError::UnprocessableEntity(_) => "unprocessable_entity",
             ApiError::OptimizerError(_) => "optimizer_error",
             ApiError::Other(_) => "internal_error",
         }
@@ -98,7 +114,7 @@ impl ApiError {
 }
 
 /// API error response
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
 pub struct ErrorResponse {
     /// Error type
     pub error: String,
@@ -136,6 +152,64 @@ impl ErrorResponse {
     }
 }
 
+/// How error responses are serialized
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorFormat {
+    /// [`ErrorResponse`] as `application/json`
+    #[default]
+    Standard,
+    /// RFC 7807 [`ProblemJson`] as `application/problem+json`
+    ProblemJson,
+}
+
+/// Content type of RFC 7807 problem details
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Base URI of problem `type`s; the error type is appended
+pub const PROBLEM_TYPE_BASE: &str = "https://llmdevops.dev/problems/";
+
+/// RFC 7807 problem details
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProblemJson {
+    /// URI identifying the problem type
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// HTTP status code
+    pub status: u16,
+    /// Explanation specific to this occurrence
+    pub detail: String,
+    /// Request ID of this occurrence
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+}
+
+impl ProblemJson {
+    /// Describe an error response sent with `status`
+    pub fn from_error(status: StatusCode, error: &ErrorResponse) -> Self {
+        Self {
+            problem_type: format!("{}{}", PROBLEM_TYPE_BASE, error.error),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: error.message.clone(),
+            instance: error.request_id.clone(),
+        }
+    }
+}
+
+impl IntoResponse for ProblemJson {
+    fn into_response(self) -> Response {
+        let status = StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        let mut response = (status, Json(self)).into_response();
+        response.headers_mut().insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+        );
+        response
+    }
+}
+
 impl fmt::Display for ErrorResponse {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}: {}", self.error, self.message)
@@ -158,7 +232,10 @@ impl IntoResponse for ApiError {
 
         let body = ErrorResponse::new(error_type, message);
 
-        (status, Json(body)).into_response()
+        // Kept so `error_format_middleware` can re-serialize the error
+        let mut response = (status, Json(body.clone())).into_response();
+        response.extensions_mut().insert(body);
+        response
     }
 }
 
@@ -219,6 +296,18 @@ mod tests {
             ApiError::RateLimit("test".into()).status_code(),
             StatusCode::TOO_MANY_REQUESTS
         );
+        assert_eq!(
+            ApiError::FailedPrecondition("test".into()).status_code(),
+            StatusCode::CONFLICT
+        );
+        assert_eq!(
+            ApiError::PayloadTooLarge("test".into()).status_code(),
+            StatusCode::PAYLOAD_TOO_LARGE
+        );
+        assert_eq!(
+            ApiError::UnprocessableEntity("test".into()).status_code(),
+            StatusCode::UNPROCESSABLE_ENTITY
+        );
     }
 
     #[test]
@@ -25,7 +25,7 @@ pub mod openapi;
 pub mod routes;
 pub mod server;
 
-pub use error::{ApiError, ApiResult, ErrorResponse};
+pub use error::{ApiError, ApiResult, ErrorFormat, ErrorResponse, ProblemJson};
 pub use server::{build_app, start_server, ServerConfig};
 
 /// Re-export commonly used types
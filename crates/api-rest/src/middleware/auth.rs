@@ -7,12 +7,21 @@ use axum::{
     response::Response,
 };
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
+use dashmap::DashMap;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use uuid::Uuid;
 
 use crate::error::{ApiError, ApiResult};
+use crate::middleware::rbac::{Permission, Role};
+
+/// Metadata claim marking a token as a refresh token
+const TOKEN_USE_CLAIM: &str = "token_use";
+
+/// `token_use` value of refresh tokens
+const REFRESH_TOKEN_USE: &str = "refresh";
 
 /// JWT claims structure
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +36,9 @@ pub struct Claims {
     pub jti: String,
     /// User roles
     pub roles: Vec<String>,
+    /// Permissions granted directly, on top of those of the roles
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub permissions: Vec<String>,
     /// Additional metadata
     #[serde(flatten)]
     pub metadata: std::collections::HashMap<String, serde_json::Value>,
@@ -35,10 +47,7 @@ pub struct Claims {
 impl Claims {
     /// Create new claims
     pub fn new(user_id: String, roles: Vec<String>, ttl_seconds: u64) -> Self {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let now = unix_now();
 
         Self {
             sub: user_id,
@@ -46,17 +55,14 @@ impl Claims {
             exp: now + ttl_seconds,
             jti: Uuid::new_v4().to_string(),
             roles,
+            permissions: Vec::new(),
             metadata: std::collections::HashMap::new(),
         }
     }
 
     /// Check if token is expired
     pub fn is_expired(&self) -> bool {
-        let now = SystemTime::now()
-            .duration_since(UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
-        self.exp <= now
+        self.exp <= unix_now()
     }
 
     /// Check if user has a specific role
@@ -68,6 +74,153 @@ impl Claims {
     pub fn has_any_role(&self, roles: &[&str]) -> bool {
         roles.iter().any(|role| self.has_role(role))
     }
+
+    /// Check if these are refresh token claims
+    pub fn is_refresh(&self) -> bool {
+        self.metadata.get(TOKEN_USE_CLAIM).and_then(|v| v.as_str()) == Some(REFRESH_TOKEN_USE)
+    }
+}
+
+/// Issued refresh token, keyed by its JWT ID in the store
+#[derive(Debug, Clone)]
+struct RefreshTokenRecord {
+    /// Rotation chain the token belongs to, shared by every token rotated
+    /// from the same login
+    family: String,
+    /// Expiration time
+    exp: u64,
+    /// Whether the token has already been exchanged
+    rotated: bool,
+}
+
+/// Refresh tokens issued by this server
+///
+/// Each refresh token can be exchanged once. Presenting a token that was
+/// already rotated means it was copied, so the whole family is revoked and
+/// both the thief and the legitimate client have to log in again.
+#[derive(Debug, Default)]
+pub struct RefreshTokenStore {
+    tokens: DashMap<String, RefreshTokenRecord>,
+}
+
+impl RefreshTokenStore {
+    /// Create an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of tokens still held, including rotated ones kept for reuse
+    /// detection
+    pub fn len(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Whether the store holds no tokens
+    pub fn is_empty(&self) -> bool {
+        self.tokens.is_empty()
+    }
+
+    fn insert(&self, claims: &Claims, family: String) {
+        let now = unix_now();
+        self.tokens.retain(|_, record| record.exp > now);
+        self.tokens.insert(
+            claims.jti.clone(),
+            RefreshTokenRecord {
+                family,
+                exp: claims.exp,
+                rotated: false,
+            },
+        );
+    }
+
+    /// Mark a token as exchanged, returning its family
+    fn rotate(&self, jti: &str) -> ApiResult<String> {
+        let family = {
+            let mut record = self
+                .tokens
+                .get_mut(jti)
+                .ok_or_else(|| ApiError::Authentication("Unknown refresh token".into()))?;
+            if !record.rotated {
+                record.rotated = true;
+                return Ok(record.family.clone());
+            }
+            record.family.clone()
+        };
+
+        self.revoke_family(&family);
+        tracing::warn!(family = %family, "Refresh token reused, revoking token family");
+        Err(ApiError::Authentication("Refresh token already used".into()))
+    }
+
+    fn revoke_family(&self, family: &str) {
+        self.tokens.retain(|_, record| record.family != family);
+    }
+}
+
+/// Roles and permissions granted to an API key
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ApiKeyScope {
+    /// Roles the key acts with
+    pub roles: Vec<Role>,
+    /// Permissions granted directly, on top of those of the roles
+    pub permissions: Vec<Permission>,
+}
+
+impl ApiKeyScope {
+    /// Create a scope granting nothing
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Grant a role
+    pub fn with_role(mut self, role: Role) -> Self {
+        self.roles.push(role);
+        self
+    }
+
+    /// Grant a single permission
+    pub fn with_permission(mut self, permission: Permission) -> Self {
+        self.permissions.push(permission);
+        self
+    }
+
+    /// Claims of a request authenticated with `api_key`
+    ///
+    /// The subject only carries a prefix of the key so it can be logged.
+    fn claims(&self, api_key: &str, ttl_seconds: u64) -> Claims {
+        let prefix = api_key.get(..8).unwrap_or(api_key);
+        let roles = self.roles.iter().map(|role| role.as_str().to_string()).collect();
+        let mut claims = Claims::new(format!("api_key:{}", prefix), roles, ttl_seconds);
+        claims.permissions = self
+            .permissions
+            .iter()
+            .map(|permission| permission.as_str().to_string())
+            .collect();
+        claims
+    }
+}
+
+/// Resolves API keys to the scope they were issued with
+pub trait ApiKeyStore: Send + Sync {
+    /// Look up an API key, returning `None` for unknown or revoked keys
+    fn resolve(&self, api_key: &str) -> Option<ApiKeyScope>;
+}
+
+impl ApiKeyStore for HashMap<String, ApiKeyScope> {
+    fn resolve(&self, api_key: &str) -> Option<ApiKeyScope> {
+        self.get(api_key).cloned()
+    }
+}
+
+/// Access token and the refresh token to exchange for the next one
+#[derive(Debug, Clone)]
+pub struct TokenPair {
+    /// Signed access token
+    pub access_token: String,
+    /// Signed refresh token
+    pub refresh_token: String,
+    /// Access token lifetime in seconds
+    pub expires_in: u64,
 }
 
 /// Authentication configuration
@@ -77,26 +230,58 @@ pub struct AuthConfig {
     pub jwt_secret: String,
     /// Token TTL in seconds
     pub token_ttl: u64,
+    /// Refresh token secret key; must differ from `jwt_secret` in production
+    pub refresh_secret: String,
     /// Refresh token TTL in seconds
     pub refresh_token_ttl: u64,
-    /// Valid API keys
-    pub api_keys: Arc<std::collections::HashSet<String>>,
+    /// API keys configured at startup, with their scopes
+    pub api_keys: Arc<HashMap<String, ApiKeyScope>>,
+    /// Store consulted for keys not configured at startup
+    pub api_key_store: Option<Arc<dyn ApiKeyStore>>,
+    /// Issued refresh tokens
+    pub refresh_tokens: Arc<RefreshTokenStore>,
 }
 
 impl AuthConfig {
     /// Create a new auth config
     pub fn new(jwt_secret: String) -> Self {
         Self {
+            refresh_secret: jwt_secret.clone(),
             jwt_secret,
             token_ttl: 3600, // 1 hour
             refresh_token_ttl: 604800, // 7 days
-            api_keys: Arc::new(std::collections::HashSet::new()),
+            api_keys: Arc::new(HashMap::new()),
+            api_key_store: None,
+            refresh_tokens: Arc::new(RefreshTokenStore::new()),
         }
     }
 
-    /// Add an API key
-    pub fn with_api_key(mut self, api_key: String) -> Self {
-        Arc::make_mut(&mut self.api_keys).insert(api_key);
+    /// Set the refresh token secret key
+    pub fn with_refresh_secret(mut self, refresh_secret: String) -> Self {
+        self.refresh_secret = refresh_secret;
+        self
+    }
+
+    /// Set the refresh token TTL in seconds
+    pub fn with_refresh_token_ttl(mut self, refresh_token_ttl: u64) -> Self {
+        self.refresh_token_ttl = refresh_token_ttl;
+        self
+    }
+
+    /// Add an API key acting as an `api_user`
+    pub fn with_api_key(self, api_key: String) -> Self {
+        self.with_scoped_api_key(api_key, ApiKeyScope::new().with_role(Role::ApiUser))
+    }
+
+    /// Add an API key limited to `scope`
+    pub fn with_scoped_api_key(mut self, api_key: String, scope: ApiKeyScope) -> Self {
+        Arc::make_mut(&mut self.api_keys).insert(api_key, scope);
+        self
+    }
+
+    /// Resolve keys not configured at startup through `store`
+    pub fn with_api_key_store(mut self, store: Arc<dyn ApiKeyStore>) -> Self {
+        self.api_key_store = Some(store);
         self
     }
 
@@ -112,15 +297,83 @@ impl AuthConfig {
         let decoding_key = DecodingKey::from_secret(self.jwt_secret.as_bytes());
         let validation = Validation::default();
 
-        decode::<Claims>(token, &decoding_key, &validation)
+        let claims = decode::<Claims>(token, &decoding_key, &validation)
             .map(|data| data.claims)
-            .map_err(|e| ApiError::Authentication(format!("Invalid token: {}", e)))
+            .map_err(|e| ApiError::Authentication(format!("Invalid token: {}", e)))?;
+        if claims.is_refresh() {
+            return Err(ApiError::Authentication(
+                "Refresh tokens cannot be used for authentication".into(),
+            ));
+        }
+        Ok(claims)
+    }
+
+    /// Issue an access token and a refresh token starting a new rotation chain
+    pub fn issue_token_pair(&self, user_id: String, roles: Vec<String>) -> ApiResult<TokenPair> {
+        self.issue_in_family(user_id, roles, Uuid::new_v4().to_string())
+    }
+
+    /// Exchange a refresh token for a new access token and a rotated refresh
+    /// token
+    ///
+    /// The presented token is invalidated. Presenting it again revokes every
+    /// refresh token rotated from the same login.
+    pub fn refresh(&self, refresh_token: &str) -> ApiResult<TokenPair> {
+        let decoding_key = DecodingKey::from_secret(self.refresh_secret.as_bytes());
+        let claims = decode::<Claims>(refresh_token, &decoding_key, &Validation::default())
+            .map(|data| data.claims)
+            .map_err(|e| ApiError::Authentication(format!("Invalid refresh token: {}", e)))?;
+        if !claims.is_refresh() {
+            return Err(ApiError::Authentication("Not a refresh token".into()));
+        }
+
+        let family = self.refresh_tokens.rotate(&claims.jti)?;
+        self.issue_in_family(claims.sub, claims.roles, family)
+    }
+
+    fn issue_in_family(
+        &self,
+        user_id: String,
+        roles: Vec<String>,
+        family: String,
+    ) -> ApiResult<TokenPair> {
+        let access_token =
+            self.generate_token(&Claims::new(user_id.clone(), roles.clone(), self.token_ttl))?;
+
+        let mut refresh_claims = Claims::new(user_id, roles, self.refresh_token_ttl);
+        refresh_claims
+            .metadata
+            .insert(TOKEN_USE_CLAIM.to_string(), REFRESH_TOKEN_USE.into());
+        let encoding_key = EncodingKey::from_secret(self.refresh_secret.as_bytes());
+        let refresh_token = encode(&Header::default(), &refresh_claims, &encoding_key)
+            .map_err(|e| ApiError::Internal(format!("Failed to generate token: {}", e)))?;
+        self.refresh_tokens.insert(&refresh_claims, family);
+
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            expires_in: self.token_ttl,
+        })
     }
 
     /// Verify an API key
     pub fn verify_api_key(&self, api_key: &str) -> bool {
-        self.api_keys.contains(api_key)
+        self.resolve_api_key(api_key).is_some()
     }
+
+    /// Look up the scope of an API key
+    pub fn resolve_api_key(&self, api_key: &str) -> Option<ApiKeyScope> {
+        self.api_keys
+            .resolve(api_key)
+            .or_else(|| self.api_key_store.as_ref()?.resolve(api_key))
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
 }
 
 /// Authentication method
@@ -128,25 +381,26 @@ impl AuthConfig {
 pub enum AuthMethod {
     /// JWT bearer token
     Bearer(Claims),
-    /// API key
-    ApiKey(String),
+    /// API key, with claims built from its scope
+    ApiKey { key: String, claims: Claims },
 }
 
 impl AuthMethod {
-    /// Get user ID
-    pub fn user_id(&self) -> String {
+    /// Get the claims, issued in the token or resolved from the API key
+    pub fn claims(&self) -> &Claims {
         match self {
-            AuthMethod::Bearer(claims) => claims.sub.clone(),
-            AuthMethod::ApiKey(key) => format!("api_key:{}", &key[..8]),
+            AuthMethod::Bearer(claims) | AuthMethod::ApiKey { claims, .. } => claims,
         }
     }
 
+    /// Get user ID
+    pub fn user_id(&self) -> String {
+        self.claims().sub.clone()
+    }
+
     /// Get roles
     pub fn roles(&self) -> Vec<String> {
-        match self {
-            AuthMethod::Bearer(claims) => claims.roles.clone(),
-            AuthMethod::ApiKey(_) => vec!["api_user".to_string()],
-        }
+        self.claims().roles.clone()
     }
 
     /// Check if has role
@@ -178,11 +432,13 @@ fn extract_auth(headers: &HeaderMap, config: &AuthConfig) -> ApiResult<AuthMetho
             .to_str()
             .map_err(|_| ApiError::Authentication("Invalid API key header".into()))?;
 
-        if config.verify_api_key(key) {
-            return Ok(AuthMethod::ApiKey(key.to_string()));
-        } else {
-            return Err(ApiError::Authentication("Invalid API key".into()));
-        }
+        return match config.resolve_api_key(key) {
+            Some(scope) => Ok(AuthMethod::ApiKey {
+                key: key.to_string(),
+                claims: scope.claims(key, config.token_ttl),
+            }),
+            None => Err(ApiError::Authentication("Invalid API key".into())),
+        };
     }
 
     Err(ApiError::Authentication(
@@ -252,6 +508,80 @@ mod tests {
         assert!(!config.verify_api_key("invalid-key"));
     }
 
+    #[test]
+    fn test_api_key_scopes_become_claims() {
+        struct Keys;
+        impl ApiKeyStore for Keys {
+            fn resolve(&self, api_key: &str) -> Option<ApiKeyScope> {
+                (api_key == "stored-key-123")
+                    .then(|| ApiKeyScope::new().with_permission(Permission::MetricsRead))
+            }
+        }
+
+        let config = AuthConfig::new("test-secret".to_string())
+            .with_api_key("default-key-123".to_string())
+            .with_scoped_api_key(
+                "readonly-key-123".to_string(),
+                ApiKeyScope::new().with_role(Role::ReadOnly),
+            )
+            .with_api_key_store(Arc::new(Keys));
+        let auth = |key: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert("x-api-key", key.parse().unwrap());
+            extract_auth(&headers, &config)
+        };
+
+        assert_eq!(auth("default-key-123").unwrap().roles(), vec!["api_user"]);
+        let readonly = auth("readonly-key-123").unwrap();
+        assert_eq!(readonly.user_id(), "api_key:readonly");
+        assert_eq!(readonly.roles(), vec!["readonly"]);
+        let stored = auth("stored-key-123").unwrap();
+        assert!(stored.roles().is_empty());
+        assert_eq!(stored.claims().permissions, vec!["metrics:read"]);
+        assert!(auth("unknown-key").is_err());
+    }
+
+    #[test]
+    fn test_refresh_rotates_tokens() {
+        let config = AuthConfig::new("test-secret".to_string())
+            .with_refresh_secret("refresh-secret".to_string());
+        let pair = config
+            .issue_token_pair("user-123".to_string(), vec!["admin".to_string()])
+            .unwrap();
+
+        let rotated = config.refresh(&pair.refresh_token).unwrap();
+        assert_ne!(rotated.refresh_token, pair.refresh_token);
+        let claims = config.verify_token(&rotated.access_token).unwrap();
+        assert_eq!(claims.sub, "user-123");
+        assert!(claims.has_role("admin"));
+
+        config.refresh(&rotated.refresh_token).unwrap();
+    }
+
+    #[test]
+    fn test_reused_refresh_token_revokes_family() {
+        let config = AuthConfig::new("test-secret".to_string());
+        let pair = config.issue_token_pair("user-123".to_string(), vec![]).unwrap();
+        let other = config.issue_token_pair("user-456".to_string(), vec![]).unwrap();
+
+        let rotated = config.refresh(&pair.refresh_token).unwrap();
+        let err = config.refresh(&pair.refresh_token).unwrap_err();
+        assert!(matches!(err, ApiError::Authentication(ref m) if m.contains("already used")));
+
+        // The token handed out by the rotation is revoked too, other logins are not
+        assert!(config.refresh(&rotated.refresh_token).is_err());
+        assert!(config.refresh(&other.refresh_token).is_ok());
+    }
+
+    #[test]
+    fn test_access_and_refresh_tokens_are_not_interchangeable() {
+        let config = AuthConfig::new("test-secret".to_string());
+        let pair = config.issue_token_pair("user-123".to_string(), vec![]).unwrap();
+
+        assert!(config.verify_token(&pair.refresh_token).is_err());
+        assert!(config.refresh(&pair.access_token).is_err());
+    }
+
     #[test]
     fn test_expired_token() {
         let mut claims = Claims::new("user-123".to_string(), vec![], 0);
@@ -0,0 +1,135 @@
+//! Request body size limit middleware
+//!
+//! Bodies that declare a `Content-Length` over the limit are rejected before
+//! any of the body is read. Bodies without one, such as chunked uploads, are
+//! counted as they stream in and rejected as soon as they cross the limit.
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    http::{header, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use http_body_util::Limited;
+
+use crate::error::ApiError;
+
+/// Default largest request body accepted, in bytes (2 MiB)
+pub const DEFAULT_MAX_BODY_BYTES: usize = 2 * 1024 * 1024;
+
+/// Reject request bodies larger than `max_bytes` with `413 Payload Too Large`
+///
+/// Handlers that fail to read an over-long streamed body produce a bare 413;
+/// those responses are replaced with the structured error so clients always
+/// see the same error body.
+pub async fn body_limit_middleware(
+    State(max_bytes): State<usize>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let declared_length = request
+        .headers()
+        .get(header::CONTENT_LENGTH)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<u64>().ok());
+    if declared_length.is_some_and(|length| length > max_bytes as u64) {
+        return payload_too_large(max_bytes);
+    }
+
+    let request = request.map(|body| Body::new(Limited::new(body, max_bytes)));
+    let response = next.run(request).await;
+
+    let is_json = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .is_some_and(|v| v.as_bytes().starts_with(b"application/json"));
+    if response.status() == StatusCode::PAYLOAD_TOO_LARGE && !is_json {
+        return payload_too_large(max_bytes);
+    }
+
+    response
+}
+
+fn payload_too_large(max_bytes: usize) -> Response {
+    ApiError::PayloadTooLarge(format!("Request body exceeds {} bytes", max_bytes)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Bytes, extract::DefaultBodyLimit, middleware, routing::post, Router};
+    use futures::StreamExt;
+    use http_body_util::BodyExt;
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    };
+    use tower::ServiceExt;
+
+    const LIMIT: usize = 1024;
+
+    fn app() -> Router {
+        Router::new()
+            .route("/upload", post(|body: Bytes| async move { body.len().to_string() }))
+            .layer(middleware::from_fn_with_state(LIMIT, body_limit_middleware))
+            .layer(DefaultBodyLimit::disable())
+    }
+
+    async fn error_code(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        body["error"].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn test_declared_oversized_body_is_rejected() {
+        let response = app()
+            .oneshot(
+                Request::post("/upload")
+                    .header(header::CONTENT_LENGTH, LIMIT + 1)
+                    .body(Body::from(vec![b'x'; LIMIT + 1]))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error_code(response).await, "payload_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_streamed_body_is_cut_off_at_the_limit() {
+        let chunks_read = Arc::new(AtomicUsize::new(0));
+        let counter = chunks_read.clone();
+        let stream = futures::stream::iter(0..100).map(move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Ok::<_, std::io::Error>(Bytes::from(vec![b'x'; 256]))
+        });
+
+        let response = app()
+            .oneshot(
+                Request::post("/upload")
+                    .body(Body::from_stream(stream))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(error_code(response).await, "payload_too_large");
+        assert!(chunks_read.load(Ordering::SeqCst) <= LIMIT / 256 + 1);
+    }
+
+    #[tokio::test]
+    async fn test_body_within_limit_passes() {
+        let response = app()
+            .oneshot(Request::post("/upload").body(Body::from(vec![b'x'; LIMIT])).unwrap())
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(&bytes[..], LIMIT.to_string().as_bytes());
+    }
+}
@@ -0,0 +1,137 @@
+//! Response compression configuration
+
+use tower_http::compression::{
+    predicate::{NotForContentType, Predicate, SizeAbove},
+    CompressionLayer,
+};
+
+pub use tower_http::compression::CompressionLevel;
+
+/// Default minimum response size to compress, in bytes
+pub const DEFAULT_MIN_COMPRESS_SIZE: u16 = 1024;
+
+/// Compression configuration
+///
+/// The encoding (brotli or gzip) is negotiated from the request's
+/// `Accept-Encoding` header. Images, gRPC and event streams are never
+/// compressed.
+#[derive(Clone, Copy, Debug)]
+pub struct CompressionConfig {
+    /// Responses smaller than this many bytes are sent uncompressed
+    pub min_size: u16,
+    /// Compression level
+    pub level: CompressionLevel,
+}
+
+impl CompressionConfig {
+    /// Create a new compression config
+    pub fn new() -> Self {
+        Self {
+            min_size: DEFAULT_MIN_COMPRESS_SIZE,
+            level: CompressionLevel::Default,
+        }
+    }
+
+    /// Set the minimum response size to compress
+    pub fn with_min_size(mut self, min_size: u16) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Set the compression level
+    pub fn with_level(mut self, level: CompressionLevel) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Build the CompressionLayer
+    pub fn build(self) -> CompressionLayer<impl Predicate> {
+        CompressionLayer::new()
+            .quality(self.level)
+            .compress_when(
+                SizeAbove::new(self.min_size)
+                    .and(NotForContentType::GRPC)
+                    .and(NotForContentType::IMAGES)
+                    .and(NotForContentType::SSE),
+            )
+    }
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+        response::Response,
+        routing::get,
+        Json, Router,
+    };
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn large_body() -> serde_json::Value {
+        let items: Vec<_> = (0..200)
+            .map(|i| serde_json::json!({"id": i, "service": "chat", "status": "deployed"}))
+            .collect();
+        serde_json::json!({ "items": items })
+    }
+
+    fn app(config: CompressionConfig) -> Router {
+        Router::new()
+            .route("/large", get(|| async { Json(large_body()) }))
+            .route("/small", get(|| async { Json(serde_json::json!({"status": "ok"})) }))
+            .layer(config.build())
+    }
+
+    async fn fetch(app: Router, uri: &str, accept_encoding: &str) -> Response {
+        app.oneshot(
+            Request::get(uri)
+                .header(header::ACCEPT_ENCODING, accept_encoding)
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_large_json_is_brotli_compressed() {
+        let response = fetch(app(CompressionConfig::new()), "/large", "gzip, br").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+
+        let compressed = response.into_body().collect().await.unwrap().to_bytes();
+        assert!(compressed.len() < large_body().to_string().len());
+    }
+
+    #[tokio::test]
+    async fn test_encoding_follows_accept_encoding() {
+        let config = CompressionConfig::new().with_level(CompressionLevel::Fastest);
+
+        let response = fetch(app(config), "/large", "gzip").await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "gzip");
+
+        let response = fetch(app(config), "/large", "identity").await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_responses_under_threshold_are_not_compressed() {
+        let response = fetch(app(CompressionConfig::new()), "/small", "br").await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+
+        let response = fetch(app(CompressionConfig::new().with_min_size(0)), "/small", "br").await;
+        assert_eq!(response.headers()[header::CONTENT_ENCODING], "br");
+
+        let response = fetch(app(CompressionConfig::new().with_min_size(u16::MAX)), "/large", "br").await;
+        assert!(response.headers().get(header::CONTENT_ENCODING).is_none());
+    }
+}
@@ -0,0 +1,100 @@
+//! Drain middleware for graceful shutdown
+//!
+//! Once draining starts, new requests are rejected with `503 Service
+//! Unavailable` while requests already in flight run to completion. The
+//! server's owner waits for [`DrainState::wait_idle`] before stopping it.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use std::sync::Arc;
+
+use crate::error::ApiError;
+
+pub use llm_optimizer_config::{DrainState, InFlight};
+
+/// Paths that keep working while draining, so probes can see the drain
+const EXEMPT_PREFIXES: &[&str] = &["/health", "/api/v1/health"];
+
+/// Reject new requests with 503 while draining and count the rest as in
+/// flight until their response is produced
+pub async fn drain_middleware(
+    State(drain): State<Arc<DrainState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if EXEMPT_PREFIXES.iter().any(|p| path.starts_with(p)) {
+        return next.run(request).await;
+    }
+
+    let Some(_in_flight) = drain.track() else {
+        return ApiError::ServiceUnavailable("Server is shutting down".to_string())
+            .into_response();
+    };
+
+    next.run(request).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::StatusCode, middleware, routing::get, Router};
+    use std::time::Duration;
+    use tokio::sync::Notify;
+    use tower::ServiceExt;
+
+    fn app(drain: Arc<DrainState>, release: Arc<Notify>) -> Router {
+        Router::new()
+            .route(
+                "/api/v1/optimize",
+                get(move || {
+                    let release = release.clone();
+                    async move {
+                        release.notified().await;
+                        "ok"
+                    }
+                }),
+            )
+            .route("/health/ready", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(drain, drain_middleware))
+    }
+
+    fn get_request(uri: &str) -> Request {
+        Request::get(uri).body(Body::empty()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_request_completes_while_new_ones_are_rejected() {
+        let drain = Arc::new(DrainState::new());
+        let release = Arc::new(Notify::new());
+        let app = app(drain.clone(), release.clone());
+
+        let in_flight = tokio::spawn(app.clone().oneshot(get_request("/api/v1/optimize")));
+        while drain.in_flight() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        drain.start();
+        let rejected = app.clone().oneshot(get_request("/api/v1/optimize")).await.unwrap();
+        assert_eq!(rejected.status(), StatusCode::SERVICE_UNAVAILABLE);
+        let probe = app.oneshot(get_request("/health/ready")).await.unwrap();
+        assert_eq!(probe.status(), StatusCode::OK);
+
+        let idle = tokio::spawn({
+            let drain = drain.clone();
+            async move { drain.wait_idle().await }
+        });
+        release.notify_one();
+
+        let completed = in_flight.await.unwrap().unwrap();
+        assert_eq!(completed.status(), StatusCode::OK);
+        tokio::time::timeout(Duration::from_secs(1), idle)
+            .await
+            .expect("drain did not become idle")
+            .unwrap();
+        assert_eq!(drain.in_flight(), 0);
+    }
+}
@@ -0,0 +1,173 @@
+//! Error response format selection
+//!
+//! Handlers and middleware fail with [`ApiError`](crate::error::ApiError),
+//! which always renders an [`ErrorResponse`]. When the server is configured
+//! for [`ErrorFormat::ProblemJson`], this middleware re-serializes those
+//! errors as RFC 7807 problem details, with the request ID as `instance`.
+
+use axum::{
+    extract::{Request, State},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::error::{ErrorFormat, ErrorResponse, ProblemJson};
+use crate::middleware::logging::RequestId;
+use crate::middleware::negotiation::is_json;
+
+/// Serialize API errors in the configured format
+///
+/// Must run inside `request_id_middleware` for `instance` to be set. Only
+/// responses produced from an `ApiError` are rewritten, and only while still
+/// JSON, so errors already re-encoded by content negotiation are left alone.
+pub async fn error_format_middleware(
+    State(format): State<ErrorFormat>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let request_id = request.extensions().get::<RequestId>().map(|id| id.0.clone());
+    let response = next.run(request).await;
+
+    if format != ErrorFormat::ProblemJson {
+        return response;
+    }
+    let Some(error) = response.extensions().get::<ErrorResponse>() else {
+        return response;
+    };
+    if !is_json(response.headers()) {
+        return response;
+    }
+
+    let mut error = error.clone();
+    error.request_id = error.request_id.or(request_id);
+    let (mut parts, _) = response.into_parts();
+    let problem = ProblemJson::from_error(parts.status, &error).into_response();
+
+    // Keep headers such as `Retry-After`, but take the new body's framing
+    let (problem_parts, body) = problem.into_parts();
+    parts.headers.remove(axum::http::header::CONTENT_LENGTH);
+    parts.headers.extend(problem_parts.headers);
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{ApiError, PROBLEM_JSON_CONTENT_TYPE};
+    use crate::middleware::logging::{request_id_middleware, REQUEST_ID_HEADER};
+    use axum::{
+        body::Body,
+        http::{header, StatusCode},
+        middleware,
+        routing::get,
+        Router,
+    };
+    use tower::ServiceExt;
+
+    fn app(format: ErrorFormat) -> Router {
+        Router::new()
+            .route(
+                "/optimize/:id",
+                get(|| async { ApiError::NotFound("Optimization not found".into()) }),
+            )
+            .route(
+                "/config/:key",
+                get(|| async { ApiError::Validation("value must be positive".into()) }),
+            )
+            .route("/ok", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(format, error_format_middleware))
+            .layer(middleware::from_fn(request_id_middleware))
+    }
+
+    async fn get_problem(app: Router, uri: &str) -> (StatusCode, String, serde_json::Value) {
+        let response = app
+            .oneshot(
+                Request::get(uri)
+                    .header(REQUEST_ID_HEADER, "req-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_not_found_as_problem_json() {
+        let (status, content_type, body) =
+            get_problem(app(ErrorFormat::ProblemJson), "/optimize/opt-1").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["type"], "https://llmdevops.dev/problems/not_found");
+        assert_eq!(body["title"], "Not Found");
+        assert_eq!(body["status"], 404);
+        assert_eq!(body["detail"], "Not found: Optimization not found");
+        assert_eq!(body["instance"], "req-42");
+    }
+
+    #[tokio::test]
+    async fn test_validation_error_as_problem_json() {
+        let (status, content_type, body) =
+            get_problem(app(ErrorFormat::ProblemJson), "/config/max_tokens").await;
+
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body["type"], "https://llmdevops.dev/problems/validation_error");
+        assert_eq!(body["title"], "Bad Request");
+        assert_eq!(body["status"], 400);
+        assert_eq!(body["detail"], "Validation failed: value must be positive");
+        assert_eq!(body["instance"], "req-42");
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_errors_are_left_alone() {
+        use crate::middleware::negotiation::{content_negotiation_middleware, MSGPACK_CONTENT_TYPE};
+
+        let app = Router::new()
+            .route(
+                "/optimize/:id",
+                get(|| async { ApiError::NotFound("Optimization not found".into()) }),
+            )
+            .layer(middleware::from_fn(content_negotiation_middleware))
+            .layer(middleware::from_fn_with_state(
+                ErrorFormat::ProblemJson,
+                error_format_middleware,
+            ));
+        let response = app
+            .oneshot(
+                Request::get("/optimize/opt-1")
+                    .header(header::ACCEPT, MSGPACK_CONTENT_TYPE)
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], MSGPACK_CONTENT_TYPE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = rmp_serde::from_slice(&body).unwrap();
+        assert_eq!(body["error"], "not_found");
+    }
+
+    #[tokio::test]
+    async fn test_standard_format_is_unchanged() {
+        let (status, content_type, body) =
+            get_problem(app(ErrorFormat::Standard), "/optimize/opt-1").await;
+
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert_eq!(content_type, "application/json");
+        assert_eq!(body["error"], "not_found");
+        assert!(body.get("type").is_none());
+
+        let response = app(ErrorFormat::ProblemJson)
+            .oneshot(Request::get("/ok").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
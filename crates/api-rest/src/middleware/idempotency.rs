@@ -0,0 +1,373 @@
+//! `Idempotency-Key` support for non-idempotent endpoints
+//!
+//! A client that retries a request after a timeout sends the same
+//! `Idempotency-Key` header. The first request with a key runs the handler
+//! and its response is stored; later requests with that key get the stored
+//! response back instead of running the handler again. Reusing a key with a
+//! different body is a client bug and is rejected with `422`.
+//!
+//! Keys are scoped to the authenticated caller, so one client cannot replay
+//! another's responses.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use dashmap::{mapref::entry::Entry, DashMap};
+use http_body_util::LengthLimitError;
+use sha2::{Digest, Sha256};
+
+use crate::error::ApiError;
+use crate::middleware::auth::AuthMethod;
+
+/// Request header carrying the client's idempotency key
+pub const IDEMPOTENCY_KEY_HEADER: &str = "idempotency-key";
+
+/// Response header set on replayed responses
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Default time a stored response is replayed for (24 hours)
+pub const DEFAULT_IDEMPOTENCY_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Longest accepted idempotency key
+const MAX_KEY_LEN: usize = 255;
+
+/// Response stored for replay
+#[derive(Debug, Clone)]
+pub struct StoredResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Bytes,
+}
+
+/// Outcome of reserving an idempotency key
+#[derive(Debug, Clone)]
+pub enum Reservation {
+    /// The key is new; the caller runs the request and then completes or
+    /// releases the key
+    Acquired,
+    /// A request with this key is still running
+    InProgress,
+    /// A request with this key finished; replay its response
+    Completed(StoredResponse),
+    /// The key was used with a different request
+    Mismatch,
+}
+
+/// Stores idempotency keys and the responses they produced
+pub trait IdempotencyStore: Send + Sync {
+    /// Reserve `key` for a request with `fingerprint`, or report how an
+    /// earlier request with the key went
+    fn reserve(&self, key: &str, fingerprint: &str) -> Reservation;
+
+    /// Store the response for a reserved key
+    fn complete(&self, key: &str, response: StoredResponse);
+
+    /// Drop a reservation so the request can be retried with the same key
+    fn release(&self, key: &str);
+}
+
+#[derive(Debug)]
+struct IdempotencyRecord {
+    fingerprint: String,
+    created_at: Instant,
+    response: Option<StoredResponse>,
+}
+
+/// In-memory [`IdempotencyStore`] that forgets keys after a TTL
+#[derive(Debug)]
+pub struct InMemoryIdempotencyStore {
+    records: DashMap<String, IdempotencyRecord>,
+    ttl: Duration,
+}
+
+impl InMemoryIdempotencyStore {
+    /// Create an empty store that keeps keys for `ttl`
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            records: DashMap::new(),
+            ttl,
+        }
+    }
+
+    /// Number of keys held, including expired ones not yet pruned
+    pub fn len(&self) -> usize {
+        self.records.len()
+    }
+
+    /// Whether the store holds no keys
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+}
+
+impl Default for InMemoryIdempotencyStore {
+    fn default() -> Self {
+        Self::new(DEFAULT_IDEMPOTENCY_TTL)
+    }
+}
+
+impl IdempotencyStore for InMemoryIdempotencyStore {
+    fn reserve(&self, key: &str, fingerprint: &str) -> Reservation {
+        let ttl = self.ttl;
+        self.records.retain(|_, record| record.created_at.elapsed() < ttl);
+
+        match self.records.entry(key.to_string()) {
+            Entry::Vacant(entry) => {
+                entry.insert(IdempotencyRecord {
+                    fingerprint: fingerprint.to_string(),
+                    created_at: Instant::now(),
+                    response: None,
+                });
+                Reservation::Acquired
+            }
+            Entry::Occupied(entry) => {
+                let record = entry.get();
+                if record.fingerprint != fingerprint {
+                    Reservation::Mismatch
+                } else if let Some(response) = &record.response {
+                    Reservation::Completed(response.clone())
+                } else {
+                    Reservation::InProgress
+                }
+            }
+        }
+    }
+
+    fn complete(&self, key: &str, response: StoredResponse) {
+        if let Some(mut record) = self.records.get_mut(key) {
+            record.response = Some(response);
+        }
+    }
+
+    fn release(&self, key: &str) {
+        self.records.remove(key);
+    }
+}
+
+/// Replay stored responses for requests carrying an `Idempotency-Key`
+///
+/// Requests without the header pass straight through. Responses with a 5xx
+/// status are not stored, so a retry after a server error runs again.
+pub async fn idempotency_middleware(
+    State(store): State<Arc<dyn IdempotencyStore>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let Some(key) = request.headers().get(IDEMPOTENCY_KEY_HEADER) else {
+        return Ok(next.run(request).await);
+    };
+    let key = key
+        .to_str()
+        .ok()
+        .filter(|key| !key.is_empty() && key.len() <= MAX_KEY_LEN)
+        .ok_or_else(|| {
+            ApiError::BadRequest(format!(
+                "Idempotency-Key must be 1 to {} visible ASCII characters",
+                MAX_KEY_LEN
+            ))
+        })?;
+    let key = match request.extensions().get::<AuthMethod>() {
+        Some(auth) => format!("{}:{}", auth.user_id(), key),
+        None => key.to_string(),
+    };
+
+    let (parts, body) = request.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX).await.map_err(|e| {
+        if is_length_limit(&e) {
+            ApiError::PayloadTooLarge("Request body is too large".into())
+        } else {
+            ApiError::BadRequest(format!("Failed to read request body: {}", e))
+        }
+    })?;
+    let fingerprint = fingerprint(&parts.method, parts.uri.path(), &body);
+
+    match store.reserve(&key, &fingerprint) {
+        Reservation::Acquired => {}
+        Reservation::Completed(stored) => return Ok(replay(stored)),
+        Reservation::InProgress => {
+            return Err(ApiError::Conflict(
+                "A request with this Idempotency-Key is still in progress".into(),
+            ))
+        }
+        Reservation::Mismatch => {
+            return Err(ApiError::UnprocessableEntity(
+                "Idempotency-Key was already used with a different request".into(),
+            ))
+        }
+    }
+
+    // Released if the request fails or is cancelled, e.g. by the timeout
+    // layer, so a retry isn't stuck behind a key that never completes
+    let reservation = ReservationGuard {
+        store: store.as_ref(),
+        key: &key,
+        completed: false,
+    };
+
+    let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+    if response.status().is_server_error() {
+        return Ok(response);
+    }
+
+    let (parts, body) = response.into_parts();
+    let body = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| ApiError::Internal(format!("Failed to read response body: {}", e)))?;
+    reservation.complete(StoredResponse {
+        status: parts.status,
+        headers: parts.headers.clone(),
+        body: body.clone(),
+    });
+
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+struct ReservationGuard<'a> {
+    store: &'a dyn IdempotencyStore,
+    key: &'a str,
+    completed: bool,
+}
+
+impl ReservationGuard<'_> {
+    fn complete(mut self, response: StoredResponse) {
+        self.store.complete(self.key, response);
+        self.completed = true;
+    }
+}
+
+impl Drop for ReservationGuard<'_> {
+    fn drop(&mut self) {
+        if !self.completed {
+            self.store.release(self.key);
+        }
+    }
+}
+
+fn replay(stored: StoredResponse) -> Response {
+    let mut response = (stored.status, stored.body).into_response();
+    *response.headers_mut() = stored.headers;
+    response.headers_mut().insert(
+        HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+        HeaderValue::from_static("true"),
+    );
+    response
+}
+
+fn fingerprint(method: &axum::http::Method, path: &str, body: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(method.as_str());
+    hasher.update(b" ");
+    hasher.update(path);
+    hasher.update(b"\n");
+    hasher.update(body);
+    format!("{:x}", hasher.finalize())
+}
+
+fn is_length_limit(error: &axum::Error) -> bool {
+    let mut source: Option<&(dyn std::error::Error + 'static)> = Some(error);
+    while let Some(e) = source {
+        if e.is::<LengthLimitError>() {
+            return true;
+        }
+        source = e.source();
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::post, Router};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use tower::ServiceExt;
+
+    fn app(store: Arc<dyn IdempotencyStore>, created: Arc<AtomicUsize>) -> Router {
+        Router::new()
+            .route(
+                "/items",
+                post(move |body: Bytes| async move {
+                    let id = created.fetch_add(1, Ordering::SeqCst);
+                    (StatusCode::CREATED, format!("item-{} {}", id, body.len()))
+                }),
+            )
+            .layer(middleware::from_fn_with_state(store, idempotency_middleware))
+    }
+
+    async fn send(app: &Router, key: Option<&str>, body: &str) -> Response {
+        let mut request = Request::post("/items");
+        if let Some(key) = key {
+            request = request.header(IDEMPOTENCY_KEY_HEADER, key);
+        }
+        app.clone()
+            .oneshot(request.body(Body::from(body.to_string())).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn text(response: Response) -> String {
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_repeated_key_replays_response() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::new(InMemoryIdempotencyStore::default()), created.clone());
+
+        let first = send(&app, Some("retry-1"), "{}").await;
+        assert_eq!(first.status(), StatusCode::CREATED);
+        assert!(first.headers().get(IDEMPOTENT_REPLAYED_HEADER).is_none());
+        let first = text(first).await;
+
+        let replayed = send(&app, Some("retry-1"), "{}").await;
+        assert_eq!(replayed.status(), StatusCode::CREATED);
+        assert_eq!(replayed.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+        assert_eq!(text(replayed).await, first);
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+
+        send(&app, None, "{}").await;
+        send(&app, Some("retry-2"), "{}").await;
+        assert_eq!(created.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_repeated_key_with_different_body_is_rejected() {
+        let created = Arc::new(AtomicUsize::new(0));
+        let app = app(Arc::new(InMemoryIdempotencyStore::default()), created.clone());
+
+        assert_eq!(send(&app, Some("retry-1"), "{\"a\":1}").await.status(), StatusCode::CREATED);
+
+        let response = send(&app, Some("retry-1"), "{\"a\":2}").await;
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        let body: serde_json::Value = serde_json::from_str(&text(response).await).unwrap();
+        assert_eq!(body["error"], "unprocessable_entity");
+        assert_eq!(created.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_keys_expire_after_ttl() {
+        let store = InMemoryIdempotencyStore::new(Duration::ZERO);
+        let stored = StoredResponse {
+            status: StatusCode::CREATED,
+            headers: HeaderMap::new(),
+            body: Bytes::from_static(b"item-0"),
+        };
+
+        assert!(matches!(store.reserve("key", "a"), Reservation::Acquired));
+        store.complete("key", stored);
+        assert!(matches!(store.reserve("key", "b"), Reservation::Acquired));
+
+        let store = InMemoryIdempotencyStore::default();
+        assert!(matches!(store.reserve("key", "a"), Reservation::Acquired));
+        assert!(matches!(store.reserve("key", "a"), Reservation::InProgress));
+        store.release("key");
+        assert!(matches!(store.reserve("key", "a"), Reservation::Acquired));
+    }
+}
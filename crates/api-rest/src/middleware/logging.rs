@@ -2,12 +2,15 @@
 
 use axum::{
     body::Body,
-    extract::Request,
+    extract::{Request, State},
     http::StatusCode,
     middleware::Next,
     response::{IntoResponse, Response},
 };
-use std::time::Instant;
+use std::sync::Arc;
+pub use llm_optimizer_config::LoggingConfig;
+use llm_optimizer_integrations::request_context::with_request_id;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 use crate::middleware::auth::AuthMethod;
@@ -16,6 +19,9 @@ use crate::middleware::auth::AuthMethod;
 pub const REQUEST_ID_HEADER: &str = "x-request-id";
 
 /// Add request ID to requests
+///
+/// The ID is also made current for integration clients called while handling
+/// the request, which forward it in their own `x-request-id` header.
 pub async fn request_id_middleware(mut request: Request, next: Next) -> Response {
     // Get or generate request ID
     let request_id = request
@@ -29,7 +35,7 @@ pub async fn request_id_middleware(mut request: Request, next: Next) -> Response
     request.extensions_mut().insert(RequestId(request_id.clone()));
 
     // Run the request
-    let mut response = next.run(request).await;
+    let mut response = with_request_id(request_id.clone(), next.run(request)).await;
 
     // Add request ID to response headers
     response.headers_mut().insert(
@@ -50,8 +56,17 @@ impl std::fmt::Display for RequestId {
     }
 }
 
+/// Whether a request that completed with `status` should be logged
+fn should_log(config: &LoggingConfig, status: StatusCode, duration: Duration) -> bool {
+    config.should_log(status.is_client_error() || status.is_server_error(), duration)
+}
+
 /// Logging middleware
-pub async fn logging_middleware(request: Request, next: Next) -> Response {
+pub async fn logging_middleware(
+    State(config): State<Arc<LoggingConfig>>,
+    request: Request,
+    next: Next,
+) -> Response {
     let start = Instant::now();
     let method = request.method().clone();
     let uri = request.uri().clone();
@@ -71,7 +86,8 @@ pub async fn logging_middleware(request: Request, next: Next) -> Response {
         .map(|auth| auth.user_id())
         .unwrap_or_else(|| "anonymous".to_string());
 
-    tracing::info!(
+    // Sampling is decided on completion, so only trace the start
+    tracing::debug!(
         request_id = %request_id,
         method = %method,
         uri = %uri,
@@ -85,41 +101,40 @@ pub async fn logging_middleware(request: Request, next: Next) -> Response {
     let duration = start.elapsed();
     let status = response.status();
 
+    if !should_log(&config, status, duration) {
+        return response;
+    }
+
+    let slow = duration >= config.slow_threshold;
     match status.as_u16() {
-        200..=299 => tracing::info!(
-            request_id = %request_id,
-            method = %method,
-            uri = %uri,
-            status = %status.as_u16(),
-            duration_ms = %duration.as_millis(),
-            user_id = %user_id,
-            "Request completed"
-        ),
-        300..=399 => tracing::info!(
+        400..=499 => tracing::warn!(
             request_id = %request_id,
             method = %method,
             uri = %uri,
             status = %status.as_u16(),
             duration_ms = %duration.as_millis(),
             user_id = %user_id,
+            slow,
             "Request completed"
         ),
-        400..=499 => tracing::warn!(
+        500..=599 => tracing::error!(
             request_id = %request_id,
             method = %method,
             uri = %uri,
             status = %status.as_u16(),
             duration_ms = %duration.as_millis(),
             user_id = %user_id,
+            slow,
             "Request completed"
         ),
-        500..=599 => tracing::error!(
+        _ if slow => tracing::warn!(
             request_id = %request_id,
             method = %method,
             uri = %uri,
             status = %status.as_u16(),
             duration_ms = %duration.as_millis(),
             user_id = %user_id,
+            slow,
             "Request completed"
         ),
         _ => tracing::info!(
@@ -174,4 +189,58 @@ mod tests {
         let id = RequestId("test-id-123".to_string());
         assert_eq!(format!("{}", id), "test-id-123");
     }
+
+    #[tokio::test]
+    async fn test_request_id_is_current_for_integration_calls() {
+        use axum::{middleware, routing::get, Router};
+        use llm_optimizer_integrations::request_context::current_request_id;
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/", get(|| async { current_request_id().unwrap_or_default() }))
+            .layer(middleware::from_fn(request_id_middleware));
+
+        let response = app
+            .oneshot(
+                Request::get("/")
+                    .header(REQUEST_ID_HEADER, "req-42")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(&body[..], b"req-42");
+    }
+
+    #[test]
+    fn test_errors_and_slow_requests_always_logged_successes_sampled() {
+        let config = LoggingConfig::new()
+            .with_sample_rate(0.25)
+            .with_slow_threshold(Duration::from_millis(500));
+        let fast = Duration::from_millis(5);
+
+        for _ in 0..1000 {
+            assert!(should_log(&config, StatusCode::INTERNAL_SERVER_ERROR, fast));
+            assert!(should_log(&config, StatusCode::NOT_FOUND, fast));
+            assert!(should_log(&config, StatusCode::OK, Duration::from_millis(750)));
+        }
+
+        let logged = (0..10_000)
+            .filter(|_| should_log(&config, StatusCode::OK, fast))
+            .count();
+        assert!((2000..=3000).contains(&logged), "logged {} of 10000", logged);
+    }
+
+    #[test]
+    fn test_sample_rate_bounds() {
+        let fast = Duration::from_millis(5);
+        let all = LoggingConfig::new();
+        assert!((0..100).all(|_| should_log(&all, StatusCode::OK, fast)));
+
+        let none = LoggingConfig::new().with_sample_rate(-1.0);
+        assert_eq!(none.sample_rate, 0.0);
+        assert!((0..100).all(|_| !should_log(&none, StatusCode::CREATED, fast)));
+        assert!(should_log(&none, StatusCode::BAD_GATEWAY, fast));
+    }
 }
@@ -0,0 +1,141 @@
+//! Maintenance mode middleware
+
+use axum::{
+    extract::{Request, State},
+    http::StatusCode,
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::{DateTime, Utc};
+use std::sync::{Arc, RwLock};
+
+use crate::error::ErrorResponse;
+use crate::models::admin::MaintenanceStatus;
+
+/// Paths that keep working while the service is in maintenance
+const EXEMPT_PREFIXES: &[&str] = &["/health", "/api/v1/health", "/api/v1/admin"];
+
+/// Shared maintenance-mode flag
+#[derive(Debug, Default)]
+pub struct MaintenanceMode {
+    state: RwLock<Option<MaintenanceWindow>>,
+}
+
+#[derive(Debug, Clone)]
+struct MaintenanceWindow {
+    message: Option<String>,
+    since: DateTime<Utc>,
+}
+
+impl MaintenanceMode {
+    /// Create a new flag, initially disabled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether maintenance mode is on
+    pub fn is_enabled(&self) -> bool {
+        self.state.read().unwrap().is_some()
+    }
+
+    /// Turn maintenance mode on or off
+    ///
+    /// Enabling while already enabled only updates the message.
+    pub fn set(&self, enabled: bool, message: Option<String>) -> MaintenanceStatus {
+        let mut state = self.state.write().unwrap();
+        *state = if enabled {
+            let since = state.as_ref().map(|w| w.since).unwrap_or_else(Utc::now);
+            Some(MaintenanceWindow { message, since })
+        } else {
+            None
+        };
+        Self::to_status(&state)
+    }
+
+    /// Current maintenance status
+    pub fn status(&self) -> MaintenanceStatus {
+        Self::to_status(&self.state.read().unwrap())
+    }
+
+    fn to_status(state: &Option<MaintenanceWindow>) -> MaintenanceStatus {
+        MaintenanceStatus {
+            enabled: state.is_some(),
+            message: state.as_ref().and_then(|w| w.message.clone()),
+            since: state.as_ref().map(|w| w.since),
+        }
+    }
+}
+
+/// Reject non-admin requests with 503 while maintenance mode is on
+pub async fn maintenance_middleware(
+    State(mode): State<Arc<MaintenanceMode>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path();
+    if !mode.is_enabled() || EXEMPT_PREFIXES.iter().any(|p| path.starts_with(p)) {
+        return next.run(request).await;
+    }
+
+    let status = mode.status();
+    let message = status
+        .message
+        .clone()
+        .unwrap_or_else(|| "Service is under maintenance".to_string());
+
+    let mut body = ErrorResponse::new("maintenance", message);
+    body.details = serde_json::to_value(&status).ok();
+
+    (StatusCode::SERVICE_UNAVAILABLE, Json(body)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, middleware, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(mode: Arc<MaintenanceMode>) -> Router {
+        Router::new()
+            .route("/api/v1/optimize", get(|| async { "ok" }))
+            .route("/api/v1/admin/stats", get(|| async { "ok" }))
+            .route("/health/ready", get(|| async { "ok" }))
+            .layer(middleware::from_fn_with_state(mode, maintenance_middleware))
+    }
+
+    async fn status_of(app: &Router, uri: &str) -> StatusCode {
+        app.clone()
+            .oneshot(Request::get(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+            .status()
+    }
+
+    #[tokio::test]
+    async fn test_maintenance_blocks_non_admin_requests() {
+        let mode = Arc::new(MaintenanceMode::new());
+        let app = app(mode.clone());
+
+        assert_eq!(status_of(&app, "/api/v1/optimize").await, StatusCode::OK);
+
+        mode.set(true, Some("Deploying v2".to_string()));
+        assert_eq!(status_of(&app, "/api/v1/optimize").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status_of(&app, "/api/v1/admin/stats").await, StatusCode::OK);
+        assert_eq!(status_of(&app, "/health/ready").await, StatusCode::OK);
+
+        mode.set(false, None);
+        assert_eq!(status_of(&app, "/api/v1/optimize").await, StatusCode::OK);
+    }
+
+    #[test]
+    fn test_re_enabling_keeps_start_time() {
+        let mode = MaintenanceMode::new();
+        let first = mode.set(true, None);
+        let second = mode.set(true, Some("still deploying".to_string()));
+
+        assert_eq!(first.since, second.since);
+        assert_eq!(second.message.as_deref(), Some("still deploying"));
+        assert!(!mode.set(false, None).enabled);
+    }
+}
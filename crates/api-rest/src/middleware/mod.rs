@@ -1,17 +1,38 @@
 //! Middleware components
 
 pub mod auth;
+pub mod body_limit;
+pub mod drain;
+pub mod idempotency;
 pub mod rbac;
 pub mod ratelimit;
+pub mod compression;
+pub mod error_format;
 pub mod cors;
 pub mod logging;
 pub mod validation;
 pub mod timeout;
+pub mod maintenance;
+pub mod negotiation;
 
-pub use auth::{AuthConfig, AuthMethod, Claims};
-pub use rbac::{Permission, Role, has_permission, require_admin, require_any_role};
-pub use ratelimit::{RateLimitConfig, rate_limit_middleware};
+pub use auth::{
+    ApiKeyScope, ApiKeyStore, AuthConfig, AuthMethod, Claims, RefreshTokenStore, TokenPair,
+};
+pub use rbac::{
+    ensure_permission, has_permission, policy_middleware, require_admin, require_any_role,
+    Permission, PolicyRule, PolicyTable, Role,
+};
+pub use body_limit::{body_limit_middleware, DEFAULT_MAX_BODY_BYTES};
+pub use drain::{drain_middleware, DrainState, InFlight};
+pub use idempotency::{
+    idempotency_middleware, IdempotencyStore, InMemoryIdempotencyStore, DEFAULT_IDEMPOTENCY_TTL,
+};
+pub use ratelimit::{RateLimitAlgorithm, RateLimitConfig, RateLimiter, rate_limit_middleware};
+pub use compression::{CompressionConfig, CompressionLevel};
+pub use error_format::error_format_middleware;
 pub use cors::{CorsConfig, development_cors, production_cors};
-pub use logging::{RequestId, logging_middleware, metrics_middleware, request_id_middleware};
+pub use logging::{LoggingConfig, RequestId, logging_middleware, metrics_middleware, request_id_middleware};
 pub use validation::validate_request;
 pub use timeout::timeout_middleware;
+pub use maintenance::{MaintenanceMode, maintenance_middleware};
+pub use negotiation::{ResponseFormat, content_negotiation_middleware};
@@ -0,0 +1,261 @@
+//! Response content negotiation middleware
+//!
+//! Handlers always produce JSON; this middleware re-encodes JSON responses
+//! as MessagePack for clients that ask for it in `Accept`.
+
+use axum::{
+    body::{to_bytes, Body},
+    extract::Request,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+
+use crate::error::ErrorResponse;
+
+/// MessagePack media type
+pub const MSGPACK_CONTENT_TYPE: &str = "application/msgpack";
+
+/// Legacy MessagePack media type, accepted as an alias
+const MSGPACK_LEGACY_CONTENT_TYPE: &str = "application/x-msgpack";
+
+/// Response encoding chosen from the `Accept` header
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    /// `application/json`, the default
+    Json,
+    /// `application/msgpack`
+    MessagePack,
+}
+
+impl ResponseFormat {
+    /// Choose the response format for an `Accept` header value
+    ///
+    /// MessagePack is only chosen when requested explicitly and ranked at
+    /// least as high as JSON; ties go to whichever is listed first. A missing
+    /// header, `*/*` or any unparsable value selects JSON.
+    pub fn from_accept(accept: Option<&str>) -> Self {
+        let Some(accept) = accept else {
+            return Self::Json;
+        };
+
+        // (quality, position) of the best explicit entry for each format
+        let mut json: Option<(f32, usize)> = None;
+        let mut msgpack: Option<(f32, usize)> = None;
+
+        for (position, range) in accept.split(',').enumerate() {
+            let mut parts = range.split(';');
+            let media_type = parts.next().unwrap_or_default().trim().to_ascii_lowercase();
+            let quality = parts
+                .filter_map(|param| param.trim().strip_prefix("q="))
+                .find_map(|q| q.trim().parse::<f32>().ok())
+                .unwrap_or(1.0);
+
+            let slot = match media_type.as_str() {
+                "application/json" => &mut json,
+                MSGPACK_CONTENT_TYPE | MSGPACK_LEGACY_CONTENT_TYPE => &mut msgpack,
+                _ => continue,
+            };
+            if slot.map_or(true, |(best, _)| quality > best) {
+                *slot = Some((quality, position));
+            }
+        }
+
+        match (msgpack, json) {
+            (Some((q, _)), _) if q <= 0.0 => Self::Json,
+            (Some(_), None) => Self::MessagePack,
+            (Some((mq, mpos)), Some((jq, jpos))) if mq > jq || (mq == jq && mpos < jpos) => {
+                Self::MessagePack
+            }
+            _ => Self::Json,
+        }
+    }
+
+    /// Choose the response format for a request's headers
+    pub fn from_headers(headers: &HeaderMap) -> Self {
+        Self::from_accept(headers.get(header::ACCEPT).and_then(|v| v.to_str().ok()))
+    }
+}
+
+/// Re-encode JSON responses as MessagePack when the client prefers it
+///
+/// Responses that are not JSON (event streams, documentation pages) pass
+/// through unchanged. Every JSON response gets `Vary: Accept` so caches keep
+/// the two encodings apart.
+pub async fn content_negotiation_middleware(request: Request, next: Next) -> Response {
+    let format = ResponseFormat::from_headers(request.headers());
+    let mut response = next.run(request).await;
+
+    if !is_json(response.headers()) {
+        return response;
+    }
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("accept"));
+
+    if format == ResponseFormat::Json {
+        return response;
+    }
+
+    let (mut parts, body) = response.into_parts();
+    let encoded = to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| e.to_string())
+        .and_then(|bytes| json_to_msgpack(&bytes));
+
+    match encoded {
+        Ok(bytes) => {
+            parts.headers.insert(
+                header::CONTENT_TYPE,
+                HeaderValue::from_static(MSGPACK_CONTENT_TYPE),
+            );
+            parts.headers.remove(header::CONTENT_LENGTH);
+            Response::from_parts(parts, Body::from(bytes))
+        }
+        Err(e) => {
+            tracing::error!(error = %e, "Failed to encode response as MessagePack");
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ErrorResponse::new(
+                    "internal_error",
+                    "Failed to encode response as MessagePack",
+                )),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// Whether a response carries a JSON body
+pub(crate) fn is_json(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.parse::<mime::Mime>().ok())
+        .is_some_and(|mime| {
+            mime.type_() == mime::APPLICATION
+                && (mime.subtype() == mime::JSON || mime.suffix() == Some(mime::JSON))
+        })
+}
+
+/// Transcode a JSON document to MessagePack, keeping field names so structs
+/// round-trip
+fn json_to_msgpack(json: &[u8]) -> Result<Vec<u8>, String> {
+    let value: serde_json::Value = serde_json::from_slice(json).map_err(|e| e.to_string())?;
+    rmp_serde::to_vec_named(&value).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{middleware, routing::get, Router};
+    use http_body_util::BodyExt;
+    use serde::{Deserialize, Serialize};
+    use std::collections::HashMap;
+    use tower::ServiceExt;
+
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    struct Report {
+        id: String,
+        count: u64,
+        ratio: f64,
+        enabled: bool,
+        note: Option<String>,
+        tags: Vec<String>,
+        limits: HashMap<String, i64>,
+    }
+
+    fn report() -> Report {
+        Report {
+            id: "opt-42".to_string(),
+            count: 7,
+            ratio: 0.25,
+            enabled: true,
+            note: None,
+            tags: vec!["latency".to_string(), "cost".to_string()],
+            limits: HashMap::from([("max_tokens".to_string(), -1)]),
+        }
+    }
+
+    fn app() -> Router {
+        Router::new()
+            .route("/report", get(|| async { Json(report()) }))
+            .route("/text", get(|| async { "plain" }))
+            .layer(middleware::from_fn(content_negotiation_middleware))
+    }
+
+    async fn get_with_accept(accept: Option<&str>, uri: &str) -> Response {
+        let mut request = Request::get(uri);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        app()
+            .oneshot(request.body(Body::empty()).unwrap())
+            .await
+            .unwrap()
+    }
+
+    async fn body_bytes(response: Response) -> Vec<u8> {
+        response.into_body().collect().await.unwrap().to_bytes().to_vec()
+    }
+
+    #[test]
+    fn test_format_from_accept() {
+        use ResponseFormat::{Json as J, MessagePack as M};
+
+        assert_eq!(ResponseFormat::from_accept(None), J);
+        assert_eq!(ResponseFormat::from_accept(Some("*/*")), J);
+        assert_eq!(ResponseFormat::from_accept(Some("application/json")), J);
+        assert_eq!(ResponseFormat::from_accept(Some("application/msgpack")), M);
+        assert_eq!(ResponseFormat::from_accept(Some("application/x-msgpack")), M);
+        assert_eq!(ResponseFormat::from_accept(Some("application/msgpack, */*;q=0.8")), M);
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/json, application/msgpack")),
+            J
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/json;q=0.5, application/msgpack")),
+            M
+        );
+        assert_eq!(ResponseFormat::from_accept(Some("application/msgpack;q=0")), J);
+    }
+
+    #[tokio::test]
+    async fn test_msgpack_round_trips_typed_response() {
+        let response = get_with_accept(Some("application/msgpack"), "/report").await;
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            MSGPACK_CONTENT_TYPE
+        );
+        assert_eq!(response.headers().get(header::VARY).unwrap(), "accept");
+
+        let decoded: Report = rmp_serde::from_slice(&body_bytes(response).await).unwrap();
+        assert_eq!(decoded, report());
+    }
+
+    #[tokio::test]
+    async fn test_json_is_default() {
+        for accept in [None, Some("*/*"), Some("application/json")] {
+            let response = get_with_accept(accept, "/report").await;
+
+            assert_eq!(
+                response.headers().get(header::CONTENT_TYPE).unwrap(),
+                "application/json",
+                "{accept:?}"
+            );
+            let decoded: Report = serde_json::from_slice(&body_bytes(response).await).unwrap();
+            assert_eq!(decoded, report());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_non_json_responses_pass_through() {
+        let response = get_with_accept(Some("application/msgpack"), "/text").await;
+
+        assert!(response.headers().get(header::VARY).is_none());
+        assert_eq!(body_bytes(response).await, b"plain");
+    }
+}
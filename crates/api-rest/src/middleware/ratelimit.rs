@@ -5,55 +5,79 @@ use axum::{
     middleware::Next,
     response::Response,
 };
-use governor::{
-    clock::DefaultClock,
-    state::{InMemoryState, NotKeyed},
-    Quota, RateLimiter as GovernorRateLimiter,
-};
-use std::num::NonZeroU32;
+use dashmap::DashMap;
 use std::sync::Arc;
 use std::time::Duration;
 
 use crate::error::ApiError;
 use crate::middleware::auth::AuthMethod;
 
+pub use llm_optimizer_config::{RateLimitAlgorithm, RateLimited, RateLimiter};
+
+/// Window over which the per-minute limits are counted
+const WINDOW: Duration = Duration::from_secs(60);
+
 /// Rate limiter configuration
 #[derive(Clone)]
 pub struct RateLimitConfig {
     /// Global rate limiter (shared across all users)
-    pub global: Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>,
+    pub global: Arc<RateLimiter>,
     /// Per-user rate limiter
-    pub per_user: Arc<dashmap::DashMap<String, Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>,
+    pub per_user: Arc<DashMap<String, Arc<RateLimiter>>>,
+    /// Requests per minute across all users
+    pub global_rpm: u32,
     /// Requests per minute for authenticated users
     pub authenticated_rpm: u32,
     /// Requests per minute for anonymous users
     pub anonymous_rpm: u32,
     /// Requests per minute for API keys
     pub api_key_rpm: u32,
+    /// Algorithm used by the global and per-user limiters
+    pub algorithm: RateLimitAlgorithm,
+    /// Token bucket size for per-user limiters; a full minute's worth of
+    /// requests when unset
+    pub burst_size: Option<u32>,
 }
 
 impl RateLimitConfig {
     /// Create a new rate limit config
     pub fn new(global_rpm: u32, authenticated_rpm: u32, anonymous_rpm: u32, api_key_rpm: u32) -> Self {
-        let global_quota = Quota::per_minute(NonZeroU32::new(global_rpm).unwrap());
-        let global_limiter = Arc::new(GovernorRateLimiter::direct(global_quota));
+        let algorithm = RateLimitAlgorithm::default();
 
         Self {
-            global: global_limiter,
-            per_user: Arc::new(dashmap::DashMap::new()),
+            global: Arc::new(RateLimiter::new(algorithm, global_rpm, WINDOW, global_rpm)),
+            per_user: Arc::new(DashMap::new()),
+            global_rpm,
             authenticated_rpm,
             anonymous_rpm,
             api_key_rpm,
+            algorithm,
+            burst_size: None,
         }
     }
 
+    /// Set the rate limiting algorithm
+    pub fn with_algorithm(mut self, algorithm: RateLimitAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self.global = Arc::new(RateLimiter::new(algorithm, self.global_rpm, WINDOW, self.global_rpm));
+        self.per_user = Arc::new(DashMap::new());
+        self
+    }
+
+    /// Set the token bucket size for per-user limiters
+    pub fn with_burst_size(mut self, burst_size: u32) -> Self {
+        self.burst_size = Some(burst_size);
+        self.per_user = Arc::new(DashMap::new());
+        self
+    }
+
     /// Get or create rate limiter for a user
-    fn get_user_limiter(&self, user_id: &str, rpm: u32) -> Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>> {
+    fn get_user_limiter(&self, user_id: &str, rpm: u32) -> Arc<RateLimiter> {
         self.per_user
             .entry(user_id.to_string())
             .or_insert_with(|| {
-                let quota = Quota::per_minute(NonZeroU32::new(rpm).unwrap());
-                Arc::new(GovernorRateLimiter::direct(quota))
+                let burst = self.burst_size.unwrap_or(rpm);
+                Arc::new(RateLimiter::new(self.algorithm, rpm, WINDOW, burst))
             })
             .clone()
     }
@@ -96,7 +120,7 @@ pub async fn rate_limit_middleware(
     let (user_id, rpm) = if let Some(auth) = request.extensions().get::<AuthMethod>() {
         match auth {
             AuthMethod::Bearer(claims) => (claims.sub.clone(), config.authenticated_rpm),
-            AuthMethod::ApiKey(key) => (format!("api:{}", key), config.api_key_rpm),
+            AuthMethod::ApiKey { key, .. } => (format!("api:{}", key), config.api_key_rpm),
         }
     } else {
         // For anonymous users, use IP address or a generic identifier
@@ -115,40 +139,52 @@ pub async fn rate_limit_middleware(
     Ok(next.run(request).await)
 }
 
-/// Endpoint-specific rate limiter
+/// Endpoint-specific rate limiter, keyed per endpoint and user
 pub struct EndpointRateLimiter {
-    limiters: Arc<dashmap::DashMap<String, Arc<GovernorRateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>,
+    /// Requests per minute for each limited endpoint
+    limits: Arc<DashMap<String, u32>>,
+    /// Limiters by endpoint and user
+    limiters: Arc<DashMap<(String, String), Arc<RateLimiter>>>,
+    algorithm: RateLimitAlgorithm,
 }
 
 impl EndpointRateLimiter {
     /// Create a new endpoint rate limiter
     pub fn new() -> Self {
         Self {
-            limiters: Arc::new(dashmap::DashMap::new()),
+            limits: Arc::new(DashMap::new()),
+            limiters: Arc::new(DashMap::new()),
+            algorithm: RateLimitAlgorithm::default(),
         }
     }
 
+    /// Set the rate limiting algorithm
+    pub fn with_algorithm(mut self, algorithm: RateLimitAlgorithm) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
     /// Create a rate limiter for a specific endpoint
     pub fn create_limiter(&self, endpoint: &str, requests_per_minute: u32) {
-        let quota = Quota::per_minute(NonZeroU32::new(requests_per_minute).unwrap());
-        let limiter = Arc::new(GovernorRateLimiter::direct(quota));
-        self.limiters.insert(endpoint.to_string(), limiter);
+        self.limits.insert(endpoint.to_string(), requests_per_minute);
+        self.limiters.retain(|(limited, _), _| limited != endpoint);
     }
 
     /// Check rate limit for an endpoint
     pub fn check(&self, endpoint: &str, user_id: &str) -> Result<(), ApiError> {
-        let key = format!("{}:{}", endpoint, user_id);
-
-        if let Some(limiter) = self.limiters.get(endpoint) {
-            if limiter.check().is_err() {
-                return Err(ApiError::RateLimit(format!(
-                    "Rate limit exceeded for endpoint: {}",
-                    endpoint
-                )));
-            }
-        }
+        let Some(rpm) = self.limits.get(endpoint).map(|rpm| *rpm) else {
+            return Ok(());
+        };
+
+        let limiter = self
+            .limiters
+            .entry((endpoint.to_string(), user_id.to_string()))
+            .or_insert_with(|| Arc::new(RateLimiter::new(self.algorithm, rpm, WINDOW, rpm)))
+            .clone();
 
-        Ok(())
+        limiter.check().map_err(|_| {
+            ApiError::RateLimit(format!("Rate limit exceeded for endpoint: {}", endpoint))
+        })
     }
 }
 
@@ -179,6 +215,30 @@ mod tests {
         assert!(limiter.check("/api/v1/optimize", "user-123").is_ok());
     }
 
+    #[test]
+    fn test_endpoint_limits_are_per_user() {
+        let limiter = EndpointRateLimiter::new().with_algorithm(RateLimitAlgorithm::FixedWindow);
+        limiter.create_limiter("/api/v1/optimize", 2);
+
+        assert!(limiter.check("/api/v1/optimize", "alice").is_ok());
+        assert!(limiter.check("/api/v1/optimize", "alice").is_ok());
+        assert!(limiter.check("/api/v1/optimize", "alice").is_err());
+        assert!(limiter.check("/api/v1/optimize", "bob").is_ok());
+        assert!(limiter.check("/api/v1/health", "alice").is_ok());
+    }
+
+    #[test]
+    fn test_config_applies_algorithm_and_burst() {
+        let config = RateLimitConfig::new(100, 10, 10, 10)
+            .with_algorithm(RateLimitAlgorithm::TokenBucket)
+            .with_burst_size(3);
+
+        let limiter = config.get_user_limiter("alice", config.authenticated_rpm);
+        assert_eq!(limiter.algorithm(), RateLimitAlgorithm::TokenBucket);
+        assert_eq!((0..10).filter(|_| limiter.check().is_ok()).count(), 3);
+        assert!(config.get_user_limiter("bob", config.authenticated_rpm).check().is_ok());
+    }
+
     #[tokio::test]
     async fn test_rate_limit_enforcement() {
         let config = RateLimitConfig::new(10, 5, 2, 8);
@@ -1,13 +1,21 @@
 //! Role-Based Access Control (RBAC) middleware
+//!
+//! Every `/api/v1` route needs an entry in the [`PolicyTable`], which maps a
+//! method and path pattern to the permission it requires. The
+//! [`policy_middleware`] enforces the table for all routes at once; a route
+//! without an entry is denied, so new endpoints stay closed until their
+//! policy is written down.
+
+use std::sync::Arc;
 
 use axum::{
-    extract::Request,
-    http::StatusCode,
+    extract::{Request, State},
+    http::{Method, StatusCode},
     middleware::Next,
     response::Response,
 };
 
-use crate::error::ApiError;
+use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthMethod;
 
 /// Role definition
@@ -73,6 +81,49 @@ pub enum Permission {
 }
 
 impl Permission {
+    /// Every permission
+    pub const ALL: [Permission; 14] = [
+        Permission::OptimizeRead,
+        Permission::OptimizeWrite,
+        Permission::OptimizeExecute,
+        Permission::ConfigRead,
+        Permission::ConfigWrite,
+        Permission::MetricsRead,
+        Permission::MetricsWrite,
+        Permission::IntegrationRead,
+        Permission::IntegrationWrite,
+        Permission::IntegrationDelete,
+        Permission::AdminRead,
+        Permission::AdminWrite,
+        Permission::AdminExecute,
+        Permission::SystemHealth,
+    ];
+
+    /// Convert string to permission
+    pub fn parse(s: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|permission| permission.as_str() == s)
+    }
+
+    /// Convert permission to string, as carried in claims
+    pub fn as_str(&self) -> &str {
+        match self {
+            Permission::OptimizeRead => "optimize:read",
+            Permission::OptimizeWrite => "optimize:write",
+            Permission::OptimizeExecute => "optimize:execute",
+            Permission::ConfigRead => "config:read",
+            Permission::ConfigWrite => "config:write",
+            Permission::MetricsRead => "metrics:read",
+            Permission::MetricsWrite => "metrics:write",
+            Permission::IntegrationRead => "integration:read",
+            Permission::IntegrationWrite => "integration:write",
+            Permission::IntegrationDelete => "integration:delete",
+            Permission::AdminRead => "admin:read",
+            Permission::AdminWrite => "admin:write",
+            Permission::AdminExecute => "admin:execute",
+            Permission::SystemHealth => "system:health",
+        }
+    }
+
     /// Get permissions for a role
     pub fn for_role(role: &Role) -> Vec<Permission> {
         match role {
@@ -121,10 +172,21 @@ impl Permission {
 }
 
 /// Check if auth method has required permission
+///
+/// A permission is held when one of the roles grants it or it was granted
+/// directly, the same way for JWT and API key requests.
 pub fn has_permission(auth: &AuthMethod, permission: &Permission) -> bool {
-    let roles = auth.roles();
+    let claims = auth.claims();
 
-    for role_str in &roles {
+    let granted = claims
+        .permissions
+        .iter()
+        .any(|p| Permission::parse(p).as_ref() == Some(permission));
+    if granted {
+        return true;
+    }
+
+    for role_str in &claims.roles {
         if let Some(role) = Role::from_str(role_str) {
             let permissions = Permission::for_role(&role);
             if permissions.contains(permission) {
@@ -136,6 +198,178 @@ pub fn has_permission(auth: &AuthMethod, permission: &Permission) -> bool {
     false
 }
 
+/// Fail with a 403 unless the auth method has `permission`
+pub fn ensure_permission(auth: &AuthMethod, permission: Permission) -> ApiResult<()> {
+    if has_permission(auth, &permission) {
+        Ok(())
+    } else {
+        Err(ApiError::Authorization(format!(
+            "Missing required permission: {}",
+            permission.as_str()
+        )))
+    }
+}
+
+/// Permission required by one method and path pattern
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PolicyRule {
+    /// HTTP method; `GET` rules also cover `HEAD`
+    pub method: Method,
+    /// Path pattern relative to `/api/v1`; a `:name` segment matches any
+    /// single segment
+    pub path: String,
+    /// Permission a caller needs
+    pub permission: Permission,
+}
+
+impl PolicyRule {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        let method_matches =
+            self.method == method || (self.method == Method::GET && method == Method::HEAD);
+        if !method_matches {
+            return false;
+        }
+
+        let mut pattern = self.path.trim_matches('/').split('/');
+        let mut segments = path.trim_matches('/').split('/');
+        loop {
+            match (pattern.next(), segments.next()) {
+                (None, None) => return true,
+                (Some(p), Some(s)) if p.starts_with(':') && !s.is_empty() => {}
+                (Some(p), Some(s)) if p == s => {}
+                _ => return false,
+            }
+        }
+    }
+}
+
+/// Declarative method and path to permission table, denying by default
+#[derive(Debug, Clone, Default)]
+pub struct PolicyTable {
+    rules: Vec<PolicyRule>,
+}
+
+impl PolicyTable {
+    /// Create an empty table, which denies every request
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy for every route the server exposes under `/api/v1`
+    pub fn standard() -> Self {
+        use Permission::*;
+
+        Self::new()
+            // Optimizations
+            .with_rule(Method::GET, "/optimize", OptimizeRead)
+            .with_rule(Method::POST, "/optimize", OptimizeWrite)
+            .with_rule(Method::GET, "/optimize/leaderboard", OptimizeRead)
+            .with_rule(Method::POST, "/optimize/bulk/deploy", OptimizeExecute)
+            .with_rule(Method::POST, "/optimize/bulk/delete", OptimizeWrite)
+            .with_rule(Method::GET, "/optimize/:id", OptimizeRead)
+            .with_rule(Method::POST, "/optimize/:id/deploy", OptimizeExecute)
+            .with_rule(Method::POST, "/optimize/:id/rollback", OptimizeExecute)
+            .with_rule(Method::POST, "/optimize/:id/approve", AdminWrite)
+            .with_rule(Method::POST, "/optimize/:id/reject", AdminWrite)
+            .with_rule(Method::POST, "/optimize/:id/tags", OptimizeWrite)
+            .with_rule(Method::DELETE, "/optimize/:id/tags/:key", OptimizeWrite)
+            // Configuration
+            .with_rule(Method::GET, "/config/watch", AdminRead)
+            .with_rule(Method::GET, "/config/export", ConfigRead)
+            .with_rule(Method::POST, "/config/import", ConfigWrite)
+            .with_rule(Method::POST, "/config/batch", ConfigWrite)
+            .with_rule(Method::GET, "/config/:key", ConfigRead)
+            .with_rule(Method::PUT, "/config/:key", ConfigWrite)
+            // Metrics
+            .with_rule(Method::POST, "/metrics/query", MetricsRead)
+            .with_rule(Method::GET, "/metrics/performance", MetricsRead)
+            .with_rule(Method::GET, "/metrics/cost", MetricsRead)
+            .with_rule(Method::GET, "/metrics/quality", MetricsRead)
+            // Integrations
+            .with_rule(Method::GET, "/integrations", IntegrationRead)
+            .with_rule(Method::POST, "/integrations", IntegrationWrite)
+            .with_rule(Method::GET, "/integrations/:id", IntegrationRead)
+            .with_rule(Method::PUT, "/integrations/:id", IntegrationWrite)
+            .with_rule(Method::DELETE, "/integrations/:id", IntegrationDelete)
+            .with_rule(Method::POST, "/integrations/:id/test", IntegrationWrite)
+            // Administration
+            .with_rule(Method::GET, "/admin/stats", AdminRead)
+            .with_rule(Method::POST, "/admin/cache/flush", AdminExecute)
+            .with_rule(Method::GET, "/admin/api-keys", AdminRead)
+            .with_rule(Method::POST, "/admin/api-keys", AdminWrite)
+            .with_rule(Method::DELETE, "/admin/api-keys/:id", AdminWrite)
+            .with_rule(Method::GET, "/admin/audit-logs", AdminRead)
+            .with_rule(Method::POST, "/admin/service/start", AdminExecute)
+            .with_rule(Method::POST, "/admin/service/stop", AdminExecute)
+            .with_rule(Method::GET, "/admin/services", AdminRead)
+            .with_rule(Method::GET, "/admin/maintenance", AdminRead)
+            .with_rule(Method::PUT, "/admin/maintenance", AdminWrite)
+            .with_rule(Method::POST, "/admin/decisions/archive", AdminExecute)
+            // Event stream
+            .with_rule(Method::GET, "/events", OptimizeRead)
+    }
+
+    /// Require `permission` for `method` requests to paths matching `path`
+    ///
+    /// Rules are checked in the order they were added and the first match
+    /// wins, so literal paths should come before overlapping `:name`
+    /// patterns.
+    pub fn with_rule(
+        mut self,
+        method: Method,
+        path: impl Into<String>,
+        permission: Permission,
+    ) -> Self {
+        self.rules.push(PolicyRule {
+            method,
+            path: path.into(),
+            permission,
+        });
+        self
+    }
+
+    /// All rules, in match order
+    pub fn rules(&self) -> &[PolicyRule] {
+        &self.rules
+    }
+
+    /// Permission required for a request, or `None` if no rule covers it
+    pub fn required_permission(&self, method: &Method, path: &str) -> Option<&Permission> {
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(method, path))
+            .map(|rule| &rule.permission)
+    }
+}
+
+/// Enforce the policy table on authenticated requests
+///
+/// Must run after `auth_middleware`. Requests no rule covers are rejected
+/// with a 403, as are callers missing the rule's permission.
+pub async fn policy_middleware(
+    State(policy): State<Arc<PolicyTable>>,
+    request: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let auth = request
+        .extensions()
+        .get::<AuthMethod>()
+        .ok_or_else(|| ApiError::Authentication("Not authenticated".into()))?;
+
+    let method = request.method();
+    let path = request.uri().path();
+    let Some(permission) = policy.required_permission(method, path) else {
+        tracing::warn!(%method, path, "Denied request to route without access policy");
+        return Err(ApiError::Authorization(format!(
+            "No access policy for {} {}",
+            method, path
+        )));
+    };
+
+    ensure_permission(auth, permission.clone())?;
+    Ok(next.run(request).await)
+}
+
 /// Require specific permission
 pub async fn require_permission(
     permission: Permission,
@@ -230,6 +464,82 @@ mod tests {
         assert!(has_permission(&auth, &Permission::AdminExecute));
     }
 
+    #[test]
+    fn test_permission_strings_round_trip() {
+        for permission in Permission::ALL {
+            assert_eq!(Permission::parse(permission.as_str()), Some(permission));
+        }
+        assert_eq!(Permission::parse("optimize:delete"), None);
+    }
+
+    #[test]
+    fn test_granted_permissions_without_role() {
+        let mut claims = Claims::new("user-123".to_string(), vec![], 3600);
+        claims.permissions = vec!["metrics:read".to_string()];
+        let auth = AuthMethod::Bearer(claims);
+
+        assert!(has_permission(&auth, &Permission::MetricsRead));
+        assert!(!has_permission(&auth, &Permission::OptimizeRead));
+        assert!(ensure_permission(&auth, Permission::MetricsWrite).is_err());
+    }
+
+    #[test]
+    fn test_policy_path_matching() {
+        let policy = PolicyTable::standard();
+        let required =
+            |method: Method, path: &str| policy.required_permission(&method, path).cloned();
+
+        assert_eq!(required(Method::GET, "/optimize"), Some(Permission::OptimizeRead));
+        assert_eq!(required(Method::POST, "/optimize/"), Some(Permission::OptimizeWrite));
+        assert_eq!(required(Method::HEAD, "/optimize/opt-1"), Some(Permission::OptimizeRead));
+        assert_eq!(
+            required(Method::DELETE, "/optimize/opt-1/tags/team"),
+            Some(Permission::OptimizeWrite)
+        );
+        assert_eq!(required(Method::GET, "/config/watch"), Some(Permission::AdminRead));
+        assert_eq!(required(Method::GET, "/config/max_tokens"), Some(Permission::ConfigRead));
+        assert_eq!(required(Method::PUT, "/admin/maintenance"), Some(Permission::AdminWrite));
+
+        assert_eq!(required(Method::DELETE, "/optimize"), None);
+        assert_eq!(required(Method::GET, "/optimize/opt-1/unknown"), None);
+        assert_eq!(required(Method::GET, "/config"), None);
+        assert_eq!(required(Method::GET, "/nonexistent"), None);
+    }
+
+    #[tokio::test]
+    async fn test_policy_middleware_denies_unmapped_routes() {
+        use axum::{body::Body, middleware, routing::get, Router};
+        use tower::ServiceExt;
+
+        let policy =
+            PolicyTable::new().with_rule(Method::GET, "/reports/:id", Permission::MetricsRead);
+        let claims = |role: &str| Claims::new("user-123".to_string(), vec![role.to_string()], 3600);
+        let app = |claims: Claims| {
+            Router::new()
+                .route("/reports/:id", get(|| async { "report" }).delete(|| async { "deleted" }))
+                .layer(middleware::from_fn_with_state(Arc::new(policy.clone()), policy_middleware))
+                .layer(axum::Extension(AuthMethod::Bearer(claims)))
+        };
+        let send = |app: Router, method: Method| async move {
+            app.oneshot(
+                Request::builder()
+                    .method(method)
+                    .uri("/reports/r-1")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap()
+            .status()
+        };
+
+        assert_eq!(send(app(claims("readonly")), Method::GET).await, StatusCode::OK);
+        assert_eq!(send(app(claims("admin")), Method::DELETE).await, StatusCode::FORBIDDEN);
+
+        let no_metrics = Claims::new("user-123".to_string(), vec![], 3600);
+        assert_eq!(send(app(no_metrics), Method::GET).await, StatusCode::FORBIDDEN);
+    }
+
     #[test]
     fn test_user_without_permission() {
         let claims = Claims::new("user-123".to_string(), vec!["readonly".to_string()], 3600);
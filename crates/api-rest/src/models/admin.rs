@@ -48,6 +48,107 @@ pub struct FlushCacheResponse {
     pub message: String,
 }
 
+/// Lifecycle state of the optimizer service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ServiceState {
+    Running,
+    Stopped,
+}
+
+/// Service start/stop response
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ServiceControlResponse {
+    /// Outcome ("started", "stopped", or "no_op" if already in the target state)
+    pub status: String,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Service state after the request
+    pub state: ServiceState,
+}
+
+/// Status of one of the services managed by the optimizer process
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ManagedServiceStatus {
+    /// Service name
+    pub name: String,
+
+    /// Lifecycle state, e.g. "running" or "failed"
+    pub state: String,
+
+    /// Seconds since the service last started or was restarted; absent
+    /// unless it is running
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uptime_seconds: Option<u64>,
+
+    /// Restarts by auto-recovery or config reload since startup
+    pub restart_count: u32,
+
+    /// Message from the last health check
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_health_message: Option<String>,
+}
+
+/// Set maintenance mode request
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct SetMaintenanceRequest {
+    /// Whether maintenance mode should be on
+    pub enabled: bool,
+
+    /// Message returned to rejected clients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[validate(length(max = 500))]
+    pub message: Option<String>,
+}
+
+/// Maintenance mode status
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct MaintenanceStatus {
+    /// Whether maintenance mode is on
+    pub enabled: bool,
+
+    /// Message returned to rejected clients
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+
+    /// When maintenance mode was turned on
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<DateTime<Utc>>,
+}
+
+/// Archive decisions request
+///
+/// Limits left out fall back to the server's retention policy.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Validate, ToSchema)]
+pub struct ArchiveDecisionsRequest {
+    /// Archive decisions created more than this many days ago
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(range(min = 1))]
+    pub max_age_days: Option<u32>,
+
+    /// Archive all but this many of the newest decisions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keep_last: Option<usize>,
+}
+
+/// Outcome of applying the decision retention policy
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ArchiveDecisionsResponse {
+    /// Decisions moved to the archive by this run
+    pub archived: u64,
+
+    /// Decisions deleted by this run
+    pub deleted: u64,
+
+    /// Decisions left in the active set
+    pub retained: u64,
+
+    /// Decisions in the archive in total
+    pub archived_total: u64,
+}
+
 /// Create API key request
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateApiKeyRequest {
@@ -0,0 +1,42 @@
+//! Authentication related models
+
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use validator::Validate;
+
+use crate::middleware::auth::TokenPair;
+
+/// Refresh token exchange request
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema, Validate)]
+pub struct RefreshTokenRequest {
+    /// Refresh token issued with the previous access token
+    #[validate(length(min = 1))]
+    pub refresh_token: String,
+}
+
+/// Issued tokens
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct TokenResponse {
+    /// Access token to send as `Authorization: Bearer`
+    pub access_token: String,
+
+    /// Token type, always `Bearer`
+    pub token_type: String,
+
+    /// Access token lifetime in seconds
+    pub expires_in: u64,
+
+    /// Refresh token for the next exchange; the presented one is no longer valid
+    pub refresh_token: String,
+}
+
+impl From<TokenPair> for TokenResponse {
+    fn from(pair: TokenPair) -> Self {
+        Self {
+            access_token: pair.access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: pair.expires_in,
+            refresh_token: pair.refresh_token,
+        }
+    }
+}
@@ -1,15 +1,38 @@
 //! Common request/response models
 
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
 use serde::{Deserialize, Serialize};
 use utoipa::{IntoParams, ToSchema};
 
+use crate::error::ApiResult;
+use crate::models::{
+    admin::{
+        ApiKeyResponse, AuditLogEntry, FlushCacheResponse, MaintenanceStatus,
+        ServiceControlResponse, SystemStats,
+    },
+    auth::TokenResponse,
+    config::ConfigResponse,
+    integrations::{IntegrationResponse, TestIntegrationResponse},
+    metrics::{
+        CostMetricsResponse, MetricsResponse, PerformanceMetricsResponse, QualityMetricsResponse,
+    },
+    optimize::{OptimizationResponse, StrategyLeaderboardEntry},
+};
+
+/// Default upper bound on `page_size`, applied by the server
+pub const DEFAULT_MAX_PAGE_SIZE: u32 = 100;
+
 /// Pagination parameters
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema, IntoParams)]
 pub struct Pagination {
     /// Page number (starting from 1)
     #[serde(default = "default_page")]
     pub page: u32,
-    /// Page size
+    /// Page size; requests above the server maximum are clamped
     #[serde(default = "default_page_size")]
     pub page_size: u32,
 }
@@ -41,10 +64,28 @@ impl Pagination {
     pub fn limit(&self) -> u32 {
         self.page_size
     }
+
+    /// Cap `page_size` at `max_page_size`
+    ///
+    /// Returns the size the client asked for when it had to be reduced.
+    pub fn clamp_page_size(&mut self, max_page_size: u32) -> Option<u32> {
+        if self.page_size <= max_page_size {
+            return None;
+        }
+        let requested = self.page_size;
+        self.page_size = max_page_size;
+        Some(requested)
+    }
 }
 
 /// Paginated response
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[aliases(
+    PaginatedOptimizations = PaginatedResponse<OptimizationResponse>,
+    PaginatedIntegrations = PaginatedResponse<IntegrationResponse>,
+    PaginatedApiKeys = PaginatedResponse<ApiKeyResponse>,
+    PaginatedAuditLogs = PaginatedResponse<AuditLogEntry>,
+)]
 pub struct PaginatedResponse<T> {
     /// Items in current page
     pub items: Vec<T>,
@@ -56,6 +97,10 @@ pub struct PaginatedResponse<T> {
     pub page_size: u32,
     /// Total number of pages
     pub total_pages: u32,
+    /// Page size the client asked for, present only when it exceeded the
+    /// server maximum and `page_size` was clamped
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub requested_page_size: Option<u32>,
 }
 
 impl<T> PaginatedResponse<T> {
@@ -69,8 +114,15 @@ impl<T> PaginatedResponse<T> {
             page: pagination.page,
             page_size: pagination.page_size,
             total_pages,
+            requested_page_size: None,
         }
     }
+
+    /// Record the page size the client asked for before it was clamped
+    pub fn with_requested_page_size(mut self, requested: Option<u32>) -> Self {
+        self.requested_page_size = requested;
+        self
+    }
 }
 
 /// Sort direction
@@ -96,8 +148,84 @@ pub struct DateRange {
     pub end: chrono::DateTime<chrono::Utc>,
 }
 
+/// Largest number of items a single bulk request may name
+pub const MAX_BULK_ITEMS: u64 = 100;
+
+/// Outcome of one item of a bulk operation
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkItemResult {
+    /// Item ID
+    pub id: String,
+    /// HTTP status the item would have produced as a single request
+    pub status: u16,
+    /// Why the item failed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkItemResult {
+    /// Record the outcome of one item, using `success` as its status if it
+    /// succeeded
+    pub fn from_result<T>(id: impl ToString, result: &ApiResult<T>, success: StatusCode) -> Self {
+        match result {
+            Ok(_) => Self {
+                id: id.to_string(),
+                status: success.as_u16(),
+                error: None,
+            },
+            Err(e) => Self {
+                id: id.to_string(),
+                status: e.status_code().as_u16(),
+                error: Some(e.to_string()),
+            },
+        }
+    }
+
+    /// Whether the item succeeded
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Per-item outcomes of a bulk operation
+///
+/// Always sent as `207 Multi-Status`, even when every item shares the same
+/// outcome, so clients handle a single response shape.
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct BulkResponse {
+    /// One result per requested item, in request order
+    pub items: Vec<BulkItemResult>,
+}
+
+impl IntoResponse for BulkResponse {
+    fn into_response(self) -> Response {
+        (StatusCode::MULTI_STATUS, Json(self)).into_response()
+    }
+}
+
 /// API response wrapper
+///
+/// Each wrapped type the API returns has an alias, so the OpenAPI spec gets
+/// a distinct schema per response rather than one for every `ApiResponse`.
 #[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[aliases(
+    ApiResponseOptimization = ApiResponse<OptimizationResponse>,
+    ApiResponseLeaderboard = ApiResponse<Vec<StrategyLeaderboardEntry>>,
+    ApiResponseConfig = ApiResponse<ConfigResponse>,
+    ApiResponseConfigList = ApiResponse<Vec<ConfigResponse>>,
+    ApiResponseMetrics = ApiResponse<MetricsResponse>,
+    ApiResponsePerformanceMetrics = ApiResponse<PerformanceMetricsResponse>,
+    ApiResponseCostMetrics = ApiResponse<CostMetricsResponse>,
+    ApiResponseQualityMetrics = ApiResponse<QualityMetricsResponse>,
+    ApiResponseIntegration = ApiResponse<IntegrationResponse>,
+    ApiResponseTestIntegration = ApiResponse<TestIntegrationResponse>,
+    ApiResponseSystemStats = ApiResponse<SystemStats>,
+    ApiResponseFlushCache = ApiResponse<FlushCacheResponse>,
+    ApiResponseServiceControl = ApiResponse<ServiceControlResponse>,
+    ApiResponseMaintenance = ApiResponse<MaintenanceStatus>,
+    ApiResponseApiKey = ApiResponse<ApiKeyResponse>,
+    ApiResponseToken = ApiResponse<TokenResponse>,
+)]
 pub struct ApiResponse<T> {
     /// Response data
     pub data: T,
@@ -166,6 +294,41 @@ mod tests {
         assert_eq!(response.total_pages, 4);
     }
 
+    #[test]
+    fn test_page_size_clamp() {
+        let mut pagination = Pagination {
+            page: 1,
+            page_size: 100_000,
+        };
+        assert_eq!(pagination.clamp_page_size(DEFAULT_MAX_PAGE_SIZE), Some(100_000));
+        assert_eq!(pagination.page_size, DEFAULT_MAX_PAGE_SIZE);
+
+        assert_eq!(pagination.clamp_page_size(DEFAULT_MAX_PAGE_SIZE), None);
+        assert_eq!(pagination.page_size, DEFAULT_MAX_PAGE_SIZE);
+
+        let unclamped = serde_json::to_value(PaginatedResponse::new(vec![1], 1, &pagination)).unwrap();
+        assert!(unclamped.get("requested_page_size").is_none());
+    }
+
+    #[test]
+    fn test_bulk_item_results() {
+        use crate::error::ApiError;
+
+        let ok: ApiResult<()> = Ok(());
+        let item = BulkItemResult::from_result("a", &ok, StatusCode::NO_CONTENT);
+        assert_eq!(item.status, 204);
+        assert!(item.is_success() && item.error.is_none());
+
+        let missing: ApiResult<()> = Err(ApiError::NotFound("Optimization b".into()));
+        let item = BulkItemResult::from_result("b", &missing, StatusCode::OK);
+        assert_eq!(item.status, 404);
+        assert!(!item.is_success());
+        assert_eq!(item.error.as_deref(), Some("Not found: Optimization b"));
+
+        let response = BulkResponse { items: vec![item] }.into_response();
+        assert_eq!(response.status(), StatusCode::MULTI_STATUS);
+    }
+
     #[test]
     fn test_api_response() {
         let response = ApiResponse::new("test data")
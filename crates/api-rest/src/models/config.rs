@@ -50,3 +50,26 @@ pub struct ConfigUpdate {
     pub key: String,
     pub value: serde_json::Value,
 }
+
+/// Configuration change delivered over the config watch stream
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ConfigChangeEvent {
+    /// Event ID
+    pub id: uuid::Uuid,
+
+    /// Configuration key
+    pub key: String,
+
+    /// Value before the change, absent if the key was newly set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub old_value: Option<serde_json::Value>,
+
+    /// Value after the change
+    pub new_value: serde_json::Value,
+
+    /// User or API key that made the change
+    pub changed_by: String,
+
+    /// Change timestamp
+    pub changed_at: chrono::DateTime<chrono::Utc>,
+}
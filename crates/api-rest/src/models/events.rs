@@ -0,0 +1,107 @@
+//! Optimizer event models
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Optimizer event delivered over the event stream
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OptimizerEvent {
+    /// Event ID
+    pub id: Uuid,
+
+    /// Event type (e.g. "optimization.created", "optimization.deployed")
+    pub event_type: String,
+
+    /// Related optimization
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub optimization_id: Option<Uuid>,
+
+    /// Related service
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+
+    /// Human-readable message
+    pub message: String,
+
+    /// Timestamp
+    pub timestamp: DateTime<Utc>,
+}
+
+impl OptimizerEvent {
+    /// Create a new event
+    pub fn new(event_type: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event_type: event_type.into(),
+            optimization_id: None,
+            service: None,
+            message: message.into(),
+            timestamp: Utc::now(),
+        }
+    }
+
+    /// Set the related optimization
+    pub fn with_optimization(mut self, optimization_id: Uuid) -> Self {
+        self.optimization_id = Some(optimization_id);
+        self
+    }
+
+    /// Set the related service
+    pub fn with_service(mut self, service: impl Into<String>) -> Self {
+        self.service = Some(service.into());
+        self
+    }
+}
+
+/// Event stream query parameters
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema, IntoParams)]
+pub struct EventsQuery {
+    /// Only stream events of this type
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_type: Option<String>,
+
+    /// Only stream events for this service
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<String>,
+}
+
+impl EventsQuery {
+    /// Check whether an event passes this filter
+    pub fn matches(&self, event: &OptimizerEvent) -> bool {
+        if let Some(event_type) = &self.event_type {
+            if &event.event_type != event_type {
+                return false;
+            }
+        }
+        if let Some(service) = &self.service {
+            if event.service.as_ref() != Some(service) {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_query_matches() {
+        let event = OptimizerEvent::new("optimization.deployed", "deployed").with_service("chat");
+
+        assert!(EventsQuery::default().matches(&event));
+        assert!(EventsQuery {
+            event_type: Some("optimization.deployed".to_string()),
+            service: Some("chat".to_string()),
+        }
+        .matches(&event));
+        assert!(!EventsQuery {
+            event_type: None,
+            service: Some("search".to_string()),
+        }
+        .matches(&event));
+    }
+}
@@ -7,6 +7,8 @@ pub mod integrations;
 pub mod health;
 pub mod admin;
 pub mod common;
+pub mod events;
+pub mod auth;
 
 pub use optimize::*;
 pub use config::*;
@@ -15,3 +17,5 @@ pub use integrations::*;
 pub use health::*;
 pub use admin::*;
 pub use common::*;
+pub use events::*;
+pub use auth::*;
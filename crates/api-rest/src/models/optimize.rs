@@ -2,12 +2,15 @@
 
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use utoipa::{IntoParams, ToSchema};
 use uuid::Uuid;
-use validator::Validate;
+use validator::{Validate, ValidationError};
 
 use llm_optimizer_types::decisions::{OptimizationStrategy, DecisionStatus};
 
+use crate::models::common::MAX_BULK_ITEMS;
+
 /// Request to create an optimization
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct CreateOptimizationRequest {
@@ -29,6 +32,78 @@ pub struct CreateOptimizationRequest {
     /// Dry run mode (don't actually deploy)
     #[serde(default)]
     pub dry_run: bool,
+
+    /// Operator tags such as team, ticket or experiment
+    #[serde(default)]
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: HashMap<String, String>,
+}
+
+/// Maximum number of tags on one optimization
+pub const MAX_TAGS: usize = 32;
+
+/// Maximum length of a tag key
+pub const MAX_TAG_KEY_LEN: usize = 64;
+
+/// Maximum length of a tag value
+pub const MAX_TAG_VALUE_LEN: usize = 256;
+
+/// Check tag keys and values
+///
+/// Keys and values are non-empty and use only ASCII letters, digits and
+/// `-_.:/`, so they can be written as `key=value` in a query string without
+/// escaping; keys must start with a letter or digit.
+pub fn validate_tags(tags: &HashMap<String, String>) -> Result<(), ValidationError> {
+    fn invalid(message: String) -> ValidationError {
+        let mut error = ValidationError::new("invalid_tag");
+        error.message = Some(message.into());
+        error
+    }
+    let allowed = |c: char| c.is_ascii_alphanumeric() || "-_.:/".contains(c);
+
+    if tags.len() > MAX_TAGS {
+        return Err(invalid(format!("at most {} tags are allowed", MAX_TAGS)));
+    }
+    for (key, value) in tags {
+        if key.is_empty() || key.len() > MAX_TAG_KEY_LEN {
+            return Err(invalid(format!(
+                "tag key '{}' must be 1-{} characters",
+                key, MAX_TAG_KEY_LEN
+            )));
+        }
+        if !key.starts_with(|c: char| c.is_ascii_alphanumeric()) || !key.chars().all(allowed) {
+            return Err(invalid(format!(
+                "tag key '{}' must start with a letter or digit and contain only letters, digits and -_.:/",
+                key
+            )));
+        }
+        if value.is_empty() || value.len() > MAX_TAG_VALUE_LEN {
+            return Err(invalid(format!(
+                "value of tag '{}' must be 1-{} characters",
+                key, MAX_TAG_VALUE_LEN
+            )));
+        }
+        if !value.chars().all(allowed) {
+            return Err(invalid(format!(
+                "value of tag '{}' may contain only letters, digits and -_.:/",
+                key
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Parse a tag filter of comma-separated `key=value` pairs
+pub fn parse_tag_filter(filter: &str) -> Result<HashMap<String, String>, String> {
+    filter
+        .split(',')
+        .map(|pair| match pair.split_once('=') {
+            Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+                Ok((key.to_string(), value.to_string()))
+            }
+            _ => Err(format!("Invalid tag filter '{}': expected key=value", pair)),
+        })
+        .collect()
 }
 
 /// Constraint input
@@ -64,6 +139,18 @@ pub struct OptimizationResponse {
     /// Configuration changes
     pub changes: Vec<ConfigurationChangeResponse>,
 
+    /// Configuration currently in effect
+    #[serde(default)]
+    pub config: serde_json::Value,
+
+    /// Revision currently in effect
+    #[serde(default)]
+    pub revision: u32,
+
+    /// Every configuration this optimization has had, oldest first
+    #[serde(default)]
+    pub revisions: Vec<OptimizationRevision>,
+
     /// Expected impact
     pub expected_impact: ExpectedImpactResponse,
 
@@ -80,6 +167,26 @@ pub struct OptimizationResponse {
     /// Deployed at (if deployed)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub deployed_at: Option<DateTime<Utc>>,
+
+    /// Operator tags
+    #[serde(default)]
+    pub tags: HashMap<String, String>,
+}
+
+/// A configuration recorded when an optimization is created or deployed
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct OptimizationRevision {
+    /// Revision number, starting at 1
+    pub revision: u32,
+
+    /// Configuration at this revision
+    pub config: serde_json::Value,
+
+    /// Rollout percentage at this revision
+    pub rollout_percentage: f64,
+
+    /// Recorded at
+    pub created_at: DateTime<Utc>,
 }
 
 /// Configuration change response
@@ -146,6 +253,10 @@ pub struct DeployOptimizationRequest {
     #[serde(default = "default_rollout_pct")]
     #[validate(range(min = 0.0, max = 100.0))]
     pub rollout_percentage: f64,
+
+    /// Configuration to deploy, replacing the current one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config: Option<serde_json::Value>,
 }
 
 fn default_gradual() -> bool {
@@ -156,12 +267,82 @@ fn default_rollout_pct() -> f64 {
     10.0
 }
 
+/// Request naming the optimizations a bulk operation applies to
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct BulkOptimizationRequest {
+    /// Optimization IDs
+    #[validate(length(min = 1, max = MAX_BULK_ITEMS))]
+    pub ids: Vec<Uuid>,
+}
+
+/// Request to deploy several optimizations with the same rollout
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct BulkDeployRequest {
+    /// Optimization IDs
+    #[validate(length(min = 1, max = MAX_BULK_ITEMS))]
+    pub ids: Vec<Uuid>,
+
+    /// Whether to perform gradual rollout
+    #[serde(default = "default_gradual")]
+    pub gradual: bool,
+
+    /// Rollout percentage (if gradual)
+    #[serde(default = "default_rollout_pct")]
+    #[validate(range(min = 0.0, max = 100.0))]
+    pub rollout_percentage: f64,
+}
+
 /// Request to rollback an optimization
 #[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
 pub struct RollbackOptimizationRequest {
     /// Reason for rollback
     #[validate(length(min = 1))]
     pub reason: String,
+
+    /// Revision to restore; defaults to the one before the current revision
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub to_revision: Option<u32>,
+}
+
+/// Request to reject an optimization awaiting approval
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct RejectOptimizationRequest {
+    /// Reason for rejection
+    #[validate(length(min = 1))]
+    pub reason: String,
+}
+
+/// Request to add or overwrite tags on an optimization
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct TagOptimizationRequest {
+    /// Tags to set; existing tags with the same key are overwritten
+    #[validate(custom(function = "validate_tags"))]
+    pub tags: HashMap<String, String>,
+}
+
+/// Historical performance of one optimization strategy
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct StrategyLeaderboardEntry {
+    /// Position in the leaderboard, starting at 1
+    pub rank: u32,
+
+    /// Strategy
+    pub strategy: OptimizationStrategy,
+
+    /// Number of optimizations with a measured actual impact
+    pub optimizations: u64,
+
+    /// Average actual cost reduction percentage
+    pub avg_cost_reduction_pct: f64,
+
+    /// Average actual quality change percentage
+    pub avg_quality_delta_pct: f64,
+
+    /// Average actual latency change percentage
+    pub avg_latency_delta_pct: f64,
+
+    /// Share of measured optimizations that were not rolled back (0.0-1.0)
+    pub success_rate: f64,
 }
 
 /// List optimizations query parameters
@@ -185,6 +366,10 @@ pub struct ListOptimizationsQuery {
 
     #[serde(skip_serializing_if = "Option::is_none")]
     pub to: Option<DateTime<Utc>>,
+
+    /// Filter by tags, as comma-separated `key=value` pairs that must all match
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
 }
 
 #[cfg(test)]
@@ -199,16 +384,47 @@ mod tests {
             config: serde_json::json!({}),
             constraints: vec![],
             dry_run: false,
+            tags: HashMap::from([("team".to_string(), "ml".to_string())]),
         };
 
         assert!(req.validate().is_ok());
     }
 
+    #[test]
+    fn test_tag_validation() {
+        let tags = |pairs: &[(&str, &str)]| -> HashMap<String, String> {
+            pairs.iter().map(|(k, v)| (k.to_string(), v.to_string())).collect()
+        };
+
+        assert!(validate_tags(&tags(&[("team", "ml"), ("ticket", "OPT-123"), ("exp", "a/b:2")])).is_ok());
+        assert!(validate_tags(&tags(&[("", "ml")])).is_err());
+        assert!(validate_tags(&tags(&[("-team", "ml")])).is_err());
+        assert!(validate_tags(&tags(&[("team", "")])).is_err());
+        assert!(validate_tags(&tags(&[("team", "ml,ops")])).is_err());
+        assert!(validate_tags(&tags(&[("team=x", "ml")])).is_err());
+        assert!(validate_tags(&tags(&[("team", &"x".repeat(MAX_TAG_VALUE_LEN + 1))])).is_err());
+
+        let too_many: HashMap<String, String> =
+            (0..=MAX_TAGS).map(|i| (format!("k{}", i), "v".to_string())).collect();
+        assert!(validate_tags(&too_many).is_err());
+    }
+
+    #[test]
+    fn test_parse_tag_filter() {
+        let filter = parse_tag_filter("team=ml,ticket=OPT-1").unwrap();
+        assert_eq!(filter.len(), 2);
+        assert_eq!(filter["team"], "ml");
+
+        assert!(parse_tag_filter("team").is_err());
+        assert!(parse_tag_filter("team=").is_err());
+    }
+
     #[test]
     fn test_deploy_optimization_request_defaults() {
         let req = DeployOptimizationRequest {
             gradual: default_gradual(),
             rollout_percentage: default_rollout_pct(),
+            config: None,
         };
 
         assert!(req.gradual);
@@ -1,12 +1,22 @@
 //! OpenAPI specification generation
+//!
+//! The running server serves the spec at `/openapi.json` and Swagger UI at
+//! `/docs`.
 
+use std::sync::OnceLock;
+
+use axum::Json;
 use utoipa::{
-    openapi::security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+    openapi::{
+        security::{ApiKey, ApiKeyValue, HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Content, Ref, RefOr,
+    },
     Modify, OpenApi,
 };
 
+use crate::error::{ErrorResponse, ProblemJson};
 use crate::models::{
-    admin::*, common::*, config::*, health::*, integrations::*, metrics::*, optimize::*,
+    admin::*, auth::*, common::*, config::*, events::*, health::*, integrations::*, metrics::*, optimize::*,
 };
 
 /// OpenAPI documentation
@@ -17,43 +27,87 @@ use crate::models::{
         crate::routes::health::health_check,
         crate::routes::health::liveness_check,
         crate::routes::health::readiness_check,
+        // Auth endpoints
+        crate::routes::auth::refresh_token,
         // Optimization endpoints
         crate::routes::optimize::create_optimization,
         crate::routes::optimize::list_optimizations,
+        crate::routes::optimize::get_strategy_leaderboard,
         crate::routes::optimize::get_optimization,
         crate::routes::optimize::deploy_optimization,
+        crate::routes::optimize::bulk_deploy_optimizations,
+        crate::routes::optimize::bulk_delete_optimizations,
         crate::routes::optimize::rollback_optimization,
+        crate::routes::optimize::approve_optimization,
+        crate::routes::optimize::reject_optimization,
+        crate::routes::optimize::add_optimization_tags,
+        crate::routes::optimize::remove_optimization_tag,
+        // Config endpoints
+        crate::routes::config::watch_config,
+        crate::routes::config::export_config,
+        crate::routes::config::import_config,
+        crate::routes::config::get_config,
+        crate::routes::config::update_config,
+        crate::routes::config::batch_update_config,
+        // Metrics endpoints
+        crate::routes::metrics::query_metrics,
+        crate::routes::metrics::get_performance_metrics,
+        crate::routes::metrics::get_cost_metrics,
+        crate::routes::metrics::get_quality_metrics,
+        // Integration endpoints
+        crate::routes::integrations::create_integration,
+        crate::routes::integrations::list_integrations,
+        crate::routes::integrations::get_integration,
+        crate::routes::integrations::update_integration,
+        crate::routes::integrations::delete_integration,
+        crate::routes::integrations::test_integration,
+        // Event endpoints
+        crate::routes::events::stream_events,
     ),
     components(
         schemas(
             // Common models
             Pagination,
-            PaginatedResponse<OptimizationResponse>,
-            PaginatedResponse<IntegrationResponse>,
-            PaginatedResponse<ApiKeyResponse>,
-            PaginatedResponse<AuditLogEntry>,
-            ApiResponse<OptimizationResponse>,
-            ApiResponse<ConfigResponse>,
-            ApiResponse<MetricsResponse>,
-            ApiResponse<PerformanceMetricsResponse>,
-            ApiResponse<CostMetricsResponse>,
-            ApiResponse<QualityMetricsResponse>,
-            ApiResponse<IntegrationResponse>,
-            ApiResponse<TestIntegrationResponse>,
-            ApiResponse<SystemStats>,
-            ApiResponse<FlushCacheResponse>,
-            ApiResponse<ApiKeyResponse>,
-            ApiResponse<Vec<ConfigResponse>>,
+            PaginatedOptimizations,
+            PaginatedIntegrations,
+            PaginatedApiKeys,
+            PaginatedAuditLogs,
+            ApiResponseOptimization,
+            ApiResponseLeaderboard,
+            ApiResponseConfig,
+            ApiResponseConfigList,
+            ApiResponseMetrics,
+            ApiResponsePerformanceMetrics,
+            ApiResponseCostMetrics,
+            ApiResponseQualityMetrics,
+            ApiResponseIntegration,
+            ApiResponseTestIntegration,
+            ApiResponseSystemStats,
+            ApiResponseFlushCache,
+            ApiResponseServiceControl,
+            ApiResponseMaintenance,
+            ApiResponseApiKey,
+            ApiResponseToken,
+            ErrorResponse,
+            ProblemJson,
             SortDirection,
             DateRange,
+            BulkItemResult,
+            BulkResponse,
             // Optimization models
             CreateOptimizationRequest,
             OptimizationResponse,
+            OptimizationRevision,
             ConfigurationChangeResponse,
             ExpectedImpactResponse,
             ActualImpactResponse,
             DeployOptimizationRequest,
+            BulkDeployRequest,
+            BulkOptimizationRequest,
             RollbackOptimizationRequest,
+            RejectOptimizationRequest,
+            TagOptimizationRequest,
+            StrategyLeaderboardEntry,
             ListOptimizationsQuery,
             ConstraintInput,
             // Config models
@@ -62,6 +116,7 @@ use crate::models::{
             ConfigResponse,
             BatchUpdateConfigRequest,
             ConfigUpdate,
+            ConfigChangeEvent,
             // Metrics models
             QueryMetricsRequest,
             MetricsResponse,
@@ -92,16 +147,29 @@ use crate::models::{
             ApiKeyResponse,
             AuditLogEntry,
             QueryAuditLogsRequest,
+            ServiceState,
+            ServiceControlResponse,
+            ManagedServiceStatus,
+            SetMaintenanceRequest,
+            MaintenanceStatus,
+            // Auth models
+            RefreshTokenRequest,
+            TokenResponse,
+            // Event models
+            OptimizerEvent,
+            EventsQuery,
         )
     ),
-    modifiers(&SecurityAddon),
+    modifiers(&SecurityAddon, &ErrorResponseAddon),
     tags(
         (name = "health", description = "Health check endpoints"),
+        (name = "auth", description = "Token refresh endpoints"),
         (name = "optimize", description = "Optimization management endpoints"),
         (name = "config", description = "Configuration management endpoints"),
         (name = "metrics", description = "Metrics and analytics endpoints"),
         (name = "integrations", description = "Integration management endpoints"),
         (name = "admin", description = "Administrative endpoints"),
+        (name = "events", description = "Real-time event streaming endpoints"),
     ),
     info(
         title = "LLM Auto Optimizer REST API",
@@ -149,6 +217,43 @@ impl Modify for SecurityAddon {
     }
 }
 
+/// Error response addon
+///
+/// Every error is returned as an [`ErrorResponse`], so 4xx and 5xx responses
+/// that don't name a body get its schema.
+struct ErrorResponseAddon;
+
+impl Modify for ErrorResponseAddon {
+    fn modify(&self, openapi: &mut utoipa::openapi::OpenApi) {
+        let operations = openapi
+            .paths
+            .paths
+            .values_mut()
+            .flat_map(|item| item.operations.values_mut());
+        for operation in operations {
+            for (status, response) in operation.responses.responses.iter_mut() {
+                let RefOr::T(response) = response else {
+                    continue;
+                };
+                if (status.starts_with('4') || status.starts_with('5'))
+                    && response.content.is_empty()
+                {
+                    response.content.insert(
+                        "application/json".to_string(),
+                        Content::new(Ref::from_schema_name("ErrorResponse")),
+                    );
+                }
+            }
+        }
+    }
+}
+
+/// Serve the OpenAPI specification as JSON
+pub async fn openapi_json() -> Json<utoipa::openapi::OpenApi> {
+    static SPEC: OnceLock<utoipa::openapi::OpenApi> = OnceLock::new();
+    Json(SPEC.get_or_init(ApiDoc::openapi).clone())
+}
+
 /// Generate OpenAPI YAML specification
 pub fn generate_openapi_yaml() -> Result<String, serde_yaml::Error> {
     let api_doc = ApiDoc::openapi();
@@ -1,12 +1,100 @@
 //! Admin routes
 
-use axum::{extract::{Query, State}, http::StatusCode, routing::{delete, get, post}, Json, Router};
+use async_trait::async_trait;
+use axum::{extract::{Query, State}, http::StatusCode, routing::{delete, get, post, put}, Json, Router};
 use std::sync::Arc;
-use crate::error::ApiResult;
+use tokio::sync::Mutex;
+use validator::Validate;
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::maintenance::MaintenanceMode;
 use crate::models::{admin::*, common::{ApiResponse, PaginatedResponse, Pagination}};
+use crate::routes::optimize::{OptimizationService, RetentionPolicy};
 
-#[derive(Clone)]
-pub struct AdminService;
+/// Supplies the status of the services managed by the process, normally
+/// its service manager
+#[async_trait]
+pub trait ServiceStatusSource: Send + Sync {
+    /// Current status of every managed service
+    async fn service_statuses(&self) -> Vec<ManagedServiceStatus>;
+}
+
+pub struct AdminService {
+    service_state: Mutex<ServiceState>,
+    maintenance: Arc<MaintenanceMode>,
+    decisions: Arc<OptimizationService>,
+    retention: RetentionPolicy,
+    service_status: Option<Arc<dyn ServiceStatusSource>>,
+}
+
+impl AdminService {
+    pub fn new() -> Self {
+        Self {
+            service_state: Mutex::new(ServiceState::Running),
+            maintenance: Arc::new(MaintenanceMode::new()),
+            decisions: Arc::new(OptimizationService::new()),
+            retention: RetentionPolicy::new(),
+            service_status: None,
+        }
+    }
+
+    /// Share the optimization decisions the retention policy applies to
+    pub fn with_decisions(mut self, decisions: Arc<OptimizationService>) -> Self {
+        self.decisions = decisions;
+        self
+    }
+
+    /// Set the retention policy used when archiving decisions on demand
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Report managed services from `source` at `/admin/services`
+    pub fn with_service_status(mut self, source: Arc<dyn ServiceStatusSource>) -> Self {
+        self.service_status = Some(source);
+        self
+    }
+
+    /// Share the maintenance flag checked by the maintenance middleware
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
+    /// Move the service to `target`; a request for the current state is a no-op
+    pub async fn transition(&self, target: ServiceState) -> ServiceControlResponse {
+        let mut state = self.service_state.lock().await;
+
+        if *state == target {
+            let message = match target {
+                ServiceState::Running => "Service is already running (no-op)",
+                ServiceState::Stopped => "Service is already stopped (no-op)",
+            };
+            return ServiceControlResponse {
+                status: "no_op".to_string(),
+                message: message.to_string(),
+                state: target,
+            };
+        }
+
+        *state = target;
+        let (status, message) = match target {
+            ServiceState::Running => ("started", "Service started"),
+            ServiceState::Stopped => ("stopped", "Service stopped"),
+        };
+        ServiceControlResponse {
+            status: status.to_string(),
+            message: message.to_string(),
+            state: target,
+        }
+    }
+}
+
+impl Default for AdminService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub fn admin_routes(service: Arc<AdminService>) -> Router {
     Router::new()
@@ -16,6 +104,11 @@ pub fn admin_routes(service: Arc<AdminService>) -> Router {
         .route("/admin/api-keys", get(list_api_keys))
         .route("/admin/api-keys/:id", delete(revoke_api_key))
         .route("/admin/audit-logs", get(query_audit_logs))
+        .route("/admin/service/start", post(start_service))
+        .route("/admin/service/stop", post(stop_service))
+        .route("/admin/services", get(list_services))
+        .route("/admin/maintenance", get(get_maintenance).put(set_maintenance))
+        .route("/admin/decisions/archive", post(archive_decisions))
         .with_state(service)
 }
 
@@ -64,3 +157,149 @@ async fn revoke_api_key(State(_): State<Arc<AdminService>>, axum::extract::Path(
 async fn query_audit_logs(State(_): State<Arc<AdminService>>, Query(_): Query<QueryAuditLogsRequest>) -> ApiResult<Json<PaginatedResponse<AuditLogEntry>>> {
     Ok(Json(PaginatedResponse::new(vec![], 0, &Pagination::default())))
 }
+
+async fn start_service(State(service): State<Arc<AdminService>>) -> ApiResult<Json<ApiResponse<ServiceControlResponse>>> {
+    Ok(Json(ApiResponse::new(service.transition(ServiceState::Running).await)))
+}
+
+async fn stop_service(State(service): State<Arc<AdminService>>) -> ApiResult<Json<ApiResponse<ServiceControlResponse>>> {
+    Ok(Json(ApiResponse::new(service.transition(ServiceState::Stopped).await)))
+}
+
+async fn list_services(State(service): State<Arc<AdminService>>) -> ApiResult<Json<ApiResponse<Vec<ManagedServiceStatus>>>> {
+    let statuses = match &service.service_status {
+        Some(source) => source.service_statuses().await,
+        None => Vec::new(),
+    };
+    Ok(Json(ApiResponse::new(statuses)))
+}
+
+async fn get_maintenance(State(service): State<Arc<AdminService>>) -> ApiResult<Json<ApiResponse<MaintenanceStatus>>> {
+    Ok(Json(ApiResponse::new(service.maintenance.status())))
+}
+
+async fn set_maintenance(State(service): State<Arc<AdminService>>, Json(req): Json<SetMaintenanceRequest>) -> ApiResult<Json<ApiResponse<MaintenanceStatus>>> {
+    req.validate().map_err(|e| ApiError::Validation(e.to_string()))?;
+    let status = service.maintenance.set(req.enabled, req.message);
+    tracing::warn!(enabled = status.enabled, "Maintenance mode updated");
+    Ok(Json(ApiResponse::new(status)))
+}
+
+async fn archive_decisions(State(service): State<Arc<AdminService>>, Json(req): Json<ArchiveDecisionsRequest>) -> ApiResult<Json<ApiResponse<ArchiveDecisionsResponse>>> {
+    req.validate().map_err(|e| ApiError::Validation(e.to_string()))?;
+
+    let mut policy = service.retention;
+    policy.max_age_days = req.max_age_days.or(policy.max_age_days);
+    policy.keep_last = req.keep_last.or(policy.keep_last);
+    if !policy.is_bounded() {
+        return Err(ApiError::Validation(
+            "No retention policy configured; pass max_age_days or keep_last".to_string(),
+        ));
+    }
+
+    Ok(Json(ApiResponse::new(service.decisions.apply_retention(&policy, chrono::Utc::now()))))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, http::Request};
+    use tower::ServiceExt;
+
+    async fn post_json(app: &Router, uri: &str) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(Request::post(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_double_start_is_successful_no_op() {
+        let app = admin_routes(Arc::new(AdminService::new()));
+
+        let (status, body) = post_json(&app, "/admin/service/stop").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "stopped");
+
+        let (status, body) = post_json(&app, "/admin/service/start").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "started");
+
+        let (status, body) = post_json(&app, "/admin/service/start").await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "no_op");
+        assert_eq!(body["data"]["state"], "running");
+    }
+
+    #[tokio::test]
+    async fn test_archive_decisions_requires_a_policy() {
+        let app = admin_routes(Arc::new(AdminService::new()));
+        let archive = |body: serde_json::Value| {
+            Request::post("/admin/decisions/archive")
+                .header("content-type", "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(archive(serde_json::json!({}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        let response = app.oneshot(archive(serde_json::json!({"keep_last": 10}))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"]["archived"], 0);
+    }
+
+    struct FixedStatuses;
+
+    #[async_trait]
+    impl ServiceStatusSource for FixedStatuses {
+        async fn service_statuses(&self) -> Vec<ManagedServiceStatus> {
+            vec![ManagedServiceStatus {
+                name: "storage".to_string(),
+                state: "running".to_string(),
+                uptime_seconds: Some(42),
+                restart_count: 2,
+                last_health_message: None,
+            }]
+        }
+    }
+
+    #[tokio::test]
+    async fn test_list_services_reports_source_statuses() {
+        let get_services = || Request::get("/admin/services").body(Body::empty()).unwrap();
+
+        let app = admin_routes(Arc::new(AdminService::new()));
+        let response = app.oneshot(get_services()).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"], serde_json::json!([]));
+
+        let app = admin_routes(Arc::new(
+            AdminService::new().with_service_status(Arc::new(FixedStatuses)),
+        ));
+        let response = app.oneshot(get_services()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["data"][0]["name"], "storage");
+        assert_eq!(body["data"][0]["uptime_seconds"], 42);
+        assert_eq!(body["data"][0]["restart_count"], 2);
+        assert!(body["data"][0].get("last_health_message").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_double_stop_is_no_op() {
+        let service = AdminService::new();
+
+        assert_eq!(service.transition(ServiceState::Stopped).await.status, "stopped");
+        let response = service.transition(ServiceState::Stopped).await;
+        assert_eq!(response.status, "no_op");
+        assert_eq!(response.state, ServiceState::Stopped);
+    }
+}
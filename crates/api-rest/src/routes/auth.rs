@@ -0,0 +1,92 @@
+//! Authentication routes
+//!
+//! These routes are reached without credentials; the refresh token in the
+//! body is the credential.
+
+use axum::{extract::State, routing::post, Json, Router};
+use std::sync::Arc;
+use validator::Validate;
+
+use crate::error::{ApiError, ApiResult};
+use crate::middleware::auth::AuthConfig;
+use crate::models::{auth::*, common::ApiResponse};
+
+/// Authentication routes
+pub fn auth_routes(config: Arc<AuthConfig>) -> Router {
+    Router::new()
+        .route("/auth/refresh", post(refresh_token))
+        .with_state(config)
+}
+
+/// Exchange a refresh token for a new access token
+///
+/// The refresh token is rotated: the response carries a new one and the
+/// presented one stops working. Presenting an already rotated token revokes
+/// every refresh token issued from the same login.
+#[utoipa::path(
+    post,
+    path = "/api/v1/auth/refresh",
+    tag = "auth",
+    request_body = RefreshTokenRequest,
+    responses(
+        (status = 200, description = "Tokens issued", body = ApiResponseToken),
+        (status = 400, description = "Invalid request"),
+        (status = 401, description = "Invalid, expired or reused refresh token")
+    )
+)]
+async fn refresh_token(
+    State(config): State<Arc<AuthConfig>>,
+    Json(req): Json<RefreshTokenRequest>,
+) -> ApiResult<Json<ApiResponse<TokenResponse>>> {
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let pair = config.refresh(&req.refresh_token)?;
+    Ok(Json(ApiResponse::new(pair.into())))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{
+        body::Body,
+        http::{header, Request, StatusCode},
+    };
+    use tower::ServiceExt;
+
+    async fn refresh(app: &Router, refresh_token: &str) -> (StatusCode, serde_json::Value) {
+        let body = serde_json::json!({ "refresh_token": refresh_token });
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post("/auth/refresh")
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rejects_rotated_token() {
+        let config = Arc::new(AuthConfig::new("test-secret".to_string()));
+        let pair = config
+            .issue_token_pair("user-123".to_string(), vec!["user".to_string()])
+            .unwrap();
+        let app = auth_routes(config.clone());
+
+        let (status, body) = refresh(&app, &pair.refresh_token).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["token_type"], "Bearer");
+        let access_token = body["data"]["access_token"].as_str().unwrap();
+        assert_eq!(config.verify_token(access_token).unwrap().sub, "user-123");
+
+        let (status, body) = refresh(&app, &pair.refresh_token).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(body["error"], "authentication_error");
+    }
+}
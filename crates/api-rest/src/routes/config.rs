@@ -1,29 +1,451 @@
 //! Configuration routes
 
-use axum::{extract::{Path, State}, http::StatusCode, routing::{get, post, put}, Json, Router};
+use axum::{
+    extract::{Path, State},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::{get, post, put},
+    Extension, Json, Router,
+};
+use chrono::Utc;
+use dashmap::DashMap;
+use futures::stream::{self, Stream};
+use std::collections::BTreeMap;
+use std::convert::Infallible;
 use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+use tower_http::compression::{predicate::SizeAbove, CompressionLayer};
+use tower_http::decompression::RequestDecompressionLayer;
+use uuid::Uuid;
 use crate::error::{ApiError, ApiResult};
+use crate::middleware::{
+    auth::AuthMethod,
+    body_limit::{body_limit_middleware, DEFAULT_MAX_BODY_BYTES},
+    rbac::{ensure_permission, has_permission, Permission},
+};
 use crate::models::{config::*, common::ApiResponse};
 
+/// Number of change events buffered per watcher before slow watchers lag
+const CHANGE_BUFFER_SIZE: usize = 256;
+
+/// Configuration store that notifies watchers of every change
 #[derive(Clone)]
-pub struct ConfigService;
+pub struct ConfigService {
+    values: DashMap<String, ConfigResponse>,
+    changes: broadcast::Sender<ConfigChangeEvent>,
+}
+
+impl ConfigService {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGE_BUFFER_SIZE);
+        Self {
+            values: DashMap::new(),
+            changes,
+        }
+    }
+
+    /// Current value of a key
+    pub fn get(&self, key: &str) -> Option<ConfigResponse> {
+        self.values.get(key).map(|entry| entry.clone())
+    }
+
+    /// Set a key and publish the change to watchers
+    pub fn set(&self, key: &str, value: serde_json::Value, changed_by: String) -> ConfigResponse {
+        let updated = ConfigResponse {
+            key: key.to_string(),
+            value: value.clone(),
+            updated_at: Utc::now(),
+        };
+        let old_value = self
+            .values
+            .insert(key.to_string(), updated.clone())
+            .map(|previous| previous.value);
+
+        let _ = self.changes.send(ConfigChangeEvent {
+            id: Uuid::new_v4(),
+            key: key.to_string(),
+            old_value,
+            new_value: value,
+            changed_by,
+            changed_at: updated.updated_at,
+        });
+
+        updated
+    }
+
+    /// Every key and its current value, ordered by key
+    pub fn snapshot(&self) -> BTreeMap<String, serde_json::Value> {
+        self.values
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().value.clone()))
+            .collect()
+    }
+
+    /// Subscribe to future config changes
+    pub fn subscribe(&self) -> broadcast::Receiver<ConfigChangeEvent> {
+        self.changes.subscribe()
+    }
+}
+
+impl Default for ConfigService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 pub fn config_routes(service: Arc<ConfigService>) -> Router {
     Router::new()
+        .route("/config/watch", get(watch_config))
+        // Exports are gzipped whenever the client accepts it, however small
+        .route(
+            "/config/export",
+            get(export_config).layer(CompressionLayer::new().no_br().compress_when(SizeAbove::new(0))),
+        )
+        // The server-wide body limit only sees the compressed body, so the
+        // decompressed import is limited again
+        .route(
+            "/config/import",
+            post(import_config)
+                .layer::<_, Infallible>(axum::middleware::from_fn_with_state(
+                    DEFAULT_MAX_BODY_BYTES,
+                    body_limit_middleware,
+                ))
+                .layer(RequestDecompressionLayer::new()),
+        )
         .route("/config/:key", get(get_config))
         .route("/config/:key", put(update_config))
         .route("/config/batch", post(batch_update_config))
         .with_state(service)
 }
 
-async fn get_config(State(_): State<Arc<ConfigService>>, Path(key): Path<String>) -> ApiResult<Json<ApiResponse<ConfigResponse>>> {
-    Err(ApiError::NotFound(format!("Config key not found: {}", key)))
+/// Get a configuration value
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/{key}",
+    tag = "config",
+    params(("key" = String, Path, description = "Configuration key")),
+    responses(
+        (status = 200, description = "Configuration value", body = ApiResponseConfig),
+        (status = 404, description = "Configuration key not found")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_config(State(service): State<Arc<ConfigService>>, Path(key): Path<String>) -> ApiResult<Json<ApiResponse<ConfigResponse>>> {
+    service
+        .get(&key)
+        .map(|config| Json(ApiResponse::new(config)))
+        .ok_or_else(|| ApiError::NotFound(format!("Config key not found: {}", key)))
+}
+
+/// Set a configuration value
+#[utoipa::path(
+    put,
+    path = "/api/v1/config/{key}",
+    tag = "config",
+    params(("key" = String, Path, description = "Configuration key")),
+    request_body = UpdateConfigRequest,
+    responses(
+        (status = 200, description = "Updated configuration value", body = ApiResponseConfig),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn update_config(State(service): State<Arc<ConfigService>>, Extension(auth): Extension<AuthMethod>, Path(key): Path<String>, Json(request): Json<UpdateConfigRequest>) -> ApiResult<Json<ApiResponse<ConfigResponse>>> {
+    Ok(Json(ApiResponse::new(service.set(&key, request.value, auth.user_id()))))
+}
+
+/// Set several configuration values at once
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/batch",
+    tag = "config",
+    request_body = BatchUpdateConfigRequest,
+    responses(
+        (status = 200, description = "Updated configuration values", body = ApiResponseConfigList),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn batch_update_config(State(service): State<Arc<ConfigService>>, Extension(auth): Extension<AuthMethod>, Json(request): Json<BatchUpdateConfigRequest>) -> ApiResult<Json<ApiResponse<Vec<ConfigResponse>>>> {
+    let updated = request
+        .updates
+        .into_iter()
+        .map(|update| service.set(&update.key, update.value, auth.user_id()))
+        .collect();
+    Ok(Json(ApiResponse::new(updated)))
+}
+
+/// Export every configuration value
+///
+/// Sent with `Content-Encoding: gzip` when the request's `Accept-Encoding`
+/// allows it.
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/export",
+    tag = "config",
+    responses(
+        (status = 200, description = "Configuration keys and their values", body = Object),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn export_config(
+    State(service): State<Arc<ConfigService>>,
+    Extension(auth): Extension<AuthMethod>,
+) -> ApiResult<Json<BTreeMap<String, serde_json::Value>>> {
+    ensure_permission(&auth, Permission::ConfigRead)?;
+    Ok(Json(service.snapshot()))
+}
+
+/// Import configuration values from an export
+///
+/// The body may be sent with `Content-Encoding: gzip`.
+#[utoipa::path(
+    post,
+    path = "/api/v1/config/import",
+    tag = "config",
+    request_body(content = Object, description = "Configuration keys and their values"),
+    responses(
+        (status = 200, description = "Imported configuration values", body = ApiResponseConfigList),
+        (status = 400, description = "Body is not a configuration export"),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn import_config(
+    State(service): State<Arc<ConfigService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Json(snapshot): Json<BTreeMap<String, serde_json::Value>>,
+) -> ApiResult<Json<ApiResponse<Vec<ConfigResponse>>>> {
+    ensure_permission(&auth, Permission::ConfigWrite)?;
+
+    let imported = snapshot
+        .into_iter()
+        .map(|(key, value)| service.set(&key, value, auth.user_id()))
+        .collect();
+    Ok(Json(ApiResponse::new(imported)))
+}
+
+/// Stream configuration changes as they happen
+#[utoipa::path(
+    get,
+    path = "/api/v1/config/watch",
+    tag = "config",
+    responses(
+        (status = 200, description = "Server-Sent Events stream of config changes", body = ConfigChangeEvent, content_type = "text/event-stream"),
+        (status = 403, description = "Admin access required")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn watch_config(
+    State(service): State<Arc<ConfigService>>,
+    Extension(auth): Extension<AuthMethod>,
+) -> ApiResult<Sse<impl Stream<Item = Result<Event, Infallible>>>> {
+    if !has_permission(&auth, &Permission::AdminRead) {
+        return Err(ApiError::Authorization(
+            "Watching config changes requires admin access".into(),
+        ));
+    }
+
+    Ok(Sse::new(change_stream(service.subscribe())).keep_alive(KeepAlive::default()))
 }
 
-async fn update_config(State(_): State<Arc<ConfigService>>, Path(_): Path<String>, Json(_): Json<UpdateConfigRequest>) -> ApiResult<Json<ApiResponse<ConfigResponse>>> {
-    Err(ApiError::NotFound("Not implemented".into()))
+/// Turn a subscription into a stream of SSE config change events
+fn change_stream(
+    receiver: broadcast::Receiver<ConfigChangeEvent>,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(receiver, |mut receiver| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(change) => {
+                    let sse = Event::default()
+                        .id(change.id.to_string())
+                        .event("config.changed")
+                        .json_data(&change)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable event"));
+                    return Some((Ok(sse), receiver));
+                }
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Config watch subscriber lagged, changes dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
 }
 
-async fn batch_update_config(State(_): State<Arc<ConfigService>>, Json(_): Json<BatchUpdateConfigRequest>) -> ApiResult<Json<ApiResponse<Vec<ConfigResponse>>>> {
-    Ok(Json(ApiResponse::new(vec![])))
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::Claims;
+    use axum::{body::Body, http::{Request, StatusCode}};
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app(service: Arc<ConfigService>, role: &str) -> Router {
+        let claims = Claims::new("alice".to_string(), vec![role.to_string()], 3600);
+        config_routes(service).layer(Extension(AuthMethod::Bearer(claims)))
+    }
+
+    #[tokio::test]
+    async fn test_config_change_is_streamed_to_watchers() {
+        let service = Arc::new(ConfigService::new());
+        service.set("max_tokens", serde_json::json!(1000), "bootstrap".to_string());
+        let app = app(service, "admin");
+
+        let watch = app
+            .clone()
+            .oneshot(Request::get("/config/watch").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(watch.status(), StatusCode::OK);
+
+        let update = app
+            .oneshot(
+                Request::put("/config/max_tokens")
+                    .header("content-type", "application/json")
+                    .body(Body::from(r#"{"key":"max_tokens","value":2000}"#))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(update.status(), StatusCode::OK);
+
+        let mut body = watch.into_body();
+        let frame = body.frame().await.unwrap().unwrap().into_data().unwrap();
+        let text = String::from_utf8(frame.to_vec()).unwrap();
+        assert!(text.contains("event: config.changed"));
+
+        let data = text
+            .lines()
+            .find_map(|line| line.strip_prefix("data: "))
+            .unwrap();
+        let change: ConfigChangeEvent = serde_json::from_str(data).unwrap();
+        assert_eq!(change.key, "max_tokens");
+        assert_eq!(change.old_value, Some(serde_json::json!(1000)));
+        assert_eq!(change.new_value, serde_json::json!(2000));
+        assert_eq!(change.changed_by, "alice");
+        assert!(change.changed_at <= Utc::now());
+    }
+
+    #[tokio::test]
+    async fn test_watch_requires_admin() {
+        let response = app(Arc::new(ConfigService::new()), "user")
+            .oneshot(Request::get("/config/watch").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use flate2::{write::GzEncoder, Compression};
+        use std::io::Write;
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    fn gunzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Read;
+
+        let mut decoded = Vec::new();
+        flate2::read::GzDecoder::new(data).read_to_end(&mut decoded).unwrap();
+        decoded
+    }
+
+    #[tokio::test]
+    async fn test_gzip_export_round_trips_through_import() {
+        let source = Arc::new(ConfigService::new());
+        source.set("max_tokens", serde_json::json!(1000), "bootstrap".to_string());
+        source.set("model", serde_json::json!({"name": "haiku"}), "bootstrap".to_string());
+
+        let export = app(source.clone(), "admin")
+            .oneshot(
+                Request::get("/config/export")
+                    .header("accept-encoding", "gzip")
+                    .body(Body::empty())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(export.status(), StatusCode::OK);
+        assert_eq!(export.headers()["content-encoding"], "gzip");
+        let compressed = export.into_body().collect().await.unwrap().to_bytes();
+        let plain = gunzip(&compressed);
+
+        let import = |service: Arc<ConfigService>, body: Vec<u8>, gzipped: bool| async move {
+            let mut request = Request::post("/config/import").header("content-type", "application/json");
+            if gzipped {
+                request = request.header("content-encoding", "gzip");
+            }
+            let response = app(service.clone(), "admin")
+                .oneshot(request.body(Body::from(body)).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            service.snapshot()
+        };
+
+        let from_gzip = import(Arc::new(ConfigService::new()), compressed.to_vec(), true).await;
+        let from_plain = import(Arc::new(ConfigService::new()), plain, false).await;
+        assert_eq!(from_gzip, source.snapshot());
+        assert_eq!(from_gzip, from_plain);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_gzip_import_is_rejected() {
+        // A few kilobytes of gzip that inflate past the body limit
+        let mut body = b"{\"padding\": \"".to_vec();
+        body.resize(DEFAULT_MAX_BODY_BYTES + 1024, b'x');
+        body.extend_from_slice(b"\"}");
+        let compressed = gzip(&body);
+        assert!(compressed.len() < DEFAULT_MAX_BODY_BYTES / 100);
+
+        let service = Arc::new(ConfigService::new());
+        let response = app(service.clone(), "admin")
+            .oneshot(
+                Request::post("/config/import")
+                    .header("content-type", "application/json")
+                    .header("content-encoding", "gzip")
+                    .body(Body::from(compressed))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        assert!(service.snapshot().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_import_requires_config_write() {
+        let response = app(Arc::new(ConfigService::new()), "user")
+            .oneshot(
+                Request::post("/config/import")
+                    .header("content-type", "application/json")
+                    .header("content-encoding", "gzip")
+                    .body(Body::from(gzip(br#"{"max_tokens": 1}"#)))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
 }
@@ -0,0 +1,124 @@
+//! Event streaming routes
+
+use axum::{
+    extract::{Query, State},
+    response::sse::{Event, KeepAlive, Sse},
+    routing::get,
+    Router,
+};
+use futures::stream::{self, Stream};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio::sync::broadcast::{self, error::RecvError};
+
+use crate::models::events::{EventsQuery, OptimizerEvent};
+
+/// Number of events buffered per subscriber before slow subscribers lag
+const EVENT_BUFFER_SIZE: usize = 1024;
+
+/// Event service fanning out optimizer events to stream subscribers
+#[derive(Clone)]
+pub struct EventService {
+    sender: broadcast::Sender<OptimizerEvent>,
+}
+
+impl EventService {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUFFER_SIZE);
+        Self { sender }
+    }
+
+    /// Publish an event, returning the number of subscribers it reached
+    pub fn publish(&self, event: OptimizerEvent) -> usize {
+        self.sender.send(event).unwrap_or(0)
+    }
+
+    /// Subscribe to future events
+    pub fn subscribe(&self) -> broadcast::Receiver<OptimizerEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Event routes
+pub fn events_routes(service: Arc<EventService>) -> Router {
+    Router::new()
+        .route("/events", get(stream_events))
+        .with_state(service)
+}
+
+/// Stream optimizer events
+#[utoipa::path(
+    get,
+    path = "/api/v1/events",
+    tag = "events",
+    params(EventsQuery),
+    responses(
+        (status = 200, description = "Server-Sent Events stream of optimizer events", body = OptimizerEvent, content_type = "text/event-stream")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn stream_events(
+    State(service): State<Arc<EventService>>,
+    Query(query): Query<EventsQuery>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    Sse::new(event_stream(service.subscribe(), query)).keep_alive(KeepAlive::default())
+}
+
+/// Turn a subscription into a stream of SSE events matching `query`
+fn event_stream(
+    receiver: broadcast::Receiver<OptimizerEvent>,
+    query: EventsQuery,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold((receiver, query), |(mut receiver, query)| async move {
+        loop {
+            match receiver.recv().await {
+                Ok(event) if query.matches(&event) => {
+                    let sse = Event::default()
+                        .id(event.id.to_string())
+                        .event(event.event_type.clone())
+                        .json_data(&event)
+                        .unwrap_or_else(|_| Event::default().comment("unserializable event"));
+                    return Some((Ok(sse), (receiver, query)));
+                }
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    tracing::warn!(skipped, "Event stream subscriber lagged, events dropped");
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+
+    #[tokio::test]
+    async fn test_event_stream_filters_events() {
+        let service = EventService::new();
+        let query = EventsQuery {
+            event_type: None,
+            service: Some("chat".to_string()),
+        };
+        let stream = event_stream(service.subscribe(), query);
+        futures::pin_mut!(stream);
+
+        service.publish(OptimizerEvent::new("optimization.created", "other").with_service("search"));
+        service.publish(OptimizerEvent::new("optimization.created", "mine").with_service("chat"));
+        drop(service);
+
+        assert!(stream.next().await.is_some());
+        assert!(stream.next().await.is_none());
+    }
+}
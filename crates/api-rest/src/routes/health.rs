@@ -11,6 +11,7 @@ use std::sync::Arc;
 use std::time::SystemTime;
 
 use crate::error::ApiResult;
+use crate::middleware::maintenance::MaintenanceMode;
 use crate::models::health::{
     ComponentHealth, HealthResponse, HealthStatus, LivenessResponse, ReadinessResponse,
     ComponentReadiness,
@@ -21,6 +22,8 @@ use crate::models::health::{
 pub struct HealthState {
     pub start_time: SystemTime,
     pub version: String,
+    /// Maintenance flag; the service reports not-ready while it is on
+    pub maintenance: Arc<MaintenanceMode>,
 }
 
 impl HealthState {
@@ -28,9 +31,16 @@ impl HealthState {
         Self {
             start_time: SystemTime::now(),
             version,
+            maintenance: Arc::new(MaintenanceMode::new()),
         }
     }
 
+    /// Share the maintenance flag toggled through the admin API
+    pub fn with_maintenance(mut self, maintenance: Arc<MaintenanceMode>) -> Self {
+        self.maintenance = maintenance;
+        self
+    }
+
     pub fn uptime_seconds(&self) -> u64 {
         self.start_time
             .elapsed()
@@ -136,7 +146,18 @@ async fn liveness_check() -> Json<LivenessResponse> {
         (status = 503, description = "Service not ready")
     )
 )]
-async fn readiness_check() -> (StatusCode, Json<ReadinessResponse>) {
+async fn readiness_check(
+    State(state): State<Arc<HealthState>>,
+) -> (StatusCode, Json<ReadinessResponse>) {
+    if state.maintenance.is_enabled() {
+        let response = ReadinessResponse {
+            ready: false,
+            message: Some("Service is in maintenance mode".to_string()),
+            components: vec![],
+        };
+        return (StatusCode::SERVICE_UNAVAILABLE, Json(response));
+    }
+
     // Check if all critical components are ready
     let components = vec![
         ComponentReadiness {
@@ -182,6 +203,17 @@ mod tests {
         assert!(response.0.alive);
     }
 
+    #[tokio::test]
+    async fn test_readiness_during_maintenance() {
+        let state = Arc::new(HealthState::new("1.0.0".to_string()));
+        assert_eq!(readiness_check(State(state.clone())).await.0, StatusCode::OK);
+
+        state.maintenance.set(true, None);
+        let (status, response) = readiness_check(State(state)).await;
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+        assert!(!response.ready);
+    }
+
     #[tokio::test]
     async fn test_health_state() {
         let state = HealthState::new("1.0.0".to_string());
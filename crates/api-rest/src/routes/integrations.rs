@@ -1,13 +1,80 @@
 //! Integration routes
 
 use axum::{extract::{Path, State}, http::StatusCode, routing::{delete, get, post, put}, Json, Router};
+use dashmap::DashMap;
 use std::sync::Arc;
+use std::time::Duration;
 use uuid::Uuid;
+use llm_optimizer_integrations::url_guard::UrlValidator;
 use crate::error::{ApiError, ApiResult};
 use crate::models::{integrations::*, common::{ApiResponse, PaginatedResponse, Pagination}};
 
-#[derive(Clone)]
-pub struct IntegrationService;
+/// How long an integration test request may take
+const TEST_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Integration service
+///
+/// URLs in an integration's config are fetched by the server later, so they
+/// are checked against the [`UrlValidator`] before the config is accepted,
+/// and again each time they are fetched.
+#[derive(Clone, Default)]
+pub struct IntegrationService {
+    url_validator: UrlValidator,
+    /// Saved configs by integration ID
+    configs: Arc<DashMap<Uuid, serde_json::Value>>,
+}
+
+impl IntegrationService {
+    /// Create a service that accepts URLs to any public host
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Validate config URLs with `validator`, e.g. to restrict them to an
+    /// allowlist of hosts
+    pub fn with_url_validator(mut self, validator: UrlValidator) -> Self {
+        self.url_validator = validator;
+        self
+    }
+
+    /// Reject configs with a URL the server must not fetch
+    ///
+    /// Every string under a `url` or `endpoint` key, or a key ending in
+    /// `_url`, is checked, at any depth.
+    async fn validate_config_urls(&self, config: &serde_json::Value) -> ApiResult<()> {
+        let mut urls = Vec::new();
+        collect_urls(config, "config", &mut urls);
+        for (path, url) in urls {
+            self.url_validator
+                .validate(url)
+                .await
+                .map_err(|e| ApiError::Validation(format!("{}: {}", path, e)))?;
+        }
+        Ok(())
+    }
+}
+
+fn collect_urls<'a>(value: &'a serde_json::Value, path: &str, urls: &mut Vec<(String, &'a str)>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (key, value) in map {
+                let path = format!("{}.{}", path, key);
+                match value.as_str() {
+                    Some(url) if key == "url" || key == "endpoint" || key.ends_with("_url") => {
+                        urls.push((path, url));
+                    }
+                    _ => collect_urls(value, &path, urls),
+                }
+            }
+        }
+        serde_json::Value::Array(items) => {
+            for (i, item) in items.iter().enumerate() {
+                collect_urls(item, &format!("{}[{}]", path, i), urls);
+            }
+        }
+        _ => {}
+    }
+}
 
 pub fn integrations_routes(service: Arc<IntegrationService>) -> Router {
     Router::new()
@@ -20,9 +87,27 @@ pub fn integrations_routes(service: Arc<IntegrationService>) -> Router {
         .with_state(service)
 }
 
-async fn create_integration(State(_): State<Arc<IntegrationService>>, Json(req): Json<CreateIntegrationRequest>) -> ApiResult<(StatusCode, Json<ApiResponse<IntegrationResponse>>)> {
+/// Create integration
+#[utoipa::path(
+    post,
+    path = "/api/v1/integrations",
+    tag = "integrations",
+    request_body = CreateIntegrationRequest,
+    responses(
+        (status = 201, description = "Integration created", body = ApiResponseIntegration),
+        (status = 400, description = "Invalid config or a config URL the server must not fetch")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn create_integration(State(service): State<Arc<IntegrationService>>, Json(req): Json<CreateIntegrationRequest>) -> ApiResult<(StatusCode, Json<ApiResponse<IntegrationResponse>>)> {
+    service.validate_config_urls(&req.config).await?;
+    let id = Uuid::new_v4();
+    service.configs.insert(id, req.config);
     let response = IntegrationResponse {
-        id: Uuid::new_v4(),
+        id,
         name: req.name,
         integration_type: req.integration_type,
         enabled: req.enabled,
@@ -34,23 +119,135 @@ async fn create_integration(State(_): State<Arc<IntegrationService>>, Json(req):
     Ok((StatusCode::CREATED, Json(ApiResponse::new(response))))
 }
 
-async fn list_integrations(State(_): State<Arc<IntegrationService>>) -> ApiResult<Json<PaginatedResponse<IntegrationResponse>>> {
+/// List integrations
+#[utoipa::path(
+    get,
+    path = "/api/v1/integrations",
+    tag = "integrations",
+    responses(
+        (status = 200, description = "List of integrations", body = PaginatedIntegrations)
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn list_integrations(State(_service): State<Arc<IntegrationService>>) -> ApiResult<Json<PaginatedResponse<IntegrationResponse>>> {
     Ok(Json(PaginatedResponse::new(vec![], 0, &Pagination::default())))
 }
 
-async fn get_integration(State(_): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>) -> ApiResult<Json<ApiResponse<IntegrationResponse>>> {
+/// Get integration details
+#[utoipa::path(
+    get,
+    path = "/api/v1/integrations/{id}",
+    tag = "integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 200, description = "Integration details", body = ApiResponseIntegration),
+        (status = 404, description = "Integration not found")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_integration(State(_service): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>) -> ApiResult<Json<ApiResponse<IntegrationResponse>>> {
     Err(ApiError::NotFound("Integration not found".into()))
 }
 
-async fn update_integration(State(_): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>, Json(_): Json<UpdateIntegrationRequest>) -> ApiResult<Json<ApiResponse<IntegrationResponse>>> {
+/// Update integration
+#[utoipa::path(
+    put,
+    path = "/api/v1/integrations/{id}",
+    tag = "integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = UpdateIntegrationRequest,
+    responses(
+        (status = 200, description = "Integration updated", body = ApiResponseIntegration),
+        (status = 400, description = "Invalid config or a config URL the server must not fetch"),
+        (status = 404, description = "Integration not found")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn update_integration(State(service): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>, Json(req): Json<UpdateIntegrationRequest>) -> ApiResult<Json<ApiResponse<IntegrationResponse>>> {
+    if let Some(config) = &req.config {
+        service.validate_config_urls(config).await?;
+    }
     Err(ApiError::NotFound("Integration not found".into()))
 }
 
-async fn delete_integration(State(_): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>) -> ApiResult<StatusCode> {
+/// Delete integration
+#[utoipa::path(
+    delete,
+    path = "/api/v1/integrations/{id}",
+    tag = "integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    responses(
+        (status = 204, description = "Integration deleted")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn delete_integration(State(service): State<Arc<IntegrationService>>, Path(id): Path<Uuid>) -> ApiResult<StatusCode> {
+    service.configs.remove(&id);
     Ok(StatusCode::NO_CONTENT)
 }
 
-async fn test_integration(State(_): State<Arc<IntegrationService>>, Path(_id): Path<Uuid>, Json(_): Json<TestIntegrationRequest>) -> ApiResult<Json<ApiResponse<TestIntegrationResponse>>> {
+/// Send a test request through an integration
+#[utoipa::path(
+    post,
+    path = "/api/v1/integrations/{id}/test",
+    tag = "integrations",
+    params(("id" = Uuid, Path, description = "Integration ID")),
+    request_body = TestIntegrationRequest,
+    responses(
+        (status = 200, description = "Test result", body = ApiResponseTestIntegration),
+        (status = 400, description = "A config URL now resolves to an address the server must not fetch"),
+        (status = 404, description = "Integration not found")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn test_integration(State(service): State<Arc<IntegrationService>>, Path(id): Path<Uuid>, Json(request): Json<TestIntegrationRequest>) -> ApiResult<Json<ApiResponse<TestIntegrationResponse>>> {
+    let config = service
+        .configs
+        .get(&id)
+        .map(|config| config.clone())
+        .ok_or_else(|| ApiError::NotFound(format!("Integration {} not found", id)))?;
+
+    let mut urls = Vec::new();
+    collect_urls(&config, "config", &mut urls);
+    for (path, url) in urls {
+        // Resolve again: the host may point somewhere else than when it was saved
+        let (client, url) = service
+            .url_validator
+            .pinned_client(url)
+            .await
+            .map_err(|e| ApiError::Validation(format!("{}: {}", path, e)))?;
+        let sent = client
+            .post(url)
+            .timeout(TEST_REQUEST_TIMEOUT)
+            .json(&request.test_data)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status());
+        if let Err(e) = sent {
+            let response = TestIntegrationResponse {
+                success: false,
+                message: format!("{}: {}", path, e),
+                data: None,
+            };
+            return Ok(Json(ApiResponse::new(response)));
+        }
+    }
+
     let response = TestIntegrationResponse {
         success: true,
         message: "Integration test successful".to_string(),
@@ -58,3 +255,114 @@ async fn test_integration(State(_): State<Arc<IntegrationService>>, Path(_id): P
     };
     Ok(Json(ApiResponse::new(response)))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::{body::Body, http::Request};
+    use llm_optimizer_integrations::url_guard::HostResolver;
+    use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+    use tower::ServiceExt;
+
+    async fn create(config: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let app = integrations_routes(Arc::new(IntegrationService::new()));
+        let body = serde_json::json!({
+            "name": "alerts",
+            "integration_type": "webhook",
+            "config": config,
+        });
+        let response = app
+            .oneshot(
+                Request::post("/integrations")
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_config_urls_to_internal_hosts_are_rejected() {
+        for config in [
+            serde_json::json!({"webhook_url": "http://169.254.169.254/latest/meta-data/"}),
+            serde_json::json!({"base_url": "http://localhost:9200"}),
+            serde_json::json!({"targets": [{"url": "https://10.1.2.3/hook"}]}),
+        ] {
+            let (status, body) = create(config.clone()).await;
+            assert_eq!(status, StatusCode::BAD_REQUEST, "{}", config);
+            assert_eq!(body["error"], "validation_error");
+        }
+    }
+
+    #[derive(Default)]
+    struct StaticResolver(std::sync::Mutex<Vec<IpAddr>>);
+
+    #[async_trait]
+    impl HostResolver for StaticResolver {
+        async fn resolve(&self, _host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+            let ips = self.0.lock().unwrap();
+            Ok(ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_rejects_host_rebound_after_save() {
+        let resolver = Arc::new(StaticResolver::default());
+        *resolver.0.lock().unwrap() = vec![IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))];
+        let service = IntegrationService::new()
+            .with_url_validator(UrlValidator::new().with_resolver(resolver.clone()));
+        let app = integrations_routes(Arc::new(service));
+
+        let send = |uri: String, body: serde_json::Value| {
+            let app = app.clone();
+            async move {
+                let response = app
+                    .oneshot(
+                        Request::post(uri)
+                            .header("content-type", "application/json")
+                            .body(Body::from(body.to_string()))
+                            .unwrap(),
+                    )
+                    .await
+                    .unwrap();
+                let status = response.status();
+                let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+                (status, serde_json::from_slice::<serde_json::Value>(&body).unwrap())
+            }
+        };
+
+        let (status, body) = send(
+            "/integrations".to_string(),
+            serde_json::json!({
+                "name": "alerts",
+                "integration_type": "webhook",
+                "config": {"webhook_url": "https://hooks.example.test/hook"},
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        let id = body["data"]["id"].as_str().unwrap().to_string();
+
+        // The saved host now resolves to the metadata endpoint
+        *resolver.0.lock().unwrap() = vec![IpAddr::V4(Ipv4Addr::new(169, 254, 169, 254))];
+        let (status, body) = send(format!("/integrations/{}/test", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["error"], "validation_error");
+        assert!(body["message"].as_str().unwrap().contains("169.254.169.254"), "{}", body);
+    }
+
+    #[tokio::test]
+    async fn test_config_urls_to_public_hosts_are_accepted() {
+        let (status, _) = create(serde_json::json!({
+            "webhook_url": "https://93.184.216.34/hook",
+            "channel": "#alerts",
+        }))
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+}
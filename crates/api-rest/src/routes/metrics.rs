@@ -17,7 +17,22 @@ pub fn metrics_routes(service: Arc<MetricsService>) -> Router {
         .with_state(service)
 }
 
-async fn query_metrics(State(_): State<Arc<MetricsService>>, Json(_req): Json<QueryMetricsRequest>) -> ApiResult<Json<ApiResponse<MetricsResponse>>> {
+/// Query a metric's data points
+#[utoipa::path(
+    post,
+    path = "/api/v1/metrics/query",
+    tag = "metrics",
+    request_body = QueryMetricsRequest,
+    responses(
+        (status = 200, description = "Metric data points", body = ApiResponseMetrics),
+        (status = 400, description = "Invalid query")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn query_metrics(State(_service): State<Arc<MetricsService>>, Json(_req): Json<QueryMetricsRequest>) -> ApiResult<Json<ApiResponse<MetricsResponse>>> {
     let response = MetricsResponse {
         metric_name: _req.metric_name,
         data_points: vec![],
@@ -26,7 +41,20 @@ async fn query_metrics(State(_): State<Arc<MetricsService>>, Json(_req): Json<Qu
     Ok(Json(ApiResponse::new(response)))
 }
 
-async fn get_performance_metrics(State(_): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<PerformanceMetricsResponse>>> {
+/// Get latency, error rate and throughput over the last 24 hours
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/performance",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Performance metrics", body = ApiResponsePerformanceMetrics)
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_performance_metrics(State(_service): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<PerformanceMetricsResponse>>> {
     let response = PerformanceMetricsResponse {
         avg_latency_ms: 150.0,
         p50_latency_ms: 120.0,
@@ -41,7 +69,20 @@ async fn get_performance_metrics(State(_): State<Arc<MetricsService>>) -> ApiRes
     Ok(Json(ApiResponse::new(response)))
 }
 
-async fn get_cost_metrics(State(_): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<CostMetricsResponse>>> {
+/// Get cost and token usage over the last 24 hours
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/cost",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Cost metrics", body = ApiResponseCostMetrics)
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_cost_metrics(State(_service): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<CostMetricsResponse>>> {
     let response = CostMetricsResponse {
         total_cost: 1234.56,
         avg_cost_per_request: 0.012,
@@ -54,7 +95,20 @@ async fn get_cost_metrics(State(_): State<Arc<MetricsService>>) -> ApiResult<Jso
     Ok(Json(ApiResponse::new(response)))
 }
 
-async fn get_quality_metrics(State(_): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<QualityMetricsResponse>>> {
+/// Get response quality scores over the last 24 hours
+#[utoipa::path(
+    get,
+    path = "/api/v1/metrics/quality",
+    tag = "metrics",
+    responses(
+        (status = 200, description = "Quality metrics", body = ApiResponseQualityMetrics)
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_quality_metrics(State(_service): State<Arc<MetricsService>>) -> ApiResult<Json<ApiResponse<QualityMetricsResponse>>> {
     let response = QualityMetricsResponse {
         overall_score: 0.92,
         accuracy: 0.95,
@@ -6,6 +6,8 @@ pub mod metrics;
 pub mod integrations;
 pub mod health;
 pub mod admin;
+pub mod events;
+pub mod auth;
 
 pub use optimize::optimize_routes;
 pub use config::config_routes;
@@ -13,3 +15,5 @@ pub use metrics::metrics_routes;
 pub use integrations::integrations_routes;
 pub use health::health_routes;
 pub use admin::admin_routes;
+pub use events::events_routes;
+pub use auth::auth_routes;
@@ -2,37 +2,590 @@
 
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
-    routing::{get, post},
-    Json, Router,
+    http::{HeaderMap, HeaderName, HeaderValue, StatusCode},
+    middleware,
+    routing::{delete, get, post},
+    Extension, Json, Router,
 };
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use llm_optimizer_integrations::anthropic::{AnthropicClient, ClaudeModel, CostTracker};
+use llm_optimizer_types::decisions::DecisionStatus;
+use prometheus_client::metrics::{counter::Counter, gauge::Gauge};
+use prometheus_client::registry::Registry;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
 use uuid::Uuid;
 
+use super::events::EventService;
 use crate::error::{ApiError, ApiResult};
 use crate::middleware::auth::AuthMethod;
+use crate::middleware::idempotency::{
+    idempotency_middleware, IdempotencyStore, InMemoryIdempotencyStore,
+};
+use crate::middleware::rbac::{ensure_permission, has_permission, Permission};
 use crate::models::{
-    optimize::*, common::{ApiResponse, PaginatedResponse, Pagination},
+    admin::ArchiveDecisionsResponse,
+    events::OptimizerEvent,
+    optimize::*,
+    common::{
+        ApiResponse, BulkItemResult, BulkResponse, PaginatedResponse, Pagination,
+        DEFAULT_MAX_PAGE_SIZE,
+    },
 };
 
-/// Optimization service (placeholder for actual implementation)
+/// Response header carrying the LLM cost of a request, in USD
+pub const COST_USD_HEADER: HeaderName = HeaderName::from_static("x-cost-usd");
+
+/// Response header carrying the number of LLM tokens a request used
+pub const TOKENS_USED_HEADER: HeaderName = HeaderName::from_static("x-tokens-used");
+
+/// Maximum tokens for a generated rationale
+const RATIONALE_MAX_TOKENS: u32 = 300;
+
+/// Default time between runs of the retention task
+pub const DEFAULT_RETENTION_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// What happens to decisions that fall outside the retention policy
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RetentionAction {
+    /// Move them to the archive, out of the active set
+    #[default]
+    Archive,
+    /// Delete them permanently
+    Delete,
+}
+
+/// How long optimization decisions are kept in the active set
+///
+/// A decision is retired once it is older than `max_age_days` or no longer
+/// among the newest `keep_last`. Deployed decisions are never retired, though
+/// they still count towards `keep_last`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetentionPolicy {
+    /// Retire decisions created more than this many days ago
+    pub max_age_days: Option<u32>,
+    /// Retire all but this many of the newest decisions
+    pub keep_last: Option<usize>,
+    /// Archive or delete retired decisions
+    pub action: RetentionAction,
+    /// Time between runs of the background retention task
+    pub interval: Duration,
+}
+
+impl Default for RetentionPolicy {
+    fn default() -> Self {
+        Self {
+            max_age_days: None,
+            keep_last: None,
+            action: RetentionAction::Archive,
+            interval: DEFAULT_RETENTION_INTERVAL,
+        }
+    }
+}
+
+impl RetentionPolicy {
+    /// Create a policy that keeps every decision
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retire decisions created more than `days` days ago
+    pub fn with_max_age_days(mut self, days: u32) -> Self {
+        self.max_age_days = Some(days);
+        self
+    }
+
+    /// Retire all but the newest `count` decisions
+    pub fn with_keep_last(mut self, count: usize) -> Self {
+        self.keep_last = Some(count);
+        self
+    }
+
+    /// Archive or delete retired decisions
+    pub fn with_action(mut self, action: RetentionAction) -> Self {
+        self.action = action;
+        self
+    }
+
+    /// Set the time between runs of the background retention task
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Whether the policy retires anything at all
+    pub fn is_bounded(&self) -> bool {
+        self.max_age_days.is_some() || self.keep_last.is_some()
+    }
+}
+
+/// Metrics of the decision retention policy
+///
+/// Clones share the same values, so the copy registered with a metrics
+/// registry reports what the service records.
+#[derive(Debug, Clone, Default)]
+pub struct RetentionMetrics {
+    /// Decisions moved to the archive
+    pub archived: Counter,
+    /// Decisions deleted permanently
+    pub deleted: Counter,
+    /// Decisions left in the active set after the last run
+    pub retained: Gauge,
+}
+
+impl RetentionMetrics {
+    /// Create new retention metrics
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Register the metrics with a registry
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "decisions_archived",
+            "Optimization decisions archived by the retention policy",
+            self.archived.clone(),
+        );
+
+        registry.register(
+            "decisions_deleted",
+            "Optimization decisions deleted by the retention policy",
+            self.deleted.clone(),
+        );
+
+        registry.register(
+            "decisions_retained",
+            "Optimization decisions in the active set after the last retention run",
+            self.retained.clone(),
+        );
+    }
+}
+
+/// Optimization service keeping created optimizations in memory
 #[derive(Clone)]
-pub struct OptimizationService;
+pub struct OptimizationService {
+    optimizations: DashMap<Uuid, OptimizationResponse>,
+    /// Cold storage for decisions retired by the retention policy
+    archived: DashMap<Uuid, OptimizationResponse>,
+    require_approval: bool,
+    rationale_llm: Option<(Arc<AnthropicClient>, ClaudeModel)>,
+    max_page_size: u32,
+    /// Responses replayed for repeated `Idempotency-Key`s on create
+    idempotency: Arc<dyn IdempotencyStore>,
+    /// Stream that state changes are published to
+    events: Option<Arc<EventService>>,
+    /// Counts of decisions retired by the retention policy
+    retention_metrics: RetentionMetrics,
+}
+
+impl Default for OptimizationService {
+    fn default() -> Self {
+        Self {
+            optimizations: DashMap::new(),
+            archived: DashMap::new(),
+            require_approval: false,
+            rationale_llm: None,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            idempotency: Arc::new(InMemoryIdempotencyStore::default()),
+            events: None,
+            retention_metrics: RetentionMetrics::new(),
+        }
+    }
+}
 
 impl OptimizationService {
     pub fn new() -> Self {
-        Self
+        Self::default()
+    }
+
+    /// Cap the page size of list requests; larger requests are clamped
+    pub fn with_max_page_size(mut self, max_page_size: u32) -> Self {
+        self.max_page_size = max_page_size;
+        self
+    }
+
+    /// Require an admin to approve new optimizations before they can be deployed
+    pub fn with_approval_required(mut self, require_approval: bool) -> Self {
+        self.require_approval = require_approval;
+        self
+    }
+
+    /// Have Claude write the rationale for new optimizations
+    pub fn with_rationale_llm(mut self, client: Arc<AnthropicClient>, model: ClaudeModel) -> Self {
+        self.rationale_llm = Some((client, model));
+        self
+    }
+
+    /// Set where `Idempotency-Key`s for create requests are stored
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency = store;
+        self
+    }
+
+    /// Publish state changes to the event stream
+    pub fn with_events(mut self, events: Arc<EventService>) -> Self {
+        self.events = Some(events);
+        self
+    }
+
+    /// Record retention runs in `metrics`
+    pub fn with_retention_metrics(mut self, metrics: RetentionMetrics) -> Self {
+        self.retention_metrics = metrics;
+        self
+    }
+
+    /// Publish `event_type` for an optimization, tagged with its first
+    /// target service
+    fn publish(&self, event_type: &str, optimization: &OptimizationResponse, message: String) {
+        let Some(events) = &self.events else {
+            return;
+        };
+        let mut event =
+            OptimizerEvent::new(event_type, message).with_optimization(optimization.id);
+        if let Some(service) = optimization.target_services.first() {
+            event = event.with_service(service.clone());
+        }
+        events.publish(event);
+    }
+
+    /// Ask the LLM to explain an optimization
+    ///
+    /// Returns the rationale with a cost tracker holding only this call's usage,
+    /// or `None` if no LLM is configured or the call failed.
+    async fn llm_rationale(
+        &self,
+        optimization: &OptimizationResponse,
+    ) -> Option<(String, CostTracker)> {
+        let (client, model) = self.rationale_llm.as_ref()?;
+        let prompt = format!(
+            "In two or three sentences, explain why applying the {:?} optimization strategy \
+             to the services {:?} with configuration {} is expected to change cost by {:.1}%, \
+             quality by {:.1}% and latency by {:.1}%.",
+            optimization.strategy,
+            optimization.target_services,
+            optimization.config,
+            -optimization.expected_impact.cost_reduction_pct,
+            optimization.expected_impact.quality_delta_pct,
+            optimization.expected_impact.latency_delta_pct,
+        );
+
+        match client
+            .complete_with_usage(*model, &prompt, RATIONALE_MAX_TOKENS)
+            .await
+        {
+            Ok((rationale, usage)) => {
+                let mut cost = CostTracker::with_pricing(client.get_cost_stats().await.pricing);
+                cost.record_usage(&usage, *model);
+                Some((rationale, cost))
+            }
+            Err(e) => {
+                tracing::warn!("Failed to generate optimization rationale: {}", e);
+                None
+            }
+        }
+    }
+
+    fn get(&self, id: Uuid) -> ApiResult<OptimizationResponse> {
+        self.optimizations
+            .get(&id)
+            .map(|entry| entry.clone())
+            .ok_or_else(|| ApiError::NotFound(format!("Optimization not found: {}", id)))
+    }
+
+    /// Apply `change` to an optimization, returning the updated optimization
+    fn update(
+        &self,
+        id: Uuid,
+        change: impl FnOnce(&mut OptimizationResponse) -> ApiResult<()>,
+    ) -> ApiResult<OptimizationResponse> {
+        let mut entry = self
+            .optimizations
+            .get_mut(&id)
+            .ok_or_else(|| ApiError::NotFound(format!("Optimization not found: {}", id)))?;
+        change(&mut entry)?;
+        Ok(entry.clone())
+    }
+
+    /// Deploy an optimization, optionally replacing its configuration
+    fn deploy(
+        &self,
+        id: Uuid,
+        rollout_percentage: f64,
+        config: Option<serde_json::Value>,
+    ) -> ApiResult<OptimizationResponse> {
+        let optimization = self.update(id, |optimization| match optimization.status {
+            DecisionStatus::AwaitingApproval => Err(ApiError::FailedPrecondition(format!(
+                "Optimization {} is awaiting approval",
+                id
+            ))),
+            DecisionStatus::Rejected => Err(ApiError::FailedPrecondition(format!(
+                "Optimization {} was rejected",
+                id
+            ))),
+            _ => {
+                if let Some(config) = config {
+                    optimization.config = config;
+                }
+                record_revision(optimization, rollout_percentage);
+                optimization.status = DecisionStatus::Deployed;
+                optimization.deployed_at = Some(chrono::Utc::now());
+                Ok(())
+            }
+        })?;
+
+        self.publish(
+            "optimization.deployed",
+            &optimization,
+            format!(
+                "Optimization {} deployed at revision {} to {}% of traffic",
+                id, optimization.revision, rollout_percentage
+            ),
+        );
+        Ok(optimization)
+    }
+
+    /// An archived decision, if it was retired to cold storage
+    pub fn archived(&self, id: Uuid) -> Option<OptimizationResponse> {
+        self.archived.get(&id).map(|entry| entry.clone())
+    }
+
+    /// Retire the decisions that fall outside `policy` as of `now`
+    pub fn apply_retention(
+        &self,
+        policy: &RetentionPolicy,
+        now: DateTime<Utc>,
+    ) -> ArchiveDecisionsResponse {
+        let cutoff = policy
+            .max_age_days
+            .map(|days| now - chrono::Duration::days(i64::from(days)));
+
+        let mut decisions: Vec<(Uuid, DateTime<Utc>)> = self
+            .optimizations
+            .iter()
+            .map(|entry| (entry.id, entry.created_at))
+            .collect();
+        decisions.sort_by_key(|&(_, created_at)| std::cmp::Reverse(created_at));
+
+        let (mut archived, mut deleted) = (0, 0);
+        for (index, (id, created_at)) in decisions.into_iter().enumerate() {
+            let expired = cutoff.is_some_and(|cutoff| created_at < cutoff)
+                || policy.keep_last.is_some_and(|keep| index >= keep);
+            if !expired {
+                continue;
+            }
+
+            let retired = self
+                .optimizations
+                .remove_if(&id, |_, optimization| optimization.status != DecisionStatus::Deployed);
+            if let Some((id, optimization)) = retired {
+                match policy.action {
+                    RetentionAction::Archive => {
+                        self.archived.insert(id, optimization);
+                        archived += 1;
+                    }
+                    RetentionAction::Delete => deleted += 1,
+                }
+            }
+        }
+
+        let report = ArchiveDecisionsResponse {
+            archived,
+            deleted,
+            retained: self.optimizations.len() as u64,
+            archived_total: self.archived.len() as u64,
+        };
+        self.retention_metrics.archived.inc_by(report.archived);
+        self.retention_metrics.deleted.inc_by(report.deleted);
+        self.retention_metrics.retained.set(report.retained as i64);
+        if archived > 0 || deleted > 0 {
+            tracing::info!(
+                decisions_archived = report.archived,
+                decisions_deleted = report.deleted,
+                decisions_retained = report.retained,
+                decisions_archived_total = report.archived_total,
+                "Applied decision retention policy"
+            );
+        }
+        report
+    }
+
+    /// Delete an optimization that is not currently deployed
+    fn delete(&self, id: Uuid) -> ApiResult<()> {
+        self.optimizations
+            .remove_if(&id, |_, optimization| optimization.status != DecisionStatus::Deployed)
+            .map(|(_, optimization)| {
+                let message = format!("Optimization {} deleted", id);
+                self.publish("optimization.deleted", &optimization, message);
+            })
+            .ok_or_else(|| {
+                if self.optimizations.contains_key(&id) {
+                    ApiError::Conflict(format!(
+                        "Optimization {} is deployed; roll it back before deleting it",
+                        id
+                    ))
+                } else {
+                    ApiError::NotFound(format!("Optimization not found: {}", id))
+                }
+            })
+    }
+}
+
+/// Apply `policy` to the service's decisions every `policy.interval`
+///
+/// The task runs until the returned handle is aborted or the runtime shuts
+/// down.
+pub fn spawn_retention_task(
+    service: Arc<OptimizationService>,
+    policy: RetentionPolicy,
+) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(policy.interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        loop {
+            ticker.tick().await;
+            service.apply_retention(&policy, Utc::now());
+        }
+    })
+}
+
+/// Rank strategies by the measured impact of their optimizations
+///
+/// Only optimizations with an actual impact are counted. Strategies are ordered
+/// by average cost reduction, then by success rate.
+fn strategy_leaderboard<'a>(
+    optimizations: impl IntoIterator<Item = &'a OptimizationResponse>,
+) -> Vec<StrategyLeaderboardEntry> {
+    let mut entries: Vec<StrategyLeaderboardEntry> = Vec::new();
+
+    for optimization in optimizations {
+        let Some(impact) = &optimization.actual_impact else {
+            continue;
+        };
+        let succeeded = !matches!(
+            optimization.status,
+            DecisionStatus::RolledBack | DecisionStatus::DeploymentFailed
+        );
+
+        let index = match entries.iter().position(|e| e.strategy == optimization.strategy) {
+            Some(index) => index,
+            None => {
+                entries.push(StrategyLeaderboardEntry {
+                    rank: 0,
+                    strategy: optimization.strategy,
+                    optimizations: 0,
+                    avg_cost_reduction_pct: 0.0,
+                    avg_quality_delta_pct: 0.0,
+                    avg_latency_delta_pct: 0.0,
+                    success_rate: 0.0,
+                });
+                entries.len() - 1
+            }
+        };
+
+        // Accumulate sums here and turn them into averages below
+        let entry = &mut entries[index];
+        entry.optimizations += 1;
+        entry.avg_cost_reduction_pct += impact.cost_reduction_pct;
+        entry.avg_quality_delta_pct += impact.quality_delta_pct;
+        entry.avg_latency_delta_pct += impact.latency_delta_pct;
+        if succeeded {
+            entry.success_rate += 1.0;
+        }
+    }
+
+    for entry in &mut entries {
+        let count = entry.optimizations as f64;
+        entry.avg_cost_reduction_pct /= count;
+        entry.avg_quality_delta_pct /= count;
+        entry.avg_latency_delta_pct /= count;
+        entry.success_rate /= count;
+    }
+
+    entries.sort_by(|a, b| {
+        b.avg_cost_reduction_pct
+            .total_cmp(&a.avg_cost_reduction_pct)
+            .then(b.success_rate.total_cmp(&a.success_rate))
+    });
+    for (rank, entry) in entries.iter_mut().enumerate() {
+        entry.rank = rank as u32 + 1;
+    }
+
+    entries
+}
+
+/// Whether an optimization passes every filter in a list query
+fn matches_query(
+    optimization: &OptimizationResponse,
+    query: &ListOptimizationsQuery,
+    tags: &HashMap<String, String>,
+) -> bool {
+    query.status.map_or(true, |status| optimization.status == status)
+        && query.strategy.map_or(true, |strategy| optimization.strategy == strategy)
+        && query
+            .service
+            .as_ref()
+            .map_or(true, |service| optimization.target_services.contains(service))
+        && query.from.map_or(true, |from| optimization.created_at >= from)
+        && query.to.map_or(true, |to| optimization.created_at <= to)
+        && tags
+            .iter()
+            .all(|(key, value)| optimization.tags.get(key) == Some(value))
+}
+
+/// Headers reporting the LLM usage recorded while handling a request
+fn cost_headers(cost: &CostTracker) -> HeaderMap {
+    let tokens = cost.total_input_tokens
+        + cost.total_cache_creation_tokens
+        + cost.total_cache_read_tokens
+        + cost.total_output_tokens;
+
+    let mut headers = HeaderMap::new();
+    if let Ok(value) = HeaderValue::from_str(&format!("{:.6}", cost.total_cost)) {
+        headers.insert(COST_USD_HEADER, value);
     }
+    headers.insert(TOKENS_USED_HEADER, HeaderValue::from(tokens));
+    headers
+}
+
+/// Record the optimization's current config as a new revision and make it current
+fn record_revision(optimization: &mut OptimizationResponse, rollout_percentage: f64) {
+    let revision = optimization.revisions.len() as u32 + 1;
+    optimization.revisions.push(OptimizationRevision {
+        revision,
+        config: optimization.config.clone(),
+        rollout_percentage,
+        created_at: chrono::Utc::now(),
+    });
+    optimization.revision = revision;
 }
 
 /// Optimization routes
 pub fn optimize_routes(service: Arc<OptimizationService>) -> Router {
+    // Retried creates with the same Idempotency-Key get the first response
+    let create = post(create_optimization).layer(middleware::from_fn_with_state(
+        service.idempotency.clone(),
+        idempotency_middleware,
+    ));
+
     Router::new()
-        .route("/optimize", post(create_optimization))
+        .route("/optimize", create)
         .route("/optimize", get(list_optimizations))
+        .route("/optimize/leaderboard", get(get_strategy_leaderboard))
+        .route("/optimize/bulk/deploy", post(bulk_deploy_optimizations))
+        .route("/optimize/bulk/delete", post(bulk_delete_optimizations))
         .route("/optimize/:id", get(get_optimization))
         .route("/optimize/:id/deploy", post(deploy_optimization))
         .route("/optimize/:id/rollback", post(rollback_optimization))
+        .route("/optimize/:id/approve", post(approve_optimization))
+        .route("/optimize/:id/reject", post(reject_optimization))
+        .route("/optimize/:id/tags", post(add_optimization_tags))
+        .route("/optimize/:id/tags/:key", delete(remove_optimization_tag))
         .with_state(service)
 }
 
@@ -41,11 +594,25 @@ pub fn optimize_routes(service: Arc<OptimizationService>) -> Router {
     post,
     path = "/api/v1/optimize",
     tag = "optimize",
+    params(
+        ("Idempotency-Key" = Option<String>, Header,
+            description = "Replay the first response for retries with the same key")
+    ),
     request_body = CreateOptimizationRequest,
     responses(
-        (status = 201, description = "Optimization created", body = OptimizationResponse),
-        (status = 400, description = "Bad request"),
-        (status = 401, description = "Unauthorized")
+        (status = 201, description = "Optimization created", body = ApiResponseOptimization,
+            headers(
+                ("x-cost-usd" = String, description = "LLM cost of generating the rationale, in USD"),
+                ("x-tokens-used" = u64, description = "LLM tokens used generating the rationale")
+            )
+        ),
+        (status = 400, description = "Bad request", body = ErrorResponse),
+        (status = 401, description = "Unauthorized", body = ErrorResponse),
+        (status = 403, description = "Missing required permission", body = ErrorResponse),
+        (status = 409, description = "A request with the same Idempotency-Key is in progress",
+            body = ErrorResponse),
+        (status = 422, description = "Idempotency-Key reused with a different body",
+            body = ErrorResponse)
     ),
     security(
         ("bearer_auth" = []),
@@ -53,22 +620,32 @@ pub fn optimize_routes(service: Arc<OptimizationService>) -> Router {
     )
 )]
 async fn create_optimization(
-    State(_service): State<Arc<OptimizationService>>,
-    _auth: axum::Extension<AuthMethod>,
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
     Json(req): Json<CreateOptimizationRequest>,
-) -> ApiResult<(StatusCode, Json<ApiResponse<OptimizationResponse>>)> {
+) -> ApiResult<(StatusCode, HeaderMap, Json<ApiResponse<OptimizationResponse>>)> {
+    ensure_permission(&auth, Permission::OptimizeWrite)?;
+
     // Validate request
     use validator::Validate;
     req.validate()
         .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
 
-    // Placeholder response
-    let response = OptimizationResponse {
+    let status = if service.require_approval {
+        DecisionStatus::AwaitingApproval
+    } else {
+        DecisionStatus::Pending
+    };
+
+    let mut response = OptimizationResponse {
         id: Uuid::new_v4(),
         target_services: req.target_services,
         strategy: req.strategy,
-        status: llm_optimizer_types::decisions::DecisionStatus::Pending,
+        status,
         changes: vec![],
+        config: req.config,
+        revision: 0,
+        revisions: vec![],
         expected_impact: ExpectedImpactResponse {
             cost_reduction_pct: 20.0,
             quality_delta_pct: -2.0,
@@ -79,9 +656,25 @@ async fn create_optimization(
         rationale: "Optimization created successfully".to_string(),
         created_at: chrono::Utc::now(),
         deployed_at: None,
+        tags: req.tags,
     };
 
-    Ok((StatusCode::CREATED, Json(ApiResponse::new(response))))
+    // Cost headers are only sent when the request called the LLM
+    let mut headers = HeaderMap::new();
+    if let Some((rationale, cost)) = service.llm_rationale(&response).await {
+        response.rationale = rationale;
+        headers = cost_headers(&cost);
+    }
+
+    record_revision(&mut response, 0.0);
+    service.optimizations.insert(response.id, response.clone());
+    service.publish(
+        "optimization.created",
+        &response,
+        format!("Optimization {} created with status {:?}", response.id, response.status),
+    );
+
+    Ok((StatusCode::CREATED, headers, Json(ApiResponse::new(response))))
 }
 
 /// List optimizations
@@ -94,7 +687,7 @@ async fn create_optimization(
         Pagination
     ),
     responses(
-        (status = 200, description = "List of optimizations", body = PaginatedResponse<OptimizationResponse>)
+        (status = 200, description = "List of optimizations", body = PaginatedOptimizations)
     ),
     security(
         ("bearer_auth" = []),
@@ -102,14 +695,71 @@ async fn create_optimization(
     )
 )]
 async fn list_optimizations(
-    State(_service): State<Arc<OptimizationService>>,
-    Query(pagination): Query<Pagination>,
-    Query(_query): Query<ListOptimizationsQuery>,
+    State(service): State<Arc<OptimizationService>>,
+    Query(mut pagination): Query<Pagination>,
+    Query(query): Query<ListOptimizationsQuery>,
 ) -> ApiResult<Json<PaginatedResponse<OptimizationResponse>>> {
-    let items = vec![]; // Placeholder
-    let total = 0;
+    if pagination.page == 0 || pagination.page_size == 0 {
+        return Err(ApiError::Validation(
+            "page and page_size must be at least 1".to_string(),
+        ));
+    }
+    let requested_page_size = pagination.clamp_page_size(service.max_page_size);
+    if let Some(requested) = requested_page_size {
+        tracing::info!(
+            requested,
+            max_page_size = service.max_page_size,
+            "Clamped list page size"
+        );
+    }
+    let tags = match &query.tag {
+        Some(filter) => parse_tag_filter(filter).map_err(ApiError::Validation)?,
+        None => HashMap::new(),
+    };
 
-    Ok(Json(PaginatedResponse::new(items, total, &pagination)))
+    let mut matching: Vec<OptimizationResponse> = service
+        .optimizations
+        .iter()
+        .filter(|entry| matches_query(entry.value(), &query, &tags))
+        .map(|entry| entry.value().clone())
+        .collect();
+    matching.sort_by_key(|optimization| std::cmp::Reverse(optimization.created_at));
+
+    let total = matching.len() as u64;
+    let items = matching
+        .into_iter()
+        .skip(pagination.offset() as usize)
+        .take(pagination.limit() as usize)
+        .collect();
+
+    Ok(Json(
+        PaginatedResponse::new(items, total, &pagination)
+            .with_requested_page_size(requested_page_size),
+    ))
+}
+
+/// Rank optimization strategies by their measured impact
+#[utoipa::path(
+    get,
+    path = "/api/v1/optimize/leaderboard",
+    tag = "optimize",
+    responses(
+        (status = 200, description = "Strategies ranked by average cost reduction", body = ApiResponseLeaderboard)
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn get_strategy_leaderboard(
+    State(service): State<Arc<OptimizationService>>,
+) -> ApiResult<Json<ApiResponse<Vec<StrategyLeaderboardEntry>>>> {
+    let optimizations: Vec<OptimizationResponse> = service
+        .optimizations
+        .iter()
+        .map(|entry| entry.value().clone())
+        .collect();
+    Ok(Json(ApiResponse::new(strategy_leaderboard(&optimizations))))
 }
 
 /// Get optimization by ID
@@ -121,7 +771,7 @@ async fn list_optimizations(
         ("id" = Uuid, Path, description = "Optimization ID")
     ),
     responses(
-        (status = 200, description = "Optimization details", body = OptimizationResponse),
+        (status = 200, description = "Optimization details", body = ApiResponseOptimization),
         (status = 404, description = "Optimization not found")
     ),
     security(
@@ -130,10 +780,10 @@ async fn list_optimizations(
     )
 )]
 async fn get_optimization(
-    State(_service): State<Arc<OptimizationService>>,
-    Path(_id): Path<Uuid>,
+    State(service): State<Arc<OptimizationService>>,
+    Path(id): Path<Uuid>,
 ) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
-    Err(ApiError::NotFound("Optimization not found".into()))
+    Ok(Json(ApiResponse::new(service.get(id)?)))
 }
 
 /// Deploy optimization
@@ -146,8 +796,10 @@ async fn get_optimization(
     ),
     request_body = DeployOptimizationRequest,
     responses(
-        (status = 200, description = "Optimization deployed", body = OptimizationResponse),
-        (status = 404, description = "Optimization not found")
+        (status = 200, description = "Optimization deployed", body = ApiResponseOptimization),
+        (status = 404, description = "Optimization not found"),
+        (status = 409, description = "Optimization has not been approved"),
+        (status = 403, description = "Missing required permission")
     ),
     security(
         ("bearer_auth" = []),
@@ -155,11 +807,97 @@ async fn get_optimization(
     )
 )]
 async fn deploy_optimization(
-    State(_service): State<Arc<OptimizationService>>,
-    Path(_id): Path<Uuid>,
-    Json(_req): Json<DeployOptimizationRequest>,
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<DeployOptimizationRequest>,
 ) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
-    Err(ApiError::NotFound("Optimization not found".into()))
+    ensure_permission(&auth, Permission::OptimizeExecute)?;
+
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let rollout_percentage = if req.gradual { req.rollout_percentage } else { 100.0 };
+    let optimization = service.deploy(id, rollout_percentage, req.config)?;
+
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Deploy several optimizations
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize/bulk/deploy",
+    tag = "optimize",
+    request_body = BulkDeployRequest,
+    responses(
+        (status = 207, description = "Per-optimization deployment outcomes", body = BulkResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn bulk_deploy_optimizations(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Json(req): Json<BulkDeployRequest>,
+) -> ApiResult<BulkResponse> {
+    ensure_permission(&auth, Permission::OptimizeExecute)?;
+
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let rollout_percentage = if req.gradual { req.rollout_percentage } else { 100.0 };
+    let items = req
+        .ids
+        .iter()
+        .map(|&id| {
+            let result = service.deploy(id, rollout_percentage, None);
+            BulkItemResult::from_result(id, &result, StatusCode::OK)
+        })
+        .collect();
+
+    Ok(BulkResponse { items })
+}
+
+/// Delete several optimizations
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize/bulk/delete",
+    tag = "optimize",
+    request_body = BulkOptimizationRequest,
+    responses(
+        (status = 207, description = "Per-optimization deletion outcomes", body = BulkResponse),
+        (status = 400, description = "Invalid request"),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn bulk_delete_optimizations(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Json(req): Json<BulkOptimizationRequest>,
+) -> ApiResult<BulkResponse> {
+    ensure_permission(&auth, Permission::OptimizeWrite)?;
+
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let items = req
+        .ids
+        .iter()
+        .map(|&id| BulkItemResult::from_result(id, &service.delete(id), StatusCode::NO_CONTENT))
+        .collect();
+
+    Ok(BulkResponse { items })
 }
 
 /// Rollback optimization
@@ -172,8 +910,11 @@ async fn deploy_optimization(
     ),
     request_body = RollbackOptimizationRequest,
     responses(
-        (status = 200, description = "Optimization rolled back", body = OptimizationResponse),
-        (status = 404, description = "Optimization not found")
+        (status = 200, description = "Optimization rolled back", body = ApiResponseOptimization),
+        (status = 400, description = "Target revision is already in effect"),
+        (status = 404, description = "Optimization or revision not found"),
+        (status = 409, description = "Optimization has no earlier revision"),
+        (status = 403, description = "Missing required permission")
     ),
     security(
         ("bearer_auth" = []),
@@ -181,9 +922,978 @@ async fn deploy_optimization(
     )
 )]
 async fn rollback_optimization(
-    State(_service): State<Arc<OptimizationService>>,
-    Path(_id): Path<Uuid>,
-    Json(_req): Json<RollbackOptimizationRequest>,
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RollbackOptimizationRequest>,
+) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
+    ensure_permission(&auth, Permission::OptimizeExecute)?;
+
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let optimization = service.update(id, |optimization| {
+        let target = match req.to_revision {
+            Some(target) => target,
+            None if optimization.revision > 1 => optimization.revision - 1,
+            None => {
+                return Err(ApiError::FailedPrecondition(format!(
+                    "Optimization {} has no earlier revision to roll back to",
+                    id
+                )))
+            }
+        };
+
+        let restored = optimization
+            .revisions
+            .iter()
+            .find(|revision| revision.revision == target)
+            .cloned()
+            .ok_or_else(|| {
+                ApiError::NotFound(format!("Revision {} not found for optimization {}", target, id))
+            })?;
+        if restored.revision == optimization.revision {
+            return Err(ApiError::Validation(format!(
+                "Revision {} is already in effect",
+                target
+            )));
+        }
+
+        optimization.config = restored.config;
+        optimization.revision = restored.revision;
+        optimization.status = DecisionStatus::RolledBack;
+        Ok(())
+    })?;
+
+    tracing::info!(
+        optimization_id = %id,
+        revision = optimization.revision,
+        reason = %req.reason,
+        "Optimization rolled back"
+    );
+    service.publish(
+        "optimization.rolled_back",
+        &optimization,
+        format!(
+            "Optimization {} rolled back to revision {}: {}",
+            id, optimization.revision, req.reason
+        ),
+    );
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Approve an optimization awaiting approval
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize/{id}/approve",
+    tag = "optimize",
+    params(
+        ("id" = Uuid, Path, description = "Optimization ID")
+    ),
+    responses(
+        (status = 200, description = "Optimization approved", body = ApiResponseOptimization),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Optimization not found"),
+        (status = 409, description = "Optimization is not awaiting approval")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn approve_optimization(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path(id): Path<Uuid>,
+) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
+    require_reviewer(&auth)?;
+
+    let optimization = service.update(id, |optimization| {
+        ensure_awaiting_approval(optimization)?;
+        optimization.status = DecisionStatus::Pending;
+        Ok(())
+    })?;
+
+    tracing::info!(optimization_id = %id, approved_by = %auth.user_id(), "Optimization approved");
+    service.publish(
+        "optimization.approved",
+        &optimization,
+        format!("Optimization {} approved by {}", id, auth.user_id()),
+    );
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Reject an optimization awaiting approval
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize/{id}/reject",
+    tag = "optimize",
+    params(
+        ("id" = Uuid, Path, description = "Optimization ID")
+    ),
+    request_body = RejectOptimizationRequest,
+    responses(
+        (status = 200, description = "Optimization rejected", body = ApiResponseOptimization),
+        (status = 403, description = "Admin access required"),
+        (status = 404, description = "Optimization not found"),
+        (status = 409, description = "Optimization is not awaiting approval")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn reject_optimization(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<RejectOptimizationRequest>,
 ) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
-    Err(ApiError::NotFound("Optimization not found".into()))
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+    require_reviewer(&auth)?;
+
+    let optimization = service.update(id, |optimization| {
+        ensure_awaiting_approval(optimization)?;
+        optimization.status = DecisionStatus::Rejected;
+        Ok(())
+    })?;
+
+    tracing::info!(
+        optimization_id = %id,
+        rejected_by = %auth.user_id(),
+        reason = %req.reason,
+        "Optimization rejected"
+    );
+    service.publish(
+        "optimization.rejected",
+        &optimization,
+        format!("Optimization {} rejected by {}: {}", id, auth.user_id(), req.reason),
+    );
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Add or overwrite tags on an optimization
+#[utoipa::path(
+    post,
+    path = "/api/v1/optimize/{id}/tags",
+    tag = "optimize",
+    params(
+        ("id" = Uuid, Path, description = "Optimization ID")
+    ),
+    request_body = TagOptimizationRequest,
+    responses(
+        (status = 200, description = "Tags updated", body = ApiResponseOptimization),
+        (status = 400, description = "Invalid tag or too many tags"),
+        (status = 404, description = "Optimization not found"),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn add_optimization_tags(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path(id): Path<Uuid>,
+    Json(req): Json<TagOptimizationRequest>,
+) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
+    ensure_permission(&auth, Permission::OptimizeWrite)?;
+
+    use validator::Validate;
+    req.validate()
+        .map_err(|e| ApiError::Validation(format!("Invalid request: {}", e)))?;
+
+    let optimization = service.update(id, |optimization| {
+        let mut tags = optimization.tags.clone();
+        tags.extend(req.tags);
+        if tags.len() > MAX_TAGS {
+            return Err(ApiError::Validation(format!(
+                "Optimization {} would have {} tags; at most {} are allowed",
+                id,
+                tags.len(),
+                MAX_TAGS
+            )));
+        }
+        optimization.tags = tags;
+        Ok(())
+    })?;
+
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Remove a tag from an optimization
+#[utoipa::path(
+    delete,
+    path = "/api/v1/optimize/{id}/tags/{key}",
+    tag = "optimize",
+    params(
+        ("id" = Uuid, Path, description = "Optimization ID"),
+        ("key" = String, Path, description = "Tag key")
+    ),
+    responses(
+        (status = 200, description = "Tag removed", body = ApiResponseOptimization),
+        (status = 404, description = "Optimization or tag not found"),
+        (status = 403, description = "Missing required permission")
+    ),
+    security(
+        ("bearer_auth" = []),
+        ("api_key" = [])
+    )
+)]
+async fn remove_optimization_tag(
+    State(service): State<Arc<OptimizationService>>,
+    Extension(auth): Extension<AuthMethod>,
+    Path((id, key)): Path<(Uuid, String)>,
+) -> ApiResult<Json<ApiResponse<OptimizationResponse>>> {
+    ensure_permission(&auth, Permission::OptimizeWrite)?;
+    let optimization = service.update(id, |optimization| {
+        optimization
+            .tags
+            .remove(&key)
+            .map(|_| ())
+            .ok_or_else(|| ApiError::NotFound(format!("Optimization {} has no tag '{}'", id, key)))
+    })?;
+
+    Ok(Json(ApiResponse::new(optimization)))
+}
+
+/// Only admins may approve or reject optimizations
+fn require_reviewer(auth: &AuthMethod) -> ApiResult<()> {
+    if has_permission(auth, &Permission::AdminWrite) {
+        Ok(())
+    } else {
+        Err(ApiError::Authorization(
+            "Reviewing optimizations requires admin access".into(),
+        ))
+    }
+}
+
+fn ensure_awaiting_approval(optimization: &OptimizationResponse) -> ApiResult<()> {
+    if optimization.status == DecisionStatus::AwaitingApproval {
+        Ok(())
+    } else {
+        Err(ApiError::Conflict(format!(
+            "Optimization {} is not awaiting approval",
+            optimization.id
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::middleware::auth::Claims;
+    use axum::body::Body;
+    use axum::http::Request;
+    use http_body_util::BodyExt;
+    use llm_optimizer_types::decisions::OptimizationStrategy;
+    use tower::ServiceExt;
+
+    fn app(require_approval: bool, role: &str) -> Router {
+        let service = OptimizationService::new().with_approval_required(require_approval);
+        let claims = Claims::new("alice".to_string(), vec![role.to_string()], 3600);
+        optimize_routes(Arc::new(service)).layer(Extension(AuthMethod::Bearer(claims)))
+    }
+
+    async fn post(app: &Router, uri: &str, body: serde_json::Value) -> (StatusCode, serde_json::Value) {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::post(uri)
+                    .header("content-type", "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    async fn create(app: &Router) -> (String, serde_json::Value) {
+        let (status, body) = post(
+            app,
+            "/optimize",
+            serde_json::json!({"target_services": ["chat"], "strategy": "cost_performance_scoring"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        (body["data"]["id"].as_str().unwrap().to_string(), body["data"].clone())
+    }
+
+    #[tokio::test]
+    async fn test_state_changes_are_published_as_events() {
+        let events = Arc::new(EventService::new());
+        let mut subscriber = events.subscribe();
+        let service = OptimizationService::new().with_events(events.clone());
+        let claims = Claims::new("alice".to_string(), vec!["admin".to_string()], 3600);
+        let app = optimize_routes(Arc::new(service)).layer(Extension(AuthMethod::Bearer(claims)));
+
+        let (id, _) = create(&app).await;
+        let (status, _) = post(
+            &app,
+            &format!("/optimize/{}/deploy", id),
+            serde_json::json!({"gradual": false}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        for expected in ["optimization.created", "optimization.deployed"] {
+            let event = subscriber.try_recv().unwrap();
+            assert_eq!(event.event_type, expected);
+            assert_eq!(event.optimization_id.unwrap().to_string(), id);
+            assert_eq!(event.service.as_deref(), Some("chat"));
+        }
+        assert!(subscriber.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_with_idempotency_key_is_not_duplicated() {
+        let service = Arc::new(OptimizationService::new());
+        let claims = Claims::new("alice".to_string(), vec!["user".to_string()], 3600);
+        let app = optimize_routes(service.clone()).layer(Extension(AuthMethod::Bearer(claims)));
+        let create = |body: serde_json::Value| {
+            Request::post("/optimize")
+                .header("content-type", "application/json")
+                .header("idempotency-key", "create-chat-1")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+        let body = serde_json::json!({
+            "target_services": ["chat"],
+            "strategy": "cost_performance_scoring",
+        });
+
+        let first = app.clone().oneshot(create(body.clone())).await.unwrap();
+        assert_eq!(first.status(), StatusCode::CREATED);
+        let first = first.into_body().collect().await.unwrap().to_bytes();
+
+        let retry = app.clone().oneshot(create(body)).await.unwrap();
+        assert_eq!(retry.status(), StatusCode::CREATED);
+        assert_eq!(retry.headers()["idempotent-replayed"], "true");
+        assert_eq!(retry.into_body().collect().await.unwrap().to_bytes(), first);
+        assert_eq!(service.optimizations.len(), 1);
+
+        let conflicting = serde_json::json!({
+            "target_services": ["search"],
+            "strategy": "cost_performance_scoring",
+        });
+        let response = app.oneshot(create(conflicting)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+        assert_eq!(service.optimizations.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_approve_then_deploy() {
+        let app = app(true, "admin");
+        let (id, created) = create(&app).await;
+        assert_eq!(created["status"], "awaiting_approval");
+
+        let (status, body) = post(&app, &format!("/optimize/{}/approve", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "pending");
+
+        let (status, body) = post(&app, &format!("/optimize/{}/deploy", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "deployed");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_without_approval_is_rejected() {
+        let app = app(true, "admin");
+        let (id, _) = create(&app).await;
+
+        let (status, body) = post(&app, &format!("/optimize/{}/deploy", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "failed_precondition");
+
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/reject", id),
+            serde_json::json!({"reason": "too risky"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "rejected");
+
+        let (status, _) = post(&app, &format!("/optimize/{}/deploy", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::CONFLICT);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_deploy_reports_each_outcome() {
+        let app = app(true, "admin");
+        let (approved, _) = create(&app).await;
+        let (pending, _) = create(&app).await;
+        let unknown = Uuid::new_v4();
+        post(&app, &format!("/optimize/{}/approve", approved), serde_json::json!({})).await;
+
+        let (status, body) = post(
+            &app,
+            "/optimize/bulk/deploy",
+            serde_json::json!({"ids": [approved, pending, unknown], "gradual": false}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::MULTI_STATUS);
+
+        let items = body["items"].as_array().unwrap();
+        let outcomes: Vec<_> = items
+            .iter()
+            .map(|item| (item["id"].as_str().unwrap().to_string(), item["status"].as_u64().unwrap()))
+            .collect();
+        assert_eq!(
+            outcomes,
+            vec![(approved.clone(), 200), (pending, 409), (unknown.to_string(), 404)]
+        );
+        assert!(items[0].get("error").is_none());
+        assert!(items[1]["error"].as_str().unwrap().contains("awaiting approval"));
+
+        let request = Request::get(format!("/optimize/{}", approved)).body(Body::empty()).unwrap();
+        let (_, body) = send(&app, request).await;
+        assert_eq!(body["data"]["status"], "deployed");
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_keeps_deployed_optimizations() {
+        let app = app(false, "user");
+        let (deployed, _) = create(&app).await;
+        let (draft, _) = create(&app).await;
+        post(&app, &format!("/optimize/{}/deploy", deployed), serde_json::json!({})).await;
+
+        let (status, body) =
+            post(&app, "/optimize/bulk/delete", serde_json::json!({"ids": [deployed, draft]})).await;
+        assert_eq!(status, StatusCode::MULTI_STATUS);
+        assert_eq!(body["items"][0]["status"], 409);
+        assert_eq!(body["items"][1]["status"], 204);
+
+        let request = Request::get(format!("/optimize/{}", draft)).body(Body::empty()).unwrap();
+        let (status, _) = send(&app, request).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+
+        let (status, _) = post(&app, "/optimize/bulk/delete", serde_json::json!({"ids": []})).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_approval_requires_admin_and_is_optional() {
+        let gated = app(true, "user");
+        let (id, _) = create(&gated).await;
+        let (status, _) = post(&gated, &format!("/optimize/{}/approve", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+
+        let ungated = app(false, "user");
+        let (id, created) = create(&ungated).await;
+        assert_eq!(created["status"], "pending");
+        let (status, _) = post(&ungated, &format!("/optimize/{}/deploy", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_to_intermediate_revision_restores_its_config() {
+        let app = app(false, "user");
+        let (id, created) = create(&app).await;
+        assert_eq!(created["revision"], 1);
+
+        for max_tokens in [1000, 2000, 3000] {
+            let (status, _) = post(
+                &app,
+                &format!("/optimize/{}/deploy", id),
+                serde_json::json!({"config": {"max_tokens": max_tokens}}),
+            )
+            .await;
+            assert_eq!(status, StatusCode::OK);
+        }
+
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/rollback", id),
+            serde_json::json!({"reason": "quality regression", "to_revision": 3}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["status"], "rolled_back");
+        assert_eq!(body["data"]["revision"], 3);
+        assert_eq!(body["data"]["config"], serde_json::json!({"max_tokens": 2000}));
+        assert_eq!(body["data"]["revisions"].as_array().unwrap().len(), 4);
+
+        // Without a target, rollback steps back one revision from the current one
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/rollback", id),
+            serde_json::json!({"reason": "still regressed"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["revision"], 2);
+        assert_eq!(body["data"]["config"], serde_json::json!({"max_tokens": 1000}));
+    }
+
+    #[tokio::test]
+    async fn test_rollback_rejects_unknown_or_current_revision() {
+        let app = app(false, "user");
+        let (id, _) = create(&app).await;
+
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/rollback", id),
+            serde_json::json!({"reason": "nothing deployed"}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CONFLICT);
+        assert_eq!(body["error"], "failed_precondition");
+
+        let (status, _) = post(&app, &format!("/optimize/{}/deploy", id), serde_json::json!({})).await;
+        assert_eq!(status, StatusCode::OK);
+
+        // Revisions belong to a single optimization
+        let (other, _) = create(&app).await;
+        for _ in 0..2 {
+            post(&app, &format!("/optimize/{}/deploy", other), serde_json::json!({})).await;
+        }
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/rollback", id),
+            serde_json::json!({"reason": "wrong revision", "to_revision": 3}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["message"].as_str().unwrap().contains("Revision 3"));
+
+        let (status, _) = post(
+            &app,
+            &format!("/optimize/{}/rollback", id),
+            serde_json::json!({"reason": "already current", "to_revision": 2}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    async fn send(app: &Router, request: Request<Body>) -> (StatusCode, serde_json::Value) {
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        (status, serde_json::from_slice(&bytes).unwrap())
+    }
+
+    async fn create_tagged(app: &Router, service: &str, tags: serde_json::Value) -> String {
+        let (status, body) = post(
+            app,
+            "/optimize",
+            serde_json::json!({
+                "target_services": [service],
+                "strategy": "cost_performance_scoring",
+                "tags": tags
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED, "{}", body);
+        body["data"]["id"].as_str().unwrap().to_string()
+    }
+
+    async fn listed_ids(app: &Router, query: &str) -> (StatusCode, Vec<String>) {
+        let (status, body) =
+            send(app, Request::get(format!("/optimize?{}", query)).body(Body::empty()).unwrap()).await;
+        let ids = body["items"]
+            .as_array()
+            .map(|items| items.iter().map(|i| i["id"].as_str().unwrap().to_string()).collect())
+            .unwrap_or_default();
+        (status, ids)
+    }
+
+    #[tokio::test]
+    async fn test_list_filters_by_tags() {
+        let app = app(false, "user");
+        let ml = create_tagged(&app, "chat", serde_json::json!({"team": "ml", "ticket": "OPT-1"})).await;
+        let ml_search =
+            create_tagged(&app, "search", serde_json::json!({"team": "ml", "ticket": "OPT-2"})).await;
+        let infra = create_tagged(&app, "chat", serde_json::json!({"team": "infra"})).await;
+
+        let (status, mut ids) = listed_ids(&app, "tag=team=ml").await;
+        assert_eq!(status, StatusCode::OK);
+        ids.sort();
+        let mut expected = vec![ml.clone(), ml_search];
+        expected.sort();
+        assert_eq!(ids, expected);
+
+        assert_eq!(listed_ids(&app, "tag=team=ml,ticket=OPT-1").await.1, vec![ml.clone()]);
+        assert_eq!(listed_ids(&app, "tag=team=ml&service=chat").await.1, vec![ml]);
+        assert_eq!(listed_ids(&app, "tag=team=infra").await.1, vec![infra]);
+        assert!(listed_ids(&app, "tag=team=data").await.1.is_empty());
+        assert_eq!(listed_ids(&app, "").await.1.len(), 3);
+
+        assert_eq!(listed_ids(&app, "tag=team").await.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_page_is_clamped() {
+        let service = OptimizationService::new().with_max_page_size(2);
+        let claims = Claims::new("alice".to_string(), vec!["user".to_string()], 3600);
+        let app = optimize_routes(Arc::new(service)).layer(Extension(AuthMethod::Bearer(claims)));
+        for _ in 0..3 {
+            create(&app).await;
+        }
+
+        let (status, body) =
+            send(&app, Request::get("/optimize?page_size=100000").body(Body::empty()).unwrap()).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["page_size"], 2);
+        assert_eq!(body["requested_page_size"], 100_000);
+        assert_eq!(body["items"].as_array().unwrap().len(), 2);
+        assert_eq!(body["total_pages"], 2);
+
+        let (_, body) =
+            send(&app, Request::get("/optimize?page_size=2").body(Body::empty()).unwrap()).await;
+        assert_eq!(body["page_size"], 2);
+        assert!(body.get("requested_page_size").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tags_are_rejected() {
+        let app = app(false, "user");
+        let (status, body) = post(
+            &app,
+            "/optimize",
+            serde_json::json!({
+                "target_services": ["chat"],
+                "strategy": "cost_performance_scoring",
+                "tags": {"team": "ml ops"}
+            }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert!(body["message"].as_str().unwrap().contains("team"), "{}", body);
+
+        let id = create_tagged(&app, "chat", serde_json::json!({})).await;
+        let (status, _) = post(
+            &app,
+            &format!("/optimize/{}/tags", id),
+            serde_json::json!({"tags": {"-bad": "x"}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_tags() {
+        let app = app(false, "user");
+        let id = create_tagged(&app, "chat", serde_json::json!({"team": "ml"})).await;
+
+        let (status, body) = post(
+            &app,
+            &format!("/optimize/{}/tags", id),
+            serde_json::json!({"tags": {"team": "infra", "experiment": "haiku-routing"}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(
+            body["data"]["tags"],
+            serde_json::json!({"team": "infra", "experiment": "haiku-routing"})
+        );
+
+        let remove = |key: &str| {
+            Request::delete(format!("/optimize/{}/tags/{}", id, key))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let (status, body) = send(&app, remove("team")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(body["data"]["tags"], serde_json::json!({"experiment": "haiku-routing"}));
+
+        let (status, body) = send(&app, remove("team")).await;
+        assert_eq!(status, StatusCode::NOT_FOUND);
+        assert!(body["message"].as_str().unwrap().contains("'team'"));
+    }
+
+    #[tokio::test]
+    async fn test_tag_limit_applies_after_merge() {
+        let app = app(false, "user");
+        let initial: serde_json::Map<String, serde_json::Value> = (0..MAX_TAGS)
+            .map(|i| (format!("k{}", i), serde_json::json!("v")))
+            .collect();
+        let id = create_tagged(&app, "chat", serde_json::Value::Object(initial)).await;
+
+        // Overwriting an existing key stays within the limit
+        let (status, _) = post(
+            &app,
+            &format!("/optimize/{}/tags", id),
+            serde_json::json!({"tags": {"k0": "w"}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+
+        let (status, _) = post(
+            &app,
+            &format!("/optimize/{}/tags", id),
+            serde_json::json!({"tags": {"one-more": "v"}}),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    fn measured(
+        strategy: OptimizationStrategy,
+        status: DecisionStatus,
+        cost_reduction_pct: f64,
+        quality_delta_pct: f64,
+    ) -> OptimizationResponse {
+        let now = chrono::Utc::now();
+        OptimizationResponse {
+            id: Uuid::new_v4(),
+            target_services: vec!["chat".to_string()],
+            strategy,
+            status,
+            changes: vec![],
+            config: serde_json::Value::Null,
+            revision: 1,
+            revisions: vec![],
+            expected_impact: ExpectedImpactResponse {
+                cost_reduction_pct: 20.0,
+                quality_delta_pct: -2.0,
+                latency_delta_pct: -5.0,
+                confidence: 0.85,
+            },
+            actual_impact: Some(ActualImpactResponse {
+                cost_reduction_pct,
+                quality_delta_pct,
+                latency_delta_pct: 0.0,
+                requests_affected: 1000,
+                measured_from: now,
+                measured_until: now,
+            }),
+            rationale: String::new(),
+            created_at: now,
+            deployed_at: Some(now),
+            tags: HashMap::new(),
+        }
+    }
+
+    #[test]
+    fn test_retention_archives_decisions_past_the_window() {
+        use OptimizationStrategy::CostPerformanceScoring;
+
+        let now = chrono::Utc::now();
+        let service = OptimizationService::new();
+        let decision = |status, age_days| {
+            let mut optimization = measured(CostPerformanceScoring, status, 10.0, 0.0);
+            optimization.created_at = now - chrono::Duration::days(age_days);
+            service.optimizations.insert(optimization.id, optimization.clone());
+            optimization.id
+        };
+        let recent = decision(DecisionStatus::Completed, 2);
+        let old = decision(DecisionStatus::Completed, 45);
+        let old_rolled_back = decision(DecisionStatus::RolledBack, 90);
+        let old_deployed = decision(DecisionStatus::Deployed, 60);
+
+        let report = service.apply_retention(&RetentionPolicy::new().with_max_age_days(30), now);
+
+        assert_eq!((report.archived, report.deleted, report.retained), (2, 0, 2));
+        assert!(service.optimizations.contains_key(&recent));
+        assert!(service.optimizations.contains_key(&old_deployed));
+        assert_eq!(service.archived(old).unwrap().id, old);
+        assert!(service.archived(old_rolled_back).is_some());
+        assert!(service.get(old).is_err());
+
+        // A second run has nothing left to do
+        let report = service.apply_retention(&RetentionPolicy::new().with_max_age_days(30), now);
+        assert_eq!((report.archived, report.archived_total), (0, 2));
+    }
+
+    #[test]
+    fn test_retention_is_recorded_in_registered_metrics() {
+        use OptimizationStrategy::ThresholdBased;
+
+        let metrics = RetentionMetrics::new();
+        let mut registry = Registry::default();
+        metrics.register(&mut registry);
+
+        let now = chrono::Utc::now();
+        let service = OptimizationService::new().with_retention_metrics(metrics);
+        for age in 0..3 {
+            let mut optimization = measured(ThresholdBased, DecisionStatus::Completed, 0.0, 0.0);
+            optimization.created_at = now - chrono::Duration::days(age * 20);
+            service.optimizations.insert(optimization.id, optimization);
+        }
+        service.apply_retention(&RetentionPolicy::new().with_max_age_days(30), now);
+        service.apply_retention(&RetentionPolicy::new().with_keep_last(1), now);
+
+        let mut exported = String::new();
+        prometheus_client::encoding::text::encode(&mut exported, &registry).unwrap();
+        assert!(exported.contains("decisions_archived_total 2\n"), "{}", exported);
+        assert!(exported.contains("decisions_deleted_total 0\n"), "{}", exported);
+        assert!(exported.contains("decisions_retained 1\n"), "{}", exported);
+    }
+
+    #[test]
+    fn test_retention_keeps_newest_and_can_delete() {
+        use OptimizationStrategy::ThresholdBased;
+
+        let now = chrono::Utc::now();
+        let service = OptimizationService::new();
+        let ids: Vec<Uuid> = (0..5)
+            .map(|age| {
+                let mut optimization =
+                    measured(ThresholdBased, DecisionStatus::Completed, 0.0, 0.0);
+                optimization.created_at = now - chrono::Duration::hours(age);
+                service.optimizations.insert(optimization.id, optimization.clone());
+                optimization.id
+            })
+            .collect();
+
+        let policy = RetentionPolicy::new()
+            .with_keep_last(2)
+            .with_action(RetentionAction::Delete);
+        let report = service.apply_retention(&policy, now);
+
+        assert_eq!((report.archived, report.deleted, report.retained), (0, 3, 2));
+        assert!(service.optimizations.contains_key(&ids[0]));
+        assert!(service.optimizations.contains_key(&ids[1]));
+        assert!(ids[2..].iter().all(|id| service.archived(*id).is_none()));
+
+        // A policy without limits keeps everything
+        let report = service.apply_retention(&RetentionPolicy::new(), now);
+        assert_eq!(report.retained, 2);
+    }
+
+    #[tokio::test]
+    async fn test_leaderboard_ranks_strategies_by_measured_impact() {
+        use OptimizationStrategy::{ABTesting, CostPerformanceScoring, ThresholdBased};
+
+        let service = OptimizationService::new();
+        let history = [
+            measured(CostPerformanceScoring, DecisionStatus::Completed, 30.0, -1.0),
+            measured(CostPerformanceScoring, DecisionStatus::RolledBack, 10.0, -5.0),
+            measured(ThresholdBased, DecisionStatus::Completed, 25.0, 0.0),
+            measured(ThresholdBased, DecisionStatus::Deployed, 25.0, -1.0),
+            measured(ABTesting, DecisionStatus::Completed, 5.0, 2.0),
+        ];
+        for optimization in history {
+            service.optimizations.insert(optimization.id, optimization);
+        }
+        // Unmeasured optimizations don't count towards the leaderboard
+        let mut pending = measured(ABTesting, DecisionStatus::Pending, 0.0, 0.0);
+        pending.actual_impact = None;
+        service.optimizations.insert(pending.id, pending);
+
+        let claims = Claims::new("alice".to_string(), vec!["user".to_string()], 3600);
+        let app = optimize_routes(Arc::new(service)).layer(Extension(AuthMethod::Bearer(claims)));
+        let response = app
+            .oneshot(Request::get("/optimize/leaderboard").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        let entries: Vec<StrategyLeaderboardEntry> =
+            serde_json::from_value(body["data"].clone()).unwrap();
+
+        let ranking: Vec<_> = entries.iter().map(|e| (e.rank, e.strategy)).collect();
+        assert_eq!(
+            ranking,
+            vec![(1, ThresholdBased), (2, CostPerformanceScoring), (3, ABTesting)]
+        );
+
+        let threshold = &entries[0];
+        assert_eq!(threshold.optimizations, 2);
+        assert_eq!(threshold.avg_cost_reduction_pct, 25.0);
+        assert_eq!(threshold.avg_quality_delta_pct, -0.5);
+        assert_eq!(threshold.success_rate, 1.0);
+
+        let scoring = &entries[1];
+        assert_eq!(scoring.avg_cost_reduction_pct, 20.0);
+        assert_eq!(scoring.avg_quality_delta_pct, -3.0);
+        assert_eq!(scoring.success_rate, 0.5);
+
+        assert_eq!(entries[2].optimizations, 1);
+    }
+
+    #[tokio::test]
+    async fn test_create_reports_llm_cost_headers() {
+        use llm_optimizer_integrations::anthropic::AnthropicConfig;
+        use llm_optimizer_integrations::RetryPolicy;
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Routing simple prompts to Haiku cuts spend."}],
+                "model": "claude-3-haiku-20240307",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 1200, "output_tokens": 300}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = AnthropicClient::new(AnthropicConfig {
+            api_key: "test-key".to_string(),
+            base_url: server.uri(),
+            timeout_secs: 5,
+            retry: RetryPolicy::none(),
+            rate_limit_per_minute: 50,
+            api_version: "2023-06-01".to_string(),
+            pricing: Default::default(),
+            cost_store: None,
+        })
+        .await
+        .unwrap();
+        let service = OptimizationService::new()
+            .with_rationale_llm(Arc::new(client), ClaudeModel::Claude3Haiku);
+        let claims = Claims::new("alice".to_string(), vec!["admin".to_string()], 3600);
+        let app = optimize_routes(Arc::new(service)).layer(Extension(AuthMethod::Bearer(claims)));
+
+        let response = app
+            .oneshot(
+                Request::post("/optimize")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({"target_services": ["chat"], "strategy": "cost_performance_scoring"})
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+
+        // 1200 * $0.25/Mtok + 300 * $1.25/Mtok
+        let headers = response.headers();
+        let cost: f64 = headers[&COST_USD_HEADER].to_str().unwrap().parse().unwrap();
+        assert!((cost - 0.000675).abs() < 1e-9);
+        assert_eq!(headers[&TOKENS_USED_HEADER], "1500");
+
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(body["data"]["rationale"], "Routing simple prompts to Haiku cuts spend.");
+    }
+
+    #[tokio::test]
+    async fn test_create_without_llm_sends_no_cost_headers() {
+        let response = app(false, "admin")
+            .oneshot(
+                Request::post("/optimize")
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        serde_json::json!({"target_services": ["chat"], "strategy": "cost_performance_scoring"})
+                            .to_string(),
+                    ))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert!(!response.headers().contains_key(COST_USD_HEADER));
+        assert!(!response.headers().contains_key(TOKENS_USED_HEADER));
+    }
 }
@@ -1,40 +1,55 @@
 //! HTTP server implementation
 
 use axum::{
+    extract::DefaultBodyLimit,
     middleware,
+    routing::get,
     Router,
     http::{header, Method},
 };
+use llm_optimizer_integrations::anthropic::{AnthropicClient, ClaudeModel};
+use llm_optimizer_integrations::url_guard::UrlValidator;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use std::time::Duration;
 use tower::ServiceBuilder;
-use tower_http::{
-    compression::CompressionLayer,
-    trace::TraceLayer,
-};
+use tower_http::trace::TraceLayer;
 use utoipa::OpenApi;
-use utoipa_swagger_ui::SwaggerUi;
+use utoipa_swagger_ui::{Config as SwaggerConfig, SwaggerUi};
 use utoipa_rapidoc::RapiDoc;
 use utoipa_redoc::{Redoc, Servable};
 
 use crate::{
-    error::ApiResult,
+    error::{ApiResult, ErrorFormat},
     middleware::{
         auth::{auth_middleware, AuthConfig},
+        body_limit::{body_limit_middleware, DEFAULT_MAX_BODY_BYTES},
+        compression::CompressionConfig,
         cors::CorsConfig,
-        logging::{logging_middleware, metrics_middleware, request_id_middleware},
+        drain::{drain_middleware, DrainState},
+        error_format::error_format_middleware,
+        idempotency::{IdempotencyStore, InMemoryIdempotencyStore},
+        logging::{logging_middleware, metrics_middleware, request_id_middleware, LoggingConfig},
+        maintenance::{maintenance_middleware, MaintenanceMode},
+        negotiation::content_negotiation_middleware,
         ratelimit::{rate_limit_middleware, RateLimitConfig},
+        rbac::{policy_middleware, PolicyTable},
         timeout::default_timeout,
     },
-    openapi::ApiDoc,
+    models::common::DEFAULT_MAX_PAGE_SIZE,
+    openapi::{openapi_json, ApiDoc},
     routes::{
-        admin::{admin_routes, AdminService},
+        admin::{admin_routes, AdminService, ServiceStatusSource},
+        auth::auth_routes,
         config::{config_routes, ConfigService},
+        events::{events_routes, EventService},
         health::{health_routes, HealthState},
         integrations::{integrations_routes, IntegrationService},
         metrics::{metrics_routes, MetricsService},
-        optimize::{optimize_routes, OptimizationService},
+        optimize::{
+            optimize_routes, spawn_retention_task, OptimizationService, RetentionMetrics,
+            RetentionPolicy,
+        },
     },
 };
 
@@ -45,12 +60,47 @@ pub struct ServerConfig {
     pub addr: SocketAddr,
     /// Authentication config
     pub auth: Arc<AuthConfig>,
+    /// Permission required by each API route; unlisted routes are denied
+    pub policy: Arc<PolicyTable>,
     /// Rate limit config
     pub rate_limit: Arc<RateLimitConfig>,
     /// CORS config
     pub cors: CorsConfig,
+    /// Response compression config
+    pub compression: CompressionConfig,
     /// Application version
     pub version: String,
+    /// Event service backing the event stream
+    pub events: Arc<EventService>,
+    /// Maintenance flag shared by the admin API, readiness and request gating
+    pub maintenance: Arc<MaintenanceMode>,
+    /// Draining flag set on shutdown; new requests get a 503 while those in
+    /// flight finish
+    pub drain: Arc<DrainState>,
+    /// Require admin approval before new optimizations can be deployed
+    pub require_deploy_approval: bool,
+    /// Largest page size list endpoints return; larger requests are clamped
+    pub max_page_size: u32,
+    /// Largest request body accepted, in bytes; larger bodies get a 413
+    pub max_body_bytes: usize,
+    /// How long optimization decisions are kept before being archived
+    pub retention: Option<RetentionPolicy>,
+    /// Counts of decisions retired by the retention policy; register them
+    /// with a metrics registry to export them
+    pub retention_metrics: RetentionMetrics,
+    /// Checks URLs in integration configs before the server accepts them
+    pub url_validator: UrlValidator,
+    /// Responses replayed for repeated `Idempotency-Key`s
+    pub idempotency: Arc<dyn IdempotencyStore>,
+    /// How error responses are serialized
+    pub error_format: ErrorFormat,
+    /// Request log sampling
+    pub logging: Arc<LoggingConfig>,
+    /// LLM that writes optimization rationales, if any
+    pub rationale_llm: Option<(Arc<AnthropicClient>, ClaudeModel)>,
+    /// Managed services listed at `/admin/services`; without one the list is
+    /// empty
+    pub service_status: Option<Arc<dyn ServiceStatusSource>>,
 }
 
 impl ServerConfig {
@@ -59,23 +109,137 @@ impl ServerConfig {
         Self {
             addr,
             auth: Arc::new(AuthConfig::new(jwt_secret)),
+            policy: Arc::new(PolicyTable::standard()),
             rate_limit: Arc::new(RateLimitConfig::default()),
             cors: CorsConfig::default(),
+            compression: CompressionConfig::default(),
             version: env!("CARGO_PKG_VERSION").to_string(),
+            events: Arc::new(EventService::new()),
+            maintenance: Arc::new(MaintenanceMode::new()),
+            drain: Arc::new(DrainState::new()),
+            require_deploy_approval: false,
+            max_page_size: DEFAULT_MAX_PAGE_SIZE,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+            retention: None,
+            retention_metrics: RetentionMetrics::new(),
+            url_validator: UrlValidator::new(),
+            idempotency: Arc::new(InMemoryIdempotencyStore::default()),
+            error_format: ErrorFormat::default(),
+            logging: Arc::new(LoggingConfig::default()),
+            rationale_llm: None,
+            service_status: None,
         }
     }
 
+    /// Set authentication config
+    pub fn with_auth(mut self, auth: AuthConfig) -> Self {
+        self.auth = Arc::new(auth);
+        self
+    }
+
+    /// Set the route access policy
+    pub fn with_policy(mut self, policy: PolicyTable) -> Self {
+        self.policy = Arc::new(policy);
+        self
+    }
+
     /// Set CORS config
     pub fn with_cors(mut self, cors: CorsConfig) -> Self {
         self.cors = cors;
         self
     }
 
+    /// Set response compression config
+    pub fn with_compression(mut self, compression: CompressionConfig) -> Self {
+        self.compression = compression;
+        self
+    }
+
     /// Set rate limit config
     pub fn with_rate_limit(mut self, rate_limit: RateLimitConfig) -> Self {
         self.rate_limit = Arc::new(rate_limit);
         self
     }
+
+    /// Require admin approval before new optimizations can be deployed
+    pub fn with_deploy_approval(mut self, required: bool) -> Self {
+        self.require_deploy_approval = required;
+        self
+    }
+
+    /// Set the largest page size list endpoints return
+    pub fn with_max_page_size(mut self, max_page_size: u32) -> Self {
+        self.max_page_size = max_page_size;
+        self
+    }
+
+    /// Set the largest request body accepted, in bytes
+    pub fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    /// Archive old optimization decisions in the background
+    pub fn with_retention(mut self, retention: RetentionPolicy) -> Self {
+        self.retention = Some(retention);
+        self
+    }
+
+    /// Record decision retention runs in `metrics`
+    pub fn with_retention_metrics(mut self, metrics: RetentionMetrics) -> Self {
+        self.retention_metrics = metrics;
+        self
+    }
+
+    /// Set how URLs in integration configs are validated
+    pub fn with_url_validator(mut self, url_validator: UrlValidator) -> Self {
+        self.url_validator = url_validator;
+        self
+    }
+
+    /// Set where `Idempotency-Key`s and their responses are stored
+    pub fn with_idempotency_store(mut self, store: Arc<dyn IdempotencyStore>) -> Self {
+        self.idempotency = store;
+        self
+    }
+
+    /// Set how error responses are serialized, e.g. as RFC 7807 problem
+    /// details
+    pub fn with_error_format(mut self, error_format: ErrorFormat) -> Self {
+        self.error_format = error_format;
+        self
+    }
+
+    /// Set request log sampling
+    pub fn with_logging(mut self, logging: LoggingConfig) -> Self {
+        self.logging = Arc::new(logging);
+        self
+    }
+
+    /// Have Claude write the rationale for new optimizations
+    pub fn with_rationale_llm(mut self, client: Arc<AnthropicClient>, model: ClaudeModel) -> Self {
+        self.rationale_llm = Some((client, model));
+        self
+    }
+
+    /// Share a draining flag with the server's owner, so it can drain
+    /// requests before stopping the server
+    pub fn with_drain(mut self, drain: Arc<DrainState>) -> Self {
+        self.drain = drain;
+        self
+    }
+
+    /// Report the process's managed services at `/admin/services`
+    pub fn with_service_status(mut self, source: Arc<dyn ServiceStatusSource>) -> Self {
+        self.service_status = Some(source);
+        self
+    }
+
+    /// Set the event service, so callers can publish to the event stream
+    pub fn with_events(mut self, events: Arc<EventService>) -> Self {
+        self.events = events;
+        self
+    }
 }
 
 impl Default for ServerConfig {
@@ -89,26 +253,62 @@ impl Default for ServerConfig {
 
 /// Build the application router
 pub fn build_app(config: ServerConfig) -> Router {
+    build_app_with_decisions(config).0
+}
+
+/// Build the application router, also returning the optimization decisions
+/// it serves so background tasks can maintain them
+fn build_app_with_decisions(config: ServerConfig) -> (Router, Arc<OptimizationService>) {
     // Initialize services
-    let health_state = Arc::new(HealthState::new(config.version.clone()));
-    let optimize_service = Arc::new(OptimizationService::new());
-    let config_service = Arc::new(ConfigService);
+    let health_state = Arc::new(
+        HealthState::new(config.version.clone()).with_maintenance(config.maintenance.clone()),
+    );
+    let mut optimize_service = OptimizationService::new()
+        .with_approval_required(config.require_deploy_approval)
+        .with_max_page_size(config.max_page_size)
+        .with_idempotency_store(config.idempotency.clone())
+        .with_events(config.events.clone())
+        .with_retention_metrics(config.retention_metrics.clone());
+    if let Some((client, model)) = config.rationale_llm.clone() {
+        optimize_service = optimize_service.with_rationale_llm(client, model);
+    }
+    let optimize_service = Arc::new(optimize_service);
+    let config_service = Arc::new(ConfigService::new());
     let metrics_service = Arc::new(MetricsService);
-    let integrations_service = Arc::new(IntegrationService);
-    let admin_service = Arc::new(AdminService);
+    let integrations_service = Arc::new(
+        IntegrationService::new().with_url_validator(config.url_validator.clone()),
+    );
+    let mut admin_service = AdminService::new()
+        .with_maintenance(config.maintenance.clone())
+        .with_decisions(optimize_service.clone());
+    if let Some(retention) = config.retention {
+        admin_service = admin_service.with_retention(retention);
+    }
+    if let Some(source) = config.service_status.clone() {
+        admin_service = admin_service.with_service_status(source);
+    }
+    let admin_service = Arc::new(admin_service);
 
     // Build API v1 routes (protected)
     let api_v1 = Router::new()
-        .merge(optimize_routes(optimize_service))
+        .merge(optimize_routes(optimize_service.clone()))
         .merge(config_routes(config_service))
         .merge(metrics_routes(metrics_service))
         .merge(integrations_routes(integrations_service))
         .merge(admin_routes(admin_service))
+        .merge(events_routes(config.events.clone()))
+        // Enforce the route policy table (deny by default)
+        .layer(middleware::from_fn_with_state(
+            config.policy.clone(),
+            policy_middleware,
+        ))
         // Add authentication middleware
         .layer(middleware::from_fn_with_state(
             config.auth.clone(),
             auth_middleware,
         ))
+        // Token refresh authenticates with the refresh token in the body
+        .merge(auth_routes(config.auth.clone()))
         // Add rate limiting
         .layer(middleware::from_fn_with_state(
             config.rate_limit.clone(),
@@ -116,8 +316,10 @@ pub fn build_app(config: ServerConfig) -> Router {
         ));
 
     // Build complete router
-    Router::new()
+    let app = Router::new()
         // OpenAPI documentation
+        .route("/openapi.json", get(openapi_json))
+        .merge(SwaggerUi::new("/docs").config(SwaggerConfig::from("/openapi.json")))
         .merge(SwaggerUi::new("/swagger-ui").url("/api-docs/openapi.json", ApiDoc::openapi()))
         .merge(RapiDoc::new("/api-docs/openapi.json").path("/rapidoc"))
         .merge(Redoc::with_url("/redoc", ApiDoc::openapi()))
@@ -125,19 +327,45 @@ pub fn build_app(config: ServerConfig) -> Router {
         .merge(health_routes(health_state))
         // API v1 routes (authentication required)
         .nest("/api/v1", api_v1)
+        // Maintenance mode (admin and health endpoints stay available)
+        .layer(middleware::from_fn_with_state(
+            config.maintenance.clone(),
+            maintenance_middleware,
+        ))
+        // Draining on shutdown (health endpoints stay available)
+        .layer(middleware::from_fn_with_state(
+            config.drain.clone(),
+            drain_middleware,
+        ))
+        // Request body size limit, replacing axum's per-extractor default
+        .layer(middleware::from_fn_with_state(
+            config.max_body_bytes,
+            body_limit_middleware,
+        ))
+        .layer(DefaultBodyLimit::disable())
+        // Content negotiation (JSON by default, MessagePack on request)
+        .layer(middleware::from_fn(content_negotiation_middleware))
         // Global middleware (applied to all routes)
         .layer(
             ServiceBuilder::new()
                 // Timeout
                 .layer(default_timeout())
                 // Compression (gzip, brotli)
-                .layer(CompressionLayer::new())
+                .layer(config.compression.build())
                 // CORS
                 .layer(config.cors.build())
                 // Request ID
                 .layer(middleware::from_fn(request_id_middleware))
+                // Error format (needs the request ID for problem details)
+                .layer(middleware::from_fn_with_state(
+                    config.error_format,
+                    error_format_middleware,
+                ))
                 // Logging
-                .layer(middleware::from_fn(logging_middleware))
+                .layer(middleware::from_fn_with_state(
+                    config.logging.clone(),
+                    logging_middleware,
+                ))
                 // Metrics
                 .layer(middleware::from_fn(metrics_middleware))
                 // Tracing
@@ -151,17 +379,30 @@ pub fn build_app(config: ServerConfig) -> Router {
                             )
                         }),
                 ),
-        )
+        );
+
+    (app, optimize_service)
 }
 
 /// Start the HTTP server
 pub async fn start_server(config: ServerConfig) -> ApiResult<()> {
     let addr = config.addr;
-    let app = build_app(config);
+    let retention = config.retention;
+    let (app, decisions) = build_app_with_decisions(config);
+
+    if let Some(policy) = retention.filter(RetentionPolicy::is_bounded) {
+        spawn_retention_task(decisions, policy);
+        tracing::info!(
+            max_age_days = ?policy.max_age_days,
+            keep_last = ?policy.keep_last,
+            "Decision retention enabled"
+        );
+    }
 
     tracing::info!("Starting server on {}", addr);
     tracing::info!("OpenAPI documentation available at:");
-    tracing::info!("  - Swagger UI: http://{}/swagger-ui", addr);
+    tracing::info!("  - OpenAPI spec: http://{}/openapi.json", addr);
+    tracing::info!("  - Swagger UI: http://{}/docs", addr);
     tracing::info!("  - RapiDoc: http://{}/rapidoc", addr);
     tracing::info!("  - ReDoc: http://{}/redoc", addr);
 
@@ -186,6 +427,187 @@ mod tests {
         assert_eq!(config.addr.port(), 8080);
     }
 
+    #[tokio::test]
+    async fn test_app_compresses_with_configured_threshold() {
+        use axum::body::Body;
+        use axum::http::{header::ACCEPT_ENCODING, header::CONTENT_ENCODING, Request};
+        use tower::ServiceExt;
+
+        let request = || {
+            Request::get("/health")
+                .header(ACCEPT_ENCODING, "br")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let response = build_app(ServerConfig::default()).oneshot(request()).await.unwrap();
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+
+        let config =
+            ServerConfig::default().with_compression(CompressionConfig::new().with_min_size(0));
+        let response = build_app(config).oneshot(request()).await.unwrap();
+        assert_eq!(response.headers()[CONTENT_ENCODING], "br");
+    }
+
+    #[tokio::test]
+    async fn test_serves_openapi_spec() {
+        use axum::body::Body;
+        use axum::http::{Request, StatusCode};
+        use tower::ServiceExt;
+
+        let response = build_app(ServerConfig::default())
+            .oneshot(Request::get("/openapi.json").body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let spec: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        let created = &spec["paths"]["/api/v1/optimize"]["post"]["responses"]["201"];
+        assert_eq!(
+            created["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ApiResponseOptimization"
+        );
+        let schemas = &spec["components"]["schemas"];
+        assert!(schemas["ApiResponseOptimization"]["properties"]["data"].is_object());
+        assert!(schemas["ErrorResponse"]["properties"]["error"].is_object());
+
+        let forbidden = &spec["paths"]["/api/v1/optimize"]["post"]["responses"]["403"];
+        assert_eq!(
+            forbidden["content"]["application/json"]["schema"]["$ref"],
+            "#/components/schemas/ErrorResponse"
+        );
+        for scheme in ["bearer_auth", "api_key"] {
+            assert!(spec["components"]["securitySchemes"][scheme].is_object(), "{}", scheme);
+        }
+        for path in ["/api/v1/config/{key}", "/api/v1/metrics/query", "/api/v1/integrations"] {
+            assert!(spec["paths"][path].is_object(), "missing path {}", path);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_token_refresh_needs_no_access_token() {
+        use axum::body::Body;
+        use axum::http::{header::CONTENT_TYPE, Request, StatusCode};
+        use tower::ServiceExt;
+
+        let config = ServerConfig::default();
+        let pair = config
+            .auth
+            .issue_token_pair("user-123".to_string(), vec![])
+            .unwrap();
+        let body = serde_json::json!({ "refresh_token": pair.refresh_token });
+
+        let response = build_app(config)
+            .oneshot(
+                Request::post("/api/v1/auth/refresh")
+                    .header(CONTENT_TYPE, "application/json")
+                    .body(Body::from(body.to_string()))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_read_only_api_key_cannot_write() {
+        use crate::middleware::{auth::ApiKeyScope, rbac::Role};
+        use axum::body::Body;
+        use axum::http::{header::CONTENT_TYPE, Request, StatusCode};
+        use tower::ServiceExt;
+
+        let auth = AuthConfig::new("test-secret".to_string())
+            .with_scoped_api_key(
+                "readonly-key-123".to_string(),
+                ApiKeyScope::new().with_role(Role::ReadOnly),
+            )
+            .with_api_key("writer-key-123".to_string());
+        let app = build_app(ServerConfig::default().with_auth(auth));
+        let body = serde_json::json!({
+            "target_services": ["chat"],
+            "strategy": "cost_performance_scoring"
+        });
+        let request = |method: &str, key: &str| {
+            Request::builder()
+                .method(method)
+                .uri("/api/v1/optimize")
+                .header("x-api-key", key)
+                .header(CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap()
+        };
+
+        let response = app.clone().oneshot(request("GET", "readonly-key-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = app.clone().oneshot(request("POST", "readonly-key-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = app.oneshot(request("POST", "writer-key-123")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    #[tokio::test]
+    async fn test_oversized_body_is_rejected_with_413() {
+        use axum::body::Body;
+        use axum::http::{header::CONTENT_LENGTH, header::CONTENT_TYPE, Request, StatusCode};
+        use http_body_util::BodyExt;
+        use tower::ServiceExt;
+
+        let app = build_app(ServerConfig::default().with_max_body_bytes(64));
+        let body = serde_json::json!({
+            "target_services": vec!["chat"; 16],
+            "strategy": "cost_performance_scoring"
+        })
+        .to_string();
+
+        // Rejected from the declared length, before authentication or reading the body
+        let response = app
+            .oneshot(
+                Request::post("/api/v1/optimize")
+                    .header(CONTENT_TYPE, "application/json")
+                    .header(CONTENT_LENGTH, body.len())
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let error: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(error["error"], "payload_too_large");
+    }
+
+    #[tokio::test]
+    async fn test_route_without_policy_is_forbidden() {
+        use crate::middleware::{auth::Claims, rbac::Permission};
+        use axum::body::Body;
+        use axum::http::{Method, Request, StatusCode};
+        use tower::ServiceExt;
+
+        let auth = AuthConfig::new("test-secret".to_string());
+        let claims = Claims::new("admin-1".to_string(), vec!["admin".to_string()], 3600);
+        let token = auth.generate_token(&claims).unwrap();
+        let request = || {
+            Request::get("/api/v1/metrics/cost")
+                .header("authorization", format!("Bearer {}", token))
+                .body(Body::empty())
+                .unwrap()
+        };
+        let config = ServerConfig::default().with_auth(auth);
+
+        let response = build_app(config.clone()).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // Admins get no access to a route the table leaves out
+        let policy =
+            PolicyTable::new().with_rule(Method::GET, "/optimize", Permission::OptimizeRead);
+        let response = build_app(config.with_policy(policy)).oneshot(request()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    }
+
     #[test]
     fn test_app_building() {
         let config = ServerConfig::default();
@@ -0,0 +1,90 @@
+//! Bounded-concurrency fetching for bulk lookups
+
+use crate::CliResult;
+use futures::stream::{self, StreamExt, TryStreamExt};
+use std::future::Future;
+
+/// Default number of requests in flight for bulk fetches
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 4;
+
+/// Upper bound on requests in flight, so bulk fetches can't overwhelm the server
+pub const MAX_FETCH_CONCURRENCY: usize = 8;
+
+/// Fetch every key with at most `concurrency` requests in flight
+///
+/// Results keep the order of `keys` regardless of which request finishes
+/// first. `concurrency` is clamped to `1..=MAX_FETCH_CONCURRENCY`. The first
+/// error stops further requests from being issued, so a rate-limited or
+/// failing server isn't hit with the rest of the batch.
+pub async fn fetch_ordered<'a, K, T, F, Fut>(
+    keys: &'a [K],
+    concurrency: usize,
+    fetch: F,
+) -> CliResult<Vec<T>>
+where
+    F: FnMut(&'a K) -> Fut,
+    Fut: Future<Output = CliResult<T>>,
+{
+    stream::iter(keys)
+        .map(fetch)
+        .buffered(concurrency.clamp(1, MAX_FETCH_CONCURRENCY))
+        .try_collect()
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CliError;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn test_results_keep_key_order() {
+        let keys: Vec<u64> = (1..=10).collect();
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+
+        let results = fetch_ordered(&keys, 100, |&key| {
+            let in_flight = Arc::clone(&in_flight);
+            let peak = Arc::clone(&peak);
+            async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later keys finish first
+                tokio::time::sleep(Duration::from_millis(100 - key * 10)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                Ok(format!("opt-{}", key))
+            }
+        })
+        .await
+        .unwrap();
+
+        let expected: Vec<String> = keys.iter().map(|k| format!("opt-{}", k)).collect();
+        assert_eq!(results, expected);
+        assert_eq!(peak.load(Ordering::SeqCst), MAX_FETCH_CONCURRENCY);
+    }
+
+    #[tokio::test]
+    async fn test_first_error_stops_fetching() {
+        let keys: Vec<u64> = (1..=20).collect();
+        let issued = AtomicUsize::new(0);
+
+        let err = fetch_ordered(&keys, 1, |&key| {
+            issued.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if key == 3 {
+                    Err(CliError::Api("HTTP 429 Too Many Requests: slow down".to_string()))
+                } else {
+                    Ok(key)
+                }
+            }
+        })
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, CliError::Api(_)));
+        assert_eq!(issued.load(Ordering::SeqCst), 3);
+    }
+}
@@ -0,0 +1,88 @@
+//! Gzip encoding of config exports and imports
+
+use crate::{CliError, CliResult};
+use flate2::{read::GzDecoder, write::GzEncoder, Compression};
+use std::io::{Read, Write};
+
+/// First two bytes of every gzip stream
+pub const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Largest decompressed size accepted, in bytes (64 MiB)
+pub const MAX_DECOMPRESSED_BYTES: usize = 64 * 1024 * 1024;
+
+/// Whether `data` starts like a gzip stream
+pub fn is_gzip(data: &[u8]) -> bool {
+    data.starts_with(&GZIP_MAGIC)
+}
+
+/// Gzip `data`
+pub fn compress(data: &[u8]) -> CliResult<Vec<u8>> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder.write_all(data)?;
+    Ok(encoder.finish()?)
+}
+
+/// Gunzip `data`, rejecting it if it inflates past [`MAX_DECOMPRESSED_BYTES`]
+pub fn decompress(data: &[u8]) -> CliResult<Vec<u8>> {
+    decompress_at_most(data, MAX_DECOMPRESSED_BYTES)
+}
+
+fn decompress_at_most(data: &[u8], max_bytes: usize) -> CliResult<Vec<u8>> {
+    let mut decoded = Vec::new();
+    GzDecoder::new(data)
+        .take(max_bytes as u64 + 1)
+        .read_to_end(&mut decoded)
+        .map_err(|e| CliError::InvalidInput(format!("Invalid gzip data: {}", e)))?;
+
+    if decoded.len() > max_bytes {
+        return Err(CliError::InvalidInput(format!(
+            "Gzip data decompresses to more than {} bytes",
+            max_bytes
+        )));
+    }
+    Ok(decoded)
+}
+
+/// Decode config text, gunzipping it first when it is compressed
+pub fn decode_text(data: &[u8]) -> CliResult<String> {
+    let data = if is_gzip(data) {
+        decompress(data)?
+    } else {
+        data.to_vec()
+    };
+    String::from_utf8(data)
+        .map_err(|e| CliError::InvalidInput(format!("Config is not valid UTF-8: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip_and_detection() {
+        let text = r#"{"max_tokens": 1000}"#;
+        let compressed = compress(text.as_bytes()).unwrap();
+
+        assert!(is_gzip(&compressed));
+        assert!(!is_gzip(text.as_bytes()));
+        assert_eq!(decode_text(&compressed).unwrap(), text);
+        assert_eq!(decode_text(text.as_bytes()).unwrap(), text);
+    }
+
+    #[test]
+    fn test_decompressed_size_is_capped() {
+        let compressed = compress(&[b'x'; 4096]).unwrap();
+
+        assert_eq!(decompress_at_most(&compressed, 4096).unwrap().len(), 4096);
+        let err = decompress_at_most(&compressed, 4095).unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_truncated_gzip_is_rejected() {
+        let compressed = compress(b"{}").unwrap();
+
+        let err = decode_text(&compressed[..compressed.len() / 2]).unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+    }
+}
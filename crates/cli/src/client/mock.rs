@@ -0,0 +1,805 @@
+//! In-memory [`ApiClient`] for testing commands without a server
+//!
+//! [`MockClient`] keeps optimizations, config values and integrations in
+//! memory and answers every method from them. Individual methods can be
+//! given a canned response or made to fail with a specific [`CliError`], and
+//! every call is recorded with its arguments as JSON so tests can assert on
+//! what a command sent.
+//!
+//! Available in this crate's tests, and to other crates with the `testing`
+//! feature.
+
+use super::*;
+use futures::stream::{self, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// A call made through [`MockClient`]
+#[derive(Debug, Clone, PartialEq)]
+pub struct MockCall {
+    /// Trait method name, e.g. `"deploy_optimization"`
+    pub method: &'static str,
+    /// Arguments as a JSON object keyed by parameter name, or null
+    pub args: Value,
+}
+
+enum Outcome {
+    Response(Value),
+    Error(Box<dyn Fn() -> CliError + Send + Sync>),
+}
+
+#[derive(Default)]
+struct MockState {
+    calls: Vec<MockCall>,
+    running: bool,
+    next_id: u64,
+    optimizations: BTreeMap<String, OptimizationResponse>,
+    configs: BTreeMap<String, Value>,
+    integrations: BTreeMap<String, IntegrationResponse>,
+}
+
+impl MockState {
+    fn next_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{}-{}", prefix, self.next_id)
+    }
+
+    fn optimization_mut(&mut self, id: &str) -> CliResult<&mut OptimizationResponse> {
+        self.optimizations
+            .get_mut(id)
+            .ok_or_else(|| CliError::NotFound(format!("optimization {}", id)))
+    }
+
+    fn set_status(&mut self, id: &str, status: &str) -> CliResult<OptimizationResponse> {
+        let optimization = self.optimization_mut(id)?;
+        optimization.status = status.to_string();
+        Ok(optimization.clone())
+    }
+}
+
+/// [`ApiClient`] backed by in-memory state, for tests
+pub struct MockClient {
+    state: Mutex<MockState>,
+    outcomes: HashMap<&'static str, Outcome>,
+}
+
+impl MockClient {
+    /// Every [`ApiClient`] method name, as accepted by
+    /// [`MockClient::with_response`] and [`MockClient::with_error`]
+    pub const METHODS: &'static [&'static str] = &[
+        "health_check",
+        "start_service",
+        "stop_service",
+        "restart_service",
+        "get_service_status",
+        "create_optimization",
+        "list_optimizations",
+        "get_optimization",
+        "deploy_optimization",
+        "rollback_optimization",
+        "cancel_optimization",
+        "approve_optimization",
+        "reject_optimization",
+        "get_strategy_leaderboard",
+        "add_optimization_tags",
+        "remove_optimization_tag",
+        "bulk_deploy_optimizations",
+        "bulk_delete_optimizations",
+        "get_config",
+        "set_config",
+        "list_configs",
+        "validate_config",
+        "export_config",
+        "import_config",
+        "watch_config",
+        "query_metrics",
+        "get_performance_metrics",
+        "get_cost_metrics",
+        "get_quality_metrics",
+        "export_metrics",
+        "add_integration",
+        "list_integrations",
+        "test_integration",
+        "remove_integration",
+        "get_stats",
+        "flush_cache",
+        "set_maintenance",
+        "archive_decisions",
+        "get_detailed_health",
+        "get_version",
+        "stream_events",
+    ];
+
+    /// Create a client with empty state and no canned responses
+    pub fn new() -> Self {
+        Self {
+            state: Mutex::new(MockState {
+                running: true,
+                ..MockState::default()
+            }),
+            outcomes: HashMap::new(),
+        }
+    }
+
+    /// Add `optimization` to the in-memory state
+    pub fn with_optimization(self, optimization: OptimizationResponse) -> Self {
+        self.lock()
+            .optimizations
+            .insert(optimization.id.clone(), optimization);
+        self
+    }
+
+    /// Set config `key` in the in-memory state
+    pub fn with_config(self, key: impl Into<String>, value: Value) -> Self {
+        self.lock().configs.insert(key.into(), value);
+        self
+    }
+
+    /// Answer every call to `method` with `response` instead of the
+    /// in-memory state
+    ///
+    /// For streaming methods, `response` is the list of items to yield.
+    ///
+    /// # Panics
+    ///
+    /// If `method` is not an [`ApiClient`] method or `response` cannot be
+    /// serialized.
+    pub fn with_response(mut self, method: &'static str, response: impl Serialize) -> Self {
+        Self::check_method(method);
+        let response = serde_json::to_value(response).expect("canned response must serialize");
+        self.outcomes.insert(method, Outcome::Response(response));
+        self
+    }
+
+    /// Fail every call to `method` with the error returned by `error`
+    ///
+    /// # Panics
+    ///
+    /// If `method` is not an [`ApiClient`] method.
+    pub fn with_error<F>(mut self, method: &'static str, error: F) -> Self
+    where
+        F: Fn() -> CliError + Send + Sync + 'static,
+    {
+        Self::check_method(method);
+        self.outcomes
+            .insert(method, Outcome::Error(Box::new(error)));
+        self
+    }
+
+    /// Every call made so far, oldest first
+    pub fn calls(&self) -> Vec<MockCall> {
+        self.lock().calls.clone()
+    }
+
+    /// Arguments of each call made to `method`, oldest first
+    pub fn calls_to(&self, method: &str) -> Vec<Value> {
+        self.lock()
+            .calls
+            .iter()
+            .filter(|call| call.method == method)
+            .map(|call| call.args.clone())
+            .collect()
+    }
+
+    /// Current in-memory state of optimization `id`
+    pub fn optimization(&self, id: &str) -> Option<OptimizationResponse> {
+        self.lock().optimizations.get(id).cloned()
+    }
+
+    fn check_method(method: &str) {
+        assert!(
+            Self::METHODS.contains(&method),
+            "{} is not an ApiClient method",
+            method
+        );
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, MockState> {
+        self.state.lock().unwrap()
+    }
+
+    /// Record the call, then answer from the canned outcome or `default`
+    fn call<T: DeserializeOwned>(
+        &self,
+        method: &'static str,
+        args: Value,
+        default: impl FnOnce(&mut MockState) -> CliResult<T>,
+    ) -> CliResult<T> {
+        let mut state = self.lock();
+        state.calls.push(MockCall { method, args });
+
+        match self.outcomes.get(method) {
+            Some(Outcome::Error(error)) => Err(error()),
+            Some(Outcome::Response(response)) => serde_json::from_value(response.clone())
+                .map_err(|e| CliError::Serialization(format!("canned {} response: {}", method, e))),
+            None => default(&mut state),
+        }
+    }
+}
+
+impl Default for MockClient {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// An optimization with placeholder values, for seeding a [`MockClient`]
+pub fn sample_optimization(id: &str) -> OptimizationResponse {
+    OptimizationResponse {
+        id: id.to_string(),
+        target_services: vec!["chat".to_string()],
+        strategy: "balanced".to_string(),
+        status: "pending".to_string(),
+        changes: Vec::new(),
+        config: Value::Null,
+        revision: 1,
+        expected_impact: ExpectedImpact {
+            cost_reduction_pct: 0.0,
+            quality_delta_pct: 0.0,
+            latency_delta_pct: 0.0,
+            confidence: 0.0,
+        },
+        actual_impact: None,
+        rationale: String::new(),
+        created_at: Timestamp::now(),
+        deployed_at: None,
+        tags: BTreeMap::new(),
+        cost: None,
+    }
+}
+
+#[async_trait]
+impl ApiClient for MockClient {
+    async fn health_check(&self) -> CliResult<HealthResponse> {
+        self.call("health_check", Value::Null, |_| {
+            Ok(HealthResponse {
+                status: "healthy".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+            })
+        })
+    }
+
+    async fn start_service(&self) -> CliResult<ServiceResponse> {
+        self.call("start_service", Value::Null, |state| {
+            state.running = true;
+            Ok(ServiceResponse {
+                status: "running".to_string(),
+                message: "Service started".to_string(),
+            })
+        })
+    }
+
+    async fn stop_service(&self) -> CliResult<ServiceResponse> {
+        self.call("stop_service", Value::Null, |state| {
+            state.running = false;
+            Ok(ServiceResponse {
+                status: "stopped".to_string(),
+                message: "Service stopped".to_string(),
+            })
+        })
+    }
+
+    async fn restart_service(&self) -> CliResult<ServiceResponse> {
+        self.call("restart_service", Value::Null, |state| {
+            state.running = true;
+            Ok(ServiceResponse {
+                status: "running".to_string(),
+                message: "Service restarted".to_string(),
+            })
+        })
+    }
+
+    async fn get_service_status(&self) -> CliResult<ServiceStatusResponse> {
+        self.call("get_service_status", Value::Null, |state| {
+            Ok(ServiceStatusResponse {
+                running: state.running,
+                uptime_seconds: 0,
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                active_optimizations: state
+                    .optimizations
+                    .values()
+                    .filter(|optimization| optimization.status == "deployed")
+                    .count(),
+            })
+        })
+    }
+
+    async fn create_optimization(
+        &self,
+        request: CreateOptimizationRequest,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "request": request });
+        self.call("create_optimization", args, |state| {
+            let mut optimization = sample_optimization(&state.next_id("opt"));
+            optimization.target_services = request.target_services;
+            optimization.strategy = request.strategy;
+            optimization.config = request.config;
+            optimization.tags = request.tags;
+            state
+                .optimizations
+                .insert(optimization.id.clone(), optimization.clone());
+            Ok(optimization)
+        })
+    }
+
+    async fn list_optimizations(
+        &self,
+        query: ListOptimizationsQuery,
+    ) -> CliResult<Vec<OptimizationResponse>> {
+        let args = json!({ "query": query });
+        self.call("list_optimizations", args, |state| {
+            Ok(state
+                .optimizations
+                .values()
+                .filter(|o| query.status.iter().all(|s| &o.status == s))
+                .filter(|o| query.strategy.iter().all(|s| &o.strategy == s))
+                .filter(|o| query.service.iter().all(|s| o.target_services.contains(s)))
+                .filter(|o| query.tags.iter().all(|(k, v)| o.tags.get(k) == Some(v)))
+                .cloned()
+                .collect())
+        })
+    }
+
+    async fn get_optimization(&self, id: &str) -> CliResult<OptimizationResponse> {
+        self.call("get_optimization", json!({ "id": id }), |state| {
+            Ok(state.optimization_mut(id)?.clone())
+        })
+    }
+
+    async fn deploy_optimization(
+        &self,
+        id: &str,
+        request: DeployOptimizationRequest,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "id": id, "request": request });
+        self.call("deploy_optimization", args, |state| {
+            let optimization = state.optimization_mut(id)?;
+            optimization.status = "deployed".to_string();
+            optimization.deployed_at = Some(Timestamp::now());
+            Ok(optimization.clone())
+        })
+    }
+
+    async fn rollback_optimization(
+        &self,
+        id: &str,
+        request: RollbackOptimizationRequest,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "id": id, "request": request });
+        self.call("rollback_optimization", args, |state| {
+            state.set_status(id, "rolled_back")
+        })
+    }
+
+    async fn cancel_optimization(&self, id: &str) -> CliResult<OptimizationResponse> {
+        self.call("cancel_optimization", json!({ "id": id }), |state| {
+            state.set_status(id, "cancelled")
+        })
+    }
+
+    async fn approve_optimization(&self, id: &str) -> CliResult<OptimizationResponse> {
+        self.call("approve_optimization", json!({ "id": id }), |state| {
+            state.set_status(id, "approved")
+        })
+    }
+
+    async fn reject_optimization(
+        &self,
+        id: &str,
+        request: RejectOptimizationRequest,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "id": id, "request": request });
+        self.call("reject_optimization", args, |state| {
+            state.set_status(id, "rejected")
+        })
+    }
+
+    async fn get_strategy_leaderboard(&self) -> CliResult<Vec<StrategyLeaderboardEntry>> {
+        self.call("get_strategy_leaderboard", Value::Null, |_| Ok(Vec::new()))
+    }
+
+    async fn add_optimization_tags(
+        &self,
+        id: &str,
+        tags: BTreeMap<String, String>,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "id": id, "tags": tags });
+        self.call("add_optimization_tags", args, |state| {
+            let optimization = state.optimization_mut(id)?;
+            optimization.tags.extend(tags);
+            Ok(optimization.clone())
+        })
+    }
+
+    async fn remove_optimization_tag(
+        &self,
+        id: &str,
+        key: &str,
+    ) -> CliResult<OptimizationResponse> {
+        let args = json!({ "id": id, "key": key });
+        self.call("remove_optimization_tag", args, |state| {
+            let optimization = state.optimization_mut(id)?;
+            optimization.tags.remove(key);
+            Ok(optimization.clone())
+        })
+    }
+
+    async fn bulk_deploy_optimizations(
+        &self,
+        request: BulkDeployRequest,
+    ) -> CliResult<Vec<BulkItemResult>> {
+        let args = json!({ "request": request });
+        self.call("bulk_deploy_optimizations", args, |state| {
+            Ok(request
+                .ids
+                .iter()
+                .map(|id| match state.set_status(id, "deployed") {
+                    Ok(_) => BulkItemResult {
+                        id: id.clone(),
+                        status: 200,
+                        error: None,
+                    },
+                    Err(e) => BulkItemResult {
+                        id: id.clone(),
+                        status: 404,
+                        error: Some(e.to_string()),
+                    },
+                })
+                .collect())
+        })
+    }
+
+    async fn bulk_delete_optimizations(&self, ids: &[String]) -> CliResult<Vec<BulkItemResult>> {
+        self.call(
+            "bulk_delete_optimizations",
+            json!({ "ids": ids }),
+            |state| {
+                Ok(ids
+                    .iter()
+                    .map(|id| match state.optimizations.remove(id) {
+                        Some(_) => BulkItemResult {
+                            id: id.clone(),
+                            status: 204,
+                            error: None,
+                        },
+                        None => BulkItemResult {
+                            id: id.clone(),
+                            status: 404,
+                            error: Some(format!("Not found: optimization {}", id)),
+                        },
+                    })
+                    .collect())
+            },
+        )
+    }
+
+    async fn get_config(&self, key: &str) -> CliResult<ConfigValue> {
+        self.call("get_config", json!({ "key": key }), |state| {
+            let value = state
+                .configs
+                .get(key)
+                .ok_or_else(|| CliError::NotFound(format!("config key {}", key)))?;
+            Ok(ConfigValue {
+                key: key.to_string(),
+                value: value.clone(),
+                description: None,
+            })
+        })
+    }
+
+    async fn set_config(&self, key: &str, value: Value) -> CliResult<ConfigValue> {
+        let args = json!({ "key": key, "value": value });
+        self.call("set_config", args, |state| {
+            state.configs.insert(key.to_string(), value.clone());
+            Ok(ConfigValue {
+                key: key.to_string(),
+                value,
+                description: None,
+            })
+        })
+    }
+
+    async fn list_configs(&self) -> CliResult<Vec<ConfigEntry>> {
+        self.call("list_configs", Value::Null, |state| {
+            Ok(state
+                .configs
+                .iter()
+                .map(|(key, value)| ConfigEntry {
+                    key: key.clone(),
+                    value: value.clone(),
+                    description: None,
+                    default_value: None,
+                })
+                .collect())
+        })
+    }
+
+    async fn validate_config(&self) -> CliResult<ValidationResult> {
+        self.call("validate_config", Value::Null, |_| {
+            Ok(ValidationResult {
+                valid: true,
+                errors: Vec::new(),
+                warnings: Vec::new(),
+            })
+        })
+    }
+
+    async fn export_config(&self) -> CliResult<String> {
+        self.call("export_config", Value::Null, |state| {
+            Ok(serde_json::to_string_pretty(&state.configs)?)
+        })
+    }
+
+    async fn import_config(&self, config: &str, _compress: bool) -> CliResult<()> {
+        self.call("import_config", json!({ "config": config }), |state| {
+            let configs: BTreeMap<String, Value> = serde_json::from_str(config).map_err(|e| {
+                CliError::InvalidInput(format!("config must be a JSON object: {}", e))
+            })?;
+            state.configs.extend(configs);
+            Ok(())
+        })
+    }
+
+    async fn watch_config(&self) -> CliResult<ConfigChangeStream> {
+        let events: Vec<ConfigChangeEvent> =
+            self.call("watch_config", Value::Null, |_| Ok(Vec::new()))?;
+        Ok(stream::iter(events.into_iter().map(Ok)).boxed())
+    }
+
+    async fn query_metrics(&self, query: MetricsQuery) -> CliResult<MetricsResponse> {
+        self.call("query_metrics", json!({ "query": query }), |_| {
+            Ok(MetricsResponse {
+                metrics: Vec::new(),
+            })
+        })
+    }
+
+    async fn get_performance_metrics(
+        &self,
+        query: PerformanceQuery,
+    ) -> CliResult<PerformanceMetrics> {
+        self.call("get_performance_metrics", json!({ "query": query }), |_| {
+            Ok(PerformanceMetrics {
+                avg_latency_ms: 0.0,
+                p50_latency_ms: 0.0,
+                p95_latency_ms: 0.0,
+                p99_latency_ms: 0.0,
+                throughput_rps: 0.0,
+                error_rate: 0.0,
+            })
+        })
+    }
+
+    async fn get_cost_metrics(&self, query: CostQuery) -> CliResult<CostMetrics> {
+        self.call("get_cost_metrics", json!({ "query": query }), |_| {
+            Ok(CostMetrics {
+                total_cost: 0.0,
+                cost_per_request: 0.0,
+                cost_breakdown: Vec::new(),
+            })
+        })
+    }
+
+    async fn get_quality_metrics(&self, query: QualityQuery) -> CliResult<QualityMetrics> {
+        self.call("get_quality_metrics", json!({ "query": query }), |_| {
+            Ok(QualityMetrics {
+                avg_quality_score: 0.0,
+                quality_distribution: Vec::new(),
+                total_requests: 0,
+            })
+        })
+    }
+
+    async fn export_metrics(&self, query: ExportMetricsQuery) -> CliResult<String> {
+        self.call("export_metrics", json!({ "query": query }), |_| {
+            Ok(String::new())
+        })
+    }
+
+    async fn add_integration(
+        &self,
+        request: AddIntegrationRequest,
+    ) -> CliResult<IntegrationResponse> {
+        let args = json!({ "request": request });
+        self.call("add_integration", args, |state| {
+            let integration = IntegrationResponse {
+                id: state.next_id("int"),
+                integration_type: request.integration_type,
+                name: request.name,
+                status: "active".to_string(),
+                created_at: Timestamp::now().to_rfc3339(),
+            };
+            state
+                .integrations
+                .insert(integration.id.clone(), integration.clone());
+            Ok(integration)
+        })
+    }
+
+    async fn list_integrations(&self) -> CliResult<Vec<IntegrationResponse>> {
+        self.call("list_integrations", Value::Null, |state| {
+            Ok(state.integrations.values().cloned().collect())
+        })
+    }
+
+    async fn test_integration(&self, id: &str) -> CliResult<TestIntegrationResponse> {
+        self.call("test_integration", json!({ "id": id }), |state| {
+            if !state.integrations.contains_key(id) {
+                return Err(CliError::NotFound(format!("integration {}", id)));
+            }
+            Ok(TestIntegrationResponse {
+                success: true,
+                message: "Connection successful".to_string(),
+                details: None,
+            })
+        })
+    }
+
+    async fn remove_integration(&self, id: &str) -> CliResult<()> {
+        self.call("remove_integration", json!({ "id": id }), |state| {
+            state
+                .integrations
+                .remove(id)
+                .map(|_| ())
+                .ok_or_else(|| CliError::NotFound(format!("integration {}", id)))
+        })
+    }
+
+    async fn get_stats(&self) -> CliResult<SystemStats> {
+        self.call("get_stats", Value::Null, |state| {
+            let active = state
+                .optimizations
+                .values()
+                .filter(|optimization| optimization.status == "deployed")
+                .count();
+            Ok(SystemStats {
+                uptime_seconds: 0,
+                total_optimizations: state.optimizations.len() as u64,
+                active_optimizations: active as u64,
+                total_cost_saved: 0.0,
+                memory_usage_bytes: 0,
+                cpu_usage_percent: 0.0,
+            })
+        })
+    }
+
+    async fn flush_cache(&self) -> CliResult<CacheFlushResponse> {
+        self.call("flush_cache", Value::Null, |_| {
+            Ok(CacheFlushResponse {
+                entries_flushed: 0,
+                message: "Cache flushed".to_string(),
+            })
+        })
+    }
+
+    async fn set_maintenance(
+        &self,
+        request: SetMaintenanceRequest,
+    ) -> CliResult<MaintenanceStatus> {
+        let args = json!({ "request": request });
+        self.call("set_maintenance", args, |_| {
+            Ok(MaintenanceStatus {
+                enabled: request.enabled,
+                message: request.message,
+                since: request.enabled.then(Timestamp::now),
+            })
+        })
+    }
+
+    async fn archive_decisions(
+        &self,
+        request: ArchiveDecisionsRequest,
+    ) -> CliResult<ArchiveDecisionsResponse> {
+        let args = json!({ "request": request });
+        self.call("archive_decisions", args, |_| {
+            Ok(ArchiveDecisionsResponse {
+                archived: 0,
+                deleted: 0,
+                retained: 0,
+                archived_total: 0,
+            })
+        })
+    }
+
+    async fn get_detailed_health(&self) -> CliResult<DetailedHealthResponse> {
+        self.call("get_detailed_health", Value::Null, |_| {
+            Ok(DetailedHealthResponse {
+                status: "healthy".to_string(),
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                components: Vec::new(),
+            })
+        })
+    }
+
+    async fn get_version(&self) -> CliResult<VersionInfo> {
+        self.call("get_version", Value::Null, |_| {
+            Ok(VersionInfo {
+                version: env!("CARGO_PKG_VERSION").to_string(),
+                build_date: "unknown".to_string(),
+                commit_hash: "unknown".to_string(),
+                rust_version: "unknown".to_string(),
+            })
+        })
+    }
+
+    async fn stream_events(&self, filter: EventFilter) -> CliResult<EventStream> {
+        let events: Vec<OptimizerEvent> =
+            self.call("stream_events", json!({ "filter": filter }), |_| {
+                Ok(Vec::new())
+            })?;
+        Ok(stream::iter(events.into_iter().map(Ok)).boxed())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_state_is_shared_between_calls() {
+        let client = MockClient::new().with_optimization(sample_optimization("opt-1"));
+
+        client
+            .add_optimization_tags(
+                "opt-1",
+                BTreeMap::from([("team".to_string(), "search".to_string())]),
+            )
+            .await
+            .unwrap();
+        let listed = client
+            .list_optimizations(ListOptimizationsQuery {
+                status: None,
+                strategy: None,
+                service: None,
+                from: None,
+                to: None,
+                tags: vec![("team".to_string(), "search".to_string())],
+                page: None,
+                page_size: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(listed.len(), 1);
+        assert!(matches!(
+            client.get_optimization("opt-2").await,
+            Err(CliError::NotFound(_))
+        ));
+        assert_eq!(client.calls().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_canned_responses_and_errors_override_state() {
+        let client = MockClient::new()
+            .with_response(
+                "get_version",
+                json!({
+                    "version": "9.9.9",
+                    "build_date": "2024-01-01",
+                    "commit_hash": "abc123",
+                    "rust_version": "1.80.0"
+                }),
+            )
+            .with_error("flush_cache", || {
+                CliError::PermissionDenied("admin only".to_string())
+            });
+
+        assert_eq!(client.get_version().await.unwrap().version, "9.9.9");
+        assert!(matches!(
+            client.flush_cache().await,
+            Err(CliError::PermissionDenied(_))
+        ));
+        assert!(matches!(
+            client.flush_cache().await,
+            Err(CliError::PermissionDenied(_))
+        ));
+    }
+
+    #[test]
+    #[should_panic(expected = "not an ApiClient method")]
+    fn test_unknown_method_is_rejected() {
+        let _ = MockClient::new().with_error("deploy", || CliError::Api("boom".to_string()));
+    }
+}
@@ -1,12 +1,28 @@
 //! API client implementations
 
+pub mod bulk;
+pub mod gzip;
+#[cfg(any(test, feature = "testing"))]
+pub mod mock;
 pub mod rest;
+pub mod retry;
+pub mod timestamp;
+pub mod tls;
 
+pub use bulk::fetch_ordered;
+#[cfg(any(test, feature = "testing"))]
+pub use mock::MockClient;
 pub use rest::RestClient;
+pub use retry::{RetryHistory, RetryPolicy};
+pub use timestamp::Timestamp;
+pub use tls::ClientTls;
 
 use crate::{CliError, CliResult};
 use async_trait::async_trait;
+use futures::stream::BoxStream;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 use std::time::Duration;
 
 /// Client configuration
@@ -14,8 +30,55 @@ use std::time::Duration;
 pub struct ClientConfig {
     pub base_url: String,
     pub api_key: Option<String>,
+    /// Total request timeout, applied as a cap on connect and read timeouts
     pub timeout: Duration,
-}
+    /// Timeout for establishing the connection
+    pub connect_timeout: Option<Duration>,
+    /// Timeout between reads of the response
+    pub read_timeout: Option<Duration>,
+    /// Retries for transient failures of idempotent requests
+    pub retry: RetryPolicy,
+    /// Idle connections kept open per host; unlimited when unset
+    pub pool_max_idle_per_host: Option<usize>,
+    /// How long an idle connection stays pooled; 90 seconds when unset
+    pub pool_idle_timeout: Option<Duration>,
+    /// Interval between TCP keep-alive probes; 15 seconds when unset
+    pub tcp_keepalive: Option<Duration>,
+    /// Speak HTTP/2 without negotiating it first, for HTTP/2-only gateways
+    pub http2_prior_knowledge: bool,
+    /// PEM client certificate presented for mutual TLS
+    pub client_cert_path: Option<PathBuf>,
+    /// PEM private key for `client_cert_path`
+    pub client_key_path: Option<PathBuf>,
+    /// PEM CA bundle trusted in addition to the system roots
+    pub ca_cert_path: Option<PathBuf>,
+}
+
+impl Default for ClientConfig {
+    fn default() -> Self {
+        Self {
+            base_url: "http://localhost:8080".to_string(),
+            api_key: None,
+            timeout: Duration::from_secs(30),
+            connect_timeout: None,
+            read_timeout: None,
+            retry: RetryPolicy::default(),
+            pool_max_idle_per_host: None,
+            pool_idle_timeout: None,
+            tcp_keepalive: None,
+            http2_prior_knowledge: false,
+            client_cert_path: None,
+            client_key_path: None,
+            ca_cert_path: None,
+        }
+    }
+}
+
+/// Stream of optimizer events; ends with an error if the connection drops
+pub type EventStream = BoxStream<'static, CliResult<OptimizerEvent>>;
+
+/// Stream of server config changes; ends with an error if the connection drops
+pub type ConfigChangeStream = BoxStream<'static, CliResult<ConfigChangeEvent>>;
 
 /// Unified API client interface
 #[async_trait]
@@ -50,6 +113,24 @@ pub trait ApiClient: Send + Sync {
         request: RollbackOptimizationRequest,
     ) -> CliResult<OptimizationResponse>;
     async fn cancel_optimization(&self, id: &str) -> CliResult<OptimizationResponse>;
+    async fn approve_optimization(&self, id: &str) -> CliResult<OptimizationResponse>;
+    async fn reject_optimization(
+        &self,
+        id: &str,
+        request: RejectOptimizationRequest,
+    ) -> CliResult<OptimizationResponse>;
+    async fn get_strategy_leaderboard(&self) -> CliResult<Vec<StrategyLeaderboardEntry>>;
+    async fn add_optimization_tags(
+        &self,
+        id: &str,
+        tags: BTreeMap<String, String>,
+    ) -> CliResult<OptimizationResponse>;
+    async fn remove_optimization_tag(&self, id: &str, key: &str) -> CliResult<OptimizationResponse>;
+    async fn bulk_deploy_optimizations(
+        &self,
+        request: BulkDeployRequest,
+    ) -> CliResult<Vec<BulkItemResult>>;
+    async fn bulk_delete_optimizations(&self, ids: &[String]) -> CliResult<Vec<BulkItemResult>>;
 
     /// Configuration operations
     async fn get_config(&self, key: &str) -> CliResult<ConfigValue>;
@@ -57,7 +138,9 @@ pub trait ApiClient: Send + Sync {
     async fn list_configs(&self) -> CliResult<Vec<ConfigEntry>>;
     async fn validate_config(&self) -> CliResult<ValidationResult>;
     async fn export_config(&self) -> CliResult<String>;
-    async fn import_config(&self, config: &str) -> CliResult<()>;
+    /// Import a config export, gzipping the upload when `compress` is set
+    async fn import_config(&self, config: &str, compress: bool) -> CliResult<()>;
+    async fn watch_config(&self) -> CliResult<ConfigChangeStream>;
 
     /// Metrics operations
     async fn query_metrics(&self, query: MetricsQuery) -> CliResult<MetricsResponse>;
@@ -77,8 +160,15 @@ pub trait ApiClient: Send + Sync {
     /// Admin operations
     async fn get_stats(&self) -> CliResult<SystemStats>;
     async fn flush_cache(&self) -> CliResult<CacheFlushResponse>;
+    async fn set_maintenance(&self, request: SetMaintenanceRequest)
+        -> CliResult<MaintenanceStatus>;
+    async fn archive_decisions(&self, request: ArchiveDecisionsRequest)
+        -> CliResult<ArchiveDecisionsResponse>;
     async fn get_detailed_health(&self) -> CliResult<DetailedHealthResponse>;
     async fn get_version(&self) -> CliResult<VersionInfo>;
+
+    /// Event operations
+    async fn stream_events(&self, filter: EventFilter) -> CliResult<EventStream>;
 }
 
 // Request/Response types
@@ -110,6 +200,8 @@ pub struct CreateOptimizationRequest {
     pub config: serde_json::Value,
     pub constraints: Vec<ConstraintInput>,
     pub dry_run: bool,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub tags: BTreeMap<String, String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,11 +218,27 @@ pub struct OptimizationResponse {
     pub strategy: String,
     pub status: String,
     pub changes: Vec<ConfigurationChange>,
+    #[serde(default)]
+    pub config: serde_json::Value,
+    #[serde(default)]
+    pub revision: u32,
     pub expected_impact: ExpectedImpact,
     pub actual_impact: Option<ActualImpact>,
     pub rationale: String,
-    pub created_at: String,
-    pub deployed_at: Option<String>,
+    pub created_at: Timestamp,
+    pub deployed_at: Option<Timestamp>,
+    #[serde(default)]
+    pub tags: BTreeMap<String, String>,
+    /// LLM cost of the request that returned this optimization, if reported
+    #[serde(skip)]
+    pub cost: Option<OperationCost>,
+}
+
+/// LLM usage the server reported for a single operation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OperationCost {
+    pub cost_usd: f64,
+    pub tokens_used: u64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -155,17 +263,28 @@ pub struct ActualImpact {
     pub quality_delta_pct: f64,
     pub latency_delta_pct: f64,
     pub requests_affected: u64,
-    pub measured_from: String,
-    pub measured_until: String,
+    pub measured_from: Timestamp,
+    pub measured_until: Timestamp,
 }
 
+/// Page size requested by list commands when none is given; the server may
+/// clamp larger sizes to its own maximum
+pub const DEFAULT_PAGE_SIZE: u32 = 20;
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ListOptimizationsQuery {
     pub status: Option<String>,
     pub strategy: Option<String>,
     pub service: Option<String>,
-    pub from: Option<String>,
-    pub to: Option<String>,
+    pub from: Option<Timestamp>,
+    pub to: Option<Timestamp>,
+    /// Tags that must all match, as `(key, value)` pairs
+    #[serde(default)]
+    pub tags: Vec<(String, String)>,
+    /// Page number, starting from 1
+    pub page: Option<u32>,
+    /// Items per page; [`DEFAULT_PAGE_SIZE`] when unset
+    pub page_size: Option<u32>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -177,6 +296,50 @@ pub struct DeployOptimizationRequest {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RollbackOptimizationRequest {
     pub reason: String,
+    /// Revision to restore; the server defaults to the previous revision
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub to_revision: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RejectOptimizationRequest {
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BulkDeployRequest {
+    pub ids: Vec<String>,
+    pub gradual: bool,
+    pub rollout_percentage: f64,
+}
+
+/// Outcome of one item of a bulk operation, from a `207 Multi-Status` response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BulkItemResult {
+    pub id: String,
+    /// HTTP status the item would have produced as a single request
+    pub status: u16,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BulkItemResult {
+    /// Whether the item succeeded
+    pub fn is_success(&self) -> bool {
+        (200..300).contains(&self.status)
+    }
+}
+
+/// Measured performance of one strategy, ranked by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyLeaderboardEntry {
+    pub rank: u32,
+    pub strategy: String,
+    pub optimizations: u64,
+    pub avg_cost_reduction_pct: f64,
+    pub avg_quality_delta_pct: f64,
+    pub avg_latency_delta_pct: f64,
+    pub success_rate: f64,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -194,11 +357,64 @@ pub struct ConfigEntry {
     pub default_value: Option<serde_json::Value>,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConfigChangeEvent {
+    pub id: String,
+    pub key: String,
+    pub old_value: Option<serde_json::Value>,
+    pub new_value: serde_json::Value,
+    pub changed_by: String,
+    pub changed_at: Timestamp,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ValidationResult {
     pub valid: bool,
-    pub errors: Vec<String>,
-    pub warnings: Vec<String>,
+    #[serde(default)]
+    pub errors: Vec<ValidationIssue>,
+    #[serde(default)]
+    pub warnings: Vec<ValidationIssue>,
+}
+
+/// A single validation error or warning, optionally tied to a config field.
+///
+/// Deserializes from either a plain message string or a
+/// `{"field": ..., "message": ...}` object.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(from = "ValidationIssueRepr")]
+pub struct ValidationIssue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub field: Option<String>,
+    pub message: String,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum ValidationIssueRepr {
+    Message(String),
+    Detailed {
+        #[serde(default)]
+        field: Option<String>,
+        message: String,
+    },
+}
+
+impl From<ValidationIssueRepr> for ValidationIssue {
+    fn from(repr: ValidationIssueRepr) -> Self {
+        match repr {
+            ValidationIssueRepr::Message(message) => Self { field: None, message },
+            ValidationIssueRepr::Detailed { field, message } => Self { field, message },
+        }
+    }
+}
+
+impl std::fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.field {
+            Some(field) => write!(f, "{}: {}", field, self.message),
+            None => f.write_str(&self.message),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -325,6 +541,36 @@ pub struct SystemStats {
     pub cpu_usage_percent: f64,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetMaintenanceRequest {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceStatus {
+    pub enabled: bool,
+    pub message: Option<String>,
+    pub since: Option<Timestamp>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ArchiveDecisionsRequest {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_age_days: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub keep_last: Option<usize>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveDecisionsResponse {
+    pub archived: u64,
+    pub deleted: u64,
+    pub retained: u64,
+    pub archived_total: u64,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CacheFlushResponse {
     pub entries_flushed: u64,
@@ -352,3 +598,19 @@ pub struct VersionInfo {
     pub commit_hash: String,
     pub rust_version: String,
 }
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct EventFilter {
+    pub event_type: Option<String>,
+    pub service: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OptimizerEvent {
+    pub id: String,
+    pub event_type: String,
+    pub optimization_id: Option<String>,
+    pub service: Option<String>,
+    pub message: String,
+    pub timestamp: Timestamp,
+}
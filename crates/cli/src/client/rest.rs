@@ -1,27 +1,68 @@
 //! REST API client implementation
 
 use super::*;
+use crate::timing::{self, Phase};
 use crate::{CliError, CliResult};
 use async_trait::async_trait;
+use eventsource_stream::Eventsource;
+use futures::{stream, StreamExt};
 use reqwest::{Client, RequestBuilder};
 use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+
+/// Maximum number of characters of a non-JSON error body kept in error messages
+const ERROR_SNIPPET_LEN: usize = 200;
+
+/// Read timeout for event streams when none is configured; the server sends
+/// keep-alives well within this interval, so silence means a dead connection
+const STREAM_IDLE_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Response header carrying the LLM cost of a request, in USD
+const COST_USD_HEADER: &str = "x-cost-usd";
+
+/// Response header carrying the number of LLM tokens a request used
+const TOKENS_USED_HEADER: &str = "x-tokens-used";
+
+/// Read the LLM cost headers, if the server sent them
+fn operation_cost(headers: &reqwest::header::HeaderMap) -> Option<OperationCost> {
+    let header = |name| headers.get(name)?.to_str().ok();
+    Some(OperationCost {
+        cost_usd: header(COST_USD_HEADER)?.parse().ok()?,
+        tokens_used: header(TOKENS_USED_HEADER)
+            .and_then(|v| v.parse().ok())
+            .unwrap_or(0),
+    })
+}
 
 /// REST API client
 pub struct RestClient {
     client: Client,
+    /// Client without a total timeout, for long-lived streams
+    stream_client: Client,
     config: ClientConfig,
 }
 
 impl RestClient {
     /// Create a new REST client
-    pub fn new(config: ClientConfig) -> CliResult<Self> {
+    ///
+    /// Trailing slashes on the base URL are ignored, so `http://host/` and
+    /// `http://host` are equivalent. A path prefix such as
+    /// `http://host/optimizer` is kept in front of every request path.
+    pub fn new(mut config: ClientConfig) -> CliResult<Self> {
+        config.base_url = config.base_url.trim_end_matches('/').to_string();
+
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
             reqwest::header::CONTENT_TYPE,
             reqwest::header::HeaderValue::from_static("application/json"),
         );
 
-        if let Some(api_key) = &config.api_key {
+        let api_key = config
+            .api_key
+            .clone()
+            .or_else(|| crate::credentials::load_api_key(&config.base_url));
+
+        if let Some(api_key) = &api_key {
             headers.insert(
                 reqwest::header::AUTHORIZATION,
                 reqwest::header::HeaderValue::from_str(&format!("Bearer {}", api_key))
@@ -29,66 +70,298 @@ impl RestClient {
             );
         }
 
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .default_headers(headers)
+        let tls = ClientTls::load(&config)?;
+
+        let base_builder = || -> CliResult<reqwest::ClientBuilder> {
+            let mut builder = Client::builder().default_headers(headers.clone());
+            if let Some(keepalive) = config.tcp_keepalive {
+                builder = builder.tcp_keepalive(keepalive);
+            }
+            if let Some(connect_timeout) = config.connect_timeout {
+                builder = builder.connect_timeout(connect_timeout.min(config.timeout));
+            }
+            if let Some(max_idle) = config.pool_max_idle_per_host {
+                builder = builder.pool_max_idle_per_host(max_idle);
+            }
+            if let Some(idle_timeout) = config.pool_idle_timeout {
+                builder = builder.pool_idle_timeout(idle_timeout);
+            }
+            if config.http2_prior_knowledge {
+                builder = builder.http2_prior_knowledge();
+            }
+            if let Some(tls) = &tls {
+                builder = tls.apply(builder)?;
+            }
+            Ok(builder)
+        };
+
+        let stream_client = base_builder()?
+            .read_timeout(config.read_timeout.unwrap_or(STREAM_IDLE_TIMEOUT))
             .build()?;
 
-        Ok(Self { client, config })
+        let mut builder = base_builder()?.timeout(config.timeout);
+        if let Some(read_timeout) = config.read_timeout {
+            builder = builder.read_timeout(read_timeout.min(config.timeout));
+        }
+
+        let client = builder.build()?;
+
+        Ok(Self {
+            client,
+            stream_client,
+            config,
+        })
     }
 
-    /// Make a GET request
+    /// Full URL for an API path, with exactly one slash between the base URL
+    /// and the path
+    fn url(&self, path: &str) -> String {
+        format!("{}/{}", self.config.base_url, path.trim_start_matches('/'))
+    }
+
+    /// Open a Server-Sent Events stream and decode each event's JSON data
+    async fn sse_stream<T>(&self, url: &str) -> CliResult<BoxStream<'static, CliResult<T>>>
+    where
+        T: DeserializeOwned + Send + 'static,
+    {
+        let request = self
+            .stream_client
+            .get(url)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await);
+        }
+
+        let events = response
+            .bytes_stream()
+            .eventsource()
+            .filter_map(|item| async move {
+                match item {
+                    Ok(event) if event.data.is_empty() => None,
+                    Ok(event) => Some(serde_json::from_str::<T>(&event.data).map_err(CliError::from)),
+                    Err(e) => Some(Err(CliError::Api(format!("Event stream interrupted: {}", e)))),
+                }
+            });
+
+        // The server never ends the stream on its own, so EOF means the
+        // connection was dropped
+        let closed = stream::once(async {
+            Err(CliError::Api("Event stream closed by server".to_string()))
+        });
+
+        Ok(events.chain(closed).boxed())
+    }
+
+    /// Make a GET request, retrying transient failures
     async fn get<T: DeserializeOwned>(&self, path: &str) -> CliResult<T> {
-        let url = format!("{}{}", self.config.base_url, path);
-        let response = self.client.get(&url).send().await?;
+        let url = self.url(path);
+        let response = self.send_with_retry(self.client.get(&url)).await?;
         self.handle_response(response).await
     }
 
     /// Make a POST request
     async fn post<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> CliResult<T> {
-        let url = format!("{}{}", self.config.base_url, path);
-        let response = self.client.post(&url).json(body).send().await?;
+        let url = self.url(path);
+        let response = self.send(self.client.post(&url).json(body)).await?;
         self.handle_response(response).await
     }
 
+    /// POST an optimization request, attaching any LLM cost the server reports
+    async fn post_optimization<B: Serialize>(
+        &self,
+        path: &str,
+        body: &B,
+    ) -> CliResult<OptimizationResponse> {
+        let url = self.url(path);
+        let response = self.send(self.client.post(&url).json(body)).await?;
+        let cost = operation_cost(response.headers());
+        let mut optimization: OptimizationResponse = self.handle_response(response).await?;
+        optimization.cost = cost;
+        Ok(optimization)
+    }
+
+    /// POST a bulk operation and read its per-item results
+    ///
+    /// Bulk endpoints answer `207 Multi-Status` with an outcome for every
+    /// item, so a partly failed batch is a successful response here.
+    async fn post_bulk<B: Serialize>(&self, path: &str, body: &B) -> CliResult<Vec<BulkItemResult>> {
+        #[derive(Deserialize)]
+        struct BulkResponse {
+            items: Vec<BulkItemResult>,
+        }
+
+        let url = self.url(path);
+        let response = self.send(self.client.post(&url).json(body)).await?;
+        let results: BulkResponse = self.handle_response(response).await?;
+        Ok(results.items)
+    }
+
     /// Make a PUT request
     async fn put<B: Serialize, T: DeserializeOwned>(&self, path: &str, body: &B) -> CliResult<T> {
-        let url = format!("{}{}", self.config.base_url, path);
-        let response = self.client.put(&url).json(body).send().await?;
+        let url = self.url(path);
+        let response = self.send(self.client.put(&url).json(body)).await?;
         self.handle_response(response).await
     }
 
     /// Make a DELETE request
     async fn delete<T: DeserializeOwned>(&self, path: &str) -> CliResult<T> {
-        let url = format!("{}{}", self.config.base_url, path);
-        let response = self.client.delete(&url).send().await?;
+        let url = self.url(path);
+        let response = self.send(self.client.delete(&url)).await?;
         self.handle_response(response).await
     }
 
     /// Make a DELETE request without response body
     async fn delete_no_content(&self, path: &str) -> CliResult<()> {
-        let url = format!("{}{}", self.config.base_url, path);
-        let response = self.client.delete(&url).send().await?;
+        let url = self.url(path);
+        let response = self.send(self.client.delete(&url)).await?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(self.map_error(status, &error_text))
+            Err(self.error_from_response(response).await)
+        }
+    }
+
+    /// Send a request, reporting which timeout tripped if it times out
+    async fn send(&self, request: RequestBuilder) -> CliResult<reqwest::Response> {
+        let started = Instant::now();
+        let _span = timing::span(Phase::RequestSend);
+        request
+            .send()
+            .await
+            .map_err(|e| self.map_send_error(e, started.elapsed()))
+    }
+
+    /// Send an idempotent request, retrying timeouts, connection failures and
+    /// 429/502/503/504 responses with exponential backoff
+    ///
+    /// When the last allowed attempt still fails, its error is returned with
+    /// the history of every attempt attached.
+    async fn send_with_retry(&self, request: RequestBuilder) -> CliResult<reqwest::Response> {
+        let policy = self.config.retry;
+        let started = Instant::now();
+        let mut history = RetryHistory::default();
+
+        loop {
+            let attempt_started = Instant::now();
+            let attempt = request
+                .try_clone()
+                .expect("idempotent requests have no streaming body");
+
+            let sent = {
+                let _span = timing::span(Phase::RequestSend);
+                attempt.send().await
+            };
+            let error = match sent {
+                Ok(response) if is_transient_status(response.status()) => {
+                    self.error_from_response(response).await
+                }
+                Ok(response) => return Ok(response),
+                Err(e) if e.is_timeout() || e.is_connect() => {
+                    self.map_send_error(e, attempt_started.elapsed())
+                }
+                Err(e) => return Err(self.map_send_error(e, attempt_started.elapsed())),
+            };
+            history.record(&error, attempt_started.elapsed());
+
+            let retries = history.attempts.len() as u32 - 1;
+            if retries >= policy.max_retries {
+                if retries == 0 {
+                    return Err(error);
+                }
+                history.elapsed = started.elapsed();
+                return Err(CliError::RetriesExhausted {
+                    source: Box::new(error),
+                    history,
+                });
+            }
+
+            let delay = policy.delay(retries + 1);
+            tracing::debug!("Attempt {} failed ({}), retrying in {:?}", retries + 1, error, delay);
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// Map a transport error to a CLI error
+    ///
+    /// Timeouts report which timeout tripped. Other connect failures,
+    /// including DNS and TLS errors, become [`CliError::Connection`], and
+    /// failures while sending the request or reading its body become
+    /// [`CliError::Request`].
+    fn map_send_error(&self, err: reqwest::Error, elapsed: Duration) -> CliError {
+        let url = err
+            .url()
+            .map(|u| u.to_string())
+            .unwrap_or_else(|| self.config.base_url.clone());
+
+        if !err.is_timeout() {
+            return if err.is_connect() {
+                CliError::Connection(format!("{}: {}", url, root_cause(&err)))
+            } else if err.is_request() || err.is_body() {
+                CliError::Request(format!("{}: {}", url, root_cause(&err)))
+            } else {
+                CliError::Http(err)
+            };
+        }
+
+        if err.is_connect() {
+            let connect_timeout = self.config.connect_timeout.unwrap_or(self.config.timeout);
+            CliError::Timeout(format!(
+                "connect timeout after {:?} connecting to {}",
+                connect_timeout.min(self.config.timeout),
+                url
+            ))
+        } else if elapsed < self.config.timeout && self.config.read_timeout.is_some() {
+            CliError::Timeout(format!(
+                "read timeout after {:?} waiting for response from {}",
+                self.config.read_timeout.unwrap_or_default(),
+                url
+            ))
+        } else {
+            CliError::Timeout(format!(
+                "request to {} exceeded total timeout of {:?}",
+                url, self.config.timeout
+            ))
         }
     }
 
     /// Handle API response
     async fn handle_response<T: DeserializeOwned>(&self, response: reqwest::Response) -> CliResult<T> {
-        let status = response.status();
-
-        if status.is_success() {
+        if response.status().is_success() {
+            let _span = timing::span(Phase::ResponseParse);
             let body = response.json::<T>().await?;
             Ok(body)
         } else {
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(self.map_error(status, &error_text))
+            Err(self.error_from_response(response).await)
+        }
+    }
+
+    /// Build a CLI error from a non-success response
+    ///
+    /// Reverse proxies often answer with an HTML page (e.g. a 502 or 504)
+    /// instead of an API error body, so non-JSON bodies are reduced to a
+    /// short text snippet rather than echoed verbatim.
+    async fn error_from_response(&self, response: reqwest::Response) -> CliError {
+        let status = response.status();
+        let is_json = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .map(|ct| ct.contains("json"))
+            .unwrap_or(true);
+        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
+
+        if is_json {
+            return self.map_error(status, &error_text);
+        }
+
+        let snippet = text_snippet(&error_text, ERROR_SNIPPET_LEN);
+        if snippet.is_empty() {
+            CliError::Api(format!("{} from proxy", status))
+        } else {
+            CliError::Api(format!("{} from proxy: {}", status, snippet))
         }
     }
 
@@ -130,7 +403,7 @@ impl ApiClient for RestClient {
         &self,
         request: CreateOptimizationRequest,
     ) -> CliResult<OptimizationResponse> {
-        self.post("/api/v1/optimizations", &request).await
+        self.post_optimization("/api/v1/optimizations", &request).await
     }
 
     async fn list_optimizations(
@@ -155,6 +428,21 @@ impl ApiClient for RestClient {
         if let Some(to) = &query.to {
             params.push(format!("to={}", to));
         }
+        if !query.tags.is_empty() {
+            let tags: Vec<String> = query
+                .tags
+                .iter()
+                .map(|(key, value)| format!("{}={}", key, value))
+                .collect();
+            params.push(format!("tag={}", tags.join(",")));
+        }
+        if let Some(page) = query.page {
+            params.push(format!("page={}", page));
+        }
+        params.push(format!(
+            "page_size={}",
+            query.page_size.unwrap_or(DEFAULT_PAGE_SIZE)
+        ));
 
         if !params.is_empty() {
             url.push('?');
@@ -173,7 +461,7 @@ impl ApiClient for RestClient {
         id: &str,
         request: DeployOptimizationRequest,
     ) -> CliResult<OptimizationResponse> {
-        self.post(&format!("/api/v1/optimizations/{}/deploy", id), &request)
+        self.post_optimization(&format!("/api/v1/optimizations/{}/deploy", id), &request)
             .await
     }
 
@@ -191,6 +479,62 @@ impl ApiClient for RestClient {
             .await
     }
 
+    async fn approve_optimization(&self, id: &str) -> CliResult<OptimizationResponse> {
+        self.post(&format!("/api/v1/optimizations/{}/approve", id), &())
+            .await
+    }
+
+    async fn reject_optimization(
+        &self,
+        id: &str,
+        request: RejectOptimizationRequest,
+    ) -> CliResult<OptimizationResponse> {
+        self.post(&format!("/api/v1/optimizations/{}/reject", id), &request)
+            .await
+    }
+
+    async fn get_strategy_leaderboard(&self) -> CliResult<Vec<StrategyLeaderboardEntry>> {
+        self.get("/api/v1/optimizations/leaderboard").await
+    }
+
+    async fn add_optimization_tags(
+        &self,
+        id: &str,
+        tags: BTreeMap<String, String>,
+    ) -> CliResult<OptimizationResponse> {
+        #[derive(Serialize)]
+        struct TagOptimizationRequest {
+            tags: BTreeMap<String, String>,
+        }
+
+        self.post(
+            &format!("/api/v1/optimizations/{}/tags", id),
+            &TagOptimizationRequest { tags },
+        )
+        .await
+    }
+
+    async fn remove_optimization_tag(&self, id: &str, key: &str) -> CliResult<OptimizationResponse> {
+        self.delete(&format!("/api/v1/optimizations/{}/tags/{}", id, key))
+            .await
+    }
+
+    async fn bulk_deploy_optimizations(
+        &self,
+        request: BulkDeployRequest,
+    ) -> CliResult<Vec<BulkItemResult>> {
+        self.post_bulk("/api/v1/optimizations/bulk/deploy", &request)
+            .await
+    }
+
+    async fn bulk_delete_optimizations(&self, ids: &[String]) -> CliResult<Vec<BulkItemResult>> {
+        self.post_bulk(
+            "/api/v1/optimizations/bulk/delete",
+            &serde_json::json!({ "ids": ids }),
+        )
+        .await
+    }
+
     async fn get_config(&self, key: &str) -> CliResult<ConfigValue> {
         self.get(&format!("/api/v1/config/{}", key)).await
     }
@@ -214,31 +558,54 @@ impl ApiClient for RestClient {
     }
 
     async fn export_config(&self) -> CliResult<String> {
-        let url = format!("{}/api/v1/config/export", self.config.base_url);
-        let response = self.client.get(&url).send().await?;
+        let url = self.url("/api/v1/config/export");
+        let request = self
+            .client
+            .get(&url)
+            .header(reqwest::header::ACCEPT_ENCODING, "gzip");
+        let response = self.send(request).await?;
+
+        if !response.status().is_success() {
+            return Err(self.error_from_response(response).await);
+        }
 
-        if response.status().is_success() {
-            Ok(response.text().await?)
+        let gzipped = response
+            .headers()
+            .get(reqwest::header::CONTENT_ENCODING)
+            .is_some_and(|encoding| encoding == "gzip");
+        let body = response.bytes().await?;
+        if gzipped {
+            gzip::decode_text(&body)
         } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(self.map_error(status, &error_text))
+            String::from_utf8(body.to_vec())
+                .map_err(|e| CliError::Serialization(format!("Config is not valid UTF-8: {}", e)))
         }
     }
 
-    async fn import_config(&self, config: &str) -> CliResult<()> {
-        let url = format!("{}/api/v1/config/import", self.config.base_url);
-        let response = self.client.post(&url).body(config.to_string()).send().await?;
+    async fn import_config(&self, config: &str, compress: bool) -> CliResult<()> {
+        let url = self.url("/api/v1/config/import");
+        let request = if compress {
+            self.client
+                .post(&url)
+                .header(reqwest::header::CONTENT_ENCODING, "gzip")
+                .body(gzip::compress(config.as_bytes())?)
+        } else {
+            self.client.post(&url).body(config.to_string())
+        };
+        let response = self.send(request).await?;
 
         if response.status().is_success() {
             Ok(())
         } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(self.map_error(status, &error_text))
+            Err(self.error_from_response(response).await)
         }
     }
 
+    async fn watch_config(&self) -> CliResult<ConfigChangeStream> {
+        let url = self.url("/api/v1/config/watch");
+        self.sse_stream(&url).await
+    }
+
     async fn query_metrics(&self, query: MetricsQuery) -> CliResult<MetricsResponse> {
         self.post("/api/v1/metrics/query", &query).await
     }
@@ -313,7 +680,7 @@ impl ApiClient for RestClient {
     }
 
     async fn export_metrics(&self, query: ExportMetricsQuery) -> CliResult<String> {
-        let mut url = format!("{}/api/v1/metrics/export", self.config.base_url);
+        let mut url = self.url("/api/v1/metrics/export");
         let mut params = vec![format!("format={}", query.format)];
 
         if let Some(from) = &query.from {
@@ -326,14 +693,12 @@ impl ApiClient for RestClient {
         url.push('?');
         url.push_str(&params.join("&"));
 
-        let response = self.client.get(&url).send().await?;
+        let response = self.send(self.client.get(&url)).await?;
 
         if response.status().is_success() {
             Ok(response.text().await?)
         } else {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-            Err(self.map_error(status, &error_text))
+            Err(self.error_from_response(response).await)
         }
     }
 
@@ -366,6 +731,20 @@ impl ApiClient for RestClient {
         self.post("/api/v1/admin/cache/flush", &()).await
     }
 
+    async fn set_maintenance(
+        &self,
+        request: SetMaintenanceRequest,
+    ) -> CliResult<MaintenanceStatus> {
+        self.put("/api/v1/admin/maintenance", &request).await
+    }
+
+    async fn archive_decisions(
+        &self,
+        request: ArchiveDecisionsRequest,
+    ) -> CliResult<ArchiveDecisionsResponse> {
+        self.post("/api/v1/admin/decisions/archive", &request).await
+    }
+
     async fn get_detailed_health(&self) -> CliResult<DetailedHealthResponse> {
         self.get("/api/v1/admin/health").await
     }
@@ -373,4 +752,836 @@ impl ApiClient for RestClient {
     async fn get_version(&self) -> CliResult<VersionInfo> {
         self.get("/api/v1/admin/version").await
     }
+
+    async fn stream_events(&self, filter: EventFilter) -> CliResult<EventStream> {
+        let mut url = self.url("/api/v1/events");
+        let mut params = vec![];
+
+        if let Some(event_type) = &filter.event_type {
+            params.push(format!("event_type={}", event_type));
+        }
+        if let Some(service) = &filter.service {
+            params.push(format!("service={}", service));
+        }
+
+        if !params.is_empty() {
+            url.push('?');
+            url.push_str(&params.join("&"));
+        }
+
+        self.sse_stream(&url).await
+    }
+}
+
+/// Strip markup from a response body and truncate it to `max_len` characters
+fn text_snippet(body: &str, max_len: usize) -> String {
+    let mut text = String::with_capacity(body.len());
+    let mut in_tag = false;
+
+    for c in body.chars() {
+        match c {
+            '<' => {
+                in_tag = true;
+                text.push(' ');
+            }
+            '>' => in_tag = false,
+            _ if !in_tag => text.push(c),
+            _ => {}
+        }
+    }
+
+    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    if collapsed.chars().count() > max_len {
+        let truncated: String = collapsed.chars().take(max_len).collect();
+        format!("{}...", truncated.trim_end())
+    } else {
+        collapsed
+    }
+}
+
+/// Whether a response status is worth retrying
+fn is_transient_status(status: reqwest::StatusCode) -> bool {
+    matches!(
+        status,
+        reqwest::StatusCode::TOO_MANY_REQUESTS
+            | reqwest::StatusCode::BAD_GATEWAY
+            | reqwest::StatusCode::SERVICE_UNAVAILABLE
+            | reqwest::StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Innermost cause of an error, e.g. the OS or TLS error behind a failed
+/// connection, since reqwest's own message only names the URL
+fn root_cause(err: &(dyn std::error::Error + 'static)) -> String {
+    let mut cause = err;
+    while let Some(source) = cause.source() {
+        cause = source;
+    }
+    cause.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_for(server: &MockServer) -> RestClient {
+        client_at(&server.uri())
+    }
+
+    fn client_at(base_url: &str) -> RestClient {
+        RestClient::new(ClientConfig {
+            base_url: base_url.to_string(),
+            timeout: Duration::from_secs(5),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_html_error_page_is_reported_as_api_error() {
+        let server = MockServer::start().await;
+        let html = "<html><head><title>502 Bad Gateway</title></head>\
+                    <body><center><h1>502 Bad Gateway</h1></center>\
+                    <hr><center>nginx</center></body></html>";
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(502).set_body_raw(html, "text/html"))
+            .mount(&server)
+            .await;
+
+        let err = client_for(&server).health_check().await.unwrap_err();
+
+        match err {
+            CliError::Api(msg) => {
+                assert!(msg.starts_with("502 Bad Gateway from proxy"), "{}", msg);
+                assert!(msg.contains("nginx"), "{}", msg);
+                assert!(!msg.contains('<'), "{}", msg);
+            }
+            other => panic!("expected CliError::Api, got {:?}", other),
+        }
+    }
+
+    fn retrying_client_for(server: &MockServer, max_retries: u32) -> RestClient {
+        RestClient::new(ClientConfig {
+            retry: RetryPolicy {
+                max_retries,
+                backoff: Duration::from_millis(10),
+            },
+            ..client_for(server).config
+        })
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_custom_pool_settings_reuse_client() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({"status": "healthy", "version": "1.0.0"})),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = RestClient::new(ClientConfig {
+            pool_max_idle_per_host: Some(1),
+            pool_idle_timeout: Some(Duration::from_secs(5)),
+            tcp_keepalive: Some(Duration::from_secs(30)),
+            ..client_for(&server).config
+        })
+        .unwrap();
+
+        for _ in 0..2 {
+            assert_eq!(client.health_check().await.unwrap().status, "healthy");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_client_cert_is_config_error() {
+        let server = MockServer::start().await;
+        let result = RestClient::new(ClientConfig {
+            client_cert_path: Some("/nonexistent/client.crt".into()),
+            client_key_path: Some("/nonexistent/client.key".into()),
+            ..client_for(&server).config
+        });
+
+        match result {
+            Err(CliError::Config(message)) => assert!(message.contains("client.crt")),
+            other => panic!("expected CliError::Config, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_retries_carry_history() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(
+                ResponseTemplate::new(503)
+                    .set_body_raw(r#"{"error":"overloaded"}"#, "application/json"),
+            )
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let err = retrying_client_for(&server, 2)
+            .health_check()
+            .await
+            .unwrap_err();
+
+        let history = err.retry_history().expect("retry history").clone();
+        assert_eq!(history.attempts.len(), 3);
+        assert_eq!(
+            history.attempts.iter().map(|a| a.number).collect::<Vec<_>>(),
+            vec![1, 2, 3]
+        );
+        assert!(history
+            .attempts
+            .iter()
+            .all(|a| a.error.contains("503 Service Unavailable")));
+        // Backoff of 10ms then 20ms between the attempts
+        assert!(history.elapsed >= Duration::from_millis(30));
+        assert!(err.to_string().ends_with("(after 3 attempts)"), "{}", err);
+        assert!(history.to_string().starts_with("Retry history: 3 attempts"));
+
+        match err {
+            CliError::RetriesExhausted { source, .. } => {
+                assert!(matches!(*source, CliError::Api(_)))
+            }
+            other => panic!("expected CliError::RetriesExhausted, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_transient_failure_recovers_on_retry() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(502))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "status": "healthy",
+                "version": "1.0.0",
+                "uptime_seconds": 5
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        retrying_client_for(&server, 2).health_check().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_raw(r#"{"error":"not found"}"#, "application/json"),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let err = retrying_client_for(&server, 2)
+            .get_optimization("missing")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, CliError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_json_error_body_keeps_status_mapping() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/optimizations/missing"))
+            .respond_with(
+                ResponseTemplate::new(404)
+                    .set_body_raw(r#"{"error":"not found"}"#, "application/json"),
+            )
+            .mount(&server)
+            .await;
+
+        let err = client_for(&server)
+            .get_optimization("missing")
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, CliError::NotFound(_)));
+    }
+
+    #[tokio::test]
+    async fn test_connect_timeout_is_reported() {
+        // A listener whose accept backlog is full never completes new
+        // handshakes, so connecting to it hangs until the connect timeout
+        let socket = tokio::net::TcpSocket::new_v4().unwrap();
+        socket.bind("127.0.0.1:0".parse().unwrap()).unwrap();
+        let listener = socket.listen(0).unwrap();
+        let addr = listener.local_addr().unwrap();
+        let mut backlog = Vec::new();
+        while let Ok(Ok(stream)) = tokio::time::timeout(
+            Duration::from_millis(100),
+            tokio::net::TcpStream::connect(addr),
+        )
+        .await
+        {
+            backlog.push(stream);
+        }
+
+        let client = RestClient::new(ClientConfig {
+            base_url: format!("http://{}", addr),
+            timeout: Duration::from_secs(5),
+            connect_timeout: Some(Duration::from_millis(50)),
+            read_timeout: Some(Duration::from_secs(5)),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+
+        match client.health_check().await.unwrap_err() {
+            CliError::Timeout(msg) => assert!(msg.starts_with("connect timeout"), "{}", msg),
+            other => panic!("expected CliError::Timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_read_timeout_is_reported() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/health"))
+            .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_secs(2)))
+            .mount(&server)
+            .await;
+
+        let client = RestClient::new(ClientConfig {
+            base_url: server.uri(),
+            timeout: Duration::from_secs(5),
+            read_timeout: Some(Duration::from_millis(100)),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+
+        match client.health_check().await.unwrap_err() {
+            CliError::Timeout(msg) => assert!(msg.starts_with("read timeout"), "{}", msg),
+            other => panic!("expected CliError::Timeout, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_refused_connection_is_reported() {
+        // Bind and immediately release a port so nothing is listening on it
+        let addr = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+
+        let client = RestClient::new(ClientConfig {
+            base_url: format!("http://{}", addr),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+
+        match client.health_check().await.unwrap_err() {
+            CliError::Connection(msg) => assert!(msg.contains(&addr.to_string()), "{}", msg),
+            other => panic!("expected CliError::Connection, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_connection_closed_mid_request_is_reported() {
+        // Accept the connection, then close it without sending a response
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
+            }
+        });
+
+        let client = RestClient::new(ClientConfig {
+            base_url: format!("http://{}", addr),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+
+        match client.health_check().await.unwrap_err() {
+            CliError::Request(msg) => assert!(msg.contains(&addr.to_string()), "{}", msg),
+            other => panic!("expected CliError::Request, got {:?}", other),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stream_events_parses_sse_and_reports_disconnect() {
+        let server = MockServer::start().await;
+        let body = "event: optimization.deployed\n\
+                    data: {\"id\":\"e1\",\"event_type\":\"optimization.deployed\",\
+                    \"service\":\"chat\",\"message\":\"deployed\",\
+                    \"timestamp\":\"2024-03-01T12:00:00Z\"}\n\n\
+                    : keep-alive\n\n";
+        Mock::given(method("GET"))
+            .and(path("/api/v1/events"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let mut events = client_for(&server)
+            .stream_events(EventFilter::default())
+            .await
+            .unwrap();
+
+        let event = events.next().await.unwrap().unwrap();
+        assert_eq!(event.event_type, "optimization.deployed");
+        assert_eq!(event.service.as_deref(), Some("chat"));
+
+        match events.next().await {
+            Some(Err(CliError::Api(msg))) => assert!(msg.contains("closed"), "{}", msg),
+            other => panic!("expected disconnect error, got {:?}", other.map(|r| r.is_ok())),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_watch_config_parses_change_events() {
+        let server = MockServer::start().await;
+        let body = "event: config.changed\n\
+                    data: {\"id\":\"c1\",\"key\":\"max_tokens\",\"old_value\":1000,\
+                    \"new_value\":2000,\"changed_by\":\"alice\",\
+                    \"changed_at\":\"2024-03-01T12:00:00Z\"}\n\n";
+        Mock::given(method("GET"))
+            .and(path("/api/v1/config/watch"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let mut changes = client_for(&server).watch_config().await.unwrap();
+
+        let change = changes.next().await.unwrap().unwrap();
+        assert_eq!(change.key, "max_tokens");
+        assert_eq!(change.old_value, Some(serde_json::json!(1000)));
+        assert_eq!(change.new_value, serde_json::json!(2000));
+        assert_eq!(change.changed_by, "alice");
+    }
+
+    #[tokio::test]
+    async fn test_reject_optimization_posts_reason() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations/opt-1/reject"))
+            .and(body_json(serde_json::json!({"reason": "too risky"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "opt-1",
+                "target_services": ["chat"],
+                "strategy": "cost_performance_scoring",
+                "status": "rejected",
+                "changes": [],
+                "expected_impact": {
+                    "cost_reduction_pct": 20.0,
+                    "quality_delta_pct": -2.0,
+                    "latency_delta_pct": -5.0,
+                    "confidence": 0.85
+                },
+                "actual_impact": null,
+                "rationale": "",
+                "created_at": "2024-03-01T12:00:00Z",
+                "deployed_at": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let optimization = client_for(&server)
+            .reject_optimization(
+                "opt-1",
+                RejectOptimizationRequest {
+                    reason: "too risky".to_string(),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(optimization.status, "rejected");
+    }
+
+    #[tokio::test]
+    async fn test_rollback_optimization_posts_target_revision() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations/opt-1/rollback"))
+            .and(body_json(serde_json::json!({"reason": "regression", "to_revision": 2})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "opt-1",
+                "target_services": ["chat"],
+                "strategy": "cost_performance_scoring",
+                "status": "rolled_back",
+                "changes": [],
+                "config": {"max_tokens": 1000},
+                "revision": 2,
+                "expected_impact": {
+                    "cost_reduction_pct": 20.0,
+                    "quality_delta_pct": -2.0,
+                    "latency_delta_pct": -5.0,
+                    "confidence": 0.85
+                },
+                "actual_impact": null,
+                "rationale": "",
+                "created_at": "2024-03-01T12:00:00Z",
+                "deployed_at": null
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let optimization = client_for(&server)
+            .rollback_optimization(
+                "opt-1",
+                RollbackOptimizationRequest {
+                    reason: "regression".to_string(),
+                    to_revision: Some(2),
+                },
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(optimization.revision, 2);
+        assert_eq!(optimization.config, serde_json::json!({"max_tokens": 1000}));
+        assert_eq!(optimization.cost, None);
+    }
+
+    #[tokio::test]
+    async fn test_create_optimization_reads_cost_headers() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations"))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .insert_header("x-cost-usd", "0.012300")
+                    .insert_header("x-tokens-used", "1500")
+                    .set_body_json(serde_json::json!({
+                        "id": "opt-1",
+                        "target_services": ["chat"],
+                        "strategy": "cost_performance_scoring",
+                        "status": "pending",
+                        "changes": [],
+                        "expected_impact": {
+                            "cost_reduction_pct": 20.0,
+                            "quality_delta_pct": -2.0,
+                            "latency_delta_pct": -5.0,
+                            "confidence": 0.85
+                        },
+                        "actual_impact": null,
+                        "rationale": "Route simple prompts to a smaller model",
+                        "created_at": "2024-03-01T12:00:00Z",
+                        "deployed_at": null
+                    })),
+            )
+            .mount(&server)
+            .await;
+
+        let optimization = client_for(&server)
+            .create_optimization(CreateOptimizationRequest {
+                target_services: vec!["chat".to_string()],
+                strategy: "cost_performance_scoring".to_string(),
+                config: serde_json::json!({}),
+                constraints: vec![],
+                dry_run: false,
+                tags: BTreeMap::new(),
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(
+            optimization.cost,
+            Some(OperationCost {
+                cost_usd: 0.0123,
+                tokens_used: 1500,
+            })
+        );
+    }
+
+    fn tagged_optimization(tags: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": "opt-1",
+            "target_services": ["chat"],
+            "strategy": "cost_performance_scoring",
+            "status": "pending",
+            "changes": [],
+            "expected_impact": {
+                "cost_reduction_pct": 20.0,
+                "quality_delta_pct": -2.0,
+                "latency_delta_pct": -5.0,
+                "confidence": 0.85
+            },
+            "actual_impact": null,
+            "rationale": "",
+            "created_at": "2024-03-01T12:00:00Z",
+            "deployed_at": null,
+            "tags": tags
+        })
+    }
+
+    #[tokio::test]
+    async fn test_list_optimizations_sends_tag_filter() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/optimizations"))
+            .and(query_param("tag", "team=ml,ticket=OPT-1"))
+            .and(query_param("service", "chat"))
+            .and(query_param("page_size", DEFAULT_PAGE_SIZE.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                tagged_optimization(serde_json::json!({"team": "ml", "ticket": "OPT-1"}))
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let optimizations = client_for(&server)
+            .list_optimizations(ListOptimizationsQuery {
+                status: None,
+                strategy: None,
+                service: Some("chat".to_string()),
+                from: None,
+                to: None,
+                tags: vec![
+                    ("team".to_string(), "ml".to_string()),
+                    ("ticket".to_string(), "OPT-1".to_string()),
+                ],
+                page: None,
+                page_size: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(optimizations.len(), 1);
+        assert_eq!(optimizations[0].tags["team"], "ml");
+    }
+
+    #[tokio::test]
+    async fn test_add_and_remove_optimization_tags() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations/opt-1/tags"))
+            .and(body_json(serde_json::json!({"tags": {"team": "ml"}})))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(tagged_optimization(serde_json::json!({"team": "ml"}))),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("DELETE"))
+            .and(path("/api/v1/optimizations/opt-1/tags/team"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(tagged_optimization(serde_json::json!({}))),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let tagged = client
+            .add_optimization_tags("opt-1", BTreeMap::from([("team".to_string(), "ml".to_string())]))
+            .await
+            .unwrap();
+        assert_eq!(tagged.tags.get("team").map(String::as_str), Some("ml"));
+
+        let untagged = client.remove_optimization_tag("opt-1", "team").await.unwrap();
+        assert!(untagged.tags.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bulk_multi_status_is_parsed_per_item() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations/bulk/deploy"))
+            .and(body_json(serde_json::json!({
+                "ids": ["opt-1", "opt-2", "opt-3"],
+                "gradual": true,
+                "rollout_percentage": 25.0
+            })))
+            .respond_with(ResponseTemplate::new(207).set_body_json(serde_json::json!({
+                "items": [
+                    {"id": "opt-1", "status": 200},
+                    {"id": "opt-2", "status": 409, "error": "Optimization opt-2 is awaiting approval"},
+                    {"id": "opt-3", "status": 404, "error": "Optimization not found: opt-3"}
+                ]
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let results = client_for(&server)
+            .bulk_deploy_optimizations(BulkDeployRequest {
+                ids: vec!["opt-1".to_string(), "opt-2".to_string(), "opt-3".to_string()],
+                gradual: true,
+                rollout_percentage: 25.0,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 3);
+        assert!(results[0].is_success());
+        assert_eq!(results[0].error, None);
+        assert!(!results[1].is_success());
+        assert_eq!(results[1].status, 409);
+        assert_eq!(
+            results[1].error.as_deref(),
+            Some("Optimization opt-2 is awaiting approval")
+        );
+        assert_eq!(results[2].status, 404);
+    }
+
+    #[tokio::test]
+    async fn test_bulk_delete_rejected_request_is_an_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/optimizations/bulk/delete"))
+            .and(body_json(serde_json::json!({"ids": []})))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "error": "validation_error",
+                "message": "ids must not be empty"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = client_for(&server).bulk_delete_optimizations(&[]).await.unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)), "{:?}", err);
+    }
+
+    #[tokio::test]
+    async fn test_get_strategy_leaderboard() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/optimizations/leaderboard"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([
+                {
+                    "rank": 1,
+                    "strategy": "threshold_based",
+                    "optimizations": 2,
+                    "avg_cost_reduction_pct": 25.0,
+                    "avg_quality_delta_pct": -0.5,
+                    "avg_latency_delta_pct": 0.0,
+                    "success_rate": 1.0
+                },
+                {
+                    "rank": 2,
+                    "strategy": "ab_testing",
+                    "optimizations": 1,
+                    "avg_cost_reduction_pct": 5.0,
+                    "avg_quality_delta_pct": 2.0,
+                    "avg_latency_delta_pct": 0.0,
+                    "success_rate": 0.5
+                }
+            ])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let leaderboard = client_for(&server).get_strategy_leaderboard().await.unwrap();
+
+        assert_eq!(leaderboard.len(), 2);
+        assert_eq!(leaderboard[0].strategy, "threshold_based");
+        assert_eq!(leaderboard[1].rank, 2);
+    }
+
+    #[tokio::test]
+    async fn test_set_maintenance_puts_flag() {
+        let server = MockServer::start().await;
+        Mock::given(method("PUT"))
+            .and(path("/api/v1/admin/maintenance"))
+            .and(body_json(serde_json::json!({"enabled": true, "message": "deploying"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "enabled": true,
+                "message": "deploying",
+                "since": "2024-03-01T12:00:00Z"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let status = client_for(&server)
+            .set_maintenance(SetMaintenanceRequest {
+                enabled: true,
+                message: Some("deploying".to_string()),
+            })
+            .await
+            .unwrap();
+
+        assert!(status.enabled);
+        assert!(status.since.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_archive_decisions_posts_overrides() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/admin/decisions/archive"))
+            .and(body_json(serde_json::json!({"max_age_days": 30})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "archived": 12,
+                "deleted": 0,
+                "retained": 40,
+                "archived_total": 57
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let report = client_for(&server)
+            .archive_decisions(ArchiveDecisionsRequest {
+                max_age_days: Some(30),
+                keep_last: None,
+            })
+            .await
+            .unwrap();
+
+        assert_eq!(report.archived, 12);
+        assert_eq!(report.archived_total, 57);
+    }
+
+    #[test]
+    fn test_base_url_is_normalized() {
+        let cases = [
+            ("http://host:8080", "http://host:8080/api/v1/stats"),
+            ("http://host:8080/", "http://host:8080/api/v1/stats"),
+            ("http://host/optimizer", "http://host/optimizer/api/v1/stats"),
+            ("http://host/optimizer//", "http://host/optimizer/api/v1/stats"),
+        ];
+        for (base_url, expected) in cases {
+            let client = client_at(base_url);
+            assert_eq!(client.url("/api/v1/stats"), expected, "{}", base_url);
+            assert_eq!(client.url("api/v1/stats"), expected, "{}", base_url);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_requests_keep_base_path_prefix() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/optimizer/api/v1/optimizations/leaderboard"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!([])))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_at(&format!("{}/optimizer/", server.uri()));
+        assert!(client.get_strategy_leaderboard().await.unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_text_snippet_truncates() {
+        let body = format!("<p>{}</p>", "x".repeat(500));
+        assert_eq!(text_snippet(&body, 10), "xxxxxxxxxx...");
+    }
 }
@@ -0,0 +1,93 @@
+//! Retry policy and history for idempotent requests
+
+use std::fmt;
+use std::time::Duration;
+
+/// Default number of retries after the first attempt
+pub const DEFAULT_MAX_RETRIES: u32 = 2;
+
+/// Default delay before the first retry; doubles on each further retry
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(500);
+
+/// How transient failures of idempotent requests are retried
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// Delay before the first retry
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// Never retry
+    pub fn none() -> Self {
+        Self {
+            max_retries: 0,
+            backoff: Duration::ZERO,
+        }
+    }
+
+    /// Delay before retry number `retry` (1-based)
+    pub fn delay(&self, retry: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: DEFAULT_MAX_RETRIES,
+            backoff: DEFAULT_RETRY_BACKOFF,
+        }
+    }
+}
+
+/// A single failed attempt of a retried request
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryAttempt {
+    /// Attempt number, starting at 1
+    pub number: u32,
+    /// Response status or transport error of the attempt
+    pub error: String,
+    /// Time the attempt took
+    pub elapsed: Duration,
+}
+
+/// Every failed attempt of a request that exhausted its retries
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RetryHistory {
+    pub attempts: Vec<RetryAttempt>,
+    /// Time from the first attempt to the last failure, including backoff
+    pub elapsed: Duration,
+}
+
+impl RetryHistory {
+    /// Record a failed attempt
+    pub fn record(&mut self, error: impl fmt::Display, elapsed: Duration) {
+        self.attempts.push(RetryAttempt {
+            number: self.attempts.len() as u32 + 1,
+            error: error.to_string(),
+            elapsed,
+        });
+    }
+}
+
+impl fmt::Display for RetryHistory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Retry history: {} attempts over {:.2?}",
+            self.attempts.len(),
+            self.elapsed
+        )?;
+        for attempt in &self.attempts {
+            write!(
+                f,
+                "\n  attempt {} ({:.2?}): {}",
+                attempt.number, attempt.elapsed, attempt.error
+            )?;
+        }
+        Ok(())
+    }
+}
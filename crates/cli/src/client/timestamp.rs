@@ -0,0 +1,203 @@
+//! Strongly-typed timestamps for API requests and responses
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, SecondsFormat, TimeZone, Utc};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Naive formats accepted in addition to RFC3339, interpreted as UTC
+const NAIVE_DATETIME_FORMATS: &[&str] = &[
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M",
+];
+
+/// A UTC timestamp
+///
+/// Serialized as an RFC3339 string. Parsing is lenient and also accepts
+/// RFC2822, naive date-times (treated as UTC), plain dates and Unix epoch
+/// seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Timestamp(DateTime<Utc>);
+
+impl Timestamp {
+    /// Create a timestamp for the current time
+    pub fn now() -> Self {
+        Self(Utc::now())
+    }
+
+    /// Get the underlying date-time
+    pub fn as_datetime(&self) -> &DateTime<Utc> {
+        &self.0
+    }
+
+    /// Format as RFC3339 with second precision and a `Z` suffix
+    pub fn to_rfc3339(&self) -> String {
+        self.0.to_rfc3339_opts(SecondsFormat::Secs, true)
+    }
+
+    /// Parse a timestamp that may be relative to now
+    ///
+    /// Durations such as `-1h`, `30m` or `2days ago` all mean that long before
+    /// now. Anything else is parsed as an absolute timestamp.
+    pub fn parse_since(s: &str) -> Result<Self, String> {
+        Self::parse_relative_to(s, Utc::now())
+    }
+
+    /// [`Timestamp::parse_since`] against a fixed `now`
+    pub(crate) fn parse_relative_to(s: &str, now: DateTime<Utc>) -> Result<Self, String> {
+        let s = s.trim();
+        let duration = s
+            .strip_prefix('-')
+            .or_else(|| s.strip_suffix("ago"))
+            .unwrap_or(s)
+            .trim();
+
+        match humantime::parse_duration(duration) {
+            Ok(duration) => chrono::Duration::from_std(duration)
+                .ok()
+                .and_then(|d| now.checked_sub_signed(d))
+                .map(Self)
+                .ok_or_else(|| format!("Relative time out of range: {}", s)),
+            Err(_) => s.parse(),
+        }
+    }
+}
+
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(dt: DateTime<Utc>) -> Self {
+        Self(dt)
+    }
+}
+
+impl From<Timestamp> for DateTime<Utc> {
+    fn from(ts: Timestamp) -> Self {
+        ts.0
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_rfc3339())
+    }
+}
+
+impl FromStr for Timestamp {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+
+        if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+            return Ok(Self(dt.with_timezone(&Utc)));
+        }
+        if let Ok(dt) = DateTime::parse_from_rfc2822(s) {
+            return Ok(Self(dt.with_timezone(&Utc)));
+        }
+        for format in NAIVE_DATETIME_FORMATS {
+            if let Ok(dt) = NaiveDateTime::parse_from_str(s, format) {
+                return Ok(Self(Utc.from_utc_datetime(&dt)));
+            }
+        }
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            let dt = date.and_hms_opt(0, 0, 0).expect("midnight is a valid time");
+            return Ok(Self(Utc.from_utc_datetime(&dt)));
+        }
+        if let Ok(secs) = s.parse::<i64>() {
+            if let Some(dt) = Utc.timestamp_opt(secs, 0).single() {
+                return Ok(Self(dt));
+            }
+        }
+
+        Err(format!("Invalid timestamp: {}", s))
+    }
+}
+
+impl Serialize for Timestamp {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_rfc3339())
+    }
+}
+
+impl<'de> Deserialize<'de> for Timestamp {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum Raw {
+            Text(String),
+            Epoch(i64),
+        }
+
+        match Raw::deserialize(deserializer)? {
+            Raw::Text(s) => s.parse().map_err(serde::de::Error::custom),
+            Raw::Epoch(secs) => Utc
+                .timestamp_opt(secs, 0)
+                .single()
+                .map(Self)
+                .ok_or_else(|| serde::de::Error::custom(format!("Invalid epoch timestamp: {}", secs))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deserialize_formats() {
+        let expected: Timestamp = "2024-03-01T12:30:00Z".parse().unwrap();
+
+        for input in [
+            "\"2024-03-01T12:30:00Z\"",
+            "\"2024-03-01T14:30:00+02:00\"",
+            "\"2024-03-01T12:30:00.000Z\"",
+            "\"2024-03-01 12:30:00\"",
+            "\"2024-03-01T12:30\"",
+            "\"Fri, 01 Mar 2024 12:30:00 +0000\"",
+            "1709296200",
+        ] {
+            let ts: Timestamp = serde_json::from_str(input).unwrap();
+            assert_eq!(ts, expected, "input: {}", input);
+        }
+
+        let date: Timestamp = serde_json::from_str("\"2024-03-01\"").unwrap();
+        assert_eq!(date.to_string(), "2024-03-01T00:00:00Z");
+    }
+
+    #[test]
+    fn test_invalid_timestamp_is_rejected() {
+        assert!(serde_json::from_str::<Timestamp>("\"yesterday-ish\"").is_err());
+        assert!("".parse::<Timestamp>().is_err());
+    }
+
+    #[test]
+    fn test_ordering_and_roundtrip() {
+        let earlier: Timestamp = "2024-03-01T12:00:00+01:00".parse().unwrap();
+        let later: Timestamp = "2024-03-01T11:30:00Z".parse().unwrap();
+        assert!(earlier < later);
+
+        let json = serde_json::to_string(&later).unwrap();
+        assert_eq!(json, "\"2024-03-01T11:30:00Z\"");
+        assert_eq!(serde_json::from_str::<Timestamp>(&json).unwrap(), later);
+    }
+
+    #[test]
+    fn test_parse_relative_time() {
+        let now: DateTime<Utc> = "2024-03-01T12:00:00Z".parse().unwrap();
+        let parse = |s| Timestamp::parse_relative_to(s, now).unwrap().to_string();
+
+        assert_eq!(parse("-1h"), "2024-03-01T11:00:00Z");
+        assert_eq!(parse("90m"), "2024-03-01T10:30:00Z");
+        assert_eq!(parse("2days ago"), "2024-02-28T12:00:00Z");
+        assert_eq!(parse("2024-01-15"), "2024-01-15T00:00:00Z");
+        assert_eq!(parse("1709296200"), "2024-03-01T12:30:00Z");
+        assert!(Timestamp::parse_relative_to("-soon", now).is_err());
+    }
+}
@@ -1,9 +1,13 @@
 //! Admin operation commands
 
-use crate::{client::ApiClient, output::OutputWriter, Formatter, CliResult};
-use clap::Subcommand;
+use crate::{
+    client::{ApiClient, ArchiveDecisionsRequest, SetMaintenanceRequest},
+    commands::confirm::ConfirmArgs,
+    output::OutputWriter,
+    Formatter, CliResult,
+};
+use clap::{Subcommand, ValueEnum};
 use colored::Colorize;
-use dialoguer::Confirm;
 
 #[derive(Debug, Subcommand)]
 pub enum AdminCommand {
@@ -13,9 +17,8 @@ pub enum AdminCommand {
     /// Flush cache
     #[command(name = "cache")]
     CacheFlush {
-        /// Skip confirmation
-        #[arg(short = 'y', long)]
-        yes: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
 
     /// Detailed health check
@@ -23,6 +26,34 @@ pub enum AdminCommand {
 
     /// Get version information
     Version,
+
+    /// Turn maintenance mode on or off
+    Maintenance {
+        /// Desired maintenance state
+        state: MaintenanceToggle,
+
+        /// Message returned to clients while in maintenance
+        #[arg(short, long)]
+        message: Option<String>,
+    },
+
+    /// Archive optimization decisions past the retention policy
+    #[command(name = "archive-decisions")]
+    ArchiveDecisions {
+        /// Archive decisions older than this many days (overrides the server policy)
+        #[arg(long, value_name = "DAYS", value_parser = clap::value_parser!(u32).range(1..))]
+        older_than_days: Option<u32>,
+
+        /// Keep only this many of the newest decisions (overrides the server policy)
+        #[arg(long, value_name = "COUNT")]
+        keep_last: Option<usize>,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub enum MaintenanceToggle {
+    On,
+    Off,
 }
 
 impl AdminCommand {
@@ -33,9 +64,21 @@ impl AdminCommand {
     ) -> CliResult<()> {
         match self {
             AdminCommand::Stats => self.stats(client, formatter).await,
-            AdminCommand::CacheFlush { yes } => self.cache_flush(client, formatter, *yes).await,
+            AdminCommand::CacheFlush { confirm } => {
+                self.cache_flush(client, formatter, confirm).await
+            }
             AdminCommand::Health => self.health(client, formatter).await,
             AdminCommand::Version => self.version(client, formatter).await,
+            AdminCommand::Maintenance { state, message } => {
+                self.maintenance(client, formatter, *state, message).await
+            }
+            AdminCommand::ArchiveDecisions {
+                older_than_days,
+                keep_last,
+            } => {
+                self.archive_decisions(client, formatter, *older_than_days, *keep_last)
+                    .await
+            }
         }
     }
 
@@ -43,7 +86,7 @@ impl AdminCommand {
         let stats = client.get_stats().await?;
 
         let output = formatter.write(&stats)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         // Show summary
         println!("\n{}", "System Summary:".cyan().bold());
@@ -61,19 +104,14 @@ impl AdminCommand {
         &self,
         client: &dyn ApiClient,
         formatter: &Formatter,
-        yes: bool,
+        confirm: &ConfirmArgs,
     ) -> CliResult<()> {
-        if !yes {
-            let confirm = Confirm::new()
-                .with_prompt("Flush all caches? This may temporarily affect performance.")
-                .default(false)
-                .interact()
-                .unwrap();
-
-            if !confirm {
-                println!("{}", "Cache flush cancelled".yellow());
-                return Ok(());
-            }
+        if confirm.report_dry_run("flush all caches") {
+            return Ok(());
+        }
+        if !confirm.confirm("Flush all caches; this may temporarily affect performance")? {
+            println!("{}", "Cache flush cancelled".yellow());
+            return Ok(());
         }
 
         println!("{}", "Flushing cache...".cyan());
@@ -83,7 +121,7 @@ impl AdminCommand {
         println!("{} Cache flushed", "✓".green());
 
         let output = formatter.write(&result)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
@@ -92,7 +130,7 @@ impl AdminCommand {
         let health = client.get_detailed_health().await?;
 
         let output = formatter.write(&health)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         // Show component status
         println!("\n{}", "Component Health:".cyan().bold());
@@ -122,7 +160,7 @@ impl AdminCommand {
         let version = client.get_version().await?;
 
         let output = formatter.write(&version)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         println!("\n{}", "Version Information:".cyan().bold());
         println!("  Version:      {}", version.version);
@@ -132,4 +170,61 @@ impl AdminCommand {
 
         Ok(())
     }
+
+    async fn maintenance(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        state: MaintenanceToggle,
+        message: &Option<String>,
+    ) -> CliResult<()> {
+        let request = SetMaintenanceRequest {
+            enabled: state == MaintenanceToggle::On,
+            message: message.clone(),
+        };
+
+        let status = client.set_maintenance(request).await?;
+
+        if status.enabled {
+            println!(
+                "{} Maintenance mode on - non-admin requests now receive 503",
+                "⚠".yellow()
+            );
+        } else {
+            println!("{} Maintenance mode off", "✓".green());
+        }
+
+        let output = formatter.write(&status)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn archive_decisions(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        older_than_days: Option<u32>,
+        keep_last: Option<usize>,
+    ) -> CliResult<()> {
+        let request = ArchiveDecisionsRequest {
+            max_age_days: older_than_days,
+            keep_last,
+        };
+
+        let report = client.archive_decisions(request).await?;
+
+        println!(
+            "{} Archived {} decision(s), deleted {}, {} remain active",
+            "✓".green(),
+            report.archived,
+            report.deleted,
+            report.retained
+        );
+
+        let output = formatter.write(&report)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
 }
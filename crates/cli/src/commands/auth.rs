@@ -0,0 +1,74 @@
+//! Authentication commands
+
+use crate::{
+    client::{ApiClient, ClientConfig, RestClient},
+    credentials, CliError, CliResult,
+};
+use clap::Subcommand;
+use colored::Colorize;
+use dialoguer::Password;
+
+#[derive(Debug, Subcommand)]
+pub enum AuthCommand {
+    /// Store an API key in the OS keychain
+    Login,
+
+    /// Remove the stored API key from the OS keychain
+    Logout,
+}
+
+impl AuthCommand {
+    pub async fn execute(&self, config: &ClientConfig) -> CliResult<()> {
+        match self {
+            AuthCommand::Login => self.login(config).await,
+            AuthCommand::Logout => self.logout(config),
+        }
+    }
+
+    async fn login(&self, config: &ClientConfig) -> CliResult<()> {
+        println!("Logging in to {}", config.base_url.cyan());
+
+        let api_key = Password::new()
+            .with_prompt("API key")
+            .interact()
+            .map_err(|e| CliError::InvalidInput(e.to_string()))?;
+
+        if api_key.trim().is_empty() {
+            return Err(CliError::InvalidInput("API key cannot be empty".to_string()));
+        }
+
+        let client = RestClient::new(ClientConfig {
+            api_key: Some(api_key.clone()),
+            ..config.clone()
+        })?;
+
+        print!("Validating API key... ");
+        match client.health_check().await {
+            Ok(_) => println!("{}", "✓".green()),
+            Err(e) => {
+                println!("{}", "✗ Failed".red());
+                return Err(e);
+            }
+        }
+
+        if let Err(e) = credentials::store_api_key(&config.base_url, &api_key) {
+            println!("{} {}", "!".yellow(), e);
+            println!("  Set 'api_key' in the configuration file or LLM_OPTIMIZER_API_KEY instead");
+            return Err(e);
+        }
+
+        println!("{} API key stored in the system keychain", "✓".green());
+
+        Ok(())
+    }
+
+    fn logout(&self, config: &ClientConfig) -> CliResult<()> {
+        if credentials::delete_api_key(&config.base_url)? {
+            println!("{} Logged out from {}", "✓".green(), config.base_url);
+        } else {
+            println!("{} No stored credentials for {}", "!".yellow(), config.base_url);
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,15 @@
 //! Configuration management commands
 
-use crate::{client::ApiClient, output::OutputWriter, Formatter, CliResult};
+use crate::{
+    client::{gzip, ApiClient, ConfigChangeEvent, ValidationResult},
+    output::{self, OutputWriter},
+    CliError, CliResult, Formatter,
+};
 use clap::Subcommand;
 use colored::Colorize;
-use std::path::PathBuf;
+use futures::StreamExt;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Subcommand)]
 pub enum ConfigCommand {
@@ -26,20 +32,35 @@ pub enum ConfigCommand {
     List,
 
     /// Validate configuration
-    Validate,
+    Validate {
+        /// Also fail when validation reports warnings
+        #[arg(long)]
+        strict: bool,
+    },
 
     /// Export configuration
     Export {
         /// Output file path
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        #[arg(short, long, value_name = "FILE")]
+        file: Option<PathBuf>,
+
+        /// Gzip the export; `.gz` is appended to the file name if missing
+        #[arg(long)]
+        compress: bool,
     },
 
     /// Import configuration
     Import {
-        /// Input file path
+        /// Input file path, plain or gzip-compressed
         file: PathBuf,
+
+        /// Gzip the upload
+        #[arg(long)]
+        compress: bool,
     },
+
+    /// Watch server configuration changes as they happen (requires admin)
+    Watch,
 }
 
 impl ConfigCommand {
@@ -52,9 +73,10 @@ impl ConfigCommand {
             ConfigCommand::Get { key } => self.get(client, formatter, key).await,
             ConfigCommand::Set { key, value } => self.set(client, formatter, key, value).await,
             ConfigCommand::List => self.list(client, formatter).await,
-            ConfigCommand::Validate => self.validate(client).await,
-            ConfigCommand::Export { output } => self.export(client, output).await,
-            ConfigCommand::Import { file } => self.import(client, file).await,
+            ConfigCommand::Validate { strict } => self.validate(client, *strict).await,
+            ConfigCommand::Export { file, compress } => self.export(client, file, *compress).await,
+            ConfigCommand::Import { file, compress } => self.import(client, file, *compress).await,
+            ConfigCommand::Watch => self.watch(client, formatter).await,
         }
     }
 
@@ -66,7 +88,7 @@ impl ConfigCommand {
     ) -> CliResult<()> {
         let config = client.get_config(key).await?;
         let output = formatter.write(&config)?;
-        println!("{}", output);
+        formatter.print(&output);
         Ok(())
     }
 
@@ -82,7 +104,7 @@ impl ConfigCommand {
 
         println!("{} Configuration updated", "✓".green());
         let output = formatter.write(&config)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
@@ -96,14 +118,14 @@ impl ConfigCommand {
         }
 
         let output = formatter.write(&configs)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         println!("\n{} Found {} configuration(s)", "ℹ".blue(), configs.len());
 
         Ok(())
     }
 
-    async fn validate(&self, client: &dyn ApiClient) -> CliResult<()> {
+    async fn validate(&self, client: &dyn ApiClient, strict: bool) -> CliResult<()> {
         println!("{}", "Validating configuration...".cyan());
 
         let result = client.validate_config().await?;
@@ -118,38 +140,292 @@ impl ConfigCommand {
         }
 
         if !result.warnings.is_empty() {
-            println!("\n{} Warnings:", "⚠".yellow());
+            eprintln!("\n{} Warnings:", "⚠".yellow());
             for warning in &result.warnings {
-                println!("  {} {}", "•".yellow(), warning);
+                eprintln!("  {} {}", "•".yellow(), warning.to_string().yellow());
             }
         }
 
-        Ok(())
+        validation_outcome(&result, strict)
     }
 
-    async fn export(&self, client: &dyn ApiClient, output: &Option<PathBuf>) -> CliResult<()> {
-        println!("{}", "Exporting configuration...".cyan());
+    async fn export(
+        &self,
+        client: &dyn ApiClient,
+        file: &Option<PathBuf>,
+        compress: bool,
+    ) -> CliResult<()> {
+        eprintln!("{}", "Exporting configuration...".cyan());
 
         let config = client.export_config().await?;
 
-        if let Some(path) = output {
-            std::fs::write(path, &config)?;
-            println!("{} Configuration exported to {}", "✓".green(), path.display());
-        } else {
-            println!("{}", config);
+        match (file, compress) {
+            (Some(path), false) => {
+                std::fs::write(path, &config)?;
+                println!("{} Configuration exported to {}", "✓".green(), path.display());
+            }
+            (Some(path), true) => {
+                let path = gzip_path(path);
+                std::fs::write(&path, gzip::compress(config.as_bytes())?)?;
+                println!("{} Configuration exported to {}", "✓".green(), path.display());
+            }
+            (None, false) => output::file::emit(&config),
+            (None, true) => {
+                if !output::file::is_capturing() && std::io::stdout().is_terminal() {
+                    return Err(CliError::InvalidInput(
+                        "Refusing to write compressed output to a terminal; use --file or redirect"
+                            .to_string(),
+                    ));
+                }
+                output::file::emit_bytes(&gzip::compress(config.as_bytes())?)?;
+            }
         }
 
         Ok(())
     }
 
-    async fn import(&self, client: &dyn ApiClient, file: &PathBuf) -> CliResult<()> {
+    async fn import(&self, client: &dyn ApiClient, file: &Path, compress: bool) -> CliResult<()> {
         println!("{}", "Importing configuration...".cyan());
 
-        let config = std::fs::read_to_string(file)?;
-        client.import_config(&config).await?;
+        let config = read_config_file(file)?;
+        client.import_config(&config, compress).await?;
 
         println!("{} Configuration imported", "✓".green());
 
         Ok(())
     }
+
+    async fn watch(&self, client: &dyn ApiClient, formatter: &Formatter) -> CliResult<()> {
+        let mut changes = client.watch_config().await?;
+        eprintln!("{}", "Watching configuration changes (Ctrl+C to stop)...".cyan());
+
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => {
+                    eprintln!("\n{}", "Config watch closed".cyan());
+                    return Ok(());
+                }
+                next = changes.next() => match next {
+                    Some(Ok(change)) => print_change(&change, formatter)?,
+                    Some(Err(e)) => return Err(e),
+                    None => return Ok(()),
+                },
+            }
+        }
+    }
+}
+
+/// Add a `.gz` extension unless the path already has one
+fn gzip_path(path: &Path) -> PathBuf {
+    if path.extension().is_some_and(|ext| ext == "gz") {
+        path.to_path_buf()
+    } else {
+        let mut name = path.as_os_str().to_owned();
+        name.push(".gz");
+        PathBuf::from(name)
+    }
+}
+
+/// Read an exported config as JSON, decompressing it if it is gzipped
+///
+/// Gzip is recognized by its magic bytes, not the file name. Both JSON and
+/// YAML files are accepted.
+fn read_config_file(path: &Path) -> CliResult<String> {
+    let config = gzip::decode_text(&std::fs::read(path)?)?;
+    let value: serde_json::Value = serde_yaml::from_str(&config).map_err(|e| {
+        CliError::InvalidInput(format!("{} is not a config export: {}", path.display(), e))
+    })?;
+    serde_json::to_string(&value).map_err(|e| CliError::Serialization(e.to_string()))
+}
+
+/// Print a single config change, one line per change in table and JSON modes
+fn print_change(change: &ConfigChangeEvent, formatter: &Formatter) -> CliResult<()> {
+    match formatter {
+        Formatter::Table(_) => {
+            let old_value = change
+                .old_value
+                .as_ref()
+                .map(ToString::to_string)
+                .unwrap_or_else(|| "(unset)".to_string());
+            println!(
+                "{} {} {} {} {} by {}",
+                change.changed_at.to_string().dimmed(),
+                change.key.cyan(),
+                old_value.red(),
+                "→".dimmed(),
+                change.new_value.to_string().green(),
+                change.changed_by
+            );
+        }
+        Formatter::Json(_) => println!("{}", serde_json::to_string(change)?),
+        _ => println!("{}", formatter.write(change)?),
+    }
+
+    Ok(())
+}
+
+/// Decide the exit status of `config validate`: errors always fail, warnings
+/// only fail under `--strict`
+fn validation_outcome(result: &ValidationResult, strict: bool) -> CliResult<()> {
+    if !result.valid || !result.errors.is_empty() {
+        return Err(CliError::Config(format!(
+            "validation failed with {} error(s)",
+            result.errors.len()
+        )));
+    }
+
+    if strict && !result.warnings.is_empty() {
+        return Err(CliError::Config(format!(
+            "validation reported {} warning(s) in strict mode",
+            result.warnings.len()
+        )));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{ClientConfig, RestClient, RetryPolicy};
+    use crate::output::{get_formatter, OutputFormat};
+    use std::time::Duration;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn result(json: serde_json::Value) -> ValidationResult {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[test]
+    fn test_warnings_only_passes_unless_strict() {
+        let result = result(serde_json::json!({
+            "valid": true,
+            "errors": [],
+            "warnings": [
+                "cache is disabled",
+                {"field": "optimizer.max_retries", "message": "value is unusually high"}
+            ]
+        }));
+
+        assert_eq!(result.warnings[0].to_string(), "cache is disabled");
+        assert_eq!(
+            result.warnings[1].to_string(),
+            "optimizer.max_retries: value is unusually high"
+        );
+        assert!(validation_outcome(&result, false).is_ok());
+        assert!(matches!(
+            validation_outcome(&result, true),
+            Err(CliError::Config(_))
+        ));
+    }
+
+    #[test]
+    fn test_errors_fail_validation() {
+        let result = result(serde_json::json!({
+            "valid": false,
+            "errors": [{"field": "api.port", "message": "must be between 1 and 65535"}],
+            "warnings": []
+        }));
+
+        assert_eq!(result.errors[0].field.as_deref(), Some("api.port"));
+        assert!(matches!(
+            validation_outcome(&result, false),
+            Err(CliError::Config(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_gzip_export_imports_like_plain_export() {
+        let exported = serde_json::json!({"max_tokens": 1000, "model": "claude-3-haiku"});
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/api/v1/config/export"))
+            .and(header("accept-encoding", "gzip"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("content-encoding", "gzip")
+                    .set_body_raw(
+                        gzip::compress(exported.to_string().as_bytes()).unwrap(),
+                        "application/json",
+                    ),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/config/import"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let client = RestClient::new(ClientConfig {
+            base_url: server.uri(),
+            timeout: Duration::from_secs(5),
+            retry: RetryPolicy::none(),
+            ..ClientConfig::default()
+        })
+        .unwrap();
+        let formatter = get_formatter(OutputFormat::Json);
+        let dir = tempfile::tempdir().unwrap();
+        let plain = dir.path().join("config.json");
+
+        for compress in [false, true] {
+            let command = ConfigCommand::Export {
+                file: Some(plain.clone()),
+                compress,
+            };
+            command.execute(&client, &formatter).await.unwrap();
+        }
+        let compressed = dir.path().join("config.json.gz");
+        assert!(gzip::is_gzip(&std::fs::read(&compressed).unwrap()));
+
+        for (file, compress) in [(&plain, false), (&compressed, true)] {
+            let command = ConfigCommand::Import {
+                file: file.clone(),
+                compress,
+            };
+            command.execute(&client, &formatter).await.unwrap();
+        }
+
+        let imports: Vec<_> = server
+            .received_requests()
+            .await
+            .unwrap()
+            .into_iter()
+            .filter(|request| request.url.path() == "/api/v1/config/import")
+            .collect();
+        // Only the upload made with --compress is gzipped
+        let encodings: Vec<_> = imports
+            .iter()
+            .map(|request| request.headers.get("content-encoding").cloned())
+            .collect();
+        assert_eq!(encodings[0], None);
+        assert_eq!(encodings[1].as_ref().map(|v| v.to_str().unwrap()), Some("gzip"));
+        assert!(!gzip::is_gzip(&imports[0].body));
+
+        let imported: Vec<serde_json::Value> = imports
+            .iter()
+            .map(|request| serde_json::from_str(&gzip::decode_text(&request.body).unwrap()).unwrap())
+            .collect();
+        assert_eq!(imported, vec![exported.clone(), exported]);
+    }
+
+    #[test]
+    fn test_gzip_path_and_invalid_import() {
+        assert_eq!(gzip_path(Path::new("out.json")), PathBuf::from("out.json.gz"));
+        assert_eq!(gzip_path(Path::new("out.json.gz")), PathBuf::from("out.json.gz"));
+
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("config.gz");
+        std::fs::write(&file, gzip::compress(b"key: [unclosed").unwrap()).unwrap();
+        assert!(matches!(read_config_file(&file), Err(CliError::InvalidInput(_))));
+
+        let file = dir.path().join("config.yaml");
+        std::fs::write(&file, "max_tokens: 1000\n").unwrap();
+        assert_eq!(read_config_file(&file).unwrap(), r#"{"max_tokens":1000}"#);
+    }
 }
@@ -0,0 +1,92 @@
+//! Confirmation and dry runs for destructive commands
+
+use crate::{CliError, CliResult};
+use clap::Args;
+use colored::Colorize;
+use dialoguer::Confirm;
+use std::io::{self, IsTerminal};
+
+/// Flags for commands that change production state and cannot be undone
+#[derive(Debug, Clone, Default, Args)]
+pub struct ConfirmArgs {
+    /// Skip confirmation
+    #[arg(short = 'y', long)]
+    pub yes: bool,
+
+    /// Print what would happen without calling the API
+    #[arg(long)]
+    pub dry_run: bool,
+}
+
+impl ConfirmArgs {
+    /// Ask before running `action`, e.g. "Deploy optimization opt-1"
+    ///
+    /// Returns whether to go ahead. Without a terminal to prompt on, fails
+    /// instead of waiting for input unless `--yes` was given.
+    pub fn confirm(&self, action: &str) -> CliResult<bool> {
+        let interactive = io::stdin().is_terminal() && io::stderr().is_terminal();
+        self.confirm_with(action, interactive)
+    }
+
+    fn confirm_with(&self, action: &str, interactive: bool) -> CliResult<bool> {
+        if self.yes {
+            return Ok(true);
+        }
+        if !interactive {
+            return Err(CliError::InvalidInput(format!(
+                "{} needs confirmation but there is no terminal to prompt on; pass --yes to proceed",
+                action
+            )));
+        }
+
+        Confirm::new()
+            .with_prompt(format!("{}. Are you sure?", action))
+            .default(false)
+            .interact()
+            .map_err(|e| match e {
+                dialoguer::Error::IO(e) => CliError::Io(e),
+            })
+    }
+
+    /// Print `action` and return true when this is a dry run
+    pub fn report_dry_run(&self, action: &str) -> bool {
+        if self.dry_run {
+            println!("{} Dry run: would {}", "ℹ".blue(), action);
+        }
+        self.dry_run
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aborts_without_terminal() {
+        let args = ConfirmArgs::default();
+
+        let err = args.confirm_with("Flush all caches", false).unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(ref message) if message.contains("--yes")));
+    }
+
+    #[test]
+    fn test_yes_skips_prompt_without_terminal() {
+        let args = ConfirmArgs {
+            yes: true,
+            ..Default::default()
+        };
+
+        assert!(args.confirm_with("Flush all caches", false).unwrap());
+    }
+
+    #[test]
+    fn test_dry_run_is_reported() {
+        assert!(!ConfirmArgs::default().report_dry_run("flush all caches"));
+
+        let args = ConfirmArgs {
+            dry_run: true,
+            ..Default::default()
+        };
+        assert!(args.report_dry_run("flush all caches"));
+    }
+}
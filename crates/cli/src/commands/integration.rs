@@ -1,9 +1,13 @@
 //! Integration management commands
 
-use crate::{client::{AddIntegrationRequest, ApiClient}, output::OutputWriter, Formatter, CliResult};
+use crate::{
+    client::{AddIntegrationRequest, ApiClient},
+    commands::confirm::ConfirmArgs,
+    output::OutputWriter,
+    Formatter, CliResult,
+};
 use clap::Subcommand;
 use colored::Colorize;
-use dialoguer::Confirm;
 
 #[derive(Debug, Subcommand)]
 pub enum IntegrationCommand {
@@ -17,9 +21,9 @@ pub enum IntegrationCommand {
         #[arg(short, long)]
         name: String,
 
-        /// Configuration (JSON format)
-        #[arg(short, long)]
-        config: String,
+        /// Integration settings (JSON format)
+        #[arg(long, value_name = "JSON")]
+        settings: String,
     },
 
     /// List all integrations
@@ -36,9 +40,8 @@ pub enum IntegrationCommand {
         /// Integration ID
         id: String,
 
-        /// Skip confirmation
-        #[arg(short = 'y', long)]
-        yes: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
 }
 
@@ -52,11 +55,11 @@ impl IntegrationCommand {
             IntegrationCommand::Add {
                 integration_type,
                 name,
-                config,
-            } => self.add(client, formatter, integration_type, name, config).await,
+                settings,
+            } => self.add(client, formatter, integration_type, name, settings).await,
             IntegrationCommand::List => self.list(client, formatter).await,
             IntegrationCommand::Test { id } => self.test(client, formatter, id).await,
-            IntegrationCommand::Remove { id, yes } => self.remove(client, id, *yes).await,
+            IntegrationCommand::Remove { id, confirm } => self.remove(client, id, confirm).await,
         }
     }
 
@@ -66,11 +69,11 @@ impl IntegrationCommand {
         formatter: &Formatter,
         integration_type: &str,
         name: &str,
-        config: &str,
+        settings: &str,
     ) -> CliResult<()> {
         println!("{}", "Adding integration...".cyan());
 
-        let config_value: serde_json::Value = serde_json::from_str(config)?;
+        let config_value: serde_json::Value = serde_json::from_str(settings)?;
 
         let request = AddIntegrationRequest {
             integration_type: integration_type.to_string(),
@@ -83,7 +86,7 @@ impl IntegrationCommand {
         println!("{} Integration added", "✓".green());
 
         let output = formatter.write(&integration)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
@@ -97,7 +100,7 @@ impl IntegrationCommand {
         }
 
         let output = formatter.write(&integrations)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         println!("\n{} Found {} integration(s)", "ℹ".blue(), integrations.len());
 
@@ -121,23 +124,23 @@ impl IntegrationCommand {
         }
 
         let output = formatter.write(&result)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
 
-    async fn remove(&self, client: &dyn ApiClient, id: &str, yes: bool) -> CliResult<()> {
-        if !yes {
-            let confirm = Confirm::new()
-                .with_prompt(format!("Remove integration {}?", id))
-                .default(false)
-                .interact()
-                .unwrap();
-
-            if !confirm {
-                println!("{}", "Removal cancelled".yellow());
-                return Ok(());
-            }
+    async fn remove(
+        &self,
+        client: &dyn ApiClient,
+        id: &str,
+        confirm: &ConfirmArgs,
+    ) -> CliResult<()> {
+        if confirm.report_dry_run(&format!("remove integration {}", id)) {
+            return Ok(());
+        }
+        if !confirm.confirm(&format!("Remove integration {}", id))? {
+            println!("{}", "Removal cancelled".yellow());
+            return Ok(());
         }
 
         println!("{}", "Removing integration...".cyan());
@@ -0,0 +1,75 @@
+//! Event streaming command
+
+use crate::{
+    client::{ApiClient, EventFilter, OptimizerEvent},
+    output::OutputWriter,
+    CliResult, Formatter,
+};
+use clap::Args;
+use colored::Colorize;
+use futures::StreamExt;
+
+/// Tail optimizer events as they happen
+#[derive(Debug, Args)]
+pub struct LogsCommand {
+    /// Only show events of this type
+    #[arg(long = "type", value_name = "TYPE")]
+    pub event_type: Option<String>,
+
+    /// Only show events for this service
+    #[arg(long)]
+    pub service: Option<String>,
+}
+
+impl LogsCommand {
+    pub async fn execute(&self, client: &dyn ApiClient, formatter: &Formatter) -> CliResult<()> {
+        let filter = EventFilter {
+            event_type: self.event_type.clone(),
+            service: self.service.clone(),
+        };
+
+        let mut events = client.stream_events(filter).await?;
+        eprintln!("{}", "Streaming events (Ctrl+C to stop)...".cyan());
+
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => {
+                    eprintln!("\n{}", "Event stream closed".cyan());
+                    return Ok(());
+                }
+                next = events.next() => match next {
+                    Some(Ok(event)) => print_event(&event, formatter)?,
+                    Some(Err(e)) => return Err(e),
+                    None => return Ok(()),
+                },
+            }
+        }
+    }
+}
+
+/// Print a single event, one line per event in table and JSON modes
+fn print_event(event: &OptimizerEvent, formatter: &Formatter) -> CliResult<()> {
+    match formatter {
+        Formatter::Table(_) => {
+            let service = event
+                .service
+                .as_ref()
+                .map(|s| format!(" [{}]", s))
+                .unwrap_or_default();
+            println!(
+                "{} {}{} {}",
+                event.timestamp.to_string().dimmed(),
+                event.event_type.cyan(),
+                service,
+                event.message
+            );
+        }
+        Formatter::Json(_) => println!("{}", serde_json::to_string(event)?),
+        _ => println!("{}", formatter.write(event)?),
+    }
+
+    Ok(())
+}
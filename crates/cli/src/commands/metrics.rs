@@ -1,14 +1,21 @@
 //! Metrics and analytics commands
 
 use crate::{
-    client::{ApiClient, CostQuery, ExportMetricsQuery, MetricsQuery, PerformanceQuery, QualityQuery},
-    output::OutputWriter,
-    Formatter,
-    CliResult,
+    client::{
+        ApiClient, CostQuery, ExportMetricsQuery, MetricsQuery, MetricsResponse, PerformanceQuery,
+        QualityQuery, Timestamp,
+    },
+    commands::WatchArgs,
+    output::{self, OutputWriter},
+    CliError, CliResult, Formatter,
 };
-use clap::Subcommand;
+use chrono::{DateTime, NaiveTime, Utc};
+use clap::{Args, Subcommand};
 use colored::Colorize;
-use std::path::PathBuf;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Subcommand)]
 pub enum MetricsCommand {
@@ -18,17 +25,20 @@ pub enum MetricsCommand {
         #[arg(short, long, value_delimiter = ',')]
         metrics: Vec<String>,
 
-        /// Date range start
-        #[arg(long)]
+        /// Date range start: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         from: Option<String>,
 
-        /// Date range end
-        #[arg(long)]
+        /// Date range end: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         to: Option<String>,
 
         /// Aggregation method
         #[arg(short, long)]
         aggregation: Option<String>,
+
+        #[command(flatten)]
+        watch: WatchArgs,
     },
 
     /// Get performance metrics
@@ -37,13 +47,16 @@ pub enum MetricsCommand {
         #[arg(short, long)]
         service: Option<String>,
 
-        /// Date range start
-        #[arg(long)]
+        /// Date range start: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         from: Option<String>,
 
-        /// Date range end
-        #[arg(long)]
+        /// Date range end: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         to: Option<String>,
+
+        #[command(flatten)]
+        watch: WatchArgs,
     },
 
     /// Get cost analysis
@@ -52,13 +65,16 @@ pub enum MetricsCommand {
         #[arg(short, long)]
         service: Option<String>,
 
-        /// Date range start
-        #[arg(long)]
+        /// Date range start: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         from: Option<String>,
 
-        /// Date range end
-        #[arg(long)]
+        /// Date range end: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         to: Option<String>,
+
+        #[command(flatten)]
+        watch: WatchArgs,
     },
 
     /// Get quality metrics
@@ -67,13 +83,16 @@ pub enum MetricsCommand {
         #[arg(short, long)]
         service: Option<String>,
 
-        /// Date range start
-        #[arg(long)]
+        /// Date range start: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         from: Option<String>,
 
-        /// Date range end
-        #[arg(long)]
+        /// Date range end: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         to: Option<String>,
+
+        #[command(flatten)]
+        watch: WatchArgs,
     },
 
     /// Export metrics data
@@ -83,17 +102,63 @@ pub enum MetricsCommand {
         format: String,
 
         /// Output file path
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
 
-        /// Date range start
-        #[arg(long)]
+        /// Date range start: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         from: Option<String>,
 
-        /// Date range end
-        #[arg(long)]
+        /// Date range end: RFC3339, -24h, -7d, now or today
+        #[arg(long, allow_hyphen_values = true)]
         to: Option<String>,
     },
+
+    /// Compare two metrics snapshots or time ranges, highlighting regressions
+    Diff(DiffArgs),
+}
+
+#[derive(Debug, Args)]
+pub struct DiffArgs {
+    /// Baseline snapshot, as written by `metrics query --output json`
+    #[arg(long, value_name = "FILE", requires = "current", conflicts_with = "baseline_from")]
+    pub baseline: Option<PathBuf>,
+
+    /// Current snapshot, as written by `metrics query --output json`
+    #[arg(long, value_name = "FILE", requires = "baseline")]
+    pub current: Option<PathBuf>,
+
+    /// Metric names to query when comparing time ranges (comma-separated)
+    #[arg(short, long, value_delimiter = ',')]
+    pub metrics: Vec<String>,
+
+    /// Baseline range start: RFC3339, -24h, -7d, now or today
+    #[arg(long, requires = "current_from", allow_hyphen_values = true)]
+    pub baseline_from: Option<String>,
+
+    /// Baseline range end
+    #[arg(long, allow_hyphen_values = true)]
+    pub baseline_to: Option<String>,
+
+    /// Current range start
+    #[arg(long, requires = "baseline_from", allow_hyphen_values = true)]
+    pub current_from: Option<String>,
+
+    /// Current range end
+    #[arg(long, allow_hyphen_values = true)]
+    pub current_to: Option<String>,
+
+    /// Aggregation method for range queries
+    #[arg(short, long)]
+    pub aggregation: Option<String>,
+
+    /// Change in the worse direction, in percent, flagged as a regression
+    #[arg(long, default_value_t = 5.0)]
+    pub threshold: f64,
+
+    /// Exit with an error when any metric regressed
+    #[arg(long)]
+    pub fail_on_regression: bool,
 }
 
 impl MetricsCommand {
@@ -108,22 +173,34 @@ impl MetricsCommand {
                 from,
                 to,
                 aggregation,
-            } => self.query(client, formatter, metrics, from, to, aggregation).await,
-            MetricsCommand::Performance { service, from, to } => {
-                self.performance(client, formatter, service, from, to).await
+                watch,
+            } => {
+                let formatter = &watch.formatter(formatter)?;
+                watch
+                    .run(|| self.query(client, formatter, metrics, from, to, aggregation))
+                    .await
             }
-            MetricsCommand::Cost { service, from, to } => {
-                self.cost(client, formatter, service, from, to).await
+            MetricsCommand::Performance { service, from, to, watch } => {
+                let formatter = &watch.formatter(formatter)?;
+                watch
+                    .run(|| self.performance(client, formatter, service, from, to))
+                    .await
             }
-            MetricsCommand::Quality { service, from, to } => {
-                self.quality(client, formatter, service, from, to).await
+            MetricsCommand::Cost { service, from, to, watch } => {
+                let formatter = &watch.formatter(formatter)?;
+                watch.run(|| self.cost(client, formatter, service, from, to)).await
+            }
+            MetricsCommand::Quality { service, from, to, watch } => {
+                let formatter = &watch.formatter(formatter)?;
+                watch.run(|| self.quality(client, formatter, service, from, to)).await
             }
             MetricsCommand::Export {
                 format,
-                output,
+                file,
                 from,
                 to,
-            } => self.export(client, format, output, from, to).await,
+            } => self.export(client, format, file, from, to).await,
+            MetricsCommand::Diff(args) => self.diff(client, formatter, args).await,
         }
     }
 
@@ -136,17 +213,18 @@ impl MetricsCommand {
         to: &Option<String>,
         aggregation: &Option<String>,
     ) -> CliResult<()> {
+        let (from, to) = time_range(from, to)?;
         let query = MetricsQuery {
             metric_names: metrics.to_vec(),
-            from: from.clone(),
-            to: to.clone(),
+            from,
+            to,
             aggregation: aggregation.clone(),
         };
 
         let response = client.query_metrics(query).await?;
 
         let output = formatter.write(&response)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
@@ -159,16 +237,17 @@ impl MetricsCommand {
         from: &Option<String>,
         to: &Option<String>,
     ) -> CliResult<()> {
+        let (from, to) = time_range(from, to)?;
         let query = PerformanceQuery {
             service: service.clone(),
-            from: from.clone(),
-            to: to.clone(),
+            from,
+            to,
         };
 
         let metrics = client.get_performance_metrics(query).await?;
 
         let output = formatter.write(&metrics)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         // Show summary
         println!("\n{}", "Performance Summary:".cyan().bold());
@@ -189,16 +268,17 @@ impl MetricsCommand {
         from: &Option<String>,
         to: &Option<String>,
     ) -> CliResult<()> {
+        let (from, to) = time_range(from, to)?;
         let query = CostQuery {
             service: service.clone(),
-            from: from.clone(),
-            to: to.clone(),
+            from,
+            to,
         };
 
         let metrics = client.get_cost_metrics(query).await?;
 
         let output = formatter.write(&metrics)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         // Show summary
         println!("\n{}", "Cost Summary:".cyan().bold());
@@ -223,16 +303,17 @@ impl MetricsCommand {
         from: &Option<String>,
         to: &Option<String>,
     ) -> CliResult<()> {
+        let (from, to) = time_range(from, to)?;
         let query = QualityQuery {
             service: service.clone(),
-            from: from.clone(),
-            to: to.clone(),
+            from,
+            to,
         };
 
         let metrics = client.get_quality_metrics(query).await?;
 
         let output = formatter.write(&metrics)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         // Show summary
         println!("\n{}", "Quality Summary:".cyan().bold());
@@ -253,27 +334,435 @@ impl MetricsCommand {
         &self,
         client: &dyn ApiClient,
         format: &str,
-        output: &Option<PathBuf>,
+        file: &Option<PathBuf>,
         from: &Option<String>,
         to: &Option<String>,
     ) -> CliResult<()> {
+        let (from, to) = time_range(from, to)?;
         println!("{}", "Exporting metrics...".cyan());
 
         let query = ExportMetricsQuery {
             format: format.to_string(),
-            from: from.clone(),
-            to: to.clone(),
+            from,
+            to,
         };
 
         let data = client.export_metrics(query).await?;
 
-        if let Some(path) = output {
+        if let Some(path) = file {
             std::fs::write(path, &data)?;
             println!("{} Metrics exported to {}", "✓".green(), path.display());
         } else {
-            println!("{}", data);
+            output::file::emit(&data);
         }
 
         Ok(())
     }
+
+    async fn diff(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        args: &DiffArgs,
+    ) -> CliResult<()> {
+        let (baseline, current) = match (&args.baseline, &args.current) {
+            (Some(baseline), Some(current)) => (read_snapshot(baseline)?, read_snapshot(current)?),
+            _ if args.baseline_from.is_some() => {
+                let query = |(from, to)| MetricsQuery {
+                    metric_names: args.metrics.clone(),
+                    from,
+                    to,
+                    aggregation: args.aggregation.clone(),
+                };
+                let baseline = query(time_range(&args.baseline_from, &args.baseline_to)?);
+                let current = query(time_range(&args.current_from, &args.current_to)?);
+                let (baseline, current) =
+                    tokio::join!(client.query_metrics(baseline), client.query_metrics(current));
+                (baseline?, current?)
+            }
+            _ => {
+                return Err(CliError::InvalidInput(
+                    "Pass --baseline and --current files, or --baseline-from and --current-from ranges"
+                        .to_string(),
+                ))
+            }
+        };
+
+        let diff = MetricsDiff::between(&baseline, &current, args.threshold);
+
+        if matches!(formatter, Formatter::Table(_)) {
+            println!("{}", diff_table(&diff));
+            let summary = format!("{} regression(s)", diff.regressions);
+            if diff.regressions > 0 {
+                println!("\n{} {}", "✗".red(), summary.red());
+            } else {
+                println!("\n{} {}", "✓".green(), summary);
+            }
+        } else {
+            let output = formatter.write(&diff)?;
+            formatter.print(&output);
+        }
+
+        if args.fail_on_regression && diff.regressions > 0 {
+            return Err(CliError::OperationFailed(format!(
+                "{} metric(s) regressed by more than {}%",
+                diff.regressions, args.threshold
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Which way a metric has to move to be an improvement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricDirection {
+    /// Latency, errors, cost: going up is a regression
+    LowerIsBetter,
+    /// Quality, throughput, success: going down is a regression
+    HigherIsBetter,
+    /// Unknown metrics are reported but never flagged
+    Neutral,
+}
+
+impl MetricDirection {
+    /// Infer the direction from the metric name
+    pub fn for_metric(name: &str) -> Self {
+        const LOWER: [&str; 6] = ["latency", "duration", "error", "cost", "token", "timeout"];
+        const HIGHER: [&str; 6] = ["quality", "throughput", "success", "accuracy", "score", "hit"];
+
+        let name = name.to_lowercase();
+        if LOWER.iter().any(|word| name.contains(word)) {
+            Self::LowerIsBetter
+        } else if HIGHER.iter().any(|word| name.contains(word)) {
+            Self::HigherIsBetter
+        } else {
+            Self::Neutral
+        }
+    }
+}
+
+/// Change of one metric between the baseline and the current snapshot
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricDelta {
+    pub name: String,
+    /// Mean of the baseline series, if the metric was in the baseline
+    pub baseline: Option<f64>,
+    /// Mean of the current series, if the metric is in the current snapshot
+    pub current: Option<f64>,
+    pub delta: Option<f64>,
+    /// Change relative to the baseline, in percent; absent when the baseline is zero
+    pub percent_change: Option<f64>,
+    pub direction: MetricDirection,
+    pub regression: bool,
+}
+
+/// Per-metric comparison of two snapshots
+#[derive(Debug, Clone, Serialize)]
+pub struct MetricsDiff {
+    /// Percentage change in the worse direction flagged as a regression
+    pub threshold_pct: f64,
+    pub metrics: Vec<MetricDelta>,
+    pub regressions: usize,
+}
+
+impl MetricsDiff {
+    /// Compare each metric's mean value; metrics are matched by name and
+    /// reported in name order
+    pub fn between(baseline: &MetricsResponse, current: &MetricsResponse, threshold_pct: f64) -> Self {
+        let mut values: BTreeMap<&str, (Option<f64>, Option<f64>)> = BTreeMap::new();
+        for metric in &baseline.metrics {
+            values.entry(&metric.name).or_default().0 = mean(metric.values.iter().map(|v| v.value));
+        }
+        for metric in &current.metrics {
+            values.entry(&metric.name).or_default().1 = mean(metric.values.iter().map(|v| v.value));
+        }
+
+        let metrics: Vec<MetricDelta> = values
+            .into_iter()
+            .map(|(name, (baseline, current))| {
+                let direction = MetricDirection::for_metric(name);
+                let delta = baseline.zip(current).map(|(b, c)| c - b);
+                let percent_change = baseline
+                    .zip(delta)
+                    .filter(|(b, _)| *b != 0.0)
+                    .map(|(b, d)| d / b.abs() * 100.0);
+
+                // How much worse the metric got, positive for a regression
+                let worse = |change: f64| match direction {
+                    MetricDirection::LowerIsBetter => change,
+                    MetricDirection::HigherIsBetter => -change,
+                    MetricDirection::Neutral => 0.0,
+                };
+                let regression = match (percent_change, delta) {
+                    (Some(pct), _) => worse(pct) > threshold_pct,
+                    (None, Some(delta)) => worse(delta) > 0.0,
+                    (None, None) => false,
+                };
+
+                MetricDelta {
+                    name: name.to_string(),
+                    baseline,
+                    current,
+                    delta,
+                    percent_change,
+                    direction,
+                    regression,
+                }
+            })
+            .collect();
+
+        Self {
+            threshold_pct,
+            regressions: metrics.iter().filter(|m| m.regression).count(),
+            metrics,
+        }
+    }
+}
+
+/// Resolve a `--from`/`--to` expression to an RFC3339 timestamp
+///
+/// Accepts RFC3339 and the other formats [`Timestamp`] parses, offsets
+/// before `now` such as `-24h` or `-7d`, and the keywords `now` and `today`
+/// (midnight UTC).
+pub fn parse_time(expr: &str, now: DateTime<Utc>) -> CliResult<String> {
+    let timestamp = match expr.trim().to_lowercase().as_str() {
+        "now" => Ok(Timestamp::from(now)),
+        "today" => Ok(Timestamp::from(now.date_naive().and_time(NaiveTime::MIN).and_utc())),
+        _ => Timestamp::parse_relative_to(expr, now),
+    };
+    timestamp.map(|ts| ts.to_rfc3339()).map_err(|_| {
+        CliError::InvalidInput(format!(
+            "Invalid time '{}': expected an RFC3339 timestamp, an offset such as -24h or -7d, now or today",
+            expr
+        ))
+    })
+}
+
+/// Resolve both ends of a range against the same `now`
+fn time_range(
+    from: &Option<String>,
+    to: &Option<String>,
+) -> CliResult<(Option<String>, Option<String>)> {
+    let now = Utc::now();
+    let resolve = |expr: &Option<String>| expr.as_deref().map(|e| parse_time(e, now)).transpose();
+    Ok((resolve(from)?, resolve(to)?))
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), v| (sum + v, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+/// Read a snapshot written by `metrics query` as JSON or YAML
+fn read_snapshot(path: &Path) -> CliResult<MetricsResponse> {
+    let text = std::fs::read_to_string(path)?;
+    serde_yaml::from_str(&text).map_err(|e| {
+        CliError::InvalidInput(format!("{} is not a metrics snapshot: {}", path.display(), e))
+    })
+}
+
+/// Render the diff with regressions in red and improvements in green
+fn diff_table(diff: &MetricsDiff) -> String {
+    let show = |v: Option<f64>, sign: bool| match v {
+        Some(v) if sign => format!("{:+.2}", v),
+        Some(v) => format!("{:.2}", v),
+        None => "-".to_string(),
+    };
+
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            ["Metric", "Baseline", "Current", "Δ", "Δ%"]
+                .into_iter()
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+        );
+
+    for metric in &diff.metrics {
+        let improved = metric.percent_change.or(metric.delta).is_some_and(|change| {
+            match metric.direction {
+                MetricDirection::LowerIsBetter => change < 0.0,
+                MetricDirection::HigherIsBetter => change > 0.0,
+                MetricDirection::Neutral => false,
+            }
+        });
+        let percent = metric
+            .percent_change
+            .map(|p| format!("{:+.1}%", p))
+            .unwrap_or_else(|| "-".to_string());
+
+        let mut change = [Cell::new(show(metric.delta, true)), Cell::new(percent)];
+        if metric.regression {
+            change = change.map(|cell| cell.fg(Color::Red).add_attribute(Attribute::Bold));
+        } else if improved {
+            change = change.map(|cell| cell.fg(Color::Green));
+        }
+        let [delta, percent] = change;
+
+        table.add_row(vec![
+            Cell::new(&metric.name),
+            Cell::new(show(metric.baseline, false)),
+            Cell::new(show(metric.current, false)),
+            delta,
+            percent,
+        ]);
+    }
+
+    table.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::{MetricData, MetricValue, MockClient};
+    use crate::output::get_formatter;
+    use crate::OutputFormat;
+
+    fn snapshot(metrics: &[(&str, &[f64])]) -> MetricsResponse {
+        MetricsResponse {
+            metrics: metrics
+                .iter()
+                .map(|(name, values)| MetricData {
+                    name: name.to_string(),
+                    values: values
+                        .iter()
+                        .map(|&value| MetricValue {
+                            timestamp: "2024-01-01T00:00:00Z".to_string(),
+                            value,
+                        })
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    fn delta<'a>(diff: &'a MetricsDiff, name: &str) -> &'a MetricDelta {
+        diff.metrics.iter().find(|m| m.name == name).unwrap()
+    }
+
+    #[test]
+    fn test_direction_from_name() {
+        assert_eq!(MetricDirection::for_metric("p95_latency_ms"), MetricDirection::LowerIsBetter);
+        assert_eq!(MetricDirection::for_metric("error_rate"), MetricDirection::LowerIsBetter);
+        assert_eq!(MetricDirection::for_metric("quality_score"), MetricDirection::HigherIsBetter);
+        assert_eq!(MetricDirection::for_metric("throughput_rps"), MetricDirection::HigherIsBetter);
+        assert_eq!(MetricDirection::for_metric("requests"), MetricDirection::Neutral);
+    }
+
+    #[test]
+    fn test_deltas_and_regression_flags() {
+        let baseline = snapshot(&[
+            ("latency_ms", &[100.0, 120.0]),
+            ("quality_score", &[0.90]),
+            ("cost_usd", &[10.0]),
+            ("throughput_rps", &[50.0]),
+            ("requests", &[1000.0]),
+            ("retired_metric", &[1.0]),
+        ]);
+        let current = snapshot(&[
+            ("latency_ms", &[130.0, 134.0]),
+            ("quality_score", &[0.80]),
+            ("cost_usd", &[8.0]),
+            ("throughput_rps", &[51.0]),
+            ("requests", &[2000.0]),
+            ("new_metric", &[3.0]),
+        ]);
+
+        let diff = MetricsDiff::between(&baseline, &current, 5.0);
+
+        let latency = delta(&diff, "latency_ms");
+        assert_eq!(latency.baseline, Some(110.0));
+        assert_eq!(latency.current, Some(132.0));
+        assert_eq!(latency.delta, Some(22.0));
+        assert!((latency.percent_change.unwrap() - 20.0).abs() < 1e-9);
+        assert!(latency.regression);
+
+        assert!(delta(&diff, "quality_score").regression);
+        assert!(!delta(&diff, "cost_usd").regression);
+        assert!(!delta(&diff, "throughput_rps").regression);
+        assert!(!delta(&diff, "requests").regression);
+
+        let retired = delta(&diff, "retired_metric");
+        assert_eq!((retired.current, retired.delta), (None, None));
+        assert_eq!(delta(&diff, "new_metric").baseline, None);
+
+        assert_eq!(diff.regressions, 2);
+    }
+
+    #[test]
+    fn test_threshold_and_zero_baseline() {
+        let baseline = snapshot(&[("latency_ms", &[100.0]), ("error_rate", &[0.0])]);
+        let current = snapshot(&[("latency_ms", &[104.0]), ("error_rate", &[0.01])]);
+
+        let diff = MetricsDiff::between(&baseline, &current, 5.0);
+        assert!(!delta(&diff, "latency_ms").regression);
+        let errors = delta(&diff, "error_rate");
+        assert_eq!(errors.percent_change, None);
+        assert!(errors.regression);
+
+        let strict = MetricsDiff::between(&baseline, &current, 1.0);
+        assert!(delta(&strict, "latency_ms").regression);
+    }
+
+    #[test]
+    fn test_json_report_shape() {
+        let diff = MetricsDiff::between(
+            &snapshot(&[("latency_ms", &[100.0])]),
+            &snapshot(&[("latency_ms", &[150.0])]),
+            5.0,
+        );
+        let value = serde_json::to_value(&diff).unwrap();
+
+        assert_eq!(value["regressions"], 1);
+        assert_eq!(value["metrics"][0]["direction"], "lower_is_better");
+        assert_eq!(value["metrics"][0]["percent_change"], 50.0);
+        assert_eq!(value["metrics"][0]["regression"], true);
+    }
+
+    #[test]
+    fn test_parse_time_formats() {
+        let now: DateTime<Utc> = "2024-03-10T15:45:30Z".parse().unwrap();
+        let parse = |expr| parse_time(expr, now).unwrap();
+
+        assert_eq!(parse("2024-03-01T12:00:00+02:00"), "2024-03-01T10:00:00Z");
+        assert_eq!(parse("2024-03-01"), "2024-03-01T00:00:00Z");
+        assert_eq!(parse("-24h"), "2024-03-09T15:45:30Z");
+        assert_eq!(parse("-7d"), "2024-03-03T15:45:30Z");
+        assert_eq!(parse("-90m"), "2024-03-10T14:15:30Z");
+        assert_eq!(parse("now"), "2024-03-10T15:45:30Z");
+        assert_eq!(parse("Today"), "2024-03-10T00:00:00Z");
+    }
+
+    #[test]
+    fn test_parse_time_rejects_garbage() {
+        let now = Utc::now();
+        for expr in ["last tuesday", "-7x", "", "2024-13-45"] {
+            let err = parse_time(expr, now).unwrap_err();
+            assert!(matches!(err, CliError::InvalidInput(_)), "{}", expr);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relative_range_is_sent_as_timestamps() {
+        let client = MockClient::new();
+        let formatter = get_formatter(OutputFormat::Json);
+        let performance = |from: &str| MetricsCommand::Performance {
+            service: None,
+            from: Some(from.to_string()),
+            to: Some("now".to_string()),
+            watch: WatchArgs::default(),
+        };
+
+        performance("-1h").execute(&client, &formatter).await.unwrap();
+        let query = &client.calls_to("get_performance_metrics")[0]["query"];
+        let from: Timestamp = query["from"].as_str().unwrap().parse().unwrap();
+        let to: Timestamp = query["to"].as_str().unwrap().parse().unwrap();
+        assert_eq!(*to.as_datetime() - *from.as_datetime(), chrono::Duration::hours(1));
+
+        let err = performance("yesterday-ish").execute(&client, &formatter).await.unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+        assert_eq!(client.calls().len(), 1);
+    }
 }
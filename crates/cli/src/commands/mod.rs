@@ -8,6 +8,10 @@ pub mod integration;
 pub mod admin;
 pub mod util;
 pub mod run;
+pub mod auth;
+pub mod logs;
+pub mod watch;
+pub mod confirm;
 
 pub use service::ServiceCommand;
 pub use optimize::OptimizeCommand;
@@ -17,3 +21,7 @@ pub use integration::IntegrationCommand;
 pub use admin::AdminCommand;
 pub use util::UtilCommand;
 pub use run::RunCommand;
+pub use auth::AuthCommand;
+pub use logs::LogsCommand;
+pub use watch::WatchArgs;
+pub use confirm::ConfirmArgs;
@@ -2,20 +2,124 @@
 
 use crate::{
     client::{
-        ApiClient, ConstraintInput, CreateOptimizationRequest, DeployOptimizationRequest,
-        ListOptimizationsQuery, RollbackOptimizationRequest,
+        bulk::{DEFAULT_FETCH_CONCURRENCY, MAX_FETCH_CONCURRENCY},
+        fetch_ordered, ActualImpact, ApiClient, BulkDeployRequest, BulkItemResult, ConstraintInput,
+        CreateOptimizationRequest, DeployOptimizationRequest,
+        ListOptimizationsQuery, OperationCost, OptimizationResponse, RejectOptimizationRequest,
+        RollbackOptimizationRequest, StrategyLeaderboardEntry, Timestamp,
     },
     output::OutputWriter,
-    Formatter,
+    commands::confirm::ConfirmArgs,
+    CliError, Formatter,
     CliResult,
 };
-use clap::Subcommand;
+use clap::{Args, Subcommand};
 use colored::Colorize;
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
 use dialoguer::{Confirm, Input, Select};
 use indicatif::{ProgressBar, ProgressStyle};
-use serde_json::json;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::future::Future;
+use std::io::IsTerminal;
 use std::time::Duration;
 
+/// Parse a `key=value` tag argument
+fn parse_tag(s: &str) -> Result<(String, String), String> {
+    match s.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            Ok((key.to_string(), value.to_string()))
+        }
+        _ => Err(format!("invalid tag '{}': expected key=value", s)),
+    }
+}
+
+/// Filters for `optimize list`
+#[derive(Debug, Default, Args)]
+pub struct ListArgs {
+    /// Filter by status
+    #[arg(short, long)]
+    status: Option<String>,
+
+    /// Filter by strategy
+    #[arg(short = 'S', long)]
+    strategy: Option<String>,
+
+    /// Filter by service
+    #[arg(long)]
+    service: Option<String>,
+
+    /// Date range start
+    #[arg(long)]
+    from: Option<Timestamp>,
+
+    /// Only show optimizations since a relative time (e.g. -1h, 30m) or timestamp
+    #[arg(
+        long,
+        conflicts_with = "from",
+        allow_hyphen_values = true,
+        value_parser = Timestamp::parse_since
+    )]
+    since: Option<Timestamp>,
+
+    /// Date range end
+    #[arg(long)]
+    to: Option<Timestamp>,
+
+    /// Only show optimizations with this tag (key=value); repeat to require several
+    #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+    tags: Vec<(String, String)>,
+
+    /// Page number, starting from 1
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    page: Option<u32>,
+
+    /// Items per page; the server caps this at its own maximum
+    #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+    page_size: Option<u32>,
+}
+
+impl ListArgs {
+    /// Build the API query, with `--since` standing in for `--from`
+    fn query(&self) -> ListOptimizationsQuery {
+        ListOptimizationsQuery {
+            status: self.status.clone(),
+            strategy: self.strategy.clone(),
+            service: self.service.clone(),
+            from: self.since.or(self.from),
+            to: self.to,
+            tags: self.tags.clone(),
+            page: self.page,
+            page_size: self.page_size,
+        }
+    }
+}
+
+/// Options for `optimize watch-impact`
+#[derive(Debug, Args)]
+pub struct WatchImpactArgs {
+    /// Optimization ID
+    id: String,
+
+    /// Time between polls (e.g. 30s, 5m)
+    #[arg(long, default_value = "30s", value_parser = humantime::parse_duration)]
+    interval: Duration,
+
+    /// Number of consecutive measurements that must agree
+    #[arg(long, default_value_t = 5, value_parser = clap::value_parser!(u32).range(2..))]
+    window: u32,
+
+    /// Largest standard deviation, in percentage points, of each impact delta
+    /// across the window
+    #[arg(long, default_value_t = 0.5)]
+    tolerance: f64,
+
+    /// Give up if the impact has not converged after this long
+    #[arg(long, default_value = "30m", value_parser = humantime::parse_duration)]
+    timeout: Duration,
+}
+
 #[derive(Debug, Subcommand)]
 pub enum OptimizeCommand {
     /// Create a new optimization
@@ -32,38 +136,42 @@ pub enum OptimizeCommand {
         #[arg(short, long)]
         dry_run: bool,
 
+        /// Tag the optimization (key=value, e.g. team=ml); repeatable
+        #[arg(long = "tag", value_name = "KEY=VALUE", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+
         /// Interactive mode
         #[arg(short, long)]
         interactive: bool,
     },
 
     /// List optimizations
-    List {
-        /// Filter by status
-        #[arg(short, long)]
-        status: Option<String>,
-
-        /// Filter by strategy
-        #[arg(short = 'S', long)]
-        strategy: Option<String>,
+    List(ListArgs),
 
-        /// Filter by service
-        #[arg(short = 'v', long)]
-        service: Option<String>,
+    /// Get optimization details
+    Get {
+        /// Optimization IDs; several are fetched concurrently and listed in the given order
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Maximum requests in flight when fetching several IDs
+        #[arg(long, default_value_t = DEFAULT_FETCH_CONCURRENCY, help = format!(
+            "Maximum requests in flight when fetching several IDs (capped at {})",
+            MAX_FETCH_CONCURRENCY
+        ))]
+        concurrency: usize,
+    },
 
-        /// Date range start
-        #[arg(long)]
-        from: Option<String>,
+    /// Rank strategies by the measured impact of their past optimizations
+    Leaderboard,
 
-        /// Date range end
-        #[arg(long)]
-        to: Option<String>,
-    },
+    /// Compare the changes and expected impact of two optimizations
+    Diff {
+        /// Base optimization ID (e.g. the deployed one)
+        id_a: String,
 
-    /// Get optimization details
-    Get {
-        /// Optimization ID
-        id: String,
+        /// Optimization ID to compare against the base
+        id_b: String,
     },
 
     /// Deploy an optimization
@@ -79,11 +187,13 @@ pub enum OptimizeCommand {
         #[arg(short, long, default_value = "10.0")]
         percentage: f64,
 
-        /// Skip confirmation
-        #[arg(short = 'y', long)]
-        yes: bool,
+        #[command(flatten)]
+        confirm: ConfirmArgs,
     },
 
+    /// Poll a deployed optimization until its measured impact stabilizes
+    WatchImpact(WatchImpactArgs),
+
     /// Rollback an optimization
     Rollback {
         /// Optimization ID
@@ -93,13 +203,26 @@ pub enum OptimizeCommand {
         #[arg(short, long)]
         reason: Option<String>,
 
+        /// Restore this revision instead of the previous one
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        to_revision: Option<u32>,
+
+        #[command(flatten)]
+        confirm: ConfirmArgs,
+    },
+
+    /// Cancel an optimization
+    Cancel {
+        /// Optimization ID
+        id: String,
+
         /// Skip confirmation
         #[arg(short = 'y', long)]
         yes: bool,
     },
 
-    /// Cancel an optimization
-    Cancel {
+    /// Approve an optimization awaiting approval so it can be deployed (requires admin)
+    Approve {
         /// Optimization ID
         id: String,
 
@@ -107,6 +230,96 @@ pub enum OptimizeCommand {
         #[arg(short = 'y', long)]
         yes: bool,
     },
+
+    /// Reject an optimization awaiting approval (requires admin)
+    Reject {
+        /// Optimization ID
+        id: String,
+
+        /// Reason for rejection
+        #[arg(short, long)]
+        reason: Option<String>,
+
+        /// Skip confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
+    /// Add or remove optimization tags
+    Tag {
+        #[command(subcommand)]
+        command: TagCommand,
+    },
+
+    /// Deploy or delete several optimizations at once
+    Bulk {
+        #[command(subcommand)]
+        command: BulkCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum BulkCommand {
+    /// Deploy several optimizations with the same rollout
+    Deploy {
+        /// Optimization IDs
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Gradual rollout
+        #[arg(short, long)]
+        gradual: bool,
+
+        /// Rollout percentage (0-100)
+        #[arg(short, long, default_value = "10.0")]
+        percentage: f64,
+
+        /// Skip confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Exit successfully even if some optimizations failed
+        #[arg(long)]
+        ignore_partial: bool,
+    },
+
+    /// Delete several optimizations; deployed ones must be rolled back first
+    Delete {
+        /// Optimization IDs
+        #[arg(required = true)]
+        ids: Vec<String>,
+
+        /// Skip confirmation
+        #[arg(short = 'y', long)]
+        yes: bool,
+
+        /// Exit successfully even if some optimizations failed
+        #[arg(long)]
+        ignore_partial: bool,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+pub enum TagCommand {
+    /// Add tags, overwriting existing values for the same keys
+    Add {
+        /// Optimization ID
+        id: String,
+
+        /// Tags to set (key=value)
+        #[arg(required = true, value_name = "KEY=VALUE", value_parser = parse_tag)]
+        tags: Vec<(String, String)>,
+    },
+
+    /// Remove tags by key
+    Remove {
+        /// Optimization ID
+        id: String,
+
+        /// Tag keys to remove
+        #[arg(required = true, value_name = "KEY")]
+        keys: Vec<String>,
+    },
 }
 
 impl OptimizeCommand {
@@ -120,35 +333,51 @@ impl OptimizeCommand {
                 services,
                 strategy,
                 dry_run,
+                tags,
                 interactive,
             } => {
-                self.create(client, formatter, services, strategy, *dry_run, *interactive)
-                    .await
+                let request = CreateOptimizationRequest {
+                    target_services: services.clone(),
+                    strategy: strategy.clone(),
+                    config: json!({}),
+                    constraints: vec![],
+                    dry_run: *dry_run,
+                    tags: tags.iter().cloned().collect(),
+                };
+                self.create(client, formatter, request, *interactive).await
             }
-            OptimizeCommand::List {
-                status,
-                strategy,
-                service,
-                from,
-                to,
-            } => {
-                self.list(client, formatter, status, strategy, service, from, to)
-                    .await
+            OptimizeCommand::List(args) => self.list(client, formatter, args.query()).await,
+            OptimizeCommand::Get { ids, concurrency } => {
+                self.get(client, formatter, ids, *concurrency).await
             }
-            OptimizeCommand::Get { id } => self.get(client, formatter, id).await,
+            OptimizeCommand::Leaderboard => self.leaderboard(client, formatter).await,
+            OptimizeCommand::Diff { id_a, id_b } => self.diff(client, formatter, id_a, id_b).await,
             OptimizeCommand::Deploy {
                 id,
                 gradual,
                 percentage,
-                yes,
+                confirm,
             } => {
-                self.deploy(client, formatter, id, *gradual, *percentage, *yes)
+                self.deploy(client, formatter, id, *gradual, *percentage, confirm)
                     .await
             }
-            OptimizeCommand::Rollback { id, reason, yes } => {
-                self.rollback(client, formatter, id, reason, *yes).await
+            OptimizeCommand::Rollback {
+                id,
+                reason,
+                to_revision,
+                confirm,
+            } => {
+                self.rollback(client, formatter, id, reason, *to_revision, confirm)
+                    .await
             }
             OptimizeCommand::Cancel { id, yes } => self.cancel(client, formatter, id, *yes).await,
+            OptimizeCommand::Approve { id, yes } => self.approve(client, formatter, id, *yes).await,
+            OptimizeCommand::Reject { id, reason, yes } => {
+                self.reject(client, formatter, id, reason, *yes).await
+            }
+            OptimizeCommand::WatchImpact(args) => self.watch_impact(client, formatter, args).await,
+            OptimizeCommand::Tag { command } => self.tag(client, formatter, command).await,
+            OptimizeCommand::Bulk { command } => self.bulk(client, formatter, command).await,
         }
     }
 
@@ -156,16 +385,20 @@ impl OptimizeCommand {
         &self,
         client: &dyn ApiClient,
         formatter: &Formatter,
-        services: &[String],
-        strategy: &str,
-        dry_run: bool,
+        mut request: CreateOptimizationRequest,
         interactive: bool,
     ) -> CliResult<()> {
-        let (services, strategy, dry_run) = if interactive {
-            self.interactive_create(services, strategy, dry_run)?
-        } else {
-            (services.to_vec(), strategy.to_string(), dry_run)
-        };
+        if interactive {
+            let (services, strategy, dry_run) = self.interactive_create(
+                &request.target_services,
+                &request.strategy,
+                request.dry_run,
+            )?;
+            request.target_services = services;
+            request.strategy = strategy;
+            request.dry_run = dry_run;
+        }
+        let dry_run = request.dry_run;
 
         println!(
             "{}",
@@ -181,14 +414,6 @@ impl OptimizeCommand {
         pb.set_message("Analyzing configuration...");
         pb.enable_steady_tick(Duration::from_millis(100));
 
-        let request = CreateOptimizationRequest {
-            target_services: services.clone(),
-            strategy: strategy.clone(),
-            config: json!({}),
-            constraints: vec![],
-            dry_run,
-        };
-
         let optimization = client.create_optimization(request).await?;
         pb.finish_and_clear();
 
@@ -196,7 +421,11 @@ impl OptimizeCommand {
         println!();
 
         let output = formatter.write(&optimization)?;
-        println!("{}", output);
+        formatter.print(&output);
+
+        if let Some(cost) = &optimization.cost {
+            println!("\n{} {}", "ℹ".blue(), cost_summary(cost));
+        }
 
         if dry_run {
             println!("\n{} This was a dry run - no changes were deployed", "ℹ".blue());
@@ -253,20 +482,8 @@ impl OptimizeCommand {
         &self,
         client: &dyn ApiClient,
         formatter: &Formatter,
-        status: &Option<String>,
-        strategy: &Option<String>,
-        service: &Option<String>,
-        from: &Option<String>,
-        to: &Option<String>,
+        query: ListOptimizationsQuery,
     ) -> CliResult<()> {
-        let query = ListOptimizationsQuery {
-            status: status.clone(),
-            strategy: strategy.clone(),
-            service: service.clone(),
-            from: from.clone(),
-            to: to.clone(),
-        };
-
         let optimizations = client.list_optimizations(query).await?;
 
         if optimizations.is_empty() {
@@ -275,7 +492,7 @@ impl OptimizeCommand {
         }
 
         let output = formatter.write(&optimizations)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         println!(
             "\n{} Found {} optimization(s)",
@@ -290,12 +507,87 @@ impl OptimizeCommand {
         &self,
         client: &dyn ApiClient,
         formatter: &Formatter,
-        id: &str,
+        ids: &[String],
+        concurrency: usize,
     ) -> CliResult<()> {
-        let optimization = client.get_optimization(id).await?;
+        let mut optimizations =
+            fetch_ordered(ids, concurrency, |id| client.get_optimization(id)).await?;
 
-        let output = formatter.write(&optimization)?;
-        println!("{}", output);
+        // A single ID keeps printing one object rather than a list
+        let output = if optimizations.len() == 1 {
+            formatter.write(&optimizations.remove(0))?
+        } else {
+            formatter.write(&optimizations)?
+        };
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn leaderboard(&self, client: &dyn ApiClient, formatter: &Formatter) -> CliResult<()> {
+        let mut leaderboard = client.get_strategy_leaderboard().await?;
+        leaderboard.sort_by_key(|entry| entry.rank);
+
+        if !matches!(formatter, Formatter::Table(_)) {
+            println!("{}", formatter.write(&leaderboard)?);
+            return Ok(());
+        }
+
+        if leaderboard.is_empty() {
+            println!("{} no optimizations with measured impact yet", "ℹ".blue());
+            return Ok(());
+        }
+
+        println!("{}", leaderboard_table(&leaderboard));
+        Ok(())
+    }
+
+    async fn diff(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        id_a: &str,
+        id_b: &str,
+    ) -> CliResult<()> {
+        let (a, b) = tokio::join!(client.get_optimization(id_a), client.get_optimization(id_b));
+        let a = a.map_err(|e| missing_side(e, id_a))?;
+        let b = b.map_err(|e| missing_side(e, id_b))?;
+
+        let diff = OptimizationDiff::between(&a, &b);
+
+        if !matches!(formatter, Formatter::Table(_)) {
+            let output = formatter.write(&diff)?;
+            formatter.print(&output);
+            return Ok(());
+        }
+
+        println!("{} {} → {}", "Diff".cyan().bold(), diff.id_a, diff.id_b);
+
+        if diff.is_empty() {
+            println!("\n{} no differences", "ℹ".blue());
+            return Ok(());
+        }
+
+        if !diff.changes.is_empty() {
+            println!("\n{}", "Changes:".bold());
+            for change in &diff.changes {
+                println!("  {}", change.render());
+            }
+        }
+
+        if !diff.expected_impact.is_empty() {
+            println!("\n{}", "Expected impact:".bold());
+            for impact in &diff.expected_impact {
+                println!(
+                    "  {} {}: {:.2} → {:.2} ({:+.2})",
+                    "~".yellow(),
+                    impact.metric,
+                    impact.a,
+                    impact.b,
+                    impact.b - impact.a
+                );
+            }
+        }
 
         Ok(())
     }
@@ -307,22 +599,22 @@ impl OptimizeCommand {
         id: &str,
         gradual: bool,
         percentage: f64,
-        yes: bool,
+        confirm: &ConfirmArgs,
     ) -> CliResult<()> {
-        if !yes {
-            let confirm = Confirm::new()
-                .with_prompt(format!(
-                    "Deploy optimization {}? This will affect production traffic.",
-                    id
-                ))
-                .default(false)
-                .interact()
-                .unwrap();
-
-            if !confirm {
-                println!("{}", "Deployment cancelled".yellow());
-                return Ok(());
-            }
+        let rollout = if gradual {
+            format!("gradually, starting at {}% of traffic", percentage)
+        } else {
+            "to all traffic".to_string()
+        };
+        if confirm.report_dry_run(&format!("deploy optimization {} {}", id, rollout)) {
+            return Ok(());
+        }
+        if !confirm.confirm(&format!(
+            "Deploy optimization {} {}; this will affect production traffic",
+            id, rollout
+        ))? {
+            println!("{}", "Deployment cancelled".yellow());
+            return Ok(());
         }
 
         println!("{}", "Deploying optimization...".cyan());
@@ -348,7 +640,11 @@ impl OptimizeCommand {
         println!();
 
         let output = formatter.write(&optimization)?;
-        println!("{}", output);
+        formatter.print(&output);
+
+        if let Some(cost) = &optimization.cost {
+            println!("\n{} {}", "ℹ".blue(), cost_summary(cost));
+        }
 
         Ok(())
     }
@@ -359,23 +655,27 @@ impl OptimizeCommand {
         formatter: &Formatter,
         id: &str,
         reason: &Option<String>,
-        yes: bool,
+        to_revision: Option<u32>,
+        confirm: &ConfirmArgs,
     ) -> CliResult<()> {
-        if !yes {
-            let confirm = Confirm::new()
-                .with_prompt(format!("Rollback optimization {}?", id))
-                .default(false)
-                .interact()
-                .unwrap();
-
-            if !confirm {
-                println!("{}", "Rollback cancelled".yellow());
-                return Ok(());
-            }
+        let target = match to_revision {
+            Some(revision) => format!("to revision {}", revision),
+            None => "to its previous revision".to_string(),
+        };
+        if confirm.report_dry_run(&format!("roll back optimization {} {}", id, target)) {
+            return Ok(());
+        }
+        if !confirm.confirm(&format!("Roll back optimization {} {}", id, target))? {
+            println!("{}", "Rollback cancelled".yellow());
+            return Ok(());
         }
 
         let reason = if let Some(r) = reason {
             r.clone()
+        } else if !std::io::stdin().is_terminal() {
+            return Err(CliError::InvalidInput(
+                "--reason is required when there is no terminal to prompt on".to_string(),
+            ));
         } else {
             Input::new()
                 .with_prompt("Reason for rollback")
@@ -394,16 +694,23 @@ impl OptimizeCommand {
         pb.set_message("Reverting configuration changes...");
         pb.enable_steady_tick(Duration::from_millis(100));
 
-        let request = RollbackOptimizationRequest { reason };
+        let request = RollbackOptimizationRequest {
+            reason,
+            to_revision,
+        };
 
         let optimization = client.rollback_optimization(id, request).await?;
         pb.finish_and_clear();
 
-        println!("{} Optimization rolled back", "✓".green());
+        println!(
+            "{} Optimization rolled back to revision {}",
+            "✓".green(),
+            optimization.revision
+        );
         println!();
 
         let output = formatter.write(&optimization)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
@@ -436,8 +743,929 @@ impl OptimizeCommand {
         println!();
 
         let output = formatter.write(&optimization)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         Ok(())
     }
+
+    async fn approve(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        id: &str,
+        yes: bool,
+    ) -> CliResult<()> {
+        if !yes {
+            let confirm = Confirm::new()
+                .with_prompt(format!("Approve optimization {} for deployment?", id))
+                .default(false)
+                .interact()
+                .unwrap();
+
+            if !confirm {
+                println!("{}", "Approval cancelled".yellow());
+                return Ok(());
+            }
+        }
+
+        let optimization = client.approve_optimization(id).await?;
+
+        println!("{} Optimization approved", "✓".green());
+        println!();
+
+        let output = formatter.write(&optimization)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn reject(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        id: &str,
+        reason: &Option<String>,
+        yes: bool,
+    ) -> CliResult<()> {
+        if !yes {
+            let confirm = Confirm::new()
+                .with_prompt(format!("Reject optimization {}?", id))
+                .default(false)
+                .interact()
+                .unwrap();
+
+            if !confirm {
+                println!("{}", "Rejection cancelled".yellow());
+                return Ok(());
+            }
+        }
+
+        let reason = if let Some(r) = reason {
+            r.clone()
+        } else {
+            Input::new()
+                .with_prompt("Reason for rejection")
+                .interact_text()
+                .unwrap()
+        };
+
+        let request = RejectOptimizationRequest { reason };
+        let optimization = client.reject_optimization(id, request).await?;
+
+        println!("{} Optimization rejected", "✓".green());
+        println!();
+
+        let output = formatter.write(&optimization)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn tag(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        command: &TagCommand,
+    ) -> CliResult<()> {
+        let optimization = match command {
+            TagCommand::Add { id, tags } => {
+                let optimization = client
+                    .add_optimization_tags(id, tags.iter().cloned().collect())
+                    .await?;
+                println!("{} Tagged optimization {}", "✓".green(), id);
+                optimization
+            }
+            TagCommand::Remove { id, keys } => {
+                let mut optimization = None;
+                for key in keys {
+                    optimization = Some(client.remove_optimization_tag(id, key).await?);
+                }
+                println!("{} Removed {} tag(s) from optimization {}", "✓".green(), keys.len(), id);
+                // clap requires at least one key
+                optimization.expect("at least one tag key")
+            }
+        };
+        println!();
+
+        let output = formatter.write(&optimization.tags)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn watch_impact(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        args: &WatchImpactArgs,
+    ) -> CliResult<()> {
+        eprintln!(
+            "{}",
+            format!(
+                "Watching actual impact of {} every {} (Ctrl+C to stop)...",
+                args.id,
+                humantime::format_duration(args.interval)
+            )
+            .cyan()
+        );
+
+        let tracker = ImpactConvergence::new(args.window as usize, args.tolerance);
+        let impact = wait_for_convergence(
+            tracker,
+            args.interval,
+            args.timeout,
+            || async {
+                let optimization = client.get_optimization(&args.id).await?;
+                Ok(optimization.actual_impact)
+            },
+            |progress| match progress {
+                None => eprintln!("  waiting for the first measurement"),
+                Some((samples, spread)) => eprintln!(
+                    "  {}/{} measurements, spread {}",
+                    samples,
+                    args.window,
+                    spread.map_or("-".to_string(), |s| format!("{:.2} pp", s))
+                ),
+            },
+        )
+        .await
+        .map_err(|e| match e {
+            CliError::Timeout(_) => CliError::Timeout(format!(
+                "actual impact of {} did not converge within {}",
+                args.id,
+                humantime::format_duration(args.timeout)
+            )),
+            other => other,
+        })?;
+
+        println!("{} Actual impact converged", "✓".green());
+        println!();
+
+        let output = formatter.write(&impact)?;
+        formatter.print(&output);
+
+        Ok(())
+    }
+
+    async fn bulk(
+        &self,
+        client: &dyn ApiClient,
+        formatter: &Formatter,
+        command: &BulkCommand,
+    ) -> CliResult<()> {
+        let (action, ids, yes, ignore_partial) = match command {
+            BulkCommand::Deploy {
+                ids,
+                yes,
+                ignore_partial,
+                ..
+            } => ("Deploy", ids, *yes, *ignore_partial),
+            BulkCommand::Delete {
+                ids,
+                yes,
+                ignore_partial,
+            } => ("Delete", ids, *yes, *ignore_partial),
+        };
+
+        if !yes {
+            let confirm = Confirm::new()
+                .with_prompt(format!("{} {} optimization(s)?", action, ids.len()))
+                .default(false)
+                .interact()
+                .unwrap();
+
+            if !confirm {
+                println!("{}", "Bulk operation cancelled".yellow());
+                return Ok(());
+            }
+        }
+
+        let results = match command {
+            BulkCommand::Deploy {
+                ids,
+                gradual,
+                percentage,
+                ..
+            } => {
+                client
+                    .bulk_deploy_optimizations(BulkDeployRequest {
+                        ids: ids.clone(),
+                        gradual: *gradual,
+                        rollout_percentage: *percentage,
+                    })
+                    .await?
+            }
+            BulkCommand::Delete { ids, .. } => client.bulk_delete_optimizations(ids).await?,
+        };
+
+        let output = formatter.write(&results)?;
+        formatter.print(&output);
+
+        // On stderr so `--output json` stays parseable
+        let failed = results.iter().filter(|item| !item.is_success()).count();
+        eprintln!(
+            "\n{} {} succeeded, {} {} failed",
+            "✓".green(),
+            results.len() - failed,
+            "✗".red(),
+            failed
+        );
+
+        check_partial_failure(&results, ignore_partial)
+    }
+}
+
+/// Decides when a series of actual-impact measurements has stabilized
+///
+/// The impact is converged once the last `window` distinct measurements all
+/// have cost, quality and latency deltas with a standard deviation within
+/// `tolerance` percentage points. A measurement is only counted when its
+/// `measured_until` has moved on, so polling faster than the server measures
+/// doesn't fake stability.
+#[derive(Debug)]
+struct ImpactConvergence {
+    window: usize,
+    tolerance: f64,
+    samples: VecDeque<ActualImpact>,
+}
+
+impl ImpactConvergence {
+    fn new(window: usize, tolerance: f64) -> Self {
+        Self {
+            window: window.max(2),
+            tolerance,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record a measurement, returning whether the impact has converged
+    fn push(&mut self, impact: &ActualImpact) -> bool {
+        let is_new = self
+            .samples
+            .back()
+            .map_or(true, |last| impact.measured_until > last.measured_until);
+        if is_new {
+            if self.samples.len() == self.window {
+                self.samples.pop_front();
+            }
+            self.samples.push_back(impact.clone());
+        }
+
+        self.samples.len() == self.window && self.spread().is_some_and(|s| s <= self.tolerance)
+    }
+
+    /// Largest standard deviation of the three deltas over the recorded
+    /// measurements, once there are at least two
+    fn spread(&self) -> Option<f64> {
+        if self.samples.len() < 2 {
+            return None;
+        }
+
+        let metrics: [fn(&ActualImpact) -> f64; 3] = [
+            |i| i.cost_reduction_pct,
+            |i| i.quality_delta_pct,
+            |i| i.latency_delta_pct,
+        ];
+        let n = self.samples.len() as f64;
+
+        metrics
+            .iter()
+            .map(|metric| {
+                let mean = self.samples.iter().map(metric).sum::<f64>() / n;
+                let variance = self
+                    .samples
+                    .iter()
+                    .map(|s| (metric(s) - mean).powi(2))
+                    .sum::<f64>()
+                    / n;
+                variance.sqrt()
+            })
+            .reduce(f64::max)
+    }
+}
+
+/// Poll `fetch` every `interval` until `tracker` reports convergence
+///
+/// `on_poll` receives `None` while there is no measurement yet, and otherwise
+/// the number of measurements in the window and their current spread. Fails
+/// with [`CliError::Timeout`] once `timeout` has passed.
+async fn wait_for_convergence<F, Fut>(
+    mut tracker: ImpactConvergence,
+    interval: Duration,
+    timeout: Duration,
+    mut fetch: F,
+    mut on_poll: impl FnMut(Option<(usize, Option<f64>)>),
+) -> CliResult<ActualImpact>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = CliResult<Option<ActualImpact>>>,
+{
+    let deadline = tokio::time::Instant::now() + timeout;
+
+    loop {
+        match fetch().await? {
+            Some(impact) => {
+                if tracker.push(&impact) {
+                    return Ok(impact);
+                }
+                on_poll(Some((tracker.samples.len(), tracker.spread())));
+            }
+            None => on_poll(None),
+        }
+
+        if tokio::time::Instant::now() + interval > deadline {
+            return Err(CliError::Timeout(format!(
+                "impact did not converge within {:?}",
+                timeout
+            )));
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Fail when any item of a bulk operation failed, unless partial failure is
+/// acceptable
+fn check_partial_failure(results: &[BulkItemResult], ignore_partial: bool) -> CliResult<()> {
+    let failed = results.iter().filter(|item| !item.is_success()).count();
+    if failed == 0 || ignore_partial {
+        Ok(())
+    } else {
+        Err(CliError::OperationFailed(format!(
+            "{} of {} optimization(s) failed",
+            failed,
+            results.len()
+        )))
+    }
+}
+
+/// Name the optimization that could not be found in a diff
+fn missing_side(err: CliError, id: &str) -> CliError {
+    match err {
+        CliError::NotFound(_) => CliError::NotFound(format!("Optimization {} not found", id)),
+        other => other,
+    }
+}
+
+/// Describe the LLM cost the server reported for an operation
+fn cost_summary(cost: &OperationCost) -> String {
+    format!(
+        "This operation cost ${:.4} ({} tokens)",
+        cost.cost_usd, cost.tokens_used
+    )
+}
+
+/// Render the leaderboard with columns in ranking order
+fn leaderboard_table(entries: &[StrategyLeaderboardEntry]) -> String {
+    let mut table = Table::new();
+    table
+        .load_preset(UTF8_FULL)
+        .set_content_arrangement(ContentArrangement::Dynamic)
+        .set_header(
+            ["#", "Strategy", "Optimizations", "Avg cost reduction", "Avg quality Δ", "Avg latency Δ", "Success rate"]
+                .into_iter()
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+        );
+
+    for entry in entries {
+        table.add_row(vec![
+            entry.rank.to_string(),
+            entry.strategy.clone(),
+            entry.optimizations.to_string(),
+            format!("{:.1}%", entry.avg_cost_reduction_pct),
+            format!("{:+.1}%", entry.avg_quality_delta_pct),
+            format!("{:+.1}%", entry.avg_latency_delta_pct),
+            format!("{:.0}%", entry.success_rate * 100.0),
+        ]);
+    }
+
+    table.to_string()
+}
+
+/// Field-level difference between two optimizations
+#[derive(Debug, Clone, Serialize)]
+pub struct OptimizationDiff {
+    pub id_a: String,
+    pub id_b: String,
+    pub changes: Vec<ParameterDiff>,
+    pub expected_impact: Vec<ImpactDiff>,
+}
+
+/// How a parameter differs between the two sides
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DiffKind {
+    /// Only changed by the second optimization
+    Added,
+    /// Only changed by the first optimization
+    Removed,
+    /// Changed by both, to different values
+    Modified,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ParameterDiff {
+    pub parameter: String,
+    pub kind: DiffKind,
+    pub a: Option<Value>,
+    pub b: Option<Value>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ImpactDiff {
+    pub metric: String,
+    pub a: f64,
+    pub b: f64,
+}
+
+impl OptimizationDiff {
+    /// Compare `a` against `b`; changes are matched by parameter name
+    pub fn between(a: &OptimizationResponse, b: &OptimizationResponse) -> Self {
+        let find = |opt: &OptimizationResponse, parameter: &str| {
+            opt.changes
+                .iter()
+                .find(|c| c.parameter == parameter)
+                .map(|c| c.new_value.clone())
+        };
+
+        let mut changes = Vec::new();
+
+        for change in &a.changes {
+            if changes.iter().any(|d: &ParameterDiff| d.parameter == change.parameter) {
+                continue;
+            }
+            match find(b, &change.parameter) {
+                None => changes.push(ParameterDiff {
+                    parameter: change.parameter.clone(),
+                    kind: DiffKind::Removed,
+                    a: Some(change.new_value.clone()),
+                    b: None,
+                }),
+                Some(other) if other != change.new_value => changes.push(ParameterDiff {
+                    parameter: change.parameter.clone(),
+                    kind: DiffKind::Modified,
+                    a: Some(change.new_value.clone()),
+                    b: Some(other),
+                }),
+                Some(_) => {}
+            }
+        }
+
+        for change in &b.changes {
+            let seen = changes.iter().any(|d| d.parameter == change.parameter);
+            if !seen && find(a, &change.parameter).is_none() {
+                changes.push(ParameterDiff {
+                    parameter: change.parameter.clone(),
+                    kind: DiffKind::Added,
+                    a: None,
+                    b: Some(change.new_value.clone()),
+                });
+            }
+        }
+
+        let (ia, ib) = (&a.expected_impact, &b.expected_impact);
+        let expected_impact = [
+            ("cost_reduction_pct", ia.cost_reduction_pct, ib.cost_reduction_pct),
+            ("quality_delta_pct", ia.quality_delta_pct, ib.quality_delta_pct),
+            ("latency_delta_pct", ia.latency_delta_pct, ib.latency_delta_pct),
+            ("confidence", ia.confidence, ib.confidence),
+        ]
+        .into_iter()
+        .filter(|(_, a, b)| (a - b).abs() > f64::EPSILON)
+        .map(|(metric, a, b)| ImpactDiff {
+            metric: metric.to_string(),
+            a,
+            b,
+        })
+        .collect();
+
+        Self {
+            id_a: a.id.clone(),
+            id_b: b.id.clone(),
+            changes,
+            expected_impact,
+        }
+    }
+
+    /// Whether the two optimizations are equivalent
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty() && self.expected_impact.is_empty()
+    }
+}
+
+impl ParameterDiff {
+    fn render(&self) -> String {
+        let show = |v: &Option<Value>| v.as_ref().map(|v| v.to_string()).unwrap_or_default();
+        match self.kind {
+            DiffKind::Added => format!("+ {}: {}", self.parameter, show(&self.b))
+                .green()
+                .to_string(),
+            DiffKind::Removed => format!("- {}: {}", self.parameter, show(&self.a))
+                .red()
+                .to_string(),
+            DiffKind::Modified => format!(
+                "{} {}: {} → {}",
+                "~".yellow(),
+                self.parameter,
+                show(&self.a),
+                show(&self.b)
+            ),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::mock::sample_optimization;
+    use crate::client::{ConfigurationChange, ExpectedImpact, MockClient};
+    use crate::output::get_formatter;
+    use crate::OutputFormat;
+    use std::collections::BTreeMap;
+
+    fn change(parameter: &str, value: Value) -> ConfigurationChange {
+        ConfigurationChange {
+            parameter: parameter.to_string(),
+            old_value: None,
+            new_value: value,
+            change_type: "update".to_string(),
+        }
+    }
+
+    fn optimization(id: &str, changes: Vec<ConfigurationChange>, cost: f64) -> OptimizationResponse {
+        OptimizationResponse {
+            id: id.to_string(),
+            target_services: vec!["chat".to_string()],
+            strategy: "balanced".to_string(),
+            status: "pending".to_string(),
+            changes,
+            config: serde_json::Value::Null,
+            revision: 1,
+            expected_impact: ExpectedImpact {
+                cost_reduction_pct: cost,
+                quality_delta_pct: 0.0,
+                latency_delta_pct: -2.0,
+                confidence: 0.9,
+            },
+            actual_impact: None,
+            rationale: String::new(),
+            created_at: Timestamp::now(),
+            deployed_at: None,
+            tags: BTreeMap::new(),
+            cost: None,
+        }
+    }
+
+    #[test]
+    fn test_leaderboard_table_lists_strategies_in_rank_order() {
+        let entry = |rank: u32, strategy: &str, cost: f64| StrategyLeaderboardEntry {
+            rank,
+            strategy: strategy.to_string(),
+            optimizations: 4,
+            avg_cost_reduction_pct: cost,
+            avg_quality_delta_pct: -1.5,
+            avg_latency_delta_pct: 0.0,
+            success_rate: 0.75,
+        };
+        let table = leaderboard_table(&[entry(1, "threshold_based", 25.0), entry(2, "ab_testing", 5.0)]);
+
+        let first = table.find("threshold_based").unwrap();
+        let second = table.find("ab_testing").unwrap();
+        assert!(first < second);
+        assert!(table.contains("25.0%"));
+        assert!(table.contains("-1.5%"));
+        assert!(table.contains("75%"));
+    }
+
+    #[test]
+    fn test_identical_optimizations_have_no_differences() {
+        let a = optimization("a", vec![change("model", json!("gpt-4o"))], 10.0);
+        let b = optimization("b", vec![change("model", json!("gpt-4o"))], 10.0);
+
+        assert!(OptimizationDiff::between(&a, &b).is_empty());
+    }
+
+    #[test]
+    fn test_changes_of_different_lengths_are_matched_by_parameter() {
+        let a = optimization(
+            "a",
+            vec![change("model", json!("gpt-4o")), change("max_tokens", json!(1024))],
+            10.0,
+        );
+        let b = optimization(
+            "b",
+            vec![
+                change("temperature", json!(0.2)),
+                change("model", json!("gpt-4o-mini")),
+                change("cache", json!(true)),
+            ],
+            15.0,
+        );
+
+        let diff = OptimizationDiff::between(&a, &b);
+        let kinds: Vec<(&str, DiffKind)> = diff
+            .changes
+            .iter()
+            .map(|d| (d.parameter.as_str(), d.kind))
+            .collect();
+
+        assert_eq!(
+            kinds,
+            [
+                ("model", DiffKind::Modified),
+                ("max_tokens", DiffKind::Removed),
+                ("temperature", DiffKind::Added),
+                ("cache", DiffKind::Added),
+            ]
+        );
+        assert_eq!(diff.expected_impact.len(), 1);
+        assert_eq!(diff.expected_impact[0].metric, "cost_reduction_pct");
+    }
+
+    #[test]
+    fn test_diff_serializes_for_machine_output() {
+        let a = optimization("a", vec![], 10.0);
+        let b = optimization("b", vec![change("model", json!("gpt-4o"))], 10.0);
+
+        let value = serde_json::to_value(OptimizationDiff::between(&a, &b)).unwrap();
+        assert_eq!(value["changes"][0]["kind"], "added");
+        assert_eq!(value["changes"][0]["a"], Value::Null);
+        assert_eq!(value["changes"][0]["b"], "gpt-4o");
+    }
+
+    #[test]
+    fn test_cost_summary() {
+        let cost = OperationCost {
+            cost_usd: 0.01234,
+            tokens_used: 1500,
+        };
+        assert_eq!(cost_summary(&cost), "This operation cost $0.0123 (1500 tokens)");
+    }
+
+    #[test]
+    fn test_missing_side_names_the_optimization() {
+        let err = missing_side(CliError::NotFound("Not found".to_string()), "opt-9");
+        assert!(matches!(err, CliError::NotFound(msg) if msg.contains("opt-9")));
+    }
+
+    #[test]
+    fn test_since_sets_query_from() {
+        use chrono::Utc;
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            list: ListArgs,
+        }
+
+        let before = Utc::now() - chrono::Duration::hours(1);
+        let cli = Cli::try_parse_from(["list", "--since", "-1h"]).unwrap();
+        let after = Utc::now() - chrono::Duration::hours(1);
+
+        let from = *cli.list.query().from.unwrap().as_datetime();
+        assert!(before <= from && from <= after);
+
+        assert!(Cli::try_parse_from(["list", "--since", "-1h", "--from", "2024-01-01"]).is_err());
+    }
+
+    #[test]
+    fn test_tag_arguments() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(subcommand)]
+            command: OptimizeCommand,
+        }
+
+        let cli = Cli::try_parse_from(["optimize", "list", "--tag", "team=ml", "--tag", "ticket=OPT-1"])
+            .unwrap();
+        let OptimizeCommand::List(list) = cli.command else {
+            panic!("expected list");
+        };
+        assert_eq!(
+            list.query().tags,
+            vec![
+                ("team".to_string(), "ml".to_string()),
+                ("ticket".to_string(), "OPT-1".to_string()),
+            ]
+        );
+
+        let cli = Cli::try_parse_from(["optimize", "tag", "remove", "opt-1", "team", "ticket"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            OptimizeCommand::Tag { command: TagCommand::Remove { keys, .. } } if keys == ["team", "ticket"]
+        ));
+
+        assert!(Cli::try_parse_from(["optimize", "create", "--tag", "team"]).is_err());
+        assert!(Cli::try_parse_from(["optimize", "tag", "add", "opt-1", "=ml"]).is_err());
+        assert!(Cli::try_parse_from(["optimize", "tag", "add", "opt-1"]).is_err());
+    }
+
+    fn measurement(minute: i64, cost: f64, quality: f64) -> ActualImpact {
+        let start = chrono::DateTime::parse_from_rfc3339("2024-01-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&chrono::Utc);
+        ActualImpact {
+            cost_reduction_pct: cost,
+            quality_delta_pct: quality,
+            latency_delta_pct: 0.0,
+            requests_affected: 1000,
+            measured_from: start.into(),
+            measured_until: (start + chrono::Duration::minutes(minute)).into(),
+        }
+    }
+
+    #[test]
+    fn test_impact_convergence_detected_once_samples_stabilize() {
+        let samples = [
+            measurement(1, 4.0, -2.0),
+            measurement(2, 15.0, 1.0),
+            measurement(3, 9.0, -0.5),
+            measurement(4, 12.3, -0.4),
+            measurement(5, 12.0, -0.5),
+            measurement(6, 11.9, -0.6),
+            measurement(7, 12.1, -0.5),
+        ];
+        let mut tracker = ImpactConvergence::new(4, 0.25);
+
+        let converged: Vec<bool> = samples.iter().map(|s| tracker.push(s)).collect();
+        assert_eq!(converged, vec![false, false, false, false, false, false, true]);
+        assert!(tracker.spread().unwrap() <= 0.25);
+    }
+
+    #[test]
+    fn test_repeated_measurement_is_not_counted() {
+        let mut tracker = ImpactConvergence::new(3, 0.5);
+        let sample = measurement(1, 10.0, 0.0);
+
+        for _ in 0..5 {
+            assert!(!tracker.push(&sample));
+        }
+        assert_eq!(tracker.samples.len(), 1);
+        assert_eq!(tracker.spread(), None);
+
+        assert!(!tracker.push(&measurement(2, 10.0, 0.0)));
+        assert!(tracker.push(&measurement(3, 10.1, 0.0)));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_convergence_polls_until_stable() {
+        let mut responses = vec![
+            None,
+            Some(measurement(1, 3.0, 0.0)),
+            Some(measurement(2, 10.0, 0.0)),
+            Some(measurement(3, 10.0, 0.1)),
+            Some(measurement(4, 10.1, 0.0)),
+        ]
+        .into_iter();
+        let mut polls = Vec::new();
+        let started = tokio::time::Instant::now();
+
+        let impact = wait_for_convergence(
+            ImpactConvergence::new(3, 0.1),
+            Duration::from_secs(30),
+            Duration::from_secs(600),
+            || {
+                let response = responses.next().flatten();
+                async move { Ok(response) }
+            },
+            |progress| polls.push(progress.map(|(samples, _)| samples)),
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(impact.cost_reduction_pct, 10.1);
+        assert_eq!(polls, vec![None, Some(1), Some(2), Some(3)]);
+        assert_eq!(started.elapsed(), Duration::from_secs(120));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_wait_for_convergence_times_out() {
+        let mut minute = 0;
+
+        let err = wait_for_convergence(
+            ImpactConvergence::new(3, 0.1),
+            Duration::from_secs(60),
+            Duration::from_secs(300),
+            || {
+                minute += 1;
+                let cost = if minute % 2 == 0 { 5.0 } else { 15.0 };
+                async move { Ok(Some(measurement(minute, cost, 0.0))) }
+            },
+            |_| {},
+        )
+        .await
+        .unwrap_err();
+
+        assert!(matches!(err, CliError::Timeout(_)), "{:?}", err);
+        assert_eq!(minute, 6);
+    }
+
+    #[test]
+    fn test_bulk_partial_failure() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(subcommand)]
+            command: OptimizeCommand,
+        }
+
+        let cli = Cli::try_parse_from(["optimize", "bulk", "delete", "a", "b", "--ignore-partial"])
+            .unwrap();
+        assert!(matches!(
+            cli.command,
+            OptimizeCommand::Bulk { command: BulkCommand::Delete { ids, ignore_partial: true, .. } }
+                if ids == ["a", "b"]
+        ));
+        assert!(Cli::try_parse_from(["optimize", "bulk", "deploy"]).is_err());
+
+        let item = |id: &str, status| BulkItemResult {
+            id: id.to_string(),
+            status,
+            error: None,
+        };
+        let mixed = [item("a", 200), item("b", 404)];
+
+        assert!(check_partial_failure(&[item("a", 204)], false).is_ok());
+        assert!(check_partial_failure(&mixed, true).is_ok());
+        let err = check_partial_failure(&mixed, false).unwrap_err();
+        assert_eq!(err.to_string(), "Operation failed: 1 of 2 optimization(s) failed");
+    }
+
+    #[test]
+    fn test_deploy_and_rollback_accept_yes_and_dry_run() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(subcommand)]
+            command: OptimizeCommand,
+        }
+
+        let cli = Cli::try_parse_from(["optimize", "deploy", "opt-1", "--dry-run"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            OptimizeCommand::Deploy { confirm: ConfirmArgs { dry_run: true, yes: false }, .. }
+        ));
+
+        let cli = Cli::try_parse_from(["optimize", "rollback", "opt-1", "-y"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            OptimizeCommand::Rollback { confirm: ConfirmArgs { dry_run: false, yes: true }, .. }
+        ));
+    }
+
+    fn deploy(id: &str, confirm: ConfirmArgs) -> OptimizeCommand {
+        OptimizeCommand::Deploy {
+            id: id.to_string(),
+            gradual: true,
+            percentage: 25.0,
+            confirm,
+        }
+    }
+
+    fn yes() -> ConfirmArgs {
+        ConfirmArgs {
+            yes: true,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_deploy_sends_rollout_to_client() {
+        let client = MockClient::new().with_optimization(sample_optimization("opt-1"));
+        let formatter = get_formatter(OutputFormat::Json);
+
+        deploy("opt-1", yes()).execute(&client, &formatter).await.unwrap();
+
+        assert_eq!(
+            client.calls_to("deploy_optimization"),
+            [json!({
+                "id": "opt-1",
+                "request": {"gradual": true, "rollout_percentage": 25.0}
+            })]
+        );
+        assert_eq!(client.optimization("opt-1").unwrap().status, "deployed");
+    }
+
+    #[tokio::test]
+    async fn test_deploy_dry_run_makes_no_calls_and_errors_propagate() {
+        let client = MockClient::new().with_error("deploy_optimization", || {
+            CliError::PermissionDenied("deployments are frozen".to_string())
+        });
+        let formatter = get_formatter(OutputFormat::Json);
+
+        let dry_run = ConfirmArgs {
+            dry_run: true,
+            ..Default::default()
+        };
+        deploy("opt-1", dry_run).execute(&client, &formatter).await.unwrap();
+        assert!(client.calls().is_empty());
+
+        let err = deploy("opt-1", yes()).execute(&client, &formatter).await.unwrap_err();
+        assert!(matches!(err, CliError::PermissionDenied(_)));
+        assert_eq!(err.exit_code(), 5);
+    }
 }
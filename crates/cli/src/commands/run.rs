@@ -22,7 +22,7 @@ pub enum RunCommand {
         list: bool,
 
         /// Output directory for results (defaults to benchmarks/output)
-        #[arg(long, short = 'o', value_name = "PATH")]
+        #[arg(long, value_name = "PATH")]
         output_dir: Option<std::path::PathBuf>,
 
         /// Skip writing output files
@@ -1,6 +1,6 @@
 //! Service management commands
 
-use crate::{client::ApiClient, output::OutputWriter, Formatter, CliResult};
+use crate::{client::ApiClient, commands::WatchArgs, output::OutputWriter, Formatter, CliResult};
 use clap::Subcommand;
 use colored::Colorize;
 
@@ -16,7 +16,10 @@ pub enum ServiceCommand {
     Restart,
 
     /// Get service status
-    Status,
+    Status {
+        #[command(flatten)]
+        watch: WatchArgs,
+    },
 
     /// Tail service logs
     #[command(name = "logs")]
@@ -41,7 +44,10 @@ impl ServiceCommand {
             ServiceCommand::Start => self.start(client).await,
             ServiceCommand::Stop => self.stop(client).await,
             ServiceCommand::Restart => self.restart(client).await,
-            ServiceCommand::Status => self.status(client, formatter).await,
+            ServiceCommand::Status { watch } => {
+                let formatter = &watch.formatter(formatter)?;
+                watch.run(|| self.status(client, formatter)).await
+            }
             ServiceCommand::Logs { lines, follow } => self.logs(*lines, *follow).await,
         }
     }
@@ -96,7 +102,7 @@ impl ServiceCommand {
         let status = client.get_service_status().await?;
 
         let output = formatter.write(&status)?;
-        println!("{}", output);
+        formatter.print(&output);
 
         if status.running {
             println!("\n{} Service is running", "✓".green());
@@ -1,14 +1,31 @@
 //! Utility commands
 
-use crate::{client::ApiClient, CliConfig, CliResult};
-use clap::Subcommand;
+use crate::output::{self, get_formatter, CsvFormatter, OutputFormat, OutputWriter};
+use crate::{client::ApiClient, CliConfig, CliError, CliResult, Formatter};
+use clap::{Args, Subcommand};
 use clap_complete::{generate, Shell};
 use colored::Colorize;
+use dialoguer::Confirm;
+use llm_optimizer_integrations::anthropic::{parse_model, ClaudeModel, TokenCounter, Usage};
+use serde::Serialize;
+use serde_json::{Map, Value};
+use serde_yaml::Mapping;
+use std::collections::BTreeSet;
+use std::fmt;
 use std::io;
+use std::path::{Path, PathBuf};
 
+/// Config keys holding URLs
+const URL_CONFIG_KEYS: &[&str] = &["api_url", "grpc_endpoint"];
+
+/// Utility commands
+///
+/// Only `convert` is reachable as `util convert`; the skipped variants back the
+/// top-level `init`, `doctor` and `estimate` commands.
 #[derive(Debug, Subcommand)]
 pub enum UtilCommand {
     /// Initialize CLI configuration
+    #[command(skip)]
     Init {
         /// API URL
         #[arg(long, default_value = "http://localhost:8080")]
@@ -23,27 +40,160 @@ pub enum UtilCommand {
         force: bool,
     },
 
-    /// Generate shell completions
-    Completions {
-        /// Shell type
-        #[arg(value_enum)]
-        shell: Shell,
+    /// Run system diagnostics
+    #[command(skip)]
+    Doctor {
+        /// Repair safe, reversible configuration problems
+        #[arg(long)]
+        fix: bool,
+
+        /// Apply fixes without confirmation
+        #[arg(short = 'y', long, requires = "fix")]
+        yes: bool,
+
+        /// Config file to check instead of the default location
+        #[arg(skip)]
+        config_file: Option<PathBuf>,
     },
 
-    /// Run system diagnostics
-    Doctor,
+    /// Convert a file between output formats
+    #[command(
+        long_about = "Convert a file between output formats without re-querying the API.\n\n\
+                      Nested objects are flattened into dotted column names when converting to CSV.\n\n\
+                      Example: llm-optimizer util convert --from json --to csv --file data.json"
+    )]
+    Convert {
+        /// Format of the input file (json, yaml, csv)
+        #[arg(long, value_name = "FORMAT")]
+        from: OutputFormat,
+
+        /// Format to emit (table, json, yaml, csv)
+        #[arg(long, value_name = "FORMAT")]
+        to: OutputFormat,
+
+        /// Input file
+        #[arg(long, value_name = "FILE")]
+        file: PathBuf,
+    },
+
+    /// Estimate the cost of Claude requests without calling the API
+    #[command(skip)]
+    Estimate(EstimateArgs),
+}
+
+/// Flags for `estimate`
+#[derive(Debug, Clone, Args)]
+pub struct EstimateArgs {
+    /// Claude model id or short name, e.g. claude-3-haiku
+    #[arg(short, long, value_parser = parse_claude_model)]
+    pub model: ClaudeModel,
+
+    /// Input tokens per request
+    #[arg(long, required_unless_present = "prompt_file", conflicts_with = "prompt_file")]
+    pub input_tokens: Option<u32>,
+
+    /// Count input tokens from this prompt file instead
+    #[arg(long, value_name = "FILE")]
+    pub prompt_file: Option<PathBuf>,
+
+    /// Output tokens per request
+    #[arg(long, default_value = "0")]
+    pub output_tokens: u32,
+
+    /// Number of identical requests, for batch estimates
+    #[arg(
+        long,
+        value_name = "N",
+        default_value = "1",
+        value_parser = clap::value_parser!(u32).range(1..)
+    )]
+    pub count: u32,
+}
+
+impl EstimateArgs {
+    /// Estimate from the flags, counting `--prompt-file` tokens locally
+    pub fn estimate(&self) -> CliResult<CostEstimate> {
+        let input_tokens = match (&self.prompt_file, self.input_tokens) {
+            (Some(path), _) => TokenCounter::new().count_text(&std::fs::read_to_string(path)?),
+            (None, Some(tokens)) => tokens,
+            (None, None) => {
+                return Err(CliError::InvalidInput(
+                    "--input-tokens or --prompt-file is required".to_string(),
+                ))
+            }
+        };
+
+        Ok(estimate_cost(self.model, input_tokens, self.output_tokens, self.count))
+    }
+}
+
+fn parse_claude_model(s: &str) -> Result<ClaudeModel, String> {
+    parse_model(s).map_err(|e| e.to_string())
+}
+
+/// Projected cost of `count` identical Claude requests
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct CostEstimate {
+    pub model: String,
+    pub input_tokens: u32,
+    pub output_tokens: u32,
+    pub count: u32,
+    /// USD
+    pub cost_per_request: f64,
+    /// USD
+    pub total_cost: f64,
+}
+
+/// Price `count` requests of the given size with the built-in pricing
+pub fn estimate_cost(
+    model: ClaudeModel,
+    input_tokens: u32,
+    output_tokens: u32,
+    count: u32,
+) -> CostEstimate {
+    let usage = Usage {
+        input_tokens,
+        output_tokens,
+        ..Default::default()
+    };
+    let cost_per_request = usage.calculate_cost(model);
+
+    CostEstimate {
+        model: model.as_str().to_string(),
+        input_tokens,
+        output_tokens,
+        count,
+        cost_per_request,
+        total_cost: cost_per_request * count as f64,
+    }
 }
 
 impl UtilCommand {
-    pub async fn execute(&self, client: Option<&dyn ApiClient>) -> CliResult<()> {
+    pub async fn execute(
+        &self,
+        client: Option<&dyn ApiClient>,
+        formatter: &Formatter,
+    ) -> CliResult<()> {
         match self {
             UtilCommand::Init {
                 api_url,
                 api_key,
                 force,
             } => self.init(api_url, api_key, *force).await,
-            UtilCommand::Completions { shell } => self.completions(*shell),
-            UtilCommand::Doctor => self.doctor(client).await,
+            UtilCommand::Doctor {
+                fix,
+                yes,
+                config_file,
+            } => {
+                let config_file = config_file.clone().or_else(CliConfig::default_config_file);
+                self.doctor(client, config_file.as_deref(), *fix, *yes).await
+            }
+            UtilCommand::Convert { from, to, file } => convert_file(*from, *to, file),
+            UtilCommand::Estimate(args) => {
+                let output = formatter.write(&args.estimate()?)?;
+                formatter.print(&output);
+                Ok(())
+            }
         }
     }
 
@@ -90,26 +240,36 @@ impl UtilCommand {
         Ok(())
     }
 
-    fn completions(&self, shell: Shell) -> CliResult<()> {
-        // Note: This would need access to the clap Command from main.rs
-        // For now, we'll print instructions
-        println!("To generate completions, use:");
-        println!("  llm-optimizer completions {}", shell);
-        println!("\nThis should be called from the main binary, not the library.");
-        Ok(())
-    }
-
-    async fn doctor(&self, client: Option<&dyn ApiClient>) -> CliResult<()> {
+    async fn doctor(
+        &self,
+        client: Option<&dyn ApiClient>,
+        config_file: Option<&Path>,
+        fix: bool,
+        yes: bool,
+    ) -> CliResult<()> {
         println!("{}", "Running system diagnostics...\n".cyan().bold());
 
         // Check config file
         print!("Checking configuration file... ");
-        if let Some(config_file) = CliConfig::default_config_file() {
-            if config_file.exists() {
-                println!("{}", "✓".green());
-            } else {
-                println!("{}", "✗ Not found".yellow());
-                println!("  Run 'llm-optimizer init' to create configuration");
+        if let Some(config_file) = config_file {
+            match detect_config_fixes(config_file) {
+                Ok(fixes) if fixes.is_empty() => println!("{}", "✓".green()),
+                Ok(fixes) => {
+                    println!("{}", format!("✗ {} issue(s)", fixes.len()).yellow());
+                    for config_fix in &fixes {
+                        println!("  - {}", config_fix);
+                    }
+
+                    if fix {
+                        fix_config(config_file, &fixes, yes)?;
+                    } else {
+                        println!("  Run 'llm-optimizer doctor --fix' to repair");
+                    }
+                }
+                Err(e) => {
+                    println!("{}", "✗ Unreadable".red());
+                    println!("  Error: {}", e);
+                }
             }
         } else {
             println!("{}", "✗ Cannot determine config directory".red());
@@ -158,3 +318,476 @@ impl UtilCommand {
     }
 }
 
+/// A safe, reversible configuration repair offered by `doctor --fix`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigFix {
+    /// Write a config file with default settings, creating its directory
+    CreateConfig { path: PathBuf },
+    /// Add the missing scheme to a URL value
+    AddUrlScheme {
+        key: &'static str,
+        from: String,
+        to: String,
+    },
+}
+
+impl fmt::Display for ConfigFix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigFix::CreateConfig { path } => {
+                write!(f, "create {} with default settings", path.display())
+            }
+            ConfigFix::AddUrlScheme { key, from, to } => {
+                write!(f, "add missing scheme to {}: '{}' -> '{}'", key, from, to)
+            }
+        }
+    }
+}
+
+/// Find configuration problems in `path` that can be repaired safely
+pub fn detect_config_fixes(path: &Path) -> CliResult<Vec<ConfigFix>> {
+    if !path.exists() {
+        return Ok(vec![ConfigFix::CreateConfig {
+            path: path.to_path_buf(),
+        }]);
+    }
+
+    let config = read_config_mapping(path)?;
+    let mut fixes = Vec::new();
+
+    for &key in URL_CONFIG_KEYS {
+        let Some(url) = config.get(key).and_then(serde_yaml::Value::as_str) else {
+            continue;
+        };
+        // `${VAR}` references are resolved at load time
+        if url.is_empty() || url.contains("://") || url.contains("${") {
+            continue;
+        }
+
+        let host = url.split([':', '/']).next().unwrap_or_default();
+        let scheme = if host == "localhost" || host.starts_with("127.") {
+            "http"
+        } else {
+            "https"
+        };
+        fixes.push(ConfigFix::AddUrlScheme {
+            key,
+            from: url.to_string(),
+            to: format!("{}://{}", scheme, url),
+        });
+    }
+
+    Ok(fixes)
+}
+
+/// Apply `fixes` to the config file at `path`
+///
+/// The original file is copied to `<path>.bak` before it is modified; the
+/// backup path is returned when one was written.
+pub fn apply_config_fixes(path: &Path, fixes: &[ConfigFix]) -> CliResult<Option<PathBuf>> {
+    if fixes.iter().any(|f| matches!(f, ConfigFix::CreateConfig { .. })) {
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        CliConfig::default().save_to_file(path)?;
+        return Ok(None);
+    }
+
+    let mut config = read_config_mapping(path)?;
+    for config_fix in fixes {
+        match config_fix {
+            ConfigFix::CreateConfig { .. } => {}
+            ConfigFix::AddUrlScheme { key, to, .. } => {
+                config.insert((*key).into(), to.as_str().into());
+            }
+        }
+    }
+
+    let mut backup = path.as_os_str().to_owned();
+    backup.push(".bak");
+    let backup = PathBuf::from(backup);
+    std::fs::copy(path, &backup)?;
+
+    std::fs::write(path, serde_yaml::to_string(&config)?)?;
+    Ok(Some(backup))
+}
+
+/// Confirm each fix unless `yes`, then apply the accepted ones
+fn fix_config(path: &Path, fixes: &[ConfigFix], yes: bool) -> CliResult<()> {
+    let accepted: Vec<ConfigFix> = fixes
+        .iter()
+        .filter(|config_fix| {
+            yes || Confirm::new()
+                .with_prompt(format!("Fix: {}?", config_fix))
+                .default(true)
+                .interact()
+                .unwrap()
+        })
+        .cloned()
+        .collect();
+
+    if accepted.is_empty() {
+        println!("  {}", "No fixes applied".yellow());
+        return Ok(());
+    }
+
+    if let Some(backup) = apply_config_fixes(path, &accepted)? {
+        println!("  Previous configuration saved to {}", backup.display());
+    }
+    println!("  {} Applied {} fix(es)", "✓".green(), accepted.len());
+
+    Ok(())
+}
+
+fn read_config_mapping(path: &Path) -> CliResult<Mapping> {
+    let contents = std::fs::read_to_string(path)?;
+    if contents.trim().is_empty() {
+        return Ok(Mapping::new());
+    }
+    Ok(serde_yaml::from_str(&contents)?)
+}
+
+/// Write a completion script for `shell` covering every subcommand of `cmd`
+pub fn write_completions(shell: Shell, cmd: &mut clap::Command, out: &mut dyn io::Write) {
+    let bin_name = cmd.get_name().to_string();
+    generate(shell, cmd, bin_name, out);
+}
+
+/// Convert the file at `path` from one format to another and emit the result
+pub fn convert_file(from: OutputFormat, to: OutputFormat, path: &Path) -> CliResult<()> {
+    let input = std::fs::read_to_string(path)?;
+    output::file::emit(convert(from, to, &input)?.trim_end());
+    Ok(())
+}
+
+/// Re-emit `input`, written in the `from` format, through the `to` formatter
+pub fn convert(from: OutputFormat, to: OutputFormat, input: &str) -> CliResult<String> {
+    let value = parse_input(from, input)?;
+    match to {
+        OutputFormat::Csv => {
+            check_csv_layout(&value)?;
+            // Arrays stay JSON-encoded so the CSV can be converted back
+            CsvFormatter::new().with_json_arrays().write(&value)
+        }
+        // Tables would otherwise show nested objects as "[object]"; data
+        // without a row layout is printed as-is
+        OutputFormat::Table => get_formatter(to).write(&table_rows(&value).unwrap_or(value)),
+        _ => get_formatter(to).write(&value),
+    }
+}
+
+fn parse_input(format: OutputFormat, input: &str) -> CliResult<Value> {
+    match format {
+        OutputFormat::Json => Ok(serde_json::from_str(input)?),
+        OutputFormat::Yaml => Ok(serde_yaml::from_str(input)?),
+        OutputFormat::Csv => parse_csv(input),
+        OutputFormat::Table => Err(CliError::InvalidInput(
+            "table output is for display only and cannot be converted; use --from json, yaml or csv"
+                .to_string(),
+        )),
+    }
+}
+
+/// Read CSV rows back into objects, undoing the flattening applied on the way out
+fn parse_csv(input: &str) -> CliResult<Value> {
+    let mut reader = csv::Reader::from_reader(input.as_bytes());
+    let headers = reader.headers()?.clone();
+
+    let mut rows = Vec::new();
+    for record in reader.records() {
+        let record = record?;
+        let mut row = Map::new();
+        for (column, field) in headers.iter().zip(record.iter()) {
+            insert_path(&mut row, column, parse_csv_field(field));
+        }
+        rows.push(Value::Object(row));
+    }
+
+    Ok(Value::Array(rows))
+}
+
+/// Infer the JSON type of a CSV cell
+///
+/// Empty cells are null, and cells holding a JSON array or object (as written
+/// by the CSV formatter) are decoded; everything else that is not a bool or
+/// number stays a string.
+fn parse_csv_field(field: &str) -> Value {
+    if field.is_empty() {
+        return Value::Null;
+    }
+    if let Ok(b) = field.parse::<bool>() {
+        return Value::Bool(b);
+    }
+    if let Ok(n) = field.parse::<i64>() {
+        return Value::from(n);
+    }
+    if let Some(n) = field.parse::<f64>().ok().and_then(serde_json::Number::from_f64) {
+        return Value::Number(n);
+    }
+    if field.starts_with('[') || field.starts_with('{') {
+        if let Ok(value) = serde_json::from_str(field) {
+            return value;
+        }
+    }
+    Value::String(field.to_string())
+}
+
+/// Insert `value` at a dotted column path, creating nested objects as needed
+fn insert_path(obj: &mut Map<String, Value>, path: &str, value: Value) {
+    match path.split_once('.') {
+        Some((head, rest)) => {
+            let child = obj
+                .entry(head)
+                .or_insert_with(|| Value::Object(Map::new()));
+            if !child.is_object() {
+                *child = Value::Object(Map::new());
+            }
+            if let Value::Object(child) = child {
+                insert_path(child, rest, value);
+            }
+        }
+        None => {
+            obj.insert(path.to_string(), value);
+        }
+    }
+}
+
+/// Reject data the CSV formatter has no column layout for
+fn check_csv_layout(value: &Value) -> CliResult<()> {
+    match value {
+        Value::Array(items) if items.iter().any(Value::is_array) => Err(CliError::InvalidInput(
+            "nested arrays have no column layout and cannot be converted to CSV".to_string(),
+        )),
+        Value::Array(_) | Value::Object(_) => Ok(()),
+        scalar => Err(CliError::InvalidInput(format!(
+            "a single value ({}) has no rows or columns to write as CSV",
+            scalar
+        ))),
+    }
+}
+
+/// Shape `value` into rows for the table formatter, which takes its columns
+/// from the first row
+///
+/// Nested objects are flattened into dotted column names (`config.model`),
+/// every row gets the union of all columns, and array items that are not
+/// objects become a single `value` column. Returns `None` for data without
+/// a row layout.
+fn table_rows(value: &Value) -> Option<Value> {
+    let flat = |obj: &Map<String, Value>| -> Map<String, Value> {
+        output::flatten(obj)
+            .into_iter()
+            .map(|(key, value)| (key, value.clone()))
+            .collect()
+    };
+
+    match value {
+        Value::Array(items) => {
+            let mut rows = Vec::with_capacity(items.len());
+            for item in items {
+                rows.push(match item {
+                    Value::Object(obj) => flat(obj),
+                    Value::Array(_) => return None,
+                    scalar => Map::from_iter([("value".to_string(), scalar.clone())]),
+                });
+            }
+
+            let columns: BTreeSet<String> = rows.iter().flat_map(|r| r.keys().cloned()).collect();
+            for row in &mut rows {
+                for column in &columns {
+                    row.entry(column.clone()).or_insert(Value::Null);
+                }
+            }
+
+            Some(Value::Array(rows.into_iter().map(Value::Object).collect()))
+        }
+        Value::Object(obj) => Some(Value::Object(flat(obj))),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn sample() -> Value {
+        json!([
+            {
+                "id": "opt-1",
+                "cost": 12.5,
+                "active": true,
+                "config": { "model": "haiku", "max_tokens": 300 },
+                "tags": ["latency", "cost"],
+                "note": null
+            },
+            {
+                "id": "opt-2",
+                "cost": 3,
+                "active": false,
+                "config": { "model": "sonnet", "max_tokens": 1024 },
+                "tags": [],
+                "note": "manual"
+            }
+        ])
+    }
+
+    #[test]
+    fn test_estimate_cost_scales_with_count() {
+        let estimate = estimate_cost(ClaudeModel::Claude3Haiku, 10_000, 2_000, 100);
+
+        assert_eq!(estimate.model, "claude-3-haiku-20240307");
+        // $0.25/MTok in and $1.25/MTok out
+        assert!((estimate.cost_per_request - 0.005).abs() < 1e-12);
+        assert!((estimate.total_cost - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_estimate_counts_prompt_file_tokens() {
+        use clap::Parser;
+
+        #[derive(Parser)]
+        struct Cli {
+            #[command(flatten)]
+            estimate: EstimateArgs,
+        }
+
+        let prompt = "Summarize the following support ticket in one sentence. ".repeat(20);
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        io::Write::write_all(&mut file, prompt.as_bytes()).unwrap();
+        let path = file.path().to_str().unwrap();
+
+        let cli = Cli::try_parse_from(["estimate", "-m", "claude-3-5-sonnet", "--prompt-file", path])
+            .unwrap();
+        let estimate = cli.estimate.estimate().unwrap();
+        assert_eq!(estimate.model, "claude-3-5-sonnet-20241022");
+        assert_eq!(estimate.input_tokens, TokenCounter::new().count_text(&prompt));
+        assert_eq!(estimate.count, 1);
+
+        assert!(Cli::try_parse_from(["estimate", "-m", "claude-3-haiku"]).is_err());
+        assert!(Cli::try_parse_from(["estimate", "-m", "gpt-4", "--input-tokens", "1"]).is_err());
+        assert!(Cli::try_parse_from([
+            "estimate", "-m", "claude-3-haiku", "--input-tokens", "1", "--prompt-file", path,
+        ])
+        .is_err());
+    }
+
+    #[test]
+    fn test_fix_creates_missing_config_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("llm-optimizer").join("config.yaml");
+
+        let fixes = detect_config_fixes(&path).unwrap();
+        assert_eq!(fixes, vec![ConfigFix::CreateConfig { path: path.clone() }]);
+
+        assert_eq!(apply_config_fixes(&path, &fixes).unwrap(), None);
+        let config = CliConfig::from_file(&path).unwrap();
+        assert_eq!(config.api_url, CliConfig::default().api_url);
+        assert!(detect_config_fixes(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fix_adds_missing_url_scheme() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        let original = "api_url: api.example.com/v1\ntimeout: 10\n";
+        std::fs::write(&path, original).unwrap();
+
+        let fixes = detect_config_fixes(&path).unwrap();
+        assert_eq!(
+            fixes,
+            vec![ConfigFix::AddUrlScheme {
+                key: "api_url",
+                from: "api.example.com/v1".to_string(),
+                to: "https://api.example.com/v1".to_string(),
+            }]
+        );
+
+        let backup = apply_config_fixes(&path, &fixes).unwrap().unwrap();
+        assert_eq!(std::fs::read_to_string(backup).unwrap(), original);
+
+        let config = CliConfig::from_file(&path).unwrap();
+        assert_eq!(config.api_url, "https://api.example.com/v1");
+        assert_eq!(config.timeout, 10);
+        assert!(detect_config_fixes(&path).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_fix_leaves_valid_and_templated_urls_alone() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(
+            &path,
+            "api_url: ${LLM_OPTIMIZER_URL:-localhost:8080}\n\
+             grpc_endpoint: localhost:50051\n\
+             output_format: table\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            detect_config_fixes(&path).unwrap(),
+            vec![ConfigFix::AddUrlScheme {
+                key: "grpc_endpoint",
+                from: "localhost:50051".to_string(),
+                to: "http://localhost:50051".to_string(),
+            }]
+        );
+    }
+
+    #[test]
+    fn test_convert_between_each_format_pair() {
+        let data = sample();
+        let json = serde_json::to_string(&data).unwrap();
+        let sources = [OutputFormat::Json, OutputFormat::Yaml, OutputFormat::Csv];
+        let targets = [
+            OutputFormat::Json,
+            OutputFormat::Yaml,
+            OutputFormat::Csv,
+            OutputFormat::Table,
+        ];
+
+        for from in sources {
+            let input = convert(OutputFormat::Json, from, &json).unwrap();
+            for to in targets {
+                let output = convert(from, to, &input)
+                    .unwrap_or_else(|e| panic!("{} -> {} failed: {}", from, to, e));
+
+                if to == OutputFormat::Table {
+                    assert!(output.contains("opt-1"), "{} -> table: {}", from, output);
+                    assert!(output.contains("sonnet"), "{} -> table: {}", from, output);
+                } else {
+                    assert_eq!(parse_input(to, &output).unwrap(), data, "{} -> {}", from, to);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_nested_json_to_csv_flattens_columns() {
+        let input = r#"[
+            {"id": "a", "config": {"model": "haiku", "limits": {"max_tokens": 300}}},
+            {"id": "b", "owner": "team-x"}
+        ]"#;
+
+        let csv = convert(OutputFormat::Json, OutputFormat::Csv, input).unwrap();
+        let mut lines = csv.lines();
+        assert_eq!(
+            lines.next(),
+            Some("config.limits.max_tokens,config.model,id,owner")
+        );
+        assert_eq!(lines.next(), Some("300,haiku,a,"));
+        assert_eq!(lines.next(), Some(",,b,team-x"));
+    }
+
+    #[test]
+    fn test_convert_rejects_meaningless_conversions() {
+        let err = convert(OutputFormat::Table, OutputFormat::Json, "anything").unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+
+        let err = convert(OutputFormat::Json, OutputFormat::Csv, "42").unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+
+        let err = convert(OutputFormat::Json, OutputFormat::Csv, "[[1, 2]]").unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+    }
+}
@@ -0,0 +1,202 @@
+//! Re-running read-only commands on an interval
+
+use crate::{output, CliError, CliResult, Formatter};
+use clap::Args;
+use colored::Colorize;
+use std::future::Future;
+use std::io::Write;
+use std::time::Duration;
+use tokio::time::MissedTickBehavior;
+
+/// Seconds between refreshes when only `--watch` is given
+pub const DEFAULT_WATCH_INTERVAL_SECS: u64 = 2;
+
+/// Clear the screen and move the cursor to the top left
+const CLEAR_SCREEN: &str = "\x1B[2J\x1B[H";
+
+/// Flags for commands that can redraw their output on an interval
+#[derive(Debug, Clone, Default, Args)]
+pub struct WatchArgs {
+    /// Re-run the command and redraw the screen until Ctrl+C
+    #[arg(short, long)]
+    pub watch: bool,
+
+    /// Seconds between refreshes; implies --watch
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub interval: Option<u64>,
+
+    /// Stop after this many refreshes; implies --watch
+    #[arg(
+        long,
+        value_name = "N",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    pub count: Option<u64>,
+}
+
+impl WatchArgs {
+    /// Whether the command should be re-run
+    pub fn is_enabled(&self) -> bool {
+        self.watch || self.interval.is_some() || self.count.is_some()
+    }
+
+    /// Time between refreshes
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval.unwrap_or(DEFAULT_WATCH_INTERVAL_SECS))
+    }
+
+    /// The formatter frames are rendered with
+    ///
+    /// Watching redraws the screen, so CSV, which is meant for files and
+    /// pipes, and `--output-file` are rejected and table output is never
+    /// paged.
+    pub fn formatter(&self, formatter: &Formatter) -> CliResult<Formatter> {
+        if !self.is_enabled() {
+            return Ok(formatter.clone());
+        }
+        if output::file::is_capturing() {
+            return Err(CliError::InvalidInput(
+                "--watch cannot be combined with --output-file".to_string(),
+            ));
+        }
+        if let Formatter::Csv(_) = formatter {
+            return Err(CliError::InvalidInput(
+                "--watch does not support --output csv; use table, json or yaml".to_string(),
+            ));
+        }
+        Ok(formatter.without_pager())
+    }
+
+    /// Run `frame` once, or on every interval until Ctrl+C or `--count`
+    /// frames when watching
+    pub async fn run<F, Fut>(&self, mut frame: F) -> CliResult<()>
+    where
+        F: FnMut() -> Fut,
+        Fut: Future<Output = CliResult<()>>,
+    {
+        if !self.is_enabled() {
+            return frame().await;
+        }
+
+        let interval = self.interval();
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let ctrl_c = tokio::signal::ctrl_c();
+        tokio::pin!(ctrl_c);
+
+        let mut frames = 0;
+        loop {
+            tokio::select! {
+                _ = &mut ctrl_c => return Ok(()),
+                _ = ticker.tick() => {}
+            }
+
+            print!("{}", CLEAR_SCREEN);
+            println!(
+                "{}\n",
+                format!(
+                    "Every {}s (Ctrl+C to stop)    {}",
+                    interval.as_secs(),
+                    chrono::Local::now().format("%Y-%m-%d %H:%M:%S")
+                )
+                .dimmed()
+            );
+            tokio::select! {
+                _ = &mut ctrl_c => return Ok(()),
+                result = frame() => result?,
+            }
+            std::io::stdout().flush()?;
+
+            frames += 1;
+            if self.count.is_some_and(|count| frames >= count) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::output::{get_formatter, OutputFormat};
+    use std::cell::Cell;
+
+    #[tokio::test]
+    async fn test_runs_once_without_watch() {
+        let frames = &Cell::new(0);
+        let args = WatchArgs::default();
+
+        args.run(move || async move {
+            frames.set(frames.get() + 1);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(frames.get(), 1);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_count_caps_refreshes() {
+        let frames = &Cell::new(0);
+        let args = WatchArgs {
+            interval: Some(5),
+            count: Some(3),
+            ..Default::default()
+        };
+        let started = tokio::time::Instant::now();
+
+        args.run(move || async move {
+            frames.set(frames.get() + 1);
+            Ok(())
+        })
+        .await
+        .unwrap();
+
+        assert_eq!(frames.get(), 3);
+        // The first frame is drawn straight away
+        assert_eq!(started.elapsed(), Duration::from_secs(10));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_frame_error_stops_watching() {
+        let frames = &Cell::new(0);
+        let args = WatchArgs {
+            watch: true,
+            ..Default::default()
+        };
+
+        let err = args
+            .run(move || async move {
+                frames.set(frames.get() + 1);
+                Err(CliError::Api("unavailable".to_string()))
+            })
+            .await
+            .unwrap_err();
+
+        assert!(matches!(err, CliError::Api(_)));
+        assert_eq!(frames.get(), 1);
+    }
+
+    #[test]
+    fn test_watch_rejects_csv_output() {
+        let args = WatchArgs {
+            watch: true,
+            ..Default::default()
+        };
+
+        let err = args
+            .formatter(&get_formatter(OutputFormat::Csv))
+            .unwrap_err();
+        assert!(matches!(err, CliError::InvalidInput(_)));
+        assert!(args.formatter(&get_formatter(OutputFormat::Table)).is_ok());
+
+        // Without --watch any format is fine
+        let once = WatchArgs::default();
+        assert!(once.formatter(&get_formatter(OutputFormat::Csv)).is_ok());
+    }
+}
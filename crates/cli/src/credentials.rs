@@ -0,0 +1,47 @@
+//! API key storage in the platform keychain
+//!
+//! Keys are stored per API base URL so that different deployments can use
+//! different credentials. Keychain failures are never fatal for reads: callers
+//! fall back to the config-file and environment based behavior.
+
+use crate::{CliError, CliResult};
+
+/// Keychain service name under which credentials are stored
+const KEYCHAIN_SERVICE: &str = "llm-optimizer";
+
+fn entry(base_url: &str) -> keyring::Result<keyring::Entry> {
+    keyring::Entry::new(KEYCHAIN_SERVICE, base_url.trim_end_matches('/'))
+}
+
+/// Load the stored API key for `base_url`, if any
+pub fn load_api_key(base_url: &str) -> Option<String> {
+    match entry(base_url).and_then(|e| e.get_password()) {
+        Ok(key) => Some(key),
+        Err(keyring::Error::NoEntry) => None,
+        Err(e) => {
+            tracing::debug!("Keychain unavailable, ignoring stored credentials: {}", e);
+            None
+        }
+    }
+}
+
+/// Store the API key for `base_url`
+pub fn store_api_key(base_url: &str, api_key: &str) -> CliResult<()> {
+    entry(base_url)
+        .and_then(|e| e.set_password(api_key))
+        .map_err(|e| CliError::OperationFailed(format!("Could not store API key in keychain: {}", e)))
+}
+
+/// Delete the stored API key for `base_url`
+///
+/// Returns `false` if no credential was stored.
+pub fn delete_api_key(base_url: &str) -> CliResult<bool> {
+    match entry(base_url).and_then(|e| e.delete_password()) {
+        Ok(()) => Ok(true),
+        Err(keyring::Error::NoEntry) => Ok(false),
+        Err(e) => Err(CliError::OperationFailed(format!(
+            "Could not remove API key from keychain: {}",
+            e
+        ))),
+    }
+}
@@ -1,14 +1,225 @@
 //! Interactive mode for CLI
 
-use crate::{client::ApiClient, Formatter, CliResult};
+use crate::{
+    client::ApiClient,
+    commands::{AdminCommand, ConfigCommand, IntegrationCommand, MetricsCommand, OptimizeCommand},
+    CliConfig, CliError, CliResult, Formatter,
+};
+use clap::{Parser, Subcommand};
 use colored::Colorize;
 use dialoguer::{theme::ColorfulTheme, Select};
+use rustyline::{error::ReadlineError, history::FileHistory, Config, Editor};
+use std::path::PathBuf;
 
-/// Interactive mode main menu
+/// Default number of entries kept in the interactive history
+pub const DEFAULT_HISTORY_SIZE: usize = 1000;
+
+/// Words that mark a line as carrying a secret; such lines never reach the
+/// history file
+const SENSITIVE_KEYWORDS: &[&str] = &["api_key", "api-key", "apikey", "password", "secret", "token"];
+
+const PROMPT: &str = "llm-optimizer> ";
+
+type LineEditor = Editor<(), FileHistory>;
+
+/// Interactive mode options
+#[derive(Debug, Clone)]
+pub struct InteractiveOptions {
+    /// File the command history is persisted to; `None` keeps it in memory
+    pub history_file: Option<PathBuf>,
+
+    /// Maximum number of history entries kept
+    pub history_size: usize,
+}
+
+impl Default for InteractiveOptions {
+    fn default() -> Self {
+        Self {
+            history_file: default_history_file(),
+            history_size: DEFAULT_HISTORY_SIZE,
+        }
+    }
+}
+
+/// Default history file, `~/.config/llm-optimizer/history.txt` on Linux
+pub fn default_history_file() -> Option<PathBuf> {
+    CliConfig::default_config_dir().map(|d| d.join("history.txt"))
+}
+
+/// A single line typed at the interactive prompt
+#[derive(Debug, Parser)]
+#[command(no_binary_name = true, disable_version_flag = true)]
+struct ReplLine {
+    #[command(subcommand)]
+    command: ReplCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum ReplCommand {
+    /// Optimization operations
+    Optimize {
+        #[command(subcommand)]
+        command: OptimizeCommand,
+    },
+
+    /// Configuration management
+    Config {
+        #[command(subcommand)]
+        command: ConfigCommand,
+    },
+
+    /// Metrics and analytics
+    Metrics {
+        #[command(subcommand)]
+        command: MetricsCommand,
+    },
+
+    /// Integration management
+    Integration {
+        #[command(subcommand)]
+        command: IntegrationCommand,
+    },
+
+    /// Administrative operations
+    Admin {
+        #[command(subcommand)]
+        command: AdminCommand,
+    },
+
+    /// Open the guided menu
+    Menu,
+
+    /// Leave interactive mode
+    #[command(alias = "quit")]
+    Exit,
+}
+
+/// Interactive mode prompt with persistent history
+///
+/// Lines take the same subcommands as the CLI (e.g. `optimize list`).
+/// Ctrl-R searches the history, Ctrl-D or `exit` leaves.
 pub async fn run_interactive_mode(
     client: &dyn ApiClient,
     formatter: &Formatter,
+    options: &InteractiveOptions,
 ) -> CliResult<()> {
+    let mut editor = create_editor(options)?;
+
+    println!("\n{}", "=== LLM Auto Optimizer ===".cyan().bold());
+    println!(
+        "Type {} for commands, {} for the guided menu, Ctrl-R to search history",
+        "help".bold(),
+        "menu".bold()
+    );
+
+    loop {
+        let line = match editor.readline(PROMPT) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                return Err(CliError::OperationFailed(format!("Failed to read input: {}", e)))
+            }
+        };
+
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        record_history(&mut editor, line);
+
+        let words = match shell_words::split(line) {
+            Ok(words) => words,
+            Err(e) => {
+                eprintln!("{} {}", "Error:".red().bold(), e);
+                continue;
+            }
+        };
+
+        let command = match ReplLine::try_parse_from(words) {
+            Ok(parsed) => parsed.command,
+            Err(e) => {
+                let _ = e.print();
+                continue;
+            }
+        };
+
+        let result = match command {
+            ReplCommand::Optimize { command } => command.execute(client, formatter).await,
+            ReplCommand::Config { command } => command.execute(client, formatter).await,
+            ReplCommand::Metrics { command } => command.execute(client, formatter).await,
+            ReplCommand::Integration { command } => command.execute(client, formatter).await,
+            ReplCommand::Admin { command } => command.execute(client, formatter).await,
+            ReplCommand::Menu => run_menu(client, formatter).await,
+            ReplCommand::Exit => break,
+        };
+
+        if let Err(e) = result {
+            eprintln!("{} {}", "Error:".red().bold(), e);
+        }
+    }
+
+    save_history(&mut editor, options);
+    println!("{}", "Goodbye!".green());
+
+    Ok(())
+}
+
+/// Build a line editor and load any existing history
+fn create_editor(options: &InteractiveOptions) -> CliResult<LineEditor> {
+    let config = Config::builder()
+        .max_history_size(options.history_size)
+        .and_then(|b| b.history_ignore_dups(true))
+        .map_err(|e| CliError::Config(format!("Invalid history settings: {}", e)))?
+        .auto_add_history(false)
+        .build();
+
+    let mut editor = LineEditor::with_config(config)
+        .map_err(|e| CliError::OperationFailed(format!("Failed to start line editor: {}", e)))?;
+
+    if let Some(path) = options.history_file.as_ref().filter(|p| p.exists()) {
+        if let Err(e) = editor.load_history(path) {
+            tracing::warn!("Failed to load history from {}: {}", path.display(), e);
+        }
+    }
+
+    Ok(editor)
+}
+
+/// Add a line to the history unless it carries a secret
+fn record_history(editor: &mut LineEditor, line: &str) {
+    if is_sensitive(line) {
+        return;
+    }
+    let _ = editor.add_history_entry(line);
+}
+
+/// Whether a line looks like it sets or passes a secret
+fn is_sensitive(line: &str) -> bool {
+    let line = line.to_lowercase();
+    SENSITIVE_KEYWORDS.iter().any(|keyword| line.contains(keyword))
+}
+
+/// Persist the history; failures are logged rather than ending the session
+fn save_history(editor: &mut LineEditor, options: &InteractiveOptions) {
+    let Some(path) = &options.history_file else {
+        return;
+    };
+
+    if let Some(parent) = path.parent() {
+        if let Err(e) = std::fs::create_dir_all(parent) {
+            tracing::warn!("Failed to create {}: {}", parent.display(), e);
+            return;
+        }
+    }
+
+    if let Err(e) = editor.save_history(path) {
+        tracing::warn!("Failed to save history to {}: {}", path.display(), e);
+    }
+}
+
+/// Guided menu
+async fn run_menu(client: &dyn ApiClient, formatter: &Formatter) -> CliResult<()> {
     loop {
         println!("\n{}", "=== LLM Auto Optimizer ===".cyan().bold());
 
@@ -36,10 +247,7 @@ pub async fn run_interactive_mode(
             3 => manage_integrations_interactive(client, formatter).await?,
             4 => configuration_interactive(client, formatter).await?,
             5 => system_status_interactive(client, formatter).await?,
-            6 => {
-                println!("{}", "Goodbye!".green());
-                break;
-            }
+            6 => break,
             _ => {}
         }
     }
@@ -59,6 +267,7 @@ async fn create_optimization_interactive(
         services: vec![],
         strategy: "cost-performance-scoring".to_string(),
         dry_run: false,
+        tags: vec![],
         interactive: true,
     };
 
@@ -69,17 +278,11 @@ async fn list_optimizations_interactive(
     client: &dyn ApiClient,
     formatter: &Formatter,
 ) -> CliResult<()> {
-    use crate::commands::optimize::OptimizeCommand;
+    use crate::commands::optimize::{ListArgs, OptimizeCommand};
 
     println!("\n{}", "List Optimizations".cyan().bold());
 
-    let cmd = OptimizeCommand::List {
-        status: None,
-        strategy: None,
-        service: None,
-        from: None,
-        to: None,
-    };
+    let cmd = OptimizeCommand::List(ListArgs::default());
 
     cmd.execute(client, formatter).await
 }
@@ -106,6 +309,7 @@ async fn view_metrics_interactive(
                 service: None,
                 from: None,
                 to: None,
+                watch: Default::default(),
             };
             cmd.execute(client, formatter).await
         }
@@ -115,6 +319,7 @@ async fn view_metrics_interactive(
                 service: None,
                 from: None,
                 to: None,
+                watch: Default::default(),
             };
             cmd.execute(client, formatter).await
         }
@@ -124,6 +329,7 @@ async fn view_metrics_interactive(
                 service: None,
                 from: None,
                 to: None,
+                watch: Default::default(),
             };
             cmd.execute(client, formatter).await
         }
@@ -166,3 +372,65 @@ async fn system_status_interactive(
     let cmd = AdminCommand::Stats;
     cmd.execute(client, formatter).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn options_in(dir: &tempfile::TempDir, history_size: usize) -> InteractiveOptions {
+        InteractiveOptions {
+            history_file: Some(dir.path().join("nested").join("history.txt")),
+            history_size,
+        }
+    }
+
+    fn entries(editor: &LineEditor) -> Vec<String> {
+        editor.history().iter().cloned().collect()
+    }
+
+    #[test]
+    fn test_secrets_are_redacted() {
+        assert!(is_sensitive("set-config api_key sk-123"));
+        assert!(is_sensitive("config set API_KEY '\"sk-123\"'"));
+        assert!(is_sensitive("config set auth.token abc"));
+        assert!(!is_sensitive("optimize list --status active"));
+    }
+
+    #[test]
+    fn test_history_is_deduplicated_capped_and_persisted() {
+        let dir = tempfile::tempdir().unwrap();
+        let options = options_in(&dir, 3);
+
+        let mut editor = create_editor(&options).unwrap();
+        for line in [
+            "optimize list",
+            "optimize list",
+            "config list",
+            "config set api_key \"sk-123\"",
+            "metrics cost",
+            "admin stats",
+        ] {
+            record_history(&mut editor, line);
+        }
+        assert_eq!(entries(&editor), ["config list", "metrics cost", "admin stats"]);
+
+        save_history(&mut editor, &options);
+
+        let reloaded = create_editor(&options).unwrap();
+        assert_eq!(entries(&reloaded), ["config list", "metrics cost", "admin stats"]);
+
+        let saved = std::fs::read_to_string(options.history_file.unwrap()).unwrap();
+        assert!(!saved.contains("sk-123"));
+    }
+
+    #[test]
+    fn test_repl_lines_use_cli_subcommands() {
+        let line = ReplLine::try_parse_from(["optimize", "get", "opt-1"]).unwrap();
+        assert!(matches!(line.command, ReplCommand::Optimize { .. }));
+
+        let line = ReplLine::try_parse_from(["quit"]).unwrap();
+        assert!(matches!(line.command, ReplCommand::Exit));
+
+        assert!(ReplLine::try_parse_from(["launch"]).is_err());
+    }
+}
@@ -4,12 +4,15 @@
 
 pub mod client;
 pub mod commands;
+pub mod credentials;
 pub mod interactive;
 pub mod output;
+pub mod timing;
 
 pub use client::{ApiClient, ClientConfig};
 pub use output::{Formatter, OutputFormat, OutputWriter};
 
+use std::collections::BTreeMap;
 use thiserror::Error;
 
 /// CLI error types
@@ -30,6 +33,15 @@ pub enum CliError {
     #[error("HTTP error: {0}")]
     Http(#[from] reqwest::Error),
 
+    #[error("Timeout: {0}")]
+    Timeout(String),
+
+    #[error("Connection failed: {0}")]
+    Connection(String),
+
+    #[error("Request failed: {0}")]
+    Request(String),
+
     #[error("Invalid input: {0}")]
     InvalidInput(String),
 
@@ -44,6 +56,53 @@ pub enum CliError {
 
     #[error("Operation failed: {0}")]
     OperationFailed(String),
+
+    #[error("{source} (after {} attempts)", history.attempts.len())]
+    RetriesExhausted {
+        source: Box<CliError>,
+        history: client::RetryHistory,
+    },
+}
+
+impl CliError {
+    /// Process exit code for this error, for scripts to branch on
+    ///
+    /// | Code | Meaning |
+    /// |------|---------|
+    /// | 1 | Unexpected or internal error |
+    /// | 2 | Invalid input |
+    /// | 3 | Not found |
+    /// | 4 | Authentication failed |
+    /// | 5 | Permission denied |
+    /// | 6 | Network, HTTP or API error, including timeouts and connection failures |
+    /// | 7 | Configuration error |
+    ///
+    /// A request that exhausted its retries exits with the code of its last
+    /// error.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CliError::InvalidInput(_) => 2,
+            CliError::NotFound(_) => 3,
+            CliError::AuthenticationFailed(_) => 4,
+            CliError::PermissionDenied(_) => 5,
+            CliError::Api(_)
+            | CliError::Http(_)
+            | CliError::Timeout(_)
+            | CliError::Connection(_)
+            | CliError::Request(_) => 6,
+            CliError::Config(_) => 7,
+            CliError::RetriesExhausted { source, .. } => source.exit_code(),
+            CliError::Io(_) | CliError::Serialization(_) | CliError::OperationFailed(_) => 1,
+        }
+    }
+
+    /// Attempts made before giving up, if the request went through retries
+    pub fn retry_history(&self) -> Option<&client::RetryHistory> {
+        match self {
+            CliError::RetriesExhausted { history, .. } => Some(history),
+            _ => None,
+        }
+    }
 }
 
 impl From<serde_json::Error> for CliError {
@@ -91,6 +150,10 @@ pub struct CliConfig {
     /// API key for authentication
     pub api_key: Option<String>,
 
+    /// API keys per API base URL; when set, only the key for the active
+    /// `api_url` is ever sent
+    pub credentials: BTreeMap<String, String>,
+
     /// Request timeout in seconds
     pub timeout: u64,
 
@@ -100,6 +163,9 @@ pub struct CliConfig {
     /// Enable verbose output
     pub verbose: bool,
 
+    /// Maximum number of entries kept in the interactive history
+    pub history_size: usize,
+
     /// Configuration file path
     pub config_file: Option<std::path::PathBuf>,
 }
@@ -110,9 +176,11 @@ impl Default for CliConfig {
             api_url: "http://localhost:8080".to_string(),
             grpc_endpoint: Some("http://localhost:50051".to_string()),
             api_key: None,
+            credentials: BTreeMap::new(),
             timeout: 30,
             output_format: OutputFormat::Table,
             verbose: false,
+            history_size: interactive::DEFAULT_HISTORY_SIZE,
             config_file: None,
         }
     }
@@ -122,10 +190,53 @@ impl CliConfig {
     /// Load configuration from file
     pub fn from_file(path: &std::path::Path) -> CliResult<Self> {
         let contents = std::fs::read_to_string(path)?;
-        let config: CliConfig = serde_yaml::from_str(&contents)?;
+        let mut config: CliConfig = serde_yaml::from_str(&contents)?;
+        config.expand_env_vars()?;
         Ok(config)
     }
 
+    /// Expand `${VAR}` and `${VAR:-default}` references in string fields
+    /// against the process environment
+    pub fn expand_env_vars(&mut self) -> CliResult<()> {
+        self.api_url = expand_env("api_url", &self.api_url)?;
+        if let Some(api_key) = &self.api_key {
+            self.api_key = Some(expand_env("api_key", api_key)?);
+        }
+        if let Some(grpc_endpoint) = &self.grpc_endpoint {
+            self.grpc_endpoint = Some(expand_env("grpc_endpoint", grpc_endpoint)?);
+        }
+        for (url, key) in self.credentials.iter_mut() {
+            *key = expand_env(&format!("credentials.{}", url), key)?;
+        }
+        Ok(())
+    }
+
+    /// API key to send to `api_url`
+    ///
+    /// Without a `credentials` map this is `api_key`. With one, the entry for
+    /// `api_url` is used (trailing slashes are ignored) and `api_key` is never
+    /// a fallback, so a key meant for one deployment can't leak to another;
+    /// an endpoint with no entry is a configuration error.
+    pub fn credential_for(&self, api_url: &str) -> CliResult<Option<String>> {
+        if self.credentials.is_empty() {
+            return Ok(self.api_key.clone());
+        }
+
+        let wanted = api_url.trim_end_matches('/');
+        self.credentials
+            .iter()
+            .find(|(url, _)| url.trim_end_matches('/') == wanted)
+            .map(|(_, key)| Some(key.clone()))
+            .ok_or_else(|| {
+                CliError::Config(format!(
+                    "No credential configured for '{}' (credentials exist for: {}); \
+                     add it under 'credentials' or pass --api-key",
+                    wanted,
+                    self.credentials.keys().cloned().collect::<Vec<_>>().join(", ")
+                ))
+            })
+    }
+
     /// Save configuration to file
     pub fn save_to_file(&self, path: &std::path::Path) -> CliResult<()> {
         let contents = serde_yaml::to_string(self)
@@ -145,19 +256,61 @@ impl CliConfig {
     }
 }
 
+/// Expand environment variable references in a single config value
+fn expand_env(field: &str, value: &str) -> CliResult<String> {
+    let mut result = String::with_capacity(value.len());
+    let mut rest = value;
+
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        let end = after.find('}').ok_or_else(|| {
+            CliError::Config(format!("Unterminated variable reference in '{}'", field))
+        })?;
+
+        let expr = &after[..end];
+        let (name, default) = match expr.split_once(":-") {
+            Some((name, default)) => (name, Some(default)),
+            None => (expr, None),
+        };
+
+        match (std::env::var(name).ok().filter(|v| !v.is_empty()), default) {
+            (Some(v), _) => result.push_str(&v),
+            (None, Some(default)) => result.push_str(default),
+            (None, None) => {
+                return Err(CliError::Config(format!(
+                    "Environment variable '{}' referenced by '{}' is not set",
+                    name, field
+                )))
+            }
+        }
+
+        rest = &after[end + 1..];
+    }
+
+    result.push_str(rest);
+    Ok(result)
+}
+
 impl serde::Serialize for CliConfig {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
         S: serde::Serializer,
     {
         use serde::ser::SerializeStruct;
-        let mut state = serializer.serialize_struct("CliConfig", 6)?;
+        let mut state = serializer.serialize_struct("CliConfig", 8)?;
         state.serialize_field("api_url", &self.api_url)?;
         state.serialize_field("grpc_endpoint", &self.grpc_endpoint)?;
         state.serialize_field("api_key", &self.api_key)?;
+        if self.credentials.is_empty() {
+            state.skip_field("credentials")?;
+        } else {
+            state.serialize_field("credentials", &self.credentials)?;
+        }
         state.serialize_field("timeout", &self.timeout)?;
         state.serialize_field("output_format", &self.output_format.to_string())?;
         state.serialize_field("verbose", &self.verbose)?;
+        state.serialize_field("history_size", &self.history_size)?;
         state.end()
     }
 }
@@ -176,9 +329,11 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
             ApiUrl,
             GrpcEndpoint,
             ApiKey,
+            Credentials,
             Timeout,
             OutputFormat,
             Verbose,
+            HistorySize,
         }
 
         struct CliConfigVisitor;
@@ -197,9 +352,11 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
                 let mut api_url = None;
                 let mut grpc_endpoint = None;
                 let mut api_key = None;
+                let mut credentials = None;
                 let mut timeout = None;
                 let mut output_format = None;
                 let mut verbose = None;
+                let mut history_size = None;
 
                 while let Some(key) = map.next_key()? {
                     match key {
@@ -212,6 +369,9 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
                         Field::ApiKey => {
                             api_key = Some(map.next_value()?);
                         }
+                        Field::Credentials => {
+                            credentials = Some(map.next_value()?);
+                        }
                         Field::Timeout => {
                             timeout = Some(map.next_value()?);
                         }
@@ -222,6 +382,9 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
                         Field::Verbose => {
                             verbose = Some(map.next_value()?);
                         }
+                        Field::HistorySize => {
+                            history_size = Some(map.next_value()?);
+                        }
                     }
                 }
 
@@ -229,9 +392,11 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
                     api_url: api_url.unwrap_or_else(|| "http://localhost:8080".to_string()),
                     grpc_endpoint,
                     api_key,
+                    credentials: credentials.unwrap_or_default(),
                     timeout: timeout.unwrap_or(30),
                     output_format: output_format.unwrap_or(OutputFormat::Table),
                     verbose: verbose.unwrap_or(false),
+                    history_size: history_size.unwrap_or(interactive::DEFAULT_HISTORY_SIZE),
                     config_file: None,
                 })
             }
@@ -239,8 +404,152 @@ impl<'de> serde::Deserialize<'de> for CliConfig {
 
         deserializer.deserialize_struct(
             "CliConfig",
-            &["api_url", "grpc_endpoint", "api_key", "timeout", "output_format", "verbose"],
+            &[
+                "api_url",
+                "grpc_endpoint",
+                "api_key",
+                "credentials",
+                "timeout",
+                "output_format",
+                "verbose",
+                "history_size",
+            ],
             CliConfigVisitor,
         )
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn load(yaml: &str) -> CliResult<CliConfig> {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        file.write_all(yaml.as_bytes()).unwrap();
+        CliConfig::from_file(file.path())
+    }
+
+    #[test]
+    fn test_exit_codes() {
+        let http = reqwest::Client::new().get("not a url").build().unwrap_err();
+        let cases = [
+            (CliError::Api("500".to_string()), 6),
+            (CliError::Config("bad".to_string()), 7),
+            (CliError::Io(std::io::Error::other("disk")), 1),
+            (CliError::Serialization("bad json".to_string()), 1),
+            (CliError::Http(http), 6),
+            (CliError::Timeout("30s".to_string()), 6),
+            (CliError::Connection("refused".to_string()), 6),
+            (CliError::Request("reset".to_string()), 6),
+            (CliError::InvalidInput("bad".to_string()), 2),
+            (CliError::NotFound("opt-1".to_string()), 3),
+            (CliError::AuthenticationFailed("expired".to_string()), 4),
+            (CliError::PermissionDenied("admin".to_string()), 5),
+            (CliError::OperationFailed("partial".to_string()), 1),
+            (
+                CliError::RetriesExhausted {
+                    source: Box::new(CliError::Timeout("30s".to_string())),
+                    history: client::RetryHistory::default(),
+                },
+                6,
+            ),
+        ];
+
+        for (error, code) in cases {
+            assert_eq!(error.exit_code(), code, "{:?}", error);
+        }
+    }
+
+    #[test]
+    fn test_expands_env_vars_in_config() {
+        std::env::set_var("LLM_OPTIMIZER_TEST_EXPAND_KEY", "secret-key");
+        std::env::set_var("LLM_OPTIMIZER_TEST_EXPAND_HOST", "api.example.com");
+
+        let config = load(
+            "api_url: https://${LLM_OPTIMIZER_TEST_EXPAND_HOST}/v1\n\
+             api_key: ${LLM_OPTIMIZER_TEST_EXPAND_KEY}\n\
+             grpc_endpoint: ${LLM_OPTIMIZER_TEST_EXPAND_GRPC:-http://localhost:50051}\n",
+        )
+        .unwrap();
+
+        assert_eq!(config.api_url, "https://api.example.com/v1");
+        assert_eq!(config.api_key.as_deref(), Some("secret-key"));
+        assert_eq!(config.grpc_endpoint.as_deref(), Some("http://localhost:50051"));
+    }
+
+    #[test]
+    fn test_unset_env_var_without_default_names_field() {
+        std::env::remove_var("LLM_OPTIMIZER_TEST_EXPAND_UNSET");
+
+        let err = load("api_key: ${LLM_OPTIMIZER_TEST_EXPAND_UNSET}\n").unwrap_err();
+
+        match err {
+            CliError::Config(msg) => {
+                assert!(msg.contains("api_key"), "{}", msg);
+                assert!(msg.contains("LLM_OPTIMIZER_TEST_EXPAND_UNSET"), "{}", msg);
+            }
+            other => panic!("expected CliError::Config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_plain_values_are_untouched() {
+        assert_eq!(expand_env("api_url", "http://localhost:8080").unwrap(), "http://localhost:8080");
+        assert_eq!(expand_env("api_key", "$abc").unwrap(), "$abc");
+        assert!(expand_env("api_key", "${OPEN").is_err());
+    }
+
+    const MULTI_ENDPOINT: &str = "api_url: https://staging.example.com\n\
+                                  api_key: legacy-key\n\
+                                  credentials:\n  \
+                                    https://prod.example.com: prod-key\n  \
+                                    https://staging.example.com/: staging-key\n";
+
+    #[test]
+    fn test_credential_selected_per_endpoint() {
+        let config = load(MULTI_ENDPOINT).unwrap();
+
+        assert_eq!(
+            config.credential_for("https://prod.example.com/").unwrap().as_deref(),
+            Some("prod-key")
+        );
+        assert_eq!(
+            config.credential_for(&config.api_url).unwrap().as_deref(),
+            Some("staging-key")
+        );
+    }
+
+    #[test]
+    fn test_unknown_endpoint_never_falls_back_to_api_key() {
+        let config = load(MULTI_ENDPOINT).unwrap();
+
+        match config.credential_for("https://dev.example.com").unwrap_err() {
+            CliError::Config(msg) => {
+                assert!(msg.contains("https://dev.example.com"), "{}", msg);
+                assert!(msg.contains("https://prod.example.com"), "{}", msg);
+                assert!(!msg.contains("legacy-key"), "{}", msg);
+            }
+            other => panic!("expected CliError::Config, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_api_key_used_without_credentials_map() {
+        let config = load("api_key: only-key\n").unwrap();
+
+        assert_eq!(
+            config.credential_for("https://anywhere.example.com").unwrap().as_deref(),
+            Some("only-key")
+        );
+        assert!(!serde_yaml::to_string(&config).unwrap().contains("credentials"));
+    }
+
+    #[test]
+    fn test_credentials_survive_save_and_load() {
+        let config = load(MULTI_ENDPOINT).unwrap();
+        let reloaded = load(&serde_yaml::to_string(&config).unwrap()).unwrap();
+
+        assert_eq!(reloaded.credentials, config.credentials);
+    }
+}
@@ -2,19 +2,28 @@
 //!
 //! Production-grade command-line interface for managing LLM Auto Optimizer.
 
-use clap::{CommandFactory, Parser, Subcommand};
+use clap::{ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
 use colored::Colorize;
 use llm_optimizer_cli::{
-    client::{ClientConfig, RestClient},
+    client::{
+        retry::DEFAULT_MAX_RETRIES, ClientConfig, RestClient, RetryPolicy,
+    },
     commands::{
-        AdminCommand, ConfigCommand, IntegrationCommand, MetricsCommand, OptimizeCommand,
-        RunCommand, ServiceCommand, UtilCommand,
+        AdminCommand, AuthCommand, ConfigCommand, IntegrationCommand, LogsCommand, MetricsCommand,
+        OptimizeCommand,
+        util::{convert_file, write_completions, EstimateArgs}, RunCommand, ServiceCommand, UtilCommand,
+    },
+    credentials, interactive,
+    output::{
+        self, check_format_supported, get_formatter_with_options, pager::resolve_pager, CsvDelimiter,
+        FormatOptions, OutputFormat,
     },
-    interactive,
-    output::{get_formatter, OutputFormat},
+    timing::{self, Phase},
     CliConfig, CliResult,
 };
-use std::time::Duration;
+use std::ffi::OsString;
+use std::io::IsTerminal;
+use std::time::{Duration, Instant};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 #[derive(Parser)]
@@ -58,10 +67,62 @@ struct Cli {
     )]
     output: Option<OutputFormat>,
 
+    /// Render timestamps relative to now in table output
+    #[arg(
+        long,
+        global = true,
+        conflicts_with = "absolute_time",
+        help = "Show relative timestamps in tables (default when attached to a terminal)"
+    )]
+    relative_time: bool,
+
+    /// Always render absolute timestamps
+    #[arg(long, global = true, help = "Always show absolute timestamps in tables")]
+    absolute_time: bool,
+
+    /// Never page output
+    #[arg(
+        long,
+        global = true,
+        help = "Never pipe long table output through a pager ($LLM_OPTIMIZER_PAGER, $PAGER or 'less -FRX')"
+    )]
+    no_pager: bool,
+
+    /// CSV field delimiter
+    #[arg(
+        long,
+        global = true,
+        value_name = "DELIMITER",
+        default_value = "comma",
+        help = "Field delimiter for CSV output (comma, tab, semicolon)"
+    )]
+    csv_delimiter: CsvDelimiter,
+
+    /// Leave out the CSV header row
+    #[arg(long, global = true, help = "Leave out the header row in CSV output")]
+    no_headers: bool,
+
+    /// Write output to a file
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        help = "Write output to FILE atomically; the file is left untouched if the command fails"
+    )]
+    output_file: Option<std::path::PathBuf>,
+
     /// Verbose output
     #[arg(short, long, global = true, help = "Enable verbose output")]
     verbose: bool,
 
+    /// Print a per-phase latency breakdown
+    #[arg(
+        long,
+        global = true,
+        help = "Print time spent per phase (config, client, request, parse, output) to stderr"
+    )]
+    profile_timing: bool,
+
     /// Configuration file
     #[arg(
         short,
@@ -81,6 +142,101 @@ struct Cli {
     )]
     timeout: u64,
 
+    /// Connect timeout in seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Connect timeout in seconds (capped by --timeout)"
+    )]
+    connect_timeout: Option<u64>,
+
+    /// Read timeout in seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Read timeout in seconds (capped by --timeout)"
+    )]
+    read_timeout: Option<u64>,
+
+    /// Retries for transient failures of read-only requests
+    #[arg(
+        long,
+        global = true,
+        value_name = "COUNT",
+        default_value_t = DEFAULT_MAX_RETRIES,
+        help = "Retries for timeouts, connection errors and 429/502/503/504 on read-only requests"
+    )]
+    retries: u32,
+
+    /// Idle connections kept open per host
+    #[arg(
+        long,
+        global = true,
+        value_name = "COUNT",
+        help = "Idle connections kept open per host (default: unlimited)"
+    )]
+    pool_max_idle_per_host: Option<usize>,
+
+    /// Idle connection timeout in seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "How long an idle connection stays open for reuse (default: 90)"
+    )]
+    pool_idle_timeout: Option<u64>,
+
+    /// TCP keep-alive interval in seconds
+    #[arg(
+        long,
+        global = true,
+        value_name = "SECONDS",
+        help = "Send TCP keep-alive probes at this interval (default: off)"
+    )]
+    tcp_keepalive: Option<u64>,
+
+    /// Use HTTP/2 without negotiation
+    #[arg(
+        long,
+        global = true,
+        help = "Speak HTTP/2 without negotiating it first, for HTTP/2-only gateways"
+    )]
+    http2_prior_knowledge: bool,
+
+    /// Client certificate for mutual TLS
+    #[arg(
+        long,
+        global = true,
+        env = "LLM_OPTIMIZER_CLIENT_CERT",
+        value_name = "FILE",
+        requires = "client_key",
+        help = "PEM client certificate to present for mutual TLS"
+    )]
+    client_cert: Option<std::path::PathBuf>,
+
+    /// Private key for the client certificate
+    #[arg(
+        long,
+        global = true,
+        env = "LLM_OPTIMIZER_CLIENT_KEY",
+        value_name = "FILE",
+        requires = "client_cert",
+        help = "PEM private key for --client-cert"
+    )]
+    client_key: Option<std::path::PathBuf>,
+
+    /// Extra CA certificates to trust
+    #[arg(
+        long,
+        global = true,
+        env = "LLM_OPTIMIZER_CA_CERT",
+        value_name = "FILE",
+        help = "PEM CA bundle to trust in addition to the system roots"
+    )]
+    ca_cert: Option<std::path::PathBuf>,
+
     #[command(subcommand)]
     command: Option<Commands>,
 }
@@ -145,17 +301,56 @@ enum Commands {
         force: bool,
     },
 
+    /// Stream optimizer events
+    #[command(
+        name = "logs",
+        visible_alias = "events",
+        about = "Stream optimizer events as they happen"
+    )]
+    Logs(LogsCommand),
+
+    /// Store an API key in the OS keychain
+    #[command(name = "login", about = "Store an API key in the OS keychain")]
+    Login,
+
+    /// Remove the stored API key
+    #[command(name = "logout", about = "Remove the stored API key from the OS keychain")]
+    Logout,
+
     /// Generate shell completions
-    #[command(name = "completions", about = "Generate shell completions")]
+    #[command(
+        name = "completions",
+        about = "Generate shell completions",
+        long_about = "Generate a shell completion script on stdout.\n\n\
+                      Example: llm-optimizer completions bash > /etc/bash_completion.d/llm-optimizer"
+    )]
     Completions {
         /// Shell type
         #[arg(value_enum)]
         shell: clap_complete::Shell,
     },
 
+    /// Estimate request costs offline
+    #[command(
+        name = "estimate",
+        about = "Estimate the cost of Claude requests without calling the API",
+        long_about = "Estimate the cost of Claude requests locally from the built-in Anthropic pricing.\n\n\
+                      Input tokens come from --input-tokens or are counted from --prompt-file.\n\n\
+                      Example: llm-optimizer estimate --model claude-3-haiku --input-tokens 10000 --output-tokens 2000"
+    )]
+    Estimate(EstimateArgs),
+
     /// Run system diagnostics
     #[command(name = "doctor", about = "Run system diagnostics")]
-    Doctor,
+    Doctor {
+        /// Repair safe, reversible configuration problems
+        #[arg(long)]
+        fix: bool,
+
+        /// Apply fixes without confirmation
+        #[arg(short = 'y', long, requires = "fix")]
+        yes: bool,
+    },
 
     /// Interactive mode
     #[command(name = "interactive", about = "Start interactive mode")]
@@ -167,33 +362,74 @@ enum Commands {
         #[command(subcommand)]
         command: RunCommand,
     },
+
+    /// Utility commands
+    #[command(name = "util", about = "Utility commands such as format conversion")]
+    Util {
+        #[command(subcommand)]
+        command: UtilCommand,
+    },
 }
 
 #[tokio::main]
 async fn main() {
-    if let Err(e) = run().await {
+    let started = Instant::now();
+    let cli = build_cli();
+    let args = rename_legacy_flags(&cli, std::env::args_os().collect());
+    let matches = cli.get_matches_from(args);
+    let verbose = matches.get_flag("verbose");
+    let profile_timing = matches.get_flag("profile_timing");
+    let timing_format = matches.get_one::<OutputFormat>("output").copied();
+    if profile_timing {
+        timing::enable();
+    }
+
+    let result = run(matches).await;
+
+    if profile_timing {
+        print_timing(started.elapsed(), timing_format);
+    }
+    if let Err(e) = result {
         eprintln!("{} {}", "Error:".red().bold(), e);
-        std::process::exit(1);
+        if let Some(history) = e.retry_history().filter(|_| verbose) {
+            eprintln!("{}", history.to_string().dimmed());
+        }
+        std::process::exit(e.exit_code());
     }
 }
 
-async fn run() -> CliResult<()> {
-    let cli = Cli::parse();
+async fn run(matches: ArgMatches) -> CliResult<()> {
+    let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|e| e.exit());
 
     // Initialize tracing
     init_tracing(cli.verbose);
 
-    // Load configuration
-    let mut config = load_config(&cli)?;
+    // Conversion only reads and writes files, so it needs no config or client
+    if let Some(Commands::Util {
+        command: UtilCommand::Convert { from, to, file },
+    }) = &cli.command
+    {
+        if cli.output_file.is_some() {
+            output::file::start_capture();
+        }
+        convert_file(*from, *to, file)?;
+        return write_output_file(cli.output_file.as_deref());
+    }
+
+    // Load configuration; doctor reports and repairs config problems itself
+    let config_span = timing::span(Phase::ConfigLoad);
+    let mut config = match load_config(&cli) {
+        Err(_) if matches!(cli.command, Some(Commands::Doctor { .. })) => CliConfig::default(),
+        result => result?,
+    };
+    drop(config_span);
 
     // Override with CLI arguments
     if let Some(api_url) = cli.api_url {
         config.api_url = api_url;
     }
-    if let Some(api_key) = cli.api_key {
-        config.api_key = Some(api_key);
-    }
     if let Some(output) = cli.output {
+        check_format_supported(&command_path(&matches), output)?;
         config.output_format = output;
     }
     if cli.verbose {
@@ -202,7 +438,26 @@ async fn run() -> CliResult<()> {
     config.timeout = cli.timeout;
 
     // Get output formatter
-    let formatter = get_formatter(config.output_format);
+    let relative_time = if cli.absolute_time {
+        false
+    } else {
+        cli.relative_time || std::io::stdout().is_terminal()
+    };
+    let pager = resolve_pager(
+        config.output_format,
+        cli.no_pager,
+        std::io::stdout().is_terminal(),
+        |name| std::env::var(name).ok(),
+    );
+    let formatter = get_formatter_with_options(
+        config.output_format,
+        FormatOptions {
+            relative_time,
+            pager,
+            csv_delimiter: cli.csv_delimiter,
+            csv_no_headers: cli.no_headers,
+        },
+    );
 
     // Handle commands that don't require API client
     if let Some(Commands::Init {
@@ -216,35 +471,84 @@ async fn run() -> CliResult<()> {
             api_key: api_key.clone(),
             force: *force,
         };
-        return cmd.execute(None).await;
+        return cmd.execute(None, &formatter).await;
+    }
+
+    if let Some(Commands::Estimate(args)) = &cli.command {
+        if cli.output_file.is_some() {
+            output::file::start_capture();
+        }
+        UtilCommand::Estimate(args.clone())
+            .execute(None, &formatter)
+            .await?;
+        return write_output_file(cli.output_file.as_deref());
     }
 
     if let Some(Commands::Completions { shell }) = &cli.command {
-        use clap_complete::generate;
-        let mut cmd = build_cli();
-        let bin_name = cmd.get_name().to_string();
-        generate(*shell, &mut cmd, bin_name, &mut std::io::stdout());
+        write_completions(*shell, &mut build_cli(), &mut std::io::stdout());
         return Ok(());
     }
 
     // Create API client
-    let client_config = ClientConfig {
+    let mut client_config = ClientConfig {
         base_url: config.api_url.clone(),
-        api_key: config.api_key.clone(),
+        api_key: None,
         timeout: Duration::from_secs(config.timeout),
+        connect_timeout: cli.connect_timeout.map(Duration::from_secs),
+        read_timeout: cli.read_timeout.map(Duration::from_secs),
+        retry: RetryPolicy {
+            max_retries: cli.retries,
+            ..RetryPolicy::default()
+        },
+        pool_max_idle_per_host: cli.pool_max_idle_per_host,
+        pool_idle_timeout: cli.pool_idle_timeout.map(Duration::from_secs),
+        tcp_keepalive: cli.tcp_keepalive.map(Duration::from_secs),
+        http2_prior_knowledge: cli.http2_prior_knowledge,
+        client_cert_path: cli.client_cert.clone(),
+        client_key_path: cli.client_key.clone(),
+        ca_cert_path: cli.ca_cert.clone(),
+    };
+
+    // Handle login/logout before the client picks up stored credentials
+    match &cli.command {
+        Some(Commands::Login) => return AuthCommand::Login.execute(&client_config).await,
+        Some(Commands::Logout) => return AuthCommand::Logout.execute(&client_config).await,
+        _ => {}
+    }
+
+    // Pick the key for the selected endpoint; an explicit --api-key always wins
+    client_config.api_key = match cli.api_key.clone() {
+        Some(api_key) => Some(api_key),
+        None => match config.credential_for(&config.api_url) {
+            // A key stored with `login` is already specific to this endpoint
+            Err(_) if credentials::load_api_key(&config.api_url).is_some() => None,
+            Err(_) if matches!(cli.command, Some(Commands::Doctor { .. })) => None,
+            result => result?,
+        },
     };
 
-    let client = RestClient::new(client_config)?;
+    let client = {
+        let _span = timing::span(Phase::ClientBuild);
+        RestClient::new(client_config)?
+    };
 
     // Handle doctor command
-    if let Some(Commands::Doctor) = &cli.command {
-        let cmd = UtilCommand::Doctor;
-        return cmd.execute(Some(&client)).await;
+    if let Some(Commands::Doctor { fix, yes }) = &cli.command {
+        let cmd = UtilCommand::Doctor {
+            fix: *fix,
+            yes: *yes,
+            config_file: cli.config.clone(),
+        };
+        return cmd.execute(Some(&client), &formatter).await;
     }
 
     // Handle interactive mode
     if let Some(Commands::Interactive) = &cli.command {
-        return interactive::run_interactive_mode(&client, &formatter).await;
+        let options = interactive::InteractiveOptions {
+            history_size: config.history_size,
+            ..Default::default()
+        };
+        return interactive::run_interactive_mode(&client, &formatter, &options).await;
     }
 
     // If no command provided, show help
@@ -255,6 +559,10 @@ async fn run() -> CliResult<()> {
         return Ok(());
     };
 
+    if cli.output_file.is_some() {
+        output::file::start_capture();
+    }
+
     // Execute command
     match command {
         Commands::Service { command } => {
@@ -278,12 +586,30 @@ async fn run() -> CliResult<()> {
         Commands::Run { command } => {
             command.execute(&formatter).await?;
         }
-        Commands::Init { .. } | Commands::Completions { .. } | Commands::Doctor | Commands::Interactive => {
+        Commands::Logs(command) => {
+            command.execute(&client, &formatter).await?;
+        }
+        Commands::Init { .. }
+        | Commands::Estimate(_)
+        | Commands::Login
+        | Commands::Logout
+        | Commands::Completions { .. }
+        | Commands::Doctor { .. }
+        | Commands::Util { .. }
+        | Commands::Interactive => {
             // Already handled above
         }
     }
 
-    Ok(())
+    write_output_file(cli.output_file.as_deref())
+}
+
+/// Write output captured for `--output-file` once the command has succeeded
+fn write_output_file(path: Option<&std::path::Path>) -> CliResult<()> {
+    match path {
+        Some(path) => output::file::take_capture().unwrap_or_default().commit(path),
+        None => Ok(()),
+    }
 }
 
 /// Load configuration from file or create default
@@ -294,7 +620,12 @@ fn load_config(cli: &Cli) -> CliResult<CliConfig> {
     } else if let Some(default_config) = CliConfig::default_config_file() {
         // Try to load from default location
         if default_config.exists() {
-            Ok(CliConfig::from_file(&default_config).unwrap_or_default())
+            // Unresolvable variable references are surfaced; other load
+            // failures fall back to defaults as before
+            match CliConfig::from_file(&default_config) {
+                Err(e @ llm_optimizer_cli::CliError::Config(_)) => Err(e),
+                result => Ok(result.unwrap_or_default()),
+            }
         } else {
             Ok(CliConfig::default())
         }
@@ -303,6 +634,19 @@ fn load_config(cli: &Cli) -> CliResult<CliConfig> {
     }
 }
 
+/// Print the per-phase latency breakdown to stderr, as JSON with `--output json`
+fn print_timing(total: Duration, format: Option<OutputFormat>) {
+    let report = timing::report(total);
+    if format == Some(OutputFormat::Json) {
+        match serde_json::to_string(&report) {
+            Ok(json) => eprintln!("{}", json),
+            Err(e) => tracing::warn!("Failed to serialize timing report: {}", e),
+        }
+    } else {
+        eprintln!("{}", report.to_table());
+    }
+}
+
 /// Initialize tracing/logging
 fn init_tracing(verbose: bool) {
     let filter = if verbose {
@@ -321,3 +665,318 @@ fn init_tracing(verbose: bool) {
 pub fn build_cli() -> clap::Command {
     <Cli as clap::CommandFactory>::command()
 }
+
+/// Subcommand flags renamed because they clashed with global options, as
+/// `(subcommand path, old flag, new flag)`
+const RENAMED_FLAGS: &[(&str, &str, &str)] = &[
+    ("integration add", "--config", "--settings"),
+    ("integration add", "-c", "--settings"),
+    ("config export", "--output", "--file"),
+    ("config export", "-o", "--file"),
+    ("metrics export", "--output", "--file"),
+    ("metrics export", "-o", "--file"),
+    ("optimize list", "-v", "--service"),
+    ("run benchmarks", "-o", "--output-dir"),
+];
+
+/// Rewrite renamed subcommand flags to their new names, so scripts using the
+/// old spellings keep working
+fn rename_legacy_flags(root: &clap::Command, args: Vec<OsString>) -> Vec<OsString> {
+    let mut args = args.into_iter();
+    let mut renamed: Vec<OsString> = args.next().into_iter().collect();
+    let mut cmd = root;
+    let mut path: Vec<&str> = Vec::new();
+
+    while let Some(arg) = args.next() {
+        let Some(word) = arg.to_str() else {
+            renamed.push(arg);
+            continue;
+        };
+        if word == "--" {
+            renamed.push(arg);
+            break;
+        }
+        if !word.starts_with('-') || word == "-" {
+            if let Some(sub) = cmd.find_subcommand(word) {
+                path.push(sub.get_name());
+                cmd = sub;
+            }
+            renamed.push(arg);
+            continue;
+        }
+
+        // A value given as a separate word must not be taken for a subcommand
+        let value_follows = match rename_flag(&path.join(" "), word) {
+            Some((flag, value_follows)) => {
+                renamed.push(flag.into());
+                value_follows
+            }
+            None => {
+                let value_follows = takes_value(root, cmd, word);
+                renamed.push(arg);
+                value_follows
+            }
+        };
+        if value_follows {
+            renamed.extend(args.next());
+        }
+    }
+
+    renamed.extend(args);
+    renamed
+}
+
+/// The new spelling of `word` if it is a renamed flag of the subcommand at
+/// `path`, and whether its value is the next word
+fn rename_flag(path: &str, word: &str) -> Option<(String, bool)> {
+    RENAMED_FLAGS
+        .iter()
+        .filter(|(flag_path, _, _)| *flag_path == path)
+        .find_map(|&(_, old, new)| {
+            let rest = word.strip_prefix(old)?;
+            if rest.is_empty() {
+                Some((new.to_string(), true))
+            } else if let Some(value) = rest.strip_prefix('=') {
+                Some((format!("{}={}", new, value), false))
+            } else if !old.starts_with("--") {
+                // `-vchat` carries its value attached
+                Some((format!("{}={}", new, rest), false))
+            } else {
+                None
+            }
+        })
+}
+
+/// Whether `word`, an option of `cmd` or a global option, takes its value
+/// as the next word
+fn takes_value(root: &clap::Command, cmd: &clap::Command, word: &str) -> bool {
+    let is_flag = |arg: &&clap::Arg| match word.strip_prefix("--") {
+        Some(long) => arg.get_long() == Some(long),
+        None => {
+            let mut chars = word.chars().skip(1);
+            let short = chars.next();
+            chars.next().is_none() && short.is_some() && arg.get_short() == short
+        }
+    };
+    cmd.get_arguments()
+        .chain(root.get_arguments().filter(|arg| arg.is_global_set()))
+        .find(is_flag)
+        .is_some_and(|arg| arg.get_action().takes_values())
+}
+
+/// Space-separated path of the invoked subcommand, e.g. "config export"
+fn command_path(matches: &ArgMatches) -> String {
+    let mut path = Vec::new();
+    let mut current = matches;
+    while let Some((name, sub_matches)) = current.subcommand() {
+        path.push(name);
+        current = sub_matches;
+    }
+    path.join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap_complete::Shell;
+
+    #[test]
+    fn test_cli_definition_is_valid() {
+        build_cli().debug_assert();
+    }
+
+    #[test]
+    fn test_completions_include_all_subcommands() {
+        let names: Vec<String> = build_cli()
+            .get_subcommands()
+            .map(|c| c.get_name().to_string())
+            .collect();
+        assert!(names.contains(&"completions".to_string()));
+
+        for shell in [Shell::Bash, Shell::Zsh, Shell::Fish, Shell::PowerShell] {
+            let mut out = Vec::new();
+            write_completions(shell, &mut build_cli(), &mut out);
+            let script = String::from_utf8(out).unwrap();
+
+            for name in &names {
+                assert!(script.contains(name.as_str()), "{:?} script is missing '{}'", shell, name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_command_path_uses_canonical_names() {
+        let matches = build_cli()
+            .try_get_matches_from(["llm-optimizer", "config", "export", "-o", "table"])
+            .unwrap();
+        assert_eq!(command_path(&matches), "config export");
+        assert!(check_format_supported(&command_path(&matches), OutputFormat::Table).is_err());
+
+        let matches = build_cli()
+            .try_get_matches_from(["llm-optimizer", "events"])
+            .unwrap();
+        assert_eq!(command_path(&matches), "logs");
+    }
+
+    #[test]
+    fn test_watch_flags_parse_on_metrics_and_service_status() {
+        let cli = Cli::try_parse_from([
+            "llm-optimizer", "metrics", "cost", "--interval", "5", "--count", "3",
+        ])
+        .unwrap();
+        let Some(Commands::Metrics {
+            command: MetricsCommand::Cost { watch, .. },
+            ..
+        }) = cli.command
+        else {
+            panic!("expected metrics cost");
+        };
+        assert!(watch.is_enabled());
+        assert_eq!(watch.interval(), Duration::from_secs(5));
+        assert_eq!(watch.count, Some(3));
+
+        let cli = Cli::try_parse_from(["llm-optimizer", "service", "status", "--watch"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Service { command: ServiceCommand::Status { ref watch }, .. }) if watch.watch
+        ));
+
+        assert!(Cli::try_parse_from(["llm-optimizer", "metrics", "cost", "--interval", "0"]).is_err());
+    }
+
+    #[test]
+    fn test_util_only_exposes_convert() {
+        let cli = Cli::try_parse_from([
+            "llm-optimizer", "estimate", "--model", "claude-3-haiku", "--input-tokens", "10000",
+            "--output-tokens", "2000", "--count", "50",
+        ])
+        .unwrap();
+        let Some(Commands::Estimate(args)) = cli.command else {
+            panic!("expected estimate");
+        };
+        assert_eq!(args.count, 50);
+
+        let cli = Cli::try_parse_from([
+            "llm-optimizer", "util", "convert", "--from", "json", "--to", "csv", "--file", "data.json",
+        ])
+        .unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Commands::Util { command: UtilCommand::Convert { .. } })
+        ));
+
+        for duplicate in ["init", "doctor", "estimate"] {
+            assert!(Cli::try_parse_from(["llm-optimizer", "util", duplicate]).is_err());
+        }
+    }
+
+    fn matches_with_legacy_flags(args: &[&str]) -> ArgMatches {
+        let args = rename_legacy_flags(&build_cli(), args.iter().map(OsString::from).collect());
+        build_cli().try_get_matches_from(args).unwrap()
+    }
+
+    fn parse_with_legacy_flags(args: &[&str]) -> Cli {
+        Cli::from_arg_matches(&matches_with_legacy_flags(args)).unwrap()
+    }
+
+    #[test]
+    fn test_renamed_flags_keep_their_old_spellings() {
+        let cli = parse_with_legacy_flags(&[
+            "llm-optimizer", "integration", "add", "-i", "slack", "-n", "ops", "--config", "{}",
+        ]);
+        let Some(Commands::Integration {
+            command: IntegrationCommand::Add { settings, .. },
+        }) = cli.command
+        else {
+            panic!("expected integration add");
+        };
+        assert_eq!(settings, "{}");
+        assert!(cli.config.is_none());
+
+        let cli = parse_with_legacy_flags(&[
+            "llm-optimizer", "-o", "json", "config", "export", "-o", "backup.yaml",
+        ]);
+        assert_eq!(cli.output, Some(OutputFormat::Json));
+        let Some(Commands::Config {
+            command: ConfigCommand::Export { file, .. },
+        }) = cli.command
+        else {
+            panic!("expected config export");
+        };
+        assert_eq!(file, Some(std::path::PathBuf::from("backup.yaml")));
+
+        let cli = parse_with_legacy_flags(&[
+            "llm-optimizer", "metrics", "export", "--output=metrics.csv", "--output-file", "log",
+        ]);
+        let Some(Commands::Metrics {
+            command: MetricsCommand::Export { file, .. },
+        }) = cli.command
+        else {
+            panic!("expected metrics export");
+        };
+        assert_eq!(file, Some(std::path::PathBuf::from("metrics.csv")));
+        assert_eq!(cli.output_file, Some(std::path::PathBuf::from("log")));
+
+        let matches =
+            matches_with_legacy_flags(&["llm-optimizer", "-v", "optimize", "list", "-v", "chat"]);
+        assert!(matches.get_flag("verbose"));
+        let (_, list) = matches.subcommand().unwrap().1.subcommand().unwrap();
+        assert_eq!(list.get_one::<String>("service").map(String::as_str), Some("chat"));
+
+        // The value of a global option is not mistaken for a subcommand
+        let cli =
+            parse_with_legacy_flags(&["llm-optimizer", "--api-key", "optimize", "service", "status"]);
+        assert_eq!(cli.api_key.as_deref(), Some("optimize"));
+    }
+
+    #[tokio::test]
+    async fn test_failed_command_leaves_output_file_untouched() {
+        let dir = tempfile::tempdir().unwrap();
+        let input = dir.path().join("input.json");
+        std::fs::write(&input, "{ not json").unwrap();
+        let target = dir.path().join("out.yaml");
+        std::fs::write(&target, "original: true\n").unwrap();
+
+        let matches = build_cli()
+            .try_get_matches_from([
+                "llm-optimizer",
+                "--output-file",
+                target.to_str().unwrap(),
+                "util",
+                "convert",
+                "--from",
+                "json",
+                "--to",
+                "yaml",
+                "--file",
+                input.to_str().unwrap(),
+            ])
+            .unwrap();
+        assert!(run(matches).await.is_err());
+        output::file::take_capture();
+
+        assert_eq!(std::fs::read_to_string(&target).unwrap(), "original: true\n");
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 2);
+    }
+
+    #[test]
+    fn test_output_file_is_global() {
+        let cli = Cli::try_parse_from([
+            "llm-optimizer", "config", "export", "--output-file", "backup.yaml",
+        ])
+        .unwrap();
+        assert_eq!(cli.output_file, Some(std::path::PathBuf::from("backup.yaml")));
+
+        let cli = Cli::try_parse_from(["llm-optimizer", "metrics", "export"]).unwrap();
+        assert!(cli.output_file.is_none());
+    }
+
+    #[test]
+    fn test_unknown_shell_is_rejected() {
+        let err = Cli::try_parse_from(["llm-optimizer", "completions", "tcsh"])
+            .err()
+            .expect("unknown shell should not parse");
+        assert_eq!(err.kind(), clap::error::ErrorKind::InvalidValue);
+        assert!(err.to_string().contains("bash"));
+    }
+}
@@ -0,0 +1,296 @@
+//! CSV output formatter
+
+use super::OutputWriter;
+use crate::CliResult;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::collections::{HashMap, HashSet};
+use std::str::FromStr;
+
+/// Separator between the items of an array written to a single cell
+pub const DEFAULT_ARRAY_SEPARATOR: &str = "; ";
+
+/// Field delimiter for CSV output
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CsvDelimiter {
+    #[default]
+    Comma,
+    Tab,
+    Semicolon,
+}
+
+impl CsvDelimiter {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CsvDelimiter::Comma => "comma",
+            CsvDelimiter::Tab => "tab",
+            CsvDelimiter::Semicolon => "semicolon",
+        }
+    }
+
+    /// Byte written between fields
+    pub fn as_byte(&self) -> u8 {
+        match self {
+            CsvDelimiter::Comma => b',',
+            CsvDelimiter::Tab => b'\t',
+            CsvDelimiter::Semicolon => b';',
+        }
+    }
+}
+
+impl std::fmt::Display for CsvDelimiter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl FromStr for CsvDelimiter {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "comma" | "," => Ok(CsvDelimiter::Comma),
+            "tab" | "\t" => Ok(CsvDelimiter::Tab),
+            "semicolon" | ";" => Ok(CsvDelimiter::Semicolon),
+            _ => Err(format!(
+                "Unknown CSV delimiter: {} (expected comma, tab or semicolon)",
+                s
+            )),
+        }
+    }
+}
+
+/// CSV formatter
+///
+/// Nested objects are flattened into dotted columns
+/// (`expected_impact.cost_reduction_pct`) and every row gets the union of
+/// all rows' columns, with missing fields left empty. A single object is
+/// written as `key,value` pairs.
+#[derive(Debug, Clone)]
+pub struct CsvFormatter {
+    delimiter: CsvDelimiter,
+    headers: bool,
+    /// Joins array items in a cell; `None` writes arrays as JSON
+    array_separator: Option<String>,
+}
+
+impl CsvFormatter {
+    pub fn new() -> Self {
+        Self {
+            delimiter: CsvDelimiter::default(),
+            headers: true,
+            array_separator: Some(DEFAULT_ARRAY_SEPARATOR.to_string()),
+        }
+    }
+
+    /// Set the field delimiter
+    pub fn with_delimiter(mut self, delimiter: CsvDelimiter) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Write or leave out the header row
+    pub fn with_headers(mut self, headers: bool) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Write arrays as JSON instead of joining their items, so the output
+    /// can be read back without losing structure
+    pub fn with_json_arrays(mut self) -> Self {
+        self.array_separator = None;
+        self
+    }
+
+    fn cell(&self, value: &Value) -> String {
+        match value {
+            Value::String(s) => s.clone(),
+            Value::Null => String::new(),
+            Value::Array(items) => match &self.array_separator {
+                Some(separator) => items
+                    .iter()
+                    .map(|item| self.cell(item))
+                    .collect::<Vec<_>>()
+                    .join(separator),
+                None => value.to_string(),
+            },
+            other => other.to_string(),
+        }
+    }
+}
+
+impl Default for CsvFormatter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl OutputWriter for CsvFormatter {
+    fn write<T: Serialize>(&self, data: &T) -> CliResult<String> {
+        let json_value = serde_json::to_value(data)?;
+        let mut wtr = ::csv::WriterBuilder::new()
+            .delimiter(self.delimiter.as_byte())
+            .from_writer(vec![]);
+
+        match &json_value {
+            Value::Array(items) => {
+                if items.is_empty() {
+                    return Ok(String::new());
+                }
+
+                let rows: Vec<Vec<(String, &Value)>> = items
+                    .iter()
+                    .map(|item| match item {
+                        Value::Object(obj) => flatten(obj),
+                        scalar => vec![("value".to_string(), scalar)],
+                    })
+                    .collect();
+
+                // Columns in the order they first appear, so sparse rows
+                // still line up
+                let mut seen = HashSet::new();
+                let columns: Vec<&str> = rows
+                    .iter()
+                    .flatten()
+                    .map(|(key, _)| key.as_str())
+                    .filter(|key| seen.insert(*key))
+                    .collect();
+
+                if self.headers {
+                    wtr.write_record(&columns)?;
+                }
+                for row in &rows {
+                    let fields: HashMap<&str, &Value> = row
+                        .iter()
+                        .map(|(key, value)| (key.as_str(), *value))
+                        .collect();
+                    wtr.write_record(columns.iter().map(|column| {
+                        fields
+                            .get(column)
+                            .map(|value| self.cell(value))
+                            .unwrap_or_default()
+                    }))?;
+                }
+            }
+            Value::Object(obj) => {
+                if self.headers {
+                    wtr.write_record(["key", "value"])?;
+                }
+                for (key, value) in flatten(obj) {
+                    wtr.write_record([key, self.cell(value)])?;
+                }
+            }
+            scalar => return Ok(scalar.to_string()),
+        }
+
+        Ok(String::from_utf8(wtr.into_inner()?)?)
+    }
+}
+
+/// Leaf fields of `obj` keyed by their dotted path
+pub(crate) fn flatten(obj: &Map<String, Value>) -> Vec<(String, &Value)> {
+    let mut fields = Vec::new();
+    flatten_into(&mut fields, "", obj);
+    fields
+}
+
+fn flatten_into<'a>(out: &mut Vec<(String, &'a Value)>, prefix: &str, obj: &'a Map<String, Value>) {
+    for (key, value) in obj {
+        let key = if prefix.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", prefix, key)
+        };
+        match value {
+            Value::Object(nested) if !nested.is_empty() => flatten_into(out, &key, nested),
+            other => out.push((key, other)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_nested_objects_become_dotted_columns() {
+        let data = json!([{
+            "id": "opt-1",
+            "expected_impact": { "cost_reduction_pct": 30.5, "quality_delta_pct": -1 }
+        }]);
+
+        let output = CsvFormatter::new().write(&data).unwrap();
+        assert_eq!(
+            output,
+            "expected_impact.cost_reduction_pct,expected_impact.quality_delta_pct,id\n\
+             30.5,-1,opt-1\n"
+        );
+    }
+
+    #[test]
+    fn test_missing_keys_are_empty_cells() {
+        let data = json!([
+            { "id": "a", "config": { "model": "haiku" } },
+            { "id": "b", "owner": "team-x", "note": null }
+        ]);
+
+        let output = CsvFormatter::new().write(&data).unwrap();
+        let mut lines = output.lines();
+        assert_eq!(lines.next(), Some("config.model,id,note,owner"));
+        assert_eq!(lines.next(), Some("haiku,a,,"));
+        assert_eq!(lines.next(), Some(",b,,team-x"));
+        assert_eq!(lines.next(), None);
+    }
+
+    #[test]
+    fn test_arrays_are_joined() {
+        let data = json!([
+            { "id": "a", "tags": ["latency", "cost"] },
+            { "id": "b", "tags": [] }
+        ]);
+
+        let output = CsvFormatter::new().write(&data).unwrap();
+        assert_eq!(output, "id,tags\na,latency; cost\nb,\n");
+
+        let output = CsvFormatter::new().with_json_arrays().write(&data).unwrap();
+        assert_eq!(
+            output,
+            "id,tags\na,\"[\"\"latency\"\",\"\"cost\"\"]\"\nb,[]\n"
+        );
+    }
+
+    #[test]
+    fn test_delimiter_and_header_suppression() {
+        let data = json!([{ "id": "a", "savings": 1.5, "tags": ["x", "y"] }]);
+
+        let output = CsvFormatter::new()
+            .with_delimiter(CsvDelimiter::Semicolon)
+            .write(&data)
+            .unwrap();
+        assert_eq!(output, "id;savings;tags\na;1.5;\"x; y\"\n");
+
+        let output = CsvFormatter::new()
+            .with_delimiter(CsvDelimiter::Tab)
+            .with_headers(false)
+            .write(&data)
+            .unwrap();
+        assert_eq!(output, "a\t1.5\tx; y\n");
+    }
+
+    #[test]
+    fn test_single_object_is_flattened_into_key_value_pairs() {
+        let data = json!({ "id": "a", "config": { "model": "haiku" } });
+
+        let output = CsvFormatter::new().write(&data).unwrap();
+        assert_eq!(output, "key,value\nconfig.model,haiku\nid,a\n");
+    }
+
+    #[test]
+    fn test_delimiter_from_str() {
+        assert_eq!("tab".parse::<CsvDelimiter>(), Ok(CsvDelimiter::Tab));
+        assert_eq!(";".parse::<CsvDelimiter>(), Ok(CsvDelimiter::Semicolon));
+        assert_eq!("Comma".parse::<CsvDelimiter>(), Ok(CsvDelimiter::Comma));
+        assert!("pipe".parse::<CsvDelimiter>().is_err());
+    }
+}
@@ -0,0 +1,191 @@
+//! Writing command output to a file for `--output-file`
+//!
+//! While capturing, output that would go to stdout is held in memory. Once
+//! the command succeeds it is written to a temporary file next to the target
+//! and renamed over it, so a failed command, or a failed write, leaves any
+//! existing file untouched.
+
+use crate::{CliError, CliResult};
+use std::ffi::OsString;
+use std::fs::File;
+use std::io::Write;
+use std::path::Path;
+use std::sync::Mutex;
+
+static CAPTURE: Mutex<Option<CapturedOutput>> = Mutex::new(None);
+
+/// Output held back until the command finishes
+#[derive(Debug, Default)]
+pub struct CapturedOutput {
+    buffer: Vec<u8>,
+}
+
+impl CapturedOutput {
+    /// Add `line` followed by a newline
+    pub fn push_line(&mut self, line: &str) {
+        self.buffer.extend_from_slice(line.as_bytes());
+        self.buffer.push(b'\n');
+    }
+
+    /// Add raw bytes, e.g. compressed output
+    pub fn push_bytes(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Write everything captured to `path` atomically
+    pub fn commit(self, path: &Path) -> CliResult<()> {
+        write_atomic(path, &self.buffer)
+    }
+}
+
+/// Hold stdout output back until [`take_capture`]
+pub fn start_capture() {
+    *CAPTURE.lock().unwrap() = Some(CapturedOutput::default());
+}
+
+/// Whether output is being captured for `--output-file`
+pub fn is_capturing() -> bool {
+    CAPTURE.lock().unwrap().is_some()
+}
+
+/// Stop capturing and return what was captured
+pub fn take_capture() -> Option<CapturedOutput> {
+    CAPTURE.lock().unwrap().take()
+}
+
+/// Print `line` to stdout, or capture it
+pub fn emit(line: &str) {
+    match CAPTURE.lock().unwrap().as_mut() {
+        Some(captured) => captured.push_line(line),
+        None => println!("{}", line),
+    }
+}
+
+/// Write `bytes` to stdout as-is, or capture them
+pub fn emit_bytes(bytes: &[u8]) -> CliResult<()> {
+    match CAPTURE.lock().unwrap().as_mut() {
+        Some(captured) => captured.push_bytes(bytes),
+        None => std::io::stdout().write_all(bytes)?,
+    }
+    Ok(())
+}
+
+/// Replace `path` with `contents`, or leave it untouched on error
+///
+/// The temporary file is created in the target's directory so the rename
+/// stays on one filesystem and cannot leave a partial file behind. An
+/// existing file's permissions are kept.
+pub fn write_atomic(path: &Path, contents: &[u8]) -> CliResult<()> {
+    write_atomic_with(path, |file| file.write_all(contents))
+}
+
+/// [`write_atomic`] with the contents written by `write`
+fn write_atomic_with(
+    path: &Path,
+    write: impl FnOnce(&mut File) -> std::io::Result<()>,
+) -> CliResult<()> {
+    let name = path
+        .file_name()
+        .ok_or_else(|| CliError::InvalidInput(format!("{} is not a file path", path.display())))?;
+    let dir = match path.parent() {
+        Some(parent) if !parent.as_os_str().is_empty() => parent,
+        _ => Path::new("."),
+    };
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = dir.join(temp_name);
+
+    let written = File::create(&temp).and_then(|mut file| {
+        if let Ok(existing) = std::fs::metadata(path) {
+            file.set_permissions(existing.permissions())?;
+        }
+        write(&mut file)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+
+    Ok(written?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_commit_replaces_file_without_leaving_temp_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, "old").unwrap();
+
+        let mut captured = CapturedOutput::default();
+        captured.push_line("{\"a\": 1}");
+        captured.commit(&path).unwrap();
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "{\"a\": 1}\n");
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[test]
+    fn test_write_failing_partway_leaves_target_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.yaml");
+        std::fs::write(&path, "original").unwrap();
+
+        let result = write_atomic_with(&path, |file| {
+            file.write_all(b"partial")?;
+            Err(std::io::Error::other("disk full"))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        let entries: Vec<_> = std::fs::read_dir(dir.path()).unwrap().collect();
+        assert_eq!(entries.len(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_replacing_file_keeps_its_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("credentials.toml");
+        std::fs::write(&path, "old").unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+
+        write_atomic(&path, b"new").unwrap();
+
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o600);
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn test_failed_write_leaves_file_unchanged() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("metrics.csv");
+        std::fs::write(&path, "original").unwrap();
+
+        // Renaming a file over a non-empty directory fails after the
+        // temporary file was written
+        let target = dir.path().join("occupied");
+        std::fs::create_dir(&target).unwrap();
+        std::fs::write(target.join("keep"), "x").unwrap();
+        assert!(write_atomic(&target, b"new").is_err());
+
+        assert!(write_atomic(&dir.path().join("missing").join("out.csv"), b"new").is_err());
+
+        assert_eq!(std::fs::read_to_string(&path).unwrap(), "original");
+        let mut names: Vec<_> = std::fs::read_dir(dir.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["metrics.csv", "occupied"]);
+    }
+}
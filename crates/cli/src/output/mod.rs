@@ -3,12 +3,18 @@
 mod table;
 mod json;
 mod yaml;
+mod csv;
+pub mod file;
+pub mod pager;
 
 pub use table::TableFormatter;
 pub use json::JsonFormatter;
 pub use yaml::YamlFormatter;
+pub use self::csv::{CsvDelimiter, CsvFormatter, DEFAULT_ARRAY_SEPARATOR};
+pub(crate) use self::csv::flatten;
 
-use crate::CliResult;
+use crate::timing::{self, Phase};
+use crate::{CliError, CliResult};
 use serde::Serialize;
 use std::str::FromStr;
 
@@ -52,12 +58,47 @@ impl FromStr for OutputFormat {
     }
 }
 
+/// Output formats each command can render, keyed by command path.
+///
+/// Commands not listed here accept every format.
+const FORMAT_CAPABILITIES: &[(&str, &[OutputFormat])] = &[
+    // The export is a raw configuration document, not tabular data
+    ("config export", &[OutputFormat::Yaml, OutputFormat::Json]),
+    // Events are printed one per line as they arrive
+    ("logs", &[OutputFormat::Table, OutputFormat::Json, OutputFormat::Yaml]),
+];
+
+/// Get the formats a command supports, or `None` if it supports all of them
+pub fn supported_formats(command: &str) -> Option<&'static [OutputFormat]> {
+    FORMAT_CAPABILITIES
+        .iter()
+        .find(|(name, _)| *name == command)
+        .map(|(_, formats)| *formats)
+}
+
+/// Check that a command can render the requested output format
+pub fn check_format_supported(command: &str, format: OutputFormat) -> CliResult<()> {
+    match supported_formats(command) {
+        Some(formats) if !formats.contains(&format) => {
+            let alternatives: Vec<&str> = formats.iter().map(|f| f.as_str()).collect();
+            Err(CliError::InvalidInput(format!(
+                "{} does not support --output {}; use {}",
+                command,
+                format,
+                alternatives.join(" or ")
+            )))
+        }
+        _ => Ok(()),
+    }
+}
+
 /// Output writer trait
 pub trait OutputWriter {
     fn write<T: Serialize>(&self, data: &T) -> CliResult<String>;
 }
 
 /// Formatter enum that holds all formatter types
+#[derive(Debug, Clone)]
 pub enum Formatter {
     Table(TableFormatter),
     Json(JsonFormatter),
@@ -67,6 +108,7 @@ pub enum Formatter {
 
 impl OutputWriter for Formatter {
     fn write<T: Serialize>(&self, data: &T) -> CliResult<String> {
+        let _span = timing::span(Phase::OutputFormat);
         match self {
             Formatter::Table(f) => f.write(data),
             Formatter::Json(f) => f.write(data),
@@ -76,82 +118,102 @@ impl OutputWriter for Formatter {
     }
 }
 
+impl Formatter {
+    /// The same formatter without a pager, for output that is redrawn in
+    /// place
+    pub fn without_pager(&self) -> Formatter {
+        match self {
+            Formatter::Table(table) => Formatter::Table(table.clone().with_pager(None)),
+            other => other.clone(),
+        }
+    }
+
+    /// Print rendered output, through the pager when one is configured and
+    /// the output is taller than the terminal
+    ///
+    /// With `--output-file` the output is captured instead; see [`file`].
+    pub fn print(&self, output: &str) {
+        if file::is_capturing() {
+            file::emit(output);
+            return;
+        }
+
+        if let Formatter::Table(table) = self {
+            if let Some(command) = table.pager() {
+                if pager::exceeds_terminal(output) {
+                    match pager::page(command, output) {
+                        Ok(()) => return,
+                        Err(e) => tracing::debug!("Pager '{}' failed: {}", command, e),
+                    }
+                }
+            }
+        }
+
+        println!("{}", output);
+    }
+}
+
+/// Presentation options shared by formatters
+#[derive(Debug, Clone, Default)]
+pub struct FormatOptions {
+    /// Render timestamps relative to now in table output
+    pub relative_time: bool,
+    /// Pager command for long table output; see [`pager::resolve_pager`]
+    pub pager: Option<String>,
+    /// Field delimiter for CSV output
+    pub csv_delimiter: CsvDelimiter,
+    /// Leave out the CSV header row
+    pub csv_no_headers: bool,
+}
+
 /// Get formatter for the specified format
 pub fn get_formatter(format: OutputFormat) -> Formatter {
+    get_formatter_with_options(format, FormatOptions::default())
+}
+
+/// Get formatter for the specified format with presentation options
+pub fn get_formatter_with_options(format: OutputFormat, options: FormatOptions) -> Formatter {
     match format {
-        OutputFormat::Table => Formatter::Table(TableFormatter),
+        OutputFormat::Table => {
+            Formatter::Table(
+                TableFormatter::new()
+                    .with_relative_time(options.relative_time)
+                    .with_pager(options.pager),
+            )
+        }
         OutputFormat::Json => Formatter::Json(JsonFormatter),
         OutputFormat::Yaml => Formatter::Yaml(YamlFormatter),
-        OutputFormat::Csv => Formatter::Csv(CsvFormatter),
+        OutputFormat::Csv => Formatter::Csv(
+            CsvFormatter::new()
+                .with_delimiter(options.csv_delimiter)
+                .with_headers(!options.csv_no_headers),
+        ),
     }
 }
 
-/// CSV formatter
-pub struct CsvFormatter;
-
-impl OutputWriter for CsvFormatter {
-    fn write<T: Serialize>(&self, data: &T) -> CliResult<String> {
-        let json_value = serde_json::to_value(data)?;
-
-        // Convert to CSV based on structure
-        if let Some(array) = json_value.as_array() {
-            if array.is_empty() {
-                return Ok(String::new());
-            }
-
-            let mut wtr = csv::Writer::from_writer(vec![]);
-
-            // Get headers from first object
-            if let Some(first) = array.first() {
-                if let Some(obj) = first.as_object() {
-                    let headers: Vec<&String> = obj.keys().collect();
-                    wtr.write_record(&headers)?;
-
-                    // Write data rows
-                    for item in array {
-                        if let Some(obj) = item.as_object() {
-                            let values: Vec<String> = headers
-                                .iter()
-                                .map(|k| {
-                                    obj.get(*k)
-                                        .and_then(|v| match v {
-                                            serde_json::Value::String(s) => Some(s.clone()),
-                                            serde_json::Value::Number(n) => Some(n.to_string()),
-                                            serde_json::Value::Bool(b) => Some(b.to_string()),
-                                            serde_json::Value::Null => Some(String::new()),
-                                            _ => Some(serde_json::to_string(v).unwrap_or_default()),
-                                        })
-                                        .unwrap_or_default()
-                                })
-                                .collect();
-                            wtr.write_record(&values)?;
-                        }
-                    }
-                }
-            }
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_unsupported_format_is_rejected() {
+        let err = check_format_supported("config export", OutputFormat::Table).unwrap_err();
+        match err {
+            CliError::InvalidInput(msg) => assert_eq!(
+                msg,
+                "config export does not support --output table; use yaml or json"
+            ),
+            other => panic!("expected InvalidInput, got {:?}", other),
+        }
 
-            let data = String::from_utf8(wtr.into_inner()?)?;
-            Ok(data)
-        } else if let Some(obj) = json_value.as_object() {
-            // Single object - write key-value pairs
-            let mut wtr = csv::Writer::from_writer(vec![]);
-            wtr.write_record(&["key", "value"])?;
-
-            for (key, value) in obj {
-                let value_str = match value {
-                    serde_json::Value::String(s) => s.clone(),
-                    serde_json::Value::Number(n) => n.to_string(),
-                    serde_json::Value::Bool(b) => b.to_string(),
-                    serde_json::Value::Null => String::new(),
-                    _ => serde_json::to_string(value)?,
-                };
-                wtr.write_record(&[key, &value_str])?;
-            }
+        assert!(check_format_supported("logs", OutputFormat::Csv).is_err());
+    }
 
-            let data = String::from_utf8(wtr.into_inner()?)?;
-            Ok(data)
-        } else {
-            Ok(format!("{}", json_value))
-        }
+    #[test]
+    fn test_supported_and_unrestricted_formats_are_accepted() {
+        assert!(check_format_supported("config export", OutputFormat::Yaml).is_ok());
+        assert!(check_format_supported("config export", OutputFormat::Json).is_ok());
+        assert!(check_format_supported("optimize list", OutputFormat::Csv).is_ok());
+        assert!(supported_formats("optimize list").is_none());
     }
 }
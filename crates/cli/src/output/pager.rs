@@ -0,0 +1,104 @@
+//! Paging of long human-facing output
+
+use super::OutputFormat;
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+/// Pager used when neither `LLM_OPTIMIZER_PAGER` nor `PAGER` is set
+pub const DEFAULT_PAGER: &str = "less -FRX";
+
+/// Environment variable overriding `PAGER` for this CLI; empty or `cat` disables paging
+pub const PAGER_ENV: &str = "LLM_OPTIMIZER_PAGER";
+
+/// Choose the pager command for this invocation, if output should be paged
+///
+/// Only table output written to a terminal is paged; machine formats and
+/// redirected output are always written directly. `env` looks up
+/// environment variables.
+pub fn resolve_pager(
+    format: OutputFormat,
+    no_pager: bool,
+    stdout_is_terminal: bool,
+    env: impl Fn(&str) -> Option<String>,
+) -> Option<String> {
+    if no_pager || !stdout_is_terminal || format != OutputFormat::Table {
+        return None;
+    }
+
+    let command = env(PAGER_ENV)
+        .or_else(|| env("PAGER"))
+        .unwrap_or_else(|| DEFAULT_PAGER.to_string());
+    let command = command.trim();
+
+    if command.is_empty() || command == "cat" {
+        None
+    } else {
+        Some(command.to_string())
+    }
+}
+
+/// Whether `output` has more lines than the terminal can show at once
+pub fn exceeds_terminal(output: &str) -> bool {
+    match console::Term::stdout().size_checked() {
+        Some((rows, _)) => output.lines().count() > usize::from(rows),
+        None => false,
+    }
+}
+
+/// Write `output` through the pager and wait for the user to quit it
+pub fn page(command: &str, output: &str) -> std::io::Result<()> {
+    let mut parts = command.split_whitespace();
+    let program = parts.next().unwrap_or(DEFAULT_PAGER);
+
+    let mut child = Command::new(program)
+        .args(parts)
+        .stdin(Stdio::piped())
+        .spawn()?;
+
+    if let Some(mut stdin) = child.stdin.take() {
+        // The pager closes its input when the user quits early
+        match writeln!(stdin, "{}", output) {
+            Err(e) if e.kind() == std::io::ErrorKind::BrokenPipe => {}
+            result => result?,
+        }
+    }
+
+    child.wait()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_machine_formats_and_redirected_output_never_page() {
+        for format in [OutputFormat::Json, OutputFormat::Yaml, OutputFormat::Csv] {
+            assert_eq!(resolve_pager(format, false, true, env(&[])), None, "{}", format);
+        }
+        assert_eq!(resolve_pager(OutputFormat::Table, false, false, env(&[])), None);
+        assert_eq!(resolve_pager(OutputFormat::Table, true, true, env(&[])), None);
+    }
+
+    #[test]
+    fn test_pager_command_resolution() {
+        let table = |vars| resolve_pager(OutputFormat::Table, false, true, env(vars));
+
+        assert_eq!(table(&[]).as_deref(), Some(DEFAULT_PAGER));
+        assert_eq!(table(&[("PAGER", "more")]).as_deref(), Some("more"));
+        assert_eq!(
+            table(&[("PAGER", "more"), (PAGER_ENV, "bat --paging=always")]).as_deref(),
+            Some("bat --paging=always")
+        );
+        assert_eq!(table(&[(PAGER_ENV, "")]), None);
+        assert_eq!(table(&[("PAGER", "cat")]), None);
+    }
+}
@@ -2,28 +2,58 @@
 
 use super::OutputWriter;
 use crate::CliResult;
+use chrono::{DateTime, Utc};
 use comfy_table::{presets::UTF8_FULL, Attribute, Cell, Color, ContentArrangement, Table};
 use serde::Serialize;
 use serde_json::Value;
 
-pub struct TableFormatter;
+#[derive(Debug, Clone, Default)]
+pub struct TableFormatter {
+    /// Render timestamps relative to now ("3 minutes ago")
+    relative_time: bool,
+    /// Pager command for output taller than the terminal
+    pager: Option<String>,
+}
+
+impl TableFormatter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Render timestamp values as human-relative durations
+    pub fn with_relative_time(mut self, enabled: bool) -> Self {
+        self.relative_time = enabled;
+        self
+    }
+
+    /// Page output taller than the terminal through `command`
+    pub fn with_pager(mut self, command: Option<String>) -> Self {
+        self.pager = command;
+        self
+    }
+
+    /// Pager command, if output should be paged
+    pub fn pager(&self) -> Option<&str> {
+        self.pager.as_deref()
+    }
+}
 
 impl OutputWriter for TableFormatter {
     fn write<T: Serialize>(&self, data: &T) -> CliResult<String> {
         let json_value = serde_json::to_value(data)?;
-        Ok(format_as_table(&json_value))
+        Ok(format_as_table(&json_value, self.relative_time))
     }
 }
 
-fn format_as_table(value: &Value) -> String {
+fn format_as_table(value: &Value, relative_time: bool) -> String {
     match value {
-        Value::Array(items) => format_array_as_table(items),
-        Value::Object(obj) => format_object_as_table(obj),
+        Value::Array(items) => format_array_as_table(items, relative_time),
+        Value::Object(obj) => format_object_as_table(obj, relative_time),
         _ => value.to_string(),
     }
 }
 
-fn format_array_as_table(items: &[Value]) -> String {
+fn format_array_as_table(items: &[Value], relative_time: bool) -> String {
     if items.is_empty() {
         return "No data available".to_string();
     }
@@ -55,7 +85,7 @@ fn format_array_as_table(items: &[Value]) -> String {
                 if let Some(obj) = item.as_object() {
                     let row: Vec<String> = headers
                         .iter()
-                        .map(|k| format_value(obj.get(k).unwrap_or(&Value::Null)))
+                        .map(|k| format_value(obj.get(k).unwrap_or(&Value::Null), relative_time))
                         .collect();
                     table.add_row(row);
                 }
@@ -73,13 +103,13 @@ fn format_array_as_table(items: &[Value]) -> String {
         .fg(Color::Cyan)]);
 
     for item in items {
-        table.add_row(vec![format_value(item)]);
+        table.add_row(vec![format_value(item, relative_time)]);
     }
 
     table.to_string()
 }
 
-fn format_object_as_table(obj: &serde_json::Map<String, Value>) -> String {
+fn format_object_as_table(obj: &serde_json::Map<String, Value>, relative_time: bool) -> String {
     let mut table = Table::new();
     table
         .load_preset(UTF8_FULL)
@@ -93,23 +123,63 @@ fn format_object_as_table(obj: &serde_json::Map<String, Value>) -> String {
     ]);
 
     for (key, value) in obj {
-        table.add_row(vec![key.clone(), format_value(value)]);
+        table.add_row(vec![key.clone(), format_value(value, relative_time)]);
     }
 
     table.to_string()
 }
 
-fn format_value(value: &Value) -> String {
+fn format_value(value: &Value, relative_time: bool) -> String {
     match value {
         Value::Null => "null".to_string(),
         Value::Bool(b) => b.to_string(),
         Value::Number(n) => n.to_string(),
+        Value::String(s) if relative_time => match parse_timestamp(s) {
+            Some(dt) => format_relative(dt, Utc::now()),
+            None => s.clone(),
+        },
         Value::String(s) => s.clone(),
         Value::Array(arr) => format!("[{} items]", arr.len()),
         Value::Object(_) => "[object]".to_string(),
     }
 }
 
+/// Recognize serialized timestamps (RFC3339 with a date and time part)
+fn parse_timestamp(s: &str) -> Option<DateTime<Utc>> {
+    if s.len() < 20 || !s.as_bytes()[0].is_ascii_digit() {
+        return None;
+    }
+    DateTime::parse_from_rfc3339(s)
+        .ok()
+        .map(|dt| dt.with_timezone(&Utc))
+}
+
+/// Render `then` relative to `now`, e.g. "3 minutes ago" or "in 2 hours"
+fn format_relative(then: DateTime<Utc>, now: DateTime<Utc>) -> String {
+    let delta = now.signed_duration_since(then);
+    let secs = delta.num_seconds().abs();
+
+    if secs < 10 {
+        return "just now".to_string();
+    }
+
+    let (amount, unit) = match secs {
+        s if s < 60 => (s, "second"),
+        s if s < 3_600 => (s / 60, "minute"),
+        s if s < 86_400 => (s / 3_600, "hour"),
+        s if s < 30 * 86_400 => (s / 86_400, "day"),
+        s if s < 365 * 86_400 => (s / (30 * 86_400), "month"),
+        s => (s / (365 * 86_400), "year"),
+    };
+    let plural = if amount == 1 { "" } else { "s" };
+
+    if delta.num_seconds() >= 0 {
+        format!("{} {}{} ago", amount, unit, plural)
+    } else {
+        format!("in {} {}{}", amount, unit, plural)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -122,7 +192,7 @@ mod tests {
             {"id": "2", "name": "Test 2", "status": "inactive"}
         ]);
 
-        let result = format_as_table(&data);
+        let result = format_as_table(&data, false);
         assert!(result.contains("id"));
         assert!(result.contains("name"));
         assert!(result.contains("status"));
@@ -138,7 +208,7 @@ mod tests {
             "status": "active"
         });
 
-        let result = format_as_table(&data);
+        let result = format_as_table(&data, false);
         assert!(result.contains("Key"));
         assert!(result.contains("Value"));
         assert!(result.contains("id"));
@@ -148,7 +218,33 @@ mod tests {
     #[test]
     fn test_empty_array() {
         let data = json!([]);
-        let result = format_as_table(&data);
+        let result = format_as_table(&data, false);
         assert_eq!(result, "No data available");
     }
+
+    #[test]
+    fn test_relative_time_in_table() {
+        let recent = (Utc::now() - chrono::Duration::minutes(3)).to_rfc3339();
+        let data = json!({"id": "123", "created_at": recent});
+
+        let relative = TableFormatter::new().with_relative_time(true).write(&data).unwrap();
+        assert!(relative.contains("3 minutes ago"), "{}", relative);
+        assert!(!relative.contains(&recent));
+
+        let absolute = TableFormatter::new().write(&data).unwrap();
+        assert!(absolute.contains(&recent));
+
+        let json = crate::output::JsonFormatter.write(&data).unwrap();
+        assert!(json.contains(&recent));
+    }
+
+    #[test]
+    fn test_format_relative() {
+        let now = Utc::now();
+        assert_eq!(format_relative(now, now), "just now");
+        assert_eq!(format_relative(now - chrono::Duration::hours(1), now), "1 hour ago");
+        assert_eq!(format_relative(now + chrono::Duration::days(2), now), "in 2 days");
+        assert!(parse_timestamp("not a date").is_none());
+        assert!(parse_timestamp("12345678901234567890").is_none());
+    }
 }
@@ -4,6 +4,7 @@ use super::OutputWriter;
 use crate::CliResult;
 use serde::Serialize;
 
+#[derive(Debug, Clone)]
 pub struct YamlFormatter;
 
 impl OutputWriter for YamlFormatter {
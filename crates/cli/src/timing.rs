@@ -0,0 +1,238 @@
+//! Command latency breakdown for `--profile-timing`
+//!
+//! Phases of the command dispatch path are timed with [`span`] guards. Timing
+//! is off until [`enable`] is called, so an unused guard costs one atomic
+//! load.
+
+use comfy_table::{presets::UTF8_FULL, Attribute, Cell, CellAlignment, Color, Table};
+use serde::Serialize;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+static PROFILE: Mutex<Profile> = Mutex::new(Profile::new());
+
+/// Distinct phases of a command
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    /// Reading and resolving the CLI configuration
+    ConfigLoad,
+    /// Building the API client
+    ClientBuild,
+    /// Sending requests until response headers arrive
+    RequestSend,
+    /// Reading and deserializing response bodies
+    ResponseParse,
+    /// Rendering output
+    OutputFormat,
+}
+
+impl Phase {
+    /// Every phase, in dispatch order
+    pub const ALL: [Phase; 5] = [
+        Phase::ConfigLoad,
+        Phase::ClientBuild,
+        Phase::RequestSend,
+        Phase::ResponseParse,
+        Phase::OutputFormat,
+    ];
+
+    /// Human-readable name
+    pub fn label(&self) -> &'static str {
+        match self {
+            Phase::ConfigLoad => "config load",
+            Phase::ClientBuild => "client build",
+            Phase::RequestSend => "request send",
+            Phase::ResponseParse => "response parse",
+            Phase::OutputFormat => "output format",
+        }
+    }
+}
+
+/// Time accumulated per phase
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Profile {
+    phases: [Duration; Phase::ALL.len()],
+}
+
+impl Profile {
+    /// Create an empty profile
+    pub const fn new() -> Self {
+        Self {
+            phases: [Duration::ZERO; Phase::ALL.len()],
+        }
+    }
+
+    /// Add time spent in `phase`
+    pub fn record(&mut self, phase: Phase, elapsed: Duration) {
+        self.phases[phase as usize] += elapsed;
+    }
+
+    /// Break the command's `total` time down by phase
+    ///
+    /// Time outside any phase is reported as `other_ms`. Concurrent requests
+    /// add up, so phases can exceed `total`, in which case `other_ms` is zero.
+    pub fn report(&self, total: Duration) -> TimingReport {
+        let accounted: Duration = self.phases.iter().sum();
+        TimingReport {
+            phases: Phase::ALL
+                .iter()
+                .map(|&phase| PhaseTiming {
+                    phase,
+                    ms: millis(self.phases[phase as usize]),
+                })
+                .collect(),
+            other_ms: millis(total.saturating_sub(accounted)),
+            total_ms: millis(total),
+        }
+    }
+}
+
+/// Time spent in one phase
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseTiming {
+    pub phase: Phase,
+    pub ms: f64,
+}
+
+/// Per-phase breakdown of a command's latency
+#[derive(Debug, Clone, Serialize)]
+pub struct TimingReport {
+    pub phases: Vec<PhaseTiming>,
+    pub other_ms: f64,
+    pub total_ms: f64,
+}
+
+impl TimingReport {
+    /// Render the breakdown as a table with each phase's share of the total
+    pub fn to_table(&self) -> String {
+        let share = |ms: f64| {
+            if self.total_ms > 0.0 {
+                format!("{:.1}%", ms / self.total_ms * 100.0)
+            } else {
+                "-".to_string()
+            }
+        };
+        let row = |name: &str, ms: f64| {
+            vec![
+                Cell::new(name),
+                Cell::new(format!("{:.2}", ms)).set_alignment(CellAlignment::Right),
+                Cell::new(share(ms)).set_alignment(CellAlignment::Right),
+            ]
+        };
+
+        let mut table = Table::new();
+        table.load_preset(UTF8_FULL).set_header(
+            ["Phase", "Time (ms)", "Share"]
+                .into_iter()
+                .map(|h| Cell::new(h).add_attribute(Attribute::Bold).fg(Color::Cyan)),
+        );
+        for timing in &self.phases {
+            table.add_row(row(timing.phase.label(), timing.ms));
+        }
+        table.add_row(row("other", self.other_ms));
+        table.add_row(
+            row("total", self.total_ms)
+                .into_iter()
+                .map(|cell| cell.add_attribute(Attribute::Bold)),
+        );
+
+        table.to_string()
+    }
+}
+
+/// Start recording phase timings for this process
+pub fn enable() {
+    ENABLED.store(true, Ordering::Relaxed);
+}
+
+/// Time the enclosing scope as part of `phase`
+///
+/// The elapsed time is recorded when the returned guard is dropped.
+pub fn span(phase: Phase) -> Span {
+    Span {
+        phase,
+        started: ENABLED.load(Ordering::Relaxed).then(Instant::now),
+    }
+}
+
+/// Breakdown of everything recorded so far, given the command's total time
+pub fn report(total: Duration) -> TimingReport {
+    lock().report(total)
+}
+
+/// Guard returned by [`span`]
+#[must_use = "the phase is timed until the guard is dropped"]
+pub struct Span {
+    phase: Phase,
+    started: Option<Instant>,
+}
+
+impl Drop for Span {
+    fn drop(&mut self) {
+        if let Some(started) = self.started {
+            lock().record(self.phase, started.elapsed());
+        }
+    }
+}
+
+fn lock() -> std::sync::MutexGuard<'static, Profile> {
+    // Timings are plain numbers, still usable after a panic elsewhere
+    PROFILE.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+/// Milliseconds, to microsecond precision
+fn millis(duration: Duration) -> f64 {
+    duration.as_micros() as f64 / 1000.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_breakdown_sums_to_total() {
+        let mut profile = Profile::new();
+        profile.record(Phase::ConfigLoad, Duration::from_millis(3));
+        profile.record(Phase::ClientBuild, Duration::from_millis(2));
+        profile.record(Phase::RequestSend, Duration::from_millis(40));
+        profile.record(Phase::RequestSend, Duration::from_millis(10));
+        profile.record(Phase::ResponseParse, Duration::from_millis(4));
+        profile.record(Phase::OutputFormat, Duration::from_millis(1));
+
+        let report = profile.report(Duration::from_millis(65));
+
+        let phases: Vec<Phase> = report.phases.iter().map(|t| t.phase).collect();
+        assert_eq!(phases, Phase::ALL);
+        assert_eq!(report.phases[2].ms, 50.0);
+
+        let sum: f64 = report.phases.iter().map(|t| t.ms).sum::<f64>() + report.other_ms;
+        assert!((sum - report.total_ms).abs() < 1e-6);
+        assert!((report.other_ms - 5.0).abs() < 1e-6);
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(json["phases"][0]["phase"], "config_load");
+        assert_eq!(json["total_ms"], 65.0);
+
+        let table = report.to_table();
+        for phase in Phase::ALL {
+            assert!(table.contains(phase.label()), "missing {}", phase.label());
+        }
+    }
+
+    #[test]
+    fn test_spans_record_once_enabled() {
+        // Other tests may record into the shared profile once it is enabled,
+        // so only a lower bound holds
+        enable();
+        {
+            let _span = span(Phase::ConfigLoad);
+            std::thread::sleep(Duration::from_millis(5));
+        }
+
+        let report = report(Duration::from_secs(1));
+        assert!(report.phases[Phase::ConfigLoad as usize].ms >= 5.0);
+    }
+}
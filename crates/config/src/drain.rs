@@ -0,0 +1,122 @@
+//! Draining state shared by the servers, health probes and service manager
+//!
+//! Once draining starts, the REST and gRPC servers reject new requests while
+//! requests already in flight run to completion, and readiness probes fail.
+//! Shutdown waits for [`DrainState::wait_idle`] before stopping services.
+
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Notify;
+
+/// Shared draining flag and count of requests in flight
+#[derive(Debug, Default)]
+pub struct DrainState {
+    draining: AtomicBool,
+    in_flight: AtomicUsize,
+    idle: Notify,
+}
+
+impl DrainState {
+    /// Create a new state, initially accepting requests
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stop accepting new requests
+    pub fn start(&self) {
+        self.draining.store(true, Ordering::SeqCst);
+    }
+
+    /// Accept requests again, e.g. when services are restarted
+    pub fn resume(&self) {
+        self.draining.store(false, Ordering::SeqCst);
+    }
+
+    /// Whether new requests are being rejected
+    pub fn is_draining(&self) -> bool {
+        self.draining.load(Ordering::SeqCst)
+    }
+
+    /// Number of requests in flight
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::SeqCst)
+    }
+
+    /// Count a request as in flight until the returned guard is dropped, or
+    /// return `None` if draining
+    pub fn track(self: &Arc<Self>) -> Option<InFlight> {
+        // Count first so a drain that starts now either sees this request
+        // or is seen by it
+        self.in_flight.fetch_add(1, Ordering::SeqCst);
+        let guard = InFlight(Arc::clone(self));
+        if self.is_draining() {
+            return None;
+        }
+        Some(guard)
+    }
+
+    /// Wait until no requests are in flight
+    pub async fn wait_idle(&self) {
+        loop {
+            let idle = self.idle.notified();
+            tokio::pin!(idle);
+            idle.as_mut().enable();
+
+            if self.in_flight() == 0 {
+                return;
+            }
+            idle.await;
+        }
+    }
+}
+
+/// A request counted by [`DrainState::track`]
+#[derive(Debug)]
+pub struct InFlight(Arc<DrainState>);
+
+impl Drop for InFlight {
+    fn drop(&mut self) {
+        if self.0.in_flight.fetch_sub(1, Ordering::SeqCst) == 1 {
+            self.0.idle.notify_waiters();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn test_resume_accepts_requests_again() {
+        let drain = Arc::new(DrainState::new());
+        drain.start();
+        assert!(drain.track().is_none());
+        assert_eq!(drain.in_flight(), 0);
+
+        drain.resume();
+        let guard = drain.track();
+        assert!(guard.is_some());
+        assert_eq!(drain.in_flight(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_wait_idle_returns_once_requests_finish() {
+        let drain = Arc::new(DrainState::new());
+        let guard = drain.track().unwrap();
+        drain.start();
+
+        let idle = tokio::spawn({
+            let drain = drain.clone();
+            async move { drain.wait_idle().await }
+        });
+        tokio::task::yield_now().await;
+        assert!(!idle.is_finished());
+
+        drop(guard);
+        tokio::time::timeout(Duration::from_secs(1), idle)
+            .await
+            .expect("drain did not become idle")
+            .unwrap();
+    }
+}
@@ -6,6 +6,14 @@ use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use thiserror::Error;
 
+pub mod drain;
+pub mod logging;
+pub mod ratelimit;
+
+pub use drain::{DrainState, InFlight};
+pub use logging::LoggingConfig;
+pub use ratelimit::{RateLimitAlgorithm, RateLimited, RateLimiter};
+
 #[derive(Error, Debug)]
 pub enum ConfigError {
     #[error("Failed to load configuration: {0}")]
@@ -0,0 +1,84 @@
+//! Request log sampling shared by the REST and gRPC servers
+
+use std::time::Duration;
+
+/// Default latency above which a request is always logged
+const DEFAULT_SLOW_THRESHOLD: Duration = Duration::from_secs(1);
+
+/// Request logging configuration
+///
+/// Successful requests are logged at `sample_rate`; failed requests and
+/// requests slower than `slow_threshold` are always logged.
+#[derive(Debug, Clone, Copy)]
+pub struct LoggingConfig {
+    /// Fraction of successful requests to log (0.0-1.0)
+    pub sample_rate: f64,
+    /// Requests taking at least this long are always logged
+    pub slow_threshold: Duration,
+}
+
+impl LoggingConfig {
+    /// Log every request
+    pub fn new() -> Self {
+        Self {
+            sample_rate: 1.0,
+            slow_threshold: DEFAULT_SLOW_THRESHOLD,
+        }
+    }
+
+    /// Set the fraction of successful requests to log, clamped to 0.0-1.0
+    pub fn with_sample_rate(mut self, sample_rate: f64) -> Self {
+        self.sample_rate = sample_rate.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the latency above which requests are always logged
+    pub fn with_slow_threshold(mut self, slow_threshold: Duration) -> Self {
+        self.slow_threshold = slow_threshold;
+        self
+    }
+
+    /// Whether a request picked at `sample_rate` is logged
+    pub fn sampled(&self) -> bool {
+        self.sample_rate >= 1.0 || rand::random::<f64>() < self.sample_rate
+    }
+
+    /// Whether a completed request should be logged
+    pub fn should_log(&self, failed: bool, duration: Duration) -> bool {
+        failed || duration >= self.slow_threshold || self.sampled()
+    }
+}
+
+impl Default for LoggingConfig {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_failures_and_slow_requests_always_logged_successes_sampled() {
+        let config = LoggingConfig::new()
+            .with_sample_rate(0.1)
+            .with_slow_threshold(Duration::from_millis(500));
+        let fast = Duration::from_millis(5);
+
+        for _ in 0..1000 {
+            assert!(config.should_log(true, fast));
+            assert!(config.should_log(false, Duration::from_millis(750)));
+        }
+
+        let logged = (0..10_000).filter(|_| config.should_log(false, fast)).count();
+        assert!((700..=1300).contains(&logged), "logged {} of 10000", logged);
+    }
+
+    #[test]
+    fn test_sample_rate_is_clamped() {
+        assert_eq!(LoggingConfig::new().with_sample_rate(-1.0).sample_rate, 0.0);
+        assert_eq!(LoggingConfig::new().with_sample_rate(2.0).sample_rate, 1.0);
+        assert!((0..100).all(|_| LoggingConfig::new().sampled()));
+    }
+}
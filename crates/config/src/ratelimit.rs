@@ -0,0 +1,235 @@
+//! Request rate limiting shared by the REST and gRPC servers
+
+use std::sync::{Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+/// Rate limiting algorithm
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RateLimitAlgorithm {
+    /// Count requests in consecutive windows. Cheapest, but a client can
+    /// send a full window's worth on each side of a boundary.
+    FixedWindow,
+    /// Estimate the rate over the trailing window by weighting the previous
+    /// window's count by how much of it still overlaps
+    SlidingWindow,
+    /// Spend one token per request from a bucket refilled continuously at
+    /// the limit's rate, allowing bursts up to the bucket size
+    #[default]
+    TokenBucket,
+}
+
+/// A request was rejected by a [`RateLimiter`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimited;
+
+/// Rate limiter for a single key
+#[derive(Debug)]
+pub struct RateLimiter {
+    algorithm: RateLimitAlgorithm,
+    limit: u32,
+    window: Duration,
+    burst: u32,
+    state: Mutex<LimiterState>,
+}
+
+#[derive(Debug)]
+enum LimiterState {
+    Window {
+        start: Instant,
+        count: u32,
+        previous: u32,
+    },
+    Bucket {
+        tokens: f64,
+        updated: Instant,
+    },
+}
+
+impl RateLimiter {
+    /// Allow `limit` requests per `window`
+    ///
+    /// `burst` is the bucket size for [`RateLimitAlgorithm::TokenBucket`]
+    /// and is ignored by the window algorithms.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `limit` or `burst` is zero.
+    pub fn new(algorithm: RateLimitAlgorithm, limit: u32, window: Duration, burst: u32) -> Self {
+        Self::starting_at(algorithm, limit, window, burst, Instant::now())
+    }
+
+    fn starting_at(
+        algorithm: RateLimitAlgorithm,
+        limit: u32,
+        window: Duration,
+        burst: u32,
+        now: Instant,
+    ) -> Self {
+        assert!(limit > 0, "rate limit must be > 0");
+        assert!(burst > 0, "burst size must be > 0");
+
+        let state = match algorithm {
+            RateLimitAlgorithm::TokenBucket => LimiterState::Bucket {
+                tokens: f64::from(burst),
+                updated: now,
+            },
+            RateLimitAlgorithm::FixedWindow | RateLimitAlgorithm::SlidingWindow => {
+                LimiterState::Window {
+                    start: now,
+                    count: 0,
+                    previous: 0,
+                }
+            }
+        };
+
+        Self {
+            algorithm,
+            limit,
+            window,
+            burst,
+            state: Mutex::new(state),
+        }
+    }
+
+    /// Algorithm this limiter uses
+    pub fn algorithm(&self) -> RateLimitAlgorithm {
+        self.algorithm
+    }
+
+    /// Record a request, or reject it if the limit has been reached
+    pub fn check(&self) -> Result<(), RateLimited> {
+        self.check_at(Instant::now())
+    }
+
+    fn check_at(&self, now: Instant) -> Result<(), RateLimited> {
+        let mut state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+
+        let allowed = match &mut *state {
+            LimiterState::Bucket { tokens, updated } => {
+                let elapsed = now.saturating_duration_since(*updated);
+                let refill =
+                    elapsed.as_secs_f64() * f64::from(self.limit) / self.window.as_secs_f64();
+                *tokens = (*tokens + refill).min(f64::from(self.burst));
+                *updated = now.max(*updated);
+
+                let allowed = *tokens >= 1.0;
+                if allowed {
+                    *tokens -= 1.0;
+                }
+                allowed
+            }
+            LimiterState::Window {
+                start,
+                count,
+                previous,
+            } => {
+                let elapsed = now.saturating_duration_since(*start);
+                if elapsed >= self.window {
+                    // Only the window immediately before the current one counts
+                    *previous = if elapsed < self.window * 2 { *count } else { 0 };
+                    *count = 0;
+                    let into_window = elapsed.as_nanos() % self.window.as_nanos();
+                    *start = now - Duration::from_nanos(into_window as u64);
+                }
+
+                let previous_weight = match self.algorithm {
+                    RateLimitAlgorithm::SlidingWindow => {
+                        let into_window = now.saturating_duration_since(*start);
+                        1.0 - into_window.as_secs_f64() / self.window.as_secs_f64()
+                    }
+                    _ => 0.0,
+                };
+
+                let estimate = f64::from(*previous) * previous_weight + f64::from(*count);
+                let allowed = estimate < f64::from(self.limit);
+                if allowed {
+                    *count += 1;
+                }
+                allowed
+            }
+        };
+
+        if allowed {
+            Ok(())
+        } else {
+            Err(RateLimited)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_secs(60);
+
+    /// Send `n` requests at `at`, returning how many were allowed
+    fn burst(limiter: &RateLimiter, n: u32, at: Instant) -> u32 {
+        (0..n).filter(|_| limiter.check_at(at).is_ok()).count() as u32
+    }
+
+    /// Issue a full window of requests 1ms before a window boundary and
+    /// another full window 1ms after it
+    fn across_boundary(algorithm: RateLimitAlgorithm) -> (u32, u32) {
+        let start = Instant::now();
+        let limiter = RateLimiter::starting_at(algorithm, 10, WINDOW, 10, start);
+        let before = burst(&limiter, 10, start + WINDOW - Duration::from_millis(1));
+        let after = burst(&limiter, 10, start + WINDOW);
+        (before, after)
+    }
+
+    #[test]
+    fn test_window_boundary_by_algorithm() {
+        assert_eq!(across_boundary(RateLimitAlgorithm::FixedWindow), (10, 10));
+        assert_eq!(across_boundary(RateLimitAlgorithm::SlidingWindow), (10, 0));
+        assert_eq!(across_boundary(RateLimitAlgorithm::TokenBucket), (10, 0));
+    }
+
+    #[test]
+    fn test_fixed_window_resets_at_boundary() {
+        let start = Instant::now();
+        let limiter = RateLimiter::starting_at(RateLimitAlgorithm::FixedWindow, 3, WINDOW, 3, start);
+        assert_eq!(burst(&limiter, 5, start), 3);
+        assert_eq!(burst(&limiter, 5, start + WINDOW / 2), 0);
+        assert_eq!(burst(&limiter, 5, start + WINDOW * 5), 3);
+    }
+
+    #[test]
+    fn test_sliding_window_weights_previous_window() {
+        let start = Instant::now();
+        let limiter = RateLimiter::starting_at(RateLimitAlgorithm::SlidingWindow, 10, WINDOW, 10, start);
+        assert_eq!(burst(&limiter, 10, start), 10);
+        // Halfway into the next window half of the previous one still counts
+        assert_eq!(burst(&limiter, 10, start + WINDOW + WINDOW / 2), 5);
+        // Two windows later the old requests no longer count at all
+        assert_eq!(burst(&limiter, 10, start + WINDOW * 3), 10);
+    }
+
+    #[test]
+    fn test_token_bucket_refills_gradually() {
+        let start = Instant::now();
+        let limiter = RateLimiter::starting_at(RateLimitAlgorithm::TokenBucket, 60, WINDOW, 5, start);
+        // Bursts are capped at the bucket size, not the per-window rate
+        assert_eq!(burst(&limiter, 60, start), 5);
+        // One token per second at 60 requests per minute
+        assert_eq!(burst(&limiter, 60, start + Duration::from_secs(3)), 3);
+        assert_eq!(burst(&limiter, 60, start + Duration::from_secs(600)), 5);
+    }
+
+    #[test]
+    fn test_token_bucket_refills_at_rate_up_to_burst() {
+        let window = Duration::from_secs(1);
+        let start = Instant::now();
+        let limiter = RateLimiter::starting_at(RateLimitAlgorithm::TokenBucket, 4, window, 2, start);
+
+        assert_eq!(burst(&limiter, 5, start), 2);
+        assert_eq!(burst(&limiter, 5, start + Duration::from_millis(250)), 1);
+        assert_eq!(burst(&limiter, 5, start + Duration::from_secs(10)), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "burst size must be > 0")]
+    fn test_zero_burst_is_rejected() {
+        RateLimiter::new(RateLimitAlgorithm::TokenBucket, 10, WINDOW, 0);
+    }
+}
@@ -0,0 +1,230 @@
+//! Alerts when cumulative Claude spend crosses configured thresholds
+//!
+//! A [`CostAlerter`] compares [`CostTracker::total_cost`] against a list of
+//! USD thresholds. Each threshold fires once when spend first reaches it and
+//! stays quiet until it is re-armed, either by [`CostAlerter::reset`] or by
+//! spend dropping back below it after the tracker is reset.
+
+use super::client::AnthropicClient;
+use super::types::CostTracker;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// A threshold crossing
+#[derive(Debug, Clone, PartialEq)]
+pub struct CostAlert {
+    /// Threshold that was crossed, in USD
+    pub threshold: f64,
+    /// Total spend when the crossing was seen, in USD
+    pub total_cost: f64,
+    /// Requests made so far
+    pub request_count: u64,
+}
+
+impl CostAlert {
+    /// One-line summary used as the notification title
+    pub fn summary(&self) -> String {
+        format!(
+            "Claude spend reached ${:.2}, crossing the ${:.2} alert threshold",
+            self.total_cost, self.threshold
+        )
+    }
+}
+
+/// Destination for cost alerts
+#[async_trait]
+pub trait CostAlertNotifier: Send + Sync {
+    /// Deliver `alert`
+    async fn notify(&self, alert: &CostAlert) -> anyhow::Result<()>;
+}
+
+#[cfg(feature = "slack")]
+#[async_trait]
+impl CostAlertNotifier for crate::slack::SlackClient {
+    async fn notify(&self, alert: &CostAlert) -> anyhow::Result<()> {
+        let message = crate::slack::SlackMessage::new(alert.summary())
+            .header("Claude cost alert")
+            .section(alert.summary())
+            .fields([
+                ("Threshold", format!("${:.2}", alert.threshold)),
+                ("Total cost", format!("${:.2}", alert.total_cost)),
+                ("Requests", alert.request_count.to_string()),
+            ]);
+        self.post_webhook(&message).await?;
+        Ok(())
+    }
+}
+
+#[cfg(feature = "github")]
+#[async_trait]
+impl CostAlertNotifier for crate::github::GitHubClient {
+    async fn notify(&self, alert: &CostAlert) -> anyhow::Result<()> {
+        let request = crate::github::CreateIssueRequest::cost_alert(
+            alert.threshold,
+            alert.total_cost,
+            alert.request_count,
+        );
+        self.create_issue(&request).await?;
+        Ok(())
+    }
+}
+
+/// Fires notifications as spend crosses thresholds
+pub struct CostAlerter {
+    thresholds: Vec<f64>,
+    fired: Mutex<Vec<bool>>,
+    notifiers: Vec<Arc<dyn CostAlertNotifier>>,
+}
+
+impl CostAlerter {
+    /// Create an alerter for the given USD thresholds, with no notifiers
+    pub fn new(thresholds: impl IntoIterator<Item = f64>) -> Self {
+        let mut thresholds: Vec<f64> = thresholds
+            .into_iter()
+            .filter(|threshold| threshold.is_finite() && *threshold > 0.0)
+            .collect();
+        thresholds.sort_by(f64::total_cmp);
+        thresholds.dedup();
+
+        Self {
+            fired: Mutex::new(vec![false; thresholds.len()]),
+            thresholds,
+            notifiers: Vec::new(),
+        }
+    }
+
+    /// Also deliver alerts to `notifier`
+    #[must_use]
+    pub fn with_notifier(mut self, notifier: impl CostAlertNotifier + 'static) -> Self {
+        self.notifiers.push(Arc::new(notifier));
+        self
+    }
+
+    /// Configured thresholds, ascending
+    pub fn thresholds(&self) -> &[f64] {
+        &self.thresholds
+    }
+
+    /// Record the tracker's spend and return the alert to send, if any
+    ///
+    /// When one jump crosses several thresholds only the highest is
+    /// reported, and all of them are marked as fired. Thresholds above the
+    /// current spend are re-armed.
+    pub fn observe(&self, tracker: &CostTracker) -> Option<CostAlert> {
+        let mut fired = self.fired.lock().unwrap();
+        let mut crossed = None;
+
+        for (threshold, fired) in self.thresholds.iter().zip(fired.iter_mut()) {
+            if tracker.total_cost < *threshold {
+                *fired = false;
+            } else if !*fired {
+                *fired = true;
+                crossed = Some(*threshold);
+            }
+        }
+
+        crossed.map(|threshold| CostAlert {
+            threshold,
+            total_cost: tracker.total_cost,
+            request_count: tracker.request_count,
+        })
+    }
+
+    /// Record the tracker's spend and notify if a threshold was crossed
+    ///
+    /// Delivery failures are logged rather than returned so one broken
+    /// destination does not stop the others, and the threshold is not
+    /// retried.
+    pub async fn check(&self, tracker: &CostTracker) -> Option<CostAlert> {
+        let alert = self.observe(tracker)?;
+        info!("{}", alert.summary());
+
+        for notifier in &self.notifiers {
+            if let Err(e) = notifier.notify(&alert).await {
+                warn!("Failed to deliver cost alert: {}", e);
+            }
+        }
+        Some(alert)
+    }
+
+    /// Re-arm every threshold
+    pub fn reset(&self) {
+        self.fired.lock().unwrap().fill(false);
+    }
+
+    /// Check `client`'s spend every `interval` until the task is aborted
+    pub fn watch(self: Arc<Self>, client: AnthropicClient, interval: Duration) -> JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            loop {
+                ticker.tick().await;
+                self.check(&client.get_cost_stats().await).await;
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for CostAlerter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CostAlerter")
+            .field("thresholds", &self.thresholds)
+            .field("notifiers", &self.notifiers.len())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<CostAlert>>);
+
+    #[async_trait]
+    impl CostAlertNotifier for Arc<Recorder> {
+        async fn notify(&self, alert: &CostAlert) -> anyhow::Result<()> {
+            self.0.lock().unwrap().push(alert.clone());
+            Ok(())
+        }
+    }
+
+    fn spent(total_cost: f64) -> CostTracker {
+        CostTracker {
+            total_cost,
+            request_count: 3,
+            ..CostTracker::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_alert_fires_once_per_crossing() {
+        let recorder = Arc::new(Recorder::default());
+        let alerter = CostAlerter::new([10.0]).with_notifier(Arc::clone(&recorder));
+
+        assert!(alerter.check(&spent(9.5)).await.is_none());
+        let alert = alerter.check(&spent(10.25)).await.unwrap();
+        assert_eq!(alert.threshold, 10.0);
+        assert!(alerter.check(&spent(12.0)).await.is_none());
+        assert_eq!(recorder.0.lock().unwrap().len(), 1);
+
+        alerter.reset();
+        assert!(alerter.check(&spent(12.0)).await.is_some());
+        assert_eq!(recorder.0.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_thresholds_rearm_after_tracker_reset() {
+        let alerter = CostAlerter::new([5.0, 1.0, 20.0]);
+        assert_eq!(alerter.thresholds(), [1.0, 5.0, 20.0]);
+
+        // A single jump past two thresholds reports only the highest
+        assert_eq!(alerter.observe(&spent(6.0)).unwrap().threshold, 5.0);
+        assert!(alerter.observe(&spent(7.0)).is_none());
+
+        assert!(alerter.observe(&spent(0.0)).is_none());
+        assert_eq!(alerter.observe(&spent(1.5)).unwrap().threshold, 1.0);
+    }
+}
@@ -0,0 +1,504 @@
+//! Anthropic Message Batches API support
+//!
+//! Submits large sets of message requests for asynchronous processing at a
+//! reduced rate, and streams their results once the batch has ended.
+
+use super::client::{build_headers, parse_model};
+use super::types::*;
+use anyhow::{anyhow, Context, Result};
+use futures::stream::{Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tokio::sync::RwLock;
+use tokio::time::sleep;
+use tracing::{debug, error, info, warn};
+
+/// Default delay between status checks while polling a batch
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Returned when a batch has not ended before the polling timeout
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Batch '{batch_id}' still {status:?} after {timeout:?}")]
+pub struct BatchTimeoutError {
+    /// Batch ID
+    pub batch_id: String,
+    /// Processing status when polling stopped
+    pub status: BatchProcessingStatus,
+    /// Polling timeout that elapsed
+    pub timeout: Duration,
+}
+
+/// Ended batch together with all of its results
+#[derive(Debug, Clone)]
+pub struct BatchOutcome {
+    /// Final batch state
+    pub batch: MessageBatch,
+    /// Results in the order returned by the API
+    pub results: Vec<BatchResult>,
+}
+
+impl BatchOutcome {
+    /// Whether some requests were canceled or expired before being processed
+    pub fn is_partial(&self) -> bool {
+        self.batch.request_counts.canceled > 0 || self.batch.request_counts.expired > 0
+    }
+
+    /// Messages from requests that succeeded, keyed by custom ID
+    pub fn succeeded(&self) -> impl Iterator<Item = (&str, &MessageResponse)> {
+        self.results.iter().filter_map(|result| match &result.result {
+            BatchResultOutcome::Succeeded { message } => Some((result.custom_id.as_str(), message)),
+            _ => None,
+        })
+    }
+}
+
+/// Client for the Message Batches API
+pub struct BatchClient {
+    /// Configuration
+    config: Arc<RwLock<AnthropicConfig>>,
+    /// HTTP client
+    client: reqwest::Client,
+    /// Cost tracker
+    cost_tracker: Arc<RwLock<CostTracker>>,
+}
+
+impl BatchClient {
+    /// Create a new batch client
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - API configuration
+    /// * `client` - HTTP client to use
+    /// * `cost_tracker` - Cost tracker to update as results are read
+    pub fn new(
+        config: Arc<RwLock<AnthropicConfig>>,
+        client: reqwest::Client,
+        cost_tracker: Arc<RwLock<CostTracker>>,
+    ) -> Self {
+        Self {
+            config,
+            client,
+            cost_tracker,
+        }
+    }
+
+    /// Submit a batch of message requests
+    ///
+    /// # Arguments
+    ///
+    /// * `requests` - Requests to process, each with a unique custom ID
+    ///
+    /// # Returns
+    ///
+    /// Returns the newly created batch
+    pub async fn create_batch(&self, mut requests: Vec<BatchRequest>) -> Result<MessageBatch> {
+        if requests.is_empty() {
+            return Err(anyhow!("Batch must contain at least one request"));
+        }
+
+        // Batched requests cannot stream
+        for request in &mut requests {
+            request.params.stream = false;
+        }
+
+        let url = self.url("").await;
+        let body = serde_json::json!({ "requests": requests });
+        let batch: MessageBatch = self.send_json(self.client.post(&url).json(&body)).await?;
+
+        info!("Created message batch {} with {} requests", batch.id, requests.len());
+        Ok(batch)
+    }
+
+    /// Get the current state of a batch
+    pub async fn get_batch(&self, batch_id: &str) -> Result<MessageBatch> {
+        let url = self.url(&format!("/{}", batch_id)).await;
+        self.send_json(self.client.get(&url)).await
+    }
+
+    /// List batches, most recent first
+    ///
+    /// # Arguments
+    ///
+    /// * `limit` - Maximum number of batches to return
+    /// * `after_id` - Cursor from a previous page's `last_id`
+    pub async fn list_batches(&self, limit: Option<u32>, after_id: Option<&str>) -> Result<BatchList> {
+        let url = self.url("").await;
+        let mut request = self.client.get(&url);
+        if let Some(limit) = limit {
+            request = request.query(&[("limit", limit.to_string())]);
+        }
+        if let Some(after_id) = after_id {
+            request = request.query(&[("after_id", after_id)]);
+        }
+        self.send_json(request).await
+    }
+
+    /// Request cancellation of a batch
+    ///
+    /// Requests already processed keep their results; the rest end as canceled.
+    pub async fn cancel_batch(&self, batch_id: &str) -> Result<MessageBatch> {
+        let url = self.url(&format!("/{}/cancel", batch_id)).await;
+        let batch: MessageBatch = self.send_json(self.client.post(&url)).await?;
+        info!("Requested cancellation of message batch {}", batch.id);
+        Ok(batch)
+    }
+
+    /// Stream the results of an ended batch
+    ///
+    /// Results are read line by line from the JSONL results file. Usage from
+    /// each succeeded request is recorded in the cost tracker at batch rates.
+    pub async fn results(
+        &self,
+        batch_id: &str,
+    ) -> Result<Pin<Box<dyn Stream<Item = Result<BatchResult>> + Send>>> {
+        let url = self.url(&format!("/{}/results", batch_id)).await;
+        let response = self.send(self.client.get(&url)).await?;
+
+        debug!("Streaming results for message batch {}", batch_id);
+
+        let cost_tracker = Arc::clone(&self.cost_tracker);
+        let stream = jsonl_lines(response).then(move |line| {
+            let cost_tracker = Arc::clone(&cost_tracker);
+            async move {
+                let result: BatchResult =
+                    serde_json::from_str(&line?).context("Failed to parse batch result")?;
+
+                if let BatchResultOutcome::Succeeded { message } = &result.result {
+                    match parse_model(&message.model) {
+                        Ok(model) => cost_tracker
+                            .write()
+                            .await
+                            .record_batch_usage(&message.usage, model),
+                        Err(e) => warn!("Not tracking cost for {}: {}", result.custom_id, e),
+                    }
+                }
+
+                Ok(result)
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Wait for a batch to end and collect its results
+    ///
+    /// Polls every `poll_interval` until the batch has ended. A batch that was
+    /// canceled still ends, so the outcome then holds the results of the
+    /// requests processed before cancellation (see [`BatchOutcome::is_partial`]).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BatchTimeoutError`] if the batch has not ended within
+    /// `timeout`. The batch keeps running; call [`Self::cancel_batch`] to stop it.
+    pub async fn poll_until_complete(
+        &self,
+        batch_id: &str,
+        poll_interval: Duration,
+        timeout: Duration,
+    ) -> Result<BatchOutcome> {
+        let started = Instant::now();
+
+        let batch = loop {
+            let batch = self.get_batch(batch_id).await?;
+            if batch.is_ended() {
+                break batch;
+            }
+
+            let elapsed = started.elapsed();
+            if elapsed >= timeout {
+                return Err(anyhow!(BatchTimeoutError {
+                    batch_id: batch_id.to_string(),
+                    status: batch.processing_status,
+                    timeout,
+                }));
+            }
+
+            debug!(
+                "Message batch {} {:?}, {} requests processing",
+                batch_id, batch.processing_status, batch.request_counts.processing
+            );
+            sleep(poll_interval.min(timeout.saturating_sub(elapsed))).await;
+        };
+
+        let mut results = Vec::new();
+        let mut stream = self.results(batch_id).await?;
+        while let Some(result) = stream.next().await {
+            results.push(result?);
+        }
+
+        info!(
+            "Message batch {} ended: {} succeeded, {} errored, {} canceled, {} expired",
+            batch_id,
+            batch.request_counts.succeeded,
+            batch.request_counts.errored,
+            batch.request_counts.canceled,
+            batch.request_counts.expired
+        );
+
+        Ok(BatchOutcome { batch, results })
+    }
+
+    /// Build a Message Batches endpoint URL
+    async fn url(&self, suffix: &str) -> String {
+        let config = self.config.read().await;
+        format!("{}/v1/messages/batches{}", config.base_url, suffix)
+    }
+
+    /// Send a request and parse the JSON response
+    async fn send_json<T: DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        self.send(request)
+            .await?
+            .json::<T>()
+            .await
+            .context("Failed to parse response JSON")
+    }
+
+    /// Send a request, turning error statuses into errors
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let headers = build_headers(&*self.config.read().await)?;
+        let response = request
+            .headers(headers)
+            .send()
+            .await
+            .context("Failed to send batch request")?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(response);
+        }
+
+        let error_text = response.text().await.unwrap_or_default();
+        let error_msg = if let Ok(err) = serde_json::from_str::<ApiError>(&error_text) {
+            format!("Anthropic API error ({}): {}", err.error_type, err.message)
+        } else {
+            format!("Anthropic API error ({}): {}", status, error_text)
+        };
+
+        error!("{}", error_msg);
+        Err(anyhow!(error_msg))
+    }
+}
+
+/// Split a response body into non-empty lines as chunks arrive
+fn jsonl_lines(response: reqwest::Response) -> impl Stream<Item = Result<String>> + Send {
+    futures::stream::unfold(
+        (Some(response), Vec::new()),
+        |(mut response, mut buffer): (Option<reqwest::Response>, Vec<u8>)| async move {
+            loop {
+                if let Some(pos) = buffer.iter().position(|&b| b == b'\n') {
+                    let line: Vec<u8> = buffer.drain(..=pos).collect();
+                    match String::from_utf8(line) {
+                        Ok(line) if line.trim().is_empty() => continue,
+                        Ok(line) => return Some((Ok(line), (response, buffer))),
+                        Err(e) => return Some((Err(anyhow!("Invalid UTF-8: {}", e)), (response, buffer))),
+                    }
+                }
+
+                let Some(resp) = response.as_mut() else {
+                    // Body finished; emit a final line without a trailing newline
+                    if buffer.iter().all(u8::is_ascii_whitespace) {
+                        return None;
+                    }
+                    buffer.push(b'\n');
+                    continue;
+                };
+
+                match resp.chunk().await {
+                    Ok(Some(chunk)) => buffer.extend_from_slice(&chunk),
+                    Ok(None) => response = None,
+                    Err(e) => {
+                        buffer.clear();
+                        return Some((Err(anyhow!("Stream error: {}", e)), (None, buffer)));
+                    }
+                }
+            }
+        },
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn batch_client(server: &MockServer) -> (BatchClient, Arc<RwLock<CostTracker>>) {
+        let config = AnthropicConfig {
+            api_key: "test-key".to_string(),
+            base_url: server.uri(),
+            timeout_secs: 60,
+            retry: Default::default(),
+            rate_limit_per_minute: 50,
+            api_version: "2023-06-01".to_string(),
+            pricing: Default::default(),
+            cost_store: None,
+        };
+        let cost_tracker = Arc::new(RwLock::new(CostTracker::new()));
+        let client = BatchClient::new(
+            Arc::new(RwLock::new(config)),
+            reqwest::Client::new(),
+            Arc::clone(&cost_tracker),
+        );
+        (client, cost_tracker)
+    }
+
+    fn batch_json(status: &str, counts: serde_json::Value) -> serde_json::Value {
+        serde_json::json!({
+            "id": "msgbatch_1",
+            "type": "message_batch",
+            "processing_status": status,
+            "request_counts": counts,
+            "created_at": "2024-10-01T00:00:00Z",
+            "expires_at": "2024-10-02T00:00:00Z",
+            "ended_at": null,
+            "cancel_initiated_at": null,
+            "results_url": null
+        })
+    }
+
+    fn request(custom_id: &str) -> BatchRequest {
+        BatchRequest {
+            custom_id: custom_id.to_string(),
+            params: MessageRequest {
+                model: ClaudeModel::Claude3Haiku.as_str().to_string(),
+                messages: vec![Message {
+                    role: Role::User,
+                    content: MessageContent::Text("Hello".to_string()),
+                }],
+                max_tokens: 100,
+                system: None,
+                temperature: None,
+                top_p: None,
+                top_k: None,
+                stop_sequences: None,
+                stream: true,
+                metadata: None,
+                tools: None,
+                tool_choice: None,
+            },
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_list_batches() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/batches"))
+            .and(body_partial_json(serde_json::json!({
+                "requests": [{"custom_id": "eval-1", "params": {"stream": false}}]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(batch_json("in_progress", serde_json::json!({"processing": 1}))),
+            )
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches"))
+            .and(query_param("limit", "10"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "data": [batch_json("in_progress", serde_json::json!({"processing": 1}))],
+                "has_more": false,
+                "first_id": "msgbatch_1",
+                "last_id": "msgbatch_1"
+            })))
+            .mount(&server)
+            .await;
+
+        let (client, _) = batch_client(&server);
+        let batch = client.create_batch(vec![request("eval-1")]).await.unwrap();
+        assert_eq!(batch.processing_status, BatchProcessingStatus::InProgress);
+        assert_eq!(batch.request_counts.processing, 1);
+
+        let page = client.list_batches(Some(10), None).await.unwrap();
+        assert_eq!(page.data.len(), 1);
+        assert!(!page.has_more);
+
+        assert!(client.create_batch(Vec::new()).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_complete_collects_partial_results_and_cost() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(batch_json("canceling", serde_json::json!({"processing": 1}))),
+            )
+            .up_to_n_times(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(batch_json(
+                "ended",
+                serde_json::json!({"succeeded": 1, "canceled": 1}),
+            )))
+            .mount(&server)
+            .await;
+
+        let succeeded = serde_json::json!({
+            "custom_id": "eval-1",
+            "result": {"type": "succeeded", "message": {
+                "id": "msg_1",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-3-haiku-20240307",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 1_000_000, "output_tokens": 1_000_000}
+            }}
+        });
+        let canceled = serde_json::json!({"custom_id": "eval-2", "result": {"type": "canceled"}});
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1/results"))
+            .respond_with(ResponseTemplate::new(200).set_body_string(format!("{}\n{}", succeeded, canceled)))
+            .mount(&server)
+            .await;
+
+        let (client, cost_tracker) = batch_client(&server);
+        let outcome = client
+            .poll_until_complete("msgbatch_1", Duration::from_millis(10), Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        assert!(outcome.is_partial());
+        assert_eq!(outcome.results.len(), 2);
+        assert!(matches!(outcome.results[1].result, BatchResultOutcome::Canceled));
+        let succeeded: Vec<_> = outcome.succeeded().map(|(id, _)| id).collect();
+        assert_eq!(succeeded, vec!["eval-1"]);
+
+        // Haiku at $0.25 in / $1.25 out per MTok, halved for batch processing
+        let tracker = cost_tracker.read().await;
+        assert_eq!(tracker.request_count, 1);
+        assert!((tracker.total_cost - 0.75).abs() < 1e-9);
+        assert!((tracker.total_batch_cost - 0.75).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_poll_until_complete_times_out() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/v1/messages/batches/msgbatch_1"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(batch_json("in_progress", serde_json::json!({"processing": 3}))),
+            )
+            .mount(&server)
+            .await;
+
+        let (client, _) = batch_client(&server);
+        let err = client
+            .poll_until_complete("msgbatch_1", Duration::from_millis(10), Duration::from_millis(30))
+            .await
+            .unwrap_err();
+
+        let timeout = err.downcast_ref::<BatchTimeoutError>().unwrap();
+        assert_eq!(timeout.status, BatchProcessingStatus::InProgress);
+    }
+}
@@ -3,16 +3,29 @@
 //! Production-ready client for Claude API with comprehensive error handling,
 //! rate limiting, and cost tracking.
 
+use super::batch::BatchClient;
+use super::cost_store::CostStore;
+use super::streaming::{StreamHandler, TextStream};
+use super::tokens::{BudgetExceededError, TokenBudget, TokenCounter};
 use super::types::*;
+use crate::request_context::{current_request_id, insert_request_id_header};
+use crate::resilience::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+use crate::retry::{is_retryable_status, AttemptError};
 use anyhow::{anyhow, Context, Result};
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use governor::{Quota, RateLimiter};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use std::num::NonZeroU32;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::RwLock;
-use tokio::time::sleep;
-use tracing::{debug, error, info, warn};
+use tracing::{debug, error, field, info, instrument, warn, Span};
+
+/// Image media types accepted by the Messages API
+const SUPPORTED_IMAGE_MEDIA_TYPES: [&str; 4] = ["image/jpeg", "image/png", "image/gif", "image/webp"];
+
+/// Maximum decoded size of a base64 image
+const MAX_IMAGE_BYTES: usize = 5 * 1024 * 1024;
 
 /// Anthropic API client
 #[derive(Clone)]
@@ -25,8 +38,22 @@ pub struct AnthropicClient {
     rate_limiter: Arc<RateLimiter<governor::state::direct::NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock>>,
     /// Cost tracker
     cost_tracker: Arc<RwLock<CostTracker>>,
+    /// Stops requests after repeated API failures
+    circuit_breaker: Arc<CircuitBreaker>,
+    /// Caps total tokens spent across requests
+    budget: Option<SharedBudget>,
+    /// Rate limits reported by the latest response
+    rate_limit: SharedRateLimit,
+    /// Saves the cost tracker across restarts, when configured
+    cost_store: Option<Arc<CostStore>>,
 }
 
+/// Token budget shared between a client and its stream handlers
+pub(super) type SharedBudget = Arc<RwLock<TokenBudget>>;
+
+/// Latest rate limits shared between a client and its stream handlers
+pub(super) type SharedRateLimit = Arc<RwLock<Option<RateLimitInfo>>>;
+
 impl AnthropicClient {
     /// Create a new Anthropic client
     ///
@@ -52,16 +79,96 @@ impl AnthropicClient {
         let quota = Quota::per_minute(rate_limit);
         let rate_limiter = Arc::new(RateLimiter::direct(quota));
 
+        // Pick up the totals saved by a previous run
+        let (cost_store, cost_tracker) = match config.cost_store.clone() {
+            Some(store_config) => {
+                let (store, tracker) = CostStore::open(store_config, config.pricing.clone())?;
+                (Some(Arc::new(store)), tracker)
+            }
+            None => (None, CostTracker::with_pricing(config.pricing.clone())),
+        };
+        let cost_tracker = Arc::new(RwLock::new(cost_tracker));
+        if let Some(store) = &cost_store {
+            Arc::clone(store).spawn_autosave(Arc::downgrade(&cost_tracker));
+        }
+
         info!("Initialized Anthropic client");
 
         Ok(Self {
             client,
             config: Arc::new(RwLock::new(config)),
             rate_limiter,
-            cost_tracker: Arc::new(RwLock::new(CostTracker::new())),
+            cost_tracker,
+            circuit_breaker: Arc::new(CircuitBreaker::new(
+                "anthropic",
+                CircuitBreakerConfig::default(),
+            )),
+            budget: None,
+            rate_limit: Arc::new(RwLock::new(None)),
+            cost_store,
         })
     }
 
+    /// Replace the circuit breaker guarding API requests
+    ///
+    /// Use this to tune thresholds or to attach a metrics observer.
+    pub fn with_circuit_breaker(mut self, circuit_breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Arc::new(circuit_breaker);
+        self
+    }
+
+    /// Current state of the circuit breaker guarding API requests
+    pub fn circuit_state(&self) -> CircuitState {
+        self.circuit_breaker.state()
+    }
+
+    /// Enforce a token budget across all messages sent by this client
+    ///
+    /// Requests whose estimated tokens (input plus `max_tokens`) do not fit
+    /// are rejected with [`BudgetExceededError`] before being sent; actual
+    /// usage is charged once the response arrives.
+    pub fn with_token_budget(mut self, budget: TokenBudget) -> Self {
+        self.budget = Some(Arc::new(RwLock::new(budget)));
+        self
+    }
+
+    /// Tokens left in the budget, if one is set
+    pub async fn budget_remaining(&self) -> Option<u32> {
+        match &self.budget {
+            Some(budget) => Some(budget.read().await.remaining()),
+            None => None,
+        }
+    }
+
+    /// Rate limits reported by the latest response, if it carried any
+    pub async fn rate_limit_info(&self) -> Option<RateLimitInfo> {
+        self.rate_limit.read().await.clone()
+    }
+
+    /// Stream handler sharing this client's configuration, cost tracker,
+    /// rate limits and token budget
+    pub fn stream_handler(&self) -> StreamHandler {
+        let handler = StreamHandler::new(
+            Arc::clone(&self.config),
+            self.client.clone(),
+            Arc::clone(&self.cost_tracker),
+        )
+        .with_rate_limit(Arc::clone(&self.rate_limit));
+        match &self.budget {
+            Some(budget) => handler.with_token_budget(Arc::clone(budget)),
+            None => handler,
+        }
+    }
+
+    /// Message Batches client sharing this client's configuration and cost tracker
+    pub fn batches(&self) -> BatchClient {
+        BatchClient::new(
+            Arc::clone(&self.config),
+            self.client.clone(),
+            Arc::clone(&self.cost_tracker),
+        )
+    }
+
     /// Send a message to Claude
     ///
     /// # Arguments
@@ -71,6 +178,22 @@ impl AnthropicClient {
     /// # Returns
     ///
     /// Returns the message response
+    #[instrument(
+        name = "anthropic.request",
+        skip_all,
+        fields(
+            model = %request.model,
+            request_id = current_request_id(),
+            input_tokens = field::Empty,
+            output_tokens = field::Empty,
+            cache_creation_tokens = field::Empty,
+            cache_read_tokens = field::Empty,
+            cost_usd = field::Empty,
+            status = field::Empty,
+            latency_ms = field::Empty,
+            retry_count = field::Empty,
+        )
+    )]
     pub async fn send_message(&self, mut request: MessageRequest) -> Result<MessageResponse> {
         // Ensure streaming is disabled for non-streaming requests
         request.stream = false;
@@ -80,13 +203,36 @@ impl AnthropicClient {
             request.model, request.max_tokens
         );
 
-        let response: MessageResponse = self.execute_request(&request).await?;
+        self.validate_request(&request)?;
+        let reserved = reserve_budget(self.budget.as_ref(), &request).await?;
+
+        let response: MessageResponse = match self.execute_request("/v1/messages", &request).await {
+            Ok(response) => response,
+            Err(e) => {
+                charge_budget(self.budget.as_ref(), reserved, 0).await;
+                return Err(e);
+            }
+        };
+
+        charge_budget(
+            self.budget.as_ref(),
+            reserved,
+            response.usage.total_input_tokens() + response.usage.output_tokens,
+        )
+        .await;
 
         // Track costs
-        let model = self.parse_model(&response.model)?;
+        let model = parse_model(&response.model)?;
         let mut tracker = self.cost_tracker.write().await;
         tracker.record_usage(&response.usage, model);
 
+        let span = Span::current();
+        span.record("input_tokens", response.usage.input_tokens);
+        span.record("output_tokens", response.usage.output_tokens);
+        span.record("cache_creation_tokens", response.usage.cache_creation_input_tokens);
+        span.record("cache_read_tokens", response.usage.cache_read_input_tokens);
+        span.record("cost_usd", response.usage.calculate_cost_with(model, &tracker.pricing));
+
         info!(
             "Message completed. Tokens: {} in, {} out. Stop reason: {:?}",
             response.usage.input_tokens,
@@ -114,6 +260,23 @@ impl AnthropicClient {
         prompt: &str,
         max_tokens: u32,
     ) -> Result<String> {
+        Ok(self.complete_with_usage(model, prompt, max_tokens).await?.0)
+    }
+
+    /// Create a simple text message, also returning its token usage
+    ///
+    /// Use this when the cost of an individual call must be reported, since
+    /// the shared cost tracker aggregates usage across concurrent calls.
+    ///
+    /// # Returns
+    ///
+    /// Returns the assistant's response text and the call's usage
+    pub async fn complete_with_usage(
+        &self,
+        model: ClaudeModel,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<(String, Usage)> {
         let request = MessageRequest {
             model: model.as_str().to_string(),
             messages: vec![Message {
@@ -128,6 +291,8 @@ impl AnthropicClient {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let response = self.send_message(request).await?;
@@ -137,13 +302,55 @@ impl AnthropicClient {
             .content
             .iter()
             .filter_map(|block| match block {
-                ContentBlock::Text { text } => Some(text.as_str()),
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
                 _ => None,
             })
             .collect::<Vec<_>>()
             .join("\n");
 
-        Ok(text)
+        Ok((text, response.usage))
+    }
+
+    /// Stream the response to a simple text message
+    ///
+    /// Yields text deltas as they are generated:
+    ///
+    /// ```ignore
+    /// let mut stream = client.complete_stream(ClaudeModel::Claude3Haiku, "Hello", 256).await?;
+    /// while let Some(chunk) = stream.next().await {
+    ///     print!("{}", chunk?);
+    /// }
+    /// ```
+    ///
+    /// Usage is added to the cost tracker once the message finishes, and
+    /// dropping the stream early cancels the request. See
+    /// [`StreamHandler::stream_text`].
+    pub async fn complete_stream(
+        &self,
+        model: ClaudeModel,
+        prompt: &str,
+        max_tokens: u32,
+    ) -> Result<TextStream> {
+        let request = MessageRequest {
+            model: model.as_str().to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text(prompt.to_string()),
+            }],
+            max_tokens,
+            system: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: true,
+            metadata: None,
+            tools: None,
+            tool_choice: None,
+        };
+
+        self.rate_limiter.until_ready().await;
+        self.stream_handler().stream_text(request).await
     }
 
     /// Create a message with system prompt
@@ -179,6 +386,8 @@ impl AnthropicClient {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let response = self.send_message(request).await?;
@@ -187,7 +396,7 @@ impl AnthropicClient {
             .content
             .iter()
             .filter_map(|block| match block {
-                ContentBlock::Text { text } => Some(text.as_str()),
+                ContentBlock::Text { text, .. } => Some(text.as_str()),
                 _ => None,
             })
             .collect::<Vec<_>>()
@@ -204,11 +413,37 @@ impl AnthropicClient {
     ///
     /// # Returns
     ///
-    /// Returns estimated token count
+    /// Returns estimated token count. Use [`Self::count_request_tokens`] for
+    /// the count the API bills.
     pub fn count_tokens(&self, text: &str) -> u32 {
-        // Simple estimation: ~4 characters per token
-        // For production, use the official token counting API
-        (text.len() as f32 / 4.0).ceil() as u32
+        TokenCounter::new().count_text(text)
+    }
+
+    /// Count the input tokens of a request with the token counting API
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Request to count; `max_tokens` and sampling options
+    ///   are not sent
+    ///
+    /// # Returns
+    ///
+    /// Returns the input tokens the API would bill for the request
+    pub async fn count_request_tokens(&self, request: &MessageRequest) -> Result<u32> {
+        self.validate_request(request)?;
+
+        let body = CountTokensRequest {
+            model: &request.model,
+            messages: &request.messages,
+            system: request.system.as_deref(),
+            tools: request.tools.as_deref(),
+            tool_choice: request.tool_choice.as_ref(),
+        };
+        let response: CountTokensResponse = self
+            .execute_request("/v1/messages/count_tokens", &body)
+            .await?;
+
+        Ok(response.input_tokens)
     }
 
     /// Validate a request before sending
@@ -219,14 +454,15 @@ impl AnthropicClient {
     ///
     /// # Returns
     ///
-    /// Returns Ok(()) if valid, Err otherwise
+    /// Returns Ok(()) if valid, Err otherwise. Errors about message content
+    /// start with the offending field's path, e.g. `messages[1].content[0]`.
     pub fn validate_request(&self, request: &MessageRequest) -> Result<()> {
         // Check max_tokens
         if request.max_tokens == 0 {
             return Err(anyhow!("max_tokens must be greater than 0"));
         }
 
-        let model = self.parse_model(&request.model)?;
+        let model = parse_model(&request.model)?;
         if request.max_tokens > model.max_tokens() {
             return Err(anyhow!(
                 "max_tokens {} exceeds model limit {}",
@@ -254,6 +490,11 @@ impl AnthropicClient {
             }
         }
 
+        // Check images
+        for (index, message) in request.messages.iter().enumerate() {
+            validate_images(&message.content, &format!("messages[{}].content", index))?;
+        }
+
         Ok(())
     }
 
@@ -267,64 +508,90 @@ impl AnthropicClient {
         self.cost_tracker.write().await.reset();
     }
 
-    /// Execute an API request with retry logic
-    async fn execute_request<T: serde::de::DeserializeOwned>(
-        &self,
-        request: &MessageRequest,
-    ) -> Result<T> {
-        // Validate request
-        self.validate_request(request)?;
+    /// Save cost tracking to the configured store, if any
+    ///
+    /// Call this on shutdown; autosave only runs every
+    /// `autosave_interval_secs`.
+    pub async fn flush_cost_stats(&self) -> Result<()> {
+        match &self.cost_store {
+            Some(store) => store.flush(&self.cost_tracker).await,
+            None => Ok(()),
+        }
+    }
 
+    /// Execute an API request with retry logic
+    ///
+    /// `path` is relative to the configured base URL, e.g. `/v1/messages`.
+    async fn execute_request<B, T>(&self, path: &str, request: &B) -> Result<T>
+    where
+        B: serde::Serialize + ?Sized,
+        T: serde::de::DeserializeOwned,
+    {
         // Wait for rate limiter
         self.rate_limiter.until_ready().await;
 
         let config = self.config.read().await;
-        let url = format!("{}/v1/messages", config.base_url);
-        let max_retries = config.max_retries;
+        let url = format!("{}{}", config.base_url, path);
+        let span = Span::current();
+        let started = Instant::now();
 
-        let mut last_error = None;
+        config
+            .retry
+            .retry(|attempt| {
+                let (config, url, span) = (&config, &url, &span);
+                async move {
+                    if attempt > 0 {
+                        span.record("retry_count", attempt);
+                    }
 
-        for attempt in 0..=max_retries {
-            if attempt > 0 {
-                let delay = Duration::from_secs(2u64.pow(attempt));
-                debug!("Retrying after {:?} (attempt {})", delay, attempt);
-                sleep(delay).await;
-            }
+                    // Stop instead of hammering an endpoint that keeps failing
+                    if let Err(open) = self.circuit_breaker.try_acquire() {
+                        warn!("{}", open);
+                        return Err(AttemptError::terminal(open));
+                    }
 
-            let headers = self.build_headers(&config)?;
-            let http_request = self
-                .client
-                .post(&url)
-                .headers(headers)
-                .json(request);
+                    let max_wait = Duration::from_millis(config.retry.max_delay_ms);
+                    wait_for_rate_limit_reset(Some(&self.rate_limit), max_wait).await;
 
-            match http_request.send().await {
-                Ok(response) => {
-                    let status = response.status();
+                    let headers = build_headers(config).map_err(AttemptError::terminal)?;
+                    let result = self
+                        .client
+                        .post(url)
+                        .headers(headers)
+                        .json(request)
+                        .send()
+                        .await;
+                    span.record("latency_ms", started.elapsed().as_millis() as u64);
 
-                    // Handle rate limiting
-                    if status.as_u16() == 429 {
-                        warn!("Rate limited by Anthropic API");
-                        last_error = Some(anyhow!("Rate limited"));
-                        continue;
-                    }
+                    let response = match result {
+                        Ok(response) => response,
+                        Err(e) => {
+                            warn!("Request failed: {}", e);
+                            self.circuit_breaker.record_failure();
+                            return Err(AttemptError::retryable(e));
+                        }
+                    };
 
-                    // Handle overloaded
-                    if status.as_u16() == 529 {
-                        warn!("Anthropic API overloaded");
-                        last_error = Some(anyhow!("API overloaded"));
-                        continue;
+                    let status = response.status();
+                    span.record("status", status.as_u16());
+                    record_rate_limit(Some(&self.rate_limit), response.headers()).await;
+
+                    // Rate limiting, overload and server errors count against the breaker
+                    if is_retryable_status(status) {
+                        self.circuit_breaker.record_failure();
+                    } else {
+                        self.circuit_breaker.record_success();
                     }
 
-                    // Handle success
-                    if response.status().is_success() {
+                    if status.is_success() {
                         return response
                             .json::<T>()
                             .await
-                            .context("Failed to parse response JSON");
+                            .context("Failed to parse response JSON")
+                            .map_err(AttemptError::terminal);
                     }
 
-                    // Handle error responses
+                    let headers = response.headers().clone();
                     let error_text = response.text().await.unwrap_or_default();
                     let error_msg = if let Ok(err) = serde_json::from_str::<ApiError>(&error_text) {
                         format!("Anthropic API error ({}): {}", err.error_type, err.message)
@@ -332,85 +599,298 @@ impl AnthropicClient {
                         format!("Anthropic API error ({}): {}", status, error_text)
                     };
 
-                    error!("{}", error_msg);
-                    last_error = Some(anyhow!(error_msg));
-
-                    // Don't retry on client errors (except rate limit)
-                    if status.is_client_error() {
-                        break;
+                    if status.as_u16() == 429 {
+                        warn!("Rate limited by Anthropic API");
+                    } else if status.as_u16() == 529 {
+                        warn!("Anthropic API overloaded");
+                    } else {
+                        error!("{}", error_msg);
                     }
-                }
-                Err(e) => {
-                    warn!("Request failed: {}", e);
-                    last_error = Some(anyhow!(e));
-                }
-            }
-        }
 
-        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+                    Err(AttemptError::from_response(status, &headers, anyhow!(error_msg)))
+                }
+            })
+            .await
     }
+}
 
-    /// Build request headers
-    fn build_headers(&self, config: &AnthropicConfig) -> Result<HeaderMap> {
-        let mut headers = HeaderMap::new();
+/// Body of a token counting request
+#[derive(serde::Serialize)]
+struct CountTokensRequest<'a> {
+    model: &'a str,
+    messages: &'a [Message],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    system: Option<&'a str>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<&'a [ToolDefinition]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<&'a ToolChoice>,
+}
 
-        headers.insert(
-            CONTENT_TYPE,
-            HeaderValue::from_static("application/json"),
-        );
+/// Response of a token counting request
+#[derive(serde::Deserialize)]
+struct CountTokensResponse {
+    input_tokens: u32,
+}
 
-        headers.insert(
-            "x-api-key",
-            HeaderValue::from_str(&config.api_key)
-                .context("Invalid API key")?,
-        );
+/// Reserve the estimated tokens of `request`, or reject it if they do not
+/// fit in the budget
+///
+/// The check and the reservation happen under one lock, so concurrent
+/// requests cannot all fit in the same remaining tokens. Returns the tokens
+/// reserved, to be settled with [`charge_budget`].
+pub(super) async fn reserve_budget(
+    budget: Option<&SharedBudget>,
+    request: &MessageRequest,
+) -> Result<u32> {
+    let Some(budget) = budget else {
+        return Ok(0);
+    };
 
-        headers.insert(
-            "anthropic-version",
-            HeaderValue::from_str(&config.api_version)
-                .context("Invalid API version")?,
+    let estimated = TokenCounter::new().count_request(request);
+    let mut budget = budget.write().await;
+    if budget.allocate(estimated).is_err() {
+        warn!(
+            "Rejecting request: ~{} tokens exceeds remaining budget of {}",
+            estimated,
+            budget.remaining()
         );
+        return Err(anyhow!(BudgetExceededError {
+            requested: estimated,
+            remaining: budget.remaining(),
+        }));
+    }
 
-        Ok(headers)
-    }
-
-    /// Parse model string to enum
-    fn parse_model(&self, model_str: &str) -> Result<ClaudeModel> {
-        match model_str {
-            "claude-3-5-sonnet-20241022" => Ok(ClaudeModel::Claude35Sonnet),
-            "claude-3-opus-20240229" => Ok(ClaudeModel::Claude3Opus),
-            "claude-3-sonnet-20240229" => Ok(ClaudeModel::Claude3Sonnet),
-            "claude-3-haiku-20240307" => Ok(ClaudeModel::Claude3Haiku),
-            _ => {
-                // Try to match partial model names
-                if model_str.contains("sonnet") && model_str.contains("3-5") {
-                    Ok(ClaudeModel::Claude35Sonnet)
-                } else if model_str.contains("opus") {
-                    Ok(ClaudeModel::Claude3Opus)
-                } else if model_str.contains("sonnet") {
-                    Ok(ClaudeModel::Claude3Sonnet)
-                } else if model_str.contains("haiku") {
-                    Ok(ClaudeModel::Claude3Haiku)
-                } else {
-                    Err(anyhow!("Unknown model: {}", model_str))
-                }
+    Ok(estimated)
+}
+
+/// Replace `reserved` tokens with the `tokens` a request actually used
+pub(super) async fn charge_budget(budget: Option<&SharedBudget>, reserved: u32, tokens: u32) {
+    let Some(budget) = budget else {
+        return;
+    };
+
+    let mut budget = budget.write().await;
+    budget.release(reserved);
+    if let Err(e) = budget.allocate(tokens) {
+        // The tokens are already spent; exhaust the budget so the next
+        // request is rejected instead of silently undercounting
+        warn!("{}", e);
+        let remaining = budget.remaining();
+        let _ = budget.allocate(remaining);
+    }
+}
+
+/// Prefix of the rate limit headers on every Messages API response
+const RATE_LIMIT_HEADER_PREFIX: &str = "anthropic-ratelimit-";
+
+/// Read the `anthropic-ratelimit-*` headers of a response
+///
+/// Returns `None` when the response has none of them.
+pub fn parse_rate_limit_headers(headers: &HeaderMap) -> Option<RateLimitInfo> {
+    let header = |name: &str| {
+        headers
+            .get(format!("{}{}", RATE_LIMIT_HEADER_PREFIX, name))?
+            .to_str()
+            .ok()
+            .map(str::trim)
+    };
+    let count = |name: &str| header(name)?.parse().ok();
+    // Round up so a wait until the reset never ends early
+    let reset = |name: &str| {
+        let reset = chrono::DateTime::parse_from_rfc3339(header(name)?).ok()?;
+        Some(reset.timestamp() + i64::from(reset.timestamp_subsec_nanos() > 0))
+    };
+
+    let info = RateLimitInfo {
+        requests_remaining: count("requests-remaining"),
+        requests_limit: count("requests-limit"),
+        tokens_remaining: count("tokens-remaining"),
+        tokens_limit: count("tokens-limit"),
+        reset_at: reset("requests-reset").or_else(|| reset("tokens-reset")),
+    };
+
+    (info != RateLimitInfo::default()).then_some(info)
+}
+
+/// Remember the rate limits reported by a response
+pub(super) async fn record_rate_limit(rate_limit: Option<&SharedRateLimit>, headers: &HeaderMap) {
+    let (Some(rate_limit), Some(info)) = (rate_limit, parse_rate_limit_headers(headers)) else {
+        return;
+    };
+
+    debug!(
+        "Rate limit: {:?}/{:?} requests, {:?}/{:?} tokens remaining",
+        info.requests_remaining, info.requests_limit, info.tokens_remaining, info.tokens_limit
+    );
+    *rate_limit.write().await = Some(info);
+}
+
+/// Pause until the window resets when the last response left no requests
+///
+/// Waiting here avoids spending a request, and a retry, on a certain 429.
+/// The wait is capped at `max_wait` so a bad reset time cannot stall the
+/// caller.
+pub(super) async fn wait_for_rate_limit_reset(
+    rate_limit: Option<&SharedRateLimit>,
+    max_wait: Duration,
+) {
+    let Some(rate_limit) = rate_limit else {
+        return;
+    };
+
+    let reset_at = match &*rate_limit.read().await {
+        Some(RateLimitInfo {
+            requests_remaining: Some(0),
+            reset_at: Some(reset_at),
+            ..
+        }) => *reset_at,
+        _ => return,
+    };
+
+    let wait = u64::try_from(reset_at - chrono::Utc::now().timestamp()).unwrap_or(0);
+    if wait > 0 {
+        let wait = Duration::from_secs(wait).min(max_wait);
+        warn!("Request rate limit exhausted; waiting {:?} for it to reset", wait);
+        tokio::time::sleep(wait).await;
+    }
+}
+
+/// Build request headers
+pub(super) fn build_headers(config: &AnthropicConfig) -> Result<HeaderMap> {
+    let mut headers = HeaderMap::new();
+
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/json"),
+    );
+
+    headers.insert(
+        "x-api-key",
+        HeaderValue::from_str(&config.api_key)
+            .context("Invalid API key")?,
+    );
+
+    headers.insert(
+        "anthropic-version",
+        HeaderValue::from_str(&config.api_version)
+            .context("Invalid API version")?,
+    );
+
+    insert_request_id_header(&mut headers);
+
+    Ok(headers)
+}
+
+/// Validate the image blocks in message content, including inside tool results
+///
+/// `path` is the field path of `content`, used to prefix error messages.
+fn validate_images(content: &MessageContent, path: &str) -> Result<()> {
+    let MessageContent::Parts(parts) = content else {
+        return Ok(());
+    };
+
+    for (index, block) in parts.iter().enumerate() {
+        let path = format!("{}[{}]", path, index);
+        match block {
+            ContentBlock::Image { source } => validate_image_source(source, &path)?,
+            ContentBlock::ToolResult {
+                content: Some(content),
+                ..
+            } => validate_images(content, &format!("{}.content", path))?,
+            _ => {}
+        }
+    }
+
+    Ok(())
+}
+
+/// Validate a single image source
+fn validate_image_source(source: &ImageSource, path: &str) -> Result<()> {
+    match source {
+        ImageSource::Base64 { media_type, data } => {
+            if !SUPPORTED_IMAGE_MEDIA_TYPES.contains(&media_type.as_str()) {
+                return Err(anyhow!(
+                    "{}.source.media_type: unsupported image type '{}' (expected one of {})",
+                    path,
+                    media_type,
+                    SUPPORTED_IMAGE_MEDIA_TYPES.join(", ")
+                ));
+            }
+
+            let bytes = BASE64
+                .decode(data)
+                .map_err(|e| anyhow!("{}.source.data: invalid base64: {}", path, e))?;
+            if bytes.len() > MAX_IMAGE_BYTES {
+                return Err(anyhow!(
+                    "{}.source.data: image is {} bytes, over the {} byte limit",
+                    path,
+                    bytes.len(),
+                    MAX_IMAGE_BYTES
+                ));
             }
         }
+        ImageSource::Url { .. } => {}
     }
+
+    Ok(())
 }
 
+/// Parse model string to enum
+///
+/// Accepts full model ids and short names such as `claude-3-haiku`.
+pub fn parse_model(model_str: &str) -> Result<ClaudeModel> {
+    match model_str {
+        "claude-3-5-sonnet-20241022" => Ok(ClaudeModel::Claude35Sonnet),
+        "claude-3-5-haiku-20241022" => Ok(ClaudeModel::Claude35Haiku),
+        "claude-3-opus-20240229" => Ok(ClaudeModel::Claude3Opus),
+        "claude-3-sonnet-20240229" => Ok(ClaudeModel::Claude3Sonnet),
+        "claude-3-haiku-20240307" => Ok(ClaudeModel::Claude3Haiku),
+        _ => {
+            // Try to match partial model names
+            if model_str.contains("sonnet") && model_str.contains("3-5") {
+                Ok(ClaudeModel::Claude35Sonnet)
+            } else if model_str.contains("haiku") && model_str.contains("3-5") {
+                Ok(ClaudeModel::Claude35Haiku)
+            } else if model_str.contains("opus") {
+                Ok(ClaudeModel::Claude3Opus)
+            } else if model_str.contains("sonnet") {
+                Ok(ClaudeModel::Claude3Sonnet)
+            } else if model_str.contains("haiku") {
+                Ok(ClaudeModel::Claude3Haiku)
+            } else {
+                Err(anyhow!("Unknown model: {}", model_str))
+            }
+        }
+    }
+}
+
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::anthropic::cost_store::CostStoreConfig;
+    use crate::resilience::CircuitOpenError;
+    use crate::retry::RetryPolicy;
+    use std::collections::HashMap;
+    use tracing::field::{Field, Visit};
+    use tracing::span::{Attributes, Id, Record};
+    use tracing::Subscriber;
+    use tracing_subscriber::layer::{Context, Layer, SubscriberExt};
+    use tracing_subscriber::registry::LookupSpan;
+    use wiremock::matchers::{body_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn test_config() -> AnthropicConfig {
         AnthropicConfig {
             api_key: "test-key".to_string(),
             base_url: "https://api.anthropic.com".to_string(),
             timeout_secs: 60,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 50,
             api_version: "2023-06-01".to_string(),
+            pricing: Default::default(),
+            cost_store: None,
         }
     }
 
@@ -458,6 +938,8 @@ mod tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(client.validate_request(&valid_request).is_ok());
@@ -473,8 +955,584 @@ mod tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(client.validate_request(&invalid_request).is_err());
     }
+
+    #[tokio::test]
+    async fn test_validate_request_checks_images() {
+        let image = |source: ImageSource| MessageRequest {
+            model: ClaudeModel::Claude35Sonnet.as_str().to_string(),
+            messages: vec![
+                Message {
+                    role: Role::User,
+                    content: MessageContent::Text("Describe the next image".to_string()),
+                },
+                Message {
+                    role: Role::User,
+                    content: MessageContent::Parts(vec![ContentBlock::Image { source }]),
+                },
+            ],
+            max_tokens: 100,
+            system: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: false,
+            metadata: None,
+            tools: None,
+            tool_choice: None,
+        };
+        let base64 = |media_type: &str, data: String| {
+            image(ImageSource::Base64 {
+                media_type: media_type.to_string(),
+                data,
+            })
+        };
+        let png = BASE64.encode(b"\x89PNG\r\n\x1a\n");
+
+        let client = AnthropicClient::new(test_config()).await.unwrap();
+        assert!(client.validate_request(&base64("image/png", png.clone())).is_ok());
+
+        let err = client
+            .validate_request(&base64("image/bmp", png.clone()))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("messages[1].content[0].source.media_type"), "{}", err);
+
+        let err = client
+            .validate_request(&base64("image/png", "not base64!".to_string()))
+            .unwrap_err();
+        assert!(err.to_string().starts_with("messages[1].content[0].source.data"), "{}", err);
+
+        let oversized = BASE64.encode(vec![0u8; MAX_IMAGE_BYTES + 1]);
+        let err = client
+            .validate_request(&base64("image/jpeg", oversized))
+            .unwrap_err();
+        assert!(err.to_string().contains("byte limit"), "{}", err);
+
+        let url = image(ImageSource::Url {
+            url: "https://example.com/cat.png".to_string(),
+        });
+        assert!(client.validate_request(&url).is_ok());
+    }
+
+    /// Collects the fields recorded on `anthropic.request` spans
+    #[derive(Clone, Default)]
+    struct SpanRecorder(Arc<std::sync::Mutex<HashMap<String, String>>>);
+
+    impl Visit for SpanRecorder {
+        fn record_debug(&mut self, field: &Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .lock()
+                .unwrap()
+                .insert(field.name().to_string(), format!("{:?}", value));
+        }
+    }
+
+    impl<S> Layer<S> for SpanRecorder
+    where
+        S: Subscriber + for<'a> LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &Attributes<'_>, _id: &Id, _ctx: Context<'_, S>) {
+            if attrs.metadata().name() == "anthropic.request" {
+                attrs.record(&mut self.clone());
+            }
+        }
+
+        fn on_record(&self, id: &Id, values: &Record<'_>, ctx: Context<'_, S>) {
+            if ctx.span(id).is_some_and(|s| s.name() == "anthropic.request") {
+                values.record(&mut self.clone());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_count_request_tokens_uses_api_count() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages/count_tokens"))
+            .and(body_json(serde_json::json!({
+                "model": "claude-3-5-sonnet-20241022",
+                "messages": [{"role": "user", "content": "Hello, world"}]
+            })))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(serde_json::json!({"input_tokens": 14})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+        let request = MessageRequest {
+            model: ClaudeModel::Claude35Sonnet.as_str().to_string(),
+            messages: vec![Message {
+                role: Role::User,
+                content: MessageContent::Text("Hello, world".to_string()),
+            }],
+            max_tokens: 1024,
+            system: None,
+            temperature: Some(0.5),
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: false,
+            metadata: None,
+            tools: None,
+            tool_choice: None,
+        };
+
+        assert_eq!(client.count_request_tokens(&request).await.unwrap(), 14);
+    }
+
+    #[tokio::test]
+    async fn test_send_message_records_cost_span() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_123",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-3-5-sonnet-20241022",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 1000, "output_tokens": 500}
+            })))
+            .mount(&server)
+            .await;
+
+        let recorder = SpanRecorder::default();
+        let subscriber = tracing_subscriber::registry().with(recorder.clone());
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+        client
+            .complete(ClaudeModel::Claude35Sonnet, "Hello", 100)
+            .await
+            .unwrap();
+
+        let fields = recorder.0.lock().unwrap().clone();
+        assert_eq!(fields["model"], "claude-3-5-sonnet-20241022");
+        assert_eq!(fields["input_tokens"], "1000");
+        assert_eq!(fields["output_tokens"], "500");
+        assert_eq!(fields["status"], "200");
+        assert!(fields.contains_key("latency_ms"));
+        assert!(!fields.contains_key("retry_count"));
+
+        // 1000 * $3/Mtok + 500 * $15/Mtok
+        let cost: f64 = fields["cost_usd"].parse().unwrap();
+        assert!((cost - 0.0105).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_second_request_rejected_when_budget_exhausted() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_123",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-3-haiku-20240307",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 400, "output_tokens": 300}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config)
+            .await
+            .unwrap()
+            .with_token_budget(TokenBudget::new(1000, 100).unwrap());
+
+        client
+            .complete(ClaudeModel::Claude3Haiku, "Hello", 300)
+            .await
+            .unwrap();
+        assert_eq!(client.budget_remaining().await, Some(200));
+
+        // ~306 estimated tokens no longer fit in the 200 left
+        let err = client
+            .complete(ClaudeModel::Claude3Haiku, "Hello", 300)
+            .await
+            .unwrap_err();
+        let exceeded = err.downcast_ref::<BudgetExceededError>().unwrap();
+        assert_eq!(exceeded.remaining, 200);
+        assert!(exceeded.requested > 200);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_requests_cannot_share_remaining_budget() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(serde_json::json!({
+                        "id": "msg_123",
+                        "type": "message",
+                        "role": "assistant",
+                        "content": [{"type": "text", "text": "Hi"}],
+                        "model": "claude-3-haiku-20240307",
+                        "stop_reason": "end_turn",
+                        "stop_sequence": null,
+                        "usage": {"input_tokens": 10, "output_tokens": 10}
+                    }))
+                    .set_delay(Duration::from_millis(200)),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config)
+            .await
+            .unwrap()
+            .with_token_budget(TokenBudget::new(500, 100).unwrap());
+
+        // Each request reserves ~306 tokens, so only one fits in the 400 left
+        let (first, second) = tokio::join!(
+            client.complete(ClaudeModel::Claude3Haiku, "Hello", 300),
+            client.complete(ClaudeModel::Claude3Haiku, "Hello", 300),
+        );
+        let rejected = match (first, second) {
+            (Ok(_), Err(e)) | (Err(e), Ok(_)) => e,
+            (first, second) => panic!("expected one rejection, got {:?} and {:?}", first, second),
+        };
+        assert!(rejected.downcast_ref::<BudgetExceededError>().is_some());
+
+        // The reservation is settled to the 20 tokens actually used
+        assert_eq!(client.budget_remaining().await, Some(380));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_waits_for_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(429).insert_header("retry-after", "1"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "id": "msg_123",
+                "type": "message",
+                "role": "assistant",
+                "content": [{"type": "text", "text": "Hi"}],
+                "model": "claude-3-5-sonnet-20241022",
+                "stop_reason": "end_turn",
+                "stop_sequence": null,
+                "usage": {"input_tokens": 10, "output_tokens": 5}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        // A long backoff shows the wait comes from Retry-After instead
+        config.retry = RetryPolicy::default().with_initial_delay(Duration::from_secs(30));
+        let client = AnthropicClient::new(config).await.unwrap();
+
+        let started = Instant::now();
+        client
+            .complete(ClaudeModel::Claude35Sonnet, "Hello", 100)
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_secs(10));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_reset_wait_is_capped() {
+        let rate_limit: SharedRateLimit = Arc::new(RwLock::new(Some(RateLimitInfo {
+            requests_remaining: Some(0),
+            reset_at: Some(chrono::Utc::now().timestamp() + 3600),
+            ..Default::default()
+        })));
+
+        let started = Instant::now();
+        wait_for_rate_limit_reset(Some(&rate_limit), Duration::from_millis(50)).await;
+        assert!(started.elapsed() < Duration::from_secs(5));
+
+        // A reset time in the past does not wait at all
+        *rate_limit.write().await = Some(RateLimitInfo {
+            requests_remaining: Some(0),
+            reset_at: Some(0),
+            ..Default::default()
+        });
+        wait_for_rate_limit_reset(Some(&rate_limit), Duration::from_secs(60)).await;
+    }
+
+    fn message_json(model: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": "msg_123",
+            "type": "message",
+            "role": "assistant",
+            "content": [{"type": "text", "text": "Hi"}],
+            "model": model,
+            "stop_reason": "end_turn",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 5}
+        })
+    }
+
+    #[test]
+    fn test_parse_rate_limit_headers() {
+        let mut headers = HeaderMap::new();
+        headers.insert("anthropic-ratelimit-requests-limit", HeaderValue::from_static("50"));
+        headers.insert("anthropic-ratelimit-requests-remaining", HeaderValue::from_static("49"));
+        headers.insert("anthropic-ratelimit-requests-reset", HeaderValue::from_static("2024-01-01T00:00:30Z"));
+        headers.insert("anthropic-ratelimit-tokens-limit", HeaderValue::from_static("40000"));
+        headers.insert("anthropic-ratelimit-tokens-remaining", HeaderValue::from_static("39000"));
+        headers.insert("anthropic-ratelimit-tokens-reset", HeaderValue::from_static("2024-01-01T00:00:10Z"));
+
+        assert_eq!(
+            parse_rate_limit_headers(&headers),
+            Some(RateLimitInfo {
+                requests_remaining: Some(49),
+                requests_limit: Some(50),
+                tokens_remaining: Some(39000),
+                tokens_limit: Some(40000),
+                reset_at: Some(1_704_067_230),
+            })
+        );
+
+        // A fractional reset is rounded up; the token reset is the fallback
+        let mut headers = HeaderMap::new();
+        headers.insert("anthropic-ratelimit-tokens-reset", HeaderValue::from_static("2024-01-01T00:00:10.25Z"));
+        assert_eq!(parse_rate_limit_headers(&headers).unwrap().reset_at, Some(1_704_067_211));
+
+        assert_eq!(parse_rate_limit_headers(&HeaderMap::new()), None);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_request_limit_waits_for_reset() {
+        let server = MockServer::start().await;
+        let reset = (chrono::Utc::now() + chrono::Duration::seconds(2))
+            .to_rfc3339_opts(chrono::SecondsFormat::Secs, true);
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .set_body_json(message_json("claude-3-haiku-20240307"))
+                    .insert_header("anthropic-ratelimit-requests-limit", "50")
+                    .insert_header("anthropic-ratelimit-requests-remaining", "0")
+                    .insert_header("anthropic-ratelimit-requests-reset", reset.as_str()),
+            )
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+        assert_eq!(client.rate_limit_info().await, None);
+
+        client
+            .complete(ClaudeModel::Claude3Haiku, "Hello", 100)
+            .await
+            .unwrap();
+        let info = client.rate_limit_info().await.unwrap();
+        assert_eq!(info.requests_remaining, Some(0));
+        assert_eq!(info.requests_limit, Some(50));
+        assert!(info.reset_at.is_some());
+
+        // The next request holds off until the window resets
+        let started = Instant::now();
+        client
+            .complete(ClaudeModel::Claude3Haiku, "Hello", 100)
+            .await
+            .unwrap();
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_secs(1) && elapsed < Duration::from_secs(10));
+    }
+
+    fn sse(events: &[&str]) -> String {
+        events
+            .iter()
+            .map(|data| format!("event: message\ndata: {}\n\n", data))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_complete_stream_yields_text_and_records_cost() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        let body = sse(&[
+            r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"model":"claude-3-haiku-20240307","usage":{"input_tokens":1000,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"ping"}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Hello"}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":" world"}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"end_turn","stop_sequence":null},"usage":{"output_tokens":2000}}"#,
+            r#"{"type":"message_stop"}"#,
+        ]);
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+
+        let mut stream = client
+            .complete_stream(ClaudeModel::Claude3Haiku, "Hello", 2000)
+            .await
+            .unwrap();
+        let mut chunks = Vec::new();
+        while let Some(chunk) = stream.next().await {
+            chunks.push(chunk.unwrap());
+        }
+        assert_eq!(chunks, ["Hello", " world"]);
+
+        let stats = client.get_cost_stats().await;
+        assert_eq!(stats.total_input_tokens, 1000);
+        assert_eq!(stats.total_output_tokens, 2000);
+        // 1000 * $0.25/Mtok + 2000 * $1.25/Mtok
+        assert!((stats.total_cost - 0.00275).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_complete_stream_surfaces_error_event() {
+        use futures::StreamExt;
+
+        let server = MockServer::start().await;
+        let body = sse(&[
+            r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"model":"claude-3-haiku-20240307","usage":{"input_tokens":10,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Par"}}"#,
+            r#"{"type":"error","error":{"type":"overloaded_error","message":"Overloaded"}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"tial"}}"#,
+        ]);
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(200).set_body_raw(body, "text/event-stream"))
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+
+        let mut stream = client
+            .complete_stream(ClaudeModel::Claude3Haiku, "Hello", 100)
+            .await
+            .unwrap();
+        assert_eq!(stream.next().await.unwrap().unwrap(), "Par");
+        let err = stream.next().await.unwrap().unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+        assert!(stream.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "type": "invalid_request_error",
+                "message": "max_tokens: must be positive"
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        let client = AnthropicClient::new(config).await.unwrap();
+
+        assert!(client
+            .complete(ClaudeModel::Claude35Sonnet, "Hello", 100)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_opens_after_repeated_server_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(2)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        config.retry = RetryPolicy::none();
+        let client = AnthropicClient::new(config).await.unwrap().with_circuit_breaker(
+            CircuitBreaker::new(
+                "anthropic",
+                CircuitBreakerConfig::default().with_failure_threshold(2),
+            ),
+        );
+
+        for _ in 0..2 {
+            assert!(client
+                .complete(ClaudeModel::Claude35Sonnet, "Hello", 100)
+                .await
+                .is_err());
+        }
+        assert_eq!(client.circuit_state(), CircuitState::Open);
+
+        // The open breaker fails fast without reaching the server
+        let err = client
+            .complete(ClaudeModel::Claude35Sonnet, "Hello", 100)
+            .await
+            .unwrap_err();
+        assert!(err.downcast_ref::<CircuitOpenError>().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cost_stats_survive_restart() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/v1/messages"))
+            .respond_with(
+                ResponseTemplate::new(200).set_body_json(message_json("claude-3-haiku-20240307")),
+            )
+            .mount(&server)
+            .await;
+        let dir = tempfile::tempdir().unwrap();
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        config.cost_store = Some(CostStoreConfig::new(dir.path().join("costs.json")));
+        let client = AnthropicClient::new(config.clone()).await.unwrap();
+        client
+            .complete(ClaudeModel::Claude3Haiku, "Hello", 100)
+            .await
+            .unwrap();
+        let before = client.get_cost_stats().await;
+        client.flush_cost_stats().await.unwrap();
+        drop(client);
+
+        let restarted = AnthropicClient::new(config).await.unwrap();
+        let after = restarted.get_cost_stats().await;
+        assert_eq!(after.request_count, 1);
+        assert_eq!(after.total_input_tokens, before.total_input_tokens);
+        assert_eq!(after.total_cost, before.total_cost);
+    }
 }
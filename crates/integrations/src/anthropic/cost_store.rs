@@ -0,0 +1,337 @@
+//! Persisting the cost tracker across restarts
+//!
+//! The tracker is saved as JSON and reloaded when the client starts. Saves
+//! write a temporary file next to the target and rename it into place, so a
+//! crash mid-write leaves the previous save intact.
+//!
+//! # Locking
+//!
+//! The live tracker sits behind the client's `RwLock` and is shared with
+//! stream handlers and batch clients. A flush holds [`CostStore`]'s save
+//! lock for its whole duration and takes the tracker's write lock only long
+//! enough to roll the period over (writing the archive, once per period) and
+//! clone a snapshot, so requests are not blocked on routine saves and
+//! concurrent flushes (autosave racing shutdown) land on disk in the order
+//! they read the tracker. One file must not be
+//! shared by several processes; the last writer wins.
+
+use super::types::{CostTracker, ModelPricing};
+use anyhow::{Context, Result};
+use chrono::{DateTime, Datelike, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::ffi::OsString;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Weak};
+use std::time::Duration;
+use tokio::sync::{Mutex, RwLock};
+use tracing::{info, warn};
+
+/// Where and how often to persist the cost tracker
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CostStoreConfig {
+    /// JSON file holding the current period's totals
+    pub path: PathBuf,
+    /// Seconds between automatic saves
+    #[serde(default = "default_autosave_interval_secs")]
+    pub autosave_interval_secs: u64,
+    /// Start fresh totals at each period boundary, archiving the old ones
+    #[serde(default)]
+    pub billing_period: Option<BillingPeriod>,
+}
+
+fn default_autosave_interval_secs() -> u64 {
+    60
+}
+
+impl CostStoreConfig {
+    /// Persist to `path`, autosaving every minute and never rolling over
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            autosave_interval_secs: default_autosave_interval_secs(),
+            billing_period: None,
+        }
+    }
+}
+
+/// Billing period after which totals start again from zero
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BillingPeriod {
+    /// Midnight to midnight, UTC
+    Daily,
+    /// First of the month to first of the month, UTC
+    Monthly,
+}
+
+impl BillingPeriod {
+    /// Start of the period containing `now`
+    pub fn start(self, now: DateTime<Utc>) -> DateTime<Utc> {
+        let date = now.date_naive();
+        let date = match self {
+            Self::Daily => date,
+            Self::Monthly => date.with_day(1).unwrap_or(date),
+        };
+        date.and_time(NaiveTime::MIN).and_utc()
+    }
+
+    /// Label for the period starting at `start`, e.g. `2024-05` or `2024-05-31`
+    pub fn label(self, start: DateTime<Utc>) -> String {
+        match self {
+            Self::Daily => start.format("%Y-%m-%d").to_string(),
+            Self::Monthly => start.format("%Y-%m").to_string(),
+        }
+    }
+}
+
+impl CostTracker {
+    /// Write the totals to `path` as JSON, replacing any previous save
+    ///
+    /// Pricing overrides are configuration, not state, and are not saved.
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = serde_json::to_vec_pretty(self).context("Failed to serialize cost tracker")?;
+        write_atomic(path, &json)
+            .with_context(|| format!("Failed to save cost tracker to {}", path.display()))
+    }
+
+    /// Read totals written by [`CostTracker::save`]
+    pub fn load(path: &Path) -> Result<Self> {
+        let json = std::fs::read(path)
+            .with_context(|| format!("Failed to read cost tracker from {}", path.display()))?;
+        serde_json::from_slice(&json)
+            .with_context(|| format!("Failed to parse cost tracker at {}", path.display()))
+    }
+
+    /// Start a new period if `now` falls after the current one
+    ///
+    /// Returns the finished period's totals. A tracker that has never been
+    /// rolled over adopts the current period and returns `None`.
+    pub fn roll_over(&mut self, period: BillingPeriod, now: DateTime<Utc>) -> Option<Self> {
+        let start = period.start(now);
+        match self.period_start {
+            Some(current) if current >= start => None,
+            Some(_) => {
+                let finished = self.clone();
+                self.reset();
+                self.period_start = Some(start);
+                Some(finished)
+            }
+            None => {
+                self.period_start = Some(start);
+                None
+            }
+        }
+    }
+}
+
+/// Saves a shared cost tracker to disk
+#[derive(Debug)]
+pub struct CostStore {
+    config: CostStoreConfig,
+    save_lock: Mutex<()>,
+}
+
+impl CostStore {
+    /// Load the saved tracker, or start an empty one if nothing was saved yet
+    ///
+    /// A save from an earlier billing period is archived and replaced by
+    /// fresh totals.
+    pub fn open(
+        config: CostStoreConfig,
+        pricing: HashMap<String, ModelPricing>,
+    ) -> Result<(Self, CostTracker)> {
+        let mut tracker = if config.path.exists() {
+            let tracker = CostTracker::load(&config.path)?;
+            info!(
+                "Loaded cost tracker from {}: ${:.4} over {} requests",
+                config.path.display(),
+                tracker.total_cost,
+                tracker.request_count
+            );
+            tracker
+        } else {
+            CostTracker::default()
+        };
+        tracker.pricing = pricing;
+
+        let store = Self {
+            config,
+            save_lock: Mutex::new(()),
+        };
+        store.roll_over(&mut tracker)?;
+        Ok((store, tracker))
+    }
+
+    /// Path of the archive holding the period that started at `start`
+    ///
+    /// `costs.json` for May 2024 is archived as `costs.2024-05.json`.
+    pub fn archive_path(&self, period: BillingPeriod, start: DateTime<Utc>) -> PathBuf {
+        let path = &self.config.path;
+        let mut name = path.file_stem().map(OsString::from).unwrap_or_default();
+        name.push(format!(".{}", period.label(start)));
+        if let Some(extension) = path.extension() {
+            name.push(".");
+            name.push(extension);
+        }
+        path.with_file_name(name)
+    }
+
+    /// Roll `tracker` over and save it
+    pub async fn flush(&self, tracker: &RwLock<CostTracker>) -> Result<()> {
+        let _guard = self.save_lock.lock().await;
+        let snapshot = {
+            let mut tracker = tracker.write().await;
+            self.roll_over(&mut tracker)?;
+            tracker.clone()
+        };
+        snapshot.save(&self.config.path)
+    }
+
+    /// Flush every `autosave_interval_secs` until the tracker is dropped
+    pub fn spawn_autosave(self: Arc<Self>, tracker: Weak<RwLock<CostTracker>>) {
+        let interval = Duration::from_secs(self.config.autosave_interval_secs.max(1));
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(interval);
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                let Some(tracker) = tracker.upgrade() else {
+                    break;
+                };
+                if let Err(e) = self.flush(&tracker).await {
+                    warn!("Failed to autosave cost tracker: {:#}", e);
+                }
+            }
+        });
+    }
+
+    /// Archive the finished period, if the configured boundary was crossed
+    fn roll_over(&self, tracker: &mut CostTracker) -> Result<()> {
+        let Some(period) = self.config.billing_period else {
+            return Ok(());
+        };
+        if let Some(finished) = tracker.roll_over(period, Utc::now()) {
+            if let Some(start) = finished.period_start {
+                let archive = self.archive_path(period, start);
+                if let Err(e) = finished.save(&archive) {
+                    // Keep counting into the old period rather than lose it
+                    *tracker = finished;
+                    return Err(e);
+                }
+                info!(
+                    "Archived {} cost period (${:.4}) to {}",
+                    period.label(start),
+                    finished.total_cost,
+                    archive.display()
+                );
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Replace `path` with `contents` via a temporary file in the same directory
+fn write_atomic(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut temp_name = OsString::from(".");
+    temp_name.push(path.file_name().unwrap_or_default());
+    temp_name.push(format!(".{}.tmp", std::process::id()));
+    let temp = path.with_file_name(temp_name);
+
+    let written = std::fs::File::create(&temp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()?;
+        std::fs::rename(&temp, path)
+    });
+    if written.is_err() {
+        let _ = std::fs::remove_file(&temp);
+    }
+    written
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::anthropic::types::{ClaudeModel, Usage};
+    use chrono::TimeZone;
+
+    fn used_tracker() -> CostTracker {
+        let mut tracker = CostTracker::new();
+        let usage = Usage {
+            input_tokens: 1200,
+            output_tokens: 300,
+            cache_read_input_tokens: 800,
+            ..Default::default()
+        };
+        tracker.record_usage(&usage, ClaudeModel::Claude3Haiku);
+        tracker.record_batch_usage(&usage, ClaudeModel::Claude3Haiku);
+        tracker
+    }
+
+    #[test]
+    fn test_load_after_save_reproduces_totals() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("costs.json");
+        let tracker = used_tracker();
+
+        tracker.save(&path).unwrap();
+        let loaded = CostTracker::load(&path).unwrap();
+
+        assert_eq!(loaded.request_count, tracker.request_count);
+        assert_eq!(loaded.total_input_tokens, tracker.total_input_tokens);
+        assert_eq!(loaded.total_output_tokens, tracker.total_output_tokens);
+        assert_eq!(
+            loaded.total_cache_read_tokens,
+            tracker.total_cache_read_tokens
+        );
+        assert_eq!(loaded.total_cost, tracker.total_cost);
+        assert_eq!(loaded.total_batch_cost, tracker.total_batch_cost);
+        assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_roll_over_at_month_boundary() {
+        let mut tracker = used_tracker();
+        let may = Utc.with_ymd_and_hms(2024, 5, 31, 23, 0, 0).unwrap();
+        let june = Utc.with_ymd_and_hms(2024, 6, 1, 0, 30, 0).unwrap();
+
+        assert!(tracker.roll_over(BillingPeriod::Monthly, may).is_none());
+        assert!(tracker.roll_over(BillingPeriod::Monthly, may).is_none());
+        let finished = tracker.roll_over(BillingPeriod::Monthly, june).unwrap();
+
+        assert_eq!(finished.request_count, 2);
+        assert_eq!(
+            BillingPeriod::Monthly.label(finished.period_start.unwrap()),
+            "2024-05"
+        );
+        assert_eq!(tracker.request_count, 0);
+        assert_eq!(
+            tracker.period_start,
+            Some(BillingPeriod::Monthly.start(june))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_open_archives_previous_period() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("costs.json");
+        let mut saved = used_tracker();
+        saved.period_start = Some(Utc.with_ymd_and_hms(2020, 1, 1, 0, 0, 0).unwrap());
+        saved.save(&path).unwrap();
+
+        let config = CostStoreConfig {
+            billing_period: Some(BillingPeriod::Monthly),
+            ..CostStoreConfig::new(&path)
+        };
+        let (store, tracker) = CostStore::open(config, HashMap::new()).unwrap();
+        assert_eq!(tracker.request_count, 0);
+
+        let archived = CostTracker::load(&dir.path().join("costs.2020-01.json")).unwrap();
+        assert_eq!(archived.total_cost, saved.total_cost);
+
+        let tracker = RwLock::new(tracker);
+        store.flush(&tracker).await.unwrap();
+        assert_eq!(CostTracker::load(&path).unwrap().request_count, 0);
+    }
+}
@@ -4,8 +4,12 @@
 //! - API key authentication
 //! - Message/completion endpoints
 //! - Streaming support via Server-Sent Events
-//! - Token counting and validation
-//! - Cost tracking and estimation
+//! - Message Batches for large asynchronous workloads
+//! - Tool use (function calling), including streamed tool input
+//! - Token counting, validation, and budget enforcement
+//! - Cost tracking and estimation, including prompt cache usage, persisted
+//!   across restarts and rolled over per billing period
+//! - Alerts when spend crosses configured thresholds
 //! - Rate limiting
 //! - Comprehensive error handling and retry logic
 //!
@@ -22,9 +26,11 @@
 //!         api_key: "your-api-key".to_string(),
 //!         base_url: "https://api.anthropic.com".to_string(),
 //!         timeout_secs: 60,
-//!         max_retries: 3,
+//!         retry: Default::default(),
 //!         rate_limit_per_minute: 50,
 //!         api_version: "2023-06-01".to_string(),
+//!         pricing: Default::default(),
+//!         cost_store: None,
 //!     };
 //!
 //!     let client = AnthropicClient::new(config).await?;
@@ -48,41 +54,36 @@
 //! ## Streaming completion
 //!
 //! ```no_run
-//! use integrations::anthropic::{StreamHandler, AnthropicConfig, MessageRequest, Message, Role, MessageContent, ClaudeModel};
+//! use integrations::anthropic::{AnthropicClient, AnthropicConfig, ClaudeModel};
 //! use futures::StreamExt;
 //!
 //! #[tokio::main]
 //! async fn main() -> anyhow::Result<()> {
-//!     // ... setup config and client ...
-//!
-//!     let request = MessageRequest {
-//!         model: ClaudeModel::Claude3Haiku.as_str().to_string(),
-//!         messages: vec![Message {
-//!             role: Role::User,
-//!             content: MessageContent::Text("Tell me a story".to_string()),
-//!         }],
-//!         max_tokens: 500,
-//!         system: None,
-//!         temperature: None,
-//!         top_p: None,
-//!         top_k: None,
-//!         stop_sequences: None,
-//!         stream: true,
-//!         metadata: None,
-//!     };
+//!     let client = AnthropicClient::new(AnthropicConfig::new("your-api-key")).await?;
 //!
-//!     // ... create StreamHandler and stream ...
+//!     let mut stream = client
+//!         .complete_stream(ClaudeModel::Claude3Haiku, "Tell me a story", 500)
+//!         .await?;
+//!     while let Some(chunk) = stream.next().await {
+//!         print!("{}", chunk?);
+//!     }
 //!
 //!     Ok(())
 //! }
 //! ```
 
+pub mod alerts;
+pub mod batch;
 pub mod client;
+pub mod cost_store;
 pub mod streaming;
 pub mod tokens;
 pub mod types;
 
-pub use client::AnthropicClient;
-pub use streaming::{StreamCollector, StreamHandler};
-pub use tokens::{TokenBudget, TokenCounter};
+pub use alerts::{CostAlert, CostAlertNotifier, CostAlerter};
+pub use batch::{BatchClient, BatchOutcome, BatchTimeoutError, DEFAULT_POLL_INTERVAL};
+pub use client::{parse_model, parse_rate_limit_headers, AnthropicClient};
+pub use cost_store::{BillingPeriod, CostStore, CostStoreConfig};
+pub use streaming::{StreamCollector, StreamHandler, StreamedToolUse, TextStream};
+pub use tokens::{BudgetExceededError, TokenBudget, TokenCounter};
 pub use types::*;
@@ -2,12 +2,20 @@
 //!
 //! Handles Server-Sent Events (SSE) streaming from Claude API.
 
+use super::client::{
+    charge_budget, parse_model, record_rate_limit, reserve_budget, wait_for_rate_limit_reset,
+    SharedBudget, SharedRateLimit,
+};
+use super::tokens::TokenBudget;
 use super::types::*;
+use crate::request_context::insert_request_id_header;
 use anyhow::{anyhow, Context, Result};
 use futures::stream::{Stream, StreamExt};
 use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
 use std::pin::Pin;
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{debug, error, info, warn};
 
@@ -19,6 +27,10 @@ pub struct StreamHandler {
     client: reqwest::Client,
     /// Cost tracker
     cost_tracker: Arc<RwLock<CostTracker>>,
+    /// Token budget charged as usage arrives
+    budget: Option<SharedBudget>,
+    /// Rate limits shared with the client that created this handler
+    rate_limit: Option<SharedRateLimit>,
 }
 
 impl StreamHandler {
@@ -38,9 +50,23 @@ impl StreamHandler {
             config,
             client,
             cost_tracker,
+            budget: None,
+            rate_limit: None,
         }
     }
 
+    /// Share rate limit state with an [`super::AnthropicClient`]
+    pub(super) fn with_rate_limit(mut self, rate_limit: SharedRateLimit) -> Self {
+        self.rate_limit = Some(rate_limit);
+        self
+    }
+
+    /// Enforce a token budget shared with other handlers or clients
+    pub fn with_token_budget(mut self, budget: Arc<RwLock<TokenBudget>>) -> Self {
+        self.budget = Some(budget);
+        self
+    }
+
     /// Send a streaming message request
     ///
     /// # Arguments
@@ -57,31 +83,14 @@ impl StreamHandler {
         // Ensure streaming is enabled
         request.stream = true;
 
-        let config = self.config.read().await;
-        let url = format!("{}/v1/messages", config.base_url);
-
-        let headers = self.build_headers(&config)?;
-
-        debug!(
-            "Starting streaming request to model: {}",
-            request.model
-        );
-
-        let response = self
-            .client
-            .post(&url)
-            .headers(headers)
-            .json(&request)
-            .send()
-            .await
-            .context("Failed to send streaming request")?;
-
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            error!("Streaming request failed ({}): {}", status, error_text);
-            return Err(anyhow!("Streaming request failed: {}", error_text));
-        }
+        let reserved = reserve_budget(self.budget.as_ref(), &request).await?;
+        let response = match self.open_stream(&request).await {
+            Ok(response) => response,
+            Err(e) => {
+                charge_budget(self.budget.as_ref(), reserved, 0).await;
+                return Err(e);
+            }
+        };
 
         info!("Streaming response started");
 
@@ -94,7 +103,59 @@ impl StreamHandler {
             }
         });
 
-        let stream = self.parse_sse_stream(byte_stream);
+        let budget = self.budget.clone();
+        let reserved = Arc::new(AtomicU32::new(reserved));
+        let stream = self.parse_sse_stream(byte_stream).then(move |event| {
+            let budget = budget.clone();
+            let reserved = Arc::clone(&reserved);
+            async move {
+                // Input usage arrives with message_start, output with message_delta
+                let tokens = match &event {
+                    Ok(StreamEvent::MessageStart { message }) => {
+                        message.usage.total_input_tokens()
+                    }
+                    Ok(StreamEvent::MessageDelta { usage, .. }) => usage.output_tokens,
+                    _ => 0,
+                };
+                if tokens > 0 {
+                    // The first usage replaces the estimate reserved up front
+                    let reserved = reserved.swap(0, Ordering::Relaxed);
+                    charge_budget(budget.as_ref(), reserved, tokens).await;
+                }
+                event
+            }
+        });
+
+        Ok(Box::pin(stream))
+    }
+
+    /// Stream the text of a message as it is generated
+    ///
+    /// Yields each text delta. `ping` keep-alives and other events are
+    /// skipped, an `error` event is yielded as an error and ends the stream,
+    /// and usage is recorded in the cost tracker when the final
+    /// `message_delta` arrives. Dropping the stream closes the connection,
+    /// cancelling the request.
+    ///
+    /// # Arguments
+    ///
+    /// * `request` - Message request
+    pub async fn stream_text(&self, request: MessageRequest) -> Result<TextStream> {
+        let state = TextStreamState {
+            model: request.model.clone(),
+            events: self.stream_message(request).await?,
+            cost_tracker: Arc::clone(&self.cost_tracker),
+            usage: Usage::default(),
+            finished: false,
+        };
+
+        let stream = futures::stream::unfold(state, |mut state| async move {
+            let item = state.next_text().await?;
+            if item.is_err() {
+                state.finished = true;
+            }
+            Some((item, state))
+        });
 
         Ok(Box::pin(stream))
     }
@@ -115,10 +176,7 @@ impl StreamHandler {
         let mut stream = self.stream_message(request).await?;
 
         let mut text = String::new();
-        let mut usage = Usage {
-            input_tokens: 0,
-            output_tokens: 0,
-        };
+        let mut usage = Usage::default();
 
         while let Some(event_result) = stream.next().await {
             match event_result? {
@@ -134,7 +192,7 @@ impl StreamHandler {
                     usage: final_usage,
                     ..
                 } => {
-                    usage = final_usage;
+                    usage = merge_usage(usage, final_usage);
                 }
                 StreamEvent::Error { error } => {
                     return Err(anyhow!("Stream error: {}", error.message));
@@ -151,6 +209,41 @@ impl StreamHandler {
         Ok((text, usage))
     }
 
+    /// Send a streaming request and check that the API accepted it
+    async fn open_stream(&self, request: &MessageRequest) -> Result<reqwest::Response> {
+        let config = self.config.read().await;
+        let max_wait = Duration::from_millis(config.retry.max_delay_ms);
+        wait_for_rate_limit_reset(self.rate_limit.as_ref(), max_wait).await;
+
+        let url = format!("{}/v1/messages", config.base_url);
+
+        let headers = self.build_headers(&config)?;
+
+        debug!(
+            "Starting streaming request to model: {}",
+            request.model
+        );
+
+        let response = self
+            .client
+            .post(&url)
+            .headers(headers)
+            .json(request)
+            .send()
+            .await
+            .context("Failed to send streaming request")?;
+        record_rate_limit(self.rate_limit.as_ref(), response.headers()).await;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            error!("Streaming request failed ({}): {}", status, error_text);
+            return Err(anyhow!("Streaming request failed: {}", error_text));
+        }
+
+        Ok(response)
+    }
+
     /// Build request headers
     fn build_headers(&self, config: &AnthropicConfig) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
@@ -172,89 +265,147 @@ impl StreamHandler {
                 .context("Invalid API version")?,
         );
 
+        insert_request_id_header(&mut headers);
+
         Ok(headers)
     }
 
     /// Parse Server-Sent Events stream
+    ///
+    /// A chunk can carry several events, or end partway through an event or
+    /// a UTF-8 character, so bytes are buffered until an event is complete.
     fn parse_sse_stream(
         &self,
         byte_stream: impl Stream<Item = reqwest::Result<bytes::Bytes>> + Send + 'static,
     ) -> impl Stream<Item = Result<StreamEvent>> + Send {
-        let buffer = Arc::new(tokio::sync::Mutex::new(String::new()));
-
-        byte_stream.filter_map(move |chunk_result| {
-            let buffer = buffer.clone();
-            async move {
-                let chunk = match chunk_result {
-                    Ok(c) => c,
-                    Err(e) => return Some(Err(anyhow!("Stream error: {}", e))),
+        byte_stream
+            .scan(Vec::new(), |buffer, chunk_result| {
+                let events = match chunk_result {
+                    Ok(chunk) => {
+                        buffer.extend_from_slice(&chunk);
+                        drain_sse_events(buffer)
+                    }
+                    Err(e) => vec![Err(anyhow!("Stream error: {}", e))],
                 };
+                futures::future::ready(Some(futures::stream::iter(events)))
+            })
+            .flatten()
+    }
+}
 
-                // Convert bytes to string
-                let chunk_str = match std::str::from_utf8(&chunk) {
-                    Ok(s) => s,
-                    Err(e) => return Some(Err(anyhow!("Invalid UTF-8: {}", e))),
-                };
+/// Parse and remove every complete SSE message at the front of `buffer`
+fn drain_sse_events(buffer: &mut Vec<u8>) -> Vec<Result<StreamEvent>> {
+    let mut events = Vec::new();
+
+    while let Some(pos) = buffer.windows(2).position(|window| window == b"\n\n") {
+        let message: Vec<u8> = buffer.drain(..pos + 2).collect();
+        let message = match std::str::from_utf8(&message[..pos]) {
+            Ok(message) => message,
+            Err(e) => {
+                events.push(Err(anyhow!("Invalid UTF-8: {}", e)));
+                continue;
+            }
+        };
 
-                let mut buffer = buffer.lock().await;
-                buffer.push_str(chunk_str);
+        // Parse SSE message
+        let mut event_type = None;
+        let mut data = String::new();
 
-                // Process complete SSE messages
-                let mut events = Vec::new();
+        for line in message.lines() {
+            if let Some(stripped) = line.strip_prefix("event: ") {
+                event_type = Some(stripped);
+            } else if let Some(stripped) = line.strip_prefix("data: ") {
+                data.push_str(stripped);
+            }
+        }
 
-                while let Some(pos) = buffer.find("\n\n") {
-                    let message = buffer[..pos].to_string();
-                    *buffer = buffer[pos + 2..].to_string();
+        // Parse event data as JSON
+        if !data.is_empty() {
+            match serde_json::from_str::<StreamEvent>(&data) {
+                Ok(event) => {
+                    debug!("Received stream event: {:?}", event_type);
+                    events.push(Ok(event));
+                }
+                Err(e) => {
+                    warn!("Failed to parse stream event: {}", e);
+                    events.push(Err(anyhow!("Parse error: {}", e)));
+                }
+            }
+        }
+    }
 
-                    if message.is_empty() {
-                        continue;
-                    }
+    events
+}
 
-                    // Parse SSE message
-                    let mut event_type = None;
-                    let mut data = String::new();
+/// Text deltas of a streamed message; see [`StreamHandler::stream_text`]
+pub type TextStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
 
-                    for line in message.lines() {
-                        if let Some(stripped) = line.strip_prefix("event: ") {
-                            event_type = Some(stripped.to_string());
-                        } else if let Some(stripped) = line.strip_prefix("data: ") {
-                            data.push_str(stripped);
-                        }
-                    }
+/// Progress of a [`TextStream`] through the underlying events
+struct TextStreamState {
+    events: Pin<Box<dyn Stream<Item = Result<StreamEvent>> + Send>>,
+    cost_tracker: Arc<RwLock<CostTracker>>,
+    /// Model id, replaced by the one reported in `message_start`
+    model: String,
+    usage: Usage,
+    finished: bool,
+}
 
-                    // Parse event data as JSON
-                    if !data.is_empty() {
-                        match serde_json::from_str::<StreamEvent>(&data) {
-                            Ok(event) => {
-                                debug!("Received stream event: {:?}", event_type);
-                                events.push(Ok(event));
-                            }
-                            Err(e) => {
-                                warn!("Failed to parse stream event: {}", e);
-                                events.push(Err(anyhow!("Parse error: {}", e)));
-                            }
-                        }
-                    }
-                }
+impl TextStreamState {
+    /// Next text delta or error, or `None` once the stream is over
+    async fn next_text(&mut self) -> Option<Result<String>> {
+        if self.finished {
+            return None;
+        }
 
-                if events.is_empty() {
-                    None
-                } else if events.len() == 1 {
-                    Some(events.into_iter().next().unwrap())
-                } else {
-                    // If multiple events, return the first one
-                    // (This is a simplification; in practice, you might want to handle this differently)
-                    Some(events.into_iter().next().unwrap())
+        while let Some(event) = self.events.next().await {
+            match event {
+                Ok(StreamEvent::ContentBlockDelta {
+                    delta: Delta::TextDelta { text },
+                    ..
+                }) => return Some(Ok(text)),
+                Ok(StreamEvent::MessageStart { message }) => {
+                    self.model = message.model;
+                    self.usage = message.usage;
+                }
+                Ok(StreamEvent::MessageDelta { usage, .. }) => {
+                    self.usage = merge_usage(self.usage, usage);
+                    let model = match parse_model(&self.model) {
+                        Ok(model) => model,
+                        Err(e) => return Some(Err(e)),
+                    };
+                    self.cost_tracker.write().await.record_usage(&self.usage, model);
                 }
+                Ok(StreamEvent::Error { error }) => {
+                    return Some(Err(anyhow!("Stream error: {}", error.message)));
+                }
+                Ok(_) => {}
+                Err(e) => return Some(Err(e)),
             }
-        })
+        }
+
+        None
     }
 }
 
+/// Tool call being streamed, with its input JSON accumulated so far
+#[derive(Debug, Clone)]
+pub struct StreamedToolUse {
+    /// Index of the content block carrying the call
+    pub index: usize,
+    /// Tool use ID
+    pub id: String,
+    /// Tool name
+    pub name: String,
+    /// Concatenated `input_json_delta` fragments
+    pub partial_json: String,
+}
+
 /// Stream collector for aggregating streaming events
 pub struct StreamCollector {
     /// Accumulated text content
     pub text: String,
+    /// Tool calls in the order they started
+    pub tool_uses: Vec<StreamedToolUse>,
     /// Message ID
     pub message_id: Option<String>,
     /// Model used
@@ -272,12 +423,10 @@ impl StreamCollector {
     pub fn new() -> Self {
         Self {
             text: String::new(),
+            tool_uses: Vec::new(),
             message_id: None,
             model: None,
-            usage: Usage {
-                input_tokens: 0,
-                output_tokens: 0,
-            },
+            usage: Usage::default(),
             stop_reason: None,
             stop_sequence: None,
         }
@@ -300,14 +449,33 @@ impl StreamCollector {
                 self.usage = message.usage;
                 false
             }
-            StreamEvent::ContentBlockDelta { delta, .. } => {
-                if let Delta::TextDelta { text } = delta {
-                    self.text.push_str(&text);
+            StreamEvent::ContentBlockStart {
+                index,
+                content_block: ContentBlockStart::ToolUse { id, name, .. },
+            } => {
+                self.tool_uses.push(StreamedToolUse {
+                    index,
+                    id,
+                    name,
+                    partial_json: String::new(),
+                });
+                false
+            }
+            StreamEvent::ContentBlockDelta { index, delta } => {
+                match delta {
+                    Delta::TextDelta { text } => self.text.push_str(&text),
+                    Delta::InputJsonDelta { partial_json } => {
+                        if let Some(tool) = self.tool_uses.iter_mut().find(|tool| tool.index == index) {
+                            tool.partial_json.push_str(&partial_json);
+                        } else {
+                            warn!("Input JSON delta for unknown content block {}", index);
+                        }
+                    }
                 }
                 false
             }
             StreamEvent::MessageDelta { delta, usage } => {
-                self.usage = usage;
+                self.usage = merge_usage(self.usage, usage);
                 self.stop_reason = delta.stop_reason;
                 self.stop_sequence = delta.stop_sequence;
                 false
@@ -324,12 +492,38 @@ impl StreamCollector {
     }
 
     /// Convert to MessageResponse
+    ///
+    /// Tool calls follow the text block, with their accumulated input parsed
+    /// as JSON.
     pub fn to_response(self) -> Result<MessageResponse> {
+        let mut content = Vec::with_capacity(1 + self.tool_uses.len());
+        if !self.text.is_empty() || self.tool_uses.is_empty() {
+            content.push(ContentBlock::Text {
+                text: self.text,
+                cache_control: None,
+            });
+        }
+        for tool in self.tool_uses {
+            // A tool without arguments streams no input deltas
+            let input = if tool.partial_json.is_empty() {
+                serde_json::json!({})
+            } else {
+                serde_json::from_str(&tool.partial_json).with_context(|| {
+                    format!("Invalid input JSON for tool call {} ({})", tool.id, tool.name)
+                })?
+            };
+            content.push(ContentBlock::ToolUse {
+                id: tool.id,
+                name: tool.name,
+                input,
+            });
+        }
+
         Ok(MessageResponse {
             id: self.message_id.ok_or_else(|| anyhow!("Missing message ID"))?,
             type_field: "message".to_string(),
             role: Role::Assistant,
-            content: vec![ContentBlock::Text { text: self.text }],
+            content,
             model: self.model.ok_or_else(|| anyhow!("Missing model"))?,
             stop_reason: self.stop_reason,
             stop_sequence: self.stop_sequence,
@@ -344,6 +538,31 @@ impl Default for StreamCollector {
     }
 }
 
+/// Apply a `message_delta` usage update
+///
+/// Input and cache token counts are reported on `message_start`; keep them
+/// when the delta omits them.
+fn merge_usage(current: Usage, delta: Usage) -> Usage {
+    Usage {
+        input_tokens: if delta.input_tokens == 0 {
+            current.input_tokens
+        } else {
+            delta.input_tokens
+        },
+        cache_creation_input_tokens: if delta.cache_creation_input_tokens == 0 {
+            current.cache_creation_input_tokens
+        } else {
+            delta.cache_creation_input_tokens
+        },
+        cache_read_input_tokens: if delta.cache_read_input_tokens == 0 {
+            current.cache_read_input_tokens
+        } else {
+            delta.cache_read_input_tokens
+        },
+        ..delta
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -363,6 +582,7 @@ mod tests {
                 usage: Usage {
                     input_tokens: 10,
                     output_tokens: 0,
+                    ..Default::default()
                 },
             },
         };
@@ -397,6 +617,29 @@ mod tests {
         assert!(collector.process_event(stop_event));
     }
 
+    #[test]
+    fn test_drain_sse_events_handles_split_and_batched_chunks() {
+        let ping = "event: ping\ndata: {\"type\": \"ping\"}\n\n";
+        let delta = "event: content_block_delta\ndata: {\"type\":\"content_block_delta\",\"index\":0,\"delta\":{\"type\":\"text_delta\",\"text\":\"caf\u{e9}\"}}\n\n";
+        let stream = format!("{}{}", ping, delta).into_bytes();
+
+        // Split inside the multi-byte character of the second event
+        let split = stream.len() - "\"}}\n\n".len() - 1;
+        let mut buffer = stream[..split].to_vec();
+        let events = drain_sse_events(&mut buffer);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Ok(StreamEvent::Ping)));
+
+        buffer.extend_from_slice(&stream[split..]);
+        let events = drain_sse_events(&mut buffer);
+        assert_eq!(events.len(), 1);
+        assert!(matches!(
+            &events[0],
+            Ok(StreamEvent::ContentBlockDelta { delta: Delta::TextDelta { text }, .. }) if text == "caf\u{e9}"
+        ));
+        assert!(buffer.is_empty());
+    }
+
     #[test]
     fn test_stream_collector_to_response() {
         let mut collector = StreamCollector::new();
@@ -407,6 +650,7 @@ mod tests {
         collector.usage = Usage {
             input_tokens: 10,
             output_tokens: 5,
+            ..Default::default()
         };
         collector.stop_reason = Some(StopReason::EndTurn);
 
@@ -416,4 +660,39 @@ mod tests {
         assert_eq!(response.usage.input_tokens, 10);
         assert_eq!(response.usage.output_tokens, 5);
     }
+
+    #[test]
+    fn test_stream_collector_accumulates_tool_input() {
+        let events = [
+            r#"{"type":"message_start","message":{"id":"msg_1","type":"message","role":"assistant","content":[],"model":"claude-3-5-sonnet-20241022","usage":{"input_tokens":50,"output_tokens":1}}}"#,
+            r#"{"type":"content_block_start","index":0,"content_block":{"type":"text","text":""}}"#,
+            r#"{"type":"content_block_delta","index":0,"delta":{"type":"text_delta","text":"Checking."}}"#,
+            r#"{"type":"content_block_stop","index":0}"#,
+            r#"{"type":"content_block_start","index":1,"content_block":{"type":"tool_use","id":"toolu_1","name":"get_weather","input":{}}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":""}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":"{\"location\": \"San"}}"#,
+            r#"{"type":"content_block_delta","index":1,"delta":{"type":"input_json_delta","partial_json":" Francisco\"}"}}"#,
+            r#"{"type":"content_block_stop","index":1}"#,
+            r#"{"type":"message_delta","delta":{"stop_reason":"tool_use","stop_sequence":null},"usage":{"input_tokens":50,"output_tokens":30}}"#,
+            r#"{"type":"message_stop"}"#,
+        ];
+
+        let mut collector = StreamCollector::new();
+        for event in events {
+            collector.process_event(serde_json::from_str(event).unwrap());
+        }
+        let response = collector.to_response().unwrap();
+
+        assert_eq!(response.stop_reason, Some(StopReason::ToolUse));
+        assert_eq!(response.content.len(), 2);
+        assert!(matches!(&response.content[0], ContentBlock::Text { text, .. } if text == "Checking."));
+        match &response.content[1] {
+            ContentBlock::ToolUse { id, name, input } => {
+                assert_eq!(id, "toolu_1");
+                assert_eq!(name, "get_weather");
+                assert_eq!(input, &serde_json::json!({"location": "San Francisco"}));
+            }
+            other => panic!("expected tool use, got {:?}", other),
+        }
+    }
 }
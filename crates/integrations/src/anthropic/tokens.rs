@@ -2,12 +2,21 @@
 //!
 //! Provides token counting, validation, and cost estimation utilities.
 
-use super::types::{ClaudeModel, MessageContent, MessageRequest, Role};
+use super::types::{ClaudeModel, ContentBlock, MessageContent, MessageRequest, Role};
 use anyhow::{anyhow, Result};
 use std::collections::HashMap;
+use thiserror::Error;
 use tracing::debug;
 
+#[cfg(feature = "accurate-tokenizer")]
+use std::sync::OnceLock;
+#[cfg(feature = "accurate-tokenizer")]
+use tiktoken_rs::CoreBPE;
+
 /// Token counter for Claude API
+///
+/// Counts are local estimates. For the count the API bills, use
+/// [`AnthropicClient::count_request_tokens`](super::AnthropicClient::count_request_tokens).
 pub struct TokenCounter {
     /// Cache for token counts
     cache: HashMap<String, u32>,
@@ -21,7 +30,7 @@ impl TokenCounter {
         }
     }
 
-    /// Count tokens in text (estimation)
+    /// Count tokens in text
     ///
     /// # Arguments
     ///
@@ -29,22 +38,21 @@ impl TokenCounter {
     ///
     /// # Returns
     ///
-    /// Returns estimated token count
+    /// Returns the estimated token count
     ///
     /// # Note
     ///
-    /// This is a rough estimation. For production use, consider using
-    /// the official Anthropic token counting API or a proper tokenizer.
+    /// With the `accurate-tokenizer` feature, text is encoded with OpenAI's
+    /// `cl100k_base` BPE vocabulary, which approximates Claude's tokenizer.
+    /// Otherwise the count is a rough ~4 characters per token estimate,
+    /// which drifts on code-heavy prompts.
     pub fn count_text(&mut self, text: &str) -> u32 {
         // Check cache first
         if let Some(&count) = self.cache.get(text) {
             return count;
         }
 
-        // Simple estimation: ~4 characters per token on average
-        // This is a rough approximation; actual tokenization varies
-        let char_count = text.chars().count();
-        let token_count = ((char_count as f32 / 4.0).ceil() as u32).max(1);
+        let token_count = tokenize(text).max(1);
 
         // Cache the result
         if self.cache.len() < 1000 {
@@ -52,7 +60,7 @@ impl TokenCounter {
             self.cache.insert(text.to_string(), token_count);
         }
 
-        debug!("Estimated {} tokens for {} characters", token_count, char_count);
+        debug!("Counted {} tokens for {} bytes", token_count, text.len());
 
         token_count
     }
@@ -85,20 +93,14 @@ impl TokenCounter {
                 }
                 MessageContent::Parts(parts) => {
                     for part in parts {
-                        match part {
-                            super::types::ContentBlock::Text { text } => {
-                                total += self.count_text(text);
-                            }
-                            super::types::ContentBlock::Image { .. } => {
-                                // Images are roughly 1000-2000 tokens depending on size
-                                total += 1500;
-                            }
-                        }
+                        total += self.count_block(part);
                     }
                 }
             }
         }
 
+        total += self.count_tools(request);
+
         // Add expected output tokens
         total += request.max_tokens;
 
@@ -137,19 +139,14 @@ impl TokenCounter {
                 }
                 MessageContent::Parts(parts) => {
                     for part in parts {
-                        match part {
-                            super::types::ContentBlock::Text { text } => {
-                                input_tokens += self.count_text(text);
-                            }
-                            super::types::ContentBlock::Image { .. } => {
-                                input_tokens += 1500;
-                            }
-                        }
+                        input_tokens += self.count_block(part);
                     }
                 }
             }
         }
 
+        input_tokens += self.count_tools(request);
+
         let output_tokens = request.max_tokens;
 
         let input_cost = (input_tokens as f64 / 1_000_000.0) * model.input_cost_per_mtok();
@@ -158,6 +155,38 @@ impl TokenCounter {
         input_cost + output_cost
     }
 
+    /// Count tokens in a single content block
+    fn count_block(&mut self, block: &ContentBlock) -> u32 {
+        match block {
+            ContentBlock::Text { text, .. } => self.count_text(text),
+            // Images are roughly 1000-2000 tokens depending on size
+            ContentBlock::Image { .. } => 1500,
+            ContentBlock::ToolUse { name, input, .. } => {
+                self.count_text(name) + self.count_text(&input.to_string())
+            }
+            ContentBlock::ToolResult { content, .. } => match content {
+                Some(MessageContent::Text(text)) => self.count_text(text),
+                Some(MessageContent::Parts(parts)) => {
+                    parts.iter().map(|part| self.count_block(part)).sum()
+                }
+                None => 0,
+            },
+        }
+    }
+
+    /// Count tokens in the tool definitions sent with a request
+    fn count_tools(&mut self, request: &MessageRequest) -> u32 {
+        request
+            .tools
+            .iter()
+            .flatten()
+            .map(|tool| {
+                let definition = serde_json::to_string(tool).unwrap_or_default();
+                self.count_text(&definition)
+            })
+            .sum()
+    }
+
     /// Clear the cache
     pub fn clear_cache(&mut self) {
         self.cache.clear();
@@ -175,6 +204,27 @@ impl Default for TokenCounter {
     }
 }
 
+/// BPE encoder shared by all counters
+///
+/// Claude's vocabulary is not published, so `cl100k_base` counts are still
+/// estimates, though closer than the character heuristic on code.
+#[cfg(feature = "accurate-tokenizer")]
+fn bpe() -> &'static CoreBPE {
+    static BPE: OnceLock<CoreBPE> = OnceLock::new();
+    BPE.get_or_init(|| tiktoken_rs::cl100k_base().expect("cl100k_base vocabulary is bundled"))
+}
+
+#[cfg(feature = "accurate-tokenizer")]
+fn tokenize(text: &str) -> u32 {
+    bpe().encode_ordinary(text).len() as u32
+}
+
+#[cfg(not(feature = "accurate-tokenizer"))]
+fn tokenize(text: &str) -> u32 {
+    // Simple estimation: ~4 characters per token on average
+    (text.chars().count() as f32 / 4.0).ceil() as u32
+}
+
 /// Token budget manager
 ///
 /// Helps manage token budgets for conversations and prevents exceeding limits.
@@ -254,6 +304,11 @@ impl TokenBudget {
         Ok(())
     }
 
+    /// Return previously allocated tokens to the budget
+    pub fn release(&mut self, tokens: u32) {
+        self.used_tokens = self.used_tokens.saturating_sub(tokens);
+    }
+
     /// Get remaining tokens available for input
     pub fn remaining(&self) -> u32 {
         self.max_tokens
@@ -282,6 +337,18 @@ impl TokenBudget {
     }
 }
 
+/// Returned when a request would not fit in the remaining token budget
+///
+/// The request is rejected before it is sent, so callers can stop cleanly.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Error)]
+#[error("Token budget exceeded: request needs ~{requested} tokens, {remaining} remaining")]
+pub struct BudgetExceededError {
+    /// Estimated tokens for the rejected request, including max output
+    pub requested: u32,
+    /// Tokens left in the budget
+    pub remaining: u32,
+}
+
 /// Validate a message request against model limits
 ///
 /// # Arguments
@@ -361,6 +428,29 @@ mod tests {
         assert_eq!(counter.cache_size(), 0);
     }
 
+    #[cfg(feature = "accurate-tokenizer")]
+    #[test]
+    fn test_accurate_count_matches_fixtures() {
+        // Reference counts from the cl100k_base vocabulary
+        let fixtures = [
+            ("Hello, world!", 4),
+            ("The quick brown fox jumps over the lazy dog.", 10),
+            ("tiktoken is great!", 6),
+        ];
+
+        let mut counter = TokenCounter::new();
+        for (text, expected) in fixtures {
+            let count = counter.count_text(text);
+            assert!(
+                count.abs_diff(expected) <= expected / 20,
+                "{:?}: counted {}, expected {}",
+                text,
+                count,
+                expected
+            );
+        }
+    }
+
     #[test]
     fn test_token_budget() {
         let mut budget = TokenBudget::new(1000, 100).unwrap();
@@ -404,6 +494,8 @@ mod tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(validate_request(&valid_request, ClaudeModel::Claude3Haiku).is_ok());
@@ -419,6 +511,8 @@ mod tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(validate_request(&invalid_request, ClaudeModel::Claude3Haiku).is_err());
@@ -2,6 +2,9 @@
 //!
 //! This module provides comprehensive type definitions for the Claude API.
 
+use super::cost_store::CostStoreConfig;
+use crate::retry::RetryPolicy;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -16,15 +19,21 @@ pub struct AnthropicConfig {
     /// Request timeout in seconds
     #[serde(default = "default_timeout")]
     pub timeout_secs: u64,
-    /// Maximum retry attempts
-    #[serde(default = "default_max_retries")]
-    pub max_retries: u32,
+    /// Retry policy for rate-limited, overloaded and failed requests
+    #[serde(default)]
+    pub retry: RetryPolicy,
     /// Rate limit: requests per minute (tier-specific)
     #[serde(default = "default_rate_limit")]
     pub rate_limit_per_minute: u32,
     /// API version
     #[serde(default = "default_api_version")]
     pub api_version: String,
+    /// Pricing overrides keyed by model id, for when published prices change
+    #[serde(default)]
+    pub pricing: HashMap<String, ModelPricing>,
+    /// Persist cost totals across restarts
+    #[serde(default)]
+    pub cost_store: Option<CostStoreConfig>,
 }
 
 fn default_base_url() -> String {
@@ -35,10 +44,6 @@ fn default_timeout() -> u64 {
     60
 }
 
-fn default_max_retries() -> u32 {
-    3
-}
-
 fn default_rate_limit() -> u32 {
     50
 }
@@ -47,12 +52,32 @@ fn default_api_version() -> String {
     "2023-06-01".to_string()
 }
 
+impl AnthropicConfig {
+    /// Configuration for the official API with default limits and pricing
+    #[must_use]
+    pub fn new(api_key: impl Into<String>) -> Self {
+        Self {
+            api_key: api_key.into(),
+            base_url: default_base_url(),
+            timeout_secs: default_timeout(),
+            retry: RetryPolicy::default(),
+            rate_limit_per_minute: default_rate_limit(),
+            api_version: default_api_version(),
+            pricing: HashMap::new(),
+            cost_store: None,
+        }
+    }
+}
+
 /// Claude model identifiers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ClaudeModel {
     /// Claude 3.5 Sonnet (latest)
     #[serde(rename = "claude-3-5-sonnet-20241022")]
     Claude35Sonnet,
+    /// Claude 3.5 Haiku
+    #[serde(rename = "claude-3-5-haiku-20241022")]
+    Claude35Haiku,
     /// Claude 3 Opus
     #[serde(rename = "claude-3-opus-20240229")]
     Claude3Opus,
@@ -69,6 +94,7 @@ impl ClaudeModel {
     pub fn as_str(&self) -> &'static str {
         match self {
             ClaudeModel::Claude35Sonnet => "claude-3-5-sonnet-20241022",
+            ClaudeModel::Claude35Haiku => "claude-3-5-haiku-20241022",
             ClaudeModel::Claude3Opus => "claude-3-opus-20240229",
             ClaudeModel::Claude3Sonnet => "claude-3-sonnet-20240229",
             ClaudeModel::Claude3Haiku => "claude-3-haiku-20240307",
@@ -79,6 +105,7 @@ impl ClaudeModel {
     pub fn max_tokens(&self) -> u32 {
         match self {
             ClaudeModel::Claude35Sonnet => 200_000,
+            ClaudeModel::Claude35Haiku => 200_000,
             ClaudeModel::Claude3Opus => 200_000,
             ClaudeModel::Claude3Sonnet => 200_000,
             ClaudeModel::Claude3Haiku => 200_000,
@@ -89,6 +116,7 @@ impl ClaudeModel {
     pub fn input_cost_per_mtok(&self) -> f64 {
         match self {
             ClaudeModel::Claude35Sonnet => 3.0,
+            ClaudeModel::Claude35Haiku => 0.8,
             ClaudeModel::Claude3Opus => 15.0,
             ClaudeModel::Claude3Sonnet => 3.0,
             ClaudeModel::Claude3Haiku => 0.25,
@@ -99,6 +127,7 @@ impl ClaudeModel {
     pub fn output_cost_per_mtok(&self) -> f64 {
         match self {
             ClaudeModel::Claude35Sonnet => 15.0,
+            ClaudeModel::Claude35Haiku => 4.0,
             ClaudeModel::Claude3Opus => 75.0,
             ClaudeModel::Claude3Sonnet => 15.0,
             ClaudeModel::Claude3Haiku => 1.25,
@@ -106,6 +135,57 @@ impl ClaudeModel {
     }
 }
 
+/// Prompt cache writes are billed at 125% of the base input rate
+pub const CACHE_WRITE_MULTIPLIER: f64 = 1.25;
+
+/// Prompt cache reads are billed at 10% of the base input rate
+pub const CACHE_READ_MULTIPLIER: f64 = 0.1;
+
+/// Message Batches usage is billed at 50% of the standard rate
+pub const BATCH_DISCOUNT: f64 = 0.5;
+
+/// Per-million-token pricing for a model (in USD)
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct ModelPricing {
+    /// Input token cost per million tokens
+    pub input_cost_per_mtok: f64,
+    /// Output token cost per million tokens
+    pub output_cost_per_mtok: f64,
+}
+
+impl ModelPricing {
+    /// Create pricing from per-million-token costs
+    pub fn new(input_cost_per_mtok: f64, output_cost_per_mtok: f64) -> Self {
+        Self {
+            input_cost_per_mtok,
+            output_cost_per_mtok,
+        }
+    }
+
+    /// Built-in pricing for a model
+    pub fn builtin(model: ClaudeModel) -> Self {
+        Self::new(model.input_cost_per_mtok(), model.output_cost_per_mtok())
+    }
+
+    /// Cost per million tokens written to the prompt cache
+    pub fn cache_write_cost_per_mtok(&self) -> f64 {
+        self.input_cost_per_mtok * CACHE_WRITE_MULTIPLIER
+    }
+
+    /// Cost per million tokens read from the prompt cache
+    pub fn cache_read_cost_per_mtok(&self) -> f64 {
+        self.input_cost_per_mtok * CACHE_READ_MULTIPLIER
+    }
+
+    /// Pricing for a model, preferring an override for its model id
+    pub fn resolve(model: ClaudeModel, overrides: &HashMap<String, ModelPricing>) -> Self {
+        overrides
+            .get(model.as_str())
+            .copied()
+            .unwrap_or_else(|| Self::builtin(model))
+    }
+}
+
 /// Message request to Claude API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MessageRequest {
@@ -136,6 +216,36 @@ pub struct MessageRequest {
     /// Optional metadata
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<MessageMetadata>,
+    /// Tools the model may call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<ToolDefinition>>,
+    /// How the model should choose among `tools`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+}
+
+/// Tool definition the model can call
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolDefinition {
+    /// Tool name
+    pub name: String,
+    /// What the tool does and when to use it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON Schema for the tool input
+    pub input_schema: serde_json::Value,
+}
+
+/// How the model should choose tools
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ToolChoice {
+    /// Let the model decide whether to call a tool
+    Auto,
+    /// Require the model to call one of the tools
+    Any,
+    /// Require the model to call the named tool
+    Tool { name: String },
 }
 
 /// Message in a conversation
@@ -173,12 +283,42 @@ pub enum MessageContent {
 pub enum ContentBlock {
     /// Text content
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        /// Marks the prompt prefix ending at this block as cacheable
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     /// Image content
     #[serde(rename = "image")]
     Image {
         source: ImageSource,
     },
+    /// Tool call requested by the model
+    #[serde(rename = "tool_use")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    /// Result of a tool call, sent back to the model
+    #[serde(rename = "tool_result")]
+    ToolResult {
+        tool_use_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        content: Option<MessageContent>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        is_error: Option<bool>,
+    },
+}
+
+/// Prompt cache breakpoint
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum CacheControl {
+    /// Short-lived cache entry (refreshed on each hit)
+    #[serde(rename = "ephemeral")]
+    Ephemeral,
 }
 
 /// Image source
@@ -241,28 +381,59 @@ pub enum StopReason {
     MaxTokens,
     /// Matched a stop sequence
     StopSequence,
+    /// Stopped to call a tool
+    ToolUse,
 }
 
 /// Token usage statistics
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
 pub struct Usage {
-    /// Number of input tokens
+    /// Number of input tokens (excluding cached tokens)
+    ///
+    /// Omitted from `message_delta` usage, which only reports output tokens.
+    #[serde(default)]
     pub input_tokens: u32,
     /// Number of output tokens
     pub output_tokens: u32,
+    /// Number of input tokens written to the prompt cache
+    #[serde(default)]
+    pub cache_creation_input_tokens: u32,
+    /// Number of input tokens read from the prompt cache
+    #[serde(default)]
+    pub cache_read_input_tokens: u32,
 }
 
 impl Usage {
     /// Calculate total cost in USD
     pub fn calculate_cost(&self, model: ClaudeModel) -> f64 {
-        let input_cost = (self.input_tokens as f64 / 1_000_000.0) * model.input_cost_per_mtok();
-        let output_cost = (self.output_tokens as f64 / 1_000_000.0) * model.output_cost_per_mtok();
-        input_cost + output_cost
+        self.calculate_cost_with(model, &HashMap::new())
+    }
+
+    /// Calculate total cost in USD, using the model's entry in `overrides`
+    /// when present and the built-in pricing otherwise
+    pub fn calculate_cost_with(
+        &self,
+        model: ClaudeModel,
+        overrides: &HashMap<String, ModelPricing>,
+    ) -> f64 {
+        let pricing = ModelPricing::resolve(model, overrides);
+        let input_cost = (self.input_tokens as f64 / 1_000_000.0) * pricing.input_cost_per_mtok;
+        let output_cost = (self.output_tokens as f64 / 1_000_000.0) * pricing.output_cost_per_mtok;
+        let cache_write_cost = (self.cache_creation_input_tokens as f64 / 1_000_000.0)
+            * pricing.cache_write_cost_per_mtok();
+        let cache_read_cost =
+            (self.cache_read_input_tokens as f64 / 1_000_000.0) * pricing.cache_read_cost_per_mtok();
+        input_cost + output_cost + cache_write_cost + cache_read_cost
+    }
+
+    /// Total input tokens, including cache writes and reads
+    pub fn total_input_tokens(&self) -> u32 {
+        self.input_tokens + self.cache_creation_input_tokens + self.cache_read_input_tokens
     }
 
     /// Total tokens used
     pub fn total_tokens(&self) -> u32 {
-        self.input_tokens + self.output_tokens
+        self.total_input_tokens() + self.output_tokens
     }
 }
 
@@ -331,6 +502,12 @@ pub enum ContentBlockStart {
     Text {
         text: String,
     },
+    #[serde(rename = "tool_use")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
 }
 
 /// Delta (incremental content)
@@ -341,6 +518,11 @@ pub enum Delta {
     TextDelta {
         text: String,
     },
+    /// Fragment of a tool call's JSON input
+    #[serde(rename = "input_json_delta")]
+    InputJsonDelta {
+        partial_json: String,
+    },
 }
 
 /// Message delta data
@@ -358,8 +540,135 @@ pub struct ApiError {
     pub message: String,
 }
 
-/// Rate limit information
-#[derive(Debug, Clone)]
+/// Single request within a message batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchRequest {
+    /// Caller-chosen ID used to match results to requests
+    pub custom_id: String,
+    /// Message request parameters
+    pub params: MessageRequest,
+}
+
+/// Processing status of a message batch
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BatchProcessingStatus {
+    /// Requests are still being processed
+    InProgress,
+    /// Cancellation was requested and is in progress
+    Canceling,
+    /// Processing has finished and results are available
+    Ended,
+}
+
+/// Number of batch requests in each state
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BatchRequestCounts {
+    /// Requests still being processed
+    #[serde(default)]
+    pub processing: u32,
+    /// Requests that completed successfully
+    #[serde(default)]
+    pub succeeded: u32,
+    /// Requests that failed
+    #[serde(default)]
+    pub errored: u32,
+    /// Requests canceled before processing
+    #[serde(default)]
+    pub canceled: u32,
+    /// Requests that expired before processing
+    #[serde(default)]
+    pub expired: u32,
+}
+
+/// Message batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MessageBatch {
+    /// Batch ID
+    pub id: String,
+    /// Object type (always "message_batch")
+    #[serde(rename = "type")]
+    pub type_field: String,
+    /// Processing status
+    pub processing_status: BatchProcessingStatus,
+    /// Request counts by state
+    pub request_counts: BatchRequestCounts,
+    /// Creation time (RFC 3339)
+    pub created_at: String,
+    /// Time after which unprocessed requests expire (RFC 3339)
+    pub expires_at: String,
+    /// Time processing ended (RFC 3339)
+    #[serde(default)]
+    pub ended_at: Option<String>,
+    /// Time cancellation was requested (RFC 3339)
+    #[serde(default)]
+    pub cancel_initiated_at: Option<String>,
+    /// URL of the JSONL results file, once the batch has ended
+    #[serde(default)]
+    pub results_url: Option<String>,
+}
+
+impl MessageBatch {
+    /// Whether processing has finished
+    pub fn is_ended(&self) -> bool {
+        self.processing_status == BatchProcessingStatus::Ended
+    }
+}
+
+/// Page of message batches
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchList {
+    /// Batches, most recent first
+    pub data: Vec<MessageBatch>,
+    /// Whether more batches follow this page
+    pub has_more: bool,
+    /// ID of the first batch in this page
+    pub first_id: Option<String>,
+    /// ID of the last batch in this page, used as the cursor for the next page
+    pub last_id: Option<String>,
+}
+
+/// Result of a single batch request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResult {
+    /// Custom ID of the originating request
+    pub custom_id: String,
+    /// Request outcome
+    pub result: BatchResultOutcome,
+}
+
+/// Outcome of a single batch request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BatchResultOutcome {
+    /// Request completed with a message
+    Succeeded {
+        /// Generated message
+        message: MessageResponse,
+    },
+    /// Request failed
+    Errored {
+        /// Failure details
+        error: BatchResultError,
+    },
+    /// Batch was canceled before the request was processed
+    Canceled,
+    /// Batch expired before the request was processed
+    Expired,
+}
+
+/// Error wrapper carried by errored batch results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BatchResultError {
+    /// Wrapper type (always "error")
+    #[serde(rename = "type")]
+    pub type_field: String,
+    /// Underlying API error
+    pub error: ApiError,
+}
+
+/// Rate limit information from the `anthropic-ratelimit-*` response headers
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct RateLimitInfo {
     /// Requests remaining in current window
     pub requests_remaining: Option<u32>,
@@ -369,21 +678,36 @@ pub struct RateLimitInfo {
     pub tokens_remaining: Option<u32>,
     /// Token limit per window
     pub tokens_limit: Option<u32>,
-    /// Time when rate limit resets (Unix timestamp)
+    /// Time when the request limit resets, or the token limit if only that
+    /// was reported (Unix timestamp)
     pub reset_at: Option<i64>,
 }
 
 /// Cost tracking information
-#[derive(Debug, Clone, Default)]
+///
+/// Serializes to the JSON written by [`CostTracker::save`].
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct CostTracker {
     /// Total input tokens used
     pub total_input_tokens: u64,
     /// Total output tokens used
     pub total_output_tokens: u64,
+    /// Total input tokens written to the prompt cache
+    pub total_cache_creation_tokens: u64,
+    /// Total input tokens served from the prompt cache
+    pub total_cache_read_tokens: u64,
     /// Total cost in USD
     pub total_cost: f64,
+    /// Portion of `total_cost` billed at Message Batches rates
+    pub total_batch_cost: f64,
     /// Number of requests made
     pub request_count: u64,
+    /// Start of the billing period these totals cover, once rolled over
+    pub period_start: Option<DateTime<Utc>>,
+    /// Pricing overrides keyed by model id
+    #[serde(skip)]
+    pub pricing: HashMap<String, ModelPricing>,
 }
 
 impl CostTracker {
@@ -392,11 +716,33 @@ impl CostTracker {
         Self::default()
     }
 
+    /// Create a cost tracker that prices usage with the given overrides
+    pub fn with_pricing(pricing: HashMap<String, ModelPricing>) -> Self {
+        Self {
+            pricing,
+            ..Self::default()
+        }
+    }
+
     /// Record usage from a response
     pub fn record_usage(&mut self, usage: &Usage, model: ClaudeModel) {
+        self.record_tokens(usage);
+        self.total_cost += usage.calculate_cost_with(model, &self.pricing);
+    }
+
+    /// Record usage from a Message Batches result, billed at [`BATCH_DISCOUNT`]
+    pub fn record_batch_usage(&mut self, usage: &Usage, model: ClaudeModel) {
+        self.record_tokens(usage);
+        let cost = usage.calculate_cost_with(model, &self.pricing) * BATCH_DISCOUNT;
+        self.total_cost += cost;
+        self.total_batch_cost += cost;
+    }
+
+    fn record_tokens(&mut self, usage: &Usage) {
         self.total_input_tokens += usage.input_tokens as u64;
         self.total_output_tokens += usage.output_tokens as u64;
-        self.total_cost += usage.calculate_cost(model);
+        self.total_cache_creation_tokens += usage.cache_creation_input_tokens as u64;
+        self.total_cache_read_tokens += usage.cache_read_input_tokens as u64;
         self.request_count += 1;
     }
 
@@ -414,7 +760,22 @@ impl CostTracker {
         if self.request_count == 0 {
             0.0
         } else {
-            (self.total_input_tokens + self.total_output_tokens) as f64 / self.request_count as f64
+            (self.total_input_tokens
+                + self.total_cache_creation_tokens
+                + self.total_cache_read_tokens
+                + self.total_output_tokens) as f64
+                / self.request_count as f64
+        }
+    }
+
+    /// Fraction of input tokens served from the prompt cache
+    pub fn cache_hit_ratio(&self) -> f64 {
+        let total_input =
+            self.total_input_tokens + self.total_cache_creation_tokens + self.total_cache_read_tokens;
+        if total_input == 0 {
+            0.0
+        } else {
+            self.total_cache_read_tokens as f64 / total_input as f64
         }
     }
 
@@ -422,7 +783,10 @@ impl CostTracker {
     pub fn reset(&mut self) {
         self.total_input_tokens = 0;
         self.total_output_tokens = 0;
+        self.total_cache_creation_tokens = 0;
+        self.total_cache_read_tokens = 0;
         self.total_cost = 0.0;
+        self.total_batch_cost = 0.0;
         self.request_count = 0;
     }
 }
@@ -436,6 +800,7 @@ mod tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         let cost_haiku = usage.calculate_cost(ClaudeModel::Claude3Haiku);
@@ -457,6 +822,7 @@ mod tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         tracker.record_usage(&usage, ClaudeModel::Claude3Haiku);
@@ -474,4 +840,225 @@ mod tests {
         assert_eq!(tracker.request_count, 0);
         assert_eq!(tracker.total_cost, 0.0);
     }
+
+    #[test]
+    fn test_claude_35_haiku_pricing() {
+        let model: ClaudeModel = serde_json::from_str("\"claude-3-5-haiku-20241022\"").unwrap();
+        assert_eq!(model, ClaudeModel::Claude35Haiku);
+        assert_eq!(model.as_str(), "claude-3-5-haiku-20241022");
+
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            ..Default::default()
+        };
+        assert!((usage.calculate_cost(model) - 4.8).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pricing_override_replaces_builtin_cost() {
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 500_000,
+            ..Default::default()
+        };
+        let overrides = HashMap::from([(
+            "claude-3-5-sonnet-20241022".to_string(),
+            ModelPricing::new(2.0, 10.0),
+        )]);
+
+        let cost = usage.calculate_cost_with(ClaudeModel::Claude35Sonnet, &overrides);
+        assert!((cost - 7.0).abs() < 1e-9);
+
+        let mut tracker = CostTracker::with_pricing(overrides);
+        tracker.record_usage(&usage, ClaudeModel::Claude35Sonnet);
+        assert!((tracker.total_cost - 7.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_pricing_override_falls_back_for_unlisted_model() {
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 1_000_000,
+            ..Default::default()
+        };
+        let overrides = HashMap::from([(
+            "claude-4-experimental".to_string(),
+            ModelPricing::new(100.0, 100.0),
+        )]);
+
+        assert_eq!(
+            usage.calculate_cost_with(ClaudeModel::Claude3Haiku, &overrides),
+            usage.calculate_cost(ClaudeModel::Claude3Haiku)
+        );
+        assert!((usage.calculate_cost_with(ClaudeModel::Claude3Haiku, &overrides) - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_tool_request_matches_documented_schema() {
+        let request = MessageRequest {
+            model: ClaudeModel::Claude35Sonnet.as_str().to_string(),
+            messages: vec![
+                Message {
+                    role: Role::User,
+                    content: MessageContent::Text("What's the weather in SF?".to_string()),
+                },
+                Message {
+                    role: Role::Assistant,
+                    content: MessageContent::Parts(vec![ContentBlock::ToolUse {
+                        id: "toolu_1".to_string(),
+                        name: "get_weather".to_string(),
+                        input: serde_json::json!({"location": "San Francisco"}),
+                    }]),
+                },
+                Message {
+                    role: Role::User,
+                    content: MessageContent::Parts(vec![ContentBlock::ToolResult {
+                        tool_use_id: "toolu_1".to_string(),
+                        content: Some(MessageContent::Text("15 degrees".to_string())),
+                        is_error: None,
+                    }]),
+                },
+            ],
+            max_tokens: 1024,
+            system: None,
+            temperature: None,
+            top_p: None,
+            top_k: None,
+            stop_sequences: None,
+            stream: false,
+            metadata: None,
+            tools: Some(vec![ToolDefinition {
+                name: "get_weather".to_string(),
+                description: Some("Get the current weather".to_string()),
+                input_schema: serde_json::json!({
+                    "type": "object",
+                    "properties": {"location": {"type": "string"}},
+                    "required": ["location"]
+                }),
+            }]),
+            tool_choice: Some(ToolChoice::Tool {
+                name: "get_weather".to_string(),
+            }),
+        };
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["tools"][0]["name"], "get_weather");
+        assert_eq!(value["tools"][0]["input_schema"]["required"][0], "location");
+        assert_eq!(value["tool_choice"], serde_json::json!({"type": "tool", "name": "get_weather"}));
+        assert_eq!(
+            value["messages"][1]["content"][0],
+            serde_json::json!({
+                "type": "tool_use",
+                "id": "toolu_1",
+                "name": "get_weather",
+                "input": {"location": "San Francisco"}
+            })
+        );
+        assert_eq!(
+            value["messages"][2]["content"][0],
+            serde_json::json!({"type": "tool_result", "tool_use_id": "toolu_1", "content": "15 degrees"})
+        );
+
+        let round_trip: MessageRequest = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&round_trip).unwrap(), value);
+
+        assert_eq!(serde_json::to_value(ToolChoice::Auto).unwrap(), serde_json::json!({"type": "auto"}));
+        assert_eq!(serde_json::to_value(ToolChoice::Any).unwrap(), serde_json::json!({"type": "any"}));
+    }
+
+    #[test]
+    fn test_tool_use_response_deserializes() {
+        let response: MessageResponse = serde_json::from_value(serde_json::json!({
+            "id": "msg_1",
+            "type": "message",
+            "role": "assistant",
+            "content": [
+                {"type": "text", "text": "Let me check."},
+                {"type": "tool_use", "id": "toolu_1", "name": "get_weather", "input": {"location": "SF"}}
+            ],
+            "model": "claude-3-5-sonnet-20241022",
+            "stop_reason": "tool_use",
+            "stop_sequence": null,
+            "usage": {"input_tokens": 10, "output_tokens": 20}
+        }))
+        .unwrap();
+
+        assert_eq!(response.stop_reason, Some(StopReason::ToolUse));
+        assert!(matches!(
+            &response.content[1],
+            ContentBlock::ToolUse { name, input, .. } if name == "get_weather" && input["location"] == "SF"
+        ));
+
+        let result: ContentBlock = serde_json::from_value(serde_json::json!({
+            "type": "tool_result",
+            "tool_use_id": "toolu_1",
+            "content": [{"type": "text", "text": "error: unknown city"}],
+            "is_error": true
+        }))
+        .unwrap();
+        assert!(matches!(
+            result,
+            ContentBlock::ToolResult { content: Some(MessageContent::Parts(_)), is_error: Some(true), .. }
+        ));
+    }
+
+    #[test]
+    fn test_cache_control_serializes_on_text_block() {
+        let block = ContentBlock::Text {
+            text: "You are a helpful assistant.".to_string(),
+            cache_control: Some(CacheControl::Ephemeral),
+        };
+        assert_eq!(
+            serde_json::to_value(&block).unwrap(),
+            serde_json::json!({
+                "type": "text",
+                "text": "You are a helpful assistant.",
+                "cache_control": {"type": "ephemeral"}
+            })
+        );
+
+        let plain = ContentBlock::Text {
+            text: "Hi".to_string(),
+            cache_control: None,
+        };
+        assert_eq!(
+            serde_json::to_value(&plain).unwrap(),
+            serde_json::json!({"type": "text", "text": "Hi"})
+        );
+    }
+
+    #[test]
+    fn test_usage_without_cache_fields_deserializes() {
+        let usage: Usage =
+            serde_json::from_str(r#"{"input_tokens": 10, "output_tokens": 20}"#).unwrap();
+        assert_eq!(usage.cache_creation_input_tokens, 0);
+        assert_eq!(usage.cache_read_input_tokens, 0);
+        assert_eq!(usage.total_tokens(), 30);
+    }
+
+    #[test]
+    fn test_cached_tokens_priced_and_tracked() {
+        let usage = Usage {
+            input_tokens: 1_000_000,
+            output_tokens: 0,
+            cache_creation_input_tokens: 1_000_000,
+            cache_read_input_tokens: 2_000_000,
+        };
+
+        // $3 base input + $3.75 cache write + 2 * $0.30 cache read
+        let cost = usage.calculate_cost(ClaudeModel::Claude35Sonnet);
+        assert!((cost - 7.35).abs() < 1e-9);
+
+        let mut tracker = CostTracker::new();
+        tracker.record_usage(&usage, ClaudeModel::Claude35Sonnet);
+        assert_eq!(tracker.total_input_tokens, 1_000_000);
+        assert_eq!(tracker.total_cache_creation_tokens, 1_000_000);
+        assert_eq!(tracker.total_cache_read_tokens, 2_000_000);
+        assert!((tracker.cache_hit_ratio() - 0.5).abs() < 1e-9);
+
+        tracker.reset();
+        assert_eq!(tracker.total_cache_read_tokens, 0);
+        assert_eq!(tracker.cache_hit_ratio(), 0.0);
+    }
 }
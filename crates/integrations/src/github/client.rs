@@ -0,0 +1,494 @@
+//! GitHub REST API client
+//!
+//! Issue, comment, and pull request operations against a single repository.
+//! Secondary rate limits are waited out and retried; an exhausted primary
+//! quota is reported immediately instead of blocking until it resets. Polled
+//! list endpoints send `If-None-Match` with the last `ETag`, so unchanged
+//! results come back as `304 Not Modified` without using up quota.
+
+use super::types::*;
+use crate::request_context::{current_request_id, with_request_id_header};
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, ETAG, IF_NONE_MATCH, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, info, instrument, warn};
+
+/// GitHub REST API version requested on every call
+const API_VERSION: &str = "2022-11-28";
+
+/// Wait applied to a secondary rate limit that carries no `Retry-After`;
+/// GitHub asks for at least a minute, doubled on each further hit
+const SECONDARY_RATE_LIMIT_BACKOFF: Duration = Duration::from_secs(60);
+
+/// Last successful response of a conditional GET
+#[derive(Debug, Clone)]
+struct CachedResponse {
+    etag: String,
+    body: serde_json::Value,
+}
+
+/// GitHub API client
+#[derive(Clone)]
+pub struct GitHubClient {
+    /// HTTP client
+    client: reqwest::Client,
+    /// REST API base URL, without a trailing slash
+    api_base_url: String,
+    /// `owner/repo` the client operates on
+    repository: String,
+    /// Retries allowed after a secondary rate limit
+    max_retries: u32,
+    /// Cached responses of conditional GETs, keyed by URL
+    etag_cache: Arc<Mutex<HashMap<String, CachedResponse>>>,
+}
+
+impl GitHubClient {
+    /// Create a new GitHub client
+    pub fn new(config: GitHubConfig) -> Result<Self, GitHubError> {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static("application/vnd.github+json"));
+        headers.insert("x-github-api-version", HeaderValue::from_static(API_VERSION));
+        let mut authorization = HeaderValue::from_str(&format!("Bearer {}", config.auth.token()))
+            .map_err(|e| GitHubError::InvalidToken(e.to_string()))?;
+        authorization.set_sensitive(true);
+        headers.insert(reqwest::header::AUTHORIZATION, authorization);
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .user_agent("llm-auto-optimizer/1.0")
+            .default_headers(headers)
+            .build()?;
+
+        let repository = format!("{}/{}", config.owner, config.repo);
+        info!("Initialized GitHub client for: {}", repository);
+
+        Ok(Self {
+            client,
+            api_base_url: config.api_base_url.trim_end_matches('/').to_string(),
+            repository,
+            max_retries: config.max_retries,
+            etag_cache: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Create an issue
+    #[instrument(skip_all, fields(title = %request.title, request_id = current_request_id()))]
+    pub async fn create_issue(&self, request: &CreateIssueRequest) -> Result<Issue, GitHubError> {
+        let url = self.repo_url("issues");
+        let issue: Issue = self.execute(self.client.post(url).json(request)).await?;
+        info!("Created issue #{}", issue.number);
+        Ok(issue)
+    }
+
+    /// Comment on an issue or pull request
+    #[instrument(skip(self, body), fields(request_id = current_request_id()))]
+    pub async fn add_comment(&self, issue_number: u64, body: &str) -> Result<Comment, GitHubError> {
+        let url = self.repo_url(&format!("issues/{issue_number}/comments"));
+        self.execute(
+            self.client
+                .post(url)
+                .json(&serde_json::json!({ "body": body })),
+        )
+        .await
+    }
+
+    /// List issues, reusing the cached result when GitHub reports no change
+    #[instrument(skip_all, fields(request_id = current_request_id()))]
+    pub async fn list_issues(&self, query: &ListIssuesQuery) -> Result<Vec<Issue>, GitHubError> {
+        let url = self.repo_url("issues");
+        self.get_conditional(self.client.get(url).query(&query.params()))
+            .await
+    }
+
+    /// Open a pull request
+    #[instrument(
+        skip_all,
+        fields(head = %request.head, base = %request.base, request_id = current_request_id())
+    )]
+    pub async fn create_pull_request(
+        &self,
+        request: &CreatePullRequest,
+    ) -> Result<PullRequest, GitHubError> {
+        let url = self.repo_url("pulls");
+        let pull: PullRequest = self.execute(self.client.post(url).json(request)).await?;
+        info!("Created pull request #{}", pull.number);
+        Ok(pull)
+    }
+
+    fn repo_url(&self, path: &str) -> String {
+        format!("{}/repos/{}/{}", self.api_base_url, self.repository, path)
+    }
+
+    /// Send a request and decode a successful JSON response
+    async fn execute<T: DeserializeOwned>(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<T, GitHubError> {
+        let response = self.send(request_builder).await?;
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+        Ok(response.json().await?)
+    }
+
+    /// GET with `If-None-Match`, serving the cached body on `304 Not Modified`
+    async fn get_conditional<T: DeserializeOwned>(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<T, GitHubError> {
+        let key = request_builder
+            .try_clone()
+            .and_then(|b| b.build().ok())
+            .map(|request| request.url().to_string())
+            .unwrap_or_default();
+        let cached = self.cache().get(&key).cloned();
+
+        let request_builder = match &cached {
+            Some(cached) => request_builder.header(IF_NONE_MATCH, &cached.etag),
+            None => request_builder,
+        };
+        let response = self.send(request_builder).await?;
+
+        if response.status() == StatusCode::NOT_MODIFIED {
+            if let Some(cached) = cached {
+                debug!("Not modified, using cached response for {}", key);
+                return Ok(serde_json::from_value(cached.body)?);
+            }
+        }
+        if !response.status().is_success() {
+            return Err(api_error(response).await);
+        }
+
+        let etag = response
+            .headers()
+            .get(ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(ToString::to_string);
+        let body: serde_json::Value = response.json().await?;
+        let value = serde_json::from_value(body.clone())?;
+
+        if let Some(etag) = etag {
+            self.cache().insert(key, CachedResponse { etag, body });
+        }
+        Ok(value)
+    }
+
+    fn cache(&self) -> std::sync::MutexGuard<'_, HashMap<String, CachedResponse>> {
+        self.etag_cache
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+
+    /// Send a request, waiting out secondary rate limits
+    ///
+    /// Any response other than a rate limit is returned for the caller to
+    /// interpret, including plain permission errors.
+    async fn send(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, GitHubError> {
+        let mut attempt = 0;
+
+        loop {
+            let request = with_request_id_header(
+                request_builder
+                    .try_clone()
+                    .expect("GitHub requests have in-memory JSON bodies"),
+            );
+            let response = request.send().await?;
+            let status = response.status();
+
+            if status != StatusCode::FORBIDDEN && status != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            let headers = response.headers();
+            let retry_after = header_u64(headers, RETRY_AFTER.as_str()).map(Duration::from_secs);
+            let backoff = SECONDARY_RATE_LIMIT_BACKOFF.saturating_mul(1 << attempt.min(4));
+
+            let delay = if let Some(retry_after) = retry_after {
+                retry_after
+            } else if header_u64(headers, "x-ratelimit-remaining") == Some(0) {
+                return Err(GitHubError::RateLimited {
+                    reset_at: header_u64(headers, "x-ratelimit-reset").unwrap_or_default(),
+                });
+            } else if status == StatusCode::TOO_MANY_REQUESTS {
+                backoff
+            } else {
+                // A 403 is only a secondary rate limit if the message says so
+                let error = api_error(response).await;
+                match &error {
+                    GitHubError::Api { message, .. }
+                        if message.to_lowercase().contains("secondary rate limit") =>
+                    {
+                        backoff
+                    }
+                    _ => return Err(error),
+                }
+            };
+
+            if attempt >= self.max_retries {
+                return Err(GitHubError::SecondaryRateLimited { retry_after: delay });
+            }
+
+            attempt += 1;
+            warn!(
+                attempt,
+                delay_secs = delay.as_secs(),
+                "Secondary rate limit hit on GitHub"
+            );
+            sleep(delay).await;
+        }
+    }
+}
+
+/// Build an API error from a non-success response
+async fn api_error(response: reqwest::Response) -> GitHubError {
+    let status = response.status().as_u16();
+    let text = response.text().await.unwrap_or_default();
+    let message = serde_json::from_str::<ErrorResponse>(&text)
+        .ok()
+        .map(|err| err.message)
+        .filter(|message| !message.is_empty())
+        .unwrap_or(text);
+
+    GitHubError::Api { status, message }
+}
+
+fn header_u64(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path, query_param};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn client_for(server: &MockServer) -> GitHubClient {
+        GitHubClient::new(GitHubConfig {
+            api_base_url: server.uri(),
+            auth: GitHubAuth::PersonalAccessToken {
+                token: "ghp_test".to_string(),
+            },
+            owner: "acme".to_string(),
+            repo: "optimizer".to_string(),
+            timeout_secs: 5,
+            max_retries: 2,
+        })
+        .unwrap()
+    }
+
+    fn issue_json(number: u64, title: &str) -> serde_json::Value {
+        serde_json::json!({
+            "number": number,
+            "title": title,
+            "state": "open",
+            "html_url": format!("https://github.com/acme/optimizer/issues/{number}"),
+            "labels": [{"name": "optimization-rollback"}]
+        })
+    }
+
+    #[tokio::test]
+    async fn test_create_rollback_issue() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/optimizer/issues"))
+            .and(header("authorization", "Bearer ghp_test"))
+            .and(header("x-github-api-version", API_VERSION))
+            .and(body_partial_json(serde_json::json!({
+                "title": "Optimization opt-1 rolled back on chat",
+                "labels": ["optimization-rollback"]
+            })))
+            .respond_with(
+                ResponseTemplate::new(201)
+                    .set_body_json(issue_json(7, "Optimization opt-1 rolled back on chat")),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let issue = client_for(&server)
+            .create_issue(&CreateIssueRequest::rollback("opt-1", "chat", "error rate doubled"))
+            .await
+            .unwrap();
+        assert_eq!(issue.number, 7);
+        assert_eq!(issue.labels[0].name, "optimization-rollback");
+    }
+
+    #[tokio::test]
+    async fn test_add_comment_and_create_pull_request() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/optimizer/issues/7/comments"))
+            .and(body_partial_json(serde_json::json!({"body": "Rolled forward"})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "id": 99,
+                "body": "Rolled forward",
+                "html_url": "https://github.com/acme/optimizer/issues/7#issuecomment-99"
+            })))
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/optimizer/pulls"))
+            .and(body_partial_json(serde_json::json!({"head": "tune-chat", "base": "main"})))
+            .respond_with(ResponseTemplate::new(201).set_body_json(serde_json::json!({
+                "number": 8,
+                "title": "Tune chat",
+                "state": "open",
+                "html_url": "https://github.com/acme/optimizer/pull/8",
+                "draft": true
+            })))
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let comment = client.add_comment(7, "Rolled forward").await.unwrap();
+        assert_eq!(comment.id, 99);
+
+        let pull = client
+            .create_pull_request(&CreatePullRequest {
+                title: "Tune chat".to_string(),
+                head: "tune-chat".to_string(),
+                base: "main".to_string(),
+                body: None,
+                draft: true,
+            })
+            .await
+            .unwrap();
+        assert_eq!(pull.number, 8);
+        assert!(pull.draft);
+    }
+
+    #[tokio::test]
+    async fn test_list_issues_uses_etag() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/optimizer/issues"))
+            .and(header("if-none-match", "\"v1\""))
+            .respond_with(ResponseTemplate::new(304))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/repos/acme/optimizer/issues"))
+            .and(query_param("labels", "optimization-rollback"))
+            .respond_with(
+                ResponseTemplate::new(200)
+                    .insert_header("ETag", "\"v1\"")
+                    .set_body_json(serde_json::json!([issue_json(7, "Rolled back")])),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let query = ListIssuesQuery {
+            labels: vec!["optimization-rollback".to_string()],
+            ..ListIssuesQuery::default()
+        };
+
+        let first = client.list_issues(&query).await.unwrap();
+        let second = client.list_issues(&query).await.unwrap();
+        assert_eq!(first.len(), 1);
+        assert_eq!(second[0].number, first[0].number);
+    }
+
+    #[tokio::test]
+    async fn test_secondary_rate_limit_is_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("Retry-After", "1")
+                    .set_body_json(serde_json::json!({
+                        "message": "You have exceeded a secondary rate limit."
+                    })),
+            )
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(201).set_body_json(issue_json(9, "Retried")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let started = std::time::Instant::now();
+        let issue = client_for(&server)
+            .create_issue(&CreateIssueRequest::new("Retried"))
+            .await
+            .unwrap();
+        assert_eq!(issue.number, 9);
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_secondary_rate_limit_gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let err = client_for(&server)
+            .create_issue(&CreateIssueRequest::new("Never"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitHubError::SecondaryRateLimited { .. }));
+    }
+
+    #[tokio::test]
+    async fn test_primary_rate_limit_and_permission_errors() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/optimizer/issues"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .insert_header("x-ratelimit-remaining", "0")
+                    .insert_header("x-ratelimit-reset", "1700000000")
+                    .set_body_json(serde_json::json!({"message": "API rate limit exceeded"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/repos/acme/optimizer/pulls"))
+            .respond_with(
+                ResponseTemplate::new(403)
+                    .set_body_json(serde_json::json!({"message": "Resource not accessible by integration"})),
+            )
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server);
+        let err = client
+            .create_issue(&CreateIssueRequest::new("Quota"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, GitHubError::RateLimited { reset_at: 1_700_000_000 }));
+
+        let err = client
+            .create_pull_request(&CreatePullRequest {
+                title: "Denied".to_string(),
+                head: "x".to_string(),
+                base: "main".to_string(),
+                body: None,
+                draft: false,
+            })
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "GitHub API error (403): Resource not accessible by integration"
+        );
+    }
+}
@@ -0,0 +1,49 @@
+//! GitHub REST API integration
+//!
+//! This module provides a GitHub client with:
+//! - Personal access token and GitHub App installation token authentication
+//! - Issue creation, listing, and comments
+//! - Pull request creation
+//! - Secondary rate limit handling
+//! - `ETag`-based conditional requests for polled lists
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use integrations::github::{CreateIssueRequest, GitHubAuth, GitHubClient, GitHubConfig};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = GitHubClient::new(GitHubConfig {
+//!         api_base_url: "https://api.github.com".to_string(),
+//!         auth: GitHubAuth::PersonalAccessToken {
+//!             token: "ghp_your_token".to_string(),
+//!         },
+//!         owner: "your-org".to_string(),
+//!         repo: "your-repo".to_string(),
+//!         timeout_secs: 30,
+//!         max_retries: 3,
+//!     })?;
+//!
+//!     // File an issue when an optimization is rolled back
+//!     let issue = client
+//!         .create_issue(
+//!             &CreateIssueRequest::rollback(
+//!                 "opt-42",
+//!                 "summarize",
+//!                 "p95 latency regressed by 35% after switching models",
+//!             )
+//!             .with_labels(["needs-triage"]),
+//!         )
+//!         .await?;
+//!
+//!     println!("Filed {}", issue.html_url);
+//!     Ok(())
+//! }
+//! ```
+
+pub mod client;
+pub mod types;
+
+pub use client::GitHubClient;
+pub use types::*;
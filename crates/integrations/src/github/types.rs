@@ -0,0 +1,360 @@
+//! GitHub API type definitions
+//!
+//! Configuration, error, and REST API v3 payload types for the GitHub client.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// GitHub authentication
+///
+/// Both kinds of token are sent as a bearer token; installation tokens are
+/// short-lived, so callers are expected to mint a fresh one per client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GitHubAuth {
+    /// Classic or fine-grained personal access token
+    PersonalAccessToken {
+        /// PAT token
+        token: String,
+    },
+    /// GitHub App installation access token
+    AppInstallation {
+        /// Installation token (`ghs_...`)
+        token: String,
+    },
+}
+
+impl GitHubAuth {
+    /// Token sent in the `Authorization` header
+    pub fn token(&self) -> &str {
+        match self {
+            Self::PersonalAccessToken { token } | Self::AppInstallation { token } => token,
+        }
+    }
+}
+
+/// GitHub client configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GitHubConfig {
+    /// REST API base URL (`https://api.github.com`, or `https://HOST/api/v3` for GHES)
+    #[serde(default = "default_api_base_url")]
+    pub api_base_url: String,
+    /// Authentication configuration
+    pub auth: GitHubAuth,
+    /// Repository owner (user or organization)
+    pub owner: String,
+    /// Repository name
+    pub repo: String,
+    /// Request timeout in seconds
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    /// Maximum retries after a secondary rate limit response
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_api_base_url() -> String {
+    "https://api.github.com".to_string()
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+/// Errors returned by the GitHub client
+#[derive(Debug, Error)]
+pub enum GitHubError {
+    /// The configured token cannot be sent as a header
+    #[error("Invalid GitHub token: {0}")]
+    InvalidToken(String),
+    /// The primary rate limit is used up until `reset_at`
+    #[error("GitHub rate limit exhausted until {reset_at} (unix time)")]
+    RateLimited {
+        /// Unix timestamp at which the quota resets
+        reset_at: u64,
+    },
+    /// A secondary rate limit was still in effect after all retries were used
+    #[error("GitHub secondary rate limit hit (retry after {}s)", retry_after.as_secs())]
+    SecondaryRateLimited {
+        /// Delay GitHub asked for, or the backoff that was last applied
+        retry_after: Duration,
+    },
+    /// GitHub rejected the request
+    #[error("GitHub API error ({status}): {message}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Error message from the response body
+        message: String,
+    },
+    /// A response body could not be decoded
+    #[error("Failed to decode GitHub response: {0}")]
+    Decode(#[from] serde_json::Error),
+    /// The request could not be sent or its response could not be read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Error body returned by the GitHub API
+#[derive(Debug, Clone, Deserialize)]
+pub(super) struct ErrorResponse {
+    /// Error message
+    #[serde(default)]
+    pub message: String,
+}
+
+/// Issue or pull request state
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum IssueState {
+    /// Open
+    Open,
+    /// Closed
+    Closed,
+    /// Either state; only valid as a list filter
+    All,
+}
+
+impl IssueState {
+    /// Value used in query strings
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Open => "open",
+            Self::Closed => "closed",
+            Self::All => "all",
+        }
+    }
+}
+
+/// Issue label
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Label {
+    /// Label name
+    pub name: String,
+}
+
+/// GitHub issue
+///
+/// The issues API also lists pull requests; those have `pull_request` set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Issue {
+    /// Issue number within the repository
+    pub number: u64,
+    /// Issue title
+    pub title: String,
+    /// Issue body (Markdown)
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Issue state
+    pub state: IssueState,
+    /// Browser URL
+    pub html_url: String,
+    /// Labels applied to the issue
+    #[serde(default)]
+    pub labels: Vec<Label>,
+    /// Present when the issue is a pull request
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pull_request: Option<serde_json::Value>,
+}
+
+/// Issue comment
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Comment {
+    /// Comment ID
+    pub id: u64,
+    /// Comment body (Markdown)
+    pub body: String,
+    /// Browser URL
+    pub html_url: String,
+}
+
+/// Pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PullRequest {
+    /// Pull request number within the repository
+    pub number: u64,
+    /// Pull request title
+    pub title: String,
+    /// Pull request state
+    pub state: IssueState,
+    /// Browser URL
+    pub html_url: String,
+    /// Whether the pull request is a draft
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Request to create an issue
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateIssueRequest {
+    /// Issue title
+    pub title: String,
+    /// Issue body (Markdown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Labels to apply
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub labels: Vec<String>,
+    /// Logins to assign
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub assignees: Vec<String>,
+}
+
+impl CreateIssueRequest {
+    /// Label applied to issues filed for rolled-back optimizations
+    pub const ROLLBACK_LABEL: &'static str = "optimization-rollback";
+
+    /// Label applied to issues filed when Claude spend crosses an alert threshold
+    pub const COST_ALERT_LABEL: &'static str = "cost-alert";
+
+    /// Create an issue request with a title only
+    pub fn new(title: impl Into<String>) -> Self {
+        Self {
+            title: title.into(),
+            body: None,
+            labels: Vec::new(),
+            assignees: Vec::new(),
+        }
+    }
+
+    /// Issue reporting that an optimization was rolled back and why
+    pub fn rollback(optimization_id: &str, service: &str, rationale: &str) -> Self {
+        Self::new(format!(
+            "Optimization {optimization_id} rolled back on {service}"
+        ))
+        .with_body(format!(
+            "Optimization `{optimization_id}` for service `{service}` was rolled back.\n\n\
+             ## Rationale\n\n{rationale}\n"
+        ))
+        .with_labels([Self::ROLLBACK_LABEL])
+    }
+
+    /// Issue reporting that Claude spend crossed an alert threshold
+    pub fn cost_alert(threshold: f64, total_cost: f64, request_count: u64) -> Self {
+        Self::new(format!("Claude spend crossed ${threshold:.2}"))
+            .with_body(format!(
+                "Cumulative Claude spend reached ${total_cost:.2} after {request_count} requests, \
+                 crossing the ${threshold:.2} alert threshold.\n"
+            ))
+            .with_labels([Self::COST_ALERT_LABEL])
+    }
+
+    /// Set the issue body
+    pub fn with_body(mut self, body: impl Into<String>) -> Self {
+        self.body = Some(body.into());
+        self
+    }
+
+    /// Add labels
+    pub fn with_labels<I, S>(mut self, labels: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.labels.extend(labels.into_iter().map(Into::into));
+        self
+    }
+
+    /// Add assignees
+    pub fn with_assignees<I, S>(mut self, assignees: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.assignees.extend(assignees.into_iter().map(Into::into));
+        self
+    }
+}
+
+/// Request to create a pull request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePullRequest {
+    /// Pull request title
+    pub title: String,
+    /// Branch containing the changes
+    pub head: String,
+    /// Branch the changes should be merged into
+    pub base: String,
+    /// Pull request body (Markdown)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub body: Option<String>,
+    /// Open as a draft
+    #[serde(default)]
+    pub draft: bool,
+}
+
+/// Filters for listing issues
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListIssuesQuery {
+    /// Issue state; GitHub defaults to open
+    pub state: Option<IssueState>,
+    /// Only issues carrying all of these labels
+    #[serde(default)]
+    pub labels: Vec<String>,
+    /// Results per page (max 100)
+    pub per_page: Option<u32>,
+}
+
+impl ListIssuesQuery {
+    /// Query string parameters for this filter
+    pub(super) fn params(&self) -> Vec<(&'static str, String)> {
+        let mut params = Vec::new();
+        if let Some(state) = self.state {
+            params.push(("state", state.as_str().to_string()));
+        }
+        if !self.labels.is_empty() {
+            params.push(("labels", self.labels.join(",")));
+        }
+        if let Some(per_page) = self.per_page {
+            params.push(("per_page", per_page.to_string()));
+        }
+        params
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rollback_issue() {
+        let request = CreateIssueRequest::rollback(
+            "opt-42",
+            "summarize",
+            "p95 latency regressed by 35% after switching models",
+        )
+        .with_labels(["needs-triage"]);
+
+        assert_eq!(request.title, "Optimization opt-42 rolled back on summarize");
+        assert!(request
+            .body
+            .as_deref()
+            .unwrap()
+            .contains("## Rationale\n\np95 latency regressed by 35%"));
+        assert_eq!(request.labels, vec!["optimization-rollback", "needs-triage"]);
+    }
+
+    #[test]
+    fn test_list_query_params() {
+        let query = ListIssuesQuery {
+            state: Some(IssueState::All),
+            labels: vec!["optimization-rollback".to_string(), "bug".to_string()],
+            per_page: Some(50),
+        };
+
+        assert_eq!(
+            query.params(),
+            vec![
+                ("state", "all".to_string()),
+                ("labels", "optimization-rollback,bug".to_string()),
+                ("per_page", "50".to_string()),
+            ]
+        );
+        assert!(ListIssuesQuery::default().params().is_empty());
+    }
+}
@@ -3,6 +3,7 @@
 //! Handles various authentication methods for Jira API.
 
 use super::types::{JiraAuth, JiraConfig};
+use crate::retry::RetryPolicy;
 use anyhow::{Context, Result};
 use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use std::sync::Arc;
@@ -164,9 +165,9 @@ impl AuthManager {
         std::time::Duration::from_secs(self.config.read().await.timeout_secs)
     }
 
-    /// Get max retries
-    pub async fn get_max_retries(&self) -> u32 {
-        self.config.read().await.max_retries
+    /// Get the retry policy
+    pub async fn get_retry_policy(&self) -> RetryPolicy {
+        self.config.read().await.retry
     }
 
     /// Get rate limit
@@ -228,8 +229,9 @@ mod tests {
                 api_token: "test-token".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: RetryPolicy::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let manager = AuthManager::new(config);
@@ -250,8 +252,9 @@ mod tests {
                 refresh_token: Some("refresh-token".to_string()),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: RetryPolicy::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let manager = AuthManager::new(config);
@@ -270,8 +273,9 @@ mod tests {
                 token: "pat-token".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: RetryPolicy::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let manager = AuthManager::new(config);
@@ -5,14 +5,15 @@
 
 use super::auth::AuthManager;
 use super::types::*;
+use crate::request_context::{current_request_id, insert_request_id_header};
+use crate::retry::AttemptError;
 use anyhow::{anyhow, Context, Result};
 use governor::{Quota, RateLimiter};
 use reqwest::StatusCode;
 use std::num::NonZeroU32;
 use std::sync::Arc;
-use std::time::Duration;
-use tokio::time::sleep;
-use tracing::{debug, error, info, warn};
+use std::time::{Duration, Instant};
+use tracing::{debug, error, field, info, instrument, warn, Span};
 
 /// Jira API client
 #[derive(Clone)]
@@ -23,6 +24,8 @@ pub struct JiraClient {
     auth: AuthManager,
     /// Rate limiter
     rate_limiter: Arc<RateLimiter<governor::state::direct::NotKeyed, governor::state::InMemoryState, governor::clock::DefaultClock>>,
+    /// Issues requested per page when paginating searches
+    page_size: u32,
 }
 
 impl JiraClient {
@@ -36,6 +39,10 @@ impl JiraClient {
     ///
     /// Returns a new JiraClient instance
     pub async fn new(config: JiraConfig) -> Result<Self> {
+        if config.page_size == 0 {
+            return Err(anyhow!("Page size must be greater than 0"));
+        }
+
         let auth = AuthManager::new(config.clone());
         let timeout = Duration::from_secs(config.timeout_secs);
 
@@ -60,6 +67,7 @@ impl JiraClient {
             client,
             auth,
             rate_limiter,
+            page_size: config.page_size,
         })
     }
 
@@ -211,6 +219,77 @@ impl JiraClient {
         Ok(response)
     }
 
+    /// Search issues and collect every page of results
+    ///
+    /// Follows `startAt`/`maxResults` pagination with the configured page
+    /// size until all matching issues are retrieved. Each page goes through
+    /// the rate limiter like any other request.
+    ///
+    /// # Arguments
+    ///
+    /// * `jql` - JQL query string
+    ///
+    /// # Returns
+    ///
+    /// Returns all matching issues
+    pub async fn search_all(&self, jql: &str) -> Result<Vec<Issue>> {
+        let mut issues: Vec<Issue> = Vec::new();
+
+        loop {
+            let page = self
+                .search_issues(JqlSearchRequest {
+                    jql: jql.to_string(),
+                    start_at: Some(issues.len() as u32),
+                    max_results: Some(self.page_size),
+                    fields: None,
+                })
+                .await?;
+
+            // Jira may cap maxResults below the requested page size, so
+            // advance by what was actually returned
+            let fetched = page.issues.len();
+            issues.extend(page.issues);
+
+            if fetched == 0 || issues.len() as u32 >= page.total {
+                break;
+            }
+        }
+
+        Ok(issues)
+    }
+
+    /// Get several issues by key
+    ///
+    /// Keys are looked up with `key in (...)` JQL queries of at most one page
+    /// each rather than one request per issue. Jira rejects the query if a
+    /// key does not exist.
+    ///
+    /// # Arguments
+    ///
+    /// * `keys` - Issue keys (e.g., "PROJ-123")
+    ///
+    /// # Returns
+    ///
+    /// Returns the issues in the order of `keys`
+    pub async fn get_issues(&self, keys: &[String]) -> Result<Vec<Issue>> {
+        let mut issues = Vec::with_capacity(keys.len());
+
+        for chunk in keys.chunks(self.page_size as usize) {
+            let quoted: Vec<String> = chunk.iter().map(|key| format!("\"{}\"", key)).collect();
+            let jql = format!("key in ({})", quoted.join(", "));
+            issues.extend(self.search_all(&jql).await?);
+        }
+
+        // Moved issues come back under their new key and sort last
+        issues.sort_by_key(|issue| {
+            keys.iter()
+                .position(|key| *key == issue.key)
+                .unwrap_or(usize::MAX)
+        });
+
+        Ok(issues)
+    }
+
     /// Get all projects
     ///
     /// # Returns
@@ -312,155 +391,151 @@ impl JiraClient {
     }
 
     /// Execute an HTTP request with retry logic and error handling
+    #[instrument(
+        name = "jira.request",
+        skip_all,
+        fields(
+            request_id = current_request_id(),
+            method = field::Empty,
+            url = field::Empty,
+            status = field::Empty,
+            latency_ms = field::Empty,
+            retry_count = field::Empty,
+        )
+    )]
     async fn execute_request<T: serde::de::DeserializeOwned>(
         &self,
         request_builder: reqwest::RequestBuilder,
     ) -> Result<T> {
-        // Wait for rate limiter
-        self.rate_limiter.until_ready().await;
-
-        let max_retries = self.auth.get_max_retries().await;
-        let mut last_error = None;
-
-        for attempt in 0..=max_retries {
-            if attempt > 0 {
-                let delay = Duration::from_secs(2u64.pow(attempt));
-                debug!("Retrying after {:?} (attempt {})", delay, attempt);
-                sleep(delay).await;
-            }
-
-            // Clone the request builder by rebuilding it
-            let headers = self.auth.get_auth_headers().await?;
-            let request = request_builder
-                .try_clone()
-                .ok_or_else(|| anyhow!("Failed to clone request"))?
-                .headers(headers);
-
-            match request.send().await {
-                Ok(response) => {
-                    let status = response.status();
-
-                    // Handle rate limiting
-                    if status == StatusCode::TOO_MANY_REQUESTS {
-                        warn!("Rate limited by Jira API");
-                        last_error = Some(anyhow!("Rate limited"));
-                        continue;
-                    }
-
-                    // Handle auth errors - try token refresh
-                    if status == StatusCode::UNAUTHORIZED {
-                        debug!("Unauthorized - attempting token refresh");
-                        if self.auth.refresh_token_if_needed(&self.client).await? {
-                            last_error = Some(anyhow!("Token expired, refreshed"));
-                            continue;
-                        }
-                    }
-
-                    // Handle success
-                    if response.status().is_success() {
-                        return response
-                            .json::<T>()
-                            .await
-                            .context("Failed to parse response JSON");
-                    }
-
-                    // Handle error responses
-                    let error_text = response.text().await.unwrap_or_default();
-                    let error_msg = if let Ok(err) = serde_json::from_str::<ErrorResponse>(&error_text) {
-                        format!(
-                            "Jira API error: {}",
-                            err.error_messages.join(", ")
-                        )
-                    } else {
-                        format!("Jira API error ({}): {}", status, error_text)
-                    };
-
-                    error!("{}", error_msg);
-                    last_error = Some(anyhow!(error_msg));
-
-                    // Don't retry on client errors (except auth/rate limit)
-                    if status.is_client_error() {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    warn!("Request failed: {}", e);
-                    last_error = Some(anyhow!(e));
-                }
-            }
-        }
-
-        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+        self.send_with_retry(request_builder)
+            .await?
+            .json::<T>()
+            .await
+            .context("Failed to parse response JSON")
     }
 
     /// Execute a request that doesn't return a body
+    #[instrument(
+        name = "jira.request",
+        skip_all,
+        fields(
+            request_id = current_request_id(),
+            method = field::Empty,
+            url = field::Empty,
+            status = field::Empty,
+            latency_ms = field::Empty,
+            retry_count = field::Empty,
+        )
+    )]
     async fn execute_request_no_response(
         &self,
         request_builder: reqwest::RequestBuilder,
     ) -> Result<()> {
+        self.send_with_retry(request_builder).await.map(|_| ())
+    }
+
+    /// Send a request under the configured retry policy, recording on the
+    /// current `jira.request` span, and return the successful response
+    async fn send_with_retry(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response> {
         // Wait for rate limiter
         self.rate_limiter.until_ready().await;
 
-        let max_retries = self.auth.get_max_retries().await;
-        let mut last_error = None;
-
-        for attempt in 0..=max_retries {
-            if attempt > 0 {
-                let delay = Duration::from_secs(2u64.pow(attempt));
-                debug!("Retrying after {:?} (attempt {})", delay, attempt);
-                sleep(delay).await;
-            }
+        let policy = self.auth.get_retry_policy().await;
+        let span = Span::current();
+        let started = Instant::now();
+        record_request_target(&span, &request_builder);
+
+        policy
+            .retry(|attempt| {
+                let (request_builder, span) = (&request_builder, &span);
+                async move {
+                    if attempt > 0 {
+                        span.record("retry_count", attempt);
+                    }
 
-            let headers = self.auth.get_auth_headers().await?;
-            let request = request_builder
-                .try_clone()
-                .ok_or_else(|| anyhow!("Failed to clone request"))?
-                .headers(headers);
+                    let mut headers = self
+                        .auth
+                        .get_auth_headers()
+                        .await
+                        .map_err(AttemptError::terminal)?;
+                    insert_request_id_header(&mut headers);
+                    let request = request_builder
+                        .try_clone()
+                        .ok_or_else(|| AttemptError::terminal(anyhow!("Failed to clone request")))?
+                        .headers(headers);
+
+                    let result = request.send().await;
+                    span.record("latency_ms", started.elapsed().as_millis() as u64);
+
+                    let response = match result {
+                        Ok(response) => response,
+                        Err(e) => {
+                            warn!("Request failed: {}", e);
+                            return Err(AttemptError::retryable(e));
+                        }
+                    };
 
-            match request.send().await {
-                Ok(response) => {
                     let status = response.status();
+                    span.record("status", status.as_u16());
 
-                    if status == StatusCode::TOO_MANY_REQUESTS {
-                        warn!("Rate limited by Jira API");
-                        last_error = Some(anyhow!("Rate limited"));
-                        continue;
+                    if status.is_success() {
+                        return Ok(response);
                     }
 
+                    // Handle auth errors - try token refresh
                     if status == StatusCode::UNAUTHORIZED {
                         debug!("Unauthorized - attempting token refresh");
-                        if self.auth.refresh_token_if_needed(&self.client).await? {
-                            last_error = Some(anyhow!("Token expired, refreshed"));
-                            continue;
+                        if self
+                            .auth
+                            .refresh_token_if_needed(&self.client)
+                            .await
+                            .map_err(AttemptError::terminal)?
+                        {
+                            return Err(AttemptError::retryable(anyhow!(
+                                "Token expired, refreshed"
+                            )));
                         }
                     }
 
-                    if response.status().is_success() {
-                        return Ok(());
+                    if status == StatusCode::TOO_MANY_REQUESTS {
+                        warn!("Rate limited by Jira API");
                     }
 
+                    let headers = response.headers().clone();
                     let error_text = response.text().await.unwrap_or_default();
-                    error!("Jira API error ({}): {}", status, error_text);
-                    last_error = Some(anyhow!("Jira API error: {}", error_text));
+                    let error_msg = match serde_json::from_str::<ErrorResponse>(&error_text) {
+                        Ok(err) if !err.messages().is_empty() => format!(
+                            "Jira API error ({}): {}",
+                            status,
+                            err.messages().join(", ")
+                        ),
+                        _ => format!("Jira API error ({}): {}", status, error_text),
+                    };
+                    error!("{}", error_msg);
 
-                    if status.is_client_error() {
-                        break;
-                    }
-                }
-                Err(e) => {
-                    warn!("Request failed: {}", e);
-                    last_error = Some(anyhow!(e));
+                    Err(AttemptError::from_response(status, &headers, anyhow!(error_msg)))
                 }
-            }
-        }
+            })
+            .await
+    }
+}
 
-        Err(last_error.unwrap_or_else(|| anyhow!("Request failed after retries")))
+/// Record the HTTP method and URL of a request on its tracing span
+fn record_request_target(span: &Span, request_builder: &reqwest::RequestBuilder) {
+    if let Some(request) = request_builder.try_clone().and_then(|b| b.build().ok()) {
+        span.record("method", request.method().as_str());
+        span.record("url", request.url().as_str());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use wiremock::matchers::{body_partial_json, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
 
     fn test_config() -> JiraConfig {
         JiraConfig {
@@ -470,8 +545,9 @@ mod tests {
                 api_token: "test-token".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         }
     }
 
@@ -489,4 +565,170 @@ mod tests {
         let client = JiraClient::new(config).await;
         assert!(client.is_err());
     }
+
+    #[tokio::test]
+    async fn test_invalid_page_size() {
+        let mut config = test_config();
+        config.page_size = 0;
+        let client = JiraClient::new(config).await;
+        assert!(client.is_err());
+    }
+
+    fn issue_json(key: &str) -> serde_json::Value {
+        serde_json::json!({
+            "id": key.trim_start_matches("PROJ-"),
+            "key": key,
+            "self": format!("https://test.atlassian.net/rest/api/3/issue/{}", key),
+            "fields": {
+                "summary": format!("Issue {}", key),
+                "description": null,
+                "issuetype": {"id": "1", "name": "Task", "description": null},
+                "status": {
+                    "id": "1",
+                    "name": "Open",
+                    "description": null,
+                    "statusCategory": {"id": 2, "key": "new", "name": "To Do", "colorName": "blue-gray"}
+                },
+                "priority": null,
+                "assignee": null,
+                "reporter": null,
+                "project": {
+                    "id": "10000",
+                    "key": "PROJ",
+                    "name": "Project",
+                    "description": null,
+                    "projectTypeKey": "software"
+                },
+                "created": "2024-01-01T00:00:00.000+0000",
+                "updated": "2024-01-01T00:00:00.000+0000"
+            }
+        })
+    }
+
+    async fn mock_page(server: &MockServer, start_at: u32, keys: &[&str], total: u32) {
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/search"))
+            .and(body_partial_json(serde_json::json!({"startAt": start_at})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "total": total,
+                "startAt": start_at,
+                "maxResults": 2,
+                "issues": keys.iter().map(|k| issue_json(k)).collect::<Vec<_>>(),
+            })))
+            .expect(1)
+            .mount(server)
+            .await;
+    }
+
+    async fn client_for(server: &MockServer) -> JiraClient {
+        let mut config = test_config();
+        config.base_url = server.uri();
+        config.page_size = 2;
+        JiraClient::new(config).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_search_all_follows_pagination() {
+        let server = MockServer::start().await;
+        mock_page(&server, 0, &["PROJ-1", "PROJ-2"], 5).await;
+        mock_page(&server, 2, &["PROJ-3", "PROJ-4"], 5).await;
+        mock_page(&server, 4, &["PROJ-5"], 5).await;
+
+        let client = client_for(&server).await;
+        let issues = client.search_all("project = PROJ").await.unwrap();
+
+        let keys: Vec<&str> = issues.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(keys, ["PROJ-1", "PROJ-2", "PROJ-3", "PROJ-4", "PROJ-5"]);
+    }
+
+    #[tokio::test]
+    async fn test_get_issues_batches_keys_and_keeps_order() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/search"))
+            .and(body_partial_json(serde_json::json!({
+                "jql": "key in (\"PROJ-3\", \"PROJ-1\")"
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "total": 2,
+                "startAt": 0,
+                "maxResults": 2,
+                "issues": [issue_json("PROJ-1"), issue_json("PROJ-3")],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/search"))
+            .and(body_partial_json(serde_json::json!({"jql": "key in (\"PROJ-2\")"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "total": 1,
+                "startAt": 0,
+                "maxResults": 2,
+                "issues": [issue_json("PROJ-2")],
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server).await;
+        let keys = ["PROJ-3", "PROJ-1", "PROJ-2"].map(String::from);
+        let issues = client.get_issues(&keys).await.unwrap();
+
+        let fetched: Vec<&str> = issues.iter().map(|i| i.key.as_str()).collect();
+        assert_eq!(fetched, ["PROJ-3", "PROJ-1", "PROJ-2"]);
+    }
+
+    #[tokio::test]
+    async fn test_server_errors_retried_under_policy() {
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/PROJ-1"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .expect(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("GET"))
+            .and(path("/rest/api/3/issue/PROJ-1"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(issue_json("PROJ-1")))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let mut config = test_config();
+        config.base_url = server.uri();
+        config.retry = crate::retry::RetryPolicy::default()
+            .with_max_retries(2)
+            .with_initial_delay(Duration::from_millis(10));
+        let client = JiraClient::new(config).await.unwrap();
+
+        let issue = client.get_issue("PROJ-1").await.unwrap();
+        assert_eq!(issue.key, "PROJ-1");
+    }
+
+    #[tokio::test]
+    async fn test_malformed_jql_surfaces_jira_message() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/rest/api/3/search"))
+            .respond_with(ResponseTemplate::new(400).set_body_json(serde_json::json!({
+                "errorMessages": [
+                    "Error in the JQL Query: Expecting operator but got 'PROJ'. (line 1, character 9)"
+                ],
+                "errors": {}
+            })))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = client_for(&server).await;
+        let err = client.search_all("project PROJ").await.unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "Jira API error (400 Bad Request): Error in the JQL Query: \
+             Expecting operator but got 'PROJ'. (line 1, character 9)"
+        );
+    }
 }
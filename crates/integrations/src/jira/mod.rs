@@ -23,8 +23,9 @@
 //!             api_token: "your-api-token".to_string(),
 //!         },
 //!         timeout_secs: 30,
-//!         max_retries: 3,
+//!         retry: Default::default(),
 //!         rate_limit_per_minute: 100,
+//!         page_size: 50,
 //!     };
 //!
 //!     let client = JiraClient::new(config).await?;
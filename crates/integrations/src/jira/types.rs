@@ -3,6 +3,7 @@
 //! This module provides comprehensive type definitions for Jira REST API v3.
 //! All types include Serde serialization/deserialization support.
 
+use crate::retry::RetryPolicy;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
@@ -45,26 +46,29 @@ pub struct JiraConfig {
     /// Request timeout in seconds
     #[serde(default = "default_timeout")]
     pub timeout_secs: u64,
-    /// Maximum retry attempts
-    #[serde(default = "default_max_retries")]
-    pub max_retries: u32,
+    /// Retry policy for rate-limited and failed requests
+    #[serde(default)]
+    pub retry: RetryPolicy,
     /// Rate limit: requests per minute
     #[serde(default = "default_rate_limit")]
     pub rate_limit_per_minute: u32,
+    /// Issues requested per page when following JQL pagination
+    #[serde(default = "default_page_size")]
+    pub page_size: u32,
 }
 
 fn default_timeout() -> u64 {
     30
 }
 
-fn default_max_retries() -> u32 {
-    3
-}
-
 fn default_rate_limit() -> u32 {
     100
 }
 
+fn default_page_size() -> u32 {
+    50
+}
+
 /// Jira issue representation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Issue {
@@ -361,11 +365,26 @@ pub struct ChangelogItem {
 /// API error response
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ErrorResponse {
-    #[serde(rename = "errorMessages")]
+    #[serde(rename = "errorMessages", default)]
     pub error_messages: Vec<String>,
+    #[serde(default)]
     pub errors: HashMap<String, String>,
 }
 
+impl ErrorResponse {
+    /// All error messages, including per-field errors as `field: message`
+    pub fn messages(&self) -> Vec<String> {
+        let mut fields: Vec<_> = self.errors.iter().collect();
+        fields.sort();
+
+        self.error_messages
+            .iter()
+            .cloned()
+            .chain(fields.into_iter().map(|(field, msg)| format!("{}: {}", field, msg)))
+            .collect()
+    }
+}
+
 /// Rate limit info
 #[derive(Debug, Clone)]
 pub struct RateLimitInfo {
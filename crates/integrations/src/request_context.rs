@@ -0,0 +1,82 @@
+//! Request ID propagation into outbound calls
+//!
+//! The API server runs each request inside [`with_request_id`]. Integration
+//! clients called from that task read the ID back with
+//! [`current_request_id`], record it on their tracing spans, and forward it
+//! in an `X-Request-Id` header so one request can be followed through the
+//! services it calls.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use integrations::request_context::{current_request_id, with_request_id};
+//!
+//! # async fn example() {
+//! with_request_id("req-42".to_string(), async {
+//!     assert_eq!(current_request_id().as_deref(), Some("req-42"));
+//! })
+//! .await;
+//! # }
+//! ```
+
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use std::future::Future;
+
+/// Header carrying the request ID on outbound requests
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+tokio::task_local! {
+    static REQUEST_ID: String;
+}
+
+/// Run `future` with `request_id` as the current request ID
+///
+/// The ID is task-local: work spawned onto other tasks must be wrapped
+/// again to keep it.
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    REQUEST_ID.scope(request_id, future).await
+}
+
+/// The request ID of the task, if it runs inside [`with_request_id`]
+pub fn current_request_id() -> Option<String> {
+    REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Add the current request ID to outbound `headers`
+///
+/// Does nothing outside [`with_request_id`], or if the ID is not a valid
+/// header value.
+pub fn insert_request_id_header(headers: &mut HeaderMap) {
+    if let Some(value) = current_request_id().and_then(|id| HeaderValue::from_str(&id).ok()) {
+        headers.insert(HeaderName::from_static(REQUEST_ID_HEADER), value);
+    }
+}
+
+/// Add the current request ID header to a request about to be sent
+pub fn with_request_id_header(request_builder: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+    let mut headers = HeaderMap::new();
+    insert_request_id_header(&mut headers);
+    request_builder.headers(headers)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_request_id_is_scoped_to_the_task() {
+        assert_eq!(current_request_id(), None);
+
+        let headers = with_request_id("req-42".to_string(), async {
+            let mut headers = HeaderMap::new();
+            insert_request_id_header(&mut headers);
+            headers
+        })
+        .await;
+        assert_eq!(headers[REQUEST_ID_HEADER], "req-42");
+
+        let mut headers = HeaderMap::new();
+        insert_request_id_header(&mut headers);
+        assert!(headers.is_empty());
+    }
+}
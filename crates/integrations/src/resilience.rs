@@ -0,0 +1,429 @@
+//! Resilience primitives shared by the integration clients
+//!
+//! Provides a [`CircuitBreaker`] that stops calling an external service after
+//! repeated failures and probes it again once a reset timeout has elapsed.
+//!
+//! # Example
+//!
+//! ```
+//! use integrations::resilience::{CircuitBreaker, CircuitBreakerConfig, CircuitState};
+//! use std::time::Duration;
+//!
+//! # async fn example() {
+//! let breaker = CircuitBreaker::new(
+//!     "anthropic",
+//!     CircuitBreakerConfig::default()
+//!         .with_failure_threshold(3)
+//!         .with_reset_timeout(Duration::from_secs(30)),
+//! );
+//!
+//! let result = breaker.call(|| async { Ok::<_, String>("pong") }).await;
+//! assert!(result.is_ok());
+//! assert_eq!(breaker.state(), CircuitState::Closed);
+//! # }
+//! ```
+
+use std::fmt;
+use std::future::Future;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+use thiserror::Error;
+use tracing::{info, warn};
+
+/// Circuit breaker state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Calls pass through; failures are counted
+    Closed,
+    /// Calls are rejected until the reset timeout elapses
+    Open,
+    /// Trial calls are let through to probe whether the service has recovered
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Closed => write!(f, "closed"),
+            Self::Open => write!(f, "open"),
+            Self::HalfOpen => write!(f, "half_open"),
+        }
+    }
+}
+
+/// Circuit breaker configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures that open the circuit
+    pub failure_threshold: u32,
+    /// How long the circuit stays open before trial calls are allowed
+    pub reset_timeout: Duration,
+    /// Consecutive successful trial calls needed to close the circuit again
+    pub half_open_trials: u32,
+}
+
+impl CircuitBreakerConfig {
+    /// Set the number of consecutive failures that open the circuit
+    pub const fn with_failure_threshold(mut self, failure_threshold: u32) -> Self {
+        self.failure_threshold = failure_threshold;
+        self
+    }
+
+    /// Set how long the circuit stays open
+    pub const fn with_reset_timeout(mut self, reset_timeout: Duration) -> Self {
+        self.reset_timeout = reset_timeout;
+        self
+    }
+
+    /// Set the number of successful trial calls needed to close the circuit
+    pub const fn with_half_open_trials(mut self, half_open_trials: u32) -> Self {
+        self.half_open_trials = half_open_trials;
+        self
+    }
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            reset_timeout: Duration::from_secs(30),
+            half_open_trials: 2,
+        }
+    }
+}
+
+/// Hooks for exporting circuit breaker activity as metrics
+///
+/// All methods default to no-ops, so observers only implement what they need.
+pub trait CircuitBreakerObserver: Send + Sync {
+    /// The circuit moved from one state to another
+    fn on_state_change(&self, _name: &str, _from: CircuitState, _to: CircuitState) {}
+
+    /// A call was rejected because the circuit is open
+    fn on_rejected(&self, _name: &str) {}
+
+    /// A call went through and completed
+    fn on_call(&self, _name: &str, _success: bool) {}
+}
+
+/// Returned when a call is rejected because the circuit is open
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("Circuit breaker '{name}' is open, retry after {retry_after:?}")]
+pub struct CircuitOpenError {
+    /// Name of the circuit breaker
+    pub name: String,
+    /// Time until trial calls are allowed again
+    pub retry_after: Duration,
+}
+
+/// Error from a call made through [`CircuitBreaker::call`]
+#[derive(Debug, Error)]
+pub enum CircuitBreakerError<E> {
+    /// The call was rejected without being made
+    #[error(transparent)]
+    Open(CircuitOpenError),
+    /// The call was made and failed
+    #[error("{0}")]
+    Inner(E),
+}
+
+#[derive(Debug)]
+struct BreakerState {
+    state: CircuitState,
+    consecutive_failures: u32,
+    trial_successes: u32,
+    opened_at: Option<Instant>,
+}
+
+/// Circuit breaker guarding calls to an external service
+pub struct CircuitBreaker {
+    name: String,
+    config: CircuitBreakerConfig,
+    state: Mutex<BreakerState>,
+    observer: Option<Arc<dyn CircuitBreakerObserver>>,
+}
+
+impl CircuitBreaker {
+    /// Create a closed circuit breaker
+    pub fn new(name: impl Into<String>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            name: name.into(),
+            config,
+            state: Mutex::new(BreakerState {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                trial_successes: 0,
+                opened_at: None,
+            }),
+            observer: None,
+        }
+    }
+
+    /// Report state changes and calls to an observer
+    pub fn with_observer(mut self, observer: Arc<dyn CircuitBreakerObserver>) -> Self {
+        self.observer = Some(observer);
+        self
+    }
+
+    /// Circuit breaker name
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Current state
+    pub fn state(&self) -> CircuitState {
+        self.lock().state
+    }
+
+    /// Run `f` through the circuit breaker
+    ///
+    /// Any `Err` from `f` counts as a failure. Callers that need to decide
+    /// which errors count should use [`try_acquire`](Self::try_acquire) with
+    /// [`record_success`](Self::record_success) and
+    /// [`record_failure`](Self::record_failure) instead.
+    pub async fn call<F, Fut, T, E>(&self, f: F) -> Result<T, CircuitBreakerError<E>>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.try_acquire().map_err(CircuitBreakerError::Open)?;
+
+        match f().await {
+            Ok(value) => {
+                self.record_success();
+                Ok(value)
+            }
+            Err(e) => {
+                self.record_failure();
+                Err(CircuitBreakerError::Inner(e))
+            }
+        }
+    }
+
+    /// Check whether a call may be made now
+    ///
+    /// Moves an open circuit to half-open once the reset timeout has elapsed.
+    pub fn try_acquire(&self) -> Result<(), CircuitOpenError> {
+        let mut state = self.lock();
+
+        if state.state == CircuitState::Open {
+            let elapsed = state.opened_at.map_or(Duration::MAX, |at| at.elapsed());
+            if elapsed < self.config.reset_timeout {
+                drop(state);
+                if let Some(observer) = &self.observer {
+                    observer.on_rejected(&self.name);
+                }
+                return Err(CircuitOpenError {
+                    name: self.name.clone(),
+                    retry_after: self.config.reset_timeout.saturating_sub(elapsed),
+                });
+            }
+
+            state.trial_successes = 0;
+            self.transition(&mut state, CircuitState::HalfOpen);
+        }
+
+        Ok(())
+    }
+
+    /// Record a successful call
+    pub fn record_success(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+
+        if state.state == CircuitState::HalfOpen {
+            state.trial_successes += 1;
+            if state.trial_successes >= self.config.half_open_trials {
+                self.transition(&mut state, CircuitState::Closed);
+            }
+        }
+        drop(state);
+
+        if let Some(observer) = &self.observer {
+            observer.on_call(&self.name, true);
+        }
+    }
+
+    /// Record a failed call
+    pub fn record_failure(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures += 1;
+
+        let should_open = match state.state {
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => state.consecutive_failures >= self.config.failure_threshold,
+            CircuitState::Open => false,
+        };
+        if should_open {
+            state.opened_at = Some(Instant::now());
+            self.transition(&mut state, CircuitState::Open);
+        }
+        drop(state);
+
+        if let Some(observer) = &self.observer {
+            observer.on_call(&self.name, false);
+        }
+    }
+
+    /// Close the circuit and clear all counters
+    pub fn reset(&self) {
+        let mut state = self.lock();
+        state.consecutive_failures = 0;
+        state.trial_successes = 0;
+        state.opened_at = None;
+        self.transition(&mut state, CircuitState::Closed);
+    }
+
+    fn transition(&self, state: &mut BreakerState, to: CircuitState) {
+        let from = state.state;
+        if from == to {
+            return;
+        }
+        state.state = to;
+
+        if to == CircuitState::Open {
+            warn!(breaker = %self.name, "Circuit breaker opened");
+        } else {
+            info!(breaker = %self.name, from = %from, to = %to, "Circuit breaker state changed");
+        }
+        if let Some(observer) = &self.observer {
+            observer.on_state_change(&self.name, from, to);
+        }
+    }
+
+    fn lock(&self) -> MutexGuard<'_, BreakerState> {
+        // The state is always left consistent, so a poisoned lock is still usable
+        self.state.lock().unwrap_or_else(std::sync::PoisonError::into_inner)
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("name", &self.name)
+            .field("config", &self.config)
+            .field("state", &self.state())
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    fn breaker(failure_threshold: u32, reset_timeout: Duration, half_open_trials: u32) -> CircuitBreaker {
+        CircuitBreaker::new(
+            "test",
+            CircuitBreakerConfig::default()
+                .with_failure_threshold(failure_threshold)
+                .with_reset_timeout(reset_timeout)
+                .with_half_open_trials(half_open_trials),
+        )
+    }
+
+    async fn fail(breaker: &CircuitBreaker) -> Result<(), CircuitBreakerError<&'static str>> {
+        breaker.call(|| async { Err("boom") }).await
+    }
+
+    async fn succeed(breaker: &CircuitBreaker) -> Result<(), CircuitBreakerError<&'static str>> {
+        breaker.call(|| async { Ok(()) }).await
+    }
+
+    #[tokio::test]
+    async fn test_opens_after_threshold_and_rejects_calls() {
+        let breaker = breaker(3, Duration::from_secs(60), 1);
+
+        for _ in 0..2 {
+            assert!(matches!(fail(&breaker).await, Err(CircuitBreakerError::Inner("boom"))));
+        }
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        let _ = fail(&breaker).await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let calls = AtomicUsize::new(0);
+        let result = breaker
+            .call(|| async {
+                calls.fetch_add(1, Ordering::SeqCst);
+                Ok::<_, &str>(())
+            })
+            .await;
+        assert!(matches!(result, Err(CircuitBreakerError::Open(ref e)) if e.name == "test"));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_half_open_needs_trial_successes_to_close() {
+        let breaker = breaker(1, Duration::from_millis(20), 2);
+        let _ = fail(&breaker).await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        succeed(&breaker).await.unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        succeed(&breaker).await.unwrap();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_failure_while_half_open_reopens() {
+        let breaker = breaker(2, Duration::from_millis(20), 2);
+        let _ = fail(&breaker).await;
+        let _ = fail(&breaker).await;
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        let _ = fail(&breaker).await;
+        assert_eq!(breaker.state(), CircuitState::Open);
+        assert!(matches!(succeed(&breaker).await, Err(CircuitBreakerError::Open(_))));
+
+        breaker.reset();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn test_observer_sees_transitions_and_rejections() {
+        #[derive(Default)]
+        struct Recorder {
+            transitions: Mutex<Vec<(CircuitState, CircuitState)>>,
+            rejected: AtomicUsize,
+            failures: AtomicUsize,
+        }
+
+        impl CircuitBreakerObserver for Recorder {
+            fn on_state_change(&self, _name: &str, from: CircuitState, to: CircuitState) {
+                self.transitions.lock().unwrap().push((from, to));
+            }
+
+            fn on_rejected(&self, _name: &str) {
+                self.rejected.fetch_add(1, Ordering::SeqCst);
+            }
+
+            fn on_call(&self, _name: &str, success: bool) {
+                if !success {
+                    self.failures.fetch_add(1, Ordering::SeqCst);
+                }
+            }
+        }
+
+        let recorder = Arc::new(Recorder::default());
+        let breaker = breaker(1, Duration::from_millis(20), 1).with_observer(recorder.clone());
+
+        let _ = fail(&breaker).await;
+        let _ = succeed(&breaker).await;
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        succeed(&breaker).await.unwrap();
+
+        assert_eq!(
+            *recorder.transitions.lock().unwrap(),
+            vec![
+                (CircuitState::Closed, CircuitState::Open),
+                (CircuitState::Open, CircuitState::HalfOpen),
+                (CircuitState::HalfOpen, CircuitState::Closed),
+            ]
+        );
+        assert_eq!(recorder.rejected.load(Ordering::SeqCst), 1);
+        assert_eq!(recorder.failures.load(Ordering::SeqCst), 1);
+    }
+}
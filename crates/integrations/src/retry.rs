@@ -0,0 +1,317 @@
+//! Retry policy shared by the integration clients
+//!
+//! A [`RetryPolicy`] decides how often and how long to wait between attempts,
+//! and [`RetryPolicy::retry`] runs an operation under it. Each attempt reports
+//! failure as an [`AttemptError`], which says whether the failure is worth
+//! retrying and carries any `Retry-After` delay the server asked for.
+//!
+//! # Example
+//!
+//! ```
+//! use integrations::retry::{AttemptError, BackoffStrategy, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let policy = RetryPolicy::default()
+//!     .with_max_retries(2)
+//!     .with_strategy(BackoffStrategy::ExponentialJitter)
+//!     .with_initial_delay(Duration::from_millis(10));
+//!
+//! let value = policy
+//!     .retry(|attempt| async move {
+//!         if attempt == 0 {
+//!             Err(AttemptError::retryable(anyhow::anyhow!("connection reset")))
+//!         } else {
+//!             Ok(42)
+//!         }
+//!     })
+//!     .await?;
+//! assert_eq!(value, 42);
+//! # Ok(())
+//! # }
+//! ```
+
+use rand::Rng;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::info;
+
+/// How the delay between attempts grows
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackoffStrategy {
+    /// Wait `initial_delay` before every retry
+    Fixed,
+    /// Double the delay on every retry, starting at `initial_delay`
+    #[default]
+    Exponential,
+    /// Exponential, but each delay is drawn between half and all of the
+    /// exponential value so concurrent clients don't retry in lockstep
+    ExponentialJitter,
+}
+
+/// Retry policy for requests to an external service
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Retries after the first attempt
+    pub max_retries: u32,
+    /// How the delay grows between retries
+    pub strategy: BackoffStrategy,
+    /// Delay before the first retry, in milliseconds
+    pub initial_delay_ms: u64,
+    /// Upper bound on computed delays, in milliseconds; `Retry-After` is
+    /// honored even when longer
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            strategy: BackoffStrategy::Exponential,
+            initial_delay_ms: 2_000,
+            max_delay_ms: 60_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Policy that never retries
+    pub fn none() -> Self {
+        Self::default().with_max_retries(0)
+    }
+
+    /// Set the number of retries after the first attempt
+    pub const fn with_max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Set the backoff strategy
+    pub const fn with_strategy(mut self, strategy: BackoffStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Set the delay before the first retry
+    pub const fn with_initial_delay(mut self, delay: Duration) -> Self {
+        self.initial_delay_ms = delay.as_millis() as u64;
+        self
+    }
+
+    /// Set the upper bound on computed delays
+    pub const fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay_ms = delay.as_millis() as u64;
+        self
+    }
+
+    /// Delay before retry number `retry` (1-based), ignoring `Retry-After`
+    pub fn delay(&self, retry: u32) -> Duration {
+        let initial = Duration::from_millis(self.initial_delay_ms);
+        let max = Duration::from_millis(self.max_delay_ms);
+        let exponential = || {
+            initial
+                .saturating_mul(2u32.saturating_pow(retry.saturating_sub(1)))
+                .min(max)
+        };
+
+        match self.strategy {
+            BackoffStrategy::Fixed => initial.min(max),
+            BackoffStrategy::Exponential => exponential(),
+            BackoffStrategy::ExponentialJitter => {
+                let ceiling = exponential();
+                let half = ceiling / 2;
+                half + (ceiling - half).mul_f64(rand::thread_rng().gen::<f64>())
+            }
+        }
+    }
+
+    /// Run `operation` until it succeeds, fails terminally, or runs out of
+    /// retries
+    ///
+    /// `operation` receives the 0-based attempt number. Between attempts the
+    /// executor waits for the server's `Retry-After` when the failure carried
+    /// one, and the policy's backoff otherwise. The last error is returned
+    /// unchanged.
+    pub async fn retry<F, Fut, T>(&self, mut operation: F) -> anyhow::Result<T>
+    where
+        F: FnMut(u32) -> Fut,
+        Fut: Future<Output = Result<T, AttemptError>>,
+    {
+        let mut attempt = 0;
+
+        loop {
+            match operation(attempt).await {
+                Ok(value) => return Ok(value),
+                Err(failure) if !failure.retryable || attempt >= self.max_retries => {
+                    return Err(failure.error);
+                }
+                Err(failure) => {
+                    attempt += 1;
+                    let delay = failure.retry_after.unwrap_or_else(|| self.delay(attempt));
+                    info!(
+                        attempt,
+                        delay_ms = delay.as_millis() as u64,
+                        reason = %failure.error,
+                        "Retrying request"
+                    );
+                    sleep(delay).await;
+                }
+            }
+        }
+    }
+}
+
+/// Whether a response status is worth retrying: 429 and any 5xx
+///
+/// Other 4xx responses mean the request itself is wrong and are terminal.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS || status.is_server_error()
+}
+
+/// Read a `Retry-After` header given in seconds
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Failure of a single attempt
+#[derive(Debug)]
+pub struct AttemptError {
+    /// The error returned if no further attempt is made
+    pub error: anyhow::Error,
+    /// Whether another attempt may succeed
+    pub retryable: bool,
+    /// Delay the server asked for before the next attempt
+    pub retry_after: Option<Duration>,
+}
+
+impl AttemptError {
+    /// A failure that another attempt may fix, such as a dropped connection
+    pub fn retryable(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            error: error.into(),
+            retryable: true,
+            retry_after: None,
+        }
+    }
+
+    /// A failure that retrying cannot fix
+    pub fn terminal(error: impl Into<anyhow::Error>) -> Self {
+        Self {
+            error: error.into(),
+            retryable: false,
+            retry_after: None,
+        }
+    }
+
+    /// A failed HTTP response, classified with [`is_retryable_status`] and
+    /// carrying its `Retry-After` header
+    pub fn from_response(
+        status: StatusCode,
+        headers: &HeaderMap,
+        error: impl Into<anyhow::Error>,
+    ) -> Self {
+        Self {
+            error: error.into(),
+            retryable: is_retryable_status(status),
+            retry_after: retry_after(headers),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::anyhow;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    fn policy(strategy: BackoffStrategy) -> RetryPolicy {
+        RetryPolicy::default()
+            .with_strategy(strategy)
+            .with_initial_delay(Duration::from_millis(100))
+            .with_max_delay(Duration::from_millis(350))
+    }
+
+    #[test]
+    fn test_backoff_strategies() {
+        let delays = |p: RetryPolicy| (1..=4).map(|n| p.delay(n).as_millis()).collect::<Vec<_>>();
+
+        assert_eq!(delays(policy(BackoffStrategy::Fixed)), vec![100, 100, 100, 100]);
+        assert_eq!(delays(policy(BackoffStrategy::Exponential)), vec![100, 200, 350, 350]);
+
+        let jittered = policy(BackoffStrategy::ExponentialJitter);
+        for (retry, ceiling) in [(1, 100), (2, 200), (3, 350)] {
+            for _ in 0..20 {
+                let delay = jittered.delay(retry).as_millis();
+                assert!((ceiling / 2..=ceiling).contains(&delay), "{delay} for {retry}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        assert!(is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::from_u16(529).unwrap()));
+        assert!(!is_retryable_status(StatusCode::BAD_REQUEST));
+        assert!(!is_retryable_status(StatusCode::UNAUTHORIZED));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_honors_retry_after_and_stops_on_terminal() {
+        let calls = AtomicU32::new(0);
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "7".parse().unwrap());
+
+        let started = tokio::time::Instant::now();
+        let err = policy(BackoffStrategy::Fixed)
+            .retry(|attempt| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                let headers = &headers;
+                async move {
+                    let status = if attempt == 0 {
+                        StatusCode::TOO_MANY_REQUESTS
+                    } else {
+                        StatusCode::BAD_REQUEST
+                    };
+                    Err::<(), _>(AttemptError::from_response(status, headers, anyhow!("{status}")))
+                }
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(started.elapsed(), Duration::from_secs(7));
+        assert_eq!(err.to_string(), "400 Bad Request");
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn test_retry_gives_up_after_max_retries() {
+        let calls = AtomicU32::new(0);
+
+        let err = policy(BackoffStrategy::Exponential)
+            .with_max_retries(2)
+            .retry(|_| {
+                calls.fetch_add(1, Ordering::SeqCst);
+                async { Err::<(), _>(AttemptError::retryable(anyhow!("unavailable"))) }
+            })
+            .await
+            .unwrap_err();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(err.to_string(), "unavailable");
+    }
+}
@@ -0,0 +1,403 @@
+//! Slack client
+//!
+//! Posts [`SlackMessage`]s through an incoming webhook or the
+//! `chat.postMessage` Web API method, waiting out `429 Too Many Requests`
+//! responses for as long as Slack's `Retry-After` header asks.
+
+use super::types::*;
+use crate::request_context::{current_request_id, with_request_id_header};
+use reqwest::{StatusCode, Url};
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::{debug, info, instrument, warn};
+
+/// Delay used when a 429 response carries no usable `Retry-After` header
+const DEFAULT_RETRY_AFTER: Duration = Duration::from_secs(1);
+
+/// Slack API client
+#[derive(Clone)]
+pub struct SlackClient {
+    /// HTTP client
+    client: reqwest::Client,
+    /// Validated incoming webhook URL
+    webhook_url: Option<Url>,
+    /// Bot token for Web API calls
+    bot_token: Option<String>,
+    /// Web API base URL, without a trailing slash
+    api_base_url: String,
+    /// Retries allowed after a 429 response
+    max_retries: u32,
+}
+
+impl SlackClient {
+    /// Create a new Slack client
+    ///
+    /// Fails with [`SlackError::InvalidWebhookUrl`] if a webhook URL is
+    /// configured but cannot be an incoming webhook.
+    pub fn new(config: SlackConfig) -> Result<Self, SlackError> {
+        let webhook_url = config
+            .webhook_url
+            .as_deref()
+            .map(validate_webhook_url)
+            .transpose()?;
+
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .user_agent("llm-auto-optimizer/1.0")
+            .build()?;
+
+        info!(
+            webhook = webhook_url.is_some(),
+            web_api = config.bot_token.is_some(),
+            "Initialized Slack client"
+        );
+
+        Ok(Self {
+            client,
+            webhook_url,
+            bot_token: config.bot_token,
+            api_base_url: config.api_base_url.trim_end_matches('/').to_string(),
+            max_retries: config.max_retries,
+        })
+    }
+
+    /// Post a message through the configured incoming webhook
+    ///
+    /// The webhook decides the channel, so `message.channel` is ignored.
+    #[instrument(
+        skip_all,
+        fields(blocks = message.blocks.len(), request_id = current_request_id())
+    )]
+    pub async fn post_webhook(&self, message: &SlackMessage) -> Result<(), SlackError> {
+        let url = self
+            .webhook_url
+            .clone()
+            .ok_or(SlackError::MissingWebhookUrl)?;
+
+        let response = self.send(self.client.post(url).json(message)).await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            // Webhooks answer with a bare error code such as `invalid_blocks`
+            let error = response.text().await.unwrap_or_default();
+            return Err(SlackError::Api {
+                status: status.as_u16(),
+                error,
+            });
+        }
+
+        debug!("Posted Slack webhook message");
+        Ok(())
+    }
+
+    /// Post a message with `chat.postMessage`
+    ///
+    /// Requires a bot token and `message.channel`. Returns Slack's response,
+    /// whose `ts` identifies the message for threaded replies.
+    #[instrument(
+        skip_all,
+        fields(channel = message.channel.as_deref(), request_id = current_request_id())
+    )]
+    pub async fn post_message(&self, message: &SlackMessage) -> Result<ApiResponse, SlackError> {
+        let token = self.bot_token.as_ref().ok_or(SlackError::MissingBotToken)?;
+        if message.channel.is_none() {
+            return Err(SlackError::MissingChannel);
+        }
+
+        let url = format!("{}/chat.postMessage", self.api_base_url);
+        let response = self
+            .send(self.client.post(url).bearer_auth(token).json(message))
+            .await?;
+        let status = response.status();
+
+        if !status.is_success() {
+            let error = response.text().await.unwrap_or_default();
+            return Err(SlackError::Api {
+                status: status.as_u16(),
+                error,
+            });
+        }
+
+        // The Web API reports most failures as 200 with `ok: false`
+        let body: ApiResponse = response.json().await?;
+        if !body.ok {
+            return Err(SlackError::Api {
+                status: status.as_u16(),
+                error: body.error.unwrap_or_else(|| "unknown_error".to_string()),
+            });
+        }
+
+        debug!(ts = body.ts.as_deref(), "Posted Slack message");
+        Ok(body)
+    }
+
+    /// Send a request, sleeping through 429 responses until Slack accepts it
+    /// or the retry budget runs out
+    async fn send(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::Response, SlackError> {
+        let mut attempt = 0;
+
+        loop {
+            let request = with_request_id_header(
+                request_builder
+                    .try_clone()
+                    .expect("Slack requests have in-memory JSON bodies"),
+            );
+            let response = request.send().await?;
+
+            if response.status() != StatusCode::TOO_MANY_REQUESTS {
+                return Ok(response);
+            }
+
+            let retry_after = retry_after(&response);
+            if attempt >= self.max_retries {
+                return Err(SlackError::RateLimited { retry_after });
+            }
+
+            attempt += 1;
+            warn!(
+                attempt,
+                retry_after_secs = retry_after.as_secs(),
+                "Rate limited by Slack"
+            );
+            sleep(retry_after).await;
+        }
+    }
+}
+
+/// Read the `Retry-After` header of a 429 response as a number of seconds
+fn retry_after(response: &reqwest::Response) -> Duration {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .map_or(DEFAULT_RETRY_AFTER, Duration::from_secs)
+}
+
+/// Check that `raw` looks like an incoming webhook URL
+///
+/// Plain `http` is accepted only for loopback hosts, e.g. a local relay.
+fn validate_webhook_url(raw: &str) -> Result<Url, SlackError> {
+    let invalid = |reason: &str| SlackError::InvalidWebhookUrl {
+        url: raw.to_string(),
+        reason: reason.to_string(),
+    };
+
+    let url = Url::parse(raw).map_err(|e| invalid(&e.to_string()))?;
+    let host = url.host_str().ok_or_else(|| invalid("URL has no host"))?;
+    let loopback = matches!(host, "localhost" | "127.0.0.1" | "[::1]");
+
+    match url.scheme() {
+        "https" => {}
+        "http" if loopback => {}
+        scheme => return Err(invalid(&format!("scheme must be https, got {scheme}"))),
+    }
+
+    if !url.path().starts_with("/services/") {
+        return Err(invalid("path must start with /services/"));
+    }
+
+    Ok(url)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Instant;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    const WEBHOOK_PATH: &str = "/services/T000/B000/XXXX";
+
+    fn webhook_client(server: &MockServer, max_retries: u32) -> SlackClient {
+        SlackClient::new(SlackConfig {
+            webhook_url: Some(format!("{}{}", server.uri(), WEBHOOK_PATH)),
+            max_retries,
+            ..SlackConfig::default()
+        })
+        .unwrap()
+    }
+
+    fn api_client(server: &MockServer) -> SlackClient {
+        SlackClient::new(SlackConfig {
+            bot_token: Some("xoxb-test".to_string()),
+            api_base_url: format!("{}/api/", server.uri()),
+            ..SlackConfig::default()
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_invalid_webhook_urls_rejected() {
+        for (url, reason) in [
+            ("not a url", "relative URL without a base"),
+            ("http://hooks.slack.com/services/T/B/X", "scheme must be https, got http"),
+            ("https://hooks.slack.com/api/chat", "path must start with /services/"),
+        ] {
+            let config = SlackConfig {
+                webhook_url: Some(url.to_string()),
+                ..SlackConfig::default()
+            };
+            match SlackClient::new(config) {
+                Err(SlackError::InvalidWebhookUrl { url: rejected, reason: got }) => {
+                    assert_eq!(rejected, url);
+                    assert_eq!(got, reason);
+                }
+                other => panic!("expected InvalidWebhookUrl for {url}, got {:?}", other.err()),
+            }
+        }
+
+        assert!(validate_webhook_url("https://hooks.slack.com/services/T/B/X").is_ok());
+        assert!(validate_webhook_url("http://localhost:8080/services/T/B/X").is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_sends_blocks() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(WEBHOOK_PATH))
+            .and(body_partial_json(serde_json::json!({
+                "text": "done",
+                "blocks": [{"type": "divider"}]
+            })))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = webhook_client(&server, 3);
+        client
+            .post_webhook(&SlackMessage::new("done").divider())
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_request_id_is_forwarded() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path(WEBHOOK_PATH))
+            .and(header("x-request-id", "req-42"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let client = webhook_client(&server, 3);
+        crate::request_context::with_request_id(
+            "req-42".to_string(),
+            client.post_webhook(&SlackMessage::new("done")),
+        )
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_post_webhook_surfaces_slack_error() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400).set_body_string("invalid_blocks"))
+            .mount(&server)
+            .await;
+
+        let err = webhook_client(&server, 3)
+            .post_webhook(&SlackMessage::new("bad"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SlackError::Api { status: 400, ref error } if error == "invalid_blocks"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_honors_retry_after() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "1"))
+            .up_to_n_times(1)
+            .expect(1)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_string("ok"))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let started = Instant::now();
+        webhook_client(&server, 3)
+            .post_webhook(&SlackMessage::new("retry me"))
+            .await
+            .unwrap();
+        assert!(started.elapsed() >= Duration::from_secs(1));
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_gives_up_after_max_retries() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(429).insert_header("Retry-After", "0"))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let err = webhook_client(&server, 2)
+            .post_webhook(&SlackMessage::new("never"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            SlackError::RateLimited { retry_after } if retry_after == Duration::ZERO
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_post_message_uses_web_api() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/chat.postMessage"))
+            .and(header("authorization", "Bearer xoxb-test"))
+            .and(body_partial_json(serde_json::json!({"channel": "C123"})))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": true,
+                "channel": "C123",
+                "ts": "1700000000.000100"
+            })))
+            .mount(&server)
+            .await;
+
+        let client = api_client(&server);
+        let response = client
+            .post_message(&SlackMessage::new("hi").channel("C123"))
+            .await
+            .unwrap();
+        assert_eq!(response.ts.as_deref(), Some("1700000000.000100"));
+
+        assert!(matches!(
+            client.post_message(&SlackMessage::new("hi")).await,
+            Err(SlackError::MissingChannel)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_post_message_reports_not_ok() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                "ok": false,
+                "error": "channel_not_found"
+            })))
+            .mount(&server)
+            .await;
+
+        let err = api_client(&server)
+            .post_message(&SlackMessage::new("hi").channel("C404"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Slack API error (200): channel_not_found");
+    }
+}
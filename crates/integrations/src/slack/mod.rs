@@ -0,0 +1,43 @@
+//! Slack integration
+//!
+//! This module provides a Slack client with:
+//! - Incoming webhook and `chat.postMessage` delivery
+//! - Block Kit layouts (headers, sections, fields, dividers, buttons)
+//! - A typed [`SlackMessage`] builder
+//! - `Retry-After` handling for rate-limited requests
+//! - Structured errors, including validation of webhook URLs
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use integrations::slack::{Button, ButtonStyle, SlackClient, SlackConfig, SlackMessage};
+//!
+//! #[tokio::main]
+//! async fn main() -> anyhow::Result<()> {
+//!     let client = SlackClient::new(SlackConfig {
+//!         webhook_url: Some("https://hooks.slack.com/services/T000/B000/XXXX".to_string()),
+//!         ..SlackConfig::default()
+//!     })?;
+//!
+//!     let message = SlackMessage::new("Optimization applied to summarize")
+//!         .header("Optimization applied")
+//!         .section("Switched `summarize` from *claude-3-opus* to *claude-3-haiku*")
+//!         .fields([("Cost", "-42%"), ("Latency p95", "-120ms"), ("Quality", "0.97")])
+//!         .divider()
+//!         .buttons([
+//!             Button::link("View run", "https://optimizer.example.com/runs/7f3a"),
+//!             Button::new("Roll back", "rollback")
+//!                 .with_value("7f3a")
+//!                 .with_style(ButtonStyle::Danger),
+//!         ]);
+//!
+//!     client.post_webhook(&message).await?;
+//!     Ok(())
+//! }
+//! ```
+
+pub mod client;
+pub mod types;
+
+pub use client::SlackClient;
+pub use types::*;
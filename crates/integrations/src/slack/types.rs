@@ -0,0 +1,440 @@
+//! Slack API type definitions
+//!
+//! Block Kit layout blocks and the [`SlackMessage`] builder used to compose
+//! them, plus the configuration and error types for the Slack client.
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use thiserror::Error;
+
+/// Slack client configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlackConfig {
+    /// Incoming webhook URL (e.g., https://hooks.slack.com/services/T000/B000/XXXX)
+    #[serde(default)]
+    pub webhook_url: Option<String>,
+    /// Bot token (`xoxb-...`) used for Web API calls
+    #[serde(default)]
+    pub bot_token: Option<String>,
+    /// Web API base URL
+    #[serde(default = "default_api_base_url")]
+    pub api_base_url: String,
+    /// Request timeout in seconds
+    #[serde(default = "default_timeout")]
+    pub timeout_secs: u64,
+    /// Maximum retries after Slack answers 429 Too Many Requests
+    #[serde(default = "default_max_retries")]
+    pub max_retries: u32,
+}
+
+fn default_api_base_url() -> String {
+    "https://slack.com/api".to_string()
+}
+
+fn default_timeout() -> u64 {
+    30
+}
+
+fn default_max_retries() -> u32 {
+    3
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        Self {
+            webhook_url: None,
+            bot_token: None,
+            api_base_url: default_api_base_url(),
+            timeout_secs: default_timeout(),
+            max_retries: default_max_retries(),
+        }
+    }
+}
+
+/// Errors returned by the Slack client
+#[derive(Debug, Error)]
+pub enum SlackError {
+    /// The configured webhook URL cannot be used
+    #[error("Invalid Slack webhook URL {url:?}: {reason}")]
+    InvalidWebhookUrl {
+        /// The rejected URL
+        url: String,
+        /// Why it was rejected
+        reason: String,
+    },
+    /// A webhook post was attempted without a webhook URL
+    #[error("Slack webhook URL is not configured")]
+    MissingWebhookUrl,
+    /// A Web API call was attempted without a bot token
+    #[error("Slack bot token is not configured")]
+    MissingBotToken,
+    /// `chat.postMessage` was called on a message without a channel
+    #[error("Slack message has no channel")]
+    MissingChannel,
+    /// Slack kept answering 429 after all retries were used
+    #[error("Rate limited by Slack (retry after {}s)", retry_after.as_secs())]
+    RateLimited {
+        /// Delay requested by the last `Retry-After` header
+        retry_after: Duration,
+    },
+    /// Slack rejected the request
+    #[error("Slack API error ({status}): {error}")]
+    Api {
+        /// HTTP status code
+        status: u16,
+        /// Slack error code, e.g. `invalid_blocks` or `channel_not_found`
+        error: String,
+    },
+    /// The request could not be sent or its response could not be read
+    #[error("HTTP error: {0}")]
+    Http(#[from] reqwest::Error),
+}
+
+/// Block Kit text object
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Text {
+    /// Text rendered with Slack's mrkdwn formatting
+    #[serde(rename = "mrkdwn")]
+    Markdown {
+        /// Text content
+        text: String,
+    },
+    /// Unformatted text
+    #[serde(rename = "plain_text")]
+    Plain {
+        /// Text content
+        text: String,
+        /// Whether `:emoji:` codes are rendered
+        emoji: bool,
+    },
+}
+
+impl Text {
+    /// Create a mrkdwn text object
+    pub fn markdown(text: impl Into<String>) -> Self {
+        Self::Markdown { text: text.into() }
+    }
+
+    /// Create a plain text object with emoji rendering enabled
+    pub fn plain(text: impl Into<String>) -> Self {
+        Self::Plain {
+            text: text.into(),
+            emoji: true,
+        }
+    }
+}
+
+/// Visual style of a button
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ButtonStyle {
+    /// Green call-to-action button
+    Primary,
+    /// Red destructive-action button
+    Danger,
+}
+
+/// Block Kit button element
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename = "button")]
+pub struct Button {
+    /// Button label (plain text only)
+    pub text: Text,
+    /// Identifier sent back with interaction payloads
+    pub action_id: String,
+    /// URL opened in the browser when clicked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Value sent back with interaction payloads
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+    /// Button style; Slack's default grey when unset
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub style: Option<ButtonStyle>,
+}
+
+impl Button {
+    /// Create a button that reports `action_id` when clicked
+    pub fn new(label: impl Into<String>, action_id: impl Into<String>) -> Self {
+        Self {
+            text: Text::plain(label),
+            action_id: action_id.into(),
+            url: None,
+            value: None,
+            style: None,
+        }
+    }
+
+    /// Create a button that opens `url`
+    pub fn link(label: impl Into<String>, url: impl Into<String>) -> Self {
+        let label = label.into();
+        let action_id = label.to_lowercase().replace(char::is_whitespace, "_");
+        Self::new(label, action_id).with_url(url)
+    }
+
+    /// Set the URL opened when the button is clicked
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Set the value sent with interaction payloads
+    pub fn with_value(mut self, value: impl Into<String>) -> Self {
+        self.value = Some(value.into());
+        self
+    }
+
+    /// Set the button style
+    pub const fn with_style(mut self, style: ButtonStyle) -> Self {
+        self.style = Some(style);
+        self
+    }
+}
+
+/// Block Kit layout block
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum Block {
+    /// Large bold heading
+    Header {
+        /// Heading text (plain text only)
+        text: Text,
+    },
+    /// Text and/or a two-column grid of fields
+    Section {
+        /// Main section text
+        #[serde(skip_serializing_if = "Option::is_none")]
+        text: Option<Text>,
+        /// Fields laid out in two columns
+        #[serde(default, skip_serializing_if = "Vec::is_empty")]
+        fields: Vec<Text>,
+        /// Button shown to the right of the text
+        #[serde(skip_serializing_if = "Option::is_none")]
+        accessory: Option<Button>,
+    },
+    /// Horizontal rule
+    Divider,
+    /// Small, muted text
+    Context {
+        /// Context elements
+        elements: Vec<Text>,
+    },
+    /// Row of interactive buttons
+    Actions {
+        /// Buttons in the row
+        elements: Vec<Button>,
+    },
+}
+
+/// Slack message composed of Block Kit blocks
+///
+/// `text` is the notification fallback shown where blocks cannot be rendered.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SlackMessage {
+    /// Fallback text for notifications
+    pub text: String,
+    /// Layout blocks
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub blocks: Vec<Block>,
+    /// Channel ID or name; required by `chat.postMessage`, ignored by webhooks
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub channel: Option<String>,
+    /// Timestamp of the parent message when replying in a thread
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_ts: Option<String>,
+}
+
+impl SlackMessage {
+    /// Maximum number of fields Slack accepts in a section
+    pub const MAX_SECTION_FIELDS: usize = 10;
+
+    /// Create a message with the given fallback text and no blocks
+    pub fn new(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            blocks: Vec::new(),
+            channel: None,
+            thread_ts: None,
+        }
+    }
+
+    /// Post to a specific channel
+    pub fn channel(mut self, channel: impl Into<String>) -> Self {
+        self.channel = Some(channel.into());
+        self
+    }
+
+    /// Reply in the thread started by `thread_ts`
+    pub fn thread_ts(mut self, thread_ts: impl Into<String>) -> Self {
+        self.thread_ts = Some(thread_ts.into());
+        self
+    }
+
+    /// Append a header block
+    pub fn header(self, text: impl Into<String>) -> Self {
+        self.block(Block::Header {
+            text: Text::plain(text),
+        })
+    }
+
+    /// Append a mrkdwn section
+    pub fn section(self, text: impl Into<String>) -> Self {
+        self.block(Block::Section {
+            text: Some(Text::markdown(text)),
+            fields: Vec::new(),
+            accessory: None,
+        })
+    }
+
+    /// Append `(label, value)` pairs as section fields, rendered as a bold
+    /// label over its value
+    ///
+    /// Pairs beyond Slack's per-section limit spill into further sections.
+    pub fn fields<I, L, V>(mut self, fields: I) -> Self
+    where
+        I: IntoIterator<Item = (L, V)>,
+        L: AsRef<str>,
+        V: AsRef<str>,
+    {
+        let fields: Vec<Text> = fields
+            .into_iter()
+            .map(|(label, value)| {
+                Text::markdown(format!("*{}*\n{}", label.as_ref(), value.as_ref()))
+            })
+            .collect();
+
+        for chunk in fields.chunks(Self::MAX_SECTION_FIELDS) {
+            self = self.block(Block::Section {
+                text: None,
+                fields: chunk.to_vec(),
+                accessory: None,
+            });
+        }
+        self
+    }
+
+    /// Append a divider
+    pub fn divider(self) -> Self {
+        self.block(Block::Divider)
+    }
+
+    /// Append a context block with a single mrkdwn element
+    pub fn context(self, text: impl Into<String>) -> Self {
+        self.block(Block::Context {
+            elements: vec![Text::markdown(text)],
+        })
+    }
+
+    /// Append a row of buttons
+    pub fn buttons(self, buttons: impl IntoIterator<Item = Button>) -> Self {
+        self.block(Block::Actions {
+            elements: buttons.into_iter().collect(),
+        })
+    }
+
+    /// Append an arbitrary block
+    pub fn block(mut self, block: Block) -> Self {
+        self.blocks.push(block);
+        self
+    }
+}
+
+/// Response body of Slack Web API calls
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiResponse {
+    /// Whether the call succeeded
+    pub ok: bool,
+    /// Error code when `ok` is false
+    #[serde(default)]
+    pub error: Option<String>,
+    /// Channel the message was posted to
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Timestamp identifying the posted message
+    #[serde(default)]
+    pub ts: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_message_serializes_to_block_kit() {
+        let message = SlackMessage::new("Optimization applied")
+            .header("Optimization applied")
+            .section("Switched `summarize` to *claude-3-haiku*")
+            .fields([("Cost", "-42%"), ("Latency", "-120ms")])
+            .divider()
+            .context("Run 7f3a")
+            .buttons([
+                Button::link("View details", "https://example.com/runs/7f3a"),
+                Button::new("Roll back", "rollback")
+                    .with_value("7f3a")
+                    .with_style(ButtonStyle::Danger),
+            ]);
+
+        assert_eq!(
+            serde_json::to_value(&message).unwrap(),
+            json!({
+                "text": "Optimization applied",
+                "blocks": [
+                    {
+                        "type": "header",
+                        "text": {"type": "plain_text", "text": "Optimization applied", "emoji": true}
+                    },
+                    {
+                        "type": "section",
+                        "text": {"type": "mrkdwn", "text": "Switched `summarize` to *claude-3-haiku*"}
+                    },
+                    {
+                        "type": "section",
+                        "fields": [
+                            {"type": "mrkdwn", "text": "*Cost*\n-42%"},
+                            {"type": "mrkdwn", "text": "*Latency*\n-120ms"}
+                        ]
+                    },
+                    {"type": "divider"},
+                    {"type": "context", "elements": [{"type": "mrkdwn", "text": "Run 7f3a"}]},
+                    {
+                        "type": "actions",
+                        "elements": [
+                            {
+                                "type": "button",
+                                "text": {"type": "plain_text", "text": "View details", "emoji": true},
+                                "action_id": "view_details",
+                                "url": "https://example.com/runs/7f3a"
+                            },
+                            {
+                                "type": "button",
+                                "text": {"type": "plain_text", "text": "Roll back", "emoji": true},
+                                "action_id": "rollback",
+                                "value": "7f3a",
+                                "style": "danger"
+                            }
+                        ]
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn test_fields_split_across_sections() {
+        let pairs: Vec<(String, String)> =
+            (0..12).map(|i| (format!("k{i}"), format!("v{i}"))).collect();
+        let message = SlackMessage::new("many").fields(pairs);
+
+        let sizes: Vec<usize> = message
+            .blocks
+            .iter()
+            .map(|block| match block {
+                Block::Section { fields, .. } => fields.len(),
+                other => panic!("unexpected block {other:?}"),
+            })
+            .collect();
+        assert_eq!(sizes, vec![10, 2]);
+    }
+}
@@ -0,0 +1,425 @@
+//! Outbound URL validation against server-side request forgery
+//!
+//! Before the server fetches a URL a user supplied, [`UrlValidator::validate`]
+//! checks its scheme and host, resolves the host, and rejects it if any
+//! resolved address is loopback, private, link-local (which covers the
+//! `169.254.169.254` cloud metadata endpoint) or otherwise not publicly
+//! routable. Checking every address, and pinning the client to them with
+//! [`ValidatedUrl::pin`], keeps a host from passing validation and then
+//! rebinding to an internal address for the actual request.
+//!
+//! A URL that passed when it was saved may resolve elsewhere later, so
+//! validate again right before each fetch; [`UrlValidator::pinned_client`]
+//! does both.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use integrations::url_guard::UrlValidator;
+//!
+//! # async fn example() -> anyhow::Result<()> {
+//! let validator = UrlValidator::new().with_allowed_host("hooks.example.com");
+//! let target = validator.validate("https://hooks.example.com/notify").await?;
+//!
+//! let client = target.pin(reqwest::Client::builder()).build()?;
+//! client.post(target.url().clone()).send().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use async_trait::async_trait;
+use reqwest::Url;
+use std::fmt;
+use std::io;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use std::sync::Arc;
+use thiserror::Error;
+use tokio::net::lookup_host;
+
+/// Reasons a URL is refused
+#[derive(Debug, Error)]
+pub enum UrlGuardError {
+    /// The URL does not parse or has no host
+    #[error("Invalid URL {url:?}: {reason}")]
+    InvalidUrl {
+        /// The rejected URL
+        url: String,
+        /// Why it could not be used
+        reason: String,
+    },
+    /// Only `http` and `https` URLs may be fetched
+    #[error("URL scheme {0:?} is not allowed, use http or https")]
+    UnsupportedScheme(String),
+    /// The host is not on the configured allowlist
+    #[error("Host {0:?} is not on the allowlist")]
+    HostNotAllowed(String),
+    /// The host could not be resolved
+    #[error("Could not resolve host {host:?}: {reason}")]
+    Resolution {
+        /// The host that failed to resolve
+        host: String,
+        /// Resolver error
+        reason: String,
+    },
+    /// The host is, or resolves to, an internal address
+    #[error("Host {host:?} resolves to non-public address {addr}")]
+    BlockedAddress {
+        /// The rejected host
+        host: String,
+        /// The offending address
+        addr: IpAddr,
+    },
+    /// The HTTP client for a validated URL could not be built
+    #[error("Could not build HTTP client: {0}")]
+    Client(#[from] reqwest::Error),
+}
+
+/// Resolves host names to the addresses a URL would connect to
+#[async_trait]
+pub trait HostResolver: Send + Sync {
+    /// Every address `host` resolves to, with `port` applied
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>>;
+}
+
+/// Resolves hosts with the system resolver
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemResolver;
+
+#[async_trait]
+impl HostResolver for SystemResolver {
+    async fn resolve(&self, host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+        Ok(lookup_host((host, port)).await?.collect())
+    }
+}
+
+/// Checks user-supplied URLs before the server fetches them
+#[derive(Clone)]
+pub struct UrlValidator {
+    /// Hosts that may be fetched; empty allows any public host
+    allowed_hosts: Vec<String>,
+    /// Resolves hosts before their addresses are checked
+    resolver: Arc<dyn HostResolver>,
+}
+
+impl fmt::Debug for UrlValidator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("UrlValidator")
+            .field("allowed_hosts", &self.allowed_hosts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for UrlValidator {
+    fn default() -> Self {
+        Self {
+            allowed_hosts: Vec::new(),
+            resolver: Arc::new(SystemResolver),
+        }
+    }
+}
+
+impl UrlValidator {
+    /// Create a validator that allows any public `http` or `https` host
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Resolve hosts with `resolver` instead of the system resolver
+    #[must_use]
+    pub fn with_resolver(mut self, resolver: Arc<dyn HostResolver>) -> Self {
+        self.resolver = resolver;
+        self
+    }
+
+    /// Only allow URLs whose host is exactly `host`, or another host added
+    /// the same way
+    ///
+    /// Allowed hosts must still resolve to public addresses.
+    #[must_use]
+    pub fn with_allowed_host(mut self, host: impl Into<String>) -> Self {
+        self.allowed_hosts.push(host.into().to_ascii_lowercase());
+        self
+    }
+
+    /// Hosts on the allowlist; empty when any public host is allowed
+    pub fn allowed_hosts(&self) -> &[String] {
+        &self.allowed_hosts
+    }
+
+    /// Check `raw` and resolve its host
+    ///
+    /// Fails unless the scheme is `http` or `https`, the host is allowed, and
+    /// every address the host resolves to is public.
+    pub async fn validate(&self, raw: &str) -> Result<ValidatedUrl, UrlGuardError> {
+        let invalid = |reason: &str| UrlGuardError::InvalidUrl {
+            url: raw.to_string(),
+            reason: reason.to_string(),
+        };
+
+        let url = Url::parse(raw).map_err(|e| invalid(&e.to_string()))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(UrlGuardError::UnsupportedScheme(url.scheme().to_string()));
+        }
+
+        // IPv6 literals keep their brackets in `host_str`
+        let host = url
+            .host_str()
+            .ok_or_else(|| invalid("URL has no host"))?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase();
+        if !self.allowed_hosts.is_empty() && !self.allowed_hosts.contains(&host) {
+            return Err(UrlGuardError::HostNotAllowed(host));
+        }
+
+        let port = url
+            .port_or_known_default()
+            .ok_or_else(|| invalid("URL has no port"))?;
+        let addrs = self
+            .resolver
+            .resolve(&host, port)
+            .await
+            .map_err(|e| UrlGuardError::Resolution {
+                host: host.clone(),
+                reason: e.to_string(),
+            })?;
+        if addrs.is_empty() {
+            return Err(UrlGuardError::Resolution {
+                host,
+                reason: "no addresses".to_string(),
+            });
+        }
+
+        if let Some(blocked) = addrs.iter().find(|addr| is_blocked_ip(addr.ip())) {
+            return Err(UrlGuardError::BlockedAddress {
+                host,
+                addr: blocked.ip(),
+            });
+        }
+
+        Ok(ValidatedUrl { url, host, addrs })
+    }
+
+    /// Validate `raw` and build a client that can only reach the addresses
+    /// just checked
+    ///
+    /// Call this right before fetching, not only when the URL is saved.
+    /// Redirects are not followed, since their targets were never validated.
+    pub async fn pinned_client(&self, raw: &str) -> Result<(reqwest::Client, Url), UrlGuardError> {
+        let target = self.validate(raw).await?;
+        let client = target
+            .pin(reqwest::Client::builder().redirect(reqwest::redirect::Policy::none()))
+            .build()?;
+        Ok((client, target.url))
+    }
+}
+
+/// A URL that passed validation, with the addresses its host resolved to
+#[derive(Debug, Clone)]
+pub struct ValidatedUrl {
+    url: Url,
+    host: String,
+    addrs: Vec<SocketAddr>,
+}
+
+impl ValidatedUrl {
+    /// The parsed URL
+    pub const fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Every address the host resolved to, all of them public
+    pub fn addrs(&self) -> &[SocketAddr] {
+        &self.addrs
+    }
+
+    /// Make a client connect only to the validated addresses
+    ///
+    /// Without pinning, the client resolves the host again when it connects
+    /// and may get a different answer than the one that was checked.
+    pub fn pin(&self, builder: reqwest::ClientBuilder) -> reqwest::ClientBuilder {
+        builder.resolve_to_addrs(&self.host, &self.addrs)
+    }
+}
+
+/// Whether `ip` is an address the server must never fetch from
+///
+/// Covers loopback, private, link-local, shared (carrier-grade NAT),
+/// unspecified, broadcast and multicast ranges, IPv6 unique local and
+/// link-local addresses, and IPv4 addresses embedded in IPv6.
+pub const fn is_blocked_ip(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => is_blocked_ipv4(v4),
+        IpAddr::V6(v6) => is_blocked_ipv6(v6),
+    }
+}
+
+const fn is_blocked_ipv4(ip: Ipv4Addr) -> bool {
+    let [a, b, ..] = ip.octets();
+    ip.is_loopback()
+        || ip.is_private()
+        || ip.is_link_local()
+        || ip.is_broadcast()
+        || ip.is_multicast()
+        || ip.is_documentation()
+        // 0.0.0.0/8, "this network"
+        || a == 0
+        // 100.64.0.0/10, shared address space
+        || (a == 100 && (b & 0xc0) == 64)
+        // 198.18.0.0/15, benchmarking
+        || (a == 198 && (b & 0xfe) == 18)
+        // 240.0.0.0/4, reserved
+        || a >= 240
+}
+
+const fn is_blocked_ipv6(ip: Ipv6Addr) -> bool {
+    if let Some(v4) = embedded_ipv4(ip) {
+        return is_blocked_ipv4(v4);
+    }
+    let first = ip.segments()[0];
+    ip.is_loopback()
+        || ip.is_unspecified()
+        || ip.is_multicast()
+        // fc00::/7, unique local
+        || (first & 0xfe00) == 0xfc00
+        // fe80::/10, link-local
+        || (first & 0xffc0) == 0xfe80
+}
+
+/// IPv4 address an IPv6 address routes to: IPv4-mapped `::ffff:a.b.c.d`,
+/// IPv4-compatible `::a.b.c.d`, NAT64 `64:ff9b::/96` or 6to4 `2002::/16`
+const fn embedded_ipv4(ip: Ipv6Addr) -> Option<Ipv4Addr> {
+    if let Some(v4) = ip.to_ipv4_mapped() {
+        return Some(v4);
+    }
+    let s = ip.segments();
+    let (hi, lo) = match s {
+        [0, 0, 0, 0, 0, 0, hi, lo] | [0x64, 0xff9b, 0, 0, 0, 0, hi, lo] => (hi, lo),
+        [0x2002, hi, lo, ..] => (hi, lo),
+        _ => return None,
+    };
+    Some(Ipv4Addr::new((hi >> 8) as u8, hi as u8, (lo >> 8) as u8, lo as u8))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn rejection(url: &str) -> UrlGuardError {
+        UrlValidator::new()
+            .validate(url)
+            .await
+            .expect_err(&format!("{url} should be rejected"))
+    }
+
+    #[tokio::test]
+    async fn test_rejects_localhost() {
+        for url in [
+            "http://localhost/admin",
+            "http://127.0.0.1:8080/",
+            "http://[::1]/",
+            "http://[::ffff:127.0.0.1]/",
+            "http://0.0.0.0/",
+        ] {
+            assert!(
+                matches!(rejection(url).await, UrlGuardError::BlockedAddress { .. }),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_private_ranges() {
+        for url in [
+            "http://10.0.0.5/",
+            "https://172.16.3.4/",
+            "http://192.168.1.1/",
+            "http://100.64.0.1/",
+            "http://[fd00::1]/",
+            "http://198.18.0.1/",
+            "http://240.0.0.1/",
+            "http://[64:ff9b::a00:1]/",
+            "http://[2002:c0a8:101::]/",
+            "http://[::10.0.0.1]/",
+        ] {
+            assert!(
+                matches!(rejection(url).await, UrlGuardError::BlockedAddress { .. }),
+                "{url}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_metadata_endpoint() {
+        match rejection("http://169.254.169.254/latest/meta-data/").await {
+            UrlGuardError::BlockedAddress { addr, .. } => {
+                assert_eq!(addr, IpAddr::V4(Ipv4Addr::new(169, 254, 169, 254)));
+            }
+            other => panic!("expected BlockedAddress, got {other:?}"),
+        }
+        assert!(matches!(
+            rejection("http://[fe80::a9fe:a9fe]/").await,
+            UrlGuardError::BlockedAddress { .. }
+        ));
+    }
+
+    #[test]
+    fn test_public_ipv6_is_allowed() {
+        for ip in ["2606:4700::1111", "64:ff9b::101:101", "2002:101:101::"] {
+            assert!(!is_blocked_ipv6(ip.parse().unwrap()), "{ip}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rejects_other_schemes() {
+        for url in ["file:///etc/passwd", "gopher://example.com/", "ftp://example.com/"] {
+            assert!(
+                matches!(rejection(url).await, UrlGuardError::UnsupportedScheme(_)),
+                "{url}"
+            );
+        }
+    }
+
+    #[derive(Default)]
+    struct StaticResolver(std::sync::Mutex<Vec<IpAddr>>);
+
+    #[async_trait]
+    impl HostResolver for StaticResolver {
+        async fn resolve(&self, _host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+            let ips = self.0.lock().unwrap();
+            Ok(ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pinned_client_revalidates_rebound_host() {
+        let resolver = Arc::new(StaticResolver::default());
+        *resolver.0.lock().unwrap() = vec![IpAddr::V4(Ipv4Addr::new(93, 184, 216, 34))];
+        let validator = UrlValidator::new().with_resolver(resolver.clone());
+
+        let (_, url) = validator.pinned_client("https://hooks.example.test/hook").await.unwrap();
+        assert_eq!(url.host_str(), Some("hooks.example.test"));
+
+        // The host passed when it was saved, then moved to an internal address
+        *resolver.0.lock().unwrap() = vec![IpAddr::V4(Ipv4Addr::LOCALHOST)];
+        match validator.pinned_client("https://hooks.example.test/hook").await {
+            Err(UrlGuardError::BlockedAddress { addr, .. }) => {
+                assert_eq!(addr, IpAddr::V4(Ipv4Addr::LOCALHOST));
+            }
+            other => panic!("expected BlockedAddress, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_allowlist() {
+        let validator = UrlValidator::new().with_allowed_host("93.184.216.34");
+
+        let target = validator.validate("https://93.184.216.34/hook").await.unwrap();
+        assert_eq!(target.addrs()[0].port(), 443);
+
+        assert!(matches!(
+            validator.validate("https://1.1.1.1/").await,
+            Err(UrlGuardError::HostNotAllowed(_))
+        ));
+    }
+}
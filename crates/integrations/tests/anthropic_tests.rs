@@ -3,6 +3,7 @@
 //! Tests cover client creation, message handling, streaming, and token utilities.
 
 use integrations::anthropic::*;
+use integrations::RetryPolicy;
 
 #[cfg(test)]
 mod config_tests {
@@ -19,7 +20,7 @@ mod config_tests {
         assert_eq!(config.api_key, "test-key");
         assert_eq!(config.base_url, "https://api.anthropic.com");
         assert_eq!(config.timeout_secs, 60);
-        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.retry.max_retries, 3);
         assert_eq!(config.rate_limit_per_minute, 50);
         assert_eq!(config.api_version, "2023-06-01");
     }
@@ -30,13 +31,15 @@ mod config_tests {
             api_key: "custom-key".to_string(),
             base_url: "https://custom.api.com".to_string(),
             timeout_secs: 120,
-            max_retries: 5,
+            retry: RetryPolicy::default().with_max_retries(5),
             rate_limit_per_minute: 100,
             api_version: "2024-01-01".to_string(),
+            pricing: Default::default(),
+            cost_store: None,
         };
 
         assert_eq!(config.timeout_secs, 120);
-        assert_eq!(config.max_retries, 5);
+        assert_eq!(config.retry.max_retries, 5);
     }
 }
 
@@ -81,6 +84,7 @@ mod usage_tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         assert_eq!(usage.total_tokens(), 1500);
@@ -97,6 +101,7 @@ mod usage_tests {
         let usage = Usage {
             input_tokens: 0,
             output_tokens: 0,
+            ..Default::default()
         };
 
         assert_eq!(usage.total_tokens(), 0);
@@ -115,6 +120,7 @@ mod cost_tracker_tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         tracker.record_usage(&usage, ClaudeModel::Claude3Haiku);
@@ -133,6 +139,7 @@ mod cost_tracker_tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         tracker.record_usage(&usage, ClaudeModel::Claude3Haiku);
@@ -152,6 +159,7 @@ mod cost_tracker_tests {
         let usage = Usage {
             input_tokens: 1000,
             output_tokens: 500,
+            ..Default::default()
         };
 
         tracker.record_usage(&usage, ClaudeModel::Claude3Haiku);
@@ -184,9 +192,11 @@ mod client_tests {
             api_key: "test-key".to_string(),
             base_url: "https://api.anthropic.com".to_string(),
             timeout_secs: 60,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 50,
             api_version: "2023-06-01".to_string(),
+            pricing: Default::default(),
+            cost_store: None,
         }
     }
 
@@ -237,6 +247,8 @@ mod client_tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(client.validate_request(&valid_request).is_ok());
@@ -252,6 +264,8 @@ mod client_tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         assert!(client.validate_request(&invalid_request).is_err());
@@ -326,6 +340,8 @@ mod token_counter_tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let cost = counter.estimate_cost(&request, ClaudeModel::Claude3Haiku);
@@ -419,6 +435,7 @@ mod streaming_tests {
                 usage: Usage {
                     input_tokens: 10,
                     output_tokens: 0,
+                    ..Default::default()
                 },
             },
         };
@@ -474,6 +491,7 @@ mod streaming_tests {
         collector.usage = Usage {
             input_tokens: 10,
             output_tokens: 5,
+            ..Default::default()
         };
         collector.stop_reason = Some(StopReason::EndTurn);
 
@@ -507,6 +525,8 @@ mod message_tests {
             stop_sequences: None,
             stream: false,
             metadata: None,
+            tools: None,
+            tool_choice: None,
         };
 
         let json = serde_json::to_string(&request).unwrap();
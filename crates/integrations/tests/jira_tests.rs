@@ -17,8 +17,9 @@ mod auth_tests {
                 api_token: "test-token-123".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let auth_manager = AuthManager::new(config);
@@ -39,8 +40,9 @@ mod auth_tests {
                 refresh_token: Some("refresh-token".to_string()),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let auth_manager = AuthManager::new(config);
@@ -58,8 +60,9 @@ mod auth_tests {
                 token: "pat-token-123".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         };
 
         let auth_manager = AuthManager::new(config);
@@ -81,8 +84,9 @@ mod client_tests {
                 api_token: "test-token".to_string(),
             },
             timeout_secs: 30,
-            max_retries: 3,
+            retry: Default::default(),
             rate_limit_per_minute: 100,
+            page_size: 50,
         }
     }
 
@@ -339,7 +343,7 @@ mod type_tests {
         let config: JiraConfig = serde_json::from_str(config_json).unwrap();
 
         assert_eq!(config.timeout_secs, 30);
-        assert_eq!(config.max_retries, 3);
+        assert_eq!(config.retry.max_retries, 3);
         assert_eq!(config.rate_limit_per_minute, 100);
     }
 }
@@ -0,0 +1,89 @@
+//! JSON Schema for the configuration file
+//!
+//! Editors such as VS Code can use the schema printed by
+//! `--print-config-schema` for completion and validation. Every section has
+//! defaults, so only fields of optional integrations are required.
+
+use super::{Config, ENVIRONMENTS, LOG_LEVELS};
+use schemars::gen::SchemaGenerator;
+use schemars::schema::{InstanceType, RootSchema, Schema, SchemaObject};
+
+/// Generate the JSON Schema for [`Config`]
+pub fn config_schema() -> RootSchema {
+    schemars::schema_for!(Config)
+}
+
+/// Schema for a string restricted to `values`
+fn string_enum(values: &[&str]) -> Schema {
+    SchemaObject {
+        instance_type: Some(InstanceType::String.into()),
+        enum_values: Some(values.iter().map(|value| (*value).into()).collect()),
+        ..Default::default()
+    }
+    .into()
+}
+
+/// Schema for `observability.log_level`
+pub(crate) fn log_level_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(&LOG_LEVELS)
+}
+
+/// Schema for `service.environment`
+pub(crate) fn environment_schema(_: &mut SchemaGenerator) -> Schema {
+    string_enum(&ENVIRONMENTS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonschema::{Draft, JSONSchema};
+    use serde_json::json;
+
+    fn compiled() -> JSONSchema {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        JSONSchema::options()
+            .with_draft(Draft::Draft7)
+            .compile(&schema)
+            .expect("config schema is not a valid JSON Schema")
+    }
+
+    #[test]
+    fn test_default_config_validates_against_schema() {
+        let config = serde_json::to_value(Config::default()).unwrap();
+        let schema = compiled();
+        if let Err(errors) = schema.validate(&config) {
+            let errors: Vec<_> = errors.map(|e| e.to_string()).collect();
+            panic!("default config does not match schema: {:?}", errors);
+        }
+    }
+
+    #[test]
+    fn test_schema_captures_enums_defaults_and_required_fields() {
+        let schema = serde_json::to_value(config_schema()).unwrap();
+        let definitions = &schema["definitions"];
+
+        let log_level = &definitions["ObservabilityConfig"]["properties"]["log_level"];
+        assert_eq!(log_level["enum"], json!(LOG_LEVELS));
+        assert_eq!(log_level["default"], "info");
+
+        let environment = &definitions["ServiceConfig"]["properties"]["environment"];
+        assert_eq!(environment["enum"], json!(ENVIRONMENTS));
+
+        assert_eq!(
+            definitions["RestApiConfig"]["properties"]["port"]["default"],
+            8080
+        );
+        assert_eq!(
+            definitions["AnthropicConfig"]["required"],
+            json!(["api_key"])
+        );
+        assert!(schema.get("required").is_none());
+    }
+
+    #[test]
+    fn test_schema_rejects_unknown_log_level() {
+        let mut config = serde_json::to_value(Config::default()).unwrap();
+        config["observability"]["log_level"] = json!("verbose");
+        assert!(!compiled().is_valid(&config));
+    }
+}
@@ -1,156 +1,375 @@
 //! Configuration validation
+//!
+//! Validation collects every problem it finds, each tagged with the path of
+//! the offending field, rather than stopping at the first one.
 
-use anyhow::{bail, Result};
 use super::Config;
+use llm_optimizer_integrations::anthropic::parse_model;
+use std::fmt;
+
+/// A single problem with a configuration field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationIssue {
+    /// Dotted path of the field, e.g. `rest_api.port`
+    pub field: String,
+    /// What is wrong with it
+    pub reason: String,
+}
 
-/// Configuration validator
-pub struct Validator;
+impl fmt::Display for ValidationIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.reason)
+    }
+}
 
-impl Validator {
-    /// Validate the entire configuration
-    pub fn validate_config(config: &Config) -> Result<()> {
-        Self::validate_service(&config.service)?;
-        Self::validate_collector(&config.collector)?;
-        Self::validate_processor(&config.processor)?;
-        Self::validate_rest_api(&config.rest_api)?;
-        Self::validate_grpc_api(&config.grpc_api)?;
-        Self::validate_storage(&config.storage)?;
-        Self::validate_observability(&config.observability)?;
+/// Every problem found while validating a configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors {
+    /// Problems in the order they were found
+    pub issues: Vec<ValidationIssue>,
+}
 
-        Ok(())
+impl ValidationErrors {
+    /// Whether any issue concerns `field`
+    pub fn has_field(&self, field: &str) -> bool {
+        self.issues.iter().any(|issue| issue.field == field)
     }
+}
 
-    /// Validate service configuration
-    fn validate_service(config: &super::ServiceConfig) -> Result<()> {
-        if config.name.is_empty() {
-            bail!("Service name cannot be empty");
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let plural = if self.issues.len() == 1 { "" } else { "s" };
+        write!(f, "{} configuration problem{}:", self.issues.len(), plural)?;
+        for issue in &self.issues {
+            write!(f, "\n  - {}", issue)?;
         }
+        Ok(())
+    }
+}
 
-        if config.version.is_empty() {
-            bail!("Service version cannot be empty");
-        }
+impl std::error::Error for ValidationErrors {}
 
-        if config.environment.is_empty() {
-            bail!("Service environment cannot be empty");
-        }
+/// Accumulates issues while the validator walks the configuration
+#[derive(Default)]
+struct Issues(Vec<ValidationIssue>);
 
-        Ok(())
+impl Issues {
+    /// Record an issue for `field`
+    fn push(&mut self, field: impl Into<String>, reason: impl Into<String>) {
+        self.0.push(ValidationIssue {
+            field: field.into(),
+            reason: reason.into(),
+        });
     }
 
-    /// Validate collector configuration
-    fn validate_collector(config: &super::CollectorConfig) -> Result<()> {
-        if !config.enabled {
-            return Ok(());
+    /// Record an issue for `field` if `failed` holds
+    fn check(&mut self, failed: bool, field: impl Into<String>, reason: impl Into<String>) {
+        if failed {
+            self.push(field, reason);
         }
+    }
+}
 
-        if config.kafka_brokers.is_empty() {
-            bail!("Kafka brokers cannot be empty when collector is enabled");
-        }
+/// Configuration validator
+pub struct Validator;
 
-        if config.kafka_topic.is_empty() {
-            bail!("Kafka topic cannot be empty");
+impl Validator {
+    /// Validate the entire configuration, reporting every problem found
+    pub fn validate_config(config: &Config) -> Result<(), ValidationErrors> {
+        let mut issues = Issues::default();
+
+        Self::validate_service(&config.service, &mut issues);
+        Self::validate_collector(&config.collector, &mut issues);
+        Self::validate_processor(&config.processor, &mut issues);
+        Self::validate_rest_api(&config.rest_api, &mut issues);
+        Self::validate_rationale_model(config, &mut issues);
+        Self::validate_grpc_api(&config.grpc_api, &mut issues);
+        Self::validate_storage(&config.storage, &mut issues);
+        Self::validate_integrations(&config.integrations, &mut issues);
+        Self::validate_observability(&config.observability, &mut issues);
+        Self::validate_ports(config, &mut issues);
+
+        if issues.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ValidationErrors { issues: issues.0 })
         }
+    }
 
-        if config.buffer_size == 0 {
-            bail!("Buffer size must be greater than 0");
-        }
+    /// Validate service configuration
+    fn validate_service(config: &super::ServiceConfig, issues: &mut Issues) {
+        issues.check(config.name.is_empty(), "service.name", "must not be empty");
+        issues.check(
+            config.version.is_empty(),
+            "service.version",
+            "must not be empty",
+        );
+        issues.check(
+            config.environment.is_empty(),
+            "service.environment",
+            "must not be empty",
+        );
+    }
 
-        if config.batch_size == 0 {
-            bail!("Batch size must be greater than 0");
+    /// Validate collector configuration
+    fn validate_collector(config: &super::CollectorConfig, issues: &mut Issues) {
+        if !config.enabled {
+            return;
         }
 
-        if config.batch_size > config.buffer_size {
-            bail!("Batch size cannot exceed buffer size");
+        issues.check(
+            config.kafka_brokers.is_empty(),
+            "collector.kafka_brokers",
+            "must list at least one broker when the collector is enabled",
+        );
+        for (i, broker) in config.kafka_brokers.iter().enumerate() {
+            issues.check(
+                broker.trim().is_empty(),
+                format!("collector.kafka_brokers[{}]", i),
+                "broker address must not be empty",
+            );
         }
 
-        Ok(())
+        issues.check(
+            config.kafka_topic.is_empty(),
+            "collector.kafka_topic",
+            "must not be empty",
+        );
+        issues.check(
+            config.buffer_size == 0,
+            "collector.buffer_size",
+            "must be greater than 0",
+        );
+        issues.check(
+            config.batch_size == 0,
+            "collector.batch_size",
+            "must be greater than 0",
+        );
+        issues.check(
+            config.batch_size > config.buffer_size,
+            "collector.batch_size",
+            format!(
+                "{} exceeds collector.buffer_size ({})",
+                config.batch_size, config.buffer_size
+            ),
+        );
     }
 
     /// Validate processor configuration
-    fn validate_processor(config: &super::ProcessorConfig) -> Result<()> {
+    fn validate_processor(config: &super::ProcessorConfig, issues: &mut Issues) {
         if !config.enabled {
-            return Ok(());
-        }
-
-        if config.worker_threads == 0 {
-            bail!("Worker threads must be greater than 0");
-        }
-
-        if config.window_size_secs == 0 {
-            bail!("Window size must be greater than 0");
+            return;
         }
 
-        Ok(())
+        issues.check(
+            config.worker_threads == 0,
+            "processor.worker_threads",
+            "must be greater than 0",
+        );
+        issues.check(
+            config.window_size_secs == 0,
+            "processor.window_size_secs",
+            "must be greater than 0",
+        );
     }
 
     /// Validate REST API configuration
-    fn validate_rest_api(config: &super::RestApiConfig) -> Result<()> {
+    fn validate_rest_api(config: &super::RestApiConfig, issues: &mut Issues) {
         if !config.enabled {
-            return Ok(());
+            return;
         }
 
-        if config.port == 0 || config.port > 65535 {
-            bail!("REST API port must be between 1 and 65535");
-        }
+        issues.check(
+            config.port == 0,
+            "rest_api.port",
+            "must be between 1 and 65535",
+        );
+        issues.check(
+            config.timeout_secs == 0,
+            "rest_api.timeout_secs",
+            "must be greater than 0",
+        );
+        issues.check(
+            !(0.0..=1.0).contains(&config.log_sample_rate),
+            "rest_api.log_sample_rate",
+            format!(
+                "must be between 0.0 and 1.0, got {}",
+                config.log_sample_rate
+            ),
+        );
+        issues.check(
+            config.decision_max_age_days == Some(0),
+            "rest_api.decision_max_age_days",
+            "must be greater than 0",
+        );
+        issues.check(
+            config.decision_keep_last == Some(0),
+            "rest_api.decision_keep_last",
+            "must be greater than 0",
+        );
+    }
 
-        if config.timeout_secs == 0 {
-            bail!("Request timeout must be greater than 0");
-        }
+    /// Check that the rationale model is a known Claude model with a client
+    /// to call it
+    fn validate_rationale_model(config: &Config, issues: &mut Issues) {
+        let Some(model) = &config.rest_api.rationale_model else {
+            return;
+        };
 
-        Ok(())
+        if let Err(e) = parse_model(model) {
+            issues.push("rest_api.rationale_model", e.to_string());
+        }
+        issues.check(
+            config.integrations.anthropic.is_none(),
+            "rest_api.rationale_model",
+            "requires integrations.anthropic, whose client writes the rationale",
+        );
     }
 
     /// Validate gRPC API configuration
-    fn validate_grpc_api(config: &super::GrpcApiConfig) -> Result<()> {
+    fn validate_grpc_api(config: &super::GrpcApiConfig, issues: &mut Issues) {
         if !config.enabled {
-            return Ok(());
-        }
-
-        if config.port == 0 || config.port > 65535 {
-            bail!("gRPC API port must be between 1 and 65535");
+            return;
         }
 
-        Ok(())
+        issues.check(
+            config.port == 0,
+            "grpc_api.port",
+            "must be between 1 and 65535",
+        );
+        issues.check(
+            !(0.0..=1.0).contains(&config.log_sample_rate),
+            "grpc_api.log_sample_rate",
+            format!(
+                "must be between 0.0 and 1.0, got {}",
+                config.log_sample_rate
+            ),
+        );
     }
 
     /// Validate storage configuration
-    fn validate_storage(config: &super::StorageConfig) -> Result<()> {
-        if config.postgres_url.is_empty() {
-            bail!("PostgreSQL URL cannot be empty");
-        }
-
-        if config.redis_url.is_empty() {
-            bail!("Redis URL cannot be empty");
-        }
+    fn validate_storage(config: &super::StorageConfig, issues: &mut Issues) {
+        issues.check(
+            config.postgres_url.trim().is_empty(),
+            "storage.postgres_url",
+            "connection string must not be empty",
+        );
+        issues.check(
+            config.redis_url.trim().is_empty(),
+            "storage.redis_url",
+            "connection string must not be empty",
+        );
+        issues.check(
+            config.sled_path.trim().is_empty(),
+            "storage.sled_path",
+            "must not be empty",
+        );
+        issues.check(
+            config.max_connections == 0,
+            "storage.max_connections",
+            "must be greater than 0",
+        );
+    }
 
-        if config.sled_path.is_empty() {
-            bail!("Sled path cannot be empty");
+    /// Validate observability configuration
+    fn validate_observability(config: &super::ObservabilityConfig, issues: &mut Issues) {
+        issues.check(
+            !super::LOG_LEVELS.contains(&config.log_level.as_str()),
+            "observability.log_level",
+            format!(
+                "'{}' is not one of: {}",
+                config.log_level,
+                super::LOG_LEVELS.join(", ")
+            ),
+        );
+        issues.check(
+            config.metrics_port == 0,
+            "observability.metrics_port",
+            "must be between 1 and 65535",
+        );
+
+        if let Some(endpoint) = &config.otel_endpoint {
+            issues.check(
+                endpoint.trim().is_empty(),
+                "observability.otel_endpoint",
+                "must not be empty when set",
+            );
         }
 
-        if config.max_connections == 0 {
-            bail!("Max connections must be greater than 0");
+        if let Some(url) = &config.remote_write_url {
+            issues.check(
+                !url.starts_with("http://") && !url.starts_with("https://"),
+                "observability.remote_write_url",
+                "must start with http:// or https://",
+            );
+            issues.check(
+                config.remote_write_interval_secs == 0,
+                "observability.remote_write_interval_secs",
+                "must be greater than 0 when remote write is enabled",
+            );
         }
-
-        Ok(())
     }
 
-    /// Validate observability configuration
-    fn validate_observability(config: &super::ObservabilityConfig) -> Result<()> {
-        let valid_log_levels = ["trace", "debug", "info", "warn", "error"];
-
-        if !valid_log_levels.contains(&config.log_level.as_str()) {
-            bail!(
-                "Invalid log level: {}. Must be one of: {:?}",
-                config.log_level,
-                valid_log_levels
+    /// Validate integrations configuration
+    fn validate_integrations(config: &super::IntegrationsConfig, issues: &mut Issues) {
+        let alerts = &config.cost_alerts;
+        issues.check(
+            alerts
+                .thresholds_usd
+                .iter()
+                .any(|threshold| !threshold.is_finite() || *threshold <= 0.0),
+            "integrations.cost_alerts.thresholds_usd",
+            "must all be greater than 0",
+        );
+        issues.check(
+            !alerts.thresholds_usd.is_empty() && alerts.check_interval_secs == 0,
+            "integrations.cost_alerts.check_interval_secs",
+            "must be greater than 0 when thresholds are set",
+        );
+        issues.check(
+            !alerts.thresholds_usd.is_empty() && config.anthropic.is_none(),
+            "integrations.cost_alerts.thresholds_usd",
+            "requires integrations.anthropic, whose spend is tracked",
+        );
+
+        if let Some(repository) = &alerts.github_repository {
+            let valid = matches!(
+                repository.split_once('/'),
+                Some((owner, repo)) if !owner.is_empty() && !repo.is_empty() && !repo.contains('/')
+            );
+            issues.check(
+                !valid,
+                "integrations.cost_alerts.github_repository",
+                format!("'{}' is not in owner/repo form", repository),
             );
         }
+    }
 
-        if config.metrics_port == 0 || config.metrics_port > 65535 {
-            bail!("Metrics port must be between 1 and 65535");
+    /// Check that no two listeners share a port
+    fn validate_ports(config: &Config, issues: &mut Issues) {
+        let mut listeners = Vec::new();
+        if config.rest_api.enabled {
+            listeners.push(("rest_api.port", config.rest_api.port));
+        }
+        if config.grpc_api.enabled {
+            listeners.push(("grpc_api.port", config.grpc_api.port));
+        }
+        listeners.push((
+            "observability.metrics_port",
+            config.observability.metrics_port,
+        ));
+
+        for (i, (field, port)) in listeners.iter().enumerate() {
+            if *port == 0 {
+                continue;
+            }
+            if let Some((other, _)) = listeners[..i].iter().find(|(_, p)| p == port) {
+                issues.push(
+                    *field,
+                    format!("port {} is already used by {}", port, other),
+                );
+            }
         }
-
-        Ok(())
     }
 }
 
@@ -178,10 +397,145 @@ mod tests {
         assert!(Validator::validate_config(&config).is_err());
     }
 
+    #[test]
+    fn test_validate_remote_write_url() {
+        let mut config = Config::default();
+        config.observability.remote_write_url = Some("prometheus:9090/api/v1/write".to_string());
+        assert!(Validator::validate_config(&config).is_err());
+
+        config.observability.remote_write_url =
+            Some("http://prometheus:9090/api/v1/write".to_string());
+        assert!(Validator::validate_config(&config).is_ok());
+
+        config.observability.remote_write_interval_secs = 0;
+        assert!(Validator::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_cost_alerts() {
+        let mut config = Config::default();
+        config.integrations.cost_alerts.thresholds_usd = vec![50.0, 100.0];
+        config.integrations.cost_alerts.github_repository = Some("acme/optimizer".to_string());
+        assert!(Validator::validate_config(&config).is_err());
+
+        config.integrations.anthropic = Some(crate::config::AnthropicConfig {
+            api_key: "sk-ant-test".to_string(),
+            base_url: None,
+            cost_store_path: None,
+        });
+        assert!(Validator::validate_config(&config).is_ok());
+
+        config.integrations.cost_alerts.thresholds_usd = vec![-1.0];
+        assert!(Validator::validate_config(&config).is_err());
+
+        config.integrations.cost_alerts.thresholds_usd = vec![50.0];
+        config.integrations.cost_alerts.github_repository = Some("optimizer".to_string());
+        assert!(Validator::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_rationale_model() {
+        let mut config = Config::default();
+        config.rest_api.rationale_model = Some("claude-3-haiku-20240307".to_string());
+        assert!(Validator::validate_config(&config)
+            .unwrap_err()
+            .has_field("rest_api.rationale_model"));
+
+        config.integrations.anthropic = Some(crate::config::AnthropicConfig {
+            api_key: "sk-ant-test".to_string(),
+            base_url: None,
+            cost_store_path: None,
+        });
+        assert!(Validator::validate_config(&config).is_ok());
+
+        config.rest_api.rationale_model = Some("gpt-4".to_string());
+        assert!(Validator::validate_config(&config)
+            .unwrap_err()
+            .has_field("rest_api.rationale_model"));
+    }
+
+    #[test]
+    fn test_validate_log_sample_rate() {
+        let mut config = Config::default();
+        config.rest_api.log_sample_rate = 0.1;
+        assert!(Validator::validate_config(&config).is_ok());
+
+        config.rest_api.log_sample_rate = 1.5;
+        assert!(Validator::validate_config(&config).is_err());
+
+        config.rest_api.log_sample_rate = 1.0;
+        config.grpc_api.log_sample_rate = -0.1;
+        assert!(Validator::validate_config(&config).is_err());
+    }
+
+    #[test]
+    fn test_validate_decision_retention() {
+        let mut config = Config::default();
+        config.rest_api.decision_max_age_days = Some(90);
+        config.rest_api.decision_keep_last = Some(10_000);
+        assert!(Validator::validate_config(&config).is_ok());
+
+        config.rest_api.decision_keep_last = Some(0);
+        assert!(Validator::validate_config(&config)
+            .unwrap_err()
+            .has_field("rest_api.decision_keep_last"));
+
+        config.rest_api.decision_keep_last = None;
+        config.rest_api.decision_max_age_days = Some(0);
+        assert!(Validator::validate_config(&config)
+            .unwrap_err()
+            .has_field("rest_api.decision_max_age_days"));
+    }
+
     #[test]
     fn test_validate_invalid_port() {
         let mut config = Config::default();
         config.rest_api.port = 0;
         assert!(Validator::validate_config(&config).is_err());
     }
+
+    #[test]
+    fn test_validate_reports_every_problem_with_field_paths() {
+        let mut config = Config::default();
+        config.storage.max_connections = 0;
+        config.storage.postgres_url = String::new();
+        config.processor.enabled = true;
+        config.processor.worker_threads = 0;
+        config.observability.log_level = "verbose".to_string();
+
+        let errors = Validator::validate_config(&config).unwrap_err();
+        let fields: Vec<_> = errors.issues.iter().map(|i| i.field.as_str()).collect();
+        assert_eq!(
+            fields,
+            vec![
+                "processor.worker_threads",
+                "storage.postgres_url",
+                "storage.max_connections",
+                "observability.log_level",
+            ]
+        );
+
+        let message = errors.to_string();
+        assert!(message.starts_with("4 configuration problems:"));
+        assert!(message.contains("storage.max_connections: must be greater than 0"));
+    }
+
+    #[test]
+    fn test_validate_port_conflicts() {
+        let mut config = Config::default();
+        config.rest_api.enabled = true;
+        config.grpc_api.enabled = true;
+        config.grpc_api.port = config.rest_api.port;
+        config.observability.metrics_port = config.rest_api.port;
+
+        let errors = Validator::validate_config(&config).unwrap_err();
+        assert_eq!(errors.issues.len(), 2);
+        assert!(errors.has_field("grpc_api.port"));
+        assert!(errors.has_field("observability.metrics_port"));
+        assert!(errors.issues[0].reason.contains("rest_api.port"));
+
+        config.rest_api.enabled = false;
+        config.grpc_api.enabled = false;
+        assert!(Validator::validate_config(&config).is_ok());
+    }
 }
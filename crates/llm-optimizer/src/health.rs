@@ -4,6 +4,9 @@
 //! with automatic recovery capabilities.
 
 use anyhow::Result;
+use chrono::Utc;
+use llm_optimizer_config::DrainState;
+use serde_json::json;
 use std::collections::HashMap;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
@@ -39,6 +42,8 @@ pub struct ServiceHealth {
     pub consecutive_failures: u32,
     /// Total failures
     pub total_failures: u64,
+    /// Number of times the service has been recovered
+    pub restart_count: u32,
     /// Uptime duration
     pub uptime: Duration,
 }
@@ -54,6 +59,9 @@ pub struct HealthMonitorConfig {
     pub auto_recovery: bool,
     /// Recovery retry limit
     pub max_recovery_attempts: u32,
+    /// Services that must be running and healthy for the process to be
+    /// ready; other services do not affect readiness
+    pub required_services: Vec<String>,
 }
 
 impl Default for HealthMonitorConfig {
@@ -63,6 +71,7 @@ impl Default for HealthMonitorConfig {
             failure_threshold: 3,
             auto_recovery: true,
             max_recovery_attempts: 3,
+            required_services: vec!["storage".to_string(), "processor".to_string()],
         }
     }
 }
@@ -72,6 +81,7 @@ pub struct HealthMonitor {
     config: HealthMonitorConfig,
     services: Arc<RwLock<HashMap<String, ServiceHealth>>>,
     start_time: Instant,
+    drain: Arc<DrainState>,
 }
 
 impl HealthMonitor {
@@ -81,6 +91,7 @@ impl HealthMonitor {
             config,
             services: Arc::new(RwLock::new(HashMap::new())),
             start_time: Instant::now(),
+            drain: Arc::new(DrainState::new()),
         }
     }
 
@@ -96,6 +107,7 @@ impl HealthMonitor {
                 last_check_time: None,
                 consecutive_failures: 0,
                 total_failures: 0,
+                restart_count: 0,
                 uptime: Duration::from_secs(0),
             },
         );
@@ -182,10 +194,58 @@ impl HealthMonitor {
         let mut services = self.services.write().await;
         if let Some(health) = services.get_mut(name) {
             health.consecutive_failures = 0;
+            health.restart_count += 1;
             info!("Service {} marked as recovered", name);
         }
     }
 
+    /// Report the process as not ready while `drain` is draining; share it
+    /// with the service manager and API servers
+    pub fn with_drain(mut self, drain: Arc<DrainState>) -> Self {
+        self.drain = drain;
+        self
+    }
+
+    /// Whether the process is draining for shutdown
+    pub fn is_draining(&self) -> bool {
+        self.drain.is_draining()
+    }
+
+    /// Whether the process is alive: health state can still be read, so
+    /// the monitor is not deadlocked
+    pub async fn is_live(&self) -> bool {
+        tokio::time::timeout(LIVENESS_LOCK_TIMEOUT, self.services.read())
+            .await
+            .is_ok()
+    }
+
+    /// Whether the process should receive traffic, and if not why
+    ///
+    /// Ready when not draining and every required service is registered,
+    /// running and passed its last health check.
+    pub async fn readiness(&self) -> Readiness {
+        let services = self.services.read().await;
+
+        let waiting_on = self
+            .config
+            .required_services
+            .iter()
+            .filter(|name| {
+                !services.get(*name).is_some_and(|health| {
+                    health.state == ServiceState::Running && health.consecutive_failures == 0
+                })
+            })
+            .cloned()
+            .collect::<Vec<_>>();
+        let draining = self.is_draining();
+
+        Readiness {
+            ready: !draining && waiting_on.is_empty(),
+            draining,
+            waiting_on,
+        }
+    }
+
     /// Get system uptime
     pub fn uptime(&self) -> Duration {
         self.start_time.elapsed()
@@ -230,6 +290,20 @@ impl HealthMonitor {
     }
 }
 
+/// How long [`HealthMonitor::is_live`] waits for the health state lock
+const LIVENESS_LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Readiness of the process to receive traffic
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct Readiness {
+    /// Whether the process is ready
+    pub ready: bool,
+    /// Whether the process is draining for shutdown
+    pub draining: bool,
+    /// Required services that are not yet running and healthy
+    pub waiting_on: Vec<String>,
+}
+
 /// Health check endpoint data
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct HealthCheckResponse {
@@ -292,6 +366,56 @@ impl HealthMonitor {
     }
 }
 
+impl HealthMonitor {
+    /// Serialize a full health snapshot for external dashboards
+    ///
+    /// `overall` is the worst state across all services (Failed > Degraded > Running).
+    pub async fn report_json(&self) -> serde_json::Value {
+        let services = self.services.read().await;
+
+        let mut report: Vec<&ServiceHealth> = services.values().collect();
+        report.sort_by(|a, b| a.name.cmp(&b.name));
+
+        let entries: Vec<serde_json::Value> = report
+            .iter()
+            .map(|health| {
+                json!({
+                    "name": health.name,
+                    "state": health.state.to_string(),
+                    "message": health.last_check.as_ref().and_then(|c| c.message.clone()),
+                    "metadata": health
+                        .last_check
+                        .as_ref()
+                        .map(|c| c.metadata.clone())
+                        .unwrap_or_default(),
+                    "consecutive_failures": health.consecutive_failures,
+                    "restart_count": health.restart_count,
+                })
+            })
+            .collect();
+
+        json!({
+            "overall": overall_state(services.values().map(|h| h.state)).to_string(),
+            "timestamp": Utc::now().to_rfc3339(),
+            "uptime_secs": self.uptime().as_secs(),
+            "services": entries,
+        })
+    }
+}
+
+/// Worst state across services, ranking Failed > Degraded > Running
+///
+/// States outside that ranking (initializing, stopped) count as Running.
+pub fn overall_state(states: impl IntoIterator<Item = ServiceState>) -> ServiceState {
+    states
+        .into_iter()
+        .fold(ServiceState::Running, |worst, state| match (worst, state) {
+            (ServiceState::Failed, _) | (_, ServiceState::Failed) => ServiceState::Failed,
+            (ServiceState::Degraded, _) | (_, ServiceState::Degraded) => ServiceState::Degraded,
+            _ => ServiceState::Running,
+        })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -373,4 +497,100 @@ mod tests {
         let health = monitor.get_service_health("test-service").await.unwrap();
         assert_eq!(health.consecutive_failures, 0);
     }
+
+    #[tokio::test]
+    async fn test_report_json() {
+        let monitor = HealthMonitor::new(HealthMonitorConfig::default());
+        monitor.register_service("storage".to_string()).await;
+        monitor.register_service("collector".to_string()).await;
+
+        monitor
+            .update_service_health(
+                "storage",
+                ServiceState::Degraded,
+                HealthCheckResult::unhealthy("Slow queries").with_metadata("latency_ms", "900"),
+            )
+            .await;
+        monitor
+            .update_service_health("collector", ServiceState::Running, HealthCheckResult::healthy())
+            .await;
+        monitor.mark_recovered("storage").await;
+
+        let report = monitor.report_json().await;
+        assert_eq!(report["overall"], "Degraded");
+        assert!(report["timestamp"].is_string());
+
+        let storage = &report["services"][1];
+        assert_eq!(storage["name"], "storage");
+        assert_eq!(storage["state"], "Degraded");
+        assert_eq!(storage["message"], "Slow queries");
+        assert_eq!(storage["metadata"]["latency_ms"], "900");
+        assert_eq!(storage["restart_count"], 1);
+        assert_eq!(report["services"][0]["name"], "collector");
+    }
+
+    #[test]
+    fn test_overall_state_is_worst_state() {
+        let (running, degraded, failed) =
+            (ServiceState::Running, ServiceState::Degraded, ServiceState::Failed);
+
+        assert_eq!(overall_state([]), running);
+        assert_eq!(overall_state([running, ServiceState::Initializing]), running);
+        assert_eq!(overall_state([running, degraded]), degraded);
+        assert_eq!(overall_state([failed, degraded, running]), failed);
+    }
+
+    #[tokio::test]
+    async fn test_readiness_follows_required_services_and_drain() {
+        let drain = Arc::new(DrainState::new());
+        let monitor = HealthMonitor::new(HealthMonitorConfig::default()).with_drain(drain.clone());
+        for name in ["storage", "processor", "rest-api"] {
+            monitor.register_service(name.to_string()).await;
+        }
+
+        let readiness = monitor.readiness().await;
+        assert!(!readiness.ready);
+        assert_eq!(readiness.waiting_on, vec!["storage", "processor"]);
+
+        for name in ["storage", "processor"] {
+            monitor
+                .update_service_health(name, ServiceState::Running, HealthCheckResult::healthy())
+                .await;
+        }
+        // Services outside `required_services` do not gate readiness
+        monitor
+            .update_service_health(
+                "rest-api",
+                ServiceState::Failed,
+                HealthCheckResult::unhealthy("bind failed"),
+            )
+            .await;
+        assert!(monitor.readiness().await.ready);
+        assert!(monitor.is_live().await);
+
+        drain.start();
+        let readiness = monitor.readiness().await;
+        assert!(!readiness.ready);
+        assert!(readiness.draining);
+        assert!(readiness.waiting_on.is_empty());
+        assert!(monitor.is_live().await);
+    }
+
+    #[tokio::test]
+    async fn test_unhealthy_required_service_is_not_ready() {
+        let monitor = HealthMonitor::new(HealthMonitorConfig {
+            required_services: vec!["storage".to_string()],
+            ..Default::default()
+        });
+        monitor.register_service("storage".to_string()).await;
+        monitor
+            .update_service_health(
+                "storage",
+                ServiceState::Running,
+                HealthCheckResult::unhealthy("connection refused"),
+            )
+            .await;
+
+        assert_eq!(monitor.readiness().await.waiting_on, vec!["storage"]);
+    }
 }
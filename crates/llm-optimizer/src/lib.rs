@@ -10,7 +10,7 @@
 //! - **Configuration Management**: Hot-reloadable configuration with validation
 //! - **Health Monitoring**: Automatic health checks and recovery
 //! - **Metrics Aggregation**: Prometheus-compatible metrics export
-//! - **Signal Handling**: Graceful shutdown and configuration reload
+//! - **Signal Handling**: Graceful shutdown, configuration reload and runtime log levels
 //! - **Dependency Management**: Topological service startup ordering
 //!
 //! ## Architecture
@@ -60,8 +60,8 @@ pub mod signals;
 pub use config::Config;
 pub use health::{HealthMonitor, SystemHealth};
 pub use metrics::MetricsAggregator;
-pub use service::{Service, ServiceManager, ServiceState};
-pub use signals::{SignalHandler, SignalType};
+pub use service::{RestartPolicy, Service, ServiceManager, ServiceState, ServiceStatus};
+pub use signals::{LogLevelControl, SignalHandler, SignalType};
 
 /// Library version
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -5,22 +5,25 @@
 
 use anyhow::{Context, Result};
 use clap::Parser;
+use llm_optimizer_config::DrainState;
 use llm_optimizer::{
-    config::Config,
+    config::{config_schema, Config, Validator},
     health::{HealthMonitor, HealthMonitorConfig},
-    metrics::{MetricsAggregator, ResourceMonitor},
+    metrics::{MetricsAggregator, RemoteWriteExporter, ResourceMonitor},
     service::{
-        CollectorService, GrpcApiService, IntegrationsService, ProcessorService, RestApiService,
-        ServiceManager, ServiceManagerConfig, StorageService,
+        CollectorService, GrpcApiService, HealthCheckResult, IntegrationsService, ProcessorService, RestApiService,
+        ServiceManager, ServiceManagerConfig, ServiceManagerHealth, ServiceManagerStatus,
+        StorageService,
     },
-    signals::SignalHandler,
+    signals::{LogLevelControl, SignalHandler},
 };
 use std::net::SocketAddr;
 use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{error, info, warn};
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilter};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter};
 
 /// Command line arguments
 #[derive(Debug, Parser)]
@@ -32,6 +35,11 @@ struct Cli {
     #[arg(short, long, value_name = "FILE")]
     config: Option<PathBuf>,
 
+    /// Read LLM_OPTIMIZER_ variables from this file instead of `./.env`;
+    /// real environment variables still take precedence
+    #[arg(long, value_name = "FILE")]
+    env_file: Option<PathBuf>,
+
     /// Override log level
     #[arg(short, long, value_name = "LEVEL")]
     log_level: Option<String>,
@@ -47,6 +55,10 @@ struct Cli {
     /// Print default configuration and exit
     #[arg(long)]
     print_default_config: bool,
+
+    /// Print the configuration JSON Schema and exit
+    #[arg(long)]
+    print_config_schema: bool,
 }
 
 #[tokio::main]
@@ -57,24 +69,39 @@ async fn main() -> Result<()> {
     // Print default config if requested
     if cli.print_default_config {
         let default_config = Config::default();
-        let yaml = serde_yaml::to_string(&default_config)?;
+        let yaml = serde_yaml::to_string(&default_config.redacted())?;
         println!("{}", yaml);
         return Ok(());
     }
 
-    // Load configuration
-    let config = Config::load(cli.config.clone())
-        .context("Failed to load configuration")?;
+    // Print config schema if requested
+    if cli.print_config_schema {
+        let schema = serde_json::to_string_pretty(&config_schema())?;
+        println!("{}", schema);
+        return Ok(());
+    }
 
-    // Validate configuration if requested
+    // Validate configuration if requested, reporting every problem found
     if cli.validate_config {
-        config.validate().context("Configuration validation failed")?;
+        let config = Config::load_unvalidated(cli.config.clone(), cli.env_file.as_deref())
+            .context("Failed to load configuration")?;
+        if let Err(errors) = Validator::validate_config(&config) {
+            eprintln!("Configuration is invalid:");
+            for issue in &errors.issues {
+                eprintln!("  - {}", issue);
+            }
+            std::process::exit(1);
+        }
         println!("Configuration is valid");
         return Ok(());
     }
 
+    // Load configuration
+    let config = Config::load_with_env_file(cli.config.clone(), cli.env_file.as_deref())
+        .context("Failed to load configuration")?;
+
     // Initialize observability
-    init_observability(&config, &cli)?;
+    let log_level = init_observability(&config, &cli)?;
 
     info!("Starting LLM Auto Optimizer v{}", env!("CARGO_PKG_VERSION"));
     info!("Environment: {}", config.service.environment);
@@ -85,19 +112,43 @@ async fn main() -> Result<()> {
     // Initialize metrics aggregator
     let metrics = Arc::new(MetricsAggregator::new());
 
-    // Initialize health monitor
-    let health_monitor = Arc::new(HealthMonitor::new(HealthMonitorConfig::default()));
+    // Shutdown drains through one state, so the API servers reject new
+    // requests and readiness fails as soon as it starts
+    let drain = Arc::new(DrainState::new());
+
+    // Initialize health monitor; readiness waits on storage and, if enabled,
+    // the processor
+    let mut required_services = vec!["storage".to_string()];
+    if config.read().await.processor.enabled {
+        required_services.push("processor".to_string());
+    }
+    let health_monitor = Arc::new(
+        HealthMonitor::new(HealthMonitorConfig {
+            required_services,
+            ..Default::default()
+        })
+        .with_drain(Arc::clone(&drain)),
+    );
 
     // Initialize signal handler
     let signal_handler = SignalHandler::new();
     let mut shutdown_rx = signal_handler.subscribe_shutdown();
     let mut reload_rx = signal_handler.subscribe_reload();
+    let mut dump_rx = signal_handler.subscribe_dump();
+    let mut log_level_rx = signal_handler.subscribe_log_level();
 
     // Start signal handler
     signal_handler.listen().await?;
 
     // Initialize service manager
-    let service_manager = Arc::new(ServiceManager::new(ServiceManagerConfig::default()));
+    let drain_timeout = Duration::from_secs(config.read().await.service.drain_timeout_secs);
+    let service_manager = Arc::new(
+        ServiceManager::new(ServiceManagerConfig {
+            drain_timeout,
+            ..Default::default()
+        })
+        .with_drain(Arc::clone(&drain)),
+    );
 
     // Create and register services
     info!("Initializing services");
@@ -125,13 +176,15 @@ async fn main() -> Result<()> {
     }
 
     // Integrations service (no dependencies)
+    let mut integration_clients = None;
     if true {
-        let integrations_config = llm_optimizer::service::integrations::IntegrationsServiceConfig {
-            jira_config: None,
-            anthropic_config: None,
-        };
-        let integrations_service = Box::new(IntegrationsService::new(integrations_config));
-        service_manager.add_service(integrations_service).await;
+        let integrations_config =
+            llm_optimizer::service::integrations::IntegrationsServiceConfig::from_config(
+                &config_guard,
+            );
+        let integrations_service = IntegrationsService::new(integrations_config);
+        integration_clients = Some(integrations_service.shared_clients());
+        service_manager.add_service(Box::new(integrations_service)).await;
         health_monitor.register_service("integrations".to_string()).await;
     }
 
@@ -147,28 +200,34 @@ async fn main() -> Result<()> {
 
     // REST API service (depends on processor and storage)
     if config_guard.rest_api.enabled {
-        let rest_api_config = llm_optimizer::service::rest_api::RestApiServiceConfig {
-            server_config: llm_optimizer_api_rest::ServerConfig {
-                host: config_guard.service.host.clone(),
-                port: config_guard.rest_api.port,
-                timeout_secs: config_guard.rest_api.timeout_secs,
-                ..Default::default()
-            },
-        };
-        let rest_api_service = Box::new(RestApiService::new(rest_api_config));
+        let mut rest_api_config =
+            llm_optimizer::service::rest_api::RestApiServiceConfig::from_config(&config_guard);
+        rest_api_config.server_config.service_status =
+            Some(Arc::new(ServiceManagerStatus::new(&service_manager)));
+        rest_api_config.server_config.drain = Arc::clone(&drain);
+        metrics
+            .register_with(|registry| {
+                rest_api_config.server_config.retention_metrics.register(registry)
+            })
+            .await;
+        let mut rest_api_service = RestApiService::new(rest_api_config);
+        if let Some(clients) = integration_clients.clone() {
+            rest_api_service = rest_api_service.with_integrations(clients);
+        }
+        let rest_api_service = Box::new(rest_api_service);
         service_manager.add_service(rest_api_service).await;
         health_monitor.register_service("rest-api".to_string()).await;
     }
 
     // gRPC API service (depends on processor and storage)
     if config_guard.grpc_api.enabled {
-        let grpc_api_config = llm_optimizer::service::grpc_api::GrpcApiServiceConfig {
-            server_config: llm_optimizer_api_grpc::GrpcServerConfig {
-                host: config_guard.service.host.clone(),
-                port: config_guard.grpc_api.port,
-                ..Default::default()
-            },
-        };
+        let mut grpc_api_config = llm_optimizer::service::grpc_api::GrpcApiServiceConfig::from_config(
+            &config_guard,
+            Some(Arc::new(ServiceManagerHealth::new(&service_manager))),
+        );
+        grpc_api_config.server_config.service_status =
+            Some(Arc::new(ServiceManagerStatus::new(&service_manager)));
+        grpc_api_config.server_config.drain = Arc::clone(&drain);
         let grpc_api_service = Box::new(GrpcApiService::new(grpc_api_config));
         service_manager.add_service(grpc_api_service).await;
         health_monitor.register_service("grpc-api".to_string()).await;
@@ -187,8 +246,20 @@ async fn main() -> Result<()> {
     let resource_monitor = ResourceMonitor::new((*metrics).clone());
     resource_monitor.start().await?;
 
+    // Start remote-write export, if configured
+    let observability = config.read().await.observability.clone();
+    if let Some(url) = observability.remote_write_url {
+        let exporter = RemoteWriteExporter::new(
+            (*metrics).clone(),
+            url,
+            Duration::from_secs(observability.remote_write_interval_secs),
+        );
+        exporter.start().await?;
+    }
+
     // Start metrics HTTP server
     let metrics_clone = Arc::clone(&metrics);
+    let metrics_health = Arc::clone(&health_monitor);
     let config_guard = config.read().await;
     let metrics_addr: SocketAddr = format!("0.0.0.0:{}", config_guard.observability.metrics_port)
         .parse()
@@ -196,20 +267,44 @@ async fn main() -> Result<()> {
     drop(config_guard);
 
     tokio::spawn(async move {
-        if let Err(e) = metrics_clone.serve(metrics_addr).await {
+        if let Err(e) = metrics_clone.serve(metrics_addr, metrics_health).await {
             error!("Metrics server error: {}", e);
         }
     });
 
     // Start health monitoring
     let service_manager_clone = Arc::clone(&service_manager);
-    let health_monitor_clone = Arc::clone(&health_monitor);
     tokio::spawn(async move {
         if let Err(e) = service_manager_clone.run_health_monitoring().await {
             error!("Health monitoring error: {}", e);
         }
     });
 
+    // Mirror service states into the health monitor behind /health and /readyz
+    let service_manager_clone = Arc::clone(&service_manager);
+    let health_monitor_clone = Arc::clone(&health_monitor);
+    tokio::spawn(async move {
+        let result = health_monitor_clone
+            .run_periodic_checks(|name| {
+                let manager = Arc::clone(&service_manager_clone);
+                async move {
+                    manager
+                        .get_health_status()
+                        .await
+                        .into_iter()
+                        .find(|(service, _, _)| *service == name)
+                        .map(|(_, state, check)| {
+                            (state, check.unwrap_or_else(HealthCheckResult::healthy))
+                        })
+                        .with_context(|| format!("Service {} is not managed", name))
+                }
+            })
+            .await;
+        if let Err(e) = result {
+            error!("Health monitor error: {}", e);
+        }
+    });
+
     info!("LLM Auto Optimizer is running");
     info!("REST API: http://{}:{}",
         config.read().await.service.host,
@@ -222,6 +317,12 @@ async fn main() -> Result<()> {
     info!("Metrics: http://0.0.0.0:{}/metrics",
         config.read().await.observability.metrics_port
     );
+    info!("Health: http://0.0.0.0:{}/health",
+        config.read().await.observability.metrics_port
+    );
+    info!("Probes: http://0.0.0.0:{0}/livez, http://0.0.0.0:{0}/readyz",
+        config.read().await.observability.metrics_port
+    );
 
     // Main event loop
     loop {
@@ -238,9 +339,21 @@ async fn main() -> Result<()> {
 
                 if let Some(config_path) = &cli.config {
                     let mut config_guard = config.write().await;
-                    match config_guard.reload(config_path).await {
+                    let old_config = config_guard.clone();
+                    match config_guard.reload(config_path, cli.env_file.as_deref()).await {
                         Ok(()) => {
                             info!("Configuration reloaded successfully");
+                            match service_manager.apply_config_change(&old_config, &config_guard).await {
+                                Ok(restarted) if restarted.is_empty() => {
+                                    info!("No services affected by configuration change");
+                                }
+                                Ok(restarted) => {
+                                    info!("Restarted services: {}", restarted.join(", "));
+                                }
+                                Err(e) => {
+                                    error!("Failed to apply configuration change: {}", e);
+                                }
+                            }
                         }
                         Err(e) => {
                             error!("Failed to reload configuration: {}", e);
@@ -250,12 +363,36 @@ async fn main() -> Result<()> {
                     warn!("Cannot reload configuration: no config file specified");
                 }
             }
+
+            // Handle log level signals
+            Ok(signal) = log_level_rx.recv() => {
+                match log_level.handle(signal) {
+                    // Warn so the change is visible at every level but error
+                    Ok(Some(level)) => warn!("Log level changed to {}", level),
+                    Ok(None) => warn!("Log level already at {}", log_level.level()),
+                    Err(e) => error!("Failed to change log level: {}", e),
+                }
+            }
+
+            // Handle metrics snapshot dump signal
+            _ = dump_rx.recv() => {
+                // Best-effort and off the main loop so a slow disk can't stall it
+                let metrics = Arc::clone(&metrics);
+                let dir = config.read().await.observability.snapshot_dir.clone();
+                tokio::spawn(async move {
+                    match metrics.dump_snapshot(&dir).await {
+                        Ok(file) => info!("Metrics snapshot written to {}", file.display()),
+                        Err(e) => warn!("Failed to dump metrics snapshot: {}", e),
+                    }
+                });
+            }
         }
     }
 
     // Graceful shutdown
     info!("Initiating graceful shutdown");
 
+    // Draining fails readiness first so load balancers stop sending traffic
     service_manager
         .stop_all()
         .await
@@ -270,8 +407,9 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-/// Initialize observability (logging and tracing)
-fn init_observability(config: &Config, cli: &Cli) -> Result<()> {
+/// Initialize observability (logging and tracing), returning the control
+/// used to change the log level at runtime
+fn init_observability(config: &Config, cli: &Cli) -> Result<LogLevelControl> {
     // Determine log level
     let log_level = cli
         .log_level
@@ -282,6 +420,7 @@ fn init_observability(config: &Config, cli: &Cli) -> Result<()> {
     let env_filter = EnvFilter::try_from_default_env()
         .or_else(|_| EnvFilter::try_new(log_level))
         .context("Invalid log level")?;
+    let (env_filter, reload_handle) = reload::Layer::new(env_filter);
 
     // Build subscriber
     if cli.json_logs || config.observability.json_logging {
@@ -300,5 +439,10 @@ fn init_observability(config: &Config, cli: &Cli) -> Result<()> {
 
     info!("Observability initialized with log level: {}", log_level);
 
-    Ok(())
+    Ok(LogLevelControl::new(log_level, move |level| {
+        let filter = EnvFilter::try_new(level).context("Invalid log level")?;
+        reload_handle
+            .reload(filter)
+            .context("Failed to reload log filter")
+    }))
 }
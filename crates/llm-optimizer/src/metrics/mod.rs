@@ -0,0 +1,764 @@
+//! Metrics aggregation and export
+//!
+//! This module provides centralized metrics collection and export
+//! for all services using Prometheus format.
+
+mod remote_write;
+
+pub use remote_write::RemoteWriteExporter;
+
+use crate::health::HealthMonitor;
+use crate::service::ServiceState;
+use anyhow::{anyhow, Result};
+use axum::http::StatusCode;
+use axum::routing::get;
+use axum::{Json, Router};
+use chrono::Utc;
+use prometheus_client::encoding::text::encode;
+use prometheus_client::metrics::counter::Counter;
+use prometheus_client::metrics::family::Family;
+use prometheus_client::metrics::gauge::Gauge;
+use prometheus_client::metrics::histogram::{exponential_buckets, Histogram};
+use prometheus_client::registry::Registry;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use sysinfo::{Pid, Process, System};
+use tokio::sync::RwLock;
+use tracing::{info, warn};
+
+/// Metrics labels
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct ServiceLabel {
+    /// Service name
+    pub service: String,
+}
+
+impl prometheus_client::encoding::EncodeLabelSet for ServiceLabel {
+    fn encode(
+        &self,
+        mut encoder: prometheus_client::encoding::LabelSetEncoder,
+    ) -> std::result::Result<(), std::fmt::Error> {
+        ("service", self.service.as_str()).encode(encoder.encode_label())?;
+        Ok(())
+    }
+}
+
+/// Operation labels
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct OperationLabel {
+    /// Operation name
+    pub operation: String,
+    /// Status (success, error)
+    pub status: String,
+}
+
+impl prometheus_client::encoding::EncodeLabelSet for OperationLabel {
+    fn encode(
+        &self,
+        mut encoder: prometheus_client::encoding::LabelSetEncoder,
+    ) -> std::result::Result<(), std::fmt::Error> {
+        ("operation", self.operation.as_str()).encode(encoder.encode_label())?;
+        ("status", self.status.as_str()).encode(encoder.encode_label())?;
+        Ok(())
+    }
+}
+
+/// Request labels
+#[derive(Clone, Debug, Hash, PartialEq, Eq)]
+pub struct RequestLabel {
+    /// Downstream service name
+    pub service: String,
+    /// HTTP method
+    pub method: String,
+    /// Operation name
+    pub operation: String,
+    /// Status (success, error)
+    pub status: String,
+}
+
+impl prometheus_client::encoding::EncodeLabelSet for RequestLabel {
+    fn encode(
+        &self,
+        mut encoder: prometheus_client::encoding::LabelSetEncoder,
+    ) -> std::result::Result<(), std::fmt::Error> {
+        ("service", self.service.as_str()).encode(encoder.encode_label())?;
+        ("method", self.method.as_str()).encode(encoder.encode_label())?;
+        ("operation", self.operation.as_str()).encode(encoder.encode_label())?;
+        ("status", self.status.as_str()).encode(encoder.encode_label())?;
+        Ok(())
+    }
+}
+
+/// System metrics
+pub struct SystemMetrics {
+    /// Service status (1 = running, 0 = stopped)
+    pub service_status: Family<ServiceLabel, Gauge>,
+    /// Service health (1 = healthy, 0 = unhealthy)
+    pub service_health: Family<ServiceLabel, Gauge>,
+    /// Service uptime in seconds
+    pub service_uptime: Family<ServiceLabel, Gauge>,
+    /// Total requests
+    pub requests_total: Family<RequestLabel, Counter>,
+    /// Request duration histogram
+    pub request_duration: Family<RequestLabel, Histogram>,
+    /// Active connections
+    pub active_connections: Family<ServiceLabel, Gauge>,
+    /// Memory usage in bytes
+    pub memory_usage_bytes: Gauge,
+    /// CPU usage percentage
+    pub cpu_usage_percent: Gauge,
+}
+
+impl SystemMetrics {
+    /// Create new system metrics
+    pub fn new() -> Self {
+        Self {
+            service_status: Family::default(),
+            service_health: Family::default(),
+            service_uptime: Family::default(),
+            requests_total: Family::default(),
+            request_duration: Family::new_with_constructor(|| {
+                Histogram::new(exponential_buckets(0.001, 2.0, 10))
+            }),
+            active_connections: Family::default(),
+            memory_usage_bytes: Gauge::default(),
+            cpu_usage_percent: Gauge::default(),
+        }
+    }
+
+    /// Register metrics with a registry
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "service_status",
+            "Service status (1 = running, 0 = stopped)",
+            self.service_status.clone(),
+        );
+
+        registry.register(
+            "service_health",
+            "Service health (1 = healthy, 0 = unhealthy)",
+            self.service_health.clone(),
+        );
+
+        registry.register(
+            "service_uptime_seconds",
+            "Service uptime in seconds",
+            self.service_uptime.clone(),
+        );
+
+        registry.register(
+            "requests_total",
+            "Total number of requests",
+            self.requests_total.clone(),
+        );
+
+        registry.register(
+            "request_duration_seconds",
+            "Request duration histogram",
+            self.request_duration.clone(),
+        );
+
+        registry.register(
+            "active_connections",
+            "Number of active connections",
+            self.active_connections.clone(),
+        );
+
+        registry.register(
+            "memory_usage_bytes",
+            "Memory usage in bytes",
+            self.memory_usage_bytes.clone(),
+        );
+
+        registry.register(
+            "cpu_usage_percent",
+            "CPU usage percentage",
+            self.cpu_usage_percent.clone(),
+        );
+    }
+}
+
+impl Default for SystemMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Metrics aggregator
+pub struct MetricsAggregator {
+    registry: Arc<RwLock<Registry>>,
+    metrics: Arc<SystemMetrics>,
+    /// Last successfully encoded export, served if encoding fails
+    last_export: Arc<RwLock<Option<String>>>,
+}
+
+impl MetricsAggregator {
+    /// Create a new metrics aggregator
+    pub fn new() -> Self {
+        let mut registry = Registry::default();
+        let metrics = Arc::new(SystemMetrics::new());
+
+        metrics.register(&mut registry);
+
+        Self {
+            registry: Arc::new(RwLock::new(registry)),
+            metrics,
+            last_export: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// Get the system metrics
+    pub fn metrics(&self) -> Arc<SystemMetrics> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Register metrics owned by a service, such as the REST API's decision
+    /// retention counts, so they are exported alongside the system metrics
+    pub async fn register_with(&self, register: impl FnOnce(&mut Registry)) {
+        register(&mut *self.registry.write().await);
+    }
+
+    /// Export metrics in Prometheus format
+    pub async fn export(&self) -> Result<String> {
+        let registry = self.registry.read().await;
+        let mut buffer = String::new();
+        encode(&mut buffer, &registry)?;
+        *self.last_export.write().await = Some(buffer.clone());
+        Ok(buffer)
+    }
+
+    /// Export metrics, falling back to the last successful export
+    ///
+    /// If encoding fails and an earlier export exists, it is returned with a
+    /// leading `# encode-error` comment so scrapers keep receiving data.
+    pub async fn export_or_cached(&self) -> Result<String> {
+        match self.export().await {
+            Ok(metrics) => Ok(metrics),
+            Err(e) => match self.last_export.read().await.as_ref() {
+                Some(cached) => {
+                    warn!("Failed to encode metrics, serving last snapshot: {}", e);
+                    Ok(format!("# encode-error {}\n{}", e, cached))
+                }
+                None => Err(e),
+            },
+        }
+    }
+
+    /// Write a full metrics snapshot to a timestamped file in `dir`
+    ///
+    /// Intended for post-mortems; returns the path of the written file.
+    pub async fn dump_snapshot(&self, dir: &Path) -> Result<PathBuf> {
+        let snapshot = self.export().await?;
+
+        tokio::fs::create_dir_all(dir).await?;
+        let file = dir.join(format!(
+            "metrics-{}.prom",
+            Utc::now().format("%Y%m%dT%H%M%S%.3fZ")
+        ));
+        tokio::fs::write(&file, snapshot).await?;
+
+        Ok(file)
+    }
+
+    /// Start metrics HTTP server
+    ///
+    /// Serves Prometheus metrics on `/metrics`, the health report on `/health`
+    /// and Kubernetes liveness and readiness probes on `/livez` and `/readyz`.
+    pub async fn serve(&self, addr: SocketAddr, health: Arc<HealthMonitor>) -> Result<()> {
+        info!("Starting metrics server on {}", addr);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        axum::serve(listener, self.router(health)).await?;
+
+        Ok(())
+    }
+
+    /// Build the metrics server routes
+    fn router(&self, health: Arc<HealthMonitor>) -> Router {
+        let aggregator = Arc::new(self.clone());
+        let live = Arc::clone(&health);
+        let ready = Arc::clone(&health);
+
+        Router::new()
+            .route(
+                "/metrics",
+                get(move || {
+                    let agg = Arc::clone(&aggregator);
+                    async move {
+                        match agg.export_or_cached().await {
+                            Ok(metrics) => (
+                                StatusCode::OK,
+                                [("content-type", "text/plain; charset=utf-8")],
+                                metrics,
+                            ),
+                            Err(e) => (
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                [("content-type", "text/plain; charset=utf-8")],
+                                format!("Error exporting metrics: {}", e),
+                            ),
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/health",
+                get(move || {
+                    let health = Arc::clone(&health);
+                    async move {
+                        let report = health.report_json().await;
+                        let status = if report["overall"] == ServiceState::Failed.to_string() {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        } else {
+                            StatusCode::OK
+                        };
+                        (status, Json(report))
+                    }
+                }),
+            )
+            .route(
+                "/livez",
+                get(move || {
+                    let live = Arc::clone(&live);
+                    async move {
+                        if live.is_live().await {
+                            (StatusCode::OK, "ok")
+                        } else {
+                            (StatusCode::SERVICE_UNAVAILABLE, "health state unavailable")
+                        }
+                    }
+                }),
+            )
+            .route(
+                "/readyz",
+                get(move || {
+                    let ready = Arc::clone(&ready);
+                    async move {
+                        let readiness = ready.readiness().await;
+                        let status = if readiness.ready {
+                            StatusCode::OK
+                        } else {
+                            StatusCode::SERVICE_UNAVAILABLE
+                        };
+                        (status, Json(readiness))
+                    }
+                }),
+            )
+    }
+
+    /// Update service status
+    pub fn update_service_status(&self, service: &str, running: bool) {
+        self.metrics
+            .service_status
+            .get_or_create(&ServiceLabel {
+                service: service.to_string(),
+            })
+            .set(if running { 1 } else { 0 });
+    }
+
+    /// Update service health
+    pub fn update_service_health(&self, service: &str, healthy: bool) {
+        self.metrics
+            .service_health
+            .get_or_create(&ServiceLabel {
+                service: service.to_string(),
+            })
+            .set(if healthy { 1 } else { 0 });
+    }
+
+    /// Update service uptime
+    pub fn update_service_uptime(&self, service: &str, uptime_secs: u64) {
+        self.metrics
+            .service_uptime
+            .get_or_create(&ServiceLabel {
+                service: service.to_string(),
+            })
+            .set(uptime_secs as i64);
+    }
+
+    /// Increment request counter
+    pub fn increment_requests(&self, operation: &str, status: &str) {
+        self.increment_service_requests("", "", operation, status);
+    }
+
+    /// Increment request counter for a downstream service and HTTP method
+    pub fn increment_service_requests(&self, service: &str, method: &str, operation: &str, status: &str) {
+        self.metrics
+            .requests_total
+            .get_or_create(&RequestLabel {
+                service: service.to_string(),
+                method: method.to_string(),
+                operation: operation.to_string(),
+                status: status.to_string(),
+            })
+            .inc();
+    }
+
+    /// Observe request duration
+    pub fn observe_request_duration(&self, operation: &str, status: &str, duration_secs: f64) {
+        self.observe_service_request_duration("", "", operation, status, duration_secs);
+    }
+
+    /// Observe request duration for a downstream service and HTTP method
+    pub fn observe_service_request_duration(
+        &self,
+        service: &str,
+        method: &str,
+        operation: &str,
+        status: &str,
+        duration_secs: f64,
+    ) {
+        self.metrics
+            .request_duration
+            .get_or_create(&RequestLabel {
+                service: service.to_string(),
+                method: method.to_string(),
+                operation: operation.to_string(),
+                status: status.to_string(),
+            })
+            .observe(duration_secs);
+    }
+
+    /// Update active connections
+    pub fn update_active_connections(&self, service: &str, count: i64) {
+        self.metrics
+            .active_connections
+            .get_or_create(&ServiceLabel {
+                service: service.to_string(),
+            })
+            .set(count);
+    }
+
+    /// Update memory usage
+    pub fn update_memory_usage(&self, bytes: i64) {
+        self.metrics.memory_usage_bytes.set(bytes);
+    }
+
+    /// Update CPU usage
+    pub fn update_cpu_usage(&self, percent: f64) {
+        self.metrics.cpu_usage_percent.set(percent as i64);
+    }
+}
+
+impl Clone for MetricsAggregator {
+    fn clone(&self) -> Self {
+        Self {
+            registry: Arc::clone(&self.registry),
+            metrics: Arc::clone(&self.metrics),
+            last_export: Arc::clone(&self.last_export),
+        }
+    }
+}
+
+impl Default for MetricsAggregator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Resource monitor for system metrics
+pub struct ResourceMonitor {
+    aggregator: MetricsAggregator,
+}
+
+impl ResourceMonitor {
+    /// Create a new resource monitor
+    pub fn new(aggregator: MetricsAggregator) -> Self {
+        Self { aggregator }
+    }
+
+    /// Start monitoring system resources
+    pub async fn start(&self) -> Result<()> {
+        info!("Starting resource monitor");
+
+        let aggregator = self.aggregator.clone();
+
+        let mut sampler = ProcessSampler::new()?;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(std::time::Duration::from_secs(10));
+
+            loop {
+                interval.tick().await;
+
+                // Update system metrics
+                sampler.refresh();
+
+                if let Ok(memory) = sampler.memory_usage() {
+                    aggregator.update_memory_usage(memory as i64);
+                }
+
+                if let Ok(cpu) = sampler.cpu_usage() {
+                    aggregator.update_cpu_usage(cpu);
+                }
+            }
+        });
+
+        Ok(())
+    }
+}
+
+/// Samples resource usage of the current process
+///
+/// CPU usage is computed by sysinfo as the delta between two refreshes, so
+/// the sampler must be kept alive across ticks of the monitoring interval.
+struct ProcessSampler {
+    system: System,
+    pid: Pid,
+}
+
+impl ProcessSampler {
+    /// Create a sampler for the current process and take the baseline reading
+    fn new() -> Result<Self> {
+        let pid = sysinfo::get_current_pid().map_err(|e| anyhow!(e))?;
+        let mut system = System::new();
+        system.refresh_process(pid);
+        Ok(Self { system, pid })
+    }
+
+    /// Refresh the process statistics
+    fn refresh(&mut self) {
+        self.system.refresh_process(self.pid);
+    }
+
+    /// Get current memory usage (RSS) in bytes
+    fn memory_usage(&self) -> Result<u64> {
+        self.process().map(Process::memory)
+    }
+
+    /// Get CPU usage percentage since the previous refresh
+    fn cpu_usage(&self) -> Result<f64> {
+        self.process().map(|p| f64::from(p.cpu_usage()))
+    }
+
+    fn process(&self) -> Result<&Process> {
+        self.system
+            .process(self.pid)
+            .ok_or_else(|| anyhow!("Process {} not found", self.pid))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_metrics_aggregator() {
+        let aggregator = MetricsAggregator::new();
+
+        // Update metrics
+        aggregator.update_service_status("test", true);
+        aggregator.update_service_health("test", true);
+        aggregator.update_service_uptime("test", 100);
+        aggregator.increment_requests("test_op", "success");
+        aggregator.observe_request_duration("test_op", "success", 0.5);
+
+        // Export metrics
+        let exported = aggregator.export().await.unwrap();
+        assert!(!exported.is_empty());
+        assert!(exported.contains("service_status"));
+    }
+
+    #[tokio::test]
+    async fn test_registered_service_metrics_are_exported() {
+        use llm_optimizer_api_rest::routes::optimize::RetentionMetrics;
+
+        let aggregator = MetricsAggregator::new();
+        let retention = RetentionMetrics::new();
+        aggregator.register_with(|registry| retention.register(registry)).await;
+
+        retention.archived.inc_by(3);
+        let exported = aggregator.export().await.unwrap();
+        assert!(exported.contains("decisions_archived_total 3"), "{}", exported);
+    }
+
+    #[tokio::test]
+    async fn test_dump_snapshot_writes_prometheus_file() {
+        let aggregator = MetricsAggregator::new();
+        aggregator.update_service_status("storage", true);
+
+        let dir = tempfile::tempdir().unwrap();
+        let snapshot_dir = dir.path().join("snapshots");
+        let file = aggregator.dump_snapshot(&snapshot_dir).await.unwrap();
+
+        assert_eq!(file.parent(), Some(snapshot_dir.as_path()));
+        let name = file.file_name().unwrap().to_str().unwrap();
+        assert!(name.starts_with("metrics-") && name.ends_with(".prom"));
+
+        let contents = std::fs::read_to_string(&file).unwrap();
+        assert!(contents.contains("# TYPE"));
+        assert!(contents.contains("service_status{service=\"storage\"} 1"));
+        assert!(contents.ends_with("# EOF\n"));
+    }
+
+    #[tokio::test]
+    async fn test_health_route_reports_overall_state() {
+        use crate::health::HealthMonitorConfig;
+        use crate::service::HealthCheckResult;
+        use tower::ServiceExt;
+
+        let health = Arc::new(HealthMonitor::new(HealthMonitorConfig::default()));
+        health.register_service("storage".to_string()).await;
+        health
+            .update_service_health("storage", ServiceState::Failed, HealthCheckResult::unhealthy("down"))
+            .await;
+
+        let response = MetricsAggregator::new()
+            .router(health)
+            .oneshot(axum::http::Request::get("/health").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report["overall"], "Failed");
+        assert_eq!(report["services"][0]["message"], "down");
+    }
+
+    #[tokio::test]
+    async fn test_readyz_turns_unavailable_when_draining() {
+        use crate::health::HealthMonitorConfig;
+        use crate::service::HealthCheckResult;
+        use llm_optimizer_config::DrainState;
+        use tower::ServiceExt;
+
+        let drain = Arc::new(DrainState::new());
+        let health = Arc::new(
+            HealthMonitor::new(HealthMonitorConfig {
+                required_services: vec!["storage".to_string()],
+                ..Default::default()
+            })
+            .with_drain(drain.clone()),
+        );
+        health.register_service("storage".to_string()).await;
+        let router = MetricsAggregator::new().router(Arc::clone(&health));
+        let status_of = |uri: &'static str| {
+            let router = router.clone();
+            async move {
+                router
+                    .oneshot(axum::http::Request::get(uri).body(axum::body::Body::empty()).unwrap())
+                    .await
+                    .unwrap()
+                    .status()
+            }
+        };
+
+        assert_eq!(status_of("/livez").await, StatusCode::OK);
+        assert_eq!(status_of("/readyz").await, StatusCode::SERVICE_UNAVAILABLE);
+
+        health
+            .update_service_health("storage", ServiceState::Running, HealthCheckResult::healthy())
+            .await;
+        assert_eq!(status_of("/readyz").await, StatusCode::OK);
+
+        drain.start();
+        assert_eq!(status_of("/readyz").await, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(status_of("/livez").await, StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_service_request_labels() {
+        let aggregator = MetricsAggregator::new();
+
+        aggregator.increment_service_requests("anthropic", "POST", "complete", "success");
+        aggregator.observe_service_request_duration("anthropic", "POST", "complete", "success", 0.2);
+        aggregator.increment_requests("optimize", "error");
+
+        let exported = aggregator.export().await.unwrap();
+        assert!(exported.contains(r#"{service="anthropic",method="POST",operation="complete",status="success"} 1"#));
+        assert!(exported.contains(r#"{service="",method="",operation="optimize",status="error"} 1"#));
+        assert!(exported.contains(r#"request_duration_seconds_count{service="anthropic",method="POST""#));
+    }
+
+    /// Metric whose encoding always fails
+    #[derive(Debug)]
+    struct FailingMetric;
+
+    impl prometheus_client::encoding::EncodeMetric for FailingMetric {
+        fn encode(
+            &self,
+            _encoder: prometheus_client::encoding::MetricEncoder,
+        ) -> std::result::Result<(), std::fmt::Error> {
+            Err(std::fmt::Error)
+        }
+
+        fn metric_type(&self) -> prometheus_client::metrics::MetricType {
+            prometheus_client::metrics::MetricType::Gauge
+        }
+    }
+
+    #[tokio::test]
+    async fn test_metrics_route_serves_cached_snapshot_on_encode_error() {
+        use crate::health::HealthMonitorConfig;
+        use tower::ServiceExt;
+
+        let aggregator = MetricsAggregator::new();
+        aggregator.update_service_status("test", true);
+        let snapshot = aggregator.export().await.unwrap();
+
+        aggregator
+            .registry
+            .write()
+            .await
+            .register("broken", "Always fails to encode", FailingMetric);
+        assert!(aggregator.export().await.is_err());
+
+        let health = Arc::new(HealthMonitor::new(HealthMonitorConfig::default()));
+        let response = aggregator
+            .router(health)
+            .oneshot(axum::http::Request::get("/metrics").body(axum::body::Body::empty()).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        let (comment, cached) = body.split_once('\n').unwrap();
+        assert!(comment.starts_with("# encode-error"));
+        assert_eq!(cached, snapshot);
+    }
+
+    #[tokio::test]
+    async fn test_export_error_without_snapshot() {
+        let aggregator = MetricsAggregator::new();
+        aggregator
+            .registry
+            .write()
+            .await
+            .register("broken", "Always fails to encode", FailingMetric);
+
+        assert!(aggregator.export_or_cached().await.is_err());
+    }
+
+    #[test]
+    fn test_service_label() {
+        let label = ServiceLabel {
+            service: "test".to_string(),
+        };
+        assert_eq!(label.service, "test");
+    }
+
+    #[test]
+    fn test_operation_label() {
+        let label = OperationLabel {
+            operation: "test_op".to_string(),
+            status: "success".to_string(),
+        };
+        assert_eq!(label.operation, "test_op");
+        assert_eq!(label.status, "success");
+    }
+
+    #[test]
+    fn test_memory_usage_after_large_allocation() {
+        let buffer = vec![1u8; 64 * 1024 * 1024];
+
+        let mut sampler = ProcessSampler::new().unwrap();
+        sampler.refresh();
+
+        let memory = sampler.memory_usage().unwrap();
+        assert!(memory > 0);
+        assert!(sampler.cpu_usage().unwrap() >= 0.0);
+        assert_eq!(buffer.len(), 64 * 1024 * 1024);
+    }
+}
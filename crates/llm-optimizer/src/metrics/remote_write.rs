@@ -0,0 +1,421 @@
+//! Prometheus remote-write export
+//!
+//! Pushes snapshots of the metrics registry to a remote-write endpoint, for
+//! environments where the `/metrics` endpoint cannot be scraped.
+
+use super::MetricsAggregator;
+use anyhow::{anyhow, Context, Result};
+use prost::Message;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::{debug, info, warn};
+
+/// Default number of samples per request
+const DEFAULT_BATCH_SIZE: usize = 500;
+
+/// Default number of retries for a batch after the first attempt
+const DEFAULT_MAX_RETRIES: u32 = 3;
+
+/// Default delay before the first retry; doubles on every retry
+const DEFAULT_INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+
+/// Upper bound for the retry delay
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// Timeout for a single remote-write request
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// `prometheus.WriteRequest`
+#[derive(Clone, PartialEq, Message)]
+struct WriteRequest {
+    #[prost(message, repeated, tag = "1")]
+    timeseries: Vec<TimeSeries>,
+}
+
+/// `prometheus.TimeSeries`
+#[derive(Clone, PartialEq, Message)]
+struct TimeSeries {
+    #[prost(message, repeated, tag = "1")]
+    labels: Vec<Label>,
+    #[prost(message, repeated, tag = "2")]
+    samples: Vec<Sample>,
+}
+
+/// `prometheus.Label`
+#[derive(Clone, PartialEq, Message)]
+struct Label {
+    #[prost(string, tag = "1")]
+    name: String,
+    #[prost(string, tag = "2")]
+    value: String,
+}
+
+/// `prometheus.Sample`
+#[derive(Clone, PartialEq, Message)]
+struct Sample {
+    #[prost(double, tag = "1")]
+    value: f64,
+    #[prost(int64, tag = "2")]
+    timestamp: i64,
+}
+
+/// Outcome of a failed send
+enum SendError {
+    /// Network errors, 5xx and 429; worth retrying
+    Transient(String),
+    /// Other 4xx; retrying would send the same rejected payload
+    Permanent(String),
+}
+
+/// Periodically pushes registry snapshots via Prometheus remote-write
+#[derive(Clone)]
+pub struct RemoteWriteExporter {
+    aggregator: MetricsAggregator,
+    client: reqwest::Client,
+    url: String,
+    interval: Duration,
+    batch_size: usize,
+    max_retries: u32,
+    initial_backoff: Duration,
+}
+
+impl RemoteWriteExporter {
+    /// Create an exporter pushing to `url` every `interval`
+    pub fn new(aggregator: MetricsAggregator, url: impl Into<String>, interval: Duration) -> Self {
+        Self {
+            aggregator,
+            client: reqwest::Client::builder()
+                .timeout(REQUEST_TIMEOUT)
+                .build()
+                .unwrap_or_default(),
+            url: url.into(),
+            interval,
+            batch_size: DEFAULT_BATCH_SIZE,
+            max_retries: DEFAULT_MAX_RETRIES,
+            initial_backoff: DEFAULT_INITIAL_BACKOFF,
+        }
+    }
+
+    /// Set the maximum number of samples per request
+    #[must_use]
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set how often a failed batch is retried and the initial backoff
+    #[must_use]
+    pub fn with_retry(mut self, max_retries: u32, initial_backoff: Duration) -> Self {
+        self.max_retries = max_retries;
+        self.initial_backoff = initial_backoff;
+        self
+    }
+
+    /// Start pushing in the background
+    ///
+    /// Failed pushes are logged and retried on the next tick.
+    pub async fn start(&self) -> Result<()> {
+        info!("Starting remote-write export to {} every {:?}", self.url, self.interval);
+
+        let exporter = self.clone();
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(exporter.interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+            loop {
+                interval.tick().await;
+
+                if let Err(e) = exporter.push().await {
+                    warn!("Remote-write push to {} failed: {:#}", exporter.url, e);
+                }
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Snapshot the registry and push it once
+    pub async fn push(&self) -> Result<()> {
+        let text = self.aggregator.export().await?;
+        let series = parse_exposition(&text, now_millis());
+
+        for batch in series.chunks(self.batch_size) {
+            self.send_batch(batch).await?;
+        }
+
+        debug!("Pushed {} samples to {}", series.len(), self.url);
+        Ok(())
+    }
+
+    /// Send one batch, retrying transient failures with exponential backoff
+    async fn send_batch(&self, batch: &[TimeSeries]) -> Result<()> {
+        let request = WriteRequest {
+            timeseries: batch.to_vec(),
+        };
+        let body = snap::raw::Encoder::new()
+            .compress_vec(&request.encode_to_vec())
+            .context("Failed to compress remote-write payload")?;
+
+        let mut backoff = self.initial_backoff;
+        let mut attempt = 0;
+
+        loop {
+            match self.send(body.clone()).await {
+                Ok(()) => return Ok(()),
+                Err(SendError::Transient(e)) if attempt < self.max_retries => {
+                    attempt += 1;
+                    debug!("Remote-write attempt {attempt} failed ({e}), retrying in {backoff:?}");
+                    tokio::time::sleep(backoff).await;
+                    backoff = (backoff * 2).min(MAX_BACKOFF);
+                }
+                Err(SendError::Transient(e)) => {
+                    return Err(anyhow!("{e} (gave up after {attempt} retries)"));
+                }
+                Err(SendError::Permanent(e)) => return Err(anyhow!(e)),
+            }
+        }
+    }
+
+    async fn send(&self, body: Vec<u8>) -> std::result::Result<(), SendError> {
+        let response = self
+            .client
+            .post(&self.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/x-protobuf")
+            .header(reqwest::header::CONTENT_ENCODING, "snappy")
+            .header("X-Prometheus-Remote-Write-Version", "0.1.0")
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| SendError::Transient(format!("request failed: {e}")))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let message = format!("endpoint returned {status}");
+        if status.is_server_error() || status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            Err(SendError::Transient(message))
+        } else {
+            Err(SendError::Permanent(message))
+        }
+    }
+}
+
+/// Current time in milliseconds since the Unix epoch
+fn now_millis() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| i64::try_from(d.as_millis()).unwrap_or(i64::MAX))
+}
+
+/// Convert text exposition output into one series per sample line
+fn parse_exposition(text: &str, timestamp: i64) -> Vec<TimeSeries> {
+    text.lines()
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| parse_sample(line, timestamp))
+        .collect()
+}
+
+/// Parse `name{label="value",...} value [timestamp] [# exemplar]`
+fn parse_sample(line: &str, timestamp: i64) -> Option<TimeSeries> {
+    let name_end = line.find(['{', ' '])?;
+    let name = &line[..name_end];
+
+    let mut labels = vec![Label {
+        name: "__name__".to_string(),
+        value: name.to_string(),
+    }];
+
+    let rest = if line[name_end..].starts_with('{') {
+        let (parsed, rest) = parse_labels(&line[name_end + 1..])?;
+        labels.extend(parsed);
+        rest
+    } else {
+        &line[name_end..]
+    };
+
+    let value = rest.split_whitespace().next()?.parse::<f64>().ok()?;
+
+    // Remote-write requires labels sorted by name
+    labels.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Some(TimeSeries {
+        labels,
+        samples: vec![Sample { value, timestamp }],
+    })
+}
+
+/// Parse a label set up to the closing brace, returning the remaining input
+fn parse_labels(input: &str) -> Option<(Vec<Label>, &str)> {
+    let mut labels = Vec::new();
+    let mut rest = input.trim_start();
+
+    loop {
+        if let Some(after) = rest.strip_prefix('}') {
+            return Some((labels, after));
+        }
+
+        let (name, after_name) = rest.split_once('=')?;
+        let mut chars = after_name.strip_prefix('"')?.char_indices();
+        let mut value = String::new();
+
+        let end = loop {
+            match chars.next()? {
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    other => value.push(other),
+                },
+                (i, '"') => break i,
+                (_, c) => value.push(c),
+            }
+        };
+
+        labels.push(Label {
+            name: name.trim().to_string(),
+            value,
+        });
+
+        rest = after_name[end + 2..].trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{header, method, path};
+    use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+    fn decode(request: &Request) -> WriteRequest {
+        let raw = snap::raw::Decoder::new().decompress_vec(&request.body).unwrap();
+        WriteRequest::decode(raw.as_slice()).unwrap()
+    }
+
+    fn label<'a>(series: &'a TimeSeries, name: &str) -> Option<&'a str> {
+        series
+            .labels
+            .iter()
+            .find(|l| l.name == name)
+            .map(|l| l.value.as_str())
+    }
+
+    fn aggregator() -> MetricsAggregator {
+        let aggregator = MetricsAggregator::new();
+        aggregator.update_service_status("api", true);
+        aggregator.update_service_health("api", true);
+        aggregator.observe_request_duration("optimize", "success", 0.5);
+        aggregator
+    }
+
+    #[test]
+    fn test_parse_sample_with_escaped_labels() {
+        let series =
+            parse_sample(r#"requests_total{operation="a\"b",status="ok"} 3 # {} 1"#, 42).unwrap();
+
+        let names: Vec<&str> = series.labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["__name__", "operation", "status"]);
+        assert_eq!(label(&series, "operation"), Some("a\"b"));
+        assert_eq!(series.samples, [Sample { value: 3.0, timestamp: 42 }]);
+
+        let series = parse_sample("memory_usage_bytes 1024", 1).unwrap();
+        assert_eq!(label(&series, "__name__"), Some("memory_usage_bytes"));
+        assert!((series.samples[0].value - 1024.0).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn test_registry_snapshot_covers_histograms() {
+        let text = aggregator().export().await.unwrap();
+        let series = parse_exposition(&text, 1);
+
+        assert!(series
+            .iter()
+            .any(|s| label(s, "__name__") == Some("service_status") && label(s, "service") == Some("api")));
+        assert!(series
+            .iter()
+            .any(|s| label(s, "le") == Some("+Inf") && (s.samples[0].value - 1.0).abs() < f64::EPSILON));
+    }
+
+    #[tokio::test]
+    async fn test_push_sends_batched_snappy_protobuf() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/v1/write"))
+            .and(header("content-encoding", "snappy"))
+            .and(header("content-type", "application/x-protobuf"))
+            .respond_with(ResponseTemplate::new(204))
+            .mount(&server)
+            .await;
+
+        let aggregator = aggregator();
+        let expected = parse_exposition(&aggregator.export().await.unwrap(), 0).len();
+
+        RemoteWriteExporter::new(aggregator, format!("{}/api/v1/write", server.uri()), Duration::from_secs(15))
+            .with_batch_size(5)
+            .push()
+            .await
+            .unwrap();
+
+        let requests = server.received_requests().await.unwrap();
+        assert_eq!(requests.len(), expected.div_ceil(5));
+
+        let sent: usize = requests.iter().map(|r| decode(r).timeseries.len()).sum();
+        assert_eq!(sent, expected);
+        assert!(requests.iter().all(|r| decode(r).timeseries.len() <= 5));
+    }
+
+    #[tokio::test]
+    async fn test_transient_failures_are_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(503))
+            .up_to_n_times(2)
+            .mount(&server)
+            .await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .mount(&server)
+            .await;
+
+        RemoteWriteExporter::new(aggregator(), server.uri(), Duration::from_secs(15))
+            .with_batch_size(10_000)
+            .with_retry(3, Duration::from_millis(1))
+            .push()
+            .await
+            .unwrap();
+
+        assert_eq!(server.received_requests().await.unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_errors_are_not_retried() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(400))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let result = RemoteWriteExporter::new(aggregator(), server.uri(), Duration::from_secs(15))
+            .with_batch_size(10_000)
+            .with_retry(3, Duration::from_millis(1))
+            .push()
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("400"));
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_endpoint_is_an_error_not_a_panic() {
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/api/v1/write", listener.local_addr().unwrap());
+        drop(listener);
+
+        let result = RemoteWriteExporter::new(aggregator(), url, Duration::from_secs(15))
+            .with_retry(1, Duration::from_millis(1))
+            .push()
+            .await;
+
+        assert!(result.unwrap_err().to_string().contains("gave up after 1 retries"));
+    }
+}
@@ -1,10 +1,12 @@
 //! gRPC API service wrapper
 
-use super::{HealthCheckResult, Service, ServiceState};
+use super::{HealthCheckResult, Service, ServiceManager, ServiceState};
+use crate::config::Config;
 use anyhow::Result;
 use async_trait::async_trait;
-use llm_optimizer_api_grpc::{GrpcServer, GrpcServerConfig};
-use std::sync::Arc;
+use llm_optimizer_api_grpc::{GrpcServer, GrpcServerConfig, HealthSource, ServingStatus};
+use std::fmt;
+use std::sync::{Arc, Weak};
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{info, warn};
@@ -16,6 +18,23 @@ pub struct GrpcApiServiceConfig {
     pub server_config: GrpcServerConfig,
 }
 
+impl GrpcApiServiceConfig {
+    /// Build the server configuration from the `service` and `grpc_api`
+    /// sections of `config`
+    pub fn from_config(config: &Config, health_source: Option<Arc<dyn HealthSource>>) -> Self {
+        Self {
+            server_config: GrpcServerConfig {
+                host: config.service.host.clone(),
+                port: config.grpc_api.port,
+                logging: llm_optimizer_api_grpc::interceptors::LoggingConfig::new()
+                    .with_sample_rate(config.grpc_api.log_sample_rate),
+                health_source,
+                ..Default::default()
+            },
+        }
+    }
+}
+
 /// gRPC API service manages the gRPC server
 pub struct GrpcApiService {
     config: GrpcApiServiceConfig,
@@ -46,6 +65,7 @@ impl Service for GrpcApiService {
         let mut state = self.state.write().await;
         *state = ServiceState::Running;
         drop(state);
+        self.config.server_config.drain.resume();
 
         // Start the gRPC server in a background task
         let config = self.config.server_config.clone();
@@ -85,6 +105,21 @@ impl Service for GrpcApiService {
         Ok(())
     }
 
+    async fn drain(&mut self) -> Result<()> {
+        let drain = Arc::clone(&self.config.server_config.drain);
+
+        let mut state = self.state.write().await;
+        *state = ServiceState::ShuttingDown;
+        drop(state);
+
+        drain.start();
+        info!("Draining gRPC API service, {} calls in flight", drain.in_flight());
+        drain.wait_idle().await;
+
+        info!("gRPC API service drained");
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<HealthCheckResult> {
         let handle_guard = self.server_handle.read().await;
 
@@ -113,6 +148,20 @@ impl Service for GrpcApiService {
         vec!["processor".to_string(), "storage".to_string()]
     }
 
+    fn config_sections(&self) -> Vec<String> {
+        vec!["service".to_string(), "grpc_api".to_string()]
+    }
+
+    fn apply_config(&mut self, config: &Config) -> Result<()> {
+        let health_source = self.config.server_config.health_source.clone();
+        let service_status = self.config.server_config.service_status.clone();
+        let drain = Arc::clone(&self.config.server_config.drain);
+        self.config = GrpcApiServiceConfig::from_config(config, health_source);
+        self.config.server_config.service_status = service_status;
+        self.config.server_config.drain = drain;
+        Ok(())
+    }
+
     async fn recover(&mut self) -> Result<()> {
         warn!("Attempting to recover gRPC API service");
 
@@ -122,6 +171,64 @@ impl Service for GrpcApiService {
     }
 }
 
+/// Reports the service manager's view of each service over the standard
+/// gRPC health checking protocol
+///
+/// Holds the manager weakly: the manager owns the gRPC service, so a strong
+/// reference would keep both alive forever.
+#[derive(Clone)]
+pub struct ServiceManagerHealth {
+    manager: Weak<ServiceManager>,
+}
+
+impl ServiceManagerHealth {
+    /// Report the health of the services in `manager`
+    pub fn new(manager: &Arc<ServiceManager>) -> Self {
+        Self {
+            manager: Arc::downgrade(manager),
+        }
+    }
+}
+
+impl fmt::Debug for ServiceManagerHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceManagerHealth")
+            .finish_non_exhaustive()
+    }
+}
+
+#[async_trait]
+impl HealthSource for ServiceManagerHealth {
+    async fn statuses(&self) -> Vec<(String, ServingStatus)> {
+        let Some(manager) = self.manager.upgrade() else {
+            return Vec::new();
+        };
+
+        manager
+            .get_health_status()
+            .await
+            .into_iter()
+            .map(|(name, state, last_check)| (name, serving_status(state, last_check.as_ref())))
+            .collect()
+    }
+}
+
+/// gRPC serving status for a service's state and last health check
+///
+/// Only a running service whose last check passed is serving. Degraded
+/// services are reported as not serving so probes and load balancers route
+/// around them.
+pub fn serving_status(
+    state: ServiceState,
+    last_check: Option<&HealthCheckResult>,
+) -> ServingStatus {
+    match (state, last_check) {
+        (ServiceState::Running, Some(check)) if !check.healthy => ServingStatus::NotServing,
+        (ServiceState::Running, _) => ServingStatus::Serving,
+        _ => ServingStatus::NotServing,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -136,4 +243,92 @@ mod tests {
         assert_eq!(service.state(), ServiceState::Initializing);
         assert_eq!(service.dependencies(), vec!["processor", "storage"]);
     }
+
+    #[test]
+    fn test_serving_status_mapping() {
+        let healthy = HealthCheckResult::healthy();
+        let unhealthy = HealthCheckResult::unhealthy("connection refused");
+
+        assert_eq!(
+            serving_status(ServiceState::Running, None),
+            ServingStatus::Serving
+        );
+        assert_eq!(
+            serving_status(ServiceState::Running, Some(&healthy)),
+            ServingStatus::Serving
+        );
+        assert_eq!(
+            serving_status(ServiceState::Running, Some(&unhealthy)),
+            ServingStatus::NotServing
+        );
+        for state in [
+            ServiceState::Initializing,
+            ServiceState::Degraded,
+            ServiceState::ShuttingDown,
+            ServiceState::Stopped,
+            ServiceState::Failed,
+        ] {
+            assert_eq!(
+                serving_status(state, Some(&healthy)),
+                ServingStatus::NotServing
+            );
+        }
+    }
+
+    struct StubService {
+        name: &'static str,
+        state: ServiceState,
+    }
+
+    #[async_trait]
+    impl Service for StubService {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<HealthCheckResult> {
+            Ok(HealthCheckResult::healthy())
+        }
+
+        fn state(&self) -> ServiceState {
+            self.state
+        }
+    }
+
+    #[tokio::test]
+    async fn test_degraded_service_is_not_serving() {
+        let manager = Arc::new(ServiceManager::new(Default::default()));
+        manager
+            .add_service(Box::new(StubService {
+                name: "processor",
+                state: ServiceState::Running,
+            }))
+            .await;
+        manager
+            .add_service(Box::new(StubService {
+                name: "storage",
+                state: ServiceState::Degraded,
+            }))
+            .await;
+
+        let source = ServiceManagerHealth::new(&manager);
+        assert_eq!(
+            source.statuses().await,
+            vec![
+                ("processor".to_string(), ServingStatus::Serving),
+                ("storage".to_string(), ServingStatus::NotServing),
+            ]
+        );
+
+        drop(manager);
+        assert!(source.statuses().await.is_empty());
+    }
 }
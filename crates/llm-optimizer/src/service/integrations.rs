@@ -3,9 +3,15 @@
 use super::{HealthCheckResult, Service, ServiceState};
 use anyhow::Result;
 use async_trait::async_trait;
-use llm_optimizer_integrations::{AnthropicClient, JiraClient};
+use crate::config::{Config, CostAlertsConfig};
+use llm_optimizer_integrations::anthropic::{CostAlerter, CostStoreConfig};
+use llm_optimizer_integrations::{
+    AnthropicClient, AnthropicConfig, GitHubAuth, GitHubClient, GitHubConfig, JiraClient, SlackClient, SlackConfig,
+};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
+use tokio::task::JoinHandle;
 use tracing::{info, warn};
 
 /// Integration clients configuration
@@ -15,6 +21,84 @@ pub struct IntegrationsServiceConfig {
     pub jira_config: Option<llm_optimizer_integrations::JiraConfig>,
     /// Anthropic client configuration
     pub anthropic_config: Option<llm_optimizer_integrations::AnthropicConfig>,
+    /// Slack client configuration, used for cost alerts
+    pub slack_config: Option<SlackConfig>,
+    /// GitHub client configuration, used for cost alerts
+    pub github_config: Option<GitHubConfig>,
+    /// Claude spend alert settings
+    pub cost_alerts: CostAlertsConfig,
+}
+
+impl IntegrationsServiceConfig {
+    /// Build the cost alert destinations from the `integrations` section of
+    /// `config`
+    ///
+    /// Slack is used when `cost_alerts.slack` is set and a webhook URL is
+    /// configured; GitHub when both `cost_alerts.github_repository` and
+    /// `github_token` are set. The alerts watch the spend of the Anthropic
+    /// client built from `integrations.anthropic`, which every caller of
+    /// [`IntegrationsService::clients`] shares. Jira is left unconfigured.
+    pub fn from_config(config: &Config) -> Self {
+        let integrations = &config.integrations;
+        let alerts = &integrations.cost_alerts;
+
+        let slack_config = integrations
+            .slack_webhook_url
+            .clone()
+            .filter(|_| alerts.slack)
+            .map(|webhook_url| SlackConfig {
+                webhook_url: Some(webhook_url),
+                ..SlackConfig::default()
+            });
+
+        let github_config = alerts
+            .github_repository
+            .as_deref()
+            .and_then(|repository| repository.split_once('/'))
+            .zip(integrations.github_token.clone())
+            .map(|((owner, repo), token)| GitHubConfig {
+                api_base_url: "https://api.github.com".to_string(),
+                auth: GitHubAuth::PersonalAccessToken { token },
+                owner: owner.to_string(),
+                repo: repo.to_string(),
+                timeout_secs: 30,
+                max_retries: 3,
+            });
+
+        let anthropic_config = integrations.anthropic.as_ref().map(|anthropic| {
+            let mut client_config = AnthropicConfig::new(anthropic.api_key.clone());
+            if let Some(ref base_url) = anthropic.base_url {
+                client_config.base_url = base_url.clone();
+            }
+            client_config.cost_store = anthropic.cost_store_path.clone().map(CostStoreConfig::new);
+            client_config
+        });
+
+        Self {
+            jira_config: None,
+            anthropic_config,
+            slack_config,
+            github_config,
+            cost_alerts: alerts.clone(),
+        }
+    }
+
+    /// Alerter for the configured thresholds and destinations, or `None`
+    /// when no thresholds are set
+    fn cost_alerter(&self) -> Result<Option<CostAlerter>> {
+        if self.cost_alerts.thresholds_usd.is_empty() {
+            return Ok(None);
+        }
+
+        let mut alerter = CostAlerter::new(self.cost_alerts.thresholds_usd.iter().copied());
+        if let Some(ref config) = self.slack_config {
+            alerter = alerter.with_notifier(SlackClient::new(config.clone())?);
+        }
+        if let Some(ref config) = self.github_config {
+            alerter = alerter.with_notifier(GitHubClient::new(config.clone())?);
+        }
+        Ok(Some(alerter))
+    }
 }
 
 /// Integration clients container
@@ -29,6 +113,7 @@ pub struct IntegrationClients {
 pub struct IntegrationsService {
     config: IntegrationsServiceConfig,
     clients: Arc<RwLock<Option<IntegrationClients>>>,
+    cost_alert_task: Option<JoinHandle<()>>,
     state: Arc<RwLock<ServiceState>>,
 }
 
@@ -38,10 +123,17 @@ impl IntegrationsService {
         Self {
             config,
             clients: Arc::new(RwLock::new(None)),
+            cost_alert_task: None,
             state: Arc::new(RwLock::new(ServiceState::Initializing)),
         }
     }
 
+    /// Shared slot holding the clients once the service has started, for
+    /// services that call integrations
+    pub fn shared_clients(&self) -> Arc<RwLock<Option<IntegrationClients>>> {
+        Arc::clone(&self.clients)
+    }
+
     /// Get the integration clients
     pub async fn clients(&self) -> Option<IntegrationClients> {
         self.clients.read().await.as_ref().map(|clients| IntegrationClients {
@@ -77,6 +169,13 @@ impl Service for IntegrationsService {
             None
         };
 
+        // Watch Claude spend for the configured alert thresholds; clones of
+        // the client share its cost tracker
+        if let (Some(client), Some(alerter)) = (&anthropic, self.config.cost_alerter()?) {
+            let interval = Duration::from_secs(self.config.cost_alerts.check_interval_secs);
+            self.cost_alert_task = Some(Arc::new(alerter).watch(client.clone(), interval));
+        }
+
         let mut clients_lock = self.clients.write().await;
         *clients_lock = Some(IntegrationClients {
             jira,
@@ -94,8 +193,20 @@ impl Service for IntegrationsService {
         *state = ServiceState::ShuttingDown;
         drop(state);
 
-        // Clear clients
+        if let Some(task) = self.cost_alert_task.take() {
+            task.abort();
+        }
+
+        // Clear clients, saving Claude spend first
         let mut clients_lock = self.clients.write().await;
+        if let Some(anthropic) = clients_lock
+            .as_ref()
+            .and_then(|clients| clients.anthropic.as_ref())
+        {
+            if let Err(e) = anthropic.flush_cost_stats().await {
+                warn!("Failed to save Claude cost stats: {:#}", e);
+            }
+        }
         *clients_lock = None;
 
         let mut state = self.state.write().await;
@@ -138,6 +249,15 @@ impl Service for IntegrationsService {
         vec![]
     }
 
+    fn config_sections(&self) -> Vec<String> {
+        vec!["integrations".to_string()]
+    }
+
+    fn apply_config(&mut self, config: &Config) -> Result<()> {
+        self.config = IntegrationsServiceConfig::from_config(config);
+        Ok(())
+    }
+
     async fn recover(&mut self) -> Result<()> {
         warn!("Attempting to recover integrations service");
 
@@ -153,13 +273,69 @@ mod tests {
 
     #[tokio::test]
     async fn test_integrations_service_state() {
-        let config = IntegrationsServiceConfig {
-            jira_config: None,
-            anthropic_config: None,
-        };
+        let config = IntegrationsServiceConfig::from_config(&Config::default());
 
         let service = IntegrationsService::new(config);
         assert_eq!(service.state(), ServiceState::Initializing);
         assert!(service.dependencies().is_empty());
     }
+
+    #[test]
+    fn test_cost_alert_destinations_from_config() {
+        let mut config = Config::default();
+        config.integrations.slack_webhook_url =
+            Some("https://hooks.slack.com/services/T000/B000/XXXX".to_string());
+        config.integrations.github_token = Some("ghp_secret".to_string());
+        config.integrations.cost_alerts.thresholds_usd = vec![100.0];
+        config.integrations.cost_alerts.github_repository = Some("acme/optimizer".to_string());
+
+        let service_config = IntegrationsServiceConfig::from_config(&config);
+        assert!(service_config.slack_config.is_some());
+        let github = service_config.github_config.as_ref().unwrap();
+        assert_eq!((github.owner.as_str(), github.repo.as_str()), ("acme", "optimizer"));
+        let alerter = service_config.cost_alerter().unwrap().unwrap();
+        assert_eq!(alerter.thresholds(), [100.0]);
+
+        config.integrations.cost_alerts.slack = false;
+        config.integrations.cost_alerts.thresholds_usd.clear();
+        let service_config = IntegrationsServiceConfig::from_config(&config);
+        assert!(service_config.slack_config.is_none());
+        assert!(service_config.cost_alerter().unwrap().is_none());
+    }
+
+    #[test]
+    fn test_apply_config_takes_new_integrations() {
+        let mut config = Config::default();
+        let mut service = IntegrationsService::new(IntegrationsServiceConfig::from_config(&config));
+        assert_eq!(service.config_sections(), vec!["integrations"]);
+        assert!(service.config.slack_config.is_none());
+
+        config.integrations.slack_webhook_url =
+            Some("https://hooks.slack.com/services/T000/B000/XXXX".to_string());
+        service.apply_config(&config).unwrap();
+        assert!(service.config.slack_config.is_some());
+    }
+
+    #[tokio::test]
+    async fn test_cost_alerts_watch_configured_anthropic_client() {
+        let mut config = Config::default();
+        config.integrations.anthropic = Some(crate::config::AnthropicConfig {
+            api_key: "sk-ant-test".to_string(),
+            base_url: Some("http://localhost:1".to_string()),
+            cost_store_path: None,
+        });
+        config.integrations.cost_alerts.thresholds_usd = vec![100.0];
+
+        let service_config = IntegrationsServiceConfig::from_config(&config);
+        let anthropic = service_config.anthropic_config.as_ref().unwrap();
+        assert_eq!(anthropic.base_url, "http://localhost:1");
+
+        let mut service = IntegrationsService::new(service_config);
+        service.start().await.unwrap();
+        assert!(service.cost_alert_task.is_some());
+        assert!(service.clients().await.unwrap().anthropic.is_some());
+
+        service.stop().await.unwrap();
+        assert!(service.cost_alert_task.is_none());
+    }
 }
@@ -3,11 +3,15 @@
 //! This module provides the core service trait and service manager for orchestrating
 //! all components of the LLM Auto Optimizer system.
 
+use crate::config::Config;
 use anyhow::Result;
 use async_trait::async_trait;
+use futures::future::{join, join_all, try_join_all};
+use llm_optimizer_config::DrainState;
+use std::collections::HashSet;
 use std::fmt;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tokio::sync::{broadcast, RwLock};
 use tokio::time::{interval, sleep};
 use tracing::{debug, error, info, warn};
@@ -18,13 +22,15 @@ pub mod rest_api;
 pub mod grpc_api;
 pub mod storage;
 pub mod integrations;
+pub mod status;
 
 pub use collector::CollectorService;
 pub use processor::ProcessorService;
 pub use rest_api::RestApiService;
-pub use grpc_api::GrpcApiService;
+pub use grpc_api::{GrpcApiService, ServiceManagerHealth};
 pub use storage::StorageService;
 pub use integrations::IntegrationsService;
+pub use status::ServiceManagerStatus;
 
 /// Service lifecycle states
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -116,6 +122,32 @@ pub trait Service: Send + Sync {
         Vec::new()
     }
 
+    /// Wait until the service can serve its dependents, giving up after
+    /// `timeout`. Called on each dependency before its dependents start; by
+    /// default a service is ready as soon as it has started.
+    async fn wait_until_ready(&self, _timeout: Duration) -> Result<()> {
+        Ok(())
+    }
+
+    /// Config sections this service is built from; a config reload that
+    /// changes any of them restarts the service
+    fn config_sections(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// Stop accepting new work and wait for work in flight to finish. Called
+    /// on shutdown before [`Service::stop`]; the manager stops waiting after
+    /// its drain timeout.
+    async fn drain(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    /// Take new settings from `config`; called while the service is stopped
+    /// during a config reload, before it is started again
+    fn apply_config(&mut self, _config: &Config) -> Result<()> {
+        Ok(())
+    }
+
     /// Attempt to recover from a failure
     async fn recover(&mut self) -> Result<()> {
         warn!("Service {} does not implement custom recovery", self.name());
@@ -125,6 +157,29 @@ pub trait Service: Send + Sync {
     }
 }
 
+/// How often [`wait_until_healthy`] repeats a failing health check
+const READY_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Poll `service`'s health check until it passes, for services whose
+/// readiness is their health; fails with the last check's message after
+/// `timeout`
+pub async fn wait_until_healthy<S: Service + ?Sized>(service: &S, timeout: Duration) -> Result<()> {
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        let message = match service.health_check().await {
+            Ok(check) if check.healthy => return Ok(()),
+            Ok(check) => check.message.unwrap_or_else(|| "unhealthy".to_string()),
+            Err(e) => e.to_string(),
+        };
+
+        if Instant::now() + READY_POLL_INTERVAL > deadline {
+            anyhow::bail!("{} not ready after {:?}: {}", service.name(), timeout, message);
+        }
+        sleep(READY_POLL_INTERVAL).await;
+    }
+}
+
 /// Service manager configuration
 #[derive(Debug, Clone)]
 pub struct ServiceManagerConfig {
@@ -136,8 +191,18 @@ pub struct ServiceManagerConfig {
     pub restart_backoff_base: Duration,
     /// Maximum restart backoff
     pub restart_backoff_max: Duration,
+    /// How long a recovered service must stay healthy before its restart count resets
+    pub restart_cooldown: Duration,
     /// Graceful shutdown timeout
     pub shutdown_timeout: Duration,
+    /// How long shutdown waits for in-flight work to drain before stopping
+    /// services
+    pub drain_timeout: Duration,
+    /// How long startup waits for a dependency to become ready before
+    /// failing
+    pub ready_timeout: Duration,
+    /// Start services with no dependency on each other concurrently
+    pub parallel_startup: bool,
 }
 
 impl Default for ServiceManagerConfig {
@@ -147,16 +212,112 @@ impl Default for ServiceManagerConfig {
             max_restart_attempts: 3,
             restart_backoff_base: Duration::from_secs(1),
             restart_backoff_max: Duration::from_secs(60),
+            restart_cooldown: Duration::ZERO,
             shutdown_timeout: Duration::from_secs(30),
+            drain_timeout: Duration::from_secs(15),
+            ready_timeout: Duration::from_secs(30),
+            parallel_startup: false,
+        }
+    }
+}
+
+impl ServiceManagerConfig {
+    /// Restart policy applied to services added without their own
+    pub fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            max_attempts: self.max_restart_attempts,
+            backoff_base: self.restart_backoff_base,
+            backoff_max: self.restart_backoff_max,
+            cooldown_after_success: self.restart_cooldown,
         }
     }
 }
 
+/// Auto-recovery limits for a single service
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RestartPolicy {
+    /// Maximum number of restart attempts; 0 disables auto-recovery
+    pub max_attempts: u32,
+    /// Restart backoff base delay
+    pub backoff_base: Duration,
+    /// Maximum restart backoff
+    pub backoff_max: Duration,
+    /// How long a recovered service must stay healthy before its restart count resets
+    pub cooldown_after_success: Duration,
+}
+
+impl RestartPolicy {
+    /// Backoff before the restart following `attempts` earlier attempts
+    fn backoff(&self, attempts: u32) -> Duration {
+        self.backoff_base
+            .checked_mul(2_u32.saturating_pow(attempts))
+            .map_or(self.backoff_max, |backoff| backoff.min(self.backoff_max))
+    }
+}
+
+impl Default for RestartPolicy {
+    fn default() -> Self {
+        ServiceManagerConfig::default().restart_policy()
+    }
+}
+
+/// Snapshot of a managed service, as reported by the admin APIs
+#[derive(Debug, Clone)]
+pub struct ServiceStatus {
+    /// Service name
+    pub name: String,
+    /// Current state
+    pub state: ServiceState,
+    /// Time since the service last started or was restarted; `None` unless
+    /// it is running or degraded
+    pub uptime: Option<Duration>,
+    /// Successful recoveries and config restarts since startup
+    pub restart_count: u32,
+    /// Message from the last health check, if it gave one
+    pub last_health_message: Option<String>,
+}
+
 /// Service wrapper with metadata
 struct ManagedService {
     service: Box<dyn Service>,
     restart_count: u32,
     last_health_check: Option<HealthCheckResult>,
+    /// Per-service restart policy, overriding the manager default
+    restart_policy: Option<RestartPolicy>,
+    /// When the service last recovered successfully
+    recovered_at: Option<Instant>,
+    /// Auto-recovery gave up on this service
+    failed: bool,
+    /// When the service last started, recovered or was restarted
+    started_at: Option<Instant>,
+    /// Successful recoveries and config restarts since the service was added;
+    /// unlike `restart_count` this is never reset
+    restarts: u32,
+}
+
+impl ManagedService {
+    /// State reported to callers; services that auto-recovery has given up
+    /// on are [`ServiceState::Failed`]
+    fn reported_state(&self) -> ServiceState {
+        if self.failed {
+            ServiceState::Failed
+        } else {
+            self.service.state()
+        }
+    }
+
+    /// Reset the restart count once the recovery cooldown has elapsed
+    fn reset_if_cooled_down(&mut self, policy: &RestartPolicy) {
+        let cooling_down = self
+            .recovered_at
+            .is_some_and(|at| at.elapsed() < policy.cooldown_after_success);
+
+        if self.restart_count > 0 && !cooling_down {
+            debug!("Service {} is healthy, resetting restart count", self.service.name());
+            self.restart_count = 0;
+            self.recovered_at = None;
+        }
+    }
 }
 
 /// Service manager orchestrates multiple services
@@ -165,6 +326,7 @@ pub struct ServiceManager {
     services: Arc<RwLock<Vec<ManagedService>>>,
     shutdown_tx: broadcast::Sender<()>,
     running: Arc<RwLock<bool>>,
+    drain: Arc<DrainState>,
 }
 
 impl ServiceManager {
@@ -177,16 +339,38 @@ impl ServiceManager {
             services: Arc::new(RwLock::new(Vec::new())),
             shutdown_tx,
             running: Arc::new(RwLock::new(false)),
+            drain: Arc::new(DrainState::new()),
         }
     }
 
+    /// Start and stop draining through `drain` on shutdown; share it with
+    /// the API servers and health monitor so they see the drain
+    pub fn with_drain(mut self, drain: Arc<DrainState>) -> Self {
+        self.drain = drain;
+        self
+    }
+
     /// Add a service to be managed
     pub async fn add_service(&self, service: Box<dyn Service>) {
+        self.push_service(service, None).await;
+    }
+
+    /// Add a service to be managed with its own restart policy
+    pub async fn add_service_with_policy(&self, service: Box<dyn Service>, policy: RestartPolicy) {
+        self.push_service(service, Some(policy)).await;
+    }
+
+    async fn push_service(&self, service: Box<dyn Service>, restart_policy: Option<RestartPolicy>) {
         let mut services = self.services.write().await;
         services.push(ManagedService {
             service,
             restart_count: 0,
             last_health_check: None,
+            restart_policy,
+            recovered_at: None,
+            failed: false,
+            started_at: None,
+            restarts: 0,
         });
     }
 
@@ -197,27 +381,35 @@ impl ServiceManager {
         let mut running = self.running.write().await;
         *running = true;
         drop(running);
+        self.drain.resume();
 
         let mut services = self.services.write().await;
+        let mut ready = HashSet::new();
 
-        // Topological sort based on dependencies
-        let ordered_indices = self.resolve_dependencies(&services)?;
-
-        // Start services in order
-        for idx in ordered_indices {
-            let managed = &mut services[idx];
-            let service_name = managed.service.name().to_string();
+        if self.config.parallel_startup {
+            // Start each dependency level concurrently, one level at a time
+            for level in self.resolve_dependency_levels(&services)? {
+                for &idx in &level {
+                    self.wait_for_dependencies(&services, idx, &mut ready).await?;
+                }
 
-            info!("Starting service: {}", service_name);
+                let starts = services
+                    .iter_mut()
+                    .enumerate()
+                    .filter(|(idx, _)| level.contains(idx))
+                    .map(|(_, managed)| Self::start_service(managed));
 
-            match managed.service.start().await {
-                Ok(()) => {
-                    info!("Service started successfully: {}", service_name);
-                }
-                Err(e) => {
-                    error!("Failed to start service {}: {}", service_name, e);
-                    return Err(e);
-                }
+                // Dropping the remaining futures on the first error cancels them
+                try_join_all(starts).await?;
+            }
+        } else {
+            // Topological sort based on dependencies
+            let ordered_indices = self.resolve_dependencies(&services)?;
+
+            // Start services in order
+            for idx in ordered_indices {
+                self.wait_for_dependencies(&services, idx, &mut ready).await?;
+                Self::start_service(&mut services[idx]).await?;
             }
         }
 
@@ -225,7 +417,142 @@ impl ServiceManager {
         Ok(())
     }
 
-    /// Stop all services in reverse dependency order
+    /// Wait for each dependency of `services[idx]` not already in `ready` to
+    /// become ready, adding it to `ready`
+    async fn wait_for_dependencies(
+        &self,
+        services: &[ManagedService],
+        idx: usize,
+        ready: &mut HashSet<String>,
+    ) -> Result<()> {
+        let service_name = services[idx].service.name();
+        let timeout = self.config.ready_timeout;
+
+        for dep_name in services[idx].service.dependencies() {
+            if ready.contains(&dep_name) {
+                continue;
+            }
+            let Some(dep) = services.iter().find(|m| m.service.name() == dep_name) else {
+                continue;
+            };
+
+            debug!("Waiting for {} to become ready before starting {}", dep_name, service_name);
+            match tokio::time::timeout(timeout, dep.service.wait_until_ready(timeout)).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => anyhow::bail!(
+                    "Dependency {} of {} is not ready: {}",
+                    dep_name,
+                    service_name,
+                    e
+                ),
+                Err(_) => anyhow::bail!(
+                    "Dependency {} of {} did not become ready within {:?}",
+                    dep_name,
+                    service_name,
+                    timeout
+                ),
+            }
+            ready.insert(dep_name);
+        }
+
+        Ok(())
+    }
+
+    /// Start a single managed service
+    async fn start_service(managed: &mut ManagedService) -> Result<()> {
+        let service_name = managed.service.name().to_string();
+
+        info!("Starting service: {}", service_name);
+
+        match managed.service.start().await {
+            Ok(()) => {
+                info!("Service started successfully: {}", service_name);
+                managed.started_at = Some(Instant::now());
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed to start service {}: {}", service_name, e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Apply a reloaded config, restarting only the services whose config
+    /// sections changed. Returns the names of the restarted services.
+    ///
+    /// Changes to fields that need a full restart are rejected before any
+    /// service is touched.
+    pub async fn apply_config_change(&self, old: &Config, new: &Config) -> Result<Vec<String>> {
+        old.check_reloadable(new)?;
+
+        let changed = old.diff(new);
+        if changed.is_empty() {
+            debug!("Configuration unchanged, no services to restart");
+            return Ok(Vec::new());
+        }
+        info!("Configuration sections changed: {}", changed.join(", "));
+
+        let mut services = self.services.write().await;
+        let mut restarted = Vec::new();
+
+        for idx in self.resolve_dependencies(&services)? {
+            let managed = &mut services[idx];
+            let affected = managed
+                .service
+                .config_sections()
+                .iter()
+                .any(|section| changed.contains(section));
+            if !affected {
+                continue;
+            }
+
+            let service_name = managed.service.name().to_string();
+            info!("Restarting service {} to apply configuration", service_name);
+
+            match tokio::time::timeout(self.config.shutdown_timeout, managed.service.stop()).await {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => warn!("Error stopping service {}: {}", service_name, e),
+                Err(_) => warn!("Service {} shutdown timed out", service_name),
+            }
+
+            managed.service.apply_config(new)?;
+            Self::start_service(managed).await?;
+            managed.restart_count = 0;
+            managed.restarts += 1;
+            managed.failed = false;
+            restarted.push(service_name);
+        }
+
+        Ok(restarted)
+    }
+
+    /// Whether shutdown has started draining services
+    pub fn is_draining(&self) -> bool {
+        self.drain.is_draining()
+    }
+
+    /// Stop every service accepting new work, then wait up to the drain
+    /// timeout for work already in flight, including requests tracked by
+    /// the drain state, to finish
+    pub async fn drain_all(&self) {
+        self.drain.start();
+        info!("Draining services for up to {:?}", self.config.drain_timeout);
+
+        let mut services = self.services.write().await;
+        let drains = services.iter_mut().map(|managed| async move {
+            if let Err(e) = managed.service.drain().await {
+                warn!("Error draining service {}: {}", managed.service.name(), e);
+            }
+        });
+
+        let idle = self.drain.wait_idle();
+        match tokio::time::timeout(self.config.drain_timeout, join(join_all(drains), idle)).await {
+            Ok(_) => info!("All services drained"),
+            Err(_) => warn!("Drain timed out, stopping services with work still in flight"),
+        }
+    }
+
+    /// Drain, then stop all services in reverse dependency order
     pub async fn stop_all(&self) -> Result<()> {
         info!("Stopping all services");
 
@@ -236,6 +563,8 @@ impl ServiceManager {
         // Broadcast shutdown signal
         let _ = self.shutdown_tx.send(());
 
+        self.drain_all().await;
+
         let mut services = self.services.write().await;
 
         // Stop in reverse order
@@ -291,9 +620,14 @@ impl ServiceManager {
     /// Check health of all services and attempt recovery if needed
     async fn check_and_recover_services(&self) {
         let mut services = self.services.write().await;
+        let default_policy = self.config.restart_policy();
 
         for managed in services.iter_mut() {
             let service_name = managed.service.name().to_string();
+            let policy = managed
+                .restart_policy
+                .clone()
+                .unwrap_or_else(|| default_policy.clone());
 
             // Perform health check
             match managed.service.health_check().await {
@@ -308,30 +642,28 @@ impl ServiceManager {
                         );
 
                         // Attempt recovery if we haven't exceeded retry limit
-                        if managed.restart_count < self.config.max_restart_attempts {
+                        if managed.restart_count < policy.max_attempts {
                             info!(
                                 "Attempting to recover service {} (attempt {}/{})",
                                 service_name,
                                 managed.restart_count + 1,
-                                self.config.max_restart_attempts
+                                policy.max_attempts
                             );
 
-                            // Calculate backoff
-                            let backoff = std::cmp::min(
-                                self.config.restart_backoff_base * 2_u32.pow(managed.restart_count),
-                                self.config.restart_backoff_max,
-                            );
-
-                            sleep(backoff).await;
+                            sleep(policy.backoff(managed.restart_count)).await;
 
+                            managed.restart_count += 1;
                             match managed.service.recover().await {
                                 Ok(()) => {
                                     info!("Service {} recovered successfully", service_name);
-                                    managed.restart_count = 0;
+                                    let now = Instant::now();
+                                    managed.recovered_at = Some(now);
+                                    managed.started_at = Some(now);
+                                    managed.restarts += 1;
+                                    managed.reset_if_cooled_down(&policy);
                                 }
                                 Err(e) => {
                                     error!("Failed to recover service {}: {}", service_name, e);
-                                    managed.restart_count += 1;
                                 }
                             }
                         } else {
@@ -339,13 +671,12 @@ impl ServiceManager {
                                 "Service {} has exceeded maximum restart attempts",
                                 service_name
                             );
+                            managed.failed = true;
                         }
                     } else {
                         // Reset restart count on successful health check
-                        if managed.restart_count > 0 {
-                            debug!("Service {} is healthy, resetting restart count", service_name);
-                            managed.restart_count = 0;
-                        }
+                        managed.failed = false;
+                        managed.reset_if_cooled_down(&policy);
                     }
                 }
                 Err(e) => {
@@ -356,6 +687,8 @@ impl ServiceManager {
     }
 
     /// Get health status of all services
+    ///
+    /// Services that auto-recovery has given up on are reported as [`ServiceState::Failed`].
     pub async fn get_health_status(&self) -> Vec<(String, ServiceState, Option<HealthCheckResult>)> {
         let services = self.services.read().await;
 
@@ -364,13 +697,37 @@ impl ServiceManager {
             .map(|managed| {
                 (
                     managed.service.name().to_string(),
-                    managed.service.state(),
+                    managed.reported_state(),
                     managed.last_health_check.clone(),
                 )
             })
             .collect()
     }
 
+    /// Name, state, uptime and restart count of every service, as shown by
+    /// the admin APIs
+    pub async fn service_statuses(&self) -> Vec<ServiceStatus> {
+        let services = self.services.read().await;
+
+        services
+            .iter()
+            .map(|managed| {
+                let state = managed.reported_state();
+                let up = matches!(state, ServiceState::Running | ServiceState::Degraded);
+                ServiceStatus {
+                    name: managed.service.name().to_string(),
+                    state,
+                    uptime: managed.started_at.filter(|_| up).map(|at| at.elapsed()),
+                    restart_count: managed.restarts,
+                    last_health_message: managed
+                        .last_health_check
+                        .as_ref()
+                        .and_then(|check| check.message.clone()),
+                }
+            })
+            .collect()
+    }
+
     /// Subscribe to shutdown signals
     pub fn subscribe_shutdown(&self) -> broadcast::Receiver<()> {
         self.shutdown_tx.subscribe()
@@ -378,30 +735,7 @@ impl ServiceManager {
 
     /// Resolve service dependencies using topological sort
     fn resolve_dependencies(&self, services: &[ManagedService]) -> Result<Vec<usize>> {
-        let n = services.len();
-        let mut in_degree = vec![0; n];
-        let mut adj_list: Vec<Vec<usize>> = vec![Vec::new(); n];
-
-        // Build adjacency list and in-degree count
-        for (i, managed) in services.iter().enumerate() {
-            let deps = managed.service.dependencies();
-            for dep_name in deps {
-                // Find dependency index
-                if let Some(dep_idx) = services
-                    .iter()
-                    .position(|m| m.service.name() == dep_name)
-                {
-                    adj_list[dep_idx].push(i);
-                    in_degree[i] += 1;
-                } else {
-                    return Err(anyhow::anyhow!(
-                        "Service {} has unmet dependency: {}",
-                        managed.service.name(),
-                        dep_name
-                    ));
-                }
-            }
-        }
+        let (mut in_degree, adj_list) = Self::dependency_graph(services)?;
 
         // Topological sort using Kahn's algorithm
         let mut queue: Vec<usize> = in_degree
@@ -423,22 +757,164 @@ impl ServiceManager {
             }
         }
 
-        if result.len() != n {
+        if result.len() != services.len() {
             return Err(anyhow::anyhow!("Circular dependency detected in services"));
         }
 
         Ok(result)
     }
+
+    /// Group services into dependency levels
+    ///
+    /// Every service in a level depends only on services in earlier levels, so
+    /// services within a level can be started concurrently.
+    fn resolve_dependency_levels(&self, services: &[ManagedService]) -> Result<Vec<Vec<usize>>> {
+        let (mut in_degree, adj_list) = Self::dependency_graph(services)?;
+
+        let mut level: Vec<usize> = in_degree
+            .iter()
+            .enumerate()
+            .filter_map(|(i, &deg)| if deg == 0 { Some(i) } else { None })
+            .collect();
+
+        let mut levels = Vec::new();
+        let mut resolved = 0;
+
+        while !level.is_empty() {
+            let mut next_level = Vec::new();
+
+            for &curr in &level {
+                for &next in &adj_list[curr] {
+                    in_degree[next] -= 1;
+                    if in_degree[next] == 0 {
+                        next_level.push(next);
+                    }
+                }
+            }
+
+            resolved += level.len();
+            levels.push(std::mem::replace(&mut level, next_level));
+        }
+
+        if resolved != services.len() {
+            return Err(anyhow::anyhow!("Circular dependency detected in services"));
+        }
+
+        Ok(levels)
+    }
+
+    /// Build the in-degree count and adjacency list of the dependency graph
+    fn dependency_graph(services: &[ManagedService]) -> Result<(Vec<usize>, Vec<Vec<usize>>)> {
+        let n = services.len();
+        let mut in_degree = vec![0; n];
+        let mut adj_list: Vec<Vec<usize>> = vec![Vec::new(); n];
+
+        // Build adjacency list and in-degree count
+        for (i, managed) in services.iter().enumerate() {
+            let deps = managed.service.dependencies();
+            for dep_name in deps {
+                // Find dependency index
+                if let Some(dep_idx) = services
+                    .iter()
+                    .position(|m| m.service.name() == dep_name)
+                {
+                    adj_list[dep_idx].push(i);
+                    in_degree[i] += 1;
+                } else {
+                    return Err(anyhow::anyhow!(
+                        "Service {} has unmet dependency: {}",
+                        managed.service.name(),
+                        dep_name
+                    ));
+                }
+            }
+        }
+
+        Ok((in_degree, adj_list))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Mutex;
+
+    /// Records start order, the peak number of concurrent starts and recoveries
+    #[derive(Default)]
+    struct StartTracker {
+        active: AtomicUsize,
+        peak: AtomicUsize,
+        started: Mutex<Vec<String>>,
+        recoveries: AtomicUsize,
+        reconfigured: Mutex<Vec<String>>,
+        drained: Mutex<Vec<String>>,
+        waited: Mutex<Vec<String>>,
+    }
 
     struct MockService {
         name: String,
         state: ServiceState,
         fail_start: bool,
+        unhealthy: bool,
+        dependencies: Vec<String>,
+        sections: Vec<String>,
+        start_delay: Duration,
+        drain_delay: Duration,
+        ready_delay: Duration,
+        tracker: Arc<StartTracker>,
+    }
+
+    impl MockService {
+        fn new(name: &str, tracker: &Arc<StartTracker>) -> Self {
+            Self {
+                name: name.to_string(),
+                state: ServiceState::Initializing,
+                fail_start: false,
+                unhealthy: false,
+                dependencies: Vec::new(),
+                sections: Vec::new(),
+                start_delay: Duration::ZERO,
+                drain_delay: Duration::ZERO,
+                ready_delay: Duration::ZERO,
+                tracker: Arc::clone(tracker),
+            }
+        }
+
+        fn depends_on(mut self, dep: &str) -> Self {
+            self.dependencies.push(dep.to_string());
+            self
+        }
+
+        fn configured_by(mut self, section: &str) -> Self {
+            self.sections.push(section.to_string());
+            self
+        }
+
+        fn delayed(mut self, delay: Duration) -> Self {
+            self.start_delay = delay;
+            self
+        }
+
+        fn slow_to_drain(mut self, delay: Duration) -> Self {
+            self.drain_delay = delay;
+            self
+        }
+
+        fn slow_to_ready(mut self, delay: Duration) -> Self {
+            self.ready_delay = delay;
+            self
+        }
+
+        fn failing(mut self) -> Self {
+            self.fail_start = true;
+            self
+        }
+
+        fn unhealthy(mut self) -> Self {
+            self.unhealthy = true;
+            self
+        }
     }
 
     #[async_trait]
@@ -448,9 +924,15 @@ mod tests {
         }
 
         async fn start(&mut self) -> Result<()> {
+            let active = self.tracker.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.tracker.peak.fetch_max(active, Ordering::SeqCst);
+            sleep(self.start_delay).await;
+            self.tracker.active.fetch_sub(1, Ordering::SeqCst);
+
             if self.fail_start {
                 anyhow::bail!("Start failed");
             }
+            self.tracker.started.lock().unwrap().push(self.name.clone());
             self.state = ServiceState::Running;
             Ok(())
         }
@@ -460,13 +942,54 @@ mod tests {
             Ok(())
         }
 
+        async fn drain(&mut self) -> Result<()> {
+            self.state = ServiceState::ShuttingDown;
+            self.tracker.drained.lock().unwrap().push(self.name.clone());
+            sleep(self.drain_delay).await;
+            Ok(())
+        }
+
         async fn health_check(&self) -> Result<HealthCheckResult> {
+            if self.unhealthy {
+                return Ok(HealthCheckResult::unhealthy("mock failure"));
+            }
             Ok(HealthCheckResult::healthy())
         }
 
+        async fn wait_until_ready(&self, _timeout: Duration) -> Result<()> {
+            sleep(self.ready_delay).await;
+            self.tracker.waited.lock().unwrap().push(self.name.clone());
+            Ok(())
+        }
+
+        async fn recover(&mut self) -> Result<()> {
+            self.tracker.recoveries.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+
         fn state(&self) -> ServiceState {
             self.state
         }
+
+        fn dependencies(&self) -> Vec<String> {
+            self.dependencies.clone()
+        }
+
+        fn config_sections(&self) -> Vec<String> {
+            self.sections.clone()
+        }
+
+        fn apply_config(&mut self, _config: &Config) -> Result<()> {
+            self.tracker.reconfigured.lock().unwrap().push(self.name.clone());
+            Ok(())
+        }
+    }
+
+    fn parallel_manager() -> ServiceManager {
+        ServiceManager::new(ServiceManagerConfig {
+            parallel_startup: true,
+            ..Default::default()
+        })
     }
 
     #[tokio::test]
@@ -474,11 +997,8 @@ mod tests {
         let config = ServiceManagerConfig::default();
         let manager = ServiceManager::new(config);
 
-        let service = Box::new(MockService {
-            name: "test".to_string(),
-            state: ServiceState::Initializing,
-            fail_start: false,
-        });
+        let tracker = Arc::new(StartTracker::default());
+        let service = Box::new(MockService::new("test", &tracker));
 
         manager.add_service(service).await;
 
@@ -491,4 +1011,361 @@ mod tests {
         assert_eq!(ServiceState::Running.to_string(), "Running");
         assert_eq!(ServiceState::Failed.to_string(), "Failed");
     }
+
+    #[tokio::test]
+    async fn test_parallel_startup_respects_dependency_levels() {
+        let manager = parallel_manager();
+        let tracker = Arc::new(StartTracker::default());
+        let delay = Duration::from_millis(20);
+
+        manager
+            .add_service(Box::new(MockService::new("api", &tracker).depends_on("storage").depends_on("cache")))
+            .await;
+        manager.add_service(Box::new(MockService::new("storage", &tracker).delayed(delay))).await;
+        manager.add_service(Box::new(MockService::new("cache", &tracker).delayed(delay))).await;
+
+        manager.start_all().await.unwrap();
+
+        assert_eq!(tracker.peak.load(Ordering::SeqCst), 2);
+        let started = tracker.started.lock().unwrap().clone();
+        assert_eq!(started.len(), 3);
+        assert_eq!(started[2], "api");
+    }
+
+    #[tokio::test]
+    async fn test_sequential_startup_is_the_default() {
+        let manager = ServiceManager::new(ServiceManagerConfig::default());
+        let tracker = Arc::new(StartTracker::default());
+
+        for name in ["a", "b", "c"] {
+            manager
+                .add_service(Box::new(MockService::new(name, &tracker).delayed(Duration::from_millis(5))))
+                .await;
+        }
+
+        manager.start_all().await.unwrap();
+        assert_eq!(tracker.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_parallel_startup_failure_cancels_level() {
+        let manager = parallel_manager();
+        let tracker = Arc::new(StartTracker::default());
+
+        manager.add_service(Box::new(MockService::new("broken", &tracker).failing())).await;
+        manager
+            .add_service(Box::new(MockService::new("slow", &tracker).delayed(Duration::from_secs(30))))
+            .await;
+        manager
+            .add_service(Box::new(MockService::new("dependent", &tracker).depends_on("slow")))
+            .await;
+
+        let result = tokio::time::timeout(Duration::from_secs(5), manager.start_all())
+            .await
+            .expect("failed level should not wait for in-flight starts");
+
+        assert!(result.is_err());
+        assert!(tracker.started.lock().unwrap().is_empty());
+
+        let states = manager.get_health_status().await;
+        assert!(states.iter().all(|(_, state, _)| *state == ServiceState::Initializing));
+    }
+
+    #[tokio::test]
+    async fn test_dependency_levels() {
+        let manager = parallel_manager();
+        let tracker = Arc::new(StartTracker::default());
+
+        manager.add_service(Box::new(MockService::new("a", &tracker))).await;
+        manager.add_service(Box::new(MockService::new("b", &tracker).depends_on("a"))).await;
+        manager.add_service(Box::new(MockService::new("c", &tracker))).await;
+        manager.add_service(Box::new(MockService::new("d", &tracker).depends_on("b").depends_on("c"))).await;
+
+        let services = manager.services.read().await;
+        let levels = manager.resolve_dependency_levels(&services).unwrap();
+        assert_eq!(levels, vec![vec![0, 2], vec![1], vec![3]]);
+    }
+
+    #[tokio::test]
+    async fn test_circular_dependency_is_rejected_in_parallel_mode() {
+        let manager = parallel_manager();
+        let tracker = Arc::new(StartTracker::default());
+
+        manager.add_service(Box::new(MockService::new("a", &tracker).depends_on("b"))).await;
+        manager.add_service(Box::new(MockService::new("b", &tracker).depends_on("a"))).await;
+
+        let err = manager.start_all().await.unwrap_err();
+        assert!(err.to_string().contains("Circular dependency"));
+    }
+
+    #[tokio::test]
+    async fn test_zero_restart_attempts_marks_service_failed() {
+        let manager = ServiceManager::new(ServiceManagerConfig::default());
+        let tracker = Arc::new(StartTracker::default());
+        let policy = RestartPolicy {
+            max_attempts: 0,
+            ..RestartPolicy::default()
+        };
+
+        manager
+            .add_service_with_policy(Box::new(MockService::new("storage", &tracker).unhealthy()), policy)
+            .await;
+        manager.start_all().await.unwrap();
+
+        manager.check_and_recover_services().await;
+        manager.check_and_recover_services().await;
+
+        assert_eq!(tracker.recoveries.load(Ordering::SeqCst), 0);
+        let states = manager.get_health_status().await;
+        assert_eq!(states[0].1, ServiceState::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_restart_policy_overrides_manager_default() {
+        let manager = ServiceManager::new(ServiceManagerConfig {
+            max_restart_attempts: 0,
+            ..Default::default()
+        });
+        let tracker = Arc::new(StartTracker::default());
+        let policy = RestartPolicy {
+            max_attempts: 2,
+            backoff_base: Duration::ZERO,
+            cooldown_after_success: Duration::from_secs(60),
+            ..RestartPolicy::default()
+        };
+
+        manager
+            .add_service_with_policy(Box::new(MockService::new("storage", &tracker).unhealthy()), policy)
+            .await;
+        manager.add_service(Box::new(MockService::new("integrations", &tracker).unhealthy())).await;
+        manager.start_all().await.unwrap();
+
+        // Recoveries within the cooldown count towards the limit
+        for _ in 0..3 {
+            manager.check_and_recover_services().await;
+        }
+
+        assert_eq!(tracker.recoveries.load(Ordering::SeqCst), 2);
+        let states = manager.get_health_status().await;
+        assert_eq!(states[0].1, ServiceState::Failed);
+        assert_eq!(states[1].1, ServiceState::Failed);
+    }
+
+    #[tokio::test]
+    async fn test_restart_count_increments_after_recovery() {
+        let manager = ServiceManager::new(ServiceManagerConfig::default());
+        let tracker = Arc::new(StartTracker::default());
+        let policy = RestartPolicy {
+            max_attempts: 2,
+            backoff_base: Duration::ZERO,
+            ..RestartPolicy::default()
+        };
+
+        manager
+            .add_service_with_policy(Box::new(MockService::new("storage", &tracker).unhealthy()), policy)
+            .await;
+        manager.start_all().await.unwrap();
+
+        let status = &manager.service_statuses().await[0];
+        assert_eq!(status.restart_count, 0);
+        assert!(status.uptime.is_some());
+        assert_eq!(status.last_health_message, None);
+
+        manager.check_and_recover_services().await;
+
+        // The policy's attempt counter resets without a cooldown; the
+        // reported count does not
+        let status = &manager.service_statuses().await[0];
+        assert_eq!(tracker.recoveries.load(Ordering::SeqCst), 1);
+        assert_eq!(status.name, "storage");
+        assert_eq!(status.state, ServiceState::Running);
+        assert_eq!(status.restart_count, 1);
+        assert!(status.uptime.is_some());
+        assert_eq!(status.last_health_message.as_deref(), Some("mock failure"));
+
+        manager.stop_all().await.unwrap();
+        assert_eq!(manager.service_statuses().await[0].uptime, None);
+    }
+
+    #[test]
+    fn test_restart_backoff_is_capped() {
+        let policy = RestartPolicy::default();
+        assert_eq!(policy.backoff(0), Duration::from_secs(1));
+        assert_eq!(policy.backoff(3), Duration::from_secs(8));
+        assert_eq!(policy.backoff(40), Duration::from_secs(60));
+    }
+
+    #[tokio::test]
+    async fn test_config_change_restarts_only_affected_services() {
+        let manager = ServiceManager::new(ServiceManagerConfig::default());
+        let tracker = Arc::new(StartTracker::default());
+
+        manager
+            .add_service(Box::new(MockService::new("rest-api", &tracker).configured_by("rest_api")))
+            .await;
+        manager
+            .add_service(Box::new(MockService::new("grpc-api", &tracker).configured_by("grpc_api")))
+            .await;
+        manager.start_all().await.unwrap();
+        tracker.started.lock().unwrap().clear();
+
+        let old = Config::default();
+        let mut new = old.clone();
+        new.rest_api.port = 8081;
+
+        let restarted = manager.apply_config_change(&old, &new).await.unwrap();
+        assert_eq!(restarted, vec!["rest-api"]);
+        assert_eq!(*tracker.reconfigured.lock().unwrap(), vec!["rest-api"]);
+        assert_eq!(*tracker.started.lock().unwrap(), vec!["rest-api"]);
+
+        let states = manager.get_health_status().await;
+        assert!(states.iter().all(|(_, state, _)| *state == ServiceState::Running));
+    }
+
+    #[tokio::test]
+    async fn test_config_change_to_immutable_field_is_rejected() {
+        let manager = ServiceManager::new(ServiceManagerConfig::default());
+        let tracker = Arc::new(StartTracker::default());
+
+        manager
+            .add_service(Box::new(MockService::new("rest-api", &tracker).configured_by("rest_api")))
+            .await;
+        manager.start_all().await.unwrap();
+        tracker.started.lock().unwrap().clear();
+
+        let old = Config::default();
+        let mut new = old.clone();
+        new.service.name = "renamed".to_string();
+        new.rest_api.port = 8081;
+
+        let err = manager.apply_config_change(&old, &new).await.unwrap_err();
+        assert!(err.to_string().contains("service.name"));
+        assert!(tracker.started.lock().unwrap().is_empty());
+        assert!(tracker.reconfigured.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stop_all_drains_before_stopping_within_timeout() {
+        let manager = ServiceManager::new(ServiceManagerConfig {
+            drain_timeout: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let tracker = Arc::new(StartTracker::default());
+
+        manager
+            .add_service(Box::new(
+                MockService::new("rest-api", &tracker).slow_to_drain(Duration::from_secs(30)),
+            ))
+            .await;
+        manager.add_service(Box::new(MockService::new("storage", &tracker))).await;
+        manager.start_all().await.unwrap();
+        assert!(!manager.is_draining());
+
+        tokio::time::timeout(Duration::from_secs(5), manager.stop_all())
+            .await
+            .expect("stop_all should not wait past the drain timeout")
+            .unwrap();
+
+        assert!(manager.is_draining());
+        let mut drained = tracker.drained.lock().unwrap().clone();
+        drained.sort();
+        assert_eq!(drained, vec!["rest-api", "storage"]);
+
+        let states = manager.get_health_status().await;
+        assert!(states.iter().all(|(_, state, _)| *state == ServiceState::Stopped));
+    }
+
+    #[tokio::test]
+    async fn test_stop_all_drains_shared_state() {
+        use crate::health::{HealthMonitor, HealthMonitorConfig};
+
+        let drain = Arc::new(DrainState::new());
+        let manager = ServiceManager::new(ServiceManagerConfig {
+            drain_timeout: Duration::from_secs(1),
+            ..Default::default()
+        })
+        .with_drain(drain.clone());
+        let health = HealthMonitor::new(HealthMonitorConfig {
+            required_services: Vec::new(),
+            ..Default::default()
+        })
+        .with_drain(drain.clone());
+
+        let tracker = Arc::new(StartTracker::default());
+        manager.add_service(Box::new(MockService::new("rest-api", &tracker))).await;
+        manager.start_all().await.unwrap();
+        let in_flight = drain.track().expect("requests are accepted while running");
+        assert!(health.readiness().await.ready);
+
+        let stop = tokio::spawn(async move { manager.stop_all().await });
+        while !drain.is_draining() {
+            tokio::task::yield_now().await;
+        }
+        assert!(drain.track().is_none());
+        assert!(health.readiness().await.draining);
+        sleep(Duration::from_millis(50)).await;
+        assert!(!stop.is_finished(), "stop_all should wait for the request in flight");
+
+        drop(in_flight);
+        tokio::time::timeout(Duration::from_secs(5), stop)
+            .await
+            .expect("stop_all did not finish")
+            .unwrap()
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_dependents_start_once_dependency_is_ready() {
+        for parallel_startup in [false, true] {
+            let manager = ServiceManager::new(ServiceManagerConfig {
+                parallel_startup,
+                ..Default::default()
+            });
+            let tracker = Arc::new(StartTracker::default());
+            let delay = Duration::from_millis(50);
+
+            manager
+                .add_service(Box::new(MockService::new("api", &tracker).depends_on("storage")))
+                .await;
+            manager
+                .add_service(Box::new(MockService::new("storage", &tracker).slow_to_ready(delay)))
+                .await;
+
+            let started_at = Instant::now();
+            manager.start_all().await.unwrap();
+
+            assert!(started_at.elapsed() >= delay);
+            assert_eq!(*tracker.started.lock().unwrap(), vec!["storage", "api"]);
+            // Only services something depends on are waited for
+            assert_eq!(*tracker.waited.lock().unwrap(), vec!["storage"]);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_dependency_not_ready_in_time_fails_startup() {
+        let manager = ServiceManager::new(ServiceManagerConfig {
+            ready_timeout: Duration::from_millis(50),
+            ..Default::default()
+        });
+        let tracker = Arc::new(StartTracker::default());
+
+        manager
+            .add_service(Box::new(MockService::new("api", &tracker).depends_on("storage")))
+            .await;
+        manager
+            .add_service(Box::new(
+                MockService::new("storage", &tracker).slow_to_ready(Duration::from_secs(30)),
+            ))
+            .await;
+
+        let err = tokio::time::timeout(Duration::from_secs(5), manager.start_all())
+            .await
+            .expect("startup should give up after the ready timeout")
+            .unwrap_err();
+
+        let message = err.to_string();
+        assert!(message.contains("Dependency storage of api"), "{}", message);
+        assert!(message.contains("did not become ready"), "{}", message);
+        assert_eq!(*tracker.started.lock().unwrap(), vec!["storage"]);
+    }
 }
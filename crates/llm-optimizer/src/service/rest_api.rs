@@ -1,10 +1,15 @@
 //! REST API service wrapper
 
+use super::integrations::IntegrationClients;
 use super::{HealthCheckResult, Service, ServiceState};
+use crate::config::Config;
 use anyhow::Result;
 use async_trait::async_trait;
+use llm_optimizer_api_rest::routes::optimize::RetentionPolicy;
 use llm_optimizer_api_rest::{ServerConfig, start_server};
+use llm_optimizer_integrations::anthropic::{parse_model, ClaudeModel};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tokio::task::JoinHandle;
 use tracing::{info, warn};
@@ -14,11 +19,50 @@ use tracing::{info, warn};
 pub struct RestApiServiceConfig {
     /// Server configuration
     pub server_config: ServerConfig,
+    /// Claude model that writes optimization rationales, if any
+    pub rationale_model: Option<ClaudeModel>,
+}
+
+impl RestApiServiceConfig {
+    /// Build the server configuration from the `service` and `rest_api`
+    /// sections of `config`
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            server_config: ServerConfig {
+                host: config.service.host.clone(),
+                port: config.rest_api.port,
+                timeout_secs: config.rest_api.timeout_secs,
+                require_deploy_approval: config.rest_api.require_deploy_approval,
+                logging: Arc::new(
+                    llm_optimizer_api_rest::middleware::LoggingConfig::new()
+                        .with_sample_rate(config.rest_api.log_sample_rate)
+                        .with_slow_threshold(Duration::from_millis(
+                            config.rest_api.slow_request_threshold_ms,
+                        )),
+                ),
+                retention: Some(RetentionPolicy {
+                    max_age_days: config.rest_api.decision_max_age_days,
+                    keep_last: config.rest_api.decision_keep_last,
+                    ..RetentionPolicy::default()
+                })
+                .filter(RetentionPolicy::is_bounded),
+                ..Default::default()
+            },
+            // Validation has already rejected unknown models
+            rationale_model: config
+                .rest_api
+                .rationale_model
+                .as_deref()
+                .and_then(|model| parse_model(model).ok()),
+        }
+    }
 }
 
 /// REST API service manages the HTTP REST API server
 pub struct RestApiService {
     config: RestApiServiceConfig,
+    /// Clients of the integrations service, for the rationale model
+    integrations: Option<Arc<RwLock<Option<IntegrationClients>>>>,
     server_handle: Arc<RwLock<Option<JoinHandle<Result<()>>>>>,
     state: Arc<RwLock<ServiceState>>,
 }
@@ -28,10 +72,40 @@ impl RestApiService {
     pub fn new(config: RestApiServiceConfig) -> Self {
         Self {
             config,
+            integrations: None,
             server_handle: Arc::new(RwLock::new(None)),
             state: Arc::new(RwLock::new(ServiceState::Initializing)),
         }
     }
+
+    /// Use the Anthropic client of the integrations service for rationales
+    pub fn with_integrations(mut self, clients: Arc<RwLock<Option<IntegrationClients>>>) -> Self {
+        self.integrations = Some(clients);
+        self
+    }
+
+    /// The server configuration, with the rationale LLM attached when the
+    /// integrations service has an Anthropic client
+    async fn server_config(&self) -> ServerConfig {
+        let config = self.config.server_config.clone();
+        let (Some(model), Some(integrations)) = (self.config.rationale_model, &self.integrations)
+        else {
+            return config;
+        };
+
+        let client = integrations
+            .read()
+            .await
+            .as_ref()
+            .and_then(|clients| clients.anthropic.clone());
+        match client {
+            Some(client) => config.with_rationale_llm(Arc::new(client), model),
+            None => {
+                warn!("No Anthropic client is running; optimization rationales will not be generated");
+                config
+            }
+        }
+    }
 }
 
 #[async_trait]
@@ -46,9 +120,10 @@ impl Service for RestApiService {
         let mut state = self.state.write().await;
         *state = ServiceState::Running;
         drop(state);
+        self.config.server_config.drain.resume();
 
         // Start the REST API server in a background task
-        let config = self.config.server_config.clone();
+        let config = self.server_config().await;
         let handle = tokio::spawn(async move {
             start_server(config).await
         });
@@ -84,6 +159,21 @@ impl Service for RestApiService {
         Ok(())
     }
 
+    async fn drain(&mut self) -> Result<()> {
+        let drain = Arc::clone(&self.config.server_config.drain);
+
+        let mut state = self.state.write().await;
+        *state = ServiceState::ShuttingDown;
+        drop(state);
+
+        drain.start();
+        info!("Draining REST API service, {} requests in flight", drain.in_flight());
+        drain.wait_idle().await;
+
+        info!("REST API service drained");
+        Ok(())
+    }
+
     async fn health_check(&self) -> Result<HealthCheckResult> {
         let handle_guard = self.server_handle.read().await;
 
@@ -110,7 +200,26 @@ impl Service for RestApiService {
 
     fn dependencies(&self) -> Vec<String> {
         // REST API depends on processor and storage
-        vec!["processor".to_string(), "storage".to_string()]
+        let mut dependencies = vec!["processor".to_string(), "storage".to_string()];
+        if self.config.rationale_model.is_some() {
+            dependencies.push("integrations".to_string());
+        }
+        dependencies
+    }
+
+    fn config_sections(&self) -> Vec<String> {
+        vec!["service".to_string(), "rest_api".to_string()]
+    }
+
+    fn apply_config(&mut self, config: &Config) -> Result<()> {
+        let service_status = self.config.server_config.service_status.clone();
+        let drain = Arc::clone(&self.config.server_config.drain);
+        let retention_metrics = self.config.server_config.retention_metrics.clone();
+        self.config = RestApiServiceConfig::from_config(config);
+        self.config.server_config.service_status = service_status;
+        self.config.server_config.drain = drain;
+        self.config.server_config.retention_metrics = retention_metrics;
+        Ok(())
     }
 
     async fn recover(&mut self) -> Result<()> {
@@ -130,10 +239,50 @@ mod tests {
     async fn test_rest_api_service_state() {
         let config = RestApiServiceConfig {
             server_config: ServerConfig::default(),
+            rationale_model: None,
         };
 
         let service = RestApiService::new(config);
         assert_eq!(service.state(), ServiceState::Initializing);
         assert_eq!(service.dependencies(), vec!["processor", "storage"]);
     }
+
+    #[tokio::test]
+    async fn test_rationale_model_waits_for_integrations() {
+        let mut config = Config::default();
+        config.rest_api.rationale_model = Some("claude-3-haiku-20240307".to_string());
+        let service = RestApiService::new(RestApiServiceConfig::from_config(&config))
+            .with_integrations(Arc::new(RwLock::new(None)));
+
+        assert_eq!(service.config.rationale_model, Some(ClaudeModel::Claude3Haiku));
+        assert_eq!(service.dependencies(), vec!["processor", "storage", "integrations"]);
+        // Without a running Anthropic client the server starts without rationales
+        assert!(service.server_config().await.rationale_llm.is_none());
+    }
+
+    #[test]
+    fn test_apply_config_takes_new_port() {
+        let mut config = Config::default();
+        let mut service = RestApiService::new(RestApiServiceConfig::from_config(&config));
+        let drain = Arc::clone(&service.config.server_config.drain);
+
+        config.rest_api.port = 8181;
+        service.apply_config(&config).unwrap();
+        assert_eq!(service.config.server_config.port, 8181);
+        // The drain state shared with the health monitor survives a reload
+        assert!(Arc::ptr_eq(&service.config.server_config.drain, &drain));
+    }
+
+    #[test]
+    fn test_decision_retention_from_config() {
+        let mut config = Config::default();
+        let service = RestApiService::new(RestApiServiceConfig::from_config(&config));
+        assert!(service.config.server_config.retention.is_none());
+
+        config.rest_api.decision_max_age_days = Some(90);
+        let service = RestApiService::new(RestApiServiceConfig::from_config(&config));
+        let retention = service.config.server_config.retention.unwrap();
+        assert_eq!(retention.max_age_days, Some(90));
+        assert_eq!(retention.keep_last, None);
+    }
 }
@@ -0,0 +1,86 @@
+//! Managed service status for the admin APIs
+//!
+//! The REST and gRPC servers don't depend on this crate, so each defines a
+//! `ServiceStatusSource` trait; [`ServiceManagerStatus`] implements both on
+//! top of [`ServiceManager::service_statuses`].
+
+use super::{ServiceManager, ServiceState, ServiceStatus};
+use async_trait::async_trait;
+use llm_optimizer_api_grpc::proto::admin::ManagedService;
+use llm_optimizer_api_rest::models::admin::ManagedServiceStatus;
+use std::fmt;
+use std::sync::{Arc, Weak};
+
+/// Reports the services in a [`ServiceManager`] to the admin APIs
+pub struct ServiceManagerStatus {
+    manager: Weak<ServiceManager>,
+}
+
+impl ServiceManagerStatus {
+    /// Report the services in `manager`
+    pub fn new(manager: &Arc<ServiceManager>) -> Self {
+        Self {
+            manager: Arc::downgrade(manager),
+        }
+    }
+
+    async fn statuses(&self) -> Vec<ServiceStatus> {
+        match self.manager.upgrade() {
+            Some(manager) => manager.service_statuses().await,
+            None => Vec::new(),
+        }
+    }
+}
+
+impl fmt::Debug for ServiceManagerStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ServiceManagerStatus")
+            .finish_non_exhaustive()
+    }
+}
+
+/// Name of `state` as shown by the admin APIs
+pub fn state_name(state: ServiceState) -> &'static str {
+    match state {
+        ServiceState::Initializing => "initializing",
+        ServiceState::Running => "running",
+        ServiceState::Degraded => "degraded",
+        ServiceState::ShuttingDown => "shutting_down",
+        ServiceState::Stopped => "stopped",
+        ServiceState::Failed => "failed",
+    }
+}
+
+#[async_trait]
+impl llm_optimizer_api_rest::routes::admin::ServiceStatusSource for ServiceManagerStatus {
+    async fn service_statuses(&self) -> Vec<ManagedServiceStatus> {
+        self.statuses()
+            .await
+            .into_iter()
+            .map(|status| ManagedServiceStatus {
+                name: status.name,
+                state: state_name(status.state).to_string(),
+                uptime_seconds: status.uptime.map(|uptime| uptime.as_secs()),
+                restart_count: status.restart_count,
+                last_health_message: status.last_health_message,
+            })
+            .collect()
+    }
+}
+
+#[async_trait]
+impl llm_optimizer_api_grpc::ServiceStatusSource for ServiceManagerStatus {
+    async fn service_statuses(&self) -> Vec<ManagedService> {
+        self.statuses()
+            .await
+            .into_iter()
+            .map(|status| ManagedService {
+                name: status.name,
+                state: state_name(status.state).to_string(),
+                uptime_seconds: status.uptime.map_or(0, |uptime| uptime.as_secs()),
+                restart_count: status.restart_count,
+                last_health_message: status.last_health_message.unwrap_or_default(),
+            })
+            .collect()
+    }
+}
@@ -1,10 +1,11 @@
 //! Storage service wrapper
 
-use super::{HealthCheckResult, Service, ServiceState};
+use super::{wait_until_healthy, HealthCheckResult, Service, ServiceState};
 use anyhow::Result;
 use async_trait::async_trait;
 use llm_optimizer_processor::{StorageManager, StorageConfig};
 use std::sync::Arc;
+use std::time::Duration;
 use tokio::sync::RwLock;
 use tracing::{info, warn};
 
@@ -99,6 +100,11 @@ impl Service for StorageService {
         }
     }
 
+    async fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+        // Backends may still be connecting after start returns
+        wait_until_healthy(self, timeout).await
+    }
+
     fn state(&self) -> ServiceState {
         tokio::task::block_in_place(|| {
             tokio::runtime::Handle::current().block_on(async {
@@ -3,8 +3,11 @@
 //! This module provides Unix signal handling for:
 //! - SIGTERM/SIGINT: Graceful shutdown
 //! - SIGHUP: Configuration reload
+//! - SIGUSR1/SIGUSR2: Raise/lower the log level
+//! - SIGQUIT: Metrics snapshot dump
 
 use anyhow::Result;
+use std::sync::Mutex;
 use tokio::signal;
 use tokio::sync::broadcast;
 use tracing::{info, warn};
@@ -16,12 +19,20 @@ pub enum SignalType {
     Shutdown,
     /// Reload signal (SIGHUP)
     Reload,
+    /// Metrics snapshot dump signal (SIGQUIT)
+    DumpMetrics,
+    /// Make logging more verbose (SIGUSR1)
+    RaiseLogLevel,
+    /// Make logging less verbose (SIGUSR2)
+    LowerLogLevel,
 }
 
 /// Signal handler
 pub struct SignalHandler {
     shutdown_tx: broadcast::Sender<()>,
     reload_tx: broadcast::Sender<()>,
+    dump_tx: broadcast::Sender<()>,
+    log_level_tx: broadcast::Sender<SignalType>,
 }
 
 impl SignalHandler {
@@ -29,10 +40,14 @@ impl SignalHandler {
     pub fn new() -> Self {
         let (shutdown_tx, _) = broadcast::channel(16);
         let (reload_tx, _) = broadcast::channel(16);
+        let (dump_tx, _) = broadcast::channel(16);
+        let (log_level_tx, _) = broadcast::channel(16);
 
         Self {
             shutdown_tx,
             reload_tx,
+            dump_tx,
+            log_level_tx,
         }
     }
 
@@ -46,6 +61,32 @@ impl SignalHandler {
         self.reload_tx.subscribe()
     }
 
+    /// Subscribe to metrics snapshot dump signals
+    pub fn subscribe_dump(&self) -> broadcast::Receiver<()> {
+        self.dump_tx.subscribe()
+    }
+
+    /// Subscribe to log level signals, delivered as
+    /// [`SignalType::RaiseLogLevel`] or [`SignalType::LowerLogLevel`]
+    pub fn subscribe_log_level(&self) -> broadcast::Receiver<SignalType> {
+        self.log_level_tx.subscribe()
+    }
+
+    /// Notify subscribers of a received signal
+    pub fn dispatch(&self, signal: SignalType) {
+        // Sending only fails when nobody is subscribed
+        let tx = match signal {
+            SignalType::Shutdown => &self.shutdown_tx,
+            SignalType::Reload => &self.reload_tx,
+            SignalType::DumpMetrics => &self.dump_tx,
+            SignalType::RaiseLogLevel | SignalType::LowerLogLevel => {
+                let _ = self.log_level_tx.send(signal);
+                return;
+            }
+        };
+        let _ = tx.send(());
+    }
+
     /// Start listening for signals
     pub async fn listen(self) -> Result<()> {
         info!("Starting signal handler");
@@ -56,7 +97,7 @@ impl SignalHandler {
                     // Handle SIGTERM
                     _ = signal::ctrl_c() => {
                         info!("Received SIGINT (Ctrl+C), initiating graceful shutdown");
-                        let _ = self.shutdown_tx.send(());
+                        self.dispatch(SignalType::Shutdown);
                         break;
                     }
 
@@ -64,7 +105,7 @@ impl SignalHandler {
                     #[cfg(unix)]
                     _ = Self::wait_for_sigterm() => {
                         info!("Received SIGTERM, initiating graceful shutdown");
-                        let _ = self.shutdown_tx.send(());
+                        self.dispatch(SignalType::Shutdown);
                         break;
                     }
 
@@ -72,7 +113,28 @@ impl SignalHandler {
                     #[cfg(unix)]
                     _ = Self::wait_for_sighup() => {
                         info!("Received SIGHUP, triggering configuration reload");
-                        let _ = self.reload_tx.send(());
+                        self.dispatch(SignalType::Reload);
+                    }
+
+                    // Handle SIGUSR1 (Unix only)
+                    #[cfg(unix)]
+                    _ = Self::wait_for_sigusr1() => {
+                        info!("Received SIGUSR1, raising log level");
+                        self.dispatch(SignalType::RaiseLogLevel);
+                    }
+
+                    // Handle SIGUSR2 (Unix only)
+                    #[cfg(unix)]
+                    _ = Self::wait_for_sigusr2() => {
+                        info!("Received SIGUSR2, lowering log level");
+                        self.dispatch(SignalType::LowerLogLevel);
+                    }
+
+                    // Handle SIGQUIT (Unix only)
+                    #[cfg(unix)]
+                    _ = Self::wait_for_sigquit() => {
+                        info!("Received SIGQUIT, dumping metrics snapshot");
+                        self.dispatch(SignalType::DumpMetrics);
                     }
                 }
             }
@@ -104,6 +166,39 @@ impl SignalHandler {
 
         sighup.recv().await;
     }
+
+    /// Wait for SIGUSR1 signal (Unix only)
+    #[cfg(unix)]
+    async fn wait_for_sigusr1() {
+        use signal::unix::{signal, SignalKind};
+
+        let mut sigusr1 = signal(SignalKind::user_defined1())
+            .expect("Failed to register SIGUSR1 handler");
+
+        sigusr1.recv().await;
+    }
+
+    /// Wait for SIGUSR2 signal (Unix only)
+    #[cfg(unix)]
+    async fn wait_for_sigusr2() {
+        use signal::unix::{signal, SignalKind};
+
+        let mut sigusr2 = signal(SignalKind::user_defined2())
+            .expect("Failed to register SIGUSR2 handler");
+
+        sigusr2.recv().await;
+    }
+
+    /// Wait for SIGQUIT signal (Unix only)
+    #[cfg(unix)]
+    async fn wait_for_sigquit() {
+        use signal::unix::{signal, SignalKind};
+
+        let mut sigquit = signal(SignalKind::quit())
+            .expect("Failed to register SIGQUIT handler");
+
+        sigquit.recv().await;
+    }
 }
 
 impl Default for SignalHandler {
@@ -112,6 +207,56 @@ impl Default for SignalHandler {
     }
 }
 
+/// Log levels stepped through by SIGUSR1/SIGUSR2, least verbose first
+pub const LOG_LEVEL_STEPS: [&str; 5] = ["error", "warn", "info", "debug", "trace"];
+
+/// Callback that installs a new log filter directive
+type ApplyLogFilter = Box<dyn Fn(&str) -> Result<()> + Send + Sync>;
+
+/// Steps the log level up or down on [`SignalType::RaiseLogLevel`] and
+/// [`SignalType::LowerLogLevel`], applying each new level through a callback
+/// (in the binary, a `tracing_subscriber` reload handle)
+pub struct LogLevelControl {
+    step: Mutex<usize>,
+    apply: ApplyLogFilter,
+}
+
+impl LogLevelControl {
+    /// Start at `initial`, falling back to `info` if it is not a plain level
+    pub fn new(initial: &str, apply: impl Fn(&str) -> Result<()> + Send + Sync + 'static) -> Self {
+        let step = LOG_LEVEL_STEPS
+            .iter()
+            .position(|level| level.eq_ignore_ascii_case(initial.trim()))
+            .unwrap_or(2);
+
+        Self {
+            step: Mutex::new(step),
+            apply: Box::new(apply),
+        }
+    }
+
+    /// Current log level
+    pub fn level(&self) -> &'static str {
+        LOG_LEVEL_STEPS[*self.step.lock().unwrap()]
+    }
+
+    /// Apply the level one step in the direction of `signal`. Returns the
+    /// new level, or `None` if already at the end of the range or `signal`
+    /// is not a log level signal.
+    pub fn handle(&self, signal: SignalType) -> Result<Option<&'static str>> {
+        let mut step = self.step.lock().unwrap();
+        let next = match signal {
+            SignalType::RaiseLogLevel if *step + 1 < LOG_LEVEL_STEPS.len() => *step + 1,
+            SignalType::LowerLogLevel if *step > 0 => *step - 1,
+            _ => return Ok(None),
+        };
+
+        (self.apply)(LOG_LEVEL_STEPS[next])?;
+        *step = next;
+        Ok(Some(LOG_LEVEL_STEPS[next]))
+    }
+}
+
 /// Wait for shutdown signal
 pub async fn wait_for_shutdown() {
     let handler = SignalHandler::new();
@@ -180,12 +325,14 @@ impl Default for ShutdownCoordinator {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::sync::Arc;
 
     #[test]
     fn test_signal_handler_creation() {
         let handler = SignalHandler::new();
         let _shutdown_rx = handler.subscribe_shutdown();
         let _reload_rx = handler.subscribe_reload();
+        let _dump_rx = handler.subscribe_dump();
     }
 
     #[test]
@@ -199,5 +346,43 @@ mod tests {
     fn test_signal_type_equality() {
         assert_eq!(SignalType::Shutdown, SignalType::Shutdown);
         assert_ne!(SignalType::Shutdown, SignalType::Reload);
+        assert_ne!(SignalType::Reload, SignalType::DumpMetrics);
+        assert_ne!(SignalType::RaiseLogLevel, SignalType::LowerLogLevel);
+    }
+
+    #[tokio::test]
+    async fn test_log_level_signals_reload_filter() {
+        let handler = SignalHandler::new();
+        let mut log_level_rx = handler.subscribe_log_level();
+
+        let applied = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&applied);
+        let control = LogLevelControl::new("info", move |filter| {
+            recorder.lock().unwrap().push(filter.to_string());
+            Ok(())
+        });
+
+        for signal in [
+            SignalType::RaiseLogLevel,
+            SignalType::RaiseLogLevel,
+            SignalType::RaiseLogLevel,
+            SignalType::LowerLogLevel,
+        ] {
+            handler.dispatch(signal);
+            let received = log_level_rx.recv().await.unwrap();
+            control.handle(received).unwrap();
+        }
+
+        // The third raise is already at trace, so nothing is reloaded
+        assert_eq!(*applied.lock().unwrap(), vec!["debug", "trace", "debug"]);
+        assert_eq!(control.level(), "debug");
+    }
+
+    #[test]
+    fn test_log_level_unchanged_when_reload_fails() {
+        let control = LogLevelControl::new("warn", |_| anyhow::bail!("reload failed"));
+        assert!(control.handle(SignalType::LowerLogLevel).is_err());
+        assert_eq!(control.level(), "warn");
+        assert_eq!(control.handle(SignalType::Reload).unwrap(), None);
     }
 }
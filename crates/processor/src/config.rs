@@ -5,6 +5,7 @@
 
 use crate::error::{ProcessorError, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 use std::time::Duration;
 
@@ -42,6 +43,10 @@ pub struct ProcessorConfig {
     /// Enable metrics collection
     #[serde(default = "default_true")]
     pub metrics_enabled: bool,
+
+    /// Per-service resource quotas
+    #[serde(default)]
+    pub quotas: QuotaConfig,
 }
 
 impl Default for ProcessorConfig {
@@ -56,6 +61,7 @@ impl Default for ProcessorConfig {
             parallelism: default_parallelism(),
             buffer_size: default_buffer_size(),
             metrics_enabled: true,
+            quotas: QuotaConfig::default(),
         }
     }
 }
@@ -69,6 +75,7 @@ impl ProcessorConfig {
         self.aggregation.validate()?;
         self.deduplication.validate()?;
         self.normalization.validate()?;
+        self.quotas.validate()?;
 
         if self.parallelism == 0 {
             return Err(ProcessorError::Configuration {
@@ -986,6 +993,138 @@ impl Default for DeduplicationStrategy {
     }
 }
 
+/// Per-service resource quota configuration
+///
+/// Quotas are keyed by target service name. Services without an entry are
+/// not limited.
+///
+/// # Example
+///
+/// ```rust
+/// use processor::config::{QuotaConfig, ServiceQuota};
+/// use std::time::Duration;
+///
+/// let config = QuotaConfig::new()
+///     .with_window(Duration::from_secs(3600))
+///     .with_service("chat", ServiceQuota::new().with_max_in_flight(4).with_max_cost(25.0));
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuotaConfig {
+    /// Window over which cost is accumulated before resetting
+    #[serde(
+        default = "default_quota_window",
+        serialize_with = "serialize_duration",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub window: Duration,
+
+    /// Quotas by service name
+    #[serde(default)]
+    pub services: HashMap<String, ServiceQuota>,
+
+    /// Most events held back by quotas at once; events deferred beyond this
+    /// are dropped
+    #[serde(default = "default_max_deferred")]
+    pub max_deferred: usize,
+}
+
+impl Default for QuotaConfig {
+    fn default() -> Self {
+        Self {
+            window: default_quota_window(),
+            services: HashMap::new(),
+            max_deferred: default_max_deferred(),
+        }
+    }
+}
+
+impl QuotaConfig {
+    /// Create a new quota configuration with no service limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the cost accounting window
+    pub fn with_window(mut self, window: Duration) -> Self {
+        self.window = window;
+        self
+    }
+
+    /// Set the quota for a service
+    pub fn with_service<S: Into<String>>(mut self, service: S, quota: ServiceQuota) -> Self {
+        self.services.insert(service.into(), quota);
+        self
+    }
+
+    /// Set the most events held back by quotas at once
+    pub fn with_max_deferred(mut self, max_deferred: usize) -> Self {
+        self.max_deferred = max_deferred;
+        self
+    }
+
+    /// Validate quota configuration
+    pub fn validate(&self) -> Result<()> {
+        if self.window.is_zero() {
+            return Err(ProcessorError::Configuration {
+                source: "quota window must be greater than 0".into(),
+            });
+        }
+
+        if self.max_deferred == 0 {
+            return Err(ProcessorError::Configuration {
+                source: "quota max_deferred must be greater than 0".into(),
+            });
+        }
+
+        for (service, quota) in &self.services {
+            if quota.max_in_flight == Some(0) {
+                return Err(ProcessorError::Configuration {
+                    source: format!("quota max_in_flight for service '{}' must be greater than 0", service).into(),
+                });
+            }
+
+            if quota.max_cost_per_window.is_some_and(|cost| !(cost > 0.0)) {
+                return Err(ProcessorError::Configuration {
+                    source: format!("quota max_cost_per_window for service '{}' must be greater than 0", service).into(),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Resource limits for a single service
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ServiceQuota {
+    /// Maximum number of analyses running at once
+    #[serde(default)]
+    pub max_in_flight: Option<usize>,
+
+    /// Maximum cost in USD accumulated per quota window
+    #[serde(default)]
+    pub max_cost_per_window: Option<f64>,
+}
+
+impl ServiceQuota {
+    /// Create a quota with no limits
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Limit the number of in-flight analyses
+    pub fn with_max_in_flight(mut self, max: usize) -> Self {
+        self.max_in_flight = Some(max);
+        self
+    }
+
+    /// Limit the cost per quota window
+    pub fn with_max_cost(mut self, max_cost: f64) -> Self {
+        self.max_cost_per_window = Some(max_cost);
+        self
+    }
+}
+
 // Helper functions for Duration serialization/deserialization
 fn serialize_duration<S>(duration: &Duration, serializer: S) -> std::result::Result<S::Ok, S::Error>
 where
@@ -1063,6 +1202,14 @@ fn default_deduplication_ttl() -> Duration {
     Duration::from_secs(3600) // 1 hour
 }
 
+fn default_quota_window() -> Duration {
+    Duration::from_secs(3600) // 1 hour
+}
+
+fn default_max_deferred() -> usize {
+    10_000
+}
+
 fn default_redis_key_prefix() -> String {
     "dedup".to_string()
 }
@@ -5,6 +5,7 @@
 //! - EventKey: Different types of aggregation keys
 //! - EventTimeExtractor: Trait for extracting event timestamps
 //! - KeyExtractor: Trait for extracting aggregation keys
+//! - ServiceExtractor: Trait for extracting the service charged for an event
 
 use chrono::{DateTime, Utc};
 use llm_optimizer_types::events::{CloudEvent, FeedbackEvent};
@@ -188,6 +189,19 @@ pub trait KeyExtractor<T> {
     fn extract_key(&self, event: &T) -> EventKey;
 }
 
+/// Trait for extracting the target service of events
+///
+/// Implement this trait to have per-service quotas enforced on your event type.
+pub trait ServiceExtractor<T> {
+    /// Extract the service the event belongs to, if any
+    fn extract_service(&self, event: &T) -> Option<String>;
+
+    /// Cost in USD of processing the event, charged against its service's quota
+    fn extract_cost(&self, _event: &T) -> f64 {
+        0.0
+    }
+}
+
 // ============================================================================
 // Implementations for llm-optimizer-types events
 // ============================================================================
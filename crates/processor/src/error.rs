@@ -3,6 +3,7 @@
 //! This module provides comprehensive error handling for all processor operations
 //! including windowing, aggregation, state management, and watermark generation.
 
+use std::time::Duration;
 use thiserror::Error;
 
 /// Main processor error type
@@ -44,6 +45,10 @@ pub enum ProcessorError {
     #[error("I/O error: {0}")]
     Io(#[from] std::io::Error),
 
+    /// Per-service quota errors
+    #[error("quota error: {0}")]
+    Quota(#[from] QuotaError),
+
     /// Kafka-related errors
     #[error("kafka error: {source}")]
     Kafka {
@@ -238,6 +243,50 @@ pub enum WatermarkError {
     AlignmentFailed { reason: String },
 }
 
+/// Per-service quota errors
+///
+/// Work rejected with one of these errors should be deferred and retried later.
+#[derive(Error, Debug, Clone, PartialEq)]
+pub enum QuotaError {
+    /// The service already has the maximum number of analyses running
+    #[error("service '{service}' has reached its limit of {limit} in-flight analyses")]
+    InFlightExceeded { service: String, limit: usize },
+
+    /// The service has spent its cost budget for the current window
+    #[error("service '{service}' has spent ${spent:.2} of its ${limit:.2} budget, resets in {}s", retry_after.as_secs())]
+    CostExceeded {
+        service: String,
+        spent: f64,
+        limit: f64,
+        retry_after: Duration,
+    },
+}
+
+impl QuotaError {
+    /// Service whose quota was exceeded
+    pub fn service(&self) -> &str {
+        match self {
+            QuotaError::InFlightExceeded { service, .. } | QuotaError::CostExceeded { service, .. } => service,
+        }
+    }
+
+    /// Short reason, suitable as a metric label
+    pub fn reason(&self) -> &'static str {
+        match self {
+            QuotaError::InFlightExceeded { .. } => "in_flight",
+            QuotaError::CostExceeded { .. } => "cost",
+        }
+    }
+
+    /// How long to wait before the quota can admit work again, if known
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            QuotaError::InFlightExceeded { .. } => None,
+            QuotaError::CostExceeded { retry_after, .. } => Some(*retry_after),
+        }
+    }
+}
+
 /// Result type alias for processor operations
 pub type Result<T> = std::result::Result<T, ProcessorError>;
 
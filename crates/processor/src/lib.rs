@@ -15,6 +15,7 @@ pub mod kafka;
 pub mod metrics;
 pub mod normalization;
 pub mod pipeline;
+pub mod quota;
 pub mod state;
 pub mod storage;
 pub mod telemetry;
@@ -26,7 +27,7 @@ pub mod stream_processor;
 pub use core::{
     CloudEventKeyExtractor, CloudEventTimeExtractor, EventKey, EventTimeExtractor,
     FeedbackEventKeyExtractor, FeedbackEventTimeExtractor, KeyExtractor,
-    MetricPointKeyExtractor, MetricPointTimeExtractor, ProcessorEvent,
+    MetricPointKeyExtractor, MetricPointTimeExtractor, ProcessorEvent, ServiceExtractor,
 };
 
 pub use window::{
@@ -41,7 +42,7 @@ pub use stream_processor::{
 };
 
 pub use error::{
-    ProcessorError, WindowError, AggregationError, StateError, WatermarkError,
+    ProcessorError, WindowError, AggregationError, StateError, WatermarkError, QuotaError,
     Result as ProcessorResult,
 };
 
@@ -49,9 +50,11 @@ pub use config::{
     ProcessorConfig, WindowConfig, WindowType as ConfigWindowType,
     WatermarkConfig, WatermarkStrategy, StateConfig, StateBackend,
     AggregationConfig, AggregationType,
-    DeduplicationStrategy,
+    DeduplicationStrategy, QuotaConfig, ServiceQuota,
 };
 
+pub use quota::{QuotaEnforcer, QuotaPermit};
+
 pub use normalization::{
     TimeSeriesNormalizer, NormalizerBuilder, NormalizationConfig, FillStrategy,
     TimeSeriesEvent, NormalizationStats, NormalizationError, NormalizationResult,
@@ -19,6 +19,8 @@ impl LabelNames {
     pub const PARTITION: &'static str = "partition";
     pub const TOPIC: &'static str = "topic";
     pub const ERROR_TYPE: &'static str = "error_type";
+    pub const SERVICE: &'static str = "service";
+    pub const REASON: &'static str = "reason";
 }
 
 /// Label value for processing results
@@ -137,6 +137,10 @@ pub struct ProcessorMetrics {
 
     /// Duration of operator execution in seconds
     pub operator_duration_seconds: Family<Vec<(String, String)>, Histogram>,
+
+    // === Quota Metrics ===
+    /// Total number of work items deferred by per-service quotas
+    pub quota_deferrals_total: Family<Vec<(String, String)>, Counter<u64, AtomicU64>>,
 }
 
 impl ProcessorMetrics {
@@ -197,6 +201,9 @@ impl ProcessorMetrics {
             pipeline_errors_total: Family::default(),
             pipeline_operators_active: Gauge::default(),
             operator_duration_seconds: Family::new_with_constructor(Self::duration_histogram),
+
+            // Quotas
+            quota_deferrals_total: Family::default(),
         };
 
         // Register all metrics with help text and units
@@ -422,6 +429,12 @@ impl ProcessorMetrics {
             metrics.operator_duration_seconds.clone(),
         );
 
+        registry.register(
+            "processor_quota_deferrals",
+            "Total number of work items deferred by per-service quotas",
+            metrics.quota_deferrals_total.clone(),
+        );
+
         metrics
     }
 
@@ -603,6 +616,17 @@ impl ProcessorMetrics {
         self.out_of_order_events_total.inc();
     }
 
+    /// Record work deferred by a service quota
+    #[inline]
+    pub fn record_quota_deferral(&self, service: &str, reason: &str) {
+        self.quota_deferrals_total
+            .get_or_create(&vec![
+                (LabelNames::SERVICE.to_string(), service.to_string()),
+                (LabelNames::REASON.to_string(), reason.to_string()),
+            ])
+            .inc();
+    }
+
     /// Helper function to create duration histograms (1ms to 30s)
     fn duration_histogram() -> Histogram {
         Histogram::new(exponential_buckets(0.001, 2.0, 16))
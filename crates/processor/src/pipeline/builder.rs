@@ -375,6 +375,21 @@ impl StreamPipelineBuilder {
         self
     }
 
+    /// Set the per-service quotas enforced by executors
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// # use processor::pipeline::StreamPipelineBuilder;
+    /// # use processor::config::{QuotaConfig, ServiceQuota};
+    /// let builder = StreamPipelineBuilder::new()
+    ///     .with_quotas(QuotaConfig::new().with_service("chat", ServiceQuota::new().with_max_cost(25.0)));
+    /// ```
+    pub fn with_quotas(mut self, quotas: crate::config::QuotaConfig) -> Self {
+        self.config.processor.quotas = quotas;
+        self
+    }
+
     /// Set the deduplication configuration
     ///
     /// # Example
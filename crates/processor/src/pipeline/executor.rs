@@ -3,16 +3,19 @@
 //! This module provides the execution engine for stream processing pipelines,
 //! handling event ingestion, watermark propagation, window management, and result emission.
 
-use crate::core::{EventTimeExtractor, KeyExtractor, ProcessorEvent};
+use crate::core::{EventTimeExtractor, KeyExtractor, ProcessorEvent, ServiceExtractor};
 use crate::error::{ProcessorError, Result};
 use crate::pipeline::builder::StreamPipeline;
+use crate::quota::QuotaEnforcer;
 use crate::watermark::{BoundedOutOfOrdernessWatermark, Watermark, WatermarkGenerator};
 use crate::window::{
     SlidingWindowAssigner, SessionWindowAssigner,
     TumblingWindowAssigner, Window, WindowAssigner,
 };
 use dashmap::DashMap;
+use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
 use std::time::Duration;
@@ -20,6 +23,10 @@ use tokio::sync::{mpsc, RwLock};
 use tokio::time;
 use tracing::{debug, error, info, trace, warn};
 
+/// How often deferred events are retried, including while new events keep
+/// arriving
+const DEFERRED_RETRY_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Statistics for the stream executor
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct ExecutorStats {
@@ -29,6 +36,10 @@ pub struct ExecutorStats {
     /// Total events dropped (late events)
     pub events_dropped: u64,
 
+    /// Total times an event was deferred by its service's quota
+    #[serde(default)]
+    pub events_deferred: u64,
+
     /// Total windows created
     pub windows_created: u64,
 
@@ -67,6 +78,11 @@ impl ExecutorStats {
         self.events_dropped += 1;
     }
 
+    /// Increment events deferred
+    pub fn inc_events_deferred(&mut self) {
+        self.events_deferred += 1;
+    }
+
     /// Increment windows created
     pub fn inc_windows_created(&mut self) {
         self.windows_created += 1;
@@ -211,6 +227,15 @@ where
     /// Window states per key
     window_states: Arc<DashMap<String, Vec<WindowState<T>>>>,
 
+    /// Per-service quotas
+    quotas: Arc<QuotaEnforcer>,
+
+    /// Extracts the service whose quota an event is charged to
+    service_extractor: Option<Arc<dyn ServiceExtractor<T> + Send + Sync>>,
+
+    /// Events held back by their service's quota, oldest first
+    deferred: Arc<Mutex<VecDeque<T>>>,
+
     /// Execution statistics
     stats: Arc<RwLock<ExecutorStats>>,
 
@@ -240,6 +265,8 @@ where
                 Some(Duration::from_millis(watermark_config.idle_timeout_ms)),
             ));
 
+        let quotas = Arc::new(QuotaEnforcer::new(pipeline.config().processor.quotas.clone()));
+
         Self {
             pipeline,
             input_tx,
@@ -248,12 +275,42 @@ where
             output_rx: Arc::new(RwLock::new(output_rx)),
             watermark_generator: Arc::new(RwLock::new(watermark_generator)),
             window_states: Arc::new(DashMap::new()),
+            quotas,
+            service_extractor: None,
+            deferred: Arc::new(Mutex::new(VecDeque::new())),
             stats: Arc::new(RwLock::new(ExecutorStats::new())),
             running: Arc::new(AtomicU64::new(0)),
             partition: 0,
         }
     }
 
+    /// Enforce the pipeline's per-service quotas, charging each event to
+    /// the service `extractor` finds for it
+    ///
+    /// Events without a service, and all events when no extractor is set,
+    /// are not limited.
+    pub fn with_service_extractor<E>(mut self, extractor: E) -> Self
+    where
+        E: ServiceExtractor<T> + Send + Sync + 'static,
+    {
+        self.service_extractor = Some(Arc::new(extractor));
+        self
+    }
+
+    /// Share a quota enforcer with other executors
+    ///
+    /// In-flight limits only span executors that share an enforcer; by
+    /// default each executor builds its own from the pipeline's quotas.
+    pub fn with_quota_enforcer(mut self, quotas: Arc<QuotaEnforcer>) -> Self {
+        self.quotas = quotas;
+        self
+    }
+
+    /// Number of events currently deferred by service quotas
+    pub fn deferred_count(&self) -> usize {
+        self.deferred.lock().len()
+    }
+
     /// Ingest an event into the pipeline
     ///
     /// # Example
@@ -341,6 +398,7 @@ where
         self.spawn_watermark_updater(Duration::from_millis(watermark_interval));
 
         // Process events
+        let mut next_retry = time::Instant::now() + DEFERRED_RETRY_INTERVAL;
         loop {
             if self.running.load(Ordering::Relaxed) == 0 {
                 info!("Executor stopped");
@@ -348,7 +406,7 @@ where
             }
 
             let mut rx = self.input_rx.write().await;
-            match time::timeout(Duration::from_millis(100), rx.recv()).await {
+            match time::timeout(DEFERRED_RETRY_INTERVAL, rx.recv()).await {
                 Ok(Some(event)) => {
                     drop(rx); // Release the lock
                     if let Err(e) = self.process_event(event).await {
@@ -358,12 +416,25 @@ where
                 }
                 Ok(None) => {
                     debug!("Input channel closed");
+                    drop(rx);
+                    self.retry_deferred_or_log().await;
+                    let remaining = self.deferred_count();
+                    if remaining > 0 {
+                        warn!(
+                            remaining,
+                            "Input closed with events still deferred by quotas"
+                        );
+                    }
                     break;
                 }
-                Err(_) => {
-                    // Timeout - continue
-                    continue;
-                }
+                Err(_) => drop(rx),
+            }
+
+            // Give deferred events another chance on a timer, so they are
+            // retried under steady load too
+            if time::Instant::now() >= next_retry {
+                self.retry_deferred_or_log().await;
+                next_retry = time::Instant::now() + DEFERRED_RETRY_INTERVAL;
             }
         }
 
@@ -377,8 +448,128 @@ where
         self.running.store(0, Ordering::Relaxed);
     }
 
-    /// Process a single event
+    /// Re-process deferred events, oldest first
+    ///
+    /// Events whose service is still over quota, and events that fail to
+    /// process, stay deferred in their original order. Every event is tried
+    /// before the first processing error is returned.
+    pub async fn retry_deferred(&self) -> Result<()>
+    where
+        T: EventTimeExtractor<T> + KeyExtractor<T>,
+    {
+        let pending: Vec<T> = self.deferred.lock().drain(..).collect();
+        let mut still_deferred = VecDeque::new();
+        let mut first_error = None;
+
+        for event in pending {
+            match self.try_process(event.clone(), true).await {
+                Ok(None) => {}
+                Ok(Some(event)) => still_deferred.push_back(event),
+                Err(e) => {
+                    still_deferred.push_back(event);
+                    first_error.get_or_insert(e);
+                }
+            }
+        }
+
+        if !still_deferred.is_empty() {
+            let mut deferred = self.deferred.lock();
+            // Ahead of any events deferred while these were retried
+            still_deferred.extend(deferred.drain(..));
+            *deferred = still_deferred;
+        }
+
+        first_error.map_or(Ok(()), Err)
+    }
+
+    /// Retry deferred events, logging a failure instead of returning it
+    async fn retry_deferred_or_log(&self)
+    where
+        T: EventTimeExtractor<T> + KeyExtractor<T>,
+    {
+        if self.deferred_count() == 0 {
+            return;
+        }
+        if let Err(e) = self.retry_deferred().await {
+            error!(error = %e, "Failed to process deferred event");
+            self.stats.write().await.inc_errors();
+        }
+    }
+
+    /// Process a single event, deferring it if its service is over quota
     async fn process_event(&self, event: T) -> Result<()>
+    where
+        T: EventTimeExtractor<T> + KeyExtractor<T>,
+    {
+        if let Some(event) = self.try_process(event, false).await? {
+            self.defer(event).await;
+        }
+        Ok(())
+    }
+
+    /// Process an event if its service's quota admits it, handing the event
+    /// back if not
+    ///
+    /// A refused retry is not recorded as another quota deferral.
+    async fn try_process(&self, event: T, retry: bool) -> Result<Option<T>>
+    where
+        T: EventTimeExtractor<T> + KeyExtractor<T>,
+    {
+        let Some(extractor) = &self.service_extractor else {
+            self.process_admitted(event).await?;
+            return Ok(None);
+        };
+        let Some(service) = extractor.extract_service(&event) else {
+            self.process_admitted(event).await?;
+            return Ok(None);
+        };
+
+        let admitted = if retry {
+            self.quotas.retry_acquire(&service)
+        } else {
+            self.quotas.try_acquire(&service)
+        };
+        let permit = match admitted {
+            Ok(permit) => permit,
+            Err(err) => {
+                trace!(error = %err, "Event not admitted by quota");
+                return Ok(Some(event));
+            }
+        };
+
+        let cost = extractor.extract_cost(&event);
+        self.process_admitted(event).await?;
+        permit.record_cost(cost);
+        Ok(None)
+    }
+
+    /// Hold an event back until its service's quota admits it
+    ///
+    /// The event is dropped if the deferred queue is already full.
+    async fn defer(&self, event: T) {
+        let max_deferred = self.pipeline.config().processor.quotas.max_deferred;
+        let queued = {
+            let mut deferred = self.deferred.lock();
+            if deferred.len() < max_deferred {
+                deferred.push_back(event);
+                true
+            } else {
+                false
+            }
+        };
+
+        let mut stats = self.stats.write().await;
+        if queued {
+            debug!("Deferring event");
+            stats.inc_events_deferred();
+        } else {
+            warn!(max_deferred, "Deferred event queue is full, dropping event");
+            stats.inc_events_dropped();
+        }
+    }
+
+    /// Process a single event admitted by its quota
+    async fn process_admitted(&self, event: T) -> Result<()>
     where
         T: EventTimeExtractor<T> + KeyExtractor<T>,
     {
@@ -653,7 +844,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::config::WindowConfig;
+    use crate::config::{QuotaConfig, ServiceQuota, WindowConfig};
     use crate::core::{EventKey, EventTimeExtractor, KeyExtractor};
     use crate::pipeline::StreamPipelineBuilder;
     use chrono::{DateTime, Utc};
@@ -893,6 +1084,156 @@ mod tests {
         assert_eq!(executor.active_window_count(), 1);
     }
 
+    struct TestServiceExtractor;
+
+    impl ServiceExtractor<TestEvent> for TestServiceExtractor {
+        fn extract_service(&self, event: &TestEvent) -> Option<String> {
+            Some(event.key.clone())
+        }
+
+        fn extract_cost(&self, event: &TestEvent) -> f64 {
+            event.value
+        }
+    }
+
+    fn event(key: &str, value: f64) -> TestEvent {
+        TestEvent {
+            value,
+            timestamp: Utc::now().timestamp_millis(),
+            key: key.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_events_beyond_quota_are_deferred() {
+        let pipeline = StreamPipelineBuilder::new()
+            .with_name("test")
+            .with_quotas(
+                QuotaConfig::new()
+                    .with_window(Duration::from_millis(50))
+                    .with_service("chat", ServiceQuota::new().with_max_cost(1.0)),
+            )
+            .build()
+            .unwrap();
+        let executor: StreamExecutor<TestEvent> =
+            pipeline.create_executor().with_service_extractor(TestServiceExtractor);
+
+        executor.process_event(event("chat", 1.5)).await.unwrap();
+        executor.process_event(event("chat", 0.5)).await.unwrap();
+        executor.process_event(event("search", 100.0)).await.unwrap();
+
+        // Chat is over its budget; search is unlimited and proceeds
+        let stats = executor.stats().await;
+        assert_eq!(stats.events_processed, 2);
+        assert_eq!(stats.events_deferred, 1);
+        assert_eq!(executor.deferred_count(), 1);
+
+        executor.retry_deferred().await.unwrap();
+        assert_eq!(executor.deferred_count(), 1);
+
+        // The deferred event goes through once the window resets
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        executor.retry_deferred().await.unwrap();
+        assert_eq!(executor.deferred_count(), 0);
+        assert_eq!(executor.stats().await.events_processed, 3);
+    }
+
+    #[tokio::test]
+    async fn test_deferred_queue_is_bounded_and_counts_each_event_once() {
+        let pipeline = StreamPipelineBuilder::new()
+            .with_name("test")
+            .with_quotas(
+                QuotaConfig::new()
+                    .with_max_deferred(2)
+                    .with_service("chat", ServiceQuota::new().with_max_in_flight(1)),
+            )
+            .build()
+            .unwrap();
+        let enforcer = Arc::new(QuotaEnforcer::new(pipeline.config().processor.quotas.clone()));
+        let executor: StreamExecutor<TestEvent> = pipeline
+            .create_executor()
+            .with_service_extractor(TestServiceExtractor)
+            .with_quota_enforcer(Arc::clone(&enforcer));
+
+        let _permit = enforcer.try_acquire("chat").unwrap();
+        for value in [1.0, 2.0, 3.0] {
+            executor.process_event(event("chat", value)).await.unwrap();
+        }
+        assert_eq!(executor.deferred_count(), 2);
+
+        // Retries that are still refused keep the events, in order, without
+        // counting them again
+        executor.retry_deferred().await.unwrap();
+        executor.retry_deferred().await.unwrap();
+        let stats = executor.stats().await;
+        assert_eq!((stats.events_deferred, stats.events_dropped), (2, 1));
+        let values: Vec<f64> = executor.deferred.lock().iter().map(|e| e.value).collect();
+        assert_eq!(values, vec![1.0, 2.0]);
+    }
+
+    #[tokio::test]
+    async fn test_deferred_events_are_retried_under_steady_load() {
+        let pipeline = StreamPipelineBuilder::new()
+            .with_name("test")
+            .with_quotas(
+                QuotaConfig::new()
+                    .with_window(Duration::from_millis(50))
+                    .with_service("chat", ServiceQuota::new().with_max_cost(1.0)),
+            )
+            .build()
+            .unwrap();
+        let executor: Arc<StreamExecutor<TestEvent>> =
+            Arc::new(pipeline.create_executor().with_service_extractor(TestServiceExtractor));
+
+        executor.process_event(event("chat", 1.5)).await.unwrap();
+        executor.process_event(event("chat", 0.5)).await.unwrap();
+        assert_eq!(executor.deferred_count(), 1);
+
+        let runner = tokio::spawn({
+            let executor = Arc::clone(&executor);
+            async move { executor.run().await }
+        });
+        // New events arrive faster than the idle timeout, so only the retry
+        // timer can release the deferred one
+        for _ in 0..20 {
+            executor.ingest(event("search", 0.0)).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(20)).await;
+        }
+        executor.stop();
+        runner.await.unwrap().unwrap();
+
+        assert_eq!(executor.deferred_count(), 0);
+        assert_eq!(executor.stats().await.events_deferred, 1);
+    }
+
+    #[tokio::test]
+    async fn test_in_flight_quota_is_shared_between_executors() {
+        let quotas = QuotaConfig::new().with_service("chat", ServiceQuota::new().with_max_in_flight(1));
+        let pipeline = StreamPipelineBuilder::new()
+            .with_name("test")
+            .with_quotas(quotas.clone())
+            .build()
+            .unwrap();
+        let enforcer = Arc::new(QuotaEnforcer::new(quotas));
+        let executor: StreamExecutor<TestEvent> = pipeline
+            .create_executor()
+            .with_service_extractor(TestServiceExtractor)
+            .with_quota_enforcer(Arc::clone(&enforcer));
+
+        // Another executor holds chat's only slot
+        let permit = enforcer.try_acquire("chat").unwrap();
+        executor.process_event(event("chat", 0.0)).await.unwrap();
+        executor.process_event(event("search", 0.0)).await.unwrap();
+        assert_eq!(executor.deferred_count(), 1);
+        assert_eq!(executor.stats().await.events_processed, 1);
+
+        drop(permit);
+        executor.retry_deferred().await.unwrap();
+        assert_eq!(executor.deferred_count(), 0);
+        assert_eq!(executor.stats().await.events_processed, 2);
+        assert_eq!(enforcer.in_flight("chat"), 0);
+    }
+
     #[tokio::test]
     async fn test_clear_windows() {
         let pipeline = StreamPipelineBuilder::new()
@@ -0,0 +1,298 @@
+//! Per-service resource quotas
+//!
+//! Keeps a single runaway target service from monopolizing the processor by
+//! capping the number of analyses it may have in flight and the cost it may
+//! accrue per quota window. Work beyond a service's quota is deferred with a
+//! [`QuotaError`] while other services keep being processed.
+//!
+//! # Example
+//!
+//! ```rust
+//! use processor::config::{QuotaConfig, ServiceQuota};
+//! use processor::quota::QuotaEnforcer;
+//!
+//! let config = QuotaConfig::new().with_service("chat", ServiceQuota::new().with_max_in_flight(1));
+//! let enforcer = QuotaEnforcer::new(config);
+//!
+//! let permit = enforcer.try_acquire("chat").unwrap();
+//! assert!(enforcer.try_acquire("chat").is_err());
+//! assert!(enforcer.try_acquire("search").is_ok());
+//!
+//! permit.record_cost(0.02);
+//! drop(permit);
+//! assert!(enforcer.try_acquire("chat").is_ok());
+//! ```
+
+use crate::config::{QuotaConfig, ServiceQuota};
+use crate::error::QuotaError;
+use crate::metrics::ProcessorMetrics;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::debug;
+
+/// Resource usage of a single service
+#[derive(Debug)]
+struct ServiceUsage {
+    in_flight: usize,
+    window_start: Instant,
+    window_cost: f64,
+}
+
+impl ServiceUsage {
+    fn new(now: Instant) -> Self {
+        Self {
+            in_flight: 0,
+            window_start: now,
+            window_cost: 0.0,
+        }
+    }
+
+    /// Start a new cost window if the current one has elapsed
+    fn roll_window(&mut self, window: Duration, now: Instant) {
+        if now.duration_since(self.window_start) >= window {
+            self.window_start = now;
+            self.window_cost = 0.0;
+        }
+    }
+
+    fn check(&self, service: &str, quota: &ServiceQuota, window: Duration, now: Instant) -> Result<(), QuotaError> {
+        if let Some(limit) = quota.max_in_flight {
+            if self.in_flight >= limit {
+                return Err(QuotaError::InFlightExceeded {
+                    service: service.to_string(),
+                    limit,
+                });
+            }
+        }
+
+        if let Some(limit) = quota.max_cost_per_window {
+            if self.window_cost >= limit {
+                return Err(QuotaError::CostExceeded {
+                    service: service.to_string(),
+                    spent: self.window_cost,
+                    limit,
+                    retry_after: window.saturating_sub(now.duration_since(self.window_start)),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+type UsageMap = Arc<Mutex<HashMap<String, ServiceUsage>>>;
+
+/// Enforces per-service quotas on processor work
+pub struct QuotaEnforcer {
+    config: QuotaConfig,
+    usage: UsageMap,
+    metrics: Option<Arc<ProcessorMetrics>>,
+}
+
+impl QuotaEnforcer {
+    /// Create an enforcer for the given quotas
+    pub fn new(config: QuotaConfig) -> Self {
+        Self {
+            config,
+            usage: Arc::new(Mutex::new(HashMap::new())),
+            metrics: None,
+        }
+    }
+
+    /// Record deferrals in the given processor metrics
+    pub fn with_metrics(mut self, metrics: Arc<ProcessorMetrics>) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
+    /// Try to admit one piece of work for a service
+    ///
+    /// Returns a permit that holds an in-flight slot until dropped, or a
+    /// [`QuotaError`] describing which quota the work would exceed. Services
+    /// without a configured quota are always admitted.
+    pub fn try_acquire(&self, service: &str) -> Result<QuotaPermit, QuotaError> {
+        self.acquire(service, true)
+    }
+
+    /// Try again to admit work that was already deferred
+    ///
+    /// Like [`try_acquire`](Self::try_acquire), but a refusal is not
+    /// recorded as another deferral.
+    pub fn retry_acquire(&self, service: &str) -> Result<QuotaPermit, QuotaError> {
+        self.acquire(service, false)
+    }
+
+    fn acquire(&self, service: &str, record_deferral: bool) -> Result<QuotaPermit, QuotaError> {
+        let now = Instant::now();
+        let mut usage = self.usage.lock();
+        let entry = usage
+            .entry(service.to_string())
+            .or_insert_with(|| ServiceUsage::new(now));
+        entry.roll_window(self.config.window, now);
+
+        if let Some(quota) = self.config.services.get(service) {
+            if let Err(err) = entry.check(service, quota, self.config.window, now) {
+                drop(usage);
+                if record_deferral {
+                    debug!("Deferring work for service {}: {}", service, err);
+                    if let Some(metrics) = &self.metrics {
+                        metrics.record_quota_deferral(service, err.reason());
+                    }
+                }
+                return Err(err);
+            }
+        }
+
+        entry.in_flight += 1;
+
+        Ok(QuotaPermit {
+            service: service.to_string(),
+            window: self.config.window,
+            usage: Arc::clone(&self.usage),
+        })
+    }
+
+    /// Number of analyses currently in flight for a service
+    pub fn in_flight(&self, service: &str) -> usize {
+        self.usage.lock().get(service).map_or(0, |u| u.in_flight)
+    }
+
+    /// Cost accrued by a service in the current window
+    pub fn window_cost(&self, service: &str) -> f64 {
+        let mut usage = self.usage.lock();
+        match usage.get_mut(service) {
+            Some(entry) => {
+                entry.roll_window(self.config.window, Instant::now());
+                entry.window_cost
+            }
+            None => 0.0,
+        }
+    }
+}
+
+/// Admission for one piece of work on a service
+///
+/// The in-flight slot is released when the permit is dropped.
+#[derive(Debug)]
+pub struct QuotaPermit {
+    service: String,
+    window: Duration,
+    usage: UsageMap,
+}
+
+impl QuotaPermit {
+    /// Service this permit was granted for
+    pub fn service(&self) -> &str {
+        &self.service
+    }
+
+    /// Charge the cost of this work against the service's window budget
+    pub fn record_cost(&self, cost_usd: f64) {
+        let mut usage = self.usage.lock();
+        if let Some(entry) = usage.get_mut(&self.service) {
+            entry.roll_window(self.window, Instant::now());
+            entry.window_cost += cost_usd;
+        }
+    }
+}
+
+impl Drop for QuotaPermit {
+    fn drop(&mut self) {
+        if let Some(entry) = self.usage.lock().get_mut(&self.service) {
+            entry.in_flight = entry.in_flight.saturating_sub(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metrics::ProcessorMetrics;
+    use prometheus_client::registry::Registry;
+
+    fn enforcer(config: QuotaConfig) -> (QuotaEnforcer, Arc<ProcessorMetrics>) {
+        let metrics = Arc::new(ProcessorMetrics::new(&mut Registry::default()));
+        (QuotaEnforcer::new(config).with_metrics(Arc::clone(&metrics)), metrics)
+    }
+
+    fn deferrals(metrics: &ProcessorMetrics, service: &str, reason: &str) -> u64 {
+        metrics
+            .quota_deferrals_total
+            .get_or_create(&vec![
+                ("service".to_string(), service.to_string()),
+                ("reason".to_string(), reason.to_string()),
+            ])
+            .get()
+    }
+
+    #[test]
+    fn test_in_flight_quota_defers_only_the_busy_service() {
+        let config = QuotaConfig::new()
+            .with_service("chat", ServiceQuota::new().with_max_in_flight(2))
+            .with_service("search", ServiceQuota::new().with_max_in_flight(2));
+        let (enforcer, metrics) = enforcer(config);
+
+        let first = enforcer.try_acquire("chat").unwrap();
+        let _second = enforcer.try_acquire("chat").unwrap();
+
+        let err = enforcer.try_acquire("chat").unwrap_err();
+        assert_eq!(
+            err,
+            QuotaError::InFlightExceeded {
+                service: "chat".to_string(),
+                limit: 2
+            }
+        );
+        assert!(err.to_string().contains("limit of 2 in-flight"));
+        assert_eq!(deferrals(&metrics, "chat", "in_flight"), 1);
+
+        // Retrying deferred work is not counted as another deferral
+        assert!(enforcer.retry_acquire("chat").is_err());
+        assert_eq!(deferrals(&metrics, "chat", "in_flight"), 1);
+
+        // Other services proceed while chat is at its limit
+        assert!(enforcer.try_acquire("search").is_ok());
+        assert!(enforcer.try_acquire("unlimited").is_ok());
+
+        drop(first);
+        assert_eq!(enforcer.in_flight("chat"), 1);
+        assert!(enforcer.try_acquire("chat").is_ok());
+    }
+
+    #[test]
+    fn test_cost_quota_defers_until_window_resets() {
+        let config = QuotaConfig::new()
+            .with_window(Duration::from_millis(50))
+            .with_service("chat", ServiceQuota::new().with_max_cost(1.0));
+        let (enforcer, metrics) = enforcer(config);
+
+        enforcer.try_acquire("chat").unwrap().record_cost(1.5);
+        enforcer.try_acquire("search").unwrap().record_cost(100.0);
+
+        let err = enforcer.try_acquire("chat").unwrap_err();
+        assert_eq!(err.reason(), "cost");
+        assert!(err.retry_after().unwrap() <= Duration::from_millis(50));
+        assert_eq!(deferrals(&metrics, "chat", "cost"), 1);
+        assert!(enforcer.try_acquire("search").is_ok());
+
+        std::thread::sleep(Duration::from_millis(60));
+        assert_eq!(enforcer.window_cost("chat"), 0.0);
+        assert!(enforcer.try_acquire("chat").is_ok());
+    }
+
+    #[test]
+    fn test_quota_config_validation() {
+        assert!(QuotaConfig::new().validate().is_ok());
+        assert!(QuotaConfig::new()
+            .with_service("chat", ServiceQuota::new().with_max_in_flight(0))
+            .validate()
+            .is_err());
+        assert!(QuotaConfig::new()
+            .with_service("chat", ServiceQuota::new().with_max_cost(-1.0))
+            .validate()
+            .is_err());
+        assert!(QuotaConfig::new().with_window(Duration::ZERO).validate().is_err());
+    }
+}
@@ -116,6 +116,10 @@ pub struct Constraint {
 pub enum DecisionStatus {
     /// Decision created but not yet deployed
     Pending,
+    /// Waiting for a human to approve the decision before it can be deployed
+    AwaitingApproval,
+    /// Rejected during review
+    Rejected,
     /// Currently being validated
     Validating,
     /// Validation failed